@@ -1,8 +1,44 @@
-use crate::aggregators::{AggregationHandler, Aggregator, BucketTimestamp};
+use crate::aggregators::{
+    AggregationHandler, AggregationType, Aggregator, BucketAlignment, BucketTimestamp, FillPolicy,
+    add_calendar_months, calc_month_bucket_start,
+};
 use crate::common::{Sample, Timestamp};
 use crate::series::request_types::AggregationOptions;
 use std::collections::VecDeque;
 
+/// A run of consecutive empty buckets in `[next_bucket, end_exclusive)`. Runs are expanded one
+/// bucket at a time, so a large gap between samples (e.g. a long range with small buckets and
+/// EMPTY) does not allocate a sample per bucket up front.
+#[derive(Debug, Clone, Copy)]
+struct EmptyBucketRun {
+    next_bucket: Timestamp,
+    end_exclusive: Timestamp,
+    value: f64,
+}
+
+/// A run of empty buckets filled by linear interpolation between the non-empty buckets around it.
+/// The empty buckets are produced one at a time from `runs`, followed by `next`.
+#[derive(Debug)]
+struct LinearGap {
+    runs: VecDeque<EmptyBucketRun>,
+    prev: Option<Sample>,
+    /// The non-empty bucket following the gap, with its sample count
+    next: Option<(Sample, usize)>,
+}
+
+impl LinearGap {
+    /// Interpolates the value of an empty bucket. Buckets at either end of the range lack a
+    /// neighbor and keep the empty value.
+    fn interpolate(&self, mut sample: Sample) -> Sample {
+        if let (Some(prev), Some((next, _))) = (self.prev, self.next) {
+            let span = (next.timestamp - prev.timestamp) as f64;
+            let slope = (next.value - prev.value) / span;
+            sample.value = prev.value + slope * (sample.timestamp - prev.timestamp) as f64;
+        }
+        sample
+    }
+}
+
 /// Helper class for minimizing monomorphization overhead for AggregationIterator
 #[derive(Debug)]
 struct AggregationHelper {
@@ -12,9 +48,14 @@ struct AggregationHelper {
     bucket_range_start: Timestamp,
     bucket_range_end: Timestamp,
     align_timestamp: Timestamp,
+    /// Set when buckets are aligned on calendar months: (months per bucket, UTC offset)
+    calendar_months: Option<(u32, i64)>,
     has_samples: bool,
+    /// The number of samples in the current bucket
     count: usize,
     report_empty: bool,
+    /// Set when each input sample carries, as its value, the number of samples it stands for
+    sample_counts: bool,
 }
 
 impl AggregationHelper {
@@ -32,9 +73,11 @@ impl AggregationHelper {
             bucket_range_start: 0,
             bucket_range_end: 0,
             align_timestamp,
+            calendar_months: None,
             has_samples: false,
             count: 0,
             report_empty,
+            sample_counts: false,
         }
     }
 
@@ -60,18 +103,24 @@ impl AggregationHelper {
             r.set_window_ms(options.bucket_duration);
         }
 
-        Self::with_parts(
+        let mut helper = Self::with_parts(
             aggregator,
             options.bucket_duration,
             options.timestamp_output,
             align_timestamp,
-            options.report_empty,
-        )
+            options.report_empty || options.fill.is_some(),
+        );
+        if let BucketAlignment::Calendar(_, utc_offset) = options.alignment
+            && let Some(months) = options.alignment.months_per_bucket(options.bucket_duration)
+        {
+            helper.calendar_months = Some((months, utc_offset));
+        }
+        helper
     }
 
     fn add_empty_bucket_internal(
         &self,
-        samples: &mut VecDeque<Sample>,
+        runs: &mut VecDeque<EmptyBucketRun>,
         start_bucket: Timestamp,
         end_bucket_exclusive: Timestamp,
     ) {
@@ -79,58 +128,62 @@ impl AggregationHelper {
             return;
         }
 
-        let value = AggregationHandler::empty_bucket_value(&self.aggregator);
-        let count = ((end_bucket_exclusive - start_bucket) / self.bucket_duration as i64) as usize;
-        samples.reserve(count);
-
-        for bucket_start in
-            (start_bucket..end_bucket_exclusive).step_by(self.bucket_duration as usize)
-        {
-            samples.push_back(Sample {
-                timestamp: self.bucket_ts.calculate(bucket_start, self.bucket_duration),
-                value,
-            });
-        }
+        runs.push_back(EmptyBucketRun {
+            next_bucket: start_bucket,
+            end_exclusive: end_bucket_exclusive,
+            value: AggregationHandler::empty_bucket_value(&self.aggregator),
+        });
     }
 
     fn add_empty_buckets_between_timestamps(
         &self,
-        samples: &mut VecDeque<Sample>,
+        runs: &mut VecDeque<EmptyBucketRun>,
         first_ts: Timestamp,
         end_ts: Timestamp,
     ) {
         let start = self.calc_bucket_start(first_ts);
         let end = self.calc_bucket_start(end_ts);
-        self.add_empty_bucket_internal(samples, start, end);
+        self.add_empty_bucket_internal(runs, start, end);
     }
 
-    fn add_empty_buckets(
-        &self,
-        samples: &mut VecDeque<Sample>,
-        first_bucket_ts: Timestamp,
-        end_bucket_ts: Timestamp,
-    ) {
-        let value = AggregationHandler::empty_bucket_value(&self.aggregator);
-        let start = self.calc_bucket_start(first_bucket_ts);
-        let end = self.calc_bucket_start(end_bucket_ts);
-
-        let count = ((end - start) / self.bucket_duration as i64) as usize;
-        samples.reserve(count);
-
-        for timestamp in (start..end).step_by(self.bucket_duration as usize) {
-            samples.push_back(Sample { timestamp, value });
+    /// Yields the next empty bucket from the front run, dropping the run once it is exhausted.
+    fn next_empty_bucket(&self, runs: &mut VecDeque<EmptyBucketRun>) -> Option<Sample> {
+        let run = runs.front_mut()?;
+        let bucket_start = run.next_bucket;
+        let value = run.value;
+        let bucket_end = self.next_bucket_start(bucket_start);
+        run.next_bucket = bucket_end;
+        if run.next_bucket >= run.end_exclusive {
+            runs.pop_front();
         }
+        Some(Sample {
+            timestamp: self.bucket_output_timestamp(bucket_start, bucket_end),
+            value,
+        })
     }
 
     fn output_timestamp(&self) -> Timestamp {
-        self.bucket_ts
-            .calculate(self.bucket_range_start, self.bucket_duration)
+        self.bucket_output_timestamp(self.bucket_range_start, self.bucket_range_end)
+    }
+
+    fn bucket_output_timestamp(&self, bucket_start: Timestamp, bucket_end: Timestamp) -> Timestamp {
+        let duration = bucket_end.saturating_sub(bucket_start) as u64;
+        self.bucket_ts.calculate(bucket_start, duration)
+    }
+
+    /// Returns the start of the bucket following the one starting at `bucket_start`. Buckets have
+    /// a fixed duration, except when aligned on calendar months.
+    fn next_bucket_start(&self, bucket_start: Timestamp) -> Timestamp {
+        match self.calendar_months {
+            Some((months, utc_offset)) => add_calendar_months(bucket_start, months, utc_offset),
+            None => bucket_start.saturating_add_unsigned(self.bucket_duration),
+        }
     }
 
     fn complete_bucket(
         &mut self,
         last_ts: Option<Timestamp>,
-        empty_buckets: &mut VecDeque<Sample>,
+        empty_buckets: &mut VecDeque<EmptyBucketRun>,
     ) -> Option<Sample> {
         let bucket = if self.count > 0 {
             Some(Sample::new(
@@ -162,6 +215,13 @@ impl AggregationHelper {
     }
 
     fn update(&mut self, sample: Sample) {
+        if self.sample_counts {
+            let count = sample.value as usize;
+            self.aggregator.add_sample_count(count);
+            self.has_samples = true;
+            self.count += count;
+            return;
+        }
         if self.aggregator.update(sample.timestamp, sample.value) {
             self.has_samples = true;
         }
@@ -175,18 +235,34 @@ impl AggregationHelper {
 
     fn update_bucket_timestamps(&mut self, timestamp: Timestamp) {
         self.bucket_range_start = self.calc_bucket_start(timestamp);
-        self.bucket_range_end = self
-            .bucket_range_start
-            .saturating_add_unsigned(self.bucket_duration);
+        self.bucket_range_end = self.next_bucket_start(self.bucket_range_start);
+        self.aggregator.set_bucket_end(self.bucket_range_end);
     }
 
     fn calc_bucket_start(&self, ts: Timestamp) -> Timestamp {
+        if let Some((months, utc_offset)) = self.calendar_months {
+            return calc_month_bucket_start(ts, months, utc_offset);
+        }
         let diff = ts - self.align_timestamp;
         let delta = self.bucket_duration as i64;
         (ts - ((diff % delta + delta) % delta)).max(0)
     }
 }
 
+/// Maps timestamps to the buckets an `AggregateIterator` with the same options places them in.
+pub(crate) struct BucketLocator(AggregationHelper);
+
+impl BucketLocator {
+    pub(crate) fn new(options: &AggregationOptions, align_timestamp: Timestamp) -> Self {
+        Self(AggregationHelper::new(options, align_timestamp))
+    }
+
+    /// Returns the start of the bucket containing `ts`.
+    pub(crate) fn bucket_start(&self, ts: Timestamp) -> Timestamp {
+        self.0.calc_bucket_start(ts)
+    }
+}
+
 pub fn aggregate(
     options: &AggregationOptions,
     aligned_timestamp: Timestamp,
@@ -199,10 +275,15 @@ pub fn aggregate(
 pub struct AggregateIterator<T: Iterator<Item = Sample>> {
     inner: T,
     aggregator: AggregationHelper,
-    empty_buckets: VecDeque<Sample>,
+    empty_buckets: VecDeque<EmptyBucketRun>,
     prev_ts: Timestamp,
     init: bool,
     query_range: Option<(Timestamp, Timestamp)>,
+    fill: Option<FillPolicy>,
+    /// The run of empty buckets being filled with FILL LINEAR
+    linear_gap: Option<LinearGap>,
+    /// The most recent non-empty bucket, used as the left neighbor when filling
+    last_bucket: Option<Sample>,
 }
 
 impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
@@ -215,6 +296,9 @@ impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
             prev_ts: 0,
             init: false,
             query_range: None,
+            fill: options.fill,
+            linear_gap: None,
+            last_bucket: None,
         }
     }
 
@@ -232,9 +316,29 @@ impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
             prev_ts: 0,
             init: false,
             query_range: Some((query_start, query_end)),
+            fill: options.fill,
+            linear_gap: None,
+            last_bucket: None,
         }
     }
 
+    /// Creates a `countall` iterator fed with pre-counted samples: each input sample stands for
+    /// `value` samples, all of which fall in the same bucket as its timestamp. This lets callers
+    /// count whole runs of samples without decoding them.
+    pub(crate) fn from_sample_counts(
+        inner: T,
+        options: &AggregationOptions,
+        aligned_timestamp: Timestamp,
+    ) -> Self {
+        debug_assert_eq!(
+            options.aggregation.aggregation_type(),
+            AggregationType::CountAll
+        );
+        let mut iter = Self::new(inner, options, aligned_timestamp);
+        iter.aggregator.sample_counts = true;
+        iter
+    }
+
     #[inline]
     fn update(&mut self, sample: Sample) {
         self.aggregator.update(sample);
@@ -252,13 +356,16 @@ impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
     }
 
     #[inline]
-    fn finalize_bucket(&mut self, ts: Option<Timestamp>) -> Option<Sample> {
-        self.aggregator.complete_bucket(ts, &mut self.empty_buckets)
+    fn finalize_bucket(&mut self, ts: Option<Timestamp>) -> Option<(Sample, usize)> {
+        let count = self.aggregator.count;
+        self.aggregator
+            .complete_bucket(ts, &mut self.empty_buckets)
+            .map(|bucket| (bucket, count))
     }
 
     #[inline]
     fn pop_empty_bucket(&mut self) -> Option<Sample> {
-        self.empty_buckets.pop_front()
+        self.aggregator.next_empty_bucket(&mut self.empty_buckets)
     }
 
     fn enqueue_leading_empty_buckets(&mut self, first_sample_ts: Timestamp) {
@@ -284,10 +391,8 @@ impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
 
         if let Some((query_start, query_end)) = self.query_range {
             let first_bucket = self.aggregator.calc_bucket_start(query_start);
-            let end_exclusive = self
-                .aggregator
-                .calc_bucket_start(query_end)
-                .saturating_add_unsigned(self.aggregator.bucket_duration);
+            let last_bucket = self.aggregator.calc_bucket_start(query_end);
+            let end_exclusive = self.aggregator.next_bucket_start(last_bucket);
             self.aggregator.add_empty_bucket_internal(
                 &mut self.empty_buckets,
                 first_bucket,
@@ -313,7 +418,7 @@ impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
         }
     }
 
-    fn process_bucket(&mut self) -> Option<Sample> {
+    fn process_bucket(&mut self) -> Option<(Sample, usize)> {
         while let Some(sample) = self.inner.next() {
             if !self.aggregator.should_finalize_bucket(sample.timestamp) {
                 self.update(sample);
@@ -333,7 +438,7 @@ impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
         None
     }
 
-    fn finalize_last_bucket_if_any(&mut self) -> Option<Sample> {
+    fn finalize_last_bucket_if_any(&mut self) -> Option<(Sample, usize)> {
         if self.aggregator.count == 0 {
             return None;
         }
@@ -343,10 +448,8 @@ impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
         if self.aggregator.report_empty
             && let Some((_, query_end)) = self.query_range
         {
-            let end_exclusive = self
-                .aggregator
-                .calc_bucket_start(query_end)
-                .saturating_add_unsigned(self.aggregator.bucket_duration);
+            let last_bucket = self.aggregator.calc_bucket_start(query_end);
+            let end_exclusive = self.aggregator.next_bucket_start(last_bucket);
 
             self.aggregator.add_empty_bucket_internal(
                 &mut self.empty_buckets,
@@ -357,33 +460,113 @@ impl<T: Iterator<Item = Sample>> AggregateIterator<T> {
 
         bucket
     }
-}
-
-impl<T: Iterator<Item = Sample>> Iterator for AggregateIterator<T> {
-    type Item = Sample;
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Returns the next bucket along with the number of samples in it. Empty buckets hold none.
+    fn next_bucket(&mut self) -> Option<(Sample, usize)> {
         if let Some(sample) = self.pop_empty_bucket() {
-            return Some(sample);
+            return Some((sample, 0));
         }
 
         if !self.ensure_initialized() {
-            return self.pop_empty_bucket();
+            return self.pop_empty_bucket().map(|sample| (sample, 0));
+        }
+
+        // leading empty buckets (queued on initialization) precede the first bucket
+        if let Some(sample) = self.pop_empty_bucket() {
+            return Some((sample, 0));
         }
 
         if let Some(bucket) = self.process_bucket() {
             return Some(bucket);
         }
 
-        self.finalize_last_bucket_if_any()
-            .or_else(|| self.pop_empty_bucket())
+        if let Some(bucket) = self.finalize_last_bucket_if_any() {
+            return Some(bucket);
+        }
+
+        self.pop_empty_bucket().map(|sample| (sample, 0))
+    }
+
+    fn next_filled(&mut self, policy: FillPolicy) -> Option<(Sample, usize)> {
+        if let Some(bucket) = self.next_linear_gap_bucket() {
+            return Some(bucket);
+        }
+
+        let (mut sample, count) = self.next_bucket()?;
+        if count > 0 {
+            self.last_bucket = Some(sample);
+            return Some((sample, count));
+        }
+
+        match policy {
+            FillPolicy::Null => sample.value = f64::NAN,
+            FillPolicy::Zero => sample.value = 0.0,
+            FillPolicy::Previous => {
+                // leading empty buckets have nothing to carry forward and keep the empty value
+                if let Some(prev) = self.last_bucket {
+                    sample.value = prev.value;
+                }
+            }
+            FillPolicy::Linear => return self.start_linear_gap(sample),
+        }
+
+        Some((sample, 0))
+    }
+
+    /// Starts filling the run of empty buckets beginning with `first_empty`. The rest of the run
+    /// is already queued, since empty buckets are queued along with the bucket preceding them. It
+    /// is set aside to read the following non-empty bucket, and then produced one bucket at a time.
+    fn start_linear_gap(&mut self, first_empty: Sample) -> Option<(Sample, usize)> {
+        let runs = std::mem::take(&mut self.empty_buckets);
+        let next = self.next_bucket();
+        debug_assert!(next.is_none_or(|(_, count)| count > 0));
+
+        let gap = LinearGap {
+            runs,
+            prev: self.last_bucket,
+            next,
+        };
+        let sample = gap.interpolate(first_empty);
+        self.linear_gap = Some(gap);
+        Some((sample, 0))
+    }
+
+    /// Returns the next bucket of the gap being filled, ending with the non-empty bucket after it.
+    fn next_linear_gap_bucket(&mut self) -> Option<(Sample, usize)> {
+        let gap = self.linear_gap.as_mut()?;
+        if let Some(sample) = self.aggregator.next_empty_bucket(&mut gap.runs) {
+            return Some((gap.interpolate(sample), 0));
+        }
+
+        let next = self.linear_gap.take().and_then(|gap| gap.next);
+        if let Some((sample, _)) = next {
+            self.last_bucket = Some(sample);
+        }
+        next
+    }
+
+    /// Returns the next bucket along with the number of samples aggregated into it. Empty and
+    /// filled buckets hold no samples.
+    pub fn next_with_count(&mut self) -> Option<(Sample, usize)> {
+        match self.fill {
+            Some(policy) => self.next_filled(policy),
+            None => self.next_bucket(),
+        }
+    }
+}
+
+impl<T: Iterator<Item = Sample>> Iterator for AggregateIterator<T> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_count().map(|(sample, _)| sample)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::aggregators::{AggregationType, BucketAlignment, BucketTimestamp};
+    use crate::aggregators::{AggregationType, BucketAlignment, BucketTimestamp, FillPolicy};
     use crate::common::Sample;
 
     fn create_test_samples() -> Vec<Sample> {
@@ -405,6 +588,9 @@ mod tests {
             timestamp_output: BucketTimestamp::Start,
             alignment: BucketAlignment::Start,
             report_empty: false,
+            fill: None,
+            with_counts: false,
+            dedup: None,
         }
     }
 
@@ -629,6 +815,161 @@ mod tests {
         assert_eq!(result[4].value, 6.0);
     }
 
+    #[test]
+    fn test_empty_buckets_are_generated_lazily() {
+        // a gap of 10^12 one-ms buckets would exhaust memory if materialized up front
+        let samples = vec![Sample::new(0, 1.0), Sample::new(1_000_000_000_000, 2.0)];
+
+        let mut options = create_options(AggregationType::Sum);
+        options.bucket_duration = 1;
+        options.report_empty = true;
+
+        let iterator = AggregateIterator::new(samples.into_iter(), &options, 0);
+        let result: Vec<Sample> = iterator.take(4).collect();
+
+        assert_eq!(
+            result,
+            vec![
+                Sample::new(0, 1.0),
+                Sample::new(1, 0.0),
+                Sample::new(2, 0.0),
+                Sample::new(3, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_linear_fill_is_generated_lazily() {
+        // the same gap, filled by interpolation, must not be buffered either
+        let samples = vec![Sample::new(0, 0.0), Sample::new(1_000_000_000_000, 1.0)];
+
+        let mut options = create_options(AggregationType::Sum);
+        options.bucket_duration = 1;
+        options.fill = Some(FillPolicy::Linear);
+
+        let mut iterator = AggregateIterator::new(samples.into_iter(), &options, 0);
+        let result: Vec<(Sample, usize)> =
+            (0..3).filter_map(|_| iterator.next_with_count()).collect();
+
+        assert_eq!(
+            result,
+            vec![
+                (Sample::new(0, 0.0), 1),
+                (Sample::new(1, 1e-12), 0),
+                (Sample::new(2, 2e-12), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_linear_fill_counts_and_consecutive_gaps() {
+        let samples = vec![
+            Sample::new(10, 1.0),
+            Sample::new(12, 1.0),
+            Sample::new(40, 4.0),
+            Sample::new(70, 1.0),
+        ];
+        let mut options = create_options(AggregationType::Avg);
+        options.fill = Some(FillPolicy::Linear);
+
+        let mut iterator = AggregateIterator::with_range(samples.into_iter(), &options, 0, 0, 89);
+        let result: Vec<(Timestamp, f64, usize)> =
+            std::iter::from_fn(|| iterator.next_with_count())
+                .map(|(sample, count)| (sample.timestamp, sample.value, count))
+                .collect();
+
+        let expected = [
+            (10, 1.0, 2),
+            (20, 2.0, 0),
+            (30, 3.0, 0),
+            (40, 4.0, 1),
+            (50, 3.0, 0),
+            (60, 2.0, 0),
+            (70, 1.0, 1),
+        ];
+        assert!(result[0].1.is_nan());
+        assert_eq!((result[0].0, result[0].2), (0, 0));
+        assert_eq!(&result[1..8], &expected);
+        assert!(
+            result[8..]
+                .iter()
+                .all(|&(_, value, count)| value.is_nan() && count == 0)
+        );
+        assert_eq!(result.len(), 9);
+    }
+
+    #[test]
+    fn test_leading_empty_buckets_precede_first_bucket() {
+        let samples = vec![Sample::new(20, 1.0), Sample::new(25, 2.0)];
+
+        let mut options = create_options(AggregationType::Sum);
+        options.report_empty = true;
+
+        let iterator =
+            AggregateIterator::with_range(samples.clone().into_iter(), &options, 0, 0, 39);
+        let result: Vec<Sample> = iterator.collect();
+        assert_eq!(
+            result,
+            vec![
+                Sample::new(0, 0.0),
+                Sample::new(10, 0.0),
+                Sample::new(20, 3.0),
+                Sample::new(30, 0.0),
+            ]
+        );
+
+        // BUCKETTIMESTAMP applies to the lazily generated empty buckets as well
+        options.timestamp_output = BucketTimestamp::End;
+        let iterator = AggregateIterator::with_range(samples.into_iter(), &options, 0, 0, 39);
+        let timestamps: Vec<Timestamp> = iterator.map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_calendar_month_buckets() {
+        use crate::aggregators::{CALENDAR_MONTH_DURATION_MS, CalendarUnit};
+        use crate::common::time::{MILLIS_PER_DAY, days_from_civil};
+
+        let day = |y, m, d| days_from_civil(y, m, d) * MILLIS_PER_DAY;
+        let samples = vec![
+            Sample::new(day(2024, 1, 31), 1.0),
+            Sample::new(day(2024, 2, 1), 2.0),
+            Sample::new(day(2024, 2, 29), 3.0),
+            Sample::new(day(2024, 4, 15), 4.0),
+        ];
+
+        let mut options = create_options(AggregationType::Sum);
+        options.bucket_duration = CALENDAR_MONTH_DURATION_MS;
+        options.alignment = BucketAlignment::Calendar(CalendarUnit::Month, 0);
+        options.report_empty = true;
+        let align = options.alignment.get_aligned_timestamp(0, 0);
+
+        let iterator = AggregateIterator::new(samples.clone().into_iter(), &options, align);
+        let result: Vec<Sample> = iterator.collect();
+        assert_eq!(
+            result,
+            vec![
+                Sample::new(day(2024, 1, 1), 1.0),
+                Sample::new(day(2024, 2, 1), 5.0),
+                Sample::new(day(2024, 3, 1), 0.0),
+                Sample::new(day(2024, 4, 1), 4.0),
+            ]
+        );
+
+        // quarters, with bucket end timestamps reflecting the variable month lengths
+        options.bucket_duration = 3 * CALENDAR_MONTH_DURATION_MS;
+        options.timestamp_output = BucketTimestamp::End;
+        let iterator = AggregateIterator::new(samples.into_iter(), &options, align);
+        let result: Vec<Sample> = iterator.collect();
+        assert_eq!(
+            result,
+            vec![
+                Sample::new(day(2024, 4, 1), 6.0),
+                Sample::new(day(2024, 7, 1), 4.0),
+            ]
+        );
+    }
+
     // #[test] TODO
     fn test_empty_buckets_last() {
         let samples = vec![
@@ -718,6 +1059,9 @@ mod tests {
             timestamp_output: BucketTimestamp::Start,
             alignment: BucketAlignment::Start,
             report_empty: false,
+            fill: None,
+            with_counts: false,
+            dedup: None,
         };
 
         let iterator = AggregateIterator::new(samples.into_iter(), &options, 0);
@@ -738,6 +1082,108 @@ mod tests {
         assert_eq!(result[2].value, 4.0);
     }
 
+    #[test]
+    fn test_rate_aggregation_with_empty_and_bucket_end() {
+        let samples = vec![
+            Sample::new(10, 1.0),
+            Sample::new(15, 3.0),
+            // gap at [20, 30)
+            Sample::new(30, 10.0),
+            Sample::new(35, 2.0), // counter reset
+        ];
+
+        let mut options = create_options(AggregationType::Rate);
+        options.report_empty = true;
+        options.timestamp_output = BucketTimestamp::End;
+
+        let iterator = AggregateIterator::new(samples.into_iter(), &options, 0);
+        let result: Vec<Sample> = iterator.collect();
+
+        assert_eq!(result.len(), 3);
+        // 10ms buckets: increase / 0.01s
+        assert_eq!(result[0].timestamp, 20);
+        assert_eq!(result[0].value, 200.0);
+        assert_eq!(result[1].timestamp, 30);
+        assert!(result[1].value.is_nan());
+        assert_eq!(result[2].timestamp, 40);
+        assert_eq!(result[2].value, 200.0);
+    }
+
+    fn run_fill(policy: FillPolicy) -> Vec<Sample> {
+        let samples = vec![
+            Sample::new(10, 1.0),
+            Sample::new(15, 1.0),
+            Sample::new(40, 4.0),
+        ];
+        let mut options = create_options(AggregationType::Avg);
+        options.fill = Some(policy);
+
+        let iterator = AggregateIterator::with_range(samples.into_iter(), &options, 0, 0, 59);
+        iterator.collect()
+    }
+
+    #[test]
+    fn test_fill_linear_interpolates_between_buckets() {
+        let result = run_fill(FillPolicy::Linear);
+        let timestamps: Vec<Timestamp> = result.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![0, 10, 20, 30, 40, 50]);
+
+        // leading and trailing buckets have no neighbor to interpolate from
+        assert!(result[0].value.is_nan());
+        assert_eq!(result[1].value, 1.0);
+        assert_eq!(result[2].value, 2.0);
+        assert_eq!(result[3].value, 3.0);
+        assert_eq!(result[4].value, 4.0);
+        assert!(result[5].value.is_nan());
+    }
+
+    #[test]
+    fn test_fill_previous_carries_last_value() {
+        let result = run_fill(FillPolicy::Previous);
+        let values: Vec<f64> = result[1..].iter().map(|s| s.value).collect();
+        assert!(result[0].value.is_nan());
+        assert_eq!(values, vec![1.0, 1.0, 1.0, 4.0, 4.0]);
+    }
+
+    #[test]
+    fn test_fill_zero_and_null() {
+        let result = run_fill(FillPolicy::Zero);
+        let values: Vec<f64> = result.iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![0.0, 1.0, 0.0, 0.0, 4.0, 0.0]);
+
+        let result = run_fill(FillPolicy::Null);
+        assert_eq!(result.len(), 6);
+        assert!(result[2].value.is_nan());
+        assert!(result[3].value.is_nan());
+        assert_eq!(result[4].value, 4.0);
+    }
+
+    #[test]
+    fn test_twa_aggregation_weights_to_bucket_end() {
+        let samples = vec![
+            Sample::new(10, 1.0),
+            Sample::new(12, 6.0),
+            Sample::new(20, 3.0),
+            Sample::new(36, 4.0),
+        ];
+
+        let options = create_options(AggregationType::Twa);
+        let iterator = AggregateIterator::new(samples.into_iter(), &options, 0);
+        let result: Vec<Sample> = iterator.collect();
+
+        assert_eq!(result.len(), 3);
+
+        // [10, 20): 1.0 holds for 2, 6.0 holds to the bucket end for 8
+        assert_eq!(result[0].timestamp, 10);
+        assert_eq!(result[0].value, (1.0 * 2.0 + 6.0 * 8.0) / 10.0);
+
+        // single-sample buckets yield the sample value
+        assert_eq!(result[1].timestamp, 20);
+        assert_eq!(result[1].value, 3.0);
+        assert_eq!(result[2].timestamp, 30);
+        assert_eq!(result[2].value, 4.0);
+    }
+
     // #[test]
     // fn test_alignment_with_offset() {
     //     let samples = vec![
@@ -11,6 +11,7 @@ use crate::common::rdb::{
     RdbSerializable, rdb_load_bool, rdb_load_optional_f64, rdb_load_u8, rdb_load_usize,
     rdb_save_bool, rdb_save_optional_f64, rdb_save_u8, rdb_save_usize,
 };
+use ahash::AHashSet;
 use enum_dispatch::enum_dispatch;
 use get_size2::GetSize;
 use std::fmt::Display;
@@ -208,6 +209,92 @@ impl Hash for MaxAggregator {
     }
 }
 
+// -- AbsMax ---------------------------------------------------------------
+
+/// Value with the largest magnitude in the bucket, keeping its sign. On ties the first value
+/// encountered wins.
+#[derive(Copy, Clone, Default, Debug, PartialEq, GetSize)]
+pub struct AbsMaxAggregator(Option<Value>);
+impl AggregationHandler for AbsMaxAggregator {
+    fn update(&mut self, _timestamp: Timestamp, value: Value) -> bool {
+        if value.is_nan() {
+            return false;
+        }
+        match self.0 {
+            Some(v) if v.abs() >= value.abs() => {}
+            _ => self.0 = Some(value),
+        }
+        true
+    }
+    fn reset(&mut self) {
+        self.0 = None;
+    }
+    fn current(&self) -> Option<Value> {
+        self.0
+    }
+}
+
+impl RdbSerializable for AbsMaxAggregator {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        rdb_save_optional_f64(rdb, self.0);
+    }
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        rdb_load_optional_f64(rdb).map(Self)
+    }
+}
+
+impl Hash for AbsMaxAggregator {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_f64(self.0.unwrap_or(f64::NAN), state);
+    }
+}
+
+// -- AbsMin ---------------------------------------------------------------
+
+/// Value with the smallest magnitude in the bucket, keeping its sign. On ties the first value
+/// encountered wins.
+#[derive(Copy, Clone, Default, Debug, PartialEq, GetSize)]
+pub struct AbsMinAggregator(Option<Value>);
+impl AggregationHandler for AbsMinAggregator {
+    fn update(&mut self, _timestamp: Timestamp, value: Value) -> bool {
+        if value.is_nan() {
+            return false;
+        }
+        match self.0 {
+            Some(v) if v.abs() <= value.abs() => {}
+            _ => self.0 = Some(value),
+        }
+        true
+    }
+    fn reset(&mut self) {
+        self.0 = None;
+    }
+    fn current(&self) -> Option<Value> {
+        self.0
+    }
+}
+
+impl RdbSerializable for AbsMinAggregator {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        rdb_save_optional_f64(rdb, self.0);
+    }
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        rdb_load_optional_f64(rdb).map(Self)
+    }
+}
+
+impl Hash for AbsMinAggregator {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_f64(self.0.unwrap_or(f64::NAN), state);
+    }
+}
+
 // -- First ----------------------------------------------------------------
 
 #[derive(Copy, Clone, Default, Debug, PartialEq, GetSize)]
@@ -346,6 +433,188 @@ impl RdbSerializable for AvgAggregator {
     }
 }
 
+// -- TrimmedMean -----------------------------------------------------------
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct TrimmedMeanAggregatorState {
+    /// Percentage of values dropped from each tail, in [0, 50)
+    pct: f64,
+    values: Vec<Value>,
+}
+
+/// Mean of the bucket values after dropping the lowest and highest `pct` percent of them.
+/// If trimming would remove every value, the plain mean is returned instead.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct TrimmedMeanAggregator(Box<TrimmedMeanAggregatorState>);
+
+impl TrimmedMeanAggregator {
+    pub fn new(pct: f64) -> Self {
+        Self(Box::new(TrimmedMeanAggregatorState {
+            pct,
+            values: Vec::new(),
+        }))
+    }
+
+    pub fn percentage(&self) -> f64 {
+        self.0.pct
+    }
+}
+
+impl AggregationHandler for TrimmedMeanAggregator {
+    fn update(&mut self, _timestamp: Timestamp, value: Value) -> bool {
+        if value.is_nan() {
+            return false;
+        }
+        self.0.values.push(value);
+        true
+    }
+    fn reset(&mut self) {
+        self.0.values.clear();
+    }
+    fn current(&self) -> Option<Value> {
+        let values = &self.0.values;
+        if values.is_empty() {
+            return None;
+        }
+        let n = values.len();
+        let trim = (n as f64 * self.0.pct / 100.0).floor() as usize;
+        if trim == 0 || trim * 2 >= n {
+            return Some(values.iter().sum::<Value>() / n as Value);
+        }
+        let mut sorted = values.clone();
+        sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+        let kept = &sorted[trim..n - trim];
+        Some(kept.iter().sum::<Value>() / kept.len() as Value)
+    }
+}
+
+impl GetSize for TrimmedMeanAggregator {
+    fn get_size(&self) -> usize {
+        size_of::<Box<TrimmedMeanAggregatorState>>()
+            + size_of::<TrimmedMeanAggregatorState>()
+            + self.0.values.capacity() * size_of::<Value>()
+    }
+}
+
+impl Hash for TrimmedMeanAggregator {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_f64(self.0.pct, state);
+        self.0.values.len().hash(state);
+        for value in self.0.values.iter() {
+            hash_f64(*value, state);
+        }
+    }
+}
+
+impl RdbSerializable for TrimmedMeanAggregator {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        raw::save_double(rdb, self.0.pct);
+        rdb_save_usize(rdb, self.0.values.len());
+        for value in self.0.values.iter() {
+            raw::save_double(rdb, *value);
+        }
+    }
+
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        let pct = raw::load_double(rdb)?;
+        let len = rdb_load_usize(rdb)?;
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len {
+            values.push(raw::load_double(rdb)?);
+        }
+        Ok(Self(Box::new(TrimmedMeanAggregatorState { pct, values })))
+    }
+}
+
+// -- Mad -------------------------------------------------------------------
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct MadAggregatorState {
+    values: Vec<Value>,
+}
+
+/// Median absolute deviation: the median of `|x - median|` over the bucket values.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct MadAggregator(Box<MadAggregatorState>);
+
+/// Returns the median of the values, reordering the slice in the process.
+fn median_in_place(values: &mut [Value]) -> Value {
+    let n = values.len();
+    let mid = n / 2;
+    let (lower, upper, _) = values.select_nth_unstable_by(mid, |a, b| a.total_cmp(b));
+    let upper = *upper;
+    if n % 2 == 1 {
+        return upper;
+    }
+    let lower_max = lower.iter().copied().fold(Value::NEG_INFINITY, Value::max);
+    (lower_max + upper) / 2.0
+}
+
+impl AggregationHandler for MadAggregator {
+    fn update(&mut self, _timestamp: Timestamp, value: Value) -> bool {
+        if value.is_nan() {
+            return false;
+        }
+        self.0.values.push(value);
+        true
+    }
+    fn reset(&mut self) {
+        self.0.values.clear();
+    }
+    fn current(&self) -> Option<Value> {
+        if self.0.values.is_empty() {
+            return None;
+        }
+        let mut scratch = self.0.values.clone();
+        let median = median_in_place(&mut scratch);
+        for value in scratch.iter_mut() {
+            *value = (*value - median).abs();
+        }
+        Some(median_in_place(&mut scratch))
+    }
+}
+
+impl GetSize for MadAggregator {
+    fn get_size(&self) -> usize {
+        size_of::<Box<MadAggregatorState>>()
+            + size_of::<MadAggregatorState>()
+            + self.0.values.capacity() * size_of::<Value>()
+    }
+}
+
+impl Hash for MadAggregator {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.values.len().hash(state);
+        for value in self.0.values.iter() {
+            hash_f64(*value, state);
+        }
+    }
+}
+
+impl RdbSerializable for MadAggregator {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        rdb_save_usize(rdb, self.0.values.len());
+        for value in self.0.values.iter() {
+            raw::save_double(rdb, *value);
+        }
+    }
+
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        let len = rdb_load_usize(rdb)?;
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len {
+            values.push(raw::load_double(rdb)?);
+        }
+        Ok(Self(Box::new(MadAggregatorState { values })))
+    }
+}
+
 // -- Sum -----------------------------------------------------------------
 
 #[derive(Copy, Clone, Default, Debug, PartialEq, GetSize, Hash)]
@@ -742,7 +1011,9 @@ impl RdbSerializable for StdSAggregator {
 }
 
 /// State for rate/increase calculations of monotonically increasing counters.
-/// It also properly handles resets
+/// It also properly handles resets: when a value drops below the previous one, the counter is
+/// assumed to have restarted from zero, so the new value is counted as the increase since the
+/// reset (equivalently, the pre-reset value is added to `last - first`).
 #[derive(Debug, Default, Clone, PartialEq, GetSize)]
 pub struct CounterAggregatorState {
     /// Accumulated deltas
@@ -782,7 +1053,11 @@ impl CounterAggregatorState {
         if v.is_nan() {
             return false;
         }
-        let delta = self.last_value.map_or(0.0, |last| (v - last).max(0.0));
+        let delta = match self.last_value {
+            Some(last) if v < last => v,
+            Some(last) => v - last,
+            None => 0.0,
+        };
         self.last_value = Some(v);
         self.sum_deltas += delta;
         true
@@ -827,6 +1102,7 @@ impl RdbSerializable for IncreaseAggregator {
 
 #[derive(Debug, Default, Clone, Hash, PartialEq, GetSize)]
 pub struct RateAggregatorState {
+    /// window length in milliseconds
     window: u64,
     counter: CounterAggregatorState,
 }
@@ -834,10 +1110,9 @@ pub struct RateAggregatorState {
 /// Online rate calculator for a monotonically increasing counter with resets.
 ///
 /// Algorithm:
-/// - For each new sample, compute delta = max(v - last_v, 0).
+/// - For each new sample, compute delta = v - last_v, or v if the value dropped.
 ///   This treats negative jumps as resets and never produces negative deltas.
-/// - Maintain a sliding time window of these deltas and their timestamps.
-/// - Rate ≈ sum(deltas in window) / window_length.
+/// - Rate = sum(deltas in bucket) / bucket duration in seconds.
 #[derive(Debug, Default, Clone, Hash, PartialEq, GetSize)]
 pub struct RateAggregator(Box<RateAggregatorState>);
 
@@ -849,15 +1124,14 @@ impl RateAggregator {
             last_value: None,
         };
         let state = Box::new(RateAggregatorState {
-            window: window.as_secs(),
+            window: window.as_millis() as u64,
             counter,
         });
         Self(state)
     }
 
     pub fn set_window_ms(&mut self, window: u64) {
-        let secs = window / 1000;
-        self.0.window = secs;
+        self.0.window = window;
     }
 
     fn clear(&mut self) {
@@ -894,7 +1168,7 @@ impl AggregationHandler for RateAggregator {
         let state = &self.0;
         state.counter.last_value?;
         if state.window > 0 {
-            Some(state.counter.sum_deltas / state.window as f64)
+            Some(state.counter.sum_deltas / (state.window as f64 / 1e3))
         } else {
             None
         }
@@ -1030,9 +1304,374 @@ impl AggregationHandler for IRateAggregator {
     }
 }
 
-// Deriv implementation for gauge aggregators
-#[derive(Clone, Debug, Hash, PartialEq, GetSize)]
-pub struct DerivAggregator(IRateAggregatorState);
+// -- Twa -------------------------------------------------------------------
+
+#[derive(Clone, Debug, Default, PartialEq, GetSize)]
+pub struct TwaAggregatorState {
+    weighted_sum: f64,
+    total_weight: f64,
+    last_sample: Option<Sample>,
+    /// Exclusive end of the current bucket. 0 means unknown.
+    bucket_end: Timestamp,
+}
+
+impl Hash for TwaAggregatorState {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_f64(self.weighted_sum, state);
+        hash_f64(self.total_weight, state);
+        if let Some(sample) = &self.last_sample {
+            sample.hash(state);
+        } else {
+            hash_f64(f64::NAN, state);
+        }
+        self.bucket_end.hash(state);
+    }
+}
+
+impl RdbSerializable for TwaAggregatorState {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        raw::save_double(rdb, self.weighted_sum);
+        raw::save_double(rdb, self.total_weight);
+        raw::save_signed(rdb, self.bucket_end);
+        if let Some(sample) = self.last_sample {
+            rdb_save_optional_f64(rdb, Some(sample.value));
+            raw::save_signed(rdb, sample.timestamp);
+            return;
+        }
+        rdb_save_optional_f64(rdb, None);
+    }
+
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        let weighted_sum = raw::load_double(rdb)?;
+        let total_weight = raw::load_double(rdb)?;
+        let bucket_end = raw::load_signed(rdb)?;
+        let last_sample = match rdb_load_optional_f64(rdb)? {
+            Some(value) => {
+                let timestamp = raw::load_signed(rdb)?;
+                Some(Sample { timestamp, value })
+            }
+            None => None,
+        };
+        Ok(Self {
+            weighted_sum,
+            total_weight,
+            last_sample,
+            bucket_end,
+        })
+    }
+}
+
+/// Time-weighted average for gauge/step signals sampled at irregular intervals.
+///
+/// Weighting convention:
+/// - each sample's value holds until the next sample in the bucket, so it is weighted by the
+///   gap to that sample.
+/// - the last sample in the bucket holds until the (exclusive) bucket end.
+/// - a bucket with a single sample weights it by the full bucket duration, i.e. the result is
+///   the sample value.
+///
+/// The bucket bounds are supplied by the caller through [`TwaAggregator::set_bucket_end`]
+/// before the first sample of each bucket is added. If they are not known, the last sample
+/// gets no tail weight.
+#[derive(Clone, Debug, Default, GetSize, Hash, PartialEq)]
+pub struct TwaAggregator(Box<TwaAggregatorState>);
+
+impl TwaAggregator {
+    pub fn set_bucket_end(&mut self, bucket_end: Timestamp) {
+        self.0.bucket_end = bucket_end;
+    }
+}
+
+impl RdbSerializable for TwaAggregator {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        self.0.rdb_save(rdb);
+    }
+
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        let inner = TwaAggregatorState::rdb_load(rdb)?;
+        Ok(Self(Box::new(inner)))
+    }
+}
+
+impl AggregationHandler for TwaAggregator {
+    fn update(&mut self, timestamp: Timestamp, value: Value) -> bool {
+        if value.is_nan() {
+            return false;
+        }
+        let state = &mut self.0;
+        if let Some(prev) = state.last_sample {
+            let dt = (timestamp - prev.timestamp).max(0) as f64;
+            state.weighted_sum += prev.value * dt;
+            state.total_weight += dt;
+        }
+        state.last_sample = Some(Sample { timestamp, value });
+        true
+    }
+
+    fn reset(&mut self) {
+        let state = &mut self.0;
+        state.weighted_sum = 0.0;
+        state.total_weight = 0.0;
+        state.last_sample = None;
+    }
+
+    fn current(&self) -> Option<Value> {
+        let state = &self.0;
+        let last = state.last_sample?;
+        if state.total_weight == 0.0 {
+            // single sample: weighted by the whole bucket
+            return Some(last.value);
+        }
+        let tail = if state.bucket_end > last.timestamp {
+            (state.bucket_end - last.timestamp) as f64
+        } else {
+            0.0
+        };
+        let weighted_sum = state.weighted_sum + last.value * tail;
+        let total_weight = state.total_weight + tail;
+        Some(weighted_sum / total_weight)
+    }
+}
+
+// -- CountDistinct ---------------------------------------------------------
+
+/// Number of unique values in the bucket.
+///
+/// Values are keyed on their raw bit pattern (`f64::to_bits`) rather than on float equality, so
+/// `0.0` and `-0.0` are counted as distinct values, and NaNs are counted once per distinct NaN
+/// payload (all NaNs produced by arithmetic share the same payload and count as a single value).
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct CountDistinctAggregator(Box<AHashSet<u64>>);
+
+impl AggregationHandler for CountDistinctAggregator {
+    fn update(&mut self, _timestamp: Timestamp, value: Value) -> bool {
+        self.0.insert(value.to_bits());
+        true
+    }
+    fn reset(&mut self) {
+        self.0.clear();
+    }
+    fn current(&self) -> Option<Value> {
+        if self.0.is_empty() {
+            return None;
+        }
+        Some(self.0.len() as Value)
+    }
+    fn empty_value(&self) -> Value {
+        0.
+    }
+}
+
+impl GetSize for CountDistinctAggregator {
+    fn get_size(&self) -> usize {
+        size_of::<Box<AHashSet<u64>>>()
+            + size_of::<AHashSet<u64>>()
+            + self.0.capacity() * size_of::<u64>()
+    }
+}
+
+impl Hash for CountDistinctAggregator {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let mut values: Vec<u64> = self.0.iter().copied().collect();
+        values.sort_unstable();
+        values.hash(state);
+    }
+}
+
+impl RdbSerializable for CountDistinctAggregator {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        rdb_save_usize(rdb, self.0.len());
+        for bits in self.0.iter() {
+            raw::save_unsigned(rdb, *bits);
+        }
+    }
+
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        let len = rdb_load_usize(rdb)?;
+        let mut values = AHashSet::with_capacity(len);
+        for _ in 0..len {
+            values.insert(raw::load_unsigned(rdb)?);
+        }
+        Ok(Self(Box::new(values)))
+    }
+}
+
+// -- Delta -----------------------------------------------------------------
+
+/// Difference between the last and first values in the bucket, without per-second normalization
+/// or counter reset handling.
+#[derive(Copy, Clone, Default, Debug, PartialEq, GetSize)]
+pub struct DeltaAggregator {
+    first: Option<Value>,
+    last: Value,
+}
+
+impl AggregationHandler for DeltaAggregator {
+    fn update(&mut self, _timestamp: Timestamp, value: Value) -> bool {
+        if value.is_nan() {
+            return false;
+        }
+        if self.first.is_none() {
+            self.first = Some(value);
+        }
+        self.last = value;
+        true
+    }
+    fn reset(&mut self) {
+        self.first = None;
+        self.last = 0.0;
+    }
+    fn current(&self) -> Option<Value> {
+        self.first.map(|first| self.last - first)
+    }
+}
+
+impl RdbSerializable for DeltaAggregator {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        rdb_save_optional_f64(rdb, self.first);
+        raw::save_double(rdb, self.last);
+    }
+
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        let first = rdb_load_optional_f64(rdb)?;
+        let last = raw::load_double(rdb)?;
+        Ok(Self { first, last })
+    }
+}
+
+impl Hash for DeltaAggregator {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        hash_f64(self.first.unwrap_or(f64::NAN), state);
+        hash_f64(self.last, state);
+    }
+}
+
+// -- Deriv -----------------------------------------------------------------
+
+/// Running sums for a least-squares fit of value over time. X values are seconds relative to the
+/// first sample in the bucket, to keep the sums well-conditioned.
+#[derive(Clone, Debug, Default, PartialEq, GetSize)]
+pub struct DerivAggregatorState {
+    first_timestamp: Option<Timestamp>,
+    count: usize,
+    sum_x: f64,
+    sum_y: f64,
+    sum_xx: f64,
+    sum_xy: f64,
+}
+
+impl Hash for DerivAggregatorState {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.first_timestamp.hash(state);
+        self.count.hash(state);
+        hash_f64(self.sum_x, state);
+        hash_f64(self.sum_y, state);
+        hash_f64(self.sum_xx, state);
+        hash_f64(self.sum_xy, state);
+    }
+}
+
+impl RdbSerializable for DerivAggregatorState {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        rdb_save_usize(rdb, self.count);
+        if self.count == 0 {
+            return;
+        }
+        raw::save_signed(rdb, self.first_timestamp.unwrap_or_default());
+        raw::save_double(rdb, self.sum_x);
+        raw::save_double(rdb, self.sum_y);
+        raw::save_double(rdb, self.sum_xx);
+        raw::save_double(rdb, self.sum_xy);
+    }
+
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        let count = rdb_load_usize(rdb)?;
+        if count == 0 {
+            return Ok(Self::default());
+        }
+        let first_timestamp = Some(raw::load_signed(rdb)?);
+        let sum_x = raw::load_double(rdb)?;
+        let sum_y = raw::load_double(rdb)?;
+        let sum_xx = raw::load_double(rdb)?;
+        let sum_xy = raw::load_double(rdb)?;
+        Ok(Self {
+            first_timestamp,
+            count,
+            sum_x,
+            sum_y,
+            sum_xx,
+            sum_xy,
+        })
+    }
+}
+
+/// Per-second slope of the simple linear regression of the bucket's samples, using the sample
+/// timestamps as x values. A bucket with a single sample has a slope of 0.
+#[derive(Clone, Debug, Default, GetSize, Hash, PartialEq)]
+pub struct DerivAggregator(Box<DerivAggregatorState>);
+
+impl RdbSerializable for DerivAggregator {
+    fn rdb_save(&self, rdb: *mut RedisModuleIO) {
+        self.0.rdb_save(rdb);
+    }
+
+    fn rdb_load(rdb: *mut RedisModuleIO) -> ValkeyResult<Self>
+    where
+        Self: Sized,
+    {
+        let inner = DerivAggregatorState::rdb_load(rdb)?;
+        Ok(Self(Box::new(inner)))
+    }
+}
+
+impl AggregationHandler for DerivAggregator {
+    fn update(&mut self, timestamp: Timestamp, value: Value) -> bool {
+        if value.is_nan() {
+            return false;
+        }
+        let state = &mut self.0;
+        let first_ts = *state.first_timestamp.get_or_insert(timestamp);
+        let x = (timestamp - first_ts) as f64 / 1e3;
+        state.count += 1;
+        state.sum_x += x;
+        state.sum_y += value;
+        state.sum_xx += x * x;
+        state.sum_xy += x * value;
+        true
+    }
+
+    fn reset(&mut self) {
+        *self.0 = DerivAggregatorState::default();
+    }
+
+    fn current(&self) -> Option<Value> {
+        let state = &self.0;
+        if state.count == 0 {
+            return None;
+        }
+        let n = state.count as f64;
+        let denominator = n * state.sum_xx - state.sum_x * state.sum_x;
+        if state.count == 1 || denominator == 0.0 {
+            return Some(0.0);
+        }
+        Some((n * state.sum_xy - state.sum_x * state.sum_y) / denominator)
+    }
+}
 
 #[enum_dispatch(AggregationHandler)]
 #[derive(Clone, Debug, Hash, PartialEq, GetSize)]
@@ -1060,6 +1699,14 @@ pub enum Aggregator {
     SumIf(SumIfAggregator),
     VarP(VarPAggregator),
     VarS(VarSAggregator),
+    Twa(TwaAggregator),
+    Delta(DeltaAggregator),
+    Deriv(DerivAggregator),
+    CountDistinct(CountDistinctAggregator),
+    AbsMax(AbsMaxAggregator),
+    AbsMin(AbsMinAggregator),
+    TrimmedMean(TrimmedMeanAggregator),
+    Mad(MadAggregator),
 }
 
 impl Default for Aggregator {
@@ -1113,6 +1760,18 @@ impl From<AggregationType> for Aggregator {
             AggregationType::VarS => Aggregator::VarS(VarSAggregator::default()),
             AggregationType::Sum => Aggregator::Sum(SumAggregator::default()),
             AggregationType::SumIf => Aggregator::SumIf(SumIfAggregator::default()),
+            AggregationType::Twa => Aggregator::Twa(TwaAggregator::default()),
+            AggregationType::Delta => Aggregator::Delta(DeltaAggregator::default()),
+            AggregationType::Deriv => Aggregator::Deriv(DerivAggregator::default()),
+            AggregationType::CountDistinct => {
+                Aggregator::CountDistinct(CountDistinctAggregator::default())
+            }
+            AggregationType::AbsMax => Aggregator::AbsMax(AbsMaxAggregator::default()),
+            AggregationType::AbsMin => Aggregator::AbsMin(AbsMinAggregator::default()),
+            AggregationType::TrimmedMean => {
+                Aggregator::TrimmedMean(TrimmedMeanAggregator::default())
+            }
+            AggregationType::Mad => Aggregator::Mad(MadAggregator::default()),
         }
     }
 }
@@ -1146,6 +1805,14 @@ impl RdbSerializable for Aggregator {
             Aggregator::StdS(agg) => agg.rdb_save(rdb),
             Aggregator::VarP(agg) => agg.rdb_save(rdb),
             Aggregator::VarS(agg) => agg.rdb_save(rdb),
+            Aggregator::Twa(agg) => agg.rdb_save(rdb),
+            Aggregator::Delta(agg) => agg.rdb_save(rdb),
+            Aggregator::Deriv(agg) => agg.rdb_save(rdb),
+            Aggregator::CountDistinct(agg) => agg.rdb_save(rdb),
+            Aggregator::AbsMax(agg) => agg.rdb_save(rdb),
+            Aggregator::AbsMin(agg) => agg.rdb_save(rdb),
+            Aggregator::TrimmedMean(agg) => agg.rdb_save(rdb),
+            Aggregator::Mad(agg) => agg.rdb_save(rdb),
         }
     }
 
@@ -1186,11 +1853,31 @@ impl RdbSerializable for Aggregator {
             AggregationType::VarS => VarSAggregator::rdb_load(rdb).map(Aggregator::VarS),
             AggregationType::Sum => SumAggregator::rdb_load(rdb).map(Aggregator::Sum),
             AggregationType::SumIf => SumIfAggregator::rdb_load(rdb).map(Aggregator::SumIf),
+            AggregationType::Twa => TwaAggregator::rdb_load(rdb).map(Aggregator::Twa),
+            AggregationType::Delta => DeltaAggregator::rdb_load(rdb).map(Aggregator::Delta),
+            AggregationType::Deriv => DerivAggregator::rdb_load(rdb).map(Aggregator::Deriv),
+            AggregationType::CountDistinct => {
+                CountDistinctAggregator::rdb_load(rdb).map(Aggregator::CountDistinct)
+            }
+            AggregationType::AbsMax => AbsMaxAggregator::rdb_load(rdb).map(Aggregator::AbsMax),
+            AggregationType::AbsMin => AbsMinAggregator::rdb_load(rdb).map(Aggregator::AbsMin),
+            AggregationType::TrimmedMean => {
+                TrimmedMeanAggregator::rdb_load(rdb).map(Aggregator::TrimmedMean)
+            }
+            AggregationType::Mad => MadAggregator::rdb_load(rdb).map(Aggregator::Mad),
         }
     }
 }
 
 impl Aggregator {
+    /// Adds `count` samples to a `countall` aggregator in one step.
+    pub(crate) fn add_sample_count(&mut self, count: usize) {
+        match self {
+            Aggregator::CountAll(aggr) => aggr.0 += count,
+            _ => debug_assert!(false, "sample counts require a countall aggregator"),
+        }
+    }
+
     pub fn aggregation_type(&self) -> AggregationType {
         match self {
             Aggregator::All(_) => AggregationType::All,
@@ -1216,6 +1903,22 @@ impl Aggregator {
             Aggregator::VarS(_) => AggregationType::VarS,
             Aggregator::Sum(_) => AggregationType::Sum,
             Aggregator::SumIf(_) => AggregationType::SumIf,
+            Aggregator::Twa(_) => AggregationType::Twa,
+            Aggregator::Delta(_) => AggregationType::Delta,
+            Aggregator::Deriv(_) => AggregationType::Deriv,
+            Aggregator::CountDistinct(_) => AggregationType::CountDistinct,
+            Aggregator::AbsMax(_) => AggregationType::AbsMax,
+            Aggregator::AbsMin(_) => AggregationType::AbsMin,
+            Aggregator::TrimmedMean(_) => AggregationType::TrimmedMean,
+            Aggregator::Mad(_) => AggregationType::Mad,
+        }
+    }
+
+    /// Sets the (exclusive) end of the current bucket for aggregators whose result depends on
+    /// the bucket bounds, not just on the samples seen. This is a no-op for other aggregators.
+    pub fn set_bucket_end(&mut self, bucket_end: Timestamp) {
+        if let Aggregator::Twa(twa) = self {
+            twa.set_bucket_end(bucket_end);
         }
     }
 }
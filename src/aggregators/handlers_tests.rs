@@ -1,11 +1,12 @@
 #[cfg(test)]
 mod tests {
     use crate::aggregators::{
-        AggregationHandler, AvgAggregator, CountAggregator, CountAllAggregator, CountIfAggregator,
-        CountNanAggregator, FirstAggregator, IRateAggregator, IncreaseAggregator, LastAggregator,
-        MaxAggregator, MinAggregator, RangeAggregator, RateAggregator, ShareAggregator,
-        StdPAggregator, StdSAggregator, SumAggregator, SumIfAggregator, VarPAggregator,
-        VarSAggregator,
+        AbsMaxAggregator, AbsMinAggregator, AggregationHandler, AvgAggregator, CountAggregator,
+        CountAllAggregator, CountDistinctAggregator, CountIfAggregator, CountNanAggregator,
+        DeltaAggregator, DerivAggregator, FirstAggregator, IRateAggregator, IncreaseAggregator,
+        LastAggregator, MadAggregator, MaxAggregator, MinAggregator, RangeAggregator,
+        RateAggregator, ShareAggregator, StdPAggregator, StdSAggregator, SumAggregator,
+        SumIfAggregator, TrimmedMeanAggregator, TwaAggregator, VarPAggregator, VarSAggregator,
     };
     use crate::common::binop::ComparisonOperator;
     use std::time::Duration;
@@ -76,6 +77,107 @@ mod tests {
         assert_eq!(agg.current(), None);
     }
 
+    #[test]
+    fn test_abs_max_aggregator_mixed_signs() {
+        let mut agg = AbsMaxAggregator::default();
+        assert_eq!(agg.current(), None);
+
+        agg.update(1000, 3.0);
+        agg.update(2000, -7.5);
+        agg.update(3000, 5.0);
+        agg.update(4000, f64::NAN);
+        assert_eq!(agg.current(), Some(-7.5));
+
+        // tie in magnitude keeps the first encountered
+        agg.update(5000, 7.5);
+        assert_eq!(agg.current(), Some(-7.5));
+
+        assert_eq!(agg.finalize(), -7.5);
+        assert_eq!(agg.current(), None);
+    }
+
+    #[test]
+    fn test_abs_min_aggregator_mixed_signs() {
+        let mut agg = AbsMinAggregator::default();
+        assert_eq!(agg.current(), None);
+
+        agg.update(1000, -4.0);
+        agg.update(2000, 2.0);
+        agg.update(3000, -1.5);
+        agg.update(4000, 9.0);
+        assert_eq!(agg.current(), Some(-1.5));
+
+        // tie in magnitude keeps the first encountered
+        agg.update(5000, 1.5);
+        assert_eq!(agg.current(), Some(-1.5));
+
+        agg.reset();
+        agg.update(6000, 0.5);
+        agg.update(7000, -0.5);
+        assert_eq!(agg.current(), Some(0.5));
+    }
+
+    #[test]
+    fn test_trimmed_mean_aggregator_excludes_outliers() {
+        let mut agg = TrimmedMeanAggregator::new(10.0);
+        assert_eq!(agg.current(), None);
+
+        // 10 values, 10% trims one value from each tail
+        let values = [1000.0, 10.0, 11.0, 9.0, 10.0, 12.0, 8.0, 10.0, 10.0, -500.0];
+        for (i, value) in values.iter().enumerate() {
+            agg.update(i as i64 * 1000, *value);
+        }
+        assert_eq!(agg.current(), Some(10.0));
+
+        agg.reset();
+        assert_eq!(agg.current(), None);
+    }
+
+    #[test]
+    fn test_trimmed_mean_aggregator_small_buckets() {
+        // 3 values at 40% trims one value per tail, leaving the median
+        let mut agg = TrimmedMeanAggregator::new(40.0);
+        agg.update(1000, 1.0);
+        agg.update(2000, 2.0);
+        agg.update(3000, 9.0);
+        assert_eq!(agg.current(), Some(2.0));
+
+        // too few values to trim anything: plain mean
+        let mut agg = TrimmedMeanAggregator::new(49.0);
+        agg.update(1000, 49.0);
+        agg.update(2000, 51.0);
+        assert_eq!(agg.current(), Some(50.0));
+
+        let mut agg = TrimmedMeanAggregator::new(0.0);
+        agg.update(1000, 100.0);
+        agg.update(2000, 0.0);
+        agg.update(3000, f64::NAN);
+        assert_eq!(agg.current(), Some(50.0));
+    }
+
+    #[test]
+    fn test_mad_aggregator() {
+        let mut agg = MadAggregator::default();
+        assert_eq!(agg.current(), None);
+
+        for (i, value) in [3.0, 1.0, 5.0, 2.0, 4.0].iter().enumerate() {
+            agg.update(i as i64 * 1000, *value);
+        }
+        assert_eq!(agg.current(), Some(1.0));
+
+        // an outlier barely moves the MAD: median 2.5, deviations [1.5, 0.5, 0.5, 97.5]
+        agg.reset();
+        for (i, value) in [1.0, 2.0, 3.0, 100.0].iter().enumerate() {
+            agg.update(i as i64 * 1000, *value);
+        }
+        assert_eq!(agg.current(), Some(1.0));
+
+        agg.reset();
+        agg.update(1000, 42.0);
+        agg.update(2000, f64::NAN);
+        assert_eq!(agg.current(), Some(0.0));
+    }
+
     #[test]
     fn test_range_aggregator() {
         let mut agg = RangeAggregator::default();
@@ -248,12 +350,12 @@ mod tests {
         agg.update(3000, 125.0);
         assert_eq!(agg.current(), Some(25.0)); // Total increase: 25
 
-        // Counter reset
+        // Counter reset: the counter restarted from zero, so 5 is counted as increase
         agg.update(4000, 5.0);
-        assert_eq!(agg.current(), Some(25.0)); // No change, reset detected
+        assert_eq!(agg.current(), Some(30.0));
 
         agg.update(5000, 15.0);
-        assert_eq!(agg.current(), Some(35.0)); // 25 + 10 = 35
+        assert_eq!(agg.current(), Some(40.0)); // 30 + 10 = 40
     }
 
     #[test]
@@ -265,10 +367,10 @@ mod tests {
 
         // Counter went backwards (reset)
         agg.update(3000, 50.0);
-        assert_eq!(agg.current(), Some(10.0)); // Only counts previous increase
+        assert_eq!(agg.current(), Some(60.0)); // 10 + 50 since the reset
 
         agg.update(4000, 60.0);
-        assert_eq!(agg.current(), Some(20.0)); // 10 + 10 = 20
+        assert_eq!(agg.current(), Some(70.0)); // 60 + 10 = 70
     }
 
     #[test]
@@ -295,9 +397,175 @@ mod tests {
         agg.update(2000, 10.0);
         agg.update(3000, 20.0);
 
-        // Total valid increase: 10 + 10 = 20 over 5 seconds = 4.0/sec
+        // Total increase: 10 + 10 (since reset) + 10 = 30 over 5 seconds = 6.0/sec
         let result = agg.current().unwrap();
-        assert_eq!(result, 4.0);
+        assert_eq!(result, 6.0);
+    }
+
+    #[test]
+    fn test_rate_aggregator_sub_second_window() {
+        let mut agg = RateAggregator::default();
+        agg.set_window_ms(500);
+
+        agg.update(0, 10.0);
+        agg.update(250, 15.0);
+
+        // 5 over half a second = 10/sec
+        assert_eq!(agg.current(), Some(10.0));
+    }
+
+    #[test]
+    fn test_twa_aggregator() {
+        let mut agg = TwaAggregator::default();
+        agg.set_bucket_end(10000);
+        assert_eq!(agg.current(), None);
+
+        // 10.0 holds for 1s, 20.0 holds for 3s, 40.0 holds to the bucket end (6s)
+        agg.update(0, 10.0);
+        agg.update(1000, 20.0);
+        agg.update(4000, 40.0);
+
+        let expected = (10.0 * 1000.0 + 20.0 * 3000.0 + 40.0 * 6000.0) / 10000.0;
+        assert_eq!(agg.current(), Some(expected));
+        assert_eq!(agg.finalize(), expected);
+        assert_eq!(agg.current(), None);
+    }
+
+    #[test]
+    fn test_twa_aggregator_single_sample() {
+        let mut agg = TwaAggregator::default();
+        agg.set_bucket_end(10000);
+        agg.update(2500, 42.0);
+        assert_eq!(agg.current(), Some(42.0));
+    }
+
+    #[test]
+    fn test_twa_aggregator_irregular_vs_avg() {
+        let mut twa = TwaAggregator::default();
+        let mut avg = AvgAggregator::default();
+        twa.set_bucket_end(1000);
+
+        // a burst of high values followed by a long stretch of a low value
+        for (ts, value) in [(0, 1.0), (900, 100.0), (950, 100.0), (990, 100.0)] {
+            twa.update(ts, value);
+            avg.update(ts, value);
+        }
+
+        let expected = (1.0 * 900.0 + 100.0 * 100.0) / 1000.0;
+        assert_eq!(twa.current(), Some(expected));
+        assert!(avg.current().unwrap() > expected);
+    }
+
+    #[test]
+    fn test_twa_aggregator_ignores_nan() {
+        let mut agg = TwaAggregator::default();
+        agg.set_bucket_end(4000);
+        agg.update(0, 10.0);
+        assert!(!agg.update(1000, f64::NAN));
+        agg.update(2000, 20.0);
+
+        // 10.0 holds for 2s, 20.0 holds for 2s
+        assert_eq!(agg.current(), Some(15.0));
+    }
+
+    #[test]
+    fn test_twa_aggregator_reset_keeps_bucket_end() {
+        let mut agg = TwaAggregator::default();
+        agg.set_bucket_end(2000);
+        agg.update(0, 10.0);
+        agg.update(1000, 30.0);
+        assert_eq!(agg.current(), Some(20.0));
+
+        agg.reset();
+        assert_eq!(agg.current(), None);
+        agg.update(0, 30.0);
+        agg.update(1000, 10.0);
+        assert_eq!(agg.current(), Some(20.0));
+    }
+
+    #[test]
+    fn test_count_distinct_aggregator() {
+        let mut agg = CountDistinctAggregator::default();
+        assert_eq!(agg.current(), None);
+        assert_eq!(agg.finalize(), 0.0);
+
+        for (i, value) in [200.0, 404.0, 200.0, 500.0, 404.0, 200.0]
+            .iter()
+            .enumerate()
+        {
+            agg.update(i as i64 * 1000, *value);
+        }
+        assert_eq!(agg.current(), Some(3.0));
+
+        assert_eq!(agg.finalize(), 3.0);
+        assert_eq!(agg.current(), None);
+    }
+
+    #[test]
+    fn test_count_distinct_aggregator_uses_bit_patterns() {
+        let mut agg = CountDistinctAggregator::default();
+        agg.update(1000, 0.0);
+        agg.update(2000, -0.0);
+        agg.update(3000, f64::NAN);
+        agg.update(4000, f64::NAN);
+
+        // +0.0 and -0.0 differ in their bit pattern; identical NaNs are one value
+        assert_eq!(agg.current(), Some(3.0));
+    }
+
+    #[test]
+    fn test_delta_aggregator() {
+        let mut agg = DeltaAggregator::default();
+        assert_eq!(agg.current(), None);
+
+        agg.update(1000, 100.0);
+        assert_eq!(agg.current(), Some(0.0));
+
+        agg.update(2000, 130.0);
+        // drops are not treated as resets
+        agg.update(3000, 90.0);
+        assert_eq!(agg.current(), Some(-10.0));
+
+        assert_eq!(agg.finalize(), -10.0);
+        assert_eq!(agg.current(), None);
+    }
+
+    #[test]
+    fn test_deriv_aggregator_linear_series() {
+        let mut agg = DerivAggregator::default();
+        assert_eq!(agg.current(), None);
+
+        // y = 2.5 * t_seconds + 10
+        for i in 0..10 {
+            let ts = 1_700_000_000_000 + i * 1000;
+            agg.update(ts, 2.5 * i as f64 + 10.0);
+        }
+
+        let slope = agg.current().unwrap();
+        assert!((slope - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_deriv_aggregator_irregular_linear_series() {
+        let mut agg = DerivAggregator::default();
+
+        // y = -4 * t_seconds, sampled at irregular intervals
+        for ts in [0, 250, 1000, 1750, 4000] {
+            agg.update(ts, -4.0 * ts as f64 / 1000.0);
+        }
+
+        let slope = agg.current().unwrap();
+        assert!((slope + 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_deriv_aggregator_single_sample() {
+        let mut agg = DerivAggregator::default();
+        agg.update(1000, 42.0);
+        assert_eq!(agg.current(), Some(0.0));
+
+        agg.reset();
+        assert_eq!(agg.current(), None);
     }
 
     #[test]
@@ -1,4 +1,5 @@
 use crate::common::Timestamp;
+use crate::common::time::{MILLIS_PER_DAY, civil_from_days, days_from_civil};
 use crate::error_consts;
 use crate::parser::timestamp::parse_timestamp;
 use std::fmt::Display;
@@ -21,6 +22,8 @@ pub enum BucketTimestamp {
     Start,
     End,
     Mid,
+    /// A fixed offset (ms) from the bucket start, capped at the bucket end
+    Offset(u64),
 }
 
 impl BucketTimestamp {
@@ -29,6 +32,7 @@ impl BucketTimestamp {
             Self::Start => ts,
             Self::Mid => ts.saturating_add_unsigned(time_delta / 2),
             Self::End => ts.saturating_add_unsigned(time_delta),
+            Self::Offset(offset) => ts.saturating_add_unsigned((*offset).min(time_delta)),
         }
     }
 }
@@ -45,12 +49,20 @@ impl TryFrom<&str> for BucketTimestamp {
             "end" => BucketTimestamp::End,
             "mid" => BucketTimestamp::Mid,
         };
-        match ts {
-            Some(ts) => Ok(ts),
-            None => Err(ValkeyError::Str(
-                error_consts::INVALID_BUCKET_TIMESTAMP_TYPE,
-            )),
+        if let Some(ts) = ts {
+            return Ok(ts);
         }
+        // numeric offset from the bucket start, e.g. `+250`
+        let offset = value.strip_prefix('+').unwrap_or(value);
+        if !offset.is_empty() && offset.bytes().all(|b| b.is_ascii_digit()) {
+            return offset
+                .parse::<u64>()
+                .map(BucketTimestamp::Offset)
+                .map_err(|_| ValkeyError::Str(error_consts::INVALID_BUCKET_TIMESTAMP_TYPE));
+        }
+        Err(ValkeyError::Str(
+            error_consts::INVALID_BUCKET_TIMESTAMP_TYPE,
+        ))
     }
 }
 
@@ -61,6 +73,65 @@ impl TryFrom<&ValkeyString> for BucketTimestamp {
     }
 }
 
+/// Controls how empty buckets are populated when reported (see `FILL`).
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum FillPolicy {
+    /// Report empty buckets as NaN
+    Null,
+    /// Report empty buckets as 0
+    Zero,
+    /// Carry forward the value of the last non-empty bucket
+    Previous,
+    /// Interpolate linearly between the surrounding non-empty buckets
+    Linear,
+}
+
+impl FillPolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FillPolicy::Null => "null",
+            FillPolicy::Zero => "zero",
+            FillPolicy::Previous => "previous",
+            FillPolicy::Linear => "linear",
+        }
+    }
+}
+
+impl Display for FillPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl TryFrom<&str> for FillPolicy {
+    type Error = ValkeyError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let policy = hashify::tiny_map_ignore_case! {
+            value.as_bytes(),
+            "null" => FillPolicy::Null,
+            "zero" => FillPolicy::Zero,
+            "previous" => FillPolicy::Previous,
+            "linear" => FillPolicy::Linear,
+        };
+        policy.ok_or(ValkeyError::Str(error_consts::INVALID_FILL_POLICY))
+    }
+}
+
+/// The nominal length of a calendar month. With `ALIGN month`, the bucket duration must be a
+/// whole multiple of this, and each multiple stands for one calendar month.
+pub const CALENDAR_MONTH_DURATION_MS: u64 = 30 * MILLIS_PER_DAY as u64;
+
+/// Maximum absolute UTC offset accepted for calendar alignment (14 hours).
+const MAX_UTC_OFFSET_MS: i64 = 14 * 60 * 60 * 1000;
+
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum CalendarUnit {
+    Day,
+    /// ISO weeks, starting on Monday
+    Week,
+    Month,
+}
+
 #[derive(Debug, Default, PartialEq, Clone, Copy, Eq)]
 pub enum BucketAlignment {
     #[default]
@@ -68,17 +139,88 @@ pub enum BucketAlignment {
     Start,
     End,
     Timestamp(Timestamp),
+    /// Align buckets on local calendar boundaries, given a fixed UTC offset in milliseconds.
+    Calendar(CalendarUnit, i64),
+    /// Align buckets to the first sample of each series, so that bucket boundaries don't move as
+    /// the query range slides. Resolved per series by `resolve_series_start`.
+    SeriesStart,
 }
 
 impl BucketAlignment {
     pub fn get_aligned_timestamp(&self, start: Timestamp, end: Timestamp) -> Timestamp {
         match self {
             BucketAlignment::Default => 0,
-            BucketAlignment::Start => start,
+            // only reached if the series is unknown, e.g. when joining two series
+            BucketAlignment::Start | BucketAlignment::SeriesStart => start,
             BucketAlignment::End => end,
             BucketAlignment::Timestamp(ts) => *ts,
+            // local midnight. 1970-01-05 is the first Monday after the epoch.
+            BucketAlignment::Calendar(CalendarUnit::Week, offset) => {
+                (4 * MILLIS_PER_DAY - offset).rem_euclid(7 * MILLIS_PER_DAY)
+            }
+            BucketAlignment::Calendar(_, offset) => (-offset).rem_euclid(MILLIS_PER_DAY),
+        }
+    }
+
+    /// Replaces `SeriesStart` with the timestamp of the first sample of the series being
+    /// aggregated. Other alignments are returned unchanged.
+    pub fn resolve_series_start(self, series_start: Timestamp) -> Self {
+        match self {
+            BucketAlignment::SeriesStart => BucketAlignment::Timestamp(series_start),
+            other => other,
         }
     }
+
+    /// Returns the number of calendar months spanned by each bucket if buckets are month aligned.
+    pub fn months_per_bucket(&self, bucket_duration: u64) -> Option<u32> {
+        match self {
+            BucketAlignment::Calendar(CalendarUnit::Month, _) => {
+                Some((bucket_duration / CALENDAR_MONTH_DURATION_MS).max(1) as u32)
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_calendar(value: &str) -> Option<Self> {
+        let (unit, offset) = match value.find(['+', '-']) {
+            Some(pos) => value.split_at(pos),
+            None => (value, ""),
+        };
+        let unit = hashify::tiny_map_ignore_case! {
+            unit.as_bytes(),
+            "day" => CalendarUnit::Day,
+            "week" => CalendarUnit::Week,
+            "month" => CalendarUnit::Month,
+        }?;
+        let offset = if offset.is_empty() {
+            0
+        } else {
+            parse_utc_offset(offset)?
+        };
+        Some(BucketAlignment::Calendar(unit, offset))
+    }
+}
+
+/// Parses a UTC offset of the form `+HH`, `+HH:MM` or `+HHMM` (or with a leading `-`) into
+/// milliseconds.
+fn parse_utc_offset(value: &str) -> Option<i64> {
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours = hours.parse::<i64>().ok()?;
+    let minutes = minutes.parse::<i64>().ok()?;
+    if !(0..60).contains(&minutes) {
+        return None;
+    }
+    let offset = sign * (hours * 60 + minutes) * 60 * 1000;
+    (offset.abs() <= MAX_UTC_OFFSET_MS).then_some(offset)
 }
 
 impl TryFrom<&str> for BucketAlignment {
@@ -90,7 +232,9 @@ impl TryFrom<&str> for BucketAlignment {
             "end" => BucketAlignment::End,
             "-" => BucketAlignment::Start,
             "+" => BucketAlignment::End,
+            "series_start" => BucketAlignment::SeriesStart,
         };
+        let alignment = alignment.or_else(|| BucketAlignment::parse_calendar(value));
         match alignment {
             Some(alignment) => Ok(alignment),
             None => {
@@ -142,6 +286,14 @@ pub enum AggregationType {
     SumIf,
     VarP,
     VarS,
+    Twa,
+    Delta,
+    Deriv,
+    CountDistinct,
+    AbsMax,
+    AbsMin,
+    TrimmedMean,
+    Mad,
 }
 
 impl AggregationType {
@@ -170,6 +322,14 @@ impl AggregationType {
             AggregationType::Rate => "rate",
             AggregationType::VarS => "var.s",
             AggregationType::VarP => "var.p",
+            AggregationType::Twa => "twa",
+            AggregationType::Delta => "delta",
+            AggregationType::Deriv => "deriv",
+            AggregationType::CountDistinct => "count_distinct",
+            AggregationType::AbsMax => "abs.max",
+            AggregationType::AbsMin => "abs.min",
+            AggregationType::TrimmedMean => "trimmed_mean",
+            AggregationType::Mad => "mad",
         }
     }
 
@@ -189,6 +349,12 @@ impl AggregationType {
         matches!(self, AggregationType::Count | AggregationType::Sum)
     }
 
+    /// Returns true if the aggregation takes a numeric parameter before the bucket duration,
+    /// e.g. `AGGREGATION trimmed_mean 10 1m`.
+    pub fn has_param(&self) -> bool {
+        matches!(self, AggregationType::TrimmedMean)
+    }
+
     /// Returns true if the aggregation type can be used for GroupBy Reduce operations
     pub fn is_groupable(&self) -> bool {
         !self.is_filtered()
@@ -243,6 +409,14 @@ impl TryFrom<&str> for AggregationType {
             "sumif" => AggregationType::SumIf,
             "var.s" => AggregationType::VarS,
             "var.p" => AggregationType::VarP,
+            "twa" => AggregationType::Twa,
+            "delta" => AggregationType::Delta,
+            "deriv" => AggregationType::Deriv,
+            "count_distinct" => AggregationType::CountDistinct,
+            "abs.max" => AggregationType::AbsMax,
+            "abs.min" => AggregationType::AbsMin,
+            "trimmed_mean" => AggregationType::TrimmedMean,
+            "mad" => AggregationType::Mad,
         };
 
         match value {
@@ -288,6 +462,14 @@ impl TryFrom<u8> for AggregationType {
             20 => Ok(AggregationType::SumIf),
             21 => Ok(AggregationType::VarP),
             22 => Ok(AggregationType::VarS),
+            23 => Ok(AggregationType::Twa),
+            24 => Ok(AggregationType::Delta),
+            25 => Ok(AggregationType::Deriv),
+            26 => Ok(AggregationType::CountDistinct),
+            27 => Ok(AggregationType::AbsMax),
+            28 => Ok(AggregationType::AbsMin),
+            29 => Ok(AggregationType::TrimmedMean),
+            30 => Ok(AggregationType::Mad),
             _ => Err(ValkeyError::Str("TSDB: invalid AGGREGATION value")),
         }
     }
@@ -319,6 +501,14 @@ impl From<AggregationType> for u8 {
             AggregationType::StdS => 20,
             AggregationType::VarP => 21,
             AggregationType::VarS => 22,
+            AggregationType::Twa => 23,
+            AggregationType::Delta => 24,
+            AggregationType::Deriv => 25,
+            AggregationType::CountDistinct => 26,
+            AggregationType::AbsMax => 27,
+            AggregationType::AbsMin => 28,
+            AggregationType::TrimmedMean => 29,
+            AggregationType::Mad => 30,
         }
     }
 }
@@ -335,10 +525,52 @@ pub fn calc_bucket_start(
     0.max(ts - ((diff % delta + delta) % delta))
 }
 
+/// Calculates the start of the calendar-month bucket containing `ts`. Buckets span `months`
+/// calendar months in the local time given by `utc_offset`, and are anchored so that e.g. 3-month
+/// buckets start in January, April, July and October.
+pub fn calc_month_bucket_start(ts: Timestamp, months: u32, utc_offset: i64) -> Timestamp {
+    let month_index = calendar_month_index(ts, utc_offset);
+    let bucket_index = month_index - month_index.rem_euclid(months as i64);
+    0.max(month_index_to_timestamp(bucket_index, utc_offset))
+}
+
+/// Returns the number of calendar months between year 0 and the local month containing `ts`.
+pub(crate) fn calendar_month_index(ts: Timestamp, utc_offset: i64) -> i64 {
+    let local_days = (ts + utc_offset).div_euclid(MILLIS_PER_DAY);
+    let (year, month, _) = civil_from_days(local_days);
+    year * 12 + month as i64 - 1
+}
+
+/// Returns the timestamp `months` calendar months after the month-aligned `bucket_start`.
+pub fn add_calendar_months(bucket_start: Timestamp, months: u32, utc_offset: i64) -> Timestamp {
+    let local_days = (bucket_start + utc_offset).div_euclid(MILLIS_PER_DAY);
+    let (year, month, _) = civil_from_days(local_days);
+    let month_index = year * 12 + month as i64 - 1 + months as i64;
+    month_index_to_timestamp(month_index, utc_offset)
+}
+
+fn month_index_to_timestamp(month_index: i64, utc_offset: i64) -> Timestamp {
+    let year = month_index.div_euclid(12);
+    let month = month_index.rem_euclid(12) as u32 + 1;
+    days_from_civil(year, month, 1) * MILLIS_PER_DAY - utc_offset
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn fill_policy_parses_case_insensitively() {
+        assert_eq!(FillPolicy::try_from("null").unwrap(), FillPolicy::Null);
+        assert_eq!(FillPolicy::try_from("ZERO").unwrap(), FillPolicy::Zero);
+        assert_eq!(
+            FillPolicy::try_from("Previous").unwrap(),
+            FillPolicy::Previous
+        );
+        assert_eq!(FillPolicy::try_from("linear").unwrap(), FillPolicy::Linear);
+        assert!(FillPolicy::try_from("cubic").is_err());
+    }
+
     #[test]
     fn bucket_timestamp_calculates_correctly_for_start() {
         let ts = Timestamp::from(1000);
@@ -346,6 +578,44 @@ mod tests {
         assert_eq!(BucketTimestamp::Start.calculate(ts, delta), ts);
     }
 
+    #[test]
+    fn bucket_timestamp_calculates_correctly_for_offset() {
+        let ts = Timestamp::from(1000);
+        let delta = 500;
+        assert_eq!(
+            BucketTimestamp::Offset(250).calculate(ts, delta),
+            Timestamp::from(1250)
+        );
+        assert_eq!(BucketTimestamp::Offset(0).calculate(ts, delta), ts);
+        // offsets past the bucket end are capped at the end
+        assert_eq!(
+            BucketTimestamp::Offset(900).calculate(ts, delta),
+            Timestamp::from(1500)
+        );
+    }
+
+    #[test]
+    fn bucket_timestamp_try_from_str_parses_offsets() {
+        assert_eq!(
+            BucketTimestamp::try_from("+250").unwrap(),
+            BucketTimestamp::Offset(250)
+        );
+        assert_eq!(
+            BucketTimestamp::try_from("100").unwrap(),
+            BucketTimestamp::Offset(100)
+        );
+        assert_eq!(
+            BucketTimestamp::try_from("+").unwrap(),
+            BucketTimestamp::End
+        );
+        assert_eq!(
+            BucketTimestamp::try_from("~").unwrap(),
+            BucketTimestamp::Mid
+        );
+        assert!(BucketTimestamp::try_from("+-5").is_err());
+        assert!(BucketTimestamp::try_from("+2.5").is_err());
+    }
+
     #[test]
     fn bucket_timestamp_calculates_correctly_for_mid() {
         let ts = Timestamp::from(1000);
@@ -418,6 +688,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn bucket_alignment_parses_calendar_units() {
+        const HOUR: i64 = 60 * 60 * 1000;
+        assert_eq!(
+            BucketAlignment::try_from("day").unwrap(),
+            BucketAlignment::Calendar(CalendarUnit::Day, 0)
+        );
+        assert_eq!(
+            BucketAlignment::try_from("WEEK+02:00").unwrap(),
+            BucketAlignment::Calendar(CalendarUnit::Week, 2 * HOUR)
+        );
+        assert_eq!(
+            BucketAlignment::try_from("month-0530").unwrap(),
+            BucketAlignment::Calendar(CalendarUnit::Month, -(5 * HOUR + HOUR / 2))
+        );
+        assert!(BucketAlignment::try_from("day+15").is_err());
+        assert!(BucketAlignment::try_from("day+02:75").is_err());
+        assert!(BucketAlignment::try_from("year").is_err());
+    }
+
+    #[test]
+    fn calendar_alignment_snaps_to_local_midnight() {
+        const HOUR: i64 = 60 * 60 * 1000;
+        let day = BucketAlignment::Calendar(CalendarUnit::Day, 2 * HOUR);
+        assert_eq!(day.get_aligned_timestamp(0, 0), 22 * HOUR);
+
+        // 1970-01-05 was a Monday
+        let week = BucketAlignment::Calendar(CalendarUnit::Week, 0);
+        assert_eq!(week.get_aligned_timestamp(0, 0), 4 * MILLIS_PER_DAY);
+        let monday = days_from_civil(2024, 6, 10) * MILLIS_PER_DAY;
+        let sunday_evening = monday + 6 * MILLIS_PER_DAY + 23 * HOUR;
+        let align = week.get_aligned_timestamp(0, 0);
+        assert_eq!(
+            calc_bucket_start(sunday_evening, align, 7 * MILLIS_PER_DAY as u64),
+            monday
+        );
+    }
+
+    #[test]
+    fn month_bucket_start_handles_offsets_and_multiples() {
+        const HOUR: i64 = 60 * 60 * 1000;
+        let ts = |y, m, d| days_from_civil(y, m, d) * MILLIS_PER_DAY;
+
+        assert_eq!(
+            calc_month_bucket_start(ts(2024, 2, 29), 1, 0),
+            ts(2024, 2, 1)
+        );
+        assert_eq!(
+            calc_month_bucket_start(ts(2024, 5, 20), 3, 0),
+            ts(2024, 4, 1)
+        );
+        assert_eq!(
+            calc_month_bucket_start(ts(2024, 12, 31), 12, 0),
+            ts(2024, 1, 1)
+        );
+        // 2024-03-01 00:30 UTC is still February in UTC-01:00
+        let local = calc_month_bucket_start(ts(2024, 3, 1) + HOUR / 2, 1, -HOUR);
+        assert_eq!(local, ts(2024, 2, 1) + HOUR);
+        assert_eq!(add_calendar_months(ts(2024, 1, 1), 1, 0), ts(2024, 2, 1));
+        assert_eq!(add_calendar_months(ts(2024, 11, 1), 3, 0), ts(2025, 2, 1));
+    }
+
     #[test]
     fn bucket_alignment_try_from_str_parses_valid_values() {
         assert_eq!(
@@ -471,6 +803,14 @@ mod tests {
         assert_eq!(AggregationType::StdP.name(), "std.p");
         assert_eq!(AggregationType::VarS.name(), "var.s");
         assert_eq!(AggregationType::VarP.name(), "var.p");
+        assert_eq!(AggregationType::Twa.name(), "twa");
+        assert_eq!(AggregationType::Delta.name(), "delta");
+        assert_eq!(AggregationType::Deriv.name(), "deriv");
+        assert_eq!(AggregationType::CountDistinct.name(), "count_distinct");
+        assert_eq!(AggregationType::AbsMax.name(), "abs.max");
+        assert_eq!(AggregationType::AbsMin.name(), "abs.min");
+        assert_eq!(AggregationType::TrimmedMean.name(), "trimmed_mean");
+        assert_eq!(AggregationType::Mad.name(), "mad");
     }
 
     #[test]
@@ -567,6 +907,38 @@ mod tests {
             AggregationType::try_from("var.p").unwrap(),
             AggregationType::VarP
         );
+        assert_eq!(
+            AggregationType::try_from("TWA").unwrap(),
+            AggregationType::Twa
+        );
+        assert_eq!(
+            AggregationType::try_from("delta").unwrap(),
+            AggregationType::Delta
+        );
+        assert_eq!(
+            AggregationType::try_from("Deriv").unwrap(),
+            AggregationType::Deriv
+        );
+        assert_eq!(
+            AggregationType::try_from("COUNT_DISTINCT").unwrap(),
+            AggregationType::CountDistinct
+        );
+        assert_eq!(
+            AggregationType::try_from("abs.max").unwrap(),
+            AggregationType::AbsMax
+        );
+        assert_eq!(
+            AggregationType::try_from("ABS.MIN").unwrap(),
+            AggregationType::AbsMin
+        );
+        assert_eq!(
+            AggregationType::try_from("trimmed_mean").unwrap(),
+            AggregationType::TrimmedMean
+        );
+        assert_eq!(
+            AggregationType::try_from("MAD").unwrap(),
+            AggregationType::Mad
+        );
     }
 
     #[test]
@@ -599,6 +971,14 @@ mod tests {
         assert_eq!(u8::from(AggregationType::StdS), 20);
         assert_eq!(u8::from(AggregationType::VarP), 21);
         assert_eq!(u8::from(AggregationType::VarS), 22);
+        assert_eq!(u8::from(AggregationType::Twa), 23);
+        assert_eq!(u8::from(AggregationType::Delta), 24);
+        assert_eq!(u8::from(AggregationType::Deriv), 25);
+        assert_eq!(u8::from(AggregationType::CountDistinct), 26);
+        assert_eq!(u8::from(AggregationType::AbsMax), 27);
+        assert_eq!(u8::from(AggregationType::AbsMin), 28);
+        assert_eq!(u8::from(AggregationType::TrimmedMean), 29);
+        assert_eq!(u8::from(AggregationType::Mad), 30);
     }
 
     #[test]
@@ -695,6 +1075,38 @@ mod tests {
             AggregationType::try_from(22u8).unwrap(),
             AggregationType::VarS
         );
+        assert_eq!(
+            AggregationType::try_from(23u8).unwrap(),
+            AggregationType::Twa
+        );
+        assert_eq!(
+            AggregationType::try_from(24u8).unwrap(),
+            AggregationType::Delta
+        );
+        assert_eq!(
+            AggregationType::try_from(25u8).unwrap(),
+            AggregationType::Deriv
+        );
+        assert_eq!(
+            AggregationType::try_from(26u8).unwrap(),
+            AggregationType::CountDistinct
+        );
+        assert_eq!(
+            AggregationType::try_from(27u8).unwrap(),
+            AggregationType::AbsMax
+        );
+        assert_eq!(
+            AggregationType::try_from(28u8).unwrap(),
+            AggregationType::AbsMin
+        );
+        assert_eq!(
+            AggregationType::try_from(29u8).unwrap(),
+            AggregationType::TrimmedMean
+        );
+        assert_eq!(
+            AggregationType::try_from(30u8).unwrap(),
+            AggregationType::Mad
+        );
     }
 
     #[test]
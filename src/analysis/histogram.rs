@@ -0,0 +1,141 @@
+/// A histogram bin holding the values in `[lower_bound, next_bin.lower_bound)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramBin {
+    pub lower_bound: f64,
+    pub count: usize,
+}
+
+/// Bins `values` into `buckets` equal-width bins spanning `[min, max]` of the finite values.
+///
+/// A value exactly on a boundary goes to the upper bin, except for the maximum value, which is
+/// counted in the last bin. If all values are equal, a single bin is returned. NaN and infinite
+/// values are ignored.
+pub fn equal_width_histogram(values: &[f64], buckets: usize) -> Vec<HistogramBin> {
+    let mut finite = values.iter().copied().filter(|v| v.is_finite()).peekable();
+    if finite.peek().is_none() || buckets == 0 {
+        return vec![];
+    }
+
+    let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+
+    if min == max {
+        let count = values.iter().filter(|v| v.is_finite()).count();
+        return vec![HistogramBin {
+            lower_bound: min,
+            count,
+        }];
+    }
+
+    let range = max - min;
+    let width = range / buckets as f64;
+    // computed from the full range rather than as multiples of `width` to limit rounding error
+    let lower_bound = |i: usize| min + range * i as f64 / buckets as f64;
+
+    let mut bins: Vec<HistogramBin> = (0..buckets)
+        .map(|i| HistogramBin {
+            lower_bound: lower_bound(i),
+            count: 0,
+        })
+        .collect();
+
+    for value in values.iter().copied().filter(|v| v.is_finite()) {
+        let mut idx = (((value - min) / width) as usize).min(buckets - 1);
+        // correct for floating point error, so that bin membership matches the reported bounds
+        while idx + 1 < buckets && value >= lower_bound(idx + 1) {
+            idx += 1;
+        }
+        while idx > 0 && value < lower_bound(idx) {
+            idx -= 1;
+        }
+        bins[idx].count += 1;
+    }
+
+    bins
+}
+
+/// Bins `values` using explicit, strictly increasing `boundaries`.
+///
+/// Returns `boundaries.len() + 1` bins: `(-inf, b1)`, `[b1, b2)`, ..., `[bn, +inf)`. A value exactly
+/// on a boundary goes to the upper bin. NaN values are ignored.
+pub fn boundary_histogram(values: &[f64], boundaries: &[f64]) -> Vec<HistogramBin> {
+    if values.iter().all(|v| v.is_nan()) {
+        return vec![];
+    }
+
+    let mut bins: Vec<HistogramBin> = std::iter::once(f64::NEG_INFINITY)
+        .chain(boundaries.iter().copied())
+        .map(|lower_bound| HistogramBin {
+            lower_bound,
+            count: 0,
+        })
+        .collect();
+
+    for value in values.iter().copied().filter(|v| !v.is_nan()) {
+        let idx = boundaries.partition_point(|b| *b <= value);
+        bins[idx].count += 1;
+    }
+
+    bins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(bins: &[HistogramBin]) -> Vec<usize> {
+        bins.iter().map(|b| b.count).collect()
+    }
+
+    #[test]
+    fn test_equal_width_uniform_distribution() {
+        let values: Vec<f64> = (0..=100).map(|v| v as f64).collect();
+        let bins = equal_width_histogram(&values, 10);
+
+        let bounds: Vec<f64> = bins.iter().map(|b| b.lower_bound).collect();
+        assert_eq!(
+            bounds,
+            vec![0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0]
+        );
+        // the maximum value (100) is counted in the last bin
+        assert_eq!(counts(&bins), vec![10, 10, 10, 10, 10, 10, 10, 10, 10, 11]);
+    }
+
+    #[test]
+    fn test_equal_width_boundary_goes_to_upper_bin() {
+        let values = [0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+        let bins = equal_width_histogram(&values, 10);
+        assert_eq!(counts(&bins), vec![1, 1, 1, 1, 1, 1, 1, 1, 1, 2]);
+        for (value, bin) in values.iter().zip(bins.iter()) {
+            assert!(*value >= bin.lower_bound);
+        }
+    }
+
+    #[test]
+    fn test_equal_width_edge_cases() {
+        assert!(equal_width_histogram(&[], 5).is_empty());
+        assert!(equal_width_histogram(&[f64::NAN], 5).is_empty());
+
+        let bins = equal_width_histogram(&[3.0, 3.0, f64::NAN, 3.0], 5);
+        assert_eq!(
+            bins,
+            vec![HistogramBin {
+                lower_bound: 3.0,
+                count: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn test_boundary_histogram() {
+        let values = [-5.0, 0.0, 1.0, 9.99, 10.0, 50.0, 100.0, 1000.0, f64::NAN];
+        let bins = boundary_histogram(&values, &[0.0, 10.0, 100.0]);
+
+        let bounds: Vec<f64> = bins.iter().map(|b| b.lower_bound).collect();
+        assert_eq!(bounds, vec![f64::NEG_INFINITY, 0.0, 10.0, 100.0]);
+        assert_eq!(counts(&bins), vec![1, 3, 2, 2]);
+
+        assert!(boundary_histogram(&[], &[1.0]).is_empty());
+    }
+}
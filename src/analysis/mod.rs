@@ -1,4 +1,5 @@
 mod error;
+pub mod histogram;
 pub mod math;
 pub mod outliers;
 pub mod quantile_estimators;
@@ -1,4 +1,7 @@
-use crate::aggregators::{AggregationType, BucketAlignment, BucketTimestamp};
+use crate::aggregators::{
+    AggregationType, BucketAlignment, BucketTimestamp, CALENDAR_MONTH_DURATION_MS, CalendarUnit,
+    FillPolicy,
+};
 use crate::common::Timestamp;
 use crate::common::binop::ComparisonOperator;
 use crate::common::rounding::{MAX_DECIMAL_DIGITS, MAX_SIGNIFICANT_DIGITS, RoundingStrategy};
@@ -7,7 +10,7 @@ use crate::error::{TsdbError, TsdbResult};
 use crate::error_consts;
 use crate::join::join_reducer::JoinReducer;
 use crate::join::{AsOfJoinOptions, AsOfJoinStrategy, JoinOptions, JoinType};
-use crate::labels::filters::SeriesSelector;
+use crate::labels::filters::{RegexMatcher, SeriesSelector};
 use crate::labels::{Label, parse_series_selector};
 use crate::parser::number::parse_number;
 use crate::parser::{
@@ -15,11 +18,13 @@ use crate::parser::{
     parse_positive_duration_value, timestamp::parse_timestamp as parse_timestamp_internal,
 };
 use crate::series::chunks::{ChunkEncoding, MAX_CHUNK_SIZE, MIN_CHUNK_SIZE};
+use crate::series::mrange::{DEFAULT_MRANGE_CURSOR_COUNT, decode_mrange_cursor};
 use crate::series::request_types::{
-    AggregationOptions, AggregatorConfig, MRangeOptions, MatchFilterOptions, MetaDateRangeFilter,
-    RangeGroupingOptions, RangeOptions, ValueComparisonFilter,
+    AggregationOptions, AggregatorConfig, DedupOptions, LabelAliases, MRangeCursor, MRangeOptions,
+    MatchFilterOptions, MetaDateRangeFilter, RangeGroupingOptions, RangeOptions, SeriesDiffMode,
+    SeriesDiffOptions, TransformOrder, ValueComparisonFilter, ValueFunction, ValueTransform,
 };
-use crate::series::types::{DuplicatePolicy, ValueFilter};
+use crate::series::types::{ChunkSplitPolicy, DuplicatePolicy, TimestampUnit, ValueFilter};
 use crate::series::{TimestampRange, TimestampValue};
 use ahash::AHashMap;
 use std::collections::BTreeSet;
@@ -35,6 +40,7 @@ pub const MAX_TS_VALUES_FILTER: usize = 128;
 // Kept because these are referenced directly in the parsing logic below.
 const CMD_ARG_AGGREGATION: &str = "AGGREGATION";
 const CMD_ARG_COUNT: &str = "COUNT";
+const CMD_ARG_MAX_POINTS: &str = "MAXPOINTS";
 const CMD_ARG_REDUCE: &str = "REDUCE";
 
 macro_rules! command_arg_tokens {
@@ -72,62 +78,93 @@ macro_rules! command_arg_tokens {
 }
 
 command_arg_tokens! {
+    After => "AFTER",
     Aggregation => "AGGREGATION",
     Align => "ALIGN",
     AllowExactMatch => "ALLOW_EXACT_MATCH",
     Anti => "ANTI",
     AsOf => "ASOF",
+    BatchSize => "BATCH_SIZE",
+    Before => "BEFORE",
+    Boundaries => "BOUNDARIES",
+    Buckets => "BUCKETS",
     BucketTimestamp => "BUCKETTIMESTAMP",
     ChunkSize => "CHUNK_SIZE",
     Compressed => "COMPRESSED",
     Compression => "COMPRESSION",
     Condition => "CONDITION",
     Count => "COUNT",
+    Cursor => "CURSOR",
     DecimalDigits => "DECIMAL_DIGITS",
+    Dedup => "DEDUP",
+    Delete => "DELETE",
     Direction => "DIRECTION",
+    DivByZero => "DIV_BY_ZERO",
+    DryRun => "DRYRUN",
     DuplicatePolicy => "DUPLICATE_POLICY",
     Empty => "EMPTY",
     Encoding => "ENCODING",
     End => "END",
     False => "FALSE",
+    Fill => "FILL",
     Filter => "FILTER",
     FilterByTs => "FILTER_BY_TS",
+    FilterByTsRange => "FILTER_BY_TS_RANGE",
     FilterByValue => "FILTER_BY_VALUE",
     FilterByRange => "FILTER_BY_RANGE",
+    From => "FROM",
+    Function => "FUNCTION",
     Full => "FULL",
     GroupBy => "GROUPBY",
     Ignore => "IGNORE",
     Inner => "INNER",
+    IntCast => "INTCAST",
     Label => "LABEL",
     Labels => "LABELS",
     Latest => "LATEST",
     Left => "LEFT",
     Limit => "LIMIT",
     Match => "MATCH",
+    MaxPoints => "MAXPOINTS",
+    MaxStale => "MAXSTALE",
     Metric => "METRIC",
     Method => "METHOD",
+    Multiple => "MULTIPLE",
     Name => "NAME",
+    NanDistinct => "NAN_DISTINCT",
     Nearest => "NEAREST",
     Next => "NEXT",
     Not => "NOT",
+    Offset => "OFFSET",
     OnDuplicate => "ON_DUPLICATE",
     Output => "OUTPUT",
+    PageSize => "PAGE_SIZE",
     Previous => "PREVIOUS",
     Prior => "PRIOR",
     Reduce => "REDUCE",
+    Repair => "REPAIR",
     Retention => "RETENTION",
+    RetentionSamples => "RETENTION_SAMPLES",
     Right => "RIGHT",
     Rounding => "ROUNDING",
+    Scale => "SCALE",
     Seasonality => "SEASONALITY",
     SelectedLabels => "SELECTED_LABELS",
     Semi => "SEMI",
     SignificantDigits => "SIGNIFICANT_DIGITS",
+    SplitPolicy => "SPLIT_POLICY",
     Start => "START",
     Step => "STEP",
     Timestamp => "TIMESTAMP",
+    TimestampUnit => "TIMESTAMP_UNIT",
+    TopK => "TOPK",
+    Transform => "TRANSFORM",
     True => "TRUE",
     Uncompressed => "UNCOMPRESSED",
+    WithAnnotations => "WITHANNOTATIONS",
+    WithCounts => "WITHCOUNTS",
     WithLabels => "WITHLABELS",
+    WithSources => "WITHSOURCES",
 }
 
 impl Display for CommandArgToken {
@@ -181,6 +218,47 @@ pub fn parse_timestamp(arg: &str) -> ValkeyResult<Timestamp> {
         .map_err(|_| ValkeyError::Str(error_consts::INVALID_TIMESTAMP))
 }
 
+/// Parses a timestamp for a series whose clients use `unit`. Only plain integers are in `unit`;
+/// dates are absolute and `*` is the current time, truncated to `unit` so that it can be returned
+/// and replicated without loss. Sub-millisecond precision is truncated.
+pub fn parse_series_timestamp(arg: &str, unit: TimestampUnit) -> ValkeyResult<Timestamp> {
+    match parse_unit_integer(arg, unit) {
+        Some(value) => unit.to_millis(value?),
+        None if arg == "*" => unit.to_millis(unit.from_millis(current_time_millis())),
+        None => parse_timestamp(arg),
+    }
+}
+
+/// Parses a timestamp for a series whose clients use `unit` into the inclusive range of
+/// milliseconds it covers, e.g. `[10000, 10999]` for `10` in seconds. The range is empty
+/// (`start > end`) for a finer unit value which falls between two milliseconds.
+pub fn parse_series_timestamp_interval(
+    arg: &str,
+    unit: TimestampUnit,
+) -> ValkeyResult<(Timestamp, Timestamp)> {
+    match parse_unit_integer(arg, unit) {
+        Some(value) => {
+            let value = value?;
+            Ok((unit.start_to_millis(value)?, unit.end_to_millis(value)?))
+        }
+        None => parse_timestamp(arg).map(|ts| (ts, ts)),
+    }
+}
+
+/// Returns the value of `arg` if it is a plain integer which has to be converted from `unit`.
+fn parse_unit_integer(arg: &str, unit: TimestampUnit) -> Option<ValkeyResult<i64>> {
+    if unit == TimestampUnit::Milliseconds
+        || arg.is_empty()
+        || !arg.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    Some(
+        arg.parse::<i64>()
+            .map_err(|_| ValkeyError::Str(error_consts::INVALID_TIMESTAMP)),
+    )
+}
+
 pub fn parse_timestamp_arg(arg: &str, name: &str) -> Result<TimestampValue, ValkeyError> {
     parse_timestamp_range_value(arg).map_err(|_e| {
         let msg = format!("TSDB: invalid {name} timestamp");
@@ -287,11 +365,48 @@ pub fn parse_duplicate_policy(args: &mut CommandArgIterator) -> ValkeyResult<Dup
 }
 
 pub fn parse_timestamp_range(args: &mut CommandArgIterator) -> ValkeyResult<TimestampRange> {
+    parse_series_timestamp_range(args, TimestampUnit::Milliseconds)
+}
+
+/// Parses `fromTimestamp toTimestamp` for a series whose clients use `unit`. Integer bounds are
+/// widened to the milliseconds they cover, so that the range selects every sample whose timestamp
+/// in `unit` lies within it.
+pub fn parse_series_timestamp_range(
+    args: &mut CommandArgIterator,
+    unit: TimestampUnit,
+) -> ValkeyResult<TimestampRange> {
     let first_arg = args.next_str()?;
-    let start = parse_timestamp_range_value(first_arg)
+    let end_arg = args.next_str().ok();
+    parse_series_timestamp_bounds(first_arg, end_arg, unit)
+}
+
+/// Parses the `fromTimestamp` and `toTimestamp` arguments of a command on a series whose clients
+/// use `unit`, for commands which only know the series after reading the rest of their arguments.
+/// A missing `toTimestamp` is the latest sample.
+pub fn parse_series_timestamp_bounds(
+    start_arg: &str,
+    end_arg: Option<&str>,
+    unit: TimestampUnit,
+) -> ValkeyResult<TimestampRange> {
+    fn parse_bound(arg: &str, unit: TimestampUnit, is_end: bool) -> ValkeyResult<TimestampValue> {
+        match parse_unit_integer(arg, unit) {
+            Some(value) => {
+                let value = value?;
+                let ts = if is_end {
+                    unit.end_to_millis(value)?
+                } else {
+                    unit.start_to_millis(value)?
+                };
+                Ok(TimestampValue::Specific(ts))
+            }
+            None => parse_timestamp_range_value(arg),
+        }
+    }
+
+    let start = parse_bound(start_arg, unit, false)
         .map_err(|_e| ValkeyError::Str(error_consts::INVALID_START_TIMESTAMP))?;
-    let end_value = if let Ok(arg) = args.next_str() {
-        parse_timestamp_range_value(arg)
+    let end_value = if let Some(arg) = end_arg {
+        parse_bound(arg, unit, true)
             .map_err(|_e| ValkeyError::Str(error_consts::INVALID_END_TIMESTAMP))?
     } else {
         TimestampValue::Latest
@@ -307,12 +422,59 @@ pub fn parse_retention(args: &mut CommandArgIterator) -> ValkeyResult<Duration>
     }
 }
 
+pub fn parse_retention_samples(args: &mut CommandArgIterator) -> ValkeyResult<usize> {
+    let arg = args
+        .next_arg()
+        .map_err(|_| ValkeyError::Str(error_consts::INVALID_RETENTION_SAMPLES))?;
+    parse_integer_arg(&arg, "RETENTION_SAMPLES", false)
+        .map(|value| value as usize)
+        .map_err(|_| ValkeyError::Str(error_consts::INVALID_RETENTION_SAMPLES))
+}
+
+pub fn parse_split_policy(args: &mut CommandArgIterator) -> ValkeyResult<ChunkSplitPolicy> {
+    let arg = args
+        .next_str()
+        .map_err(|_| ValkeyError::Str(error_consts::INVALID_SPLIT_POLICY))?;
+    ChunkSplitPolicy::try_from(arg)
+}
+
+pub fn parse_timestamp_unit(args: &mut CommandArgIterator) -> ValkeyResult<TimestampUnit> {
+    let arg = args
+        .next_str()
+        .map_err(|_| ValkeyError::Str(error_consts::INVALID_TIMESTAMP_UNIT))?;
+    TimestampUnit::try_from(arg)
+}
+
 pub fn parse_timestamp_filter(
     args: &mut CommandArgIterator,
     stop_tokens: &[CommandArgToken],
 ) -> ValkeyResult<Vec<Timestamp>> {
+    parse_timestamp_filter_values(args, stop_tokens, parse_timestamp)
+}
+
+/// Parses the values following FILTER_BY_TS for a series whose clients use a unit other than
+/// milliseconds. Each value matches the range of milliseconds it covers, so the result is a set of
+/// disjoint intervals as for FILTER_BY_TS_RANGE.
+pub fn parse_timestamp_filter_intervals(
+    args: &mut CommandArgIterator,
+    stop_tokens: &[CommandArgToken],
+    unit: TimestampUnit,
+) -> ValkeyResult<Vec<(Timestamp, Timestamp)>> {
+    let mut intervals = parse_timestamp_filter_values(args, stop_tokens, |arg| {
+        parse_series_timestamp_interval(arg, unit)
+    })?;
+    // a finer unit value between two milliseconds cannot match any sample
+    intervals.retain(|(start, end)| start <= end);
+    Ok(merge_timestamp_ranges(intervals))
+}
+
+fn parse_timestamp_filter_values<T>(
+    args: &mut CommandArgIterator,
+    stop_tokens: &[CommandArgToken],
+    parse: impl Fn(&str) -> ValkeyResult<T>,
+) -> ValkeyResult<Vec<T>> {
     // FILTER_BY_TS already seen
-    let mut values: Vec<Timestamp> = Vec::new();
+    let mut values: Vec<T> = Vec::new();
 
     while !is_stop_token_or_end(args, stop_tokens) {
         let arg = args
@@ -320,7 +482,7 @@ pub fn parse_timestamp_filter(
             .map_err(|_| ValkeyError::Str(error_consts::INVALID_TIMESTAMP_FILTER))?;
 
         let timestamp =
-            parse_timestamp(arg).map_err(|_| ValkeyError::Str(error_consts::INVALID_TIMESTAMP))?;
+            parse(arg).map_err(|_| ValkeyError::Str(error_consts::INVALID_TIMESTAMP))?;
 
         values.push(timestamp);
 
@@ -340,6 +502,94 @@ pub fn parse_timestamp_filter(
     Ok(values)
 }
 
+/// Parses the `start end [start end ...]` pairs following FILTER_BY_TS_RANGE. The resulting
+/// intervals are sorted and merged so that they are disjoint.
+pub fn parse_timestamp_range_filter(
+    args: &mut CommandArgIterator,
+    stop_tokens: &[CommandArgToken],
+    unit: TimestampUnit,
+) -> ValkeyResult<Vec<(Timestamp, Timestamp)>> {
+    // FILTER_BY_TS_RANGE already seen
+    let mut ranges: Vec<(Timestamp, Timestamp)> = Vec::new();
+
+    let next_timestamp = |args: &mut CommandArgIterator| -> ValkeyResult<(Timestamp, Timestamp)> {
+        let arg = args
+            .next_str()
+            .map_err(|_| ValkeyError::Str(error_consts::INVALID_TIMESTAMP_FILTER))?;
+        parse_series_timestamp_interval(arg, unit)
+            .map_err(|_| ValkeyError::Str(error_consts::INVALID_TIMESTAMP))
+    };
+
+    while !is_stop_token_or_end(args, stop_tokens) {
+        let start = next_timestamp(args)?;
+        let end = next_timestamp(args)?;
+        if start.0 > end.0 {
+            return Err(ValkeyError::Str(error_consts::INVALID_TIMESTAMP_FILTER));
+        }
+
+        ranges.push((start.0, end.1));
+
+        if ranges.len() > MAX_TS_VALUES_FILTER {
+            return Err(ValkeyError::Str(
+                error_consts::TOO_MANY_TIMESTAMP_FILTER_VALUES,
+            ));
+        }
+    }
+
+    if ranges.is_empty() {
+        return Err(ValkeyError::Str(
+            error_consts::MISSING_TIMESTAMP_FILTER_VALUE,
+        ));
+    }
+
+    ranges.retain(|(start, end)| start <= end);
+    Ok(merge_timestamp_ranges(ranges))
+}
+
+/// Sorts the intervals by start and coalesces overlapping or adjacent ones.
+pub(crate) fn merge_timestamp_ranges(
+    mut ranges: Vec<(Timestamp, Timestamp)>,
+) -> Vec<(Timestamp, Timestamp)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(Timestamp, Timestamp)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1.saturating_add(1) => {
+                last.1 = last.1.max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Clips the timestamp filters to the requested date range, and rejects mixing
+/// FILTER_BY_TS with FILTER_BY_TS_RANGE.
+fn finalize_timestamp_filters(options: &mut RangeOptions) -> ValkeyResult<()> {
+    if options.timestamp_filter.is_some() && options.timestamp_ranges.is_some() {
+        return Err(ValkeyError::Str(
+            "TSDB: FILTER_BY_TS and FILTER_BY_TS_RANGE cannot be used together",
+        ));
+    }
+
+    let (start_ts, end_ts) = options.date_range.get_timestamps(None);
+
+    // filter out timestamp filters that are outside the range
+    if let Some(ts_filter) = options.timestamp_filter.as_mut() {
+        ts_filter.retain(|&ts| ts >= start_ts && ts <= end_ts);
+    }
+
+    if let Some(ranges) = options.timestamp_ranges.as_mut() {
+        ranges.retain_mut(|(start, end)| {
+            *start = (*start).max(start_ts);
+            *end = (*end).min(end_ts);
+            start <= end
+        });
+    }
+
+    Ok(())
+}
+
 pub fn parse_value_filter(args: &mut CommandArgIterator) -> ValkeyResult<ValueFilter> {
     let min = parse_number_with_unit(args.next_str()?)
         .map_err(|_| ValkeyError::Str(error_consts::CANNOT_PARSE_MIN))?;
@@ -359,6 +609,50 @@ pub fn parse_value_filter(args: &mut CommandArgIterator) -> ValkeyResult<ValueFi
     ValueFilter::new(min, max)
 }
 
+/// Parses `TRANSFORM [SCALE scale] [OFFSET offset] [BEFORE | AFTER]`. `BEFORE` (the default)
+/// transforms samples before they are aggregated, `AFTER` transforms the aggregated values.
+pub fn parse_value_transform(args: &mut CommandArgIterator) -> ValkeyResult<ValueTransform> {
+    fn parse_finite(args: &mut CommandArgIterator) -> ValkeyResult<f64> {
+        let arg = args
+            .next_str()
+            .map_err(|_| ValkeyError::Str(error_consts::MISSING_TRANSFORM))?;
+        parse_number_with_unit(arg)
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or(ValkeyError::Str(error_consts::INVALID_TRANSFORM_VALUE))
+    }
+
+    let mut transform = ValueTransform::default();
+    let mut has_value = false;
+    while let Some(token) = peek_token(args) {
+        match token {
+            CommandArgToken::Scale => {
+                args.next();
+                transform.scale = parse_finite(args)?;
+                has_value = true;
+            }
+            CommandArgToken::Offset => {
+                args.next();
+                transform.offset = parse_finite(args)?;
+                has_value = true;
+            }
+            CommandArgToken::Before => {
+                args.next();
+                transform.order = TransformOrder::BeforeAggregation;
+            }
+            CommandArgToken::After => {
+                args.next();
+                transform.order = TransformOrder::AfterAggregation;
+            }
+            _ => break,
+        }
+    }
+    if !has_value {
+        return Err(ValkeyError::Str(error_consts::MISSING_TRANSFORM));
+    }
+    Ok(transform)
+}
+
 pub fn parse_count_arg(args: &mut CommandArgIterator) -> ValkeyResult<usize> {
     let next = args
         .next_arg()
@@ -368,6 +662,18 @@ pub fn parse_count_arg(args: &mut CommandArgIterator) -> ValkeyResult<usize> {
     Ok(count as usize)
 }
 
+fn parse_max_points(args: &mut CommandArgIterator) -> ValkeyResult<usize> {
+    let next = args
+        .next_arg()
+        .map_err(|_| ValkeyError::Str(error_consts::MISSING_MAX_POINTS_VALUE))?;
+    let max_points = parse_integer_arg(&next, CMD_ARG_MAX_POINTS, false)
+        .map_err(|_| ValkeyError::Str(error_consts::INVALID_MAX_POINTS_VALUE))?;
+    if max_points == 0 {
+        return Err(ValkeyError::Str(error_consts::INVALID_MAX_POINTS_VALUE));
+    }
+    Ok(max_points as usize)
+}
+
 fn expect_next_token(args: &mut CommandArgIterator, expected: CommandArgToken) -> ValkeyResult<()> {
     let found = args.next_str()?;
     let Some(found_token) = parse_command_arg_token(found.as_bytes()) else {
@@ -479,21 +785,40 @@ fn for_each_arg_until_stop(
     Ok(())
 }
 
+/// Parses a list of label names, where each name may be renamed on output using `name=alias`.
+/// Returns the (sorted) source label names along with their aliases.
 pub fn parse_label_list(
     args: &mut CommandArgIterator,
     stop_tokens: &[CommandArgToken],
-) -> ValkeyResult<Vec<String>> {
+) -> ValkeyResult<(Vec<String>, LabelAliases)> {
     let mut labels: BTreeSet<String> = BTreeSet::new();
-
-    for_each_arg_until_stop(args, stop_tokens, |label| {
+    let mut aliases = LabelAliases::default();
+    let mut output_names: BTreeSet<String> = BTreeSet::new();
+
+    for_each_arg_until_stop(args, stop_tokens, |arg| {
+        let (label, output_name) = match arg.split_once('=') {
+            Some((label, alias)) => {
+                if label.is_empty() || alias.is_empty() {
+                    return Err(ValkeyError::Str(error_consts::INVALID_LABEL_NAME));
+                }
+                (label, alias)
+            }
+            None => (arg, arg),
+        };
         if labels.contains(label) {
             return Err(ValkeyError::Str(error_consts::DUPLICATE_LABEL));
         }
+        if !output_names.insert(output_name.to_string()) {
+            return Err(ValkeyError::Str(error_consts::DUPLICATE_LABEL_ALIAS));
+        }
         labels.insert(label.to_string());
+        if label != output_name {
+            aliases.insert(label.to_string(), output_name.to_string());
+        }
         Ok(())
     })?;
 
-    Ok(labels.into_iter().collect())
+    Ok((labels.into_iter().collect(), aliases))
 }
 
 pub fn parse_label_value_pairs(
@@ -530,15 +855,44 @@ pub fn parse_label_value_pairs(
 
 pub fn parse_aggregation_options(
     args: &mut CommandArgIterator,
+) -> ValkeyResult<AggregationOptions> {
+    parse_aggregation_options_internal(args, None)
+}
+
+/// Parses `AGGREGATION aggregator bucketDuration ...`. If `extra_aggregations` is given, the
+/// aggregator may also be a bracketed list (`[avg min max]`), in which case the first entry is
+/// returned in the options and the rest are appended to `extra_aggregations`.
+fn parse_aggregation_options_internal(
+    args: &mut CommandArgIterator,
+    extra_aggregations: Option<&mut Vec<AggregatorConfig>>,
 ) -> ValkeyResult<AggregationOptions> {
     // AGGREGATION token already seen
     let agg_str = args
         .next_str()
         .map_err(|_e| ValkeyError::Str(error_consts::UNKNOWN_AGGREGATION_TYPE))?;
-    let aggregator = AggregationType::try_from(agg_str)?;
+    let (aggregator, extra_types) = if agg_str.starts_with('[') {
+        if extra_aggregations.is_none() {
+            return Err(ValkeyError::Str(
+                error_consts::MULTIPLE_AGGREGATIONS_NOT_SUPPORTED,
+            ));
+        }
+        let mut types = parse_aggregation_list(agg_str, args)?;
+        let first = types.remove(0);
+        (first, types)
+    } else {
+        (AggregationType::try_from(agg_str)?, Vec::new())
+    };
+    if !extra_types.is_empty()
+        && (aggregator.has_param() || extra_types.iter().any(|t| t.has_param()))
+    {
+        return Err(ValkeyError::Str(error_consts::MULTIPLE_AGGREGATIONS_PARAM));
+    }
+    let param = parse_aggregation_param(args, aggregator)?;
     let mut value_filter: Option<ValueComparisonFilter> = None;
     let bucket_duration = parse_duration_arg(&args.next_arg()?)
         .map_err(|_e| ValkeyError::Str("TSDB: Couldn't parse bucket duration"))?;
+    // per-bucket counts and DEDUP apply to the reply, so only TS.RANGE and TS.REVRANGE accept them
+    let is_range_command = extra_aggregations.is_some();
 
     let mut aggr: AggregationOptions = AggregationOptions {
         bucket_duration: bucket_duration.as_millis() as u64,
@@ -550,6 +904,11 @@ pub fn parse_aggregation_options(
     if let Some(token) = peek_token(args)
         && token == CommandArgToken::Condition
     {
+        if !extra_types.is_empty() {
+            return Err(ValkeyError::Str(
+                error_consts::MULTIPLE_AGGREGATIONS_CONDITION,
+            ));
+        }
         args.next(); // consume CONDITION
         value_filter = Some(parse_aggregator_value_filter(args)?);
     }
@@ -558,13 +917,22 @@ pub fn parse_aggregation_options(
         CommandArgToken::Align,
         CommandArgToken::Empty,
         CommandArgToken::BucketTimestamp,
+        CommandArgToken::Fill,
+        CommandArgToken::WithCounts,
+        CommandArgToken::Dedup,
     ];
 
-    parse_optional_token_block(args, &valid_tokens, 3, |token, args| match token {
+    parse_optional_token_block(args, &valid_tokens, 6, |token, args| match token {
         CommandArgToken::Empty => {
             aggr.report_empty = true;
             Ok(())
         }
+        CommandArgToken::Fill => {
+            let next = args.next_str()?;
+            aggr.fill = Some(FillPolicy::try_from(next)?);
+            aggr.report_empty = true;
+            Ok(())
+        }
         CommandArgToken::BucketTimestamp => {
             let next = args.next_str()?;
             aggr.timestamp_output = BucketTimestamp::try_from(next)?;
@@ -575,14 +943,100 @@ pub fn parse_aggregation_options(
             aggr.alignment = next.try_into()?;
             Ok(())
         }
+        CommandArgToken::WithCounts => {
+            if !is_range_command {
+                return Err(ValkeyError::Str(error_consts::WITH_COUNTS_NOT_SUPPORTED));
+            }
+            aggr.with_counts = true;
+            Ok(())
+        }
+        CommandArgToken::Dedup => {
+            if !is_range_command {
+                return Err(ValkeyError::Str(error_consts::DEDUP_NOT_SUPPORTED));
+            }
+            let mut dedup = DedupOptions::default();
+            if peek_token(args) == Some(CommandArgToken::NanDistinct) {
+                args.next();
+                dedup.nan_equal = false;
+            }
+            aggr.dedup = Some(dedup);
+            Ok(())
+        }
         _ => Ok(()),
     })?;
 
     aggr.aggregation = AggregatorConfig::new(aggregator, value_filter)?;
+    if let Some(param) = param {
+        aggr.aggregation = aggr.aggregation.with_param(param)?;
+    }
+
+    validate_calendar_alignment(&aggr)?;
+
+    if let Some(extra) = extra_aggregations {
+        extra.clear();
+        extra.extend(extra_types.into_iter().map(AggregatorConfig::from));
+    }
 
     Ok(aggr)
 }
 
+/// Parses an aggregation list such as `[avg min max]`, starting from its first argument. The list
+/// may be passed as a single argument or split across several, e.g. `[avg`, `min`, `max]`.
+fn parse_aggregation_list(
+    first: &str,
+    args: &mut CommandArgIterator,
+) -> ValkeyResult<Vec<AggregationType>> {
+    let mut list = first.trim_start_matches('[').to_string();
+    while !list.trim_end().ends_with(']') {
+        let next = args
+            .next_str()
+            .map_err(|_| ValkeyError::Str(error_consts::UNTERMINATED_AGGREGATION_LIST))?;
+        list.push(' ');
+        list.push_str(next);
+    }
+    let list = list.trim_end().trim_end_matches(']');
+
+    let types = list
+        .split_whitespace()
+        .map(AggregationType::try_from)
+        .collect::<ValkeyResult<Vec<_>>>()?;
+    if types.is_empty() {
+        return Err(ValkeyError::Str(error_consts::UNKNOWN_AGGREGATION_TYPE));
+    }
+    Ok(types)
+}
+
+/// Month-aligned buckets span whole calendar months, so the bucket duration must be expressed
+/// as a multiple of the nominal (30 day) month.
+fn validate_calendar_alignment(aggr: &AggregationOptions) -> ValkeyResult<()> {
+    if matches!(
+        aggr.alignment,
+        BucketAlignment::Calendar(CalendarUnit::Month, _)
+    ) && !aggr
+        .bucket_duration
+        .is_multiple_of(CALENDAR_MONTH_DURATION_MS)
+    {
+        return Err(ValkeyError::Str(
+            error_consts::INVALID_MONTH_BUCKET_DURATION,
+        ));
+    }
+    Ok(())
+}
+
+/// Parses the numeric argument of aggregations which take one (e.g. `trimmed_mean <pct>`).
+pub(super) fn parse_aggregation_param(
+    args: &mut CommandArgIterator,
+    aggregation: AggregationType,
+) -> ValkeyResult<Option<f64>> {
+    if !aggregation.has_param() {
+        return Ok(None);
+    }
+    let param = args
+        .next_f64()
+        .map_err(|_| ValkeyError::Str(error_consts::INVALID_AGGREGATION_PARAM))?;
+    Ok(Some(param))
+}
+
 pub(super) fn parse_aggregator_value_filter(
     args: &mut CommandArgIterator,
 ) -> ValkeyResult<ValueComparisonFilter> {
@@ -599,7 +1053,16 @@ pub(super) fn parse_grouping_params(
     args: &mut CommandArgIterator,
 ) -> ValkeyResult<RangeGroupingOptions> {
     // GROUPBY token already seen
-    let label = args.next_str()?;
+    let mut group_labels = vec![args.next_str()?.to_string()];
+    while let Some(next) = args.peek()
+        && parse_command_arg_token(next) != Some(CommandArgToken::Reduce)
+    {
+        let label = args.next_str()?;
+        if group_labels.iter().any(|l| l == label) {
+            return Err(ValkeyError::Str(error_consts::DUPLICATE_LABEL));
+        }
+        group_labels.push(label.to_string());
+    }
 
     expect_next_token(args, CommandArgToken::Reduce)
         .map_err(|_| ValkeyError::Str("TSDB: missing REDUCE"))?;
@@ -613,12 +1076,16 @@ pub(super) fn parse_grouping_params(
         ValkeyError::String(msg)
     })?;
 
-    // Rate requires a time range, so it is not valid for grouping.
-    if aggregator == AggregationType::Rate {
+    // Rate, TWA and Deriv require a time range, so they are not valid for grouping.
+    if matches!(
+        aggregator,
+        AggregationType::Rate | AggregationType::Twa | AggregationType::Deriv
+    ) {
         let msg = "TSDB: aggregator not supported for GROUPBY reducer";
         return Err(ValkeyError::Str(msg));
     }
 
+    let param = parse_aggregation_param(args, aggregator)?;
     let mut value_filter: Option<ValueComparisonFilter> = None;
 
     // see if we have a filter condition
@@ -629,11 +1096,18 @@ pub(super) fn parse_grouping_params(
         value_filter = Some(parse_aggregator_value_filter(args)?);
     }
 
-    let aggregation = AggregatorConfig::new(aggregator, value_filter)?;
+    let with_sources =
+        advance_if_next_token_one_of(args, &[CommandArgToken::WithSources]).is_some();
+
+    let mut aggregation = AggregatorConfig::new(aggregator, value_filter)?;
+    if let Some(param) = param {
+        aggregation = aggregation.with_param(param)?;
+    }
 
     Ok(RangeGroupingOptions {
-        group_label: label.to_string(),
+        group_labels,
         aggregation,
+        with_sources,
     })
 }
 
@@ -660,6 +1134,29 @@ pub fn parse_decimal_digit_rounding(
     Ok(RoundingStrategy::DecimalDigits(next as u8))
 }
 
+/// Parses the arguments following ROUNDING, e.g. `ROUNDING MULTIPLE 0.25`.
+pub fn parse_rounding(args: &mut CommandArgIterator) -> ValkeyResult<RoundingStrategy> {
+    let Ok(arg) = args.next_str() else {
+        return Err(ValkeyError::Str(error_consts::INVALID_ROUNDING));
+    };
+    match parse_command_arg_token(arg.as_bytes()) {
+        Some(CommandArgToken::Multiple) => {
+            let step = args
+                .next_str()
+                .map_err(|_| ValkeyError::Str(error_consts::INVALID_ROUNDING_STEP))
+                .and_then(|arg| {
+                    parse_number(arg)
+                        .map_err(|_| ValkeyError::Str(error_consts::INVALID_ROUNDING_STEP))
+                })?;
+            if !step.is_finite() || step <= 0.0 {
+                return Err(ValkeyError::Str(error_consts::INVALID_ROUNDING_STEP));
+            }
+            Ok(RoundingStrategy::Multiple(step))
+        }
+        _ => Err(ValkeyError::Str(error_consts::INVALID_ROUNDING)),
+    }
+}
+
 pub(crate) fn parse_ignore_options(args: &mut CommandArgIterator) -> ValkeyResult<(i64, f64)> {
     // ignoreMaxTimediff
     let mut str = args.next_str()?;
@@ -707,57 +1204,135 @@ pub fn parse_series_selector_list(
     Ok(matchers)
 }
 
-fn parse_align_for_aggregation(args: &mut CommandArgIterator) -> ValkeyResult<AggregationOptions> {
+fn parse_align_for_aggregation(
+    args: &mut CommandArgIterator,
+    extra_aggregations: Option<&mut Vec<AggregatorConfig>>,
+) -> ValkeyResult<AggregationOptions> {
     // ALIGN token already seen
     let alignment_str = args.next_str()?;
 
     expect_next_token(args, CommandArgToken::Aggregation)
         .map_err(|_| ValkeyError::Str("TSDB: missing AGGREGATION"))?;
 
-    let mut aggregation = parse_aggregation_options(args)?;
+    let mut aggregation = parse_aggregation_options_internal(args, extra_aggregations)?;
     aggregation.alignment = BucketAlignment::try_from(alignment_str)?;
+    validate_calendar_alignment(&aggregation)?;
     Ok(aggregation)
 }
 
 pub fn parse_range_options(args: &mut CommandArgIterator) -> ValkeyResult<RangeOptions> {
-    const RANGE_OPTION_ARGS: [CommandArgToken; 7] = [
+    parse_range_options_internal(args, None, TimestampUnit::Milliseconds)
+}
+
+/// Parses the options of TS.RANGE and TS.REVRANGE, which accept a list of aggregations. Any
+/// aggregations after the first are returned alongside the options. Timestamps are given in the
+/// series' `unit`.
+pub fn parse_range_command_options(
+    args: &mut CommandArgIterator,
+    unit: TimestampUnit,
+) -> ValkeyResult<(RangeOptions, Vec<AggregatorConfig>)> {
+    let mut extra_aggregations = Vec::new();
+    let options = parse_range_options_internal(args, Some(&mut extra_aggregations), unit)?;
+    if !extra_aggregations.is_empty() && options.max_points.is_some() {
+        return Err(ValkeyError::Str(
+            error_consts::MULTIPLE_AGGREGATIONS_MAX_POINTS,
+        ));
+    }
+    if options.max_points.is_some() && options.aggregation.is_some_and(|agg| agg.with_counts) {
+        return Err(ValkeyError::Str(error_consts::WITH_COUNTS_MAX_POINTS));
+    }
+    Ok((options, extra_aggregations))
+}
+
+fn parse_range_options_internal(
+    args: &mut CommandArgIterator,
+    mut extra_aggregations: Option<&mut Vec<AggregatorConfig>>,
+    unit: TimestampUnit,
+) -> ValkeyResult<RangeOptions> {
+    const RANGE_OPTION_ARGS: [CommandArgToken; 11] = [
         CommandArgToken::Align,
         CommandArgToken::Aggregation,
         CommandArgToken::Count,
         CommandArgToken::BucketTimestamp,
         CommandArgToken::FilterByTs,
+        CommandArgToken::FilterByTsRange,
         CommandArgToken::FilterByValue,
+        CommandArgToken::Function,
         CommandArgToken::Latest,
+        CommandArgToken::MaxPoints,
+        CommandArgToken::Transform,
     ];
 
-    let date_range = parse_timestamp_range(args)?;
+    let date_range = parse_series_timestamp_range(args, unit)?;
 
     let mut options = RangeOptions {
         date_range,
         ..Default::default()
     };
+    // FILTER_BY_TS values in a unit other than ms, which each match an interval of milliseconds
+    let mut timestamp_intervals = None;
 
     while let Some(arg) = args.next() {
         let token = parse_command_arg_token(arg.as_slice()).unwrap_or_default();
         match token {
             CommandArgToken::Align => {
-                options.aggregation = Some(parse_align_for_aggregation(args)?);
+                options.aggregation = Some(parse_align_for_aggregation(
+                    args,
+                    extra_aggregations.as_deref_mut(),
+                )?);
             }
             CommandArgToken::Aggregation => {
-                options.aggregation = Some(parse_aggregation_options(args)?);
+                options.aggregation = Some(parse_aggregation_options_internal(
+                    args,
+                    extra_aggregations.as_deref_mut(),
+                )?);
             }
             CommandArgToken::Count => {
                 options.count = Some(parse_count_arg(args)?);
+                if peek_token(args) == Some(CommandArgToken::From) {
+                    args.next();
+                    if peek_token(args) != Some(CommandArgToken::End) {
+                        return Err(ValkeyError::Str(error_consts::INVALID_COUNT_FROM));
+                    }
+                    args.next();
+                    options.count_from_end = true;
+                }
             }
             CommandArgToken::FilterByValue => {
                 options.value_filter = Some(parse_value_filter(args)?);
             }
+            CommandArgToken::FilterByTs if unit != TimestampUnit::Milliseconds => {
+                timestamp_intervals = Some(parse_timestamp_filter_intervals(
+                    args,
+                    &RANGE_OPTION_ARGS,
+                    unit,
+                )?);
+            }
             CommandArgToken::FilterByTs => {
                 options.timestamp_filter = Some(parse_timestamp_filter(args, &RANGE_OPTION_ARGS)?);
             }
+            CommandArgToken::FilterByTsRange => {
+                options.timestamp_ranges = Some(parse_timestamp_range_filter(
+                    args,
+                    &RANGE_OPTION_ARGS,
+                    unit,
+                )?);
+            }
             CommandArgToken::Latest => {
                 options.latest = true;
             }
+            CommandArgToken::MaxPoints => {
+                options.max_points = Some(parse_max_points(args)?);
+            }
+            CommandArgToken::Transform => {
+                options.transform = Some(parse_value_transform(args)?);
+            }
+            CommandArgToken::Function => {
+                let name = args
+                    .next_str()
+                    .map_err(|_| ValkeyError::Str(error_consts::MISSING_VALUE_FUNCTION))?;
+                options.function = Some(ValueFunction::try_from(name)?);
+            }
             _ => {
                 return if token == CommandArgToken::Invalid {
                     Err(ValkeyError::Str(error_consts::INVALID_ARGUMENT))
@@ -787,12 +1362,17 @@ pub fn parse_range_options(args: &mut CommandArgIterator) -> ValkeyResult<RangeO
         }
     }
 
-    // filter out timestamp filters that are outside the range
-    if let Some(ts_filter) = options.timestamp_filter.as_mut() {
-        let (start_ts, end_ts) = options.date_range.get_timestamps(None);
-        ts_filter.retain(|&ts| ts >= start_ts && ts <= end_ts);
+    if let Some(intervals) = timestamp_intervals {
+        if options.timestamp_ranges.is_some() {
+            return Err(ValkeyError::Str(
+                "TSDB: FILTER_BY_TS and FILTER_BY_TS_RANGE cannot be used together",
+            ));
+        }
+        options.timestamp_ranges = Some(intervals);
     }
 
+    finalize_timestamp_filters(&mut options)?;
+
     Ok(options)
 }
 
@@ -816,15 +1396,18 @@ pub fn parse_filter_by_range_options(
 }
 
 pub fn parse_mrange_options(args: &mut CommandArgIterator) -> ValkeyResult<MRangeOptions> {
-    const RANGE_OPTION_ARGS: [CommandArgToken; 12] = [
+    const RANGE_OPTION_ARGS: [CommandArgToken; 15] = [
         CommandArgToken::Align,
         CommandArgToken::Aggregation,
         CommandArgToken::Count,
+        CommandArgToken::Cursor,
         CommandArgToken::BucketTimestamp,
         CommandArgToken::Filter,
         CommandArgToken::FilterByTs,
+        CommandArgToken::FilterByTsRange,
         CommandArgToken::FilterByValue,
         CommandArgToken::Latest,
+        CommandArgToken::Limit,
         CommandArgToken::GroupBy,
         CommandArgToken::Reduce,
         CommandArgToken::SelectedLabels,
@@ -845,7 +1428,7 @@ pub fn parse_mrange_options(args: &mut CommandArgIterator) -> ValkeyResult<MRang
         let token = parse_command_arg_token(arg.as_slice()).unwrap_or_default();
         match token {
             CommandArgToken::Align => {
-                options.range.aggregation = Some(parse_align_for_aggregation(args)?);
+                options.range.aggregation = Some(parse_align_for_aggregation(args, None)?);
             }
             CommandArgToken::Aggregation => {
                 options.range.aggregation = Some(parse_aggregation_options(args)?);
@@ -853,6 +1436,9 @@ pub fn parse_mrange_options(args: &mut CommandArgIterator) -> ValkeyResult<MRang
             CommandArgToken::Count => {
                 options.range.count = Some(parse_count_arg(args)?);
             }
+            CommandArgToken::Cursor => {
+                options.cursor = Some(parse_mrange_cursor(args)?);
+            }
             CommandArgToken::Filter => {
                 options.filters = parse_series_selector_list(args, &RANGE_OPTION_ARGS)?;
             }
@@ -863,14 +1449,32 @@ pub fn parse_mrange_options(args: &mut CommandArgIterator) -> ValkeyResult<MRang
                 options.range.timestamp_filter =
                     Some(parse_timestamp_filter(args, &RANGE_OPTION_ARGS)?);
             }
+            CommandArgToken::FilterByTsRange => {
+                options.range.timestamp_ranges = Some(parse_timestamp_range_filter(
+                    args,
+                    &RANGE_OPTION_ARGS,
+                    TimestampUnit::Milliseconds,
+                )?);
+            }
             CommandArgToken::GroupBy => {
                 options.grouping = Some(parse_grouping_params(args)?);
             }
             CommandArgToken::Latest => {
                 options.range.latest = true;
             }
+            CommandArgToken::Limit => {
+                let next = args
+                    .next_arg()
+                    .map_err(|_| ValkeyError::Str(error_consts::MISSING_LIMIT_VALUE))?;
+                let limit = parse_integer_arg(&next, "LIMIT", false)
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or(ValkeyError::Str(error_consts::INVALID_SAMPLE_LIMIT))?;
+                options.sample_limit = Some(limit as usize);
+            }
             CommandArgToken::SelectedLabels => {
-                options.selected_labels = parse_label_list(args, &RANGE_OPTION_ARGS)?;
+                (options.selected_labels, options.label_aliases) =
+                    parse_label_list(args, &RANGE_OPTION_ARGS)?;
             }
             CommandArgToken::WithLabels => {
                 options.with_labels = true;
@@ -883,11 +1487,7 @@ pub fn parse_mrange_options(args: &mut CommandArgIterator) -> ValkeyResult<MRang
         return Err(ValkeyError::Str("TSDB: no FILTER given"));
     }
 
-    // filter out timestamp filters that are outside the range
-    if let Some(ts_filter) = options.range.timestamp_filter.as_mut() {
-        let (start_ts, end_ts) = options.range.date_range.get_timestamps(None);
-        ts_filter.retain(|&ts| ts >= start_ts && ts <= end_ts);
-    }
+    finalize_timestamp_filters(&mut options.range)?;
 
     if !options.selected_labels.is_empty() && options.with_labels {
         return Err(ValkeyError::Str(
@@ -895,20 +1495,46 @@ pub fn parse_mrange_options(args: &mut CommandArgIterator) -> ValkeyResult<MRang
         ));
     }
 
+    if options.cursor.is_some() && (options.grouping.is_some() || options.sample_limit.is_some()) {
+        return Err(ValkeyError::Str(error_consts::CURSOR_WITH_GROUPBY_OR_LIMIT));
+    }
+
     Ok(options)
 }
 
+/// Parses `CURSOR cursor [PAGE_SIZE seriesCount]`. `PAGE_SIZE` sets the number of series per page,
+/// so it does not clash with `COUNT`, which caps the samples per series.
+fn parse_mrange_cursor(args: &mut CommandArgIterator) -> ValkeyResult<MRangeCursor> {
+    let cursor = args
+        .next_str()
+        .map_err(|_| ValkeyError::Str(error_consts::MISSING_CURSOR_VALUE))?;
+    let after = decode_mrange_cursor(cursor)?;
+
+    let mut count = DEFAULT_MRANGE_CURSOR_COUNT;
+    if advance_if_next_token_one_of(args, &[CommandArgToken::PageSize]).is_some() {
+        let next = args
+            .next_arg()
+            .map_err(|_| ValkeyError::Str(error_consts::MISSING_PAGE_SIZE_VALUE))?;
+        count = parse_integer_arg(&next, "PAGE_SIZE", false)
+            .ok()
+            .filter(|count| (1..=MAX_STATS_RESULTS_LIMIT as i64).contains(count))
+            .ok_or(ValkeyError::Str(error_consts::INVALID_CURSOR_COUNT))? as usize;
+    }
+
+    Ok(MRangeCursor { after, count })
+}
+
 fn parse_asof_join_options(args: &mut CommandArgIterator) -> ValkeyResult<JoinType> {
     use CommandArgToken::*;
 
     // ASOF already seen
-    let mut tolerance = Duration::default();
+    let mut tolerance = None;
     let mut strategy = AsOfJoinStrategy::Backward;
 
-    // ASOF [PREVIOUS | NEXT | NEAREST] [tolerance] [ALLOW_EXACT_MATCH [true|false]]
-    if let Some(next) = advance_if_next_token_one_of(args, &[Previous, Next, Nearest]) {
+    // ASOF [PREVIOUS | PRIOR | NEXT | NEAREST] [tolerance] [ALLOW_EXACT_MATCH [true|false]]
+    if let Some(next) = advance_if_next_token_one_of(args, &[Previous, Prior, Next, Nearest]) {
         strategy = match next {
-            Previous => AsOfJoinStrategy::Backward,
+            Previous | Prior => AsOfJoinStrategy::Backward,
             Next => AsOfJoinStrategy::Forward,
             Nearest => AsOfJoinStrategy::Nearest,
             _ => unreachable!("BUG: invalid match arm for AsofJoinStrategy"),
@@ -934,7 +1560,7 @@ fn parse_asof_join_options(args: &mut CommandArgIterator) -> ValkeyResult<JoinTy
                 if tolerance_ms < 0 {
                     return Err(ValkeyError::Str(error_consts::INVALID_ASOF_TOLERANCE));
                 }
-                tolerance = Duration::from_millis(tolerance_ms as u64);
+                tolerance = Some(Duration::from_millis(tolerance_ms as u64));
                 let _ = args.next_arg()?;
             }
         }
@@ -955,9 +1581,12 @@ fn parse_asof_join_options(args: &mut CommandArgIterator) -> ValkeyResult<JoinTy
     }))
 }
 
+/// Parses the options of TS.JOIN, whose `FILTER_BY_TS` values are in the `unit` of the joined
+/// series.
 pub(super) fn parse_join_args(
     args: &mut CommandArgIterator,
     options: &mut JoinOptions,
+    unit: TimestampUnit,
 ) -> ValkeyResult<()> {
     use CommandArgToken::*;
     let mut join_type_set = false;
@@ -976,8 +1605,10 @@ pub(super) fn parse_join_args(
         Anti,
         AsOf,
         Count,
+        DivByZero,
         FilterByValue,
         FilterByTs,
+        Fill,
         Full,
         Inner,
         Left,
@@ -1001,11 +1632,27 @@ pub(super) fn parse_join_args(
             Count => {
                 options.count = Some(parse_count_arg(args)?);
             }
+            DivByZero => {
+                let arg = args.next_str()?;
+                options.div_by_zero = crate::join::DivByZero::try_from(arg)?;
+            }
             FilterByValue => {
                 options.value_filter = Some(parse_value_filter(args)?);
             }
             FilterByTs => {
-                options.timestamp_filter = Some(parse_timestamp_filter(args, VALID_TOKEN_ARGS)?);
+                options.timestamp_filter = Some(parse_timestamp_filter_values(
+                    args,
+                    VALID_TOKEN_ARGS,
+                    |arg| parse_series_timestamp(arg, unit),
+                )?);
+            }
+            Fill => {
+                let arg = args.next_str()?;
+                let fill = FillPolicy::try_from(arg)?;
+                if fill == FillPolicy::Linear {
+                    return Err(ValkeyError::Str(error_consts::INVALID_JOIN_FILL_POLICY));
+                }
+                options.fill = Some(fill);
             }
             Full => {
                 check_join_type_set(&mut join_type_set)?;
@@ -1046,6 +1693,12 @@ pub(super) fn parse_join_args(
         return Err(ValkeyError::Str(error_consts::MISSING_JOIN_REDUCER));
     }
 
+    if options.fill.is_some()
+        && (options.join_type == JoinType::Semi || options.join_type == JoinType::Anti)
+    {
+        return Err(ValkeyError::Str(error_consts::JOIN_FILL_NOT_SUPPORTED));
+    }
+
     // Disallow REDUCE for joins that only return single values per timestamp
     if options.reducer.is_some()
         && (options.join_type == JoinType::Semi || options.join_type == JoinType::Anti)
@@ -1061,8 +1714,32 @@ pub(crate) fn parse_metadata_command_args(
     args: &mut CommandArgIterator,
     require_matchers: bool,
 ) -> ValkeyResult<MatchFilterOptions> {
-    const ARG_TOKENS: [CommandArgToken; 2] =
-        [CommandArgToken::FilterByRange, CommandArgToken::Limit];
+    parse_metadata_command_args_internal(args, require_matchers, None, None)
+}
+
+/// Parses the arguments of TS.LABELVALUES, which additionally accepts `TOPK n` and `MATCH regex`.
+pub(crate) fn parse_label_values_command_args(
+    args: &mut CommandArgIterator,
+) -> ValkeyResult<(MatchFilterOptions, Option<usize>, Option<RegexMatcher>)> {
+    let mut topk: Option<usize> = None;
+    let mut value_match: Option<RegexMatcher> = None;
+    let options =
+        parse_metadata_command_args_internal(args, true, Some(&mut topk), Some(&mut value_match))?;
+    Ok((options, topk, value_match))
+}
+
+fn parse_metadata_command_args_internal(
+    args: &mut CommandArgIterator,
+    require_matchers: bool,
+    mut topk: Option<&mut Option<usize>>,
+    mut value_match: Option<&mut Option<RegexMatcher>>,
+) -> ValkeyResult<MatchFilterOptions> {
+    const ARG_TOKENS: [CommandArgToken; 4] = [
+        CommandArgToken::FilterByRange,
+        CommandArgToken::Limit,
+        CommandArgToken::Match,
+        CommandArgToken::TopK,
+    ];
 
     let mut matchers = Vec::with_capacity(4);
     let mut limit: Option<usize> = None;
@@ -1084,6 +1761,24 @@ pub(crate) fn parse_metadata_command_args(
                     .map_err(|_| ValkeyError::Str(error_consts::MISSING_LIMIT_VALUE))?;
                 limit = parse_limit_value(next)?;
             }
+            CommandArgToken::TopK if topk.is_some() => {
+                let next = args
+                    .next_str()
+                    .map_err(|_| ValkeyError::Str(error_consts::MISSING_TOPK_VALUE))?;
+                if let Some(topk) = topk.as_deref_mut() {
+                    *topk = parse_limit_value(next)?;
+                }
+            }
+            CommandArgToken::Match if value_match.is_some() => {
+                let next = args
+                    .next_str()
+                    .map_err(|_| ValkeyError::Str(error_consts::MISSING_MATCH_VALUE))?;
+                let regex = RegexMatcher::create(next)
+                    .map_err(|_| ValkeyError::Str(error_consts::INVALID_REGEX))?;
+                if let Some(value_match) = value_match.as_deref_mut() {
+                    *value_match = Some(regex);
+                }
+            }
             _ => {
                 let msg = "TSDB: invalid argument";
                 return Err(ValkeyError::Str(msg));
@@ -1106,14 +1801,52 @@ pub(super) fn parse_query_index_command_args(
     args: &mut CommandArgIterator,
 ) -> ValkeyResult<MatchFilterOptions> {
     let mut date_range: Option<MetaDateRangeFilter> = None;
+    let mut start: Option<TimestampValue> = None;
+    let mut end: Option<TimestampValue> = None;
 
-    if let Some(token) = peek_token(args)
-        && token == CommandArgToken::FilterByRange
-    {
-        // FILTER_BY_RANGE [NOT] <from> <to>
-        args.next(); // consume token
-        date_range = Some(parse_filter_by_range_options(args)?);
-    };
+    // FILTER_BY_RANGE [NOT] <from> <to> | START <from> | END <to>
+    while let Some(token) = peek_token(args) {
+        match token {
+            CommandArgToken::FilterByRange => {
+                args.next(); // consume token
+                date_range = Some(parse_filter_by_range_options(args)?);
+            }
+            CommandArgToken::Start => {
+                args.next();
+                let value = args.next_str()?;
+                start = Some(
+                    parse_timestamp_range_value(value)
+                        .map_err(|_| ValkeyError::Str(error_consts::INVALID_START_TIMESTAMP))?,
+                );
+            }
+            CommandArgToken::End => {
+                args.next();
+                let value = args.next_str()?;
+                end = Some(
+                    parse_timestamp_range_value(value)
+                        .map_err(|_| ValkeyError::Str(error_consts::INVALID_END_TIMESTAMP))?,
+                );
+            }
+            CommandArgToken::Filter => {
+                args.next();
+                break;
+            }
+            _ => break,
+        }
+    }
+
+    if start.is_some() || end.is_some() {
+        if date_range.is_some() {
+            return Err(ValkeyError::Str(
+                error_consts::START_END_WITH_FILTER_BY_RANGE,
+            ));
+        }
+        let range = TimestampRange::new(
+            start.unwrap_or(TimestampValue::Earliest),
+            end.unwrap_or(TimestampValue::Latest),
+        )?;
+        date_range = Some(range.into());
+    }
 
     // everything else are filters
 
@@ -1134,6 +1867,27 @@ pub(super) fn parse_query_index_command_args(
     })
 }
 
+/// Parses `<DIFF | XOR> <selectorA> <selectorB>`.
+pub(super) fn parse_series_diff_args(
+    args: &mut CommandArgIterator,
+) -> ValkeyResult<SeriesDiffOptions> {
+    let mode = SeriesDiffMode::try_from(args.next_str()?)?;
+
+    let mut next_selector = || -> ValkeyResult<SeriesSelector> {
+        let arg = args.next_str()?;
+        parse_series_selector(arg)
+            .map_err(|_| ValkeyError::Str(error_consts::INVALID_SERIES_SELECTOR))
+    };
+    let left = next_selector()?;
+    let right = next_selector()?;
+
+    if args.next().is_some() {
+        return Err(ValkeyError::WrongArity);
+    }
+
+    Ok(SeriesDiffOptions { mode, left, right })
+}
+
 pub const DEFAULT_STATS_RESULTS_LIMIT: usize = 10;
 pub const MAX_STATS_RESULTS_LIMIT: usize = 1000;
 
@@ -1165,6 +1919,42 @@ pub(super) fn parse_stats_command_args(
     Ok((label, limit))
 }
 
+/// Parses the arguments of TS.METRICS: `MATCH regex [LIMIT limit]`. Without `LIMIT`, at most
+/// `MAX_STATS_RESULTS_LIMIT` names are returned.
+pub(super) fn parse_metrics_command_args(
+    args: &mut CommandArgIterator,
+) -> ValkeyResult<(RegexMatcher, usize)> {
+    let mut name_match: Option<RegexMatcher> = None;
+    let mut limit = MAX_STATS_RESULTS_LIMIT;
+
+    while let Some(arg) = args.next() {
+        let token = parse_command_arg_token(arg.as_slice()).unwrap_or_default();
+        match token {
+            CommandArgToken::Match => {
+                let next = args
+                    .next_str()
+                    .map_err(|_| ValkeyError::Str(error_consts::MISSING_MATCH_VALUE))?;
+                let regex = RegexMatcher::create(next)
+                    .map_err(|_| ValkeyError::Str(error_consts::INVALID_REGEX))?;
+                name_match = Some(regex);
+            }
+            CommandArgToken::Limit => {
+                let next = args
+                    .next_str()
+                    .map_err(|_| ValkeyError::Str(error_consts::MISSING_LIMIT_VALUE))?;
+                limit = parse_limit_value(next)?.unwrap_or(MAX_STATS_RESULTS_LIMIT);
+            }
+            _ => {
+                let msg = "TSDB: invalid argument";
+                return Err(ValkeyError::Str(msg));
+            }
+        };
+    }
+
+    let name_match = name_match.ok_or(ValkeyError::Str(error_consts::MISSING_MATCH_VALUE))?;
+    Ok((name_match, limit))
+}
+
 fn parse_limit_value(val: &str) -> ValkeyResult<Option<usize>> {
     let limit = val
         .parse::<i64>()
@@ -1311,4 +2101,63 @@ mod tests {
             valid_near_max
         );
     }
+
+    #[test]
+    fn test_merge_timestamp_ranges() {
+        let ranges = vec![(50, 60), (10, 20), (15, 30), (31, 35), (70, 70)];
+        assert_eq!(
+            merge_timestamp_ranges(ranges),
+            vec![(10, 35), (50, 60), (70, 70)]
+        );
+
+        let nested = vec![(0, 100), (10, 20), (99, 150)];
+        assert_eq!(merge_timestamp_ranges(nested), vec![(0, 150)]);
+    }
+
+    #[test]
+    fn test_parse_series_timestamp_units() {
+        assert_eq!(
+            parse_series_timestamp("1500", TimestampUnit::Milliseconds).unwrap(),
+            1500
+        );
+        assert_eq!(
+            parse_series_timestamp("15", TimestampUnit::Seconds).unwrap(),
+            15_000
+        );
+        assert_eq!(
+            parse_series_timestamp("1500999", TimestampUnit::Microseconds).unwrap(),
+            1500
+        );
+        assert_eq!(
+            parse_series_timestamp("1500999999", TimestampUnit::Nanoseconds).unwrap(),
+            1500
+        );
+        // dates are absolute, whatever the unit
+        assert_eq!(
+            parse_series_timestamp("1970-01-01T00:00:01Z", TimestampUnit::Seconds).unwrap(),
+            1000
+        );
+        assert!(parse_series_timestamp(&i64::MAX.to_string(), TimestampUnit::Seconds).is_err());
+        assert!(parse_series_timestamp("abc", TimestampUnit::Seconds).is_err());
+    }
+
+    #[test]
+    fn test_parse_series_timestamp_interval() {
+        assert_eq!(
+            parse_series_timestamp_interval("10", TimestampUnit::Seconds).unwrap(),
+            (10_000, 10_999)
+        );
+        assert_eq!(
+            parse_series_timestamp_interval("10", TimestampUnit::Milliseconds).unwrap(),
+            (10, 10)
+        );
+        assert_eq!(
+            parse_series_timestamp_interval("10000", TimestampUnit::Microseconds).unwrap(),
+            (10, 10)
+        );
+        // between two milliseconds, so no sample can match
+        let (start, end) =
+            parse_series_timestamp_interval("10500", TimestampUnit::Microseconds).unwrap();
+        assert!(start > end);
+    }
 }
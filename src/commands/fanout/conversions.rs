@@ -3,25 +3,25 @@ use super::generated::{
     AggregationOptions as FanoutAggregationOptions, AggregationType as FanoutAggregationType,
     AggregatorConfig as FanoutAggregatorConfig, BucketAlignmentType, BucketTimestampType,
     ComparisonOperator as FanoutComparisonOperator, CompressionType as FanoutChunkEncoding,
-    DateRange, GroupingOptions as FanoutGroupingOptions, Label as FanoutLabel,
+    DateRange, FillPolicyType, GroupingOptions as FanoutGroupingOptions, Label as FanoutLabel,
     MetaDateRangeFilter as FanoutMetaDateRangeFilter, MultiRangeRequest,
     PostingStat as FanoutPostingStat, RangeRequest, Sample as FanoutSample,
     SeriesSelector as FanoutSeriesSelector, StatsResponse,
     ValueComparisonFilter as FanoutValueComparisonFilter, ValueRange as FanoutValueFilter,
 };
-use crate::commands::fanout::MGetValue;
+use crate::commands::fanout::{MGetValue, SeriesBoundsValue};
 use crate::common::binop::ComparisonOperator;
 use crate::labels::Label;
 use crate::labels::filters::SeriesSelector;
 use crate::series::chunks::ChunkEncoding;
 use crate::series::request_types::{
-    AggregationOptions, AggregationType, AggregatorConfig, BucketAlignment, MGetSeriesData,
-    MRangeOptions, MatchFilterOptions, MetaDateRangeFilter, RangeGroupingOptions, RangeOptions,
-    ValueComparisonFilter,
+    AggregationOptions, AggregationType, AggregatorConfig, BucketAlignment, FillPolicy,
+    MBoundsSeriesData, MGetSeriesData, MRangeOptions, MatchFilterOptions, MetaDateRangeFilter,
+    RangeGroupingOptions, RangeOptions, ValueComparisonFilter,
 };
 use crate::series::{TimestampRange, ValueFilter};
 use crate::{
-    aggregators::BucketTimestamp,
+    aggregators::{BucketTimestamp, CalendarUnit},
     error_consts,
     series::index::{PostingStat, PostingsStats},
 };
@@ -177,6 +177,29 @@ impl From<BucketTimestamp> for BucketTimestampType {
             BucketTimestamp::Start => BucketTimestampType::Start,
             BucketTimestamp::End => BucketTimestampType::End,
             BucketTimestamp::Mid => BucketTimestampType::Mid,
+            BucketTimestamp::Offset(_) => BucketTimestampType::Offset,
+        }
+    }
+}
+
+impl From<FillPolicy> for FillPolicyType {
+    fn from(value: FillPolicy) -> Self {
+        match value {
+            FillPolicy::Null => FillPolicyType::Null,
+            FillPolicy::Zero => FillPolicyType::Zero,
+            FillPolicy::Previous => FillPolicyType::Previous,
+            FillPolicy::Linear => FillPolicyType::Linear,
+        }
+    }
+}
+
+impl From<FillPolicyType> for FillPolicy {
+    fn from(value: FillPolicyType) -> Self {
+        match value {
+            FillPolicyType::Null => FillPolicy::Null,
+            FillPolicyType::Zero => FillPolicy::Zero,
+            FillPolicyType::Previous => FillPolicy::Previous,
+            FillPolicyType::Linear => FillPolicy::Linear,
         }
     }
 }
@@ -187,6 +210,7 @@ impl From<BucketTimestampType> for BucketTimestamp {
             BucketTimestampType::Start => BucketTimestamp::Start,
             BucketTimestampType::End => BucketTimestamp::End,
             BucketTimestampType::Mid => BucketTimestamp::Mid,
+            BucketTimestampType::Offset => BucketTimestamp::Offset(0),
         }
     }
 }
@@ -198,6 +222,10 @@ impl From<BucketAlignmentType> for BucketAlignment {
             BucketAlignmentType::AlignStart => BucketAlignment::Start,
             BucketAlignmentType::AlignEnd => BucketAlignment::End,
             BucketAlignmentType::Timestamp => BucketAlignment::Timestamp(0),
+            BucketAlignmentType::CalendarDay => BucketAlignment::Calendar(CalendarUnit::Day, 0),
+            BucketAlignmentType::CalendarWeek => BucketAlignment::Calendar(CalendarUnit::Week, 0),
+            BucketAlignmentType::CalendarMonth => BucketAlignment::Calendar(CalendarUnit::Month, 0),
+            BucketAlignmentType::SeriesStart => BucketAlignment::SeriesStart,
         }
     }
 }
@@ -228,6 +256,14 @@ impl From<AggregationType> for FanoutAggregationType {
             AggregationType::StdS => FanoutAggregationType::StdS,
             AggregationType::VarP => FanoutAggregationType::VarP,
             AggregationType::VarS => FanoutAggregationType::VarS,
+            AggregationType::Twa => FanoutAggregationType::Twa,
+            AggregationType::Delta => FanoutAggregationType::Delta,
+            AggregationType::Deriv => FanoutAggregationType::Deriv,
+            AggregationType::CountDistinct => FanoutAggregationType::CountDistinct,
+            AggregationType::AbsMax => FanoutAggregationType::AbsMax,
+            AggregationType::AbsMin => FanoutAggregationType::AbsMin,
+            AggregationType::TrimmedMean => FanoutAggregationType::TrimmedMean,
+            AggregationType::Mad => FanoutAggregationType::Mad,
         }
     }
 }
@@ -243,6 +279,7 @@ impl From<FanoutAggregationType> for FanoutAggregatorConfig {
         FanoutAggregatorConfig {
             aggregator_type: value as i32,
             value_filter: None,
+            param: None,
         }
     }
 }
@@ -273,6 +310,14 @@ impl From<FanoutAggregationType> for AggregationType {
             FanoutAggregationType::StdS => AggregationType::StdS,
             FanoutAggregationType::VarP => AggregationType::VarP,
             FanoutAggregationType::VarS => AggregationType::VarS,
+            FanoutAggregationType::Twa => AggregationType::Twa,
+            FanoutAggregationType::Delta => AggregationType::Delta,
+            FanoutAggregationType::Deriv => AggregationType::Deriv,
+            FanoutAggregationType::CountDistinct => AggregationType::CountDistinct,
+            FanoutAggregationType::AbsMax => AggregationType::AbsMax,
+            FanoutAggregationType::AbsMin => AggregationType::AbsMin,
+            FanoutAggregationType::TrimmedMean => AggregationType::TrimmedMean,
+            FanoutAggregationType::Mad => AggregationType::Mad,
         }
     }
 }
@@ -289,7 +334,11 @@ impl TryFrom<FanoutAggregatorConfig> for AggregatorConfig {
 
         let filter = value.value_filter.map(|f| f.into());
 
-        AggregatorConfig::new(aggregation_type, filter)
+        let config = AggregatorConfig::new(aggregation_type, filter)?;
+        match value.param {
+            Some(param) => config.with_param(param),
+            None => Ok(config),
+        }
     }
 }
 
@@ -305,7 +354,8 @@ impl TryFrom<&FanoutGroupingOptions> for RangeGroupingOptions {
 
         Ok(RangeGroupingOptions {
             aggregation,
-            group_label: value.group_label.clone(),
+            group_labels: value.group_labels.clone(),
+            with_sources: value.with_sources,
         })
     }
 }
@@ -319,7 +369,8 @@ impl TryFrom<FanoutGroupingOptions> for RangeGroupingOptions {
 
         Ok(RangeGroupingOptions {
             aggregation,
-            group_label: value.group_label,
+            group_labels: value.group_labels,
+            with_sources: value.with_sources,
         })
     }
 }
@@ -329,7 +380,8 @@ impl From<&RangeGroupingOptions> for FanoutGroupingOptions {
         let aggregation: FanoutAggregatorConfig = value.aggregation.into();
         FanoutGroupingOptions {
             aggregation: Some(aggregation),
-            group_label: value.group_label.clone(),
+            group_labels: value.group_labels.clone(),
+            with_sources: value.with_sources,
         }
     }
 }
@@ -339,7 +391,8 @@ impl From<RangeGroupingOptions> for FanoutGroupingOptions {
         let aggregation: FanoutAggregatorConfig = value.aggregation.into();
         FanoutGroupingOptions {
             aggregation: Some(aggregation),
-            group_label: value.group_label,
+            group_labels: value.group_labels,
+            with_sources: value.with_sources,
         }
     }
 }
@@ -365,6 +418,18 @@ impl From<MGetSeriesData> for MGetValue {
     }
 }
 
+impl From<MBoundsSeriesData> for SeriesBoundsValue {
+    fn from(value: MBoundsSeriesData) -> Self {
+        SeriesBoundsValue {
+            key: value.series_key.to_string_lossy(),
+            labels: value.labels.into_iter().map(|l| l.into()).collect(),
+            first_timestamp: value.bounds.map(|(first, _)| first),
+            last_timestamp: value.bounds.map(|(_, last)| last),
+            total_samples: value.total_samples as u64,
+        }
+    }
+}
+
 pub fn deserialize_match_filter_options(
     range: Option<FanoutMetaDateRangeFilter>,
     filters: Option<Vec<FanoutSeriesSelector>>,
@@ -441,6 +506,7 @@ impl From<AggregatorConfig> for FanoutAggregatorConfig {
         FanoutAggregatorConfig {
             aggregator_type: aggr_type.into(),
             value_filter: value.filter().map(|filter| filter.into()),
+            param: value.param(),
         }
     }
 }
@@ -455,15 +521,29 @@ impl From<AggregationOptions> for FanoutAggregationOptions {
             BucketAlignment::Start => (BucketAlignmentType::AlignStart, 0),
             BucketAlignment::End => (BucketAlignmentType::AlignEnd, 0),
             BucketAlignment::Timestamp(ts) => (BucketAlignmentType::Timestamp, ts),
+            BucketAlignment::SeriesStart => (BucketAlignmentType::SeriesStart, 0),
+            BucketAlignment::Calendar(unit, offset) => {
+                let alignment_type = match unit {
+                    CalendarUnit::Day => BucketAlignmentType::CalendarDay,
+                    CalendarUnit::Week => BucketAlignmentType::CalendarWeek,
+                    CalendarUnit::Month => BucketAlignmentType::CalendarMonth,
+                };
+                (alignment_type, offset)
+            }
         };
 
         FanoutAggregationOptions {
             aggregator: Some(aggregator),
-            bucket_duration: value.bucket_duration as u32,
+            bucket_duration: value.bucket_duration,
             bucket_timestamp_type: bucket_timestamp_type.into(),
             bucket_alignment: bucket_alignment.into(),
             alignment_timestamp,
             report_empty: value.report_empty,
+            fill: value.fill.map(|fill| FillPolicyType::from(fill).into()),
+            bucket_timestamp_offset: match value.timestamp_output {
+                BucketTimestamp::Offset(offset) => offset,
+                _ => 0,
+            },
         }
     }
 }
@@ -477,7 +557,7 @@ impl TryFrom<FanoutAggregationOptions> for AggregationOptions {
         } else {
             return Err(ValkeyError::Str("TSDB: aggregation config is required"));
         };
-        let bucket_duration = value.bucket_duration as u64;
+        let bucket_duration = value.bucket_duration;
         if bucket_duration == 0 {
             return Err(ValkeyError::Str("TSDB: bucket duration must be positive"));
         }
@@ -491,19 +571,40 @@ impl TryFrom<FanoutAggregationOptions> for AggregationOptions {
             .map_err(|_| ValkeyError::Str(error_consts::INVALID_BUCKET_ALIGNMENT))?;
 
         let mut alignment: BucketAlignment = fanout_alignment.into();
-        if matches!(alignment, BucketAlignment::Timestamp(_)) {
-            let timestamp = value.alignment_timestamp;
-            alignment = BucketAlignment::Timestamp(timestamp);
+        match alignment {
+            BucketAlignment::Timestamp(_) => {
+                alignment = BucketAlignment::Timestamp(value.alignment_timestamp);
+            }
+            BucketAlignment::Calendar(unit, _) => {
+                alignment = BucketAlignment::Calendar(unit, value.alignment_timestamp);
+            }
+            _ => {}
         }
 
         let report_empty = value.report_empty;
+        let fill = value
+            .fill
+            .map(|fill| {
+                FillPolicyType::try_from(fill)
+                    .map(FillPolicy::from)
+                    .map_err(|_| ValkeyError::Str(error_consts::INVALID_FILL_POLICY))
+            })
+            .transpose()?;
 
         Ok(AggregationOptions {
             aggregation,
             bucket_duration,
-            timestamp_output: timestamp_output.into(),
+            timestamp_output: match BucketTimestamp::from(timestamp_output) {
+                BucketTimestamp::Offset(_) => {
+                    BucketTimestamp::Offset(value.bucket_timestamp_offset)
+                }
+                output => output,
+            },
             alignment,
             report_empty,
+            fill,
+            with_counts: false,
+            dedup: None,
         })
     }
 }
@@ -546,6 +647,18 @@ impl TryFrom<&RangeRequest> for RangeOptions {
             Some(value.timestamp_filter.clone())
         };
 
+        let timestamp_ranges = if value.timestamp_ranges.is_empty() {
+            None
+        } else {
+            Some(
+                value
+                    .timestamp_ranges
+                    .iter()
+                    .map(|r| (r.start, r.end))
+                    .collect(),
+            )
+        };
+
         let value_filter: Option<ValueFilter> = value.value_filter.map(|filter| ValueFilter {
             min: filter.min,
             max: filter.max,
@@ -558,8 +671,13 @@ impl TryFrom<&RangeRequest> for RangeOptions {
             count,
             aggregation,
             timestamp_filter,
+            timestamp_ranges,
             value_filter,
             latest,
+            max_points: None,
+            count_from_end: false,
+            transform: None,
+            function: None,
         })
     }
 }
@@ -585,6 +703,13 @@ impl From<&RangeOptions> for RangeRequest {
             None => vec![],
         };
 
+        let timestamp_ranges = value
+            .timestamp_ranges
+            .iter()
+            .flatten()
+            .map(|&(start, end)| DateRange { start, end })
+            .collect();
+
         let value_filter: Option<FanoutValueFilter> =
             value.value_filter.map(|filter| FanoutValueFilter {
                 min: filter.min,
@@ -596,6 +721,7 @@ impl From<&RangeOptions> for RangeRequest {
             count,
             aggregation,
             timestamp_filter,
+            timestamp_ranges,
             value_filter,
             latest: value.latest,
         }
@@ -632,8 +758,11 @@ impl TryFrom<&MultiRangeRequest> for MRangeOptions {
             filters,
             with_labels,
             selected_labels,
+            label_aliases: Default::default(),
             grouping,
             is_reverse,
+            sample_limit: None,
+            cursor: None,
         })
     }
 }
@@ -664,8 +793,11 @@ impl TryFrom<MultiRangeRequest> for MRangeOptions {
             filters,
             with_labels,
             selected_labels,
+            label_aliases: Default::default(),
             grouping,
             is_reverse,
+            sample_limit: None,
+            cursor: None,
         })
     }
 }
@@ -737,6 +869,9 @@ mod tests {
             timestamp_output: BucketTimestamp::Start,
             alignment: BucketAlignment::Timestamp(555),
             report_empty: true,
+            fill: None,
+            with_counts: false,
+            dedup: None,
         };
 
         let fanout: FanoutAggregationOptions = options.into();
@@ -764,6 +899,70 @@ mod tests {
         assert_eq!(filter.value, 10.0);
     }
 
+    #[test]
+    fn test_aggregation_param_round_trip() {
+        let options = AggregationOptions {
+            aggregation: AggregatorConfig::from(AggregationType::TrimmedMean)
+                .with_param(12.5)
+                .unwrap(),
+            bucket_duration: 1000,
+            ..Default::default()
+        };
+
+        let fanout: FanoutAggregationOptions = options.into();
+        let f_aggr = fanout.aggregator.clone().unwrap();
+        assert_eq!(
+            f_aggr.aggregator_type,
+            FanoutAggregationType::TrimmedMean as i32
+        );
+        assert_eq!(f_aggr.param, Some(12.5));
+
+        let back: AggregationOptions = fanout.try_into().unwrap();
+        assert_eq!(
+            back.aggregation.aggregation_type(),
+            AggregationType::TrimmedMean
+        );
+        assert_eq!(back.aggregation.param(), Some(12.5));
+    }
+
+    #[test]
+    fn test_calendar_alignment_round_trip() {
+        let options = AggregationOptions {
+            bucket_duration: 60 * 24 * 60 * 60 * 1000,
+            alignment: BucketAlignment::Calendar(CalendarUnit::Month, -5 * 60 * 60 * 1000),
+            ..Default::default()
+        };
+
+        let fanout: FanoutAggregationOptions = options.into();
+        assert_eq!(
+            fanout.bucket_alignment,
+            BucketAlignmentType::CalendarMonth as i32
+        );
+        assert_eq!(fanout.alignment_timestamp, -5 * 60 * 60 * 1000);
+
+        let back: AggregationOptions = fanout.try_into().unwrap();
+        assert_eq!(back.alignment, options.alignment);
+        assert_eq!(back.bucket_duration, options.bucket_duration);
+    }
+
+    #[test]
+    fn test_bucket_timestamp_offset_round_trip() {
+        let options = AggregationOptions {
+            timestamp_output: BucketTimestamp::Offset(250),
+            ..Default::default()
+        };
+
+        let fanout: FanoutAggregationOptions = options.into();
+        assert_eq!(
+            fanout.bucket_timestamp_type,
+            BucketTimestampType::Offset as i32
+        );
+        assert_eq!(fanout.bucket_timestamp_offset, 250);
+
+        let back: AggregationOptions = fanout.try_into().unwrap();
+        assert_eq!(back.timestamp_output, BucketTimestamp::Offset(250));
+    }
+
     #[test]
     fn test_fanout_to_aggregation_options_alignments() {
         let alignments = vec![
@@ -776,6 +975,7 @@ mod tests {
             let aggregator = FanoutAggregatorConfig {
                 aggregator_type: FanoutAggregationType::Max as i32,
                 value_filter: None,
+                param: None,
             };
             let fanout = FanoutAggregationOptions {
                 aggregator: Some(aggregator),
@@ -784,6 +984,8 @@ mod tests {
                 bucket_alignment: fanout_type as i32,
                 alignment_timestamp: 0,
                 report_empty: false,
+                fill: None,
+                bucket_timestamp_offset: 0,
             };
 
             let options: AggregationOptions = fanout.try_into().unwrap();
@@ -796,6 +998,7 @@ mod tests {
         let aggregator = FanoutAggregatorConfig {
             aggregator_type: FanoutAggregationType::Count as i32,
             value_filter: None,
+            param: None,
         };
         let fanout = FanoutAggregationOptions {
             aggregator: Some(aggregator),
@@ -804,6 +1007,8 @@ mod tests {
             bucket_alignment: BucketAlignmentType::Default as i32,
             alignment_timestamp: 0,
             report_empty: false,
+            fill: None,
+            bucket_timestamp_offset: 0,
         };
 
         let result: Result<AggregationOptions, ValkeyError> = fanout.try_into();
@@ -828,8 +1033,11 @@ mod tests {
                 bucket_alignment: BucketAlignmentType::AlignStart.into(),
                 alignment_timestamp: 0,
                 report_empty: true,
+                fill: None,
+                bucket_timestamp_offset: 0,
             }),
             timestamp_filter: vec![1050, 1100],
+            timestamp_ranges: vec![],
             value_filter: Some(FanoutValueFilter {
                 min: 10.5,
                 max: 20.5,
@@ -865,8 +1073,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let request: RangeRequest = (&options).into();
@@ -880,6 +1093,29 @@ mod tests {
         assert!(!request.latest);
     }
 
+    #[test]
+    fn test_range_options_timestamp_ranges_round_trip() {
+        let options = RangeOptions {
+            date_range: TimestampRange::from_timestamps(0, 5000).unwrap(),
+            timestamp_ranges: Some(vec![(100, 200), (1000, 1500)]),
+            max_points: None,
+            count_from_end: false,
+            ..Default::default()
+        };
+
+        let request: RangeRequest = (&options).into();
+        assert_eq!(request.timestamp_ranges.len(), 2);
+        assert_eq!(request.timestamp_ranges[1].start, 1000);
+        assert_eq!(request.timestamp_ranges[1].end, 1500);
+
+        let decoded: RangeOptions = (&request).try_into().unwrap();
+        assert_eq!(
+            decoded.timestamp_ranges,
+            Some(vec![(100, 200), (1000, 1500)])
+        );
+        assert!(decoded.timestamp_filter.is_none());
+    }
+
     #[test]
     fn test_range_request_missing_range_fails() {
         let request = RangeRequest {
@@ -913,10 +1149,18 @@ mod tests {
                 timestamp_output: BucketTimestamp::End,
                 alignment: BucketAlignment::Timestamp(123),
                 report_empty: false,
+                fill: None,
+                with_counts: false,
+                dedup: None,
             }),
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: Some(ValueFilter { min: 1.0, max: 2.0 }),
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let request: RangeRequest = (&original_options).into();
@@ -5,8 +5,8 @@ use super::generated::{
 };
 use crate::commands::fanout::series_selector::Filters;
 use crate::labels::filters::{
-    FilterList, LabelFilter, OrFiltersList, PredicateMatch, PredicateValue, RegexMatcher,
-    SeriesSelector, ValueList,
+    FilterList, LabelFilter, MatchOp, NumericMatcher, OrFiltersList, PredicateMatch,
+    PredicateValue, RegexMatcher, SeriesSelector, ValueList,
 };
 use crate::series::DateRange;
 use crate::series::request_types::MetaDateRangeFilter;
@@ -47,6 +47,16 @@ impl From<&LabelFilter> for FanoutFilter {
                 let value = Value::Single(regex.value.clone());
                 (value, MatcherOpType::RegexNotEqual)
             }
+            PredicateMatch::Numeric(numeric) => {
+                let value = Value::Single(numeric.value.clone());
+                let op = match numeric.op {
+                    MatchOp::Gt => MatcherOpType::GreaterThan,
+                    MatchOp::Ge => MatcherOpType::GreaterEqual,
+                    MatchOp::Lt => MatcherOpType::LessThan,
+                    _ => MatcherOpType::LessEqual,
+                };
+                (value, op)
+            }
         };
 
         FanoutFilter {
@@ -196,6 +206,17 @@ impl TryFrom<&FanoutFilter> for LabelFilter {
             }
             Err(ValkeyError::Str("TSDB: invalid or empty regex value"))
         }
+
+        fn get_numeric_value(value: &FanoutFilter, op: MatchOp) -> ValkeyResult<PredicateMatch> {
+            let Some(Value::Single(v)) = &value.value else {
+                return Err(ValkeyError::Str(
+                    "TSDB: invalid or empty numeric matcher value",
+                ));
+            };
+            NumericMatcher::create(op, v)
+                .map(PredicateMatch::Numeric)
+                .map_err(|e| ValkeyError::String(format!("TSDB: numeric matcher error: {e:?}")))
+        }
         let op: MatcherOpType = value.op.try_into().map_err(|_| {
             ValkeyError::Str("TSDB: invalid matcher operation, cannot convert from i32")
         })?;
@@ -210,6 +231,10 @@ impl TryFrom<&FanoutFilter> for LabelFilter {
             }
             MatcherOpType::RegexEqual => PredicateMatch::RegexEqual(get_regex_value(value)?),
             MatcherOpType::RegexNotEqual => PredicateMatch::RegexNotEqual(get_regex_value(value)?),
+            MatcherOpType::GreaterThan => get_numeric_value(value, MatchOp::Gt)?,
+            MatcherOpType::GreaterEqual => get_numeric_value(value, MatchOp::Ge)?,
+            MatcherOpType::LessThan => get_numeric_value(value, MatchOp::Lt)?,
+            MatcherOpType::LessEqual => get_numeric_value(value, MatchOp::Le)?,
         };
 
         if value.label.is_empty() {
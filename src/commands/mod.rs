@@ -3,6 +3,7 @@ mod fanout;
 mod ts_add;
 mod ts_addbulk;
 mod ts_alter;
+mod ts_annotate;
 mod ts_card;
 mod ts_card_fanout_command;
 mod ts_create;
@@ -11,9 +12,13 @@ mod ts_debug;
 mod ts_debug_configs;
 mod ts_del;
 mod ts_deleterule;
+mod ts_export;
+mod ts_gc;
 mod ts_get;
+mod ts_histogram;
 mod ts_incr_decr_by;
 mod ts_info;
+mod ts_ingest;
 mod ts_join;
 mod ts_labelnames;
 mod ts_labelnames_fanout_command;
@@ -22,8 +27,12 @@ mod ts_labelstats_fanout_command;
 mod ts_labelvalues;
 mod ts_labelvalues_fanout_command;
 mod ts_madd;
+mod ts_mbounds;
+mod ts_mbounds_fanout_command;
 mod ts_mdel;
 mod ts_mdel_fanout_command;
+mod ts_merge;
+mod ts_metrics;
 mod ts_mget;
 mod ts_mget_fanout_command;
 mod ts_mrange;
@@ -32,32 +41,47 @@ mod ts_outliers;
 mod ts_queryindex;
 mod ts_queryindex_fanout_command;
 mod ts_range;
+mod ts_seal;
+mod ts_seriesdiff;
+mod ts_seriesdiff_fanout_command;
+mod ts_verifyindex;
 mod utils;
 
 pub use command_parser::*;
 pub use ts_add::*;
 pub use ts_addbulk::*;
 pub use ts_alter::*;
+pub use ts_annotate::*;
 pub use ts_card::*;
 pub use ts_create::*;
 pub use ts_createrule::*;
 pub use ts_debug::*;
 pub use ts_del::*;
 pub use ts_deleterule::*;
+pub use ts_export::*;
+pub use ts_gc::*;
 pub use ts_get::*;
+pub use ts_histogram::*;
 pub use ts_incr_decr_by::*;
 pub use ts_info::*;
+pub use ts_ingest::*;
 pub use ts_join::*;
 pub use ts_labelnames::*;
 pub use ts_labelstats::*;
 pub use ts_labelvalues::*;
 pub use ts_madd::*;
+pub use ts_mbounds::*;
 pub use ts_mdel::*;
+pub use ts_merge::*;
+pub use ts_metrics::*;
 pub use ts_mget::*;
 pub use ts_mrange::*;
 pub use ts_outliers::*;
 pub use ts_queryindex::*;
 pub use ts_range::*;
+pub use ts_seal::*;
+pub use ts_seriesdiff::*;
+pub use ts_verifyindex::*;
 use valkey_module::ValkeyResult;
 
 use crate::fanout::register_fanout_operation;
@@ -65,20 +89,24 @@ use ts_card_fanout_command::CardFanoutCommand;
 use ts_labelnames_fanout_command::LabelNamesFanoutCommand;
 use ts_labelstats_fanout_command::LabelStatsFanoutCommand;
 use ts_labelvalues_fanout_command::LabelValuesFanoutCommand;
+use ts_mbounds_fanout_command::MBoundsFanoutCommand;
 use ts_mdel_fanout_command::MDelFanoutCommand;
 use ts_mget_fanout_command::MGetFanoutCommand;
 use ts_mrange_fanout_command::MRangeFanoutCommand;
 use ts_queryindex_fanout_command::QueryIndexFanoutCommand;
+use ts_seriesdiff_fanout_command::SeriesDiffFanoutCommand;
 
 pub(crate) fn register_fanout_operations() -> ValkeyResult<()> {
     register_fanout_operation::<LabelStatsFanoutCommand>()?;
     register_fanout_operation::<CardFanoutCommand>()?;
     register_fanout_operation::<LabelNamesFanoutCommand>()?;
     register_fanout_operation::<LabelValuesFanoutCommand>()?;
+    register_fanout_operation::<MBoundsFanoutCommand>()?;
     register_fanout_operation::<MDelFanoutCommand>()?;
     register_fanout_operation::<MGetFanoutCommand>()?;
     register_fanout_operation::<MRangeFanoutCommand>()?;
     register_fanout_operation::<QueryIndexFanoutCommand>()?;
+    register_fanout_operation::<SeriesDiffFanoutCommand>()?;
     Ok(())
 }
 
@@ -1,7 +1,12 @@
-use crate::commands::command_parser::{parse_timestamp, parse_value_arg};
+use crate::commands::command_parser::{
+    parse_duplicate_policy, parse_series_timestamp, parse_value_arg,
+};
 use crate::commands::ts_create::parse_series_options;
 use crate::common::{Sample, Timestamp};
-use crate::series::{SampleAddResult, TimeSeries, create_and_store_series, get_timeseries_mut};
+use crate::series::{
+    DuplicatePolicy, SampleAddResult, TimeSeries, check_future_timestamp, create_and_store_series,
+    get_max_future_timestamp, get_timeseries_mut,
+};
 use valkey_module::{
     AclPermissions, Context, NotifyEvent, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
 };
@@ -16,28 +21,46 @@ use valkey_module::{
 ///     [METRIC metric | LABELS labelName labelValue ...]
 ///     [IGNORE ignoreMaxTimediff ignoreMaxValDiff]
 ///     [SIGNIFICANT_DIGITS significantDigits | DECIMAL_DIGITS decimalDigits]
+///     [TIMESTAMP_UNIT <s|ms|us|ns>]
 ///     [LABELS label1=value1 label2=value2 ...]
 ///
+/// The series options only apply when the series is created by this call; they are ignored if the
+/// series already exists. `ON_DUPLICATE` applies to this sample in either case. The timestamp is
+/// in the unit of the series, and so is the one returned.
 pub fn ts_add_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     if args.len() < 4 {
         return Err(ValkeyError::WrongArity);
     }
 
     let timestamp_str = args[2].try_as_str()?;
-    let timestamp = parse_timestamp(timestamp_str)?;
 
     let value = parse_value_arg(&args[3])?;
+    let on_duplicate = parse_on_duplicate(&args)?;
 
     if let Some(mut guard) = get_timeseries_mut(ctx, &args[1], false, Some(AclPermissions::UPDATE))?
     {
-        // args.done()?;
-        return handle_add(ctx, &mut guard, args, timestamp, timestamp_str, value);
+        let timestamp = parse_series_timestamp(timestamp_str, guard.timestamp_unit)?;
+        check_future_timestamp(timestamp, get_max_future_timestamp(ctx))?;
+        return handle_add(
+            ctx,
+            &mut guard,
+            args,
+            timestamp,
+            timestamp_str,
+            value,
+            on_duplicate,
+        );
     }
 
     // clones because of replicate_and_notify
     let original_args = args.clone();
 
     let options = parse_series_options(args, 4, &[])?;
+    // parsed before the series is created, so that an invalid timestamp leaves no empty series
+    let timestamp =
+        parse_series_timestamp(timestamp_str, options.timestamp_unit.unwrap_or_default())?;
+    // likewise, a sample rejected for being too far in the future should not create the series
+    check_future_timestamp(timestamp, get_max_future_timestamp(ctx))?;
 
     let key = &original_args[1];
     let mut series = create_and_store_series(ctx, key, options, true, true)?;
@@ -49,9 +72,24 @@ pub fn ts_add_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
         timestamp,
         timestamp_str,
         value,
+        on_duplicate,
     )
 }
 
+/// Finds the `ON_DUPLICATE` override, ignoring anything after `LABELS` since those are labels.
+fn parse_on_duplicate(args: &[ValkeyString]) -> ValkeyResult<Option<DuplicatePolicy>> {
+    let Some(pos) = args
+        .iter()
+        .skip(4)
+        .take_while(|arg| !arg.eq_ignore_ascii_case(b"labels"))
+        .position(|arg| arg.eq_ignore_ascii_case(b"on_duplicate"))
+    else {
+        return Ok(None);
+    };
+    let mut rest = args.to_vec().into_iter().skip(pos + 5).peekable();
+    parse_duplicate_policy(&mut rest).map(Some)
+}
+
 fn handle_add(
     ctx: &Context,
     series: &mut TimeSeries,
@@ -59,12 +97,14 @@ fn handle_add(
     timestamp: Timestamp,
     timestamp_str: &str,
     value: f64,
+    on_duplicate: Option<DuplicatePolicy>,
 ) -> ValkeyResult {
     let mut ignored = false;
+    let unit = series.timestamp_unit;
 
     let last_ts = series.last_sample.map(|s| s.timestamp);
 
-    let (replication_timestamp, ts, value) = match series.add(timestamp, value, None) {
+    let (replication_timestamp, ts, value) = match series.add(timestamp, value, on_duplicate) {
         SampleAddResult::Ignored(res_ts) => {
             ignored = true;
             let timestamp = if timestamp_str == "*" {
@@ -108,7 +148,7 @@ fn handle_add(
                 return Err(ValkeyError::String(msg));
             }
             // run compaction_upsert
-            return Ok(ValkeyValue::Integer(ts));
+            return Ok(ValkeyValue::Integer(unit.from_millis(ts)));
         } else {
             let sample = series.last_sample.unwrap_or(Sample::new(ts, value));
             // If the sample is not an upsert, we run compaction
@@ -116,8 +156,10 @@ fn handle_add(
         }
     }
 
+    // replicas read the timestamp in the series unit, and "*" was truncated to it when parsed
+    let replication_timestamp = replication_timestamp.map(|ts| unit.from_millis(ts));
     replicate_and_notify(ctx, args, replication_timestamp);
-    Ok(ValkeyValue::Integer(ts))
+    Ok(ValkeyValue::Integer(unit.from_millis(ts)))
 }
 
 fn replicate_and_notify(ctx: &Context, args: Vec<ValkeyString>, timestamp: Option<Timestamp>) {
@@ -1,7 +1,8 @@
 use crate::commands::ts_create::parse_series_options;
 use crate::common::Sample;
 use crate::series::{
-    IngestedSamples, TimeSeries, bulk_insert_samples, create_and_store_series, get_timeseries_mut,
+    IngestedSamples, TimeSeries, bulk_insert_samples, check_future_timestamp,
+    create_and_store_series, get_max_future_timestamp, get_timeseries_mut,
 };
 use valkey_module::{AclPermissions, Context, ValkeyResult, ValkeyString, ValkeyValue};
 
@@ -30,6 +31,12 @@ pub fn ts_addbulk_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
         sample_data.samples
     };
 
+    // the samples are replicated verbatim, so reject the whole batch rather than some samples
+    let max_timestamp = get_max_future_timestamp(ctx);
+    for sample in samples.iter() {
+        check_future_timestamp(sample.timestamp, max_timestamp)?;
+    }
+
     let options = parse_series_options(args, 4, &[])?;
 
     if let Some(mut guard) = get_timeseries_mut(ctx, &key, false, Some(AclPermissions::UPDATE))? {
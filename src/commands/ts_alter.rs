@@ -1,9 +1,9 @@
 use crate::commands::command_parser::CommandArgToken;
-use crate::commands::ts_create::parse_series_options;
+use crate::commands::ts_create::parse_series_options_with_defaults;
 use crate::labels::MetricName;
+use crate::series::chunks::ChunkEncoding;
 use crate::series::index::get_timeseries_index;
 use crate::series::{SampleDuplicatePolicy, TimeSeries, TimeSeriesOptions, with_timeseries_mut};
-use std::ops::Deref;
 use valkey_module::{
     AclPermissions, Context, NotifyEvent, VALKEY_OK, ValkeyError, ValkeyResult, ValkeyString,
 };
@@ -12,6 +12,10 @@ use valkey_module::{
 ///
 /// TS.ALTER key
 ///   [RETENTION retentionPeriod]
+///   [RETENTION_SAMPLES maxSamples]
+///   [ENCODING <COMPRESSED|UNCOMPRESSED|GORILLA|PCO|AUTO>]
+///   [SPLIT_POLICY <HALF|INSERTION|percent>]
+///   [TIMESTAMP_UNIT <s|ms|us|ns>]
 ///   [DUPLICATE_POLICY duplicatePolicy]
 ///   [SIGNIFICANT_DIGITS significantDigits | DECIMAL_DIGITS decimalDigits]
 ///   [IGNORE ignoreMaxTimediff ignoreMaxValDiff]
@@ -25,13 +29,19 @@ pub fn ts_alter_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let key = args.remove(1);
 
     with_timeseries_mut(ctx, &key, Some(AclPermissions::UPDATE), |series| {
-        let options = parse_series_options(
+        let encoding_set = has_encoding_arg(&args);
+        // options not given keep their current value, rather than reverting to the config
+        let options = parse_series_options_with_defaults(
+            options_from_series(series),
             args,
             1,
-            &[CommandArgToken::Encoding, CommandArgToken::OnDuplicate],
+            &[CommandArgToken::OnDuplicate],
         )?;
+        // AUTO does not re-encode: it only defers the encoding of new chunks to the data
+        let encoding =
+            (encoding_set && !options.auto_encoding).then_some(options.chunk_compression);
 
-        let changed = update_series(ctx, series, options, &key);
+        let changed = update_series(ctx, series, options, encoding, &key)?;
 
         ctx.replicate_verbatim();
         if changed {
@@ -41,6 +51,17 @@ pub fn ts_alter_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     })
 }
 
+/// `TimeSeriesOptions` always carries an encoding, so check whether one was actually requested.
+/// Only the arguments before `LABELS` are considered, since everything after it is a label.
+fn has_encoding_arg(args: &[ValkeyString]) -> bool {
+    args.iter()
+        .skip(1)
+        .take_while(|arg| !arg.eq_ignore_ascii_case(b"labels"))
+        .any(|arg| arg.eq_ignore_ascii_case(b"encoding"))
+}
+
+/// The current settings of `series`, used as the defaults when parsing ALTER arguments. Labels and
+/// rounding are left unset, so that they are only touched when given explicitly.
 fn options_from_series(series: &TimeSeries) -> TimeSeriesOptions {
     let policy_default = SampleDuplicatePolicy::default();
     let sample_duplicates = if series.sample_duplicates == policy_default {
@@ -48,18 +69,15 @@ fn options_from_series(series: &TimeSeries) -> TimeSeriesOptions {
     } else {
         Some(series.sample_duplicates)
     };
-    let labels = if series.labels.is_empty() {
-        None
-    } else {
-        Some(series.labels.to_label_vec())
-    };
     TimeSeriesOptions {
         retention: Some(series.retention),
+        max_samples: series.max_samples,
         chunk_size: Some(series.chunk_size_bytes),
-        labels,
         sample_duplicate_policy: sample_duplicates,
         chunk_compression: series.chunk_compression,
-        rounding: series.rounding,
+        auto_encoding: series.auto_encoding,
+        split_policy: Some(series.split_policy),
+        timestamp_unit: Some(series.timestamp_unit),
         ..Default::default()
     }
 }
@@ -68,10 +86,24 @@ fn update_series(
     ctx: &Context,
     series: &mut TimeSeries,
     options: TimeSeriesOptions,
+    encoding: Option<ChunkEncoding>,
     key: &ValkeyString,
-) -> bool {
+) -> ValkeyResult<bool> {
     let mut has_changed = false;
 
+    if let Some(labels) = options.labels {
+        let labels = if labels.is_empty() {
+            MetricName::default()
+        } else {
+            MetricName::new(&labels)
+        };
+
+        // Applied first, so that a label set rejected as a duplicate leaves the series untouched
+        let guard = get_timeseries_index(ctx);
+        guard.relabel_timeseries(series, labels, key.as_slice())?;
+        has_changed = true;
+    }
+
     if let Some(chunk_size) = options.chunk_size
         && chunk_size != series.chunk_size_bytes
     {
@@ -80,28 +112,34 @@ fn update_series(
         has_changed = true;
     }
 
-    if let Some(labels) = options.labels {
+    if let Some(retention) = options.retention
+        && retention != series.retention
+    {
+        series.retention = retention;
         has_changed = true;
+    }
 
-        // reindex the series
-        let guard = get_timeseries_index(ctx);
-        let ts_index = guard.deref();
-
-        ts_index.remove_timeseries(series);
-        // update labels in series
-        series.labels = if labels.is_empty() {
-            MetricName::default()
-        } else {
-            MetricName::new(&labels)
-        };
+    if let Some(max_samples) = options.max_samples {
+        let max_samples = (max_samples > 0).then_some(max_samples);
+        if max_samples != series.max_samples {
+            series.max_samples = max_samples;
+            series.enforce_max_samples();
+            has_changed = true;
+        }
+    }
 
-        ts_index.index_timeseries(series, key.as_slice());
+    if let Some(split_policy) = options.split_policy
+        && split_policy != series.split_policy
+    {
+        series.split_policy = split_policy;
+        has_changed = true;
     }
 
-    if let Some(retention) = options.retention
-        && retention != series.retention
+    if let Some(timestamp_unit) = options.timestamp_unit
+        && timestamp_unit != series.timestamp_unit
     {
-        series.retention = retention;
+        // samples are stored in ms, so only the unit used by commands changes
+        series.timestamp_unit = timestamp_unit;
         has_changed = true;
     }
 
@@ -112,5 +150,16 @@ fn update_series(
         has_changed = true;
     }
 
-    has_changed
+    if options.auto_encoding != series.auto_encoding {
+        series.auto_encoding = options.auto_encoding;
+        has_changed = true;
+    }
+
+    if let Some(encoding) = encoding {
+        has_changed |= series
+            .set_encoding(encoding)
+            .map_err(|e| ValkeyError::String(format!("TSDB: error re-encoding series: {e}")))?;
+    }
+
+    Ok(has_changed)
 }
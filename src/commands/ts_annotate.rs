@@ -0,0 +1,55 @@
+use crate::commands::command_parser::parse_series_timestamp;
+use crate::error_consts;
+use crate::series::with_timeseries_mut;
+use valkey_module::{
+    AclPermissions, Context, NextArg, NotifyEvent, ValkeyError, ValkeyResult, ValkeyString,
+    ValkeyValue,
+};
+
+/// Annotations are meant for short notes such as deploy markers, not for payloads.
+const MAX_ANNOTATION_LEN: usize = 1024;
+/// Annotations are kept in memory outside the chunks and are not compressed, so a series only
+/// holds a bounded number of them.
+const MAX_ANNOTATIONS_PER_SERIES: usize = 10_000;
+
+///
+/// TS.ANNOTATE key timestamp text
+///
+/// Attaches a note to a timestamp of the series, replacing any note already there. An empty
+/// `text` removes the note. The timestamp is in the unit of the series, and so is the one returned.
+pub fn ts_annotate_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() != 4 {
+        return Err(ValkeyError::WrongArity);
+    }
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_arg()?;
+    let timestamp_str = args.next_string()?;
+    let text = args.next_string()?;
+
+    if text.len() > MAX_ANNOTATION_LEN {
+        return Err(ValkeyError::Str(error_consts::ANNOTATION_TOO_LONG));
+    }
+
+    let timestamp = with_timeseries_mut(ctx, &key, Some(AclPermissions::UPDATE), |series| {
+        let timestamp = parse_series_timestamp(&timestamp_str, series.timestamp_unit)?;
+        if !series.retention.is_zero() && timestamp < series.get_min_timestamp() {
+            return Err(ValkeyError::Str(error_consts::ANNOTATION_TOO_OLD));
+        }
+        if !text.is_empty()
+            && series.annotations.len() >= MAX_ANNOTATIONS_PER_SERIES
+            && !series.annotations.contains_key(&timestamp)
+        {
+            return Err(ValkeyError::Str(error_consts::TOO_MANY_ANNOTATIONS));
+        }
+        series.annotate(timestamp, text.clone());
+        Ok(series.timestamp_unit.from_millis(timestamp))
+    })?;
+
+    // "*" could have a completely different value on a replica, so send the resolved value instead
+    let ts_str = ctx.create_string(timestamp.to_string().as_bytes());
+    let text = ctx.create_string(text.as_bytes());
+    ctx.replicate("TS.ANNOTATE", &[&key, &ts_str, &text][..]);
+    ctx.notify_keyspace_event(NotifyEvent::MODULE, "ts.annotate", &key);
+
+    Ok(ValkeyValue::Integer(timestamp))
+}
@@ -1,16 +1,26 @@
 use super::ts_card_fanout_command::CardFanoutCommand;
-use crate::commands::command_parser::parse_metadata_command_args;
+use crate::commands::DEFAULT_STATS_RESULTS_LIMIT;
+use crate::commands::command_parser::{CommandArgToken, parse_metadata_command_args, peek_token};
 use crate::fanout::{FanoutClientCommand, is_clustered};
-use crate::series::index::count_matched_series;
+use crate::series::index::{
+    LabelValueCardinality, PostingStat, count_matched_series, label_value_cardinality,
+    sort_posting_stats,
+};
+use std::collections::HashMap;
 use valkey_module::{Context, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
 
 ///
-/// TS.CARD [FILTER_BY_RANGE fromTimestamp toTimestamp] [FILTER filter...]
+/// TS.CARD [WITHLABELS] [FILTER_BY_RANGE fromTimestamp toTimestamp] [LIMIT limit] [FILTER filter...]
 ///
-/// returns the number of unique time series that match a certain label set.
+/// returns the number of unique time series that match a certain label set. With WITHLABELS,
+/// also returns the number of distinct values of each label among the matched series.
 pub fn ts_card_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let mut args = args.into_iter().skip(1).peekable();
-    let options = parse_metadata_command_args(&mut args, false)?;
+    let with_labels = peek_token(&mut args) == Some(CommandArgToken::WithLabels);
+    if with_labels {
+        args.next();
+    }
+    let options = parse_metadata_command_args(&mut args, with_labels)?;
 
     if is_clustered(ctx) {
         if options.matchers.is_empty() {
@@ -18,11 +28,46 @@ pub fn ts_card_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
                 "TS.CARD in cluster mode requires at least one matcher",
             ));
         }
-        let operation = CardFanoutCommand::new(options);
+        let operation = CardFanoutCommand::new(options, with_labels);
         return operation.exec(ctx);
     }
 
+    if with_labels {
+        let cardinality = label_value_cardinality(ctx, options.date_range, &options.matchers)?;
+        return Ok(label_cardinality_reply(cardinality, options.limit));
+    }
+
     let counter = count_matched_series(ctx, options.date_range, &options.matchers)?;
 
     Ok(ValkeyValue::from(counter))
 }
+
+/// Builds the WITHLABELS reply, keeping the `limit` labels with the most distinct values.
+pub(super) fn label_cardinality_reply(
+    cardinality: LabelValueCardinality,
+    limit: Option<usize>,
+) -> ValkeyValue {
+    // the number of distinct label names is small, so sort everything to break ties by name
+    let limit = limit.unwrap_or(DEFAULT_STATS_RESULTS_LIMIT);
+    let mut stats: Vec<PostingStat> = cardinality.value_count_by_label_name;
+    sort_posting_stats(&mut stats);
+    stats.truncate(limit);
+
+    let counts = stats
+        .into_iter()
+        .map(|stat| {
+            ValkeyValue::Array(vec![
+                ValkeyValue::BulkString(stat.name),
+                ValkeyValue::Integer(stat.count as i64),
+            ])
+        })
+        .collect();
+
+    let mut map = HashMap::with_capacity(2);
+    map.insert(
+        "totalSeries".into(),
+        ValkeyValue::Integer(cardinality.series_count as i64),
+    );
+    map.insert("valueCountByLabelName".into(), ValkeyValue::Array(counts));
+    ValkeyValue::Map(map)
+}
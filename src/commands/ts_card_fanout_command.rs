@@ -1,20 +1,40 @@
-use super::fanout::generated::{CardinalityRequest, CardinalityResponse};
+use super::fanout::generated::{
+    CardinalityRequest, CardinalityResponse, PostingStat as MPostingStat,
+};
+use super::ts_card::label_cardinality_reply;
 use crate::commands::fanout::filters::{deserialize_matchers_list, serialize_matchers_list};
 use crate::fanout::FanoutContext;
 use crate::fanout::{FanoutClientCommand, NodeInfo};
-use crate::series::index::count_matched_series;
+use crate::series::index::{
+    LabelValueCardinality, PostingStat, count_matched_series, label_value_cardinality,
+};
 use crate::series::request_types::{MatchFilterOptions, MetaDateRangeFilter};
+use ahash::AHashMap;
 use valkey_module::{Context, Status, ValkeyResult};
 
-#[derive(Default)]
 pub struct CardFanoutCommand {
     options: MatchFilterOptions,
+    with_labels: bool,
     result: usize,
+    /// Per-shard distinct value counts are summed, so a value present on several shards is
+    /// counted once per shard. The totals are therefore an upper bound in cluster mode.
+    value_counts: AHashMap<String, u64>,
+}
+
+impl Default for CardFanoutCommand {
+    fn default() -> Self {
+        Self::new(MatchFilterOptions::default(), false)
+    }
 }
 
 impl CardFanoutCommand {
-    pub fn new(options: MatchFilterOptions) -> Self {
-        Self { options, result: 0 }
+    pub fn new(options: MatchFilterOptions, with_labels: bool) -> Self {
+        Self {
+            options,
+            with_labels,
+            result: 0,
+            value_counts: AHashMap::new(),
+        }
     }
 }
 
@@ -32,8 +52,26 @@ impl FanoutClientCommand for CardFanoutCommand {
     ) -> ValkeyResult<CardinalityResponse> {
         let date_range: Option<MetaDateRangeFilter> = req.range.map(|r| r.into());
         let matchers = deserialize_matchers_list(Some(req.filters))?;
-        let count = count_matched_series(ctx, date_range, &matchers)? as u64;
-        Ok(CardinalityResponse { cardinality: count })
+        if !req.with_labels {
+            let count = count_matched_series(ctx, date_range, &matchers)? as u64;
+            return Ok(CardinalityResponse {
+                cardinality: count,
+                ..Default::default()
+            });
+        }
+
+        let cardinality = label_value_cardinality(ctx, date_range, &matchers)?;
+        Ok(CardinalityResponse {
+            cardinality: cardinality.series_count as u64,
+            value_count_by_label_name: cardinality
+                .value_count_by_label_name
+                .into_iter()
+                .map(|stat| MPostingStat {
+                    name: stat.name,
+                    count: stat.count,
+                })
+                .collect(),
+        })
     }
 
     fn generate_request(&self) -> CardinalityRequest {
@@ -41,14 +79,32 @@ impl FanoutClientCommand for CardFanoutCommand {
         CardinalityRequest {
             range: self.options.date_range.map(|r| r.into()),
             filters,
+            with_labels: self.with_labels,
         }
     }
 
     fn on_response(&mut self, resp: Self::Response, _target: &NodeInfo) {
         self.result += resp.cardinality as usize;
+        if self.with_labels {
+            for stat in resp.value_count_by_label_name {
+                *self.value_counts.entry(stat.name).or_default() += stat.count;
+            }
+        }
     }
 
     fn reply(&mut self, ctx: &FanoutContext) -> Status {
-        ctx.reply_with_i64(self.result as i64)
+        if !self.with_labels {
+            return ctx.reply_with_i64(self.result as i64);
+        }
+
+        let cardinality = LabelValueCardinality {
+            series_count: self.result,
+            value_count_by_label_name: self
+                .value_counts
+                .drain()
+                .map(|(name, count)| PostingStat { name, count })
+                .collect(),
+        };
+        ctx.reply(Ok(label_cardinality_reply(cardinality, self.options.limit)))
     }
 }
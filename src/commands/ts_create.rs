@@ -1,10 +1,12 @@
 use crate::commands::command_parser::{
     CommandArgToken, parse_chunk_compression, parse_chunk_size, parse_command_arg_token,
     parse_decimal_digit_rounding, parse_duplicate_policy, parse_ignore_options, parse_metric_name,
-    parse_retention, parse_significant_digit_rounding,
+    parse_retention, parse_retention_samples, parse_rounding, parse_significant_digit_rounding,
+    parse_split_policy, parse_timestamp_unit,
 };
 use crate::error_consts;
 use crate::labels::Label;
+use crate::series::chunks::ChunkEncoding;
 use crate::series::{DuplicatePolicy, TimeSeriesOptions, create_and_store_series};
 use valkey_module::{Context, NextArg, VALKEY_OK, ValkeyError, ValkeyResult, ValkeyString};
 
@@ -13,10 +15,13 @@ use valkey_module::{Context, NextArg, VALKEY_OK, ValkeyError, ValkeyResult, Valk
 /// TS.CREATE key
 ///   [METRIC metric]
 ///   [RETENTION retentionPeriod]
-///   [ENCODING <pco|gorilla|uncompressed|compressed>]
+///   [RETENTION_SAMPLES maxSamples]
+///   [ENCODING <pco|gorilla|uncompressed|compressed|auto>]
 ///   [CHUNK_SIZE chunkSize]
+///   [SPLIT_POLICY <HALF|INSERTION|percent>]
+///   [TIMESTAMP_UNIT <s|ms|us|ns>]
 ///   [DUPLICATE_POLICY duplicatePolicy]
-///   [SIGNIFICANT_DIGITS significantDigits | DECIMAL_DIGITS decimalDigits]
+///   [SIGNIFICANT_DIGITS significantDigits | DECIMAL_DIGITS decimalDigits | ROUNDING MULTIPLE step]
 ///   [IGNORE ignoreMaxTimediff ignoreMaxValDiff]
 ///   [LABELS label1=value1 label2=value2 ...]
 pub fn ts_create_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
@@ -52,10 +57,26 @@ pub fn parse_series_options(
     args: Vec<ValkeyString>,
     args_to_skip: usize,
     invalid_args: &[CommandArgToken],
+) -> ValkeyResult<TimeSeriesOptions> {
+    parse_series_options_with_defaults(
+        TimeSeriesOptions::from_config(),
+        args,
+        args_to_skip,
+        invalid_args,
+    )
+}
+
+/// Like `parse_series_options`, but options missing from `args` keep their value in `defaults`
+/// rather than the configured one.
+pub fn parse_series_options_with_defaults(
+    defaults: TimeSeriesOptions,
+    args: Vec<ValkeyString>,
+    args_to_skip: usize,
+    invalid_args: &[CommandArgToken],
 ) -> ValkeyResult<TimeSeriesOptions> {
     let mut metric_set = false;
 
-    let mut options = TimeSeriesOptions::from_config();
+    let mut options = defaults;
 
     // Labels are variadic, so we handle them first to make parsing easier.
     let pos = args.iter().rposition(|x| x.eq_ignore_ascii_case(b"labels"));
@@ -93,7 +114,18 @@ pub fn parse_series_options(
                 options.chunk_size = Some(parse_chunk_size(arg)?)
             }
             CommandArgToken::Encoding => {
-                options.chunk_compression = parse_chunk_compression(&mut args_iter)?;
+                if args_iter
+                    .peek()
+                    .is_some_and(|arg| arg.eq_ignore_ascii_case(b"auto"))
+                {
+                    args_iter.next();
+                    // start uncompressed until the first chunk shows what the data looks like
+                    options.chunk_compression = ChunkEncoding::Uncompressed;
+                    options.auto_encoding = true;
+                } else {
+                    options.chunk_compression = parse_chunk_compression(&mut args_iter)?;
+                    options.auto_encoding = false;
+                }
             }
             CommandArgToken::DecimalDigits => {
                 if options.rounding.is_some() {
@@ -130,12 +162,27 @@ pub fn parse_series_options(
                 options.sample_duplicate_policy = Some(ignore_options);
             }
             CommandArgToken::Retention => options.retention(parse_retention(&mut args_iter)?),
+            CommandArgToken::RetentionSamples => {
+                options.max_samples = Some(parse_retention_samples(&mut args_iter)?);
+            }
+            CommandArgToken::Rounding => {
+                if options.rounding.is_some() {
+                    return Err(ValkeyError::Str(error_consts::ROUNDING_ALREADY_SET));
+                }
+                options.rounding = Some(parse_rounding(&mut args_iter)?);
+            }
             CommandArgToken::SignificantDigits => {
                 if options.rounding.is_some() {
                     return Err(ValkeyError::Str(error_consts::ROUNDING_ALREADY_SET));
                 }
                 options.rounding = Some(parse_significant_digit_rounding(&mut args_iter)?);
             }
+            CommandArgToken::SplitPolicy => {
+                options.split_policy = Some(parse_split_policy(&mut args_iter)?);
+            }
+            CommandArgToken::TimestampUnit => {
+                options.timestamp_unit = Some(parse_timestamp_unit(&mut args_iter)?);
+            }
             _ => {
                 return Err(ValkeyError::Str(error_consts::INVALID_ARGUMENT));
             }
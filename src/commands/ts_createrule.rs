@@ -1,5 +1,5 @@
 use crate::aggregators::{AggregationType, Aggregator};
-use crate::commands::command_parser::parse_aggregator_value_filter;
+use crate::commands::command_parser::{parse_aggregation_param, parse_aggregator_value_filter};
 use crate::commands::{CommandArgIterator, CommandArgToken, parse_duration};
 use crate::error_consts;
 use crate::parser::timestamp::parse_timestamp;
@@ -100,6 +100,7 @@ fn parse_args(args: &mut CommandArgIterator, dest_id: SeriesRef) -> ValkeyResult
     }
 
     let aggregation_type = AggregationType::try_from(args.next_str()?)?;
+    let param = parse_aggregation_param(args, aggregation_type)?;
     let duration_str = args
         .next_str()
         .map_err(|_| ValkeyError::Str("TSDB: missing bucket duration"))?;
@@ -120,7 +121,10 @@ fn parse_args(args: &mut CommandArgIterator, dest_id: SeriesRef) -> ValkeyResult
 
     let bucket_duration = duration.as_millis() as u64;
     // Configure the aggregator with the possible value filter
-    let aggr_config = AggregatorConfig::new(aggregation_type, value_filter)?;
+    let mut aggr_config = AggregatorConfig::new(aggregation_type, value_filter)?;
+    if let Some(param) = param {
+        aggr_config = aggr_config.with_param(param)?;
+    }
     let mut aggregator = aggr_config.create_aggregator();
 
     // if we're a Rate aggregator, we need to set the bucket duration
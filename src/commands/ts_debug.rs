@@ -32,14 +32,17 @@ fn dump_bucket(ctx: &Context, bucket: &BucketStats) {
 fn dump_top_k_entry(ctx: &Context, entry: &TopKEntry) {
     reply_with_array(ctx, 8);
 
+    // label pairs are interned with the terminator of the index keys, which is not part of the value
+    let value = entry.value.trim_end_matches('\0');
+
     reply_with_str(ctx, "value");
-    reply_with_bulk_string(ctx, &entry.value);
+    reply_with_bulk_string(ctx, value);
 
     reply_with_str(ctx, "refCount");
     reply_with_usize(ctx, entry.ref_count);
 
     reply_with_str(ctx, "bytes");
-    reply_with_usize(ctx, entry.bytes);
+    reply_with_usize(ctx, value.len());
 
     reply_with_str(ctx, "allocated");
     reply_with_usize(ctx, entry.allocated);
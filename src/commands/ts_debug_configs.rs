@@ -8,9 +8,10 @@ use crate::config::{
     DECIMAL_DIGITS_DEFAULT, DECIMAL_DIGITS_MAX, DEFAULT_CHUNK_SIZE_BYTES, DEFAULT_RETENTION_PERIOD,
     DEFAULT_THREADS, FANOUT_COMMAND_TIMEOUT_DEFAULT, FANOUT_COMMAND_TIMEOUT_MAX,
     FANOUT_COMMAND_TIMEOUT_MIN, IGNORE_MAX_TIME_DIFF_DEFAULT, IGNORE_MAX_TIME_DIFF_MAX,
-    IGNORE_MAX_TIME_DIFF_MIN, IGNORE_MAX_VALUE_DIFF_MAX, IGNORE_MAX_VALUE_DIFF_MIN, MAX_THREADS,
-    MIN_THREADS, RETENTION_POLICY_MAX, RETENTION_POLICY_MIN, SIGNIFICANT_DIGITS_DEFAULT,
-    SIGNIFICANT_DIGITS_MAX, get_config,
+    IGNORE_MAX_TIME_DIFF_MIN, IGNORE_MAX_VALUE_DIFF_MAX, IGNORE_MAX_VALUE_DIFF_MIN,
+    MAX_FUTURE_SKEW_DEFAULT, MAX_FUTURE_SKEW_MAX, MAX_FUTURE_SKEW_MIN, MAX_SERIES_DEFAULT,
+    MAX_SERIES_MAX, MAX_SERIES_MIN, MAX_THREADS, MIN_THREADS, RETENTION_POLICY_MAX,
+    RETENTION_POLICY_MIN, SIGNIFICANT_DIGITS_DEFAULT, SIGNIFICANT_DIGITS_MAX, get_config,
 };
 use std::convert::Into;
 use std::fmt::Display;
@@ -220,6 +221,14 @@ fn get_registry() -> Vec<ConfigMeta> {
             max: Some(IGNORE_MAX_VALUE_DIFF_MAX.into()),
             description: "Max value delta for which a duplicate sample is ignored",
         },
+        ConfigMeta {
+            name: "ts-max-future-skew",
+            config_type: ConfigType::Duration,
+            default: ConfigValue::from_duration_ms(MAX_FUTURE_SKEW_DEFAULT),
+            min: Some(ConfigValue::from_duration_ms(MAX_FUTURE_SKEW_MIN)),
+            max: Some(ConfigValue::from_duration_ms(MAX_FUTURE_SKEW_MAX)),
+            description: "How far past the current time sample timestamps may be (0 = no limit)",
+        },
         ConfigMeta {
             name: "ts-num-threads",
             config_type: ConfigType::Integer,
@@ -228,6 +237,14 @@ fn get_registry() -> Vec<ConfigMeta> {
             max: Some(MAX_THREADS.into()),
             description: "Number of worker threads for parallel query processing",
         },
+        ConfigMeta {
+            name: "ts-max-series",
+            config_type: ConfigType::Integer,
+            default: MAX_SERIES_DEFAULT.into(),
+            min: Some(MAX_SERIES_MIN.into()),
+            max: Some(MAX_SERIES_MAX.into()),
+            description: "Maximum number of series per db (0 = no limit)",
+        },
         ConfigMeta {
             name: "ts-fanout-command-timeout",
             config_type: ConfigType::Duration,
@@ -300,7 +317,15 @@ fn reply_with_config_value(ctx: &Context, cfg: &ConfigMeta, settings: &ConfigSet
             let max_value = settings.duplicate_policy.max_value_delta;
             let _ = reply_with_double(ctx, max_value);
         },
+        "ts-max-future-skew" => {
+            if let Some(skew) = settings.max_future_skew {
+                let _ = reply_with_duration(ctx, skew);
+            } else {
+                let _ = reply_with_bulk_string(ctx, "none");
+            }
+        },
         "ts-num-threads" => { let _ = reply_with_usize(ctx, settings.num_threads); },
+        "ts-max-series" => { let _ = reply_with_usize(ctx, settings.max_series); },
         "ts-fanout-command-timeout" => { let _ = reply_with_duration(ctx, settings.fanout_command_timeout); },
         "ts-cluster-map-expiration-ms" => { let _ = reply_with_duration(ctx, settings.cluster_map_expiration); },
          _ => { let _ = reply_with_str(ctx, "<unknown>"); }
@@ -1,26 +1,48 @@
-use crate::commands::command_parser::parse_timestamp_range;
-use crate::series::with_timeseries_mut;
+use crate::commands::command_parser::{
+    CommandArgToken, parse_command_arg_token, parse_series_timestamp_bounds,
+};
+use crate::common::Timestamp;
+use crate::error_consts;
+use crate::series::{TimeSeries, get_timeseries, with_timeseries_mut};
 use valkey_module::{
     AclPermissions, Context, NextArg, NotifyEvent, ValkeyError, ValkeyResult, ValkeyString,
     ValkeyValue,
 };
 
 ///
-/// TS.DEL key fromTimestamp toTimestamp
+/// TS.DEL key fromTimestamp toTimestamp [DRYRUN]
 ///
+/// The timestamps are in the unit of the series.
 pub fn ts_del_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let mut args = args.into_iter().skip(1).peekable();
     let key = args.next_arg()?;
 
-    let date_range = parse_timestamp_range(&mut args)?;
-    let count = with_timeseries_mut(ctx, &key, Some(AclPermissions::DELETE), |series| {
-        let (start_ts, end_ts) = date_range.get_series_range(series, None, false);
+    // parsed once the series is known, since the timestamps are given in its unit
+    let start_arg = args.next_str()?;
+    let end_arg = args.next_str().ok();
 
-        if series.is_older_than_retention(start_ts) {
-            return Err(ValkeyError::String(
-                "TSDB: cannot delete samples older than retention".to_string(),
-            ));
+    let dry_run = match args.next() {
+        None => false,
+        Some(arg) if parse_command_arg_token(arg.as_slice()) == Some(CommandArgToken::DryRun) => {
+            true
         }
+        Some(_) => return Err(ValkeyError::Str(error_consts::INVALID_ARGUMENT)),
+    };
+    if args.next().is_some() {
+        return Err(ValkeyError::WrongArity);
+    }
+
+    // a dry run reports the count the delete below would return, without modifying the series
+    if dry_run {
+        let Some(series) = get_timeseries(ctx, &key, Some(AclPermissions::ACCESS), true)? else {
+            return Err(ValkeyError::Str(error_consts::KEY_NOT_FOUND));
+        };
+        let (start_ts, end_ts) = get_delete_range(&series, start_arg, end_arg)?;
+        return Ok(ValkeyValue::from(series.count_range(start_ts, end_ts)));
+    }
+
+    let count = with_timeseries_mut(ctx, &key, Some(AclPermissions::DELETE), |series| {
+        let (start_ts, end_ts) = get_delete_range(series, start_arg, end_arg)?;
 
         series
             .remove_range_with_compaction(ctx, start_ts, end_ts)
@@ -33,3 +55,20 @@ pub fn ts_del_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
 
     Ok(ValkeyValue::from(count))
 }
+
+fn get_delete_range(
+    series: &TimeSeries,
+    start_arg: &str,
+    end_arg: Option<&str>,
+) -> ValkeyResult<(Timestamp, Timestamp)> {
+    let date_range = parse_series_timestamp_bounds(start_arg, end_arg, series.timestamp_unit)?;
+    let (start_ts, end_ts) = date_range.get_series_range(series, None, false);
+
+    if series.is_older_than_retention(start_ts) {
+        return Err(ValkeyError::String(
+            "TSDB: cannot delete samples older than retention".to_string(),
+        ));
+    }
+
+    Ok((start_ts, end_ts))
+}
@@ -0,0 +1,114 @@
+use crate::commands::command_parser::{
+    parse_series_selector_list, parse_series_timestamp_bounds, peek_token,
+};
+use crate::commands::{CommandArgToken, parse_integer_arg};
+use crate::common::replies::{
+    reply_set_array_length, reply_with_array, reply_with_labels, reply_with_postponed_array,
+    reply_with_samples,
+};
+use crate::error_consts;
+use crate::labels::Label;
+use crate::series::TimestampUnit;
+use crate::series::acl::check_metadata_permissions;
+use crate::series::index::series_by_selectors;
+use valkey_module::{Context, NextArg, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
+
+/// Default number of samples per frame in TS.EXPORT replies.
+const DEFAULT_EXPORT_BATCH_SIZE: usize = 1000;
+const MAX_EXPORT_BATCH_SIZE: usize = 100_000;
+
+/// TS.EXPORT fromTimestamp toTimestamp
+//   [BATCH_SIZE size]
+//   FILTER selector...
+//
+// Emits each matching series as a (labels, frames) pair in a shape that maps directly onto a
+// Prometheus remote-read response. Series are ordered by their sorted label set, and samples
+// are returned in ascending order, split into frames of at most BATCH_SIZE samples. Timestamps
+// are in the unit of each series.
+pub fn ts_export_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() < 5 {
+        return Err(ValkeyError::WrongArity);
+    }
+    let mut args = args.into_iter().skip(1).peekable();
+
+    // parsed again in the unit of each series once they are known
+    let start_arg = args.next_str()?;
+    let end_arg = args.next_str().ok();
+    parse_series_timestamp_bounds(start_arg, end_arg, TimestampUnit::Milliseconds)?;
+    let mut batch_size = DEFAULT_EXPORT_BATCH_SIZE;
+
+    loop {
+        let token = peek_token(&mut args);
+        args.next();
+        match token {
+            Some(CommandArgToken::BatchSize) => {
+                let arg = args.next_arg()?;
+                let value = parse_integer_arg(&arg, "BATCH_SIZE", false)
+                    .map_err(|_| ValkeyError::Str(error_consts::INVALID_BATCH_SIZE))?
+                    as usize;
+                if value == 0 || value > MAX_EXPORT_BATCH_SIZE {
+                    return Err(ValkeyError::Str(error_consts::INVALID_BATCH_SIZE));
+                }
+                batch_size = value;
+            }
+            Some(CommandArgToken::Filter) => break,
+            _ => return Err(ValkeyError::Str(error_consts::MISSING_FILTER)),
+        }
+    }
+
+    let selectors = parse_series_selector_list(&mut args, &[])?;
+
+    check_metadata_permissions(ctx)?;
+
+    let series_guards = series_by_selectors(ctx, &selectors, None)?;
+
+    let mut items: Vec<(Vec<Label>, usize)> = series_guards
+        .iter()
+        .enumerate()
+        .map(|(i, (guard, _key))| {
+            let mut labels = guard.labels.to_label_vec();
+            labels.sort_by(|a, b| a.name.cmp(&b.name));
+            (labels, i)
+        })
+        .collect();
+
+    // resolved before replying, so that a bound which does not fit a series' unit is an error
+    let ranges = series_guards
+        .iter()
+        .map(|(guard, _key)| {
+            parse_series_timestamp_bounds(start_arg, end_arg, guard.timestamp_unit)
+                .map(|range| range.get_timestamps(None))
+        })
+        .collect::<ValkeyResult<Vec<_>>>()?;
+
+    items.sort_by(|(a, _), (b, _)| {
+        a.iter()
+            .map(|l| (&l.name, &l.value))
+            .cmp(b.iter().map(|l| (&l.name, &l.value)))
+    });
+
+    reply_with_array(ctx, items.len());
+    for (labels, index) in items {
+        let (series, _) = &series_guards[index];
+
+        reply_with_array(ctx, 2);
+        reply_with_labels(ctx, &labels);
+
+        // Frames are written straight from the series iterator so that a large series is never
+        // materialized as a single buffer.
+        reply_with_postponed_array(ctx);
+        let mut frames = 0;
+        let (start_ts, end_ts) = ranges[index];
+        let mut iter = series
+            .range_iter(start_ts, end_ts)
+            .map(|sample| series.to_client_sample(sample))
+            .peekable();
+        while iter.peek().is_some() {
+            reply_with_samples(ctx, iter.by_ref().take(batch_size));
+            frames += 1;
+        }
+        reply_set_array_length(ctx, frames);
+    }
+
+    Ok(ValkeyValue::NoReply)
+}
@@ -0,0 +1,56 @@
+use crate::commands::command_parser::{CommandArgToken, parse_command_arg_token, parse_count_arg};
+use crate::error_consts;
+use crate::series::index::{IndexGcStats, get_timeseries_index};
+use std::collections::HashMap;
+use valkey_module::{Context, NextArg, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
+
+/// Default number of index entries processed per batch (i.e. per write lock acquisition).
+const DEFAULT_GC_BATCH_SIZE: usize = 500;
+
+///
+/// TS.GC [COUNT count]
+///
+/// Purges stale series ids from the index of the current database and optimizes all postings
+/// bitmaps, processing at most `count` index entries per batch. The command runs to completion on
+/// the main thread, so other commands wait for it; batching only bounds how long the index write
+/// lock is held at a time.
+pub fn ts_gc_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1).peekable();
+
+    let mut batch_size = DEFAULT_GC_BATCH_SIZE;
+    if let Some(arg) = args.next() {
+        match parse_command_arg_token(arg.as_slice()) {
+            Some(CommandArgToken::Count) => {
+                batch_size = parse_count_arg(&mut args)?;
+                if batch_size == 0 {
+                    return Err(ValkeyError::Str(error_consts::INVALID_GC_BATCH_SIZE));
+                }
+            }
+            _ => return Err(ValkeyError::Str(error_consts::INVALID_ARGUMENT)),
+        }
+    }
+    args.done()?;
+
+    let index = get_timeseries_index(ctx);
+    let stats = index.collect_garbage(batch_size);
+
+    Ok(gc_stats_reply(&stats))
+}
+
+fn gc_stats_reply(stats: &IndexGcStats) -> ValkeyValue {
+    let mut map = HashMap::with_capacity(4);
+    map.insert(
+        "staleIdsRemoved".into(),
+        ValkeyValue::Integer(stats.stale_ids_removed as i64),
+    );
+    map.insert(
+        "emptyPostingsRemoved".into(),
+        ValkeyValue::Integer(stats.empty_postings_removed as i64),
+    );
+    map.insert(
+        "bitmapsOptimized".into(),
+        ValkeyValue::Integer(stats.bitmaps_optimized as i64),
+    );
+    map.insert("batches".into(), ValkeyValue::Integer(stats.batches as i64));
+    ValkeyValue::Map(map)
+}
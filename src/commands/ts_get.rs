@@ -1,13 +1,31 @@
-use crate::series::{get_latest_compaction_sample, with_timeseries};
+use crate::commands::command_parser::parse_series_timestamp;
+use crate::error_consts;
+use crate::fanout::is_clustered;
+use crate::series::index::get_series_key_by_id;
+use crate::series::{
+    SampleLookupMode, SeriesRef, get_latest_compaction_sample, get_timeseries, with_timeseries,
+};
 use valkey_module::ValkeyError::WrongArity;
-use valkey_module::{Context, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
+use valkey_module::{
+    AclPermissions, Context, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
+};
 
 /// TS.GET key [LATEST]
+/// TS.GET key AT timestamp [PREVIOUS | NEXT | NEAREST | LINEAR]
+/// TS.GET BYID id [LATEST]
 pub fn ts_get_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
-    if args.len() < 2 || args.len() > 3 {
+    if args.len() < 2 || args.len() > 5 {
         return Err(WrongArity);
     }
 
+    if is_by_id(&args) {
+        return ts_get_by_id(ctx, &args);
+    }
+
+    if args.len() > 3 {
+        return ts_get_at(ctx, &args);
+    }
+
     let latest = args
         .get(2)
         .map(|arg| arg.eq_ignore_ascii_case("latest".as_ref()))
@@ -19,12 +37,101 @@ pub fn ts_get_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
 
     let key = &args[1];
     let sample = with_timeseries(ctx, key, true, |series| {
-        if latest && let Some(value) = get_latest_compaction_sample(ctx, series) {
-            Ok(Some(value))
+        let sample = if latest && let Some(value) = get_latest_compaction_sample(ctx, series) {
+            Some(value)
         } else {
-            Ok(series.last_sample)
-        }
+            series.last_sample
+        };
+        Ok(sample.map(|sample| series.to_client_sample(sample)))
+    })?;
+
+    Ok(sample.map_or_else(|| ValkeyValue::Array(vec![]), Into::into))
+}
+
+/// Handles the `AT timestamp [mode]` form, returning the sample selected (or interpolated) for an
+/// arbitrary timestamp. The mode defaults to PREVIOUS.
+fn ts_get_at(ctx: &Context, args: &[ValkeyString]) -> ValkeyResult {
+    if !args[2].eq_ignore_ascii_case("at".as_ref()) {
+        return Err(WrongArity);
+    }
+
+    let timestamp = args[3].try_as_str()?;
+    let mode = match args.get(4) {
+        Some(arg) => parse_lookup_mode(arg)?,
+        None => SampleLookupMode::Previous,
+    };
+
+    let key = &args[1];
+    let sample = with_timeseries(ctx, key, true, |series| {
+        let timestamp = parse_series_timestamp(timestamp, series.timestamp_unit)?;
+        let sample = series.sample_at(timestamp, mode);
+        Ok(sample.map(|sample| series.to_client_sample(sample)))
     })?;
 
     Ok(sample.map_or_else(|| ValkeyValue::Array(vec![]), Into::into))
 }
+
+/// `TS.GET BYID ...` is told apart from a series named `BYID` by its third argument, which is an
+/// id rather than `LATEST` or `AT`.
+fn is_by_id(args: &[ValkeyString]) -> bool {
+    args.len() >= 3
+        && args[1].eq_ignore_ascii_case("byid".as_ref())
+        && !args[2].eq_ignore_ascii_case("latest".as_ref())
+        && !args[2].eq_ignore_ascii_case("at".as_ref())
+}
+
+/// Handles the `BYID id [LATEST]` form, returning `[key, sample]` for the series with the given
+/// internal id, or nil if no series has that id.
+fn ts_get_by_id(ctx: &Context, args: &[ValkeyString]) -> ValkeyResult {
+    if args.len() > 4 {
+        return Err(WrongArity);
+    }
+    if is_clustered(ctx) {
+        return Err(ValkeyError::Str(
+            error_consts::BYID_NOT_SUPPORTED_IN_CLUSTER,
+        ));
+    }
+
+    let id = args[2]
+        .parse_unsigned_integer()
+        .map_err(|_| ValkeyError::Str(error_consts::INVALID_SERIES_ID))? as SeriesRef;
+    let latest = match args.get(3) {
+        Some(arg) if arg.eq_ignore_ascii_case("latest".as_ref()) => true,
+        Some(_) => return Err(ValkeyError::Str("TSDB: wrong 4th argument")),
+        None => false,
+    };
+
+    let Some(key) = get_series_key_by_id(ctx, id) else {
+        return Ok(ValkeyValue::Null);
+    };
+    // the id may be stale: its key was deleted, or now holds a different series
+    let Some(series) = get_timeseries(ctx, &key, Some(AclPermissions::ACCESS), false)? else {
+        return Ok(ValkeyValue::Null);
+    };
+    if series.id != id {
+        return Ok(ValkeyValue::Null);
+    }
+
+    let sample = if latest {
+        get_latest_compaction_sample(ctx, &series).or(series.last_sample)
+    } else {
+        series.last_sample
+    };
+    let sample = sample.map_or_else(
+        || ValkeyValue::Array(vec![]),
+        |sample| series.to_client_sample(sample).into(),
+    );
+
+    Ok(ValkeyValue::Array(vec![ValkeyValue::from(key), sample]))
+}
+
+fn parse_lookup_mode(arg: &ValkeyString) -> ValkeyResult<SampleLookupMode> {
+    let mode = hashify::tiny_map_ignore_case! {
+        arg.as_slice(),
+        "previous" => SampleLookupMode::Previous,
+        "next" => SampleLookupMode::Next,
+        "nearest" => SampleLookupMode::Nearest,
+        "linear" => SampleLookupMode::Linear,
+    };
+    mode.ok_or(ValkeyError::Str(error_consts::INVALID_LOOKUP_MODE))
+}
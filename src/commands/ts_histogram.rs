@@ -0,0 +1,106 @@
+use crate::analysis::histogram::{HistogramBin, boundary_histogram, equal_width_histogram};
+use crate::commands::{
+    CommandArgIterator, CommandArgToken, parse_command_arg_token, parse_integer_arg,
+    parse_number_arg, parse_series_timestamp_bounds,
+};
+use crate::error_consts;
+use crate::series::get_timeseries;
+use valkey_module::{
+    AclPermissions, Context, NextArg, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
+};
+
+const MAX_HISTOGRAM_BINS: usize = 1000;
+
+enum Binning {
+    Buckets(usize),
+    Boundaries(Vec<f64>),
+}
+
+/// TS.HISTOGRAM key fromTimestamp toTimestamp
+///     BUCKETS n | BOUNDARIES b1 [b2 ...]
+///
+/// Returns a histogram of the sample values in the range as an array of `[lowerBound, count]`.
+/// The timestamps are in the unit of the series.
+pub fn ts_histogram_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() < 6 {
+        return Err(ValkeyError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1).peekable();
+
+    let key = args.next_arg()?;
+    // parsed once the series is known, since the timestamps are given in its unit
+    let start_arg = args.next_str()?;
+    let end_arg = args.next_str().ok();
+
+    let mut binning: Option<Binning> = None;
+    while let Some(arg) = args.next() {
+        let token = parse_command_arg_token(arg.as_slice()).unwrap_or_default();
+        let parsed = match token {
+            CommandArgToken::Buckets => {
+                let arg = args.next_arg()?;
+                let buckets = parse_integer_arg(&arg, "BUCKETS", false)
+                    .map_err(|_| ValkeyError::Str(error_consts::INVALID_HISTOGRAM_BUCKETS))?
+                    as usize;
+                if buckets == 0 || buckets > MAX_HISTOGRAM_BINS {
+                    return Err(ValkeyError::Str(error_consts::INVALID_HISTOGRAM_BUCKETS));
+                }
+                Binning::Buckets(buckets)
+            }
+            CommandArgToken::Boundaries => Binning::Boundaries(parse_boundaries(&mut args)?),
+            _ => {
+                return Err(ValkeyError::String(format!(
+                    "TSDB: unknown option HISTOGRAM {arg}"
+                )));
+            }
+        };
+        if binning.replace(parsed).is_some() {
+            return Err(ValkeyError::Str(error_consts::HISTOGRAM_BINNING_CONFLICT));
+        }
+    }
+
+    let Some(binning) = binning else {
+        return Err(ValkeyError::Str(error_consts::MISSING_HISTOGRAM_BINNING));
+    };
+
+    let series = get_timeseries(ctx, &key, Some(AclPermissions::ACCESS), true)?.unwrap();
+    let date_range = parse_series_timestamp_bounds(start_arg, end_arg, series.timestamp_unit)?;
+    let (start, end) = date_range.get_series_range(&series, None, false);
+    let values: Vec<f64> = series.range_iter(start, end).map(|s| s.value).collect();
+
+    let bins = match binning {
+        Binning::Buckets(buckets) => equal_width_histogram(&values, buckets),
+        Binning::Boundaries(boundaries) => boundary_histogram(&values, &boundaries),
+    };
+
+    Ok(ValkeyValue::Array(
+        bins.into_iter().map(bin_to_value).collect(),
+    ))
+}
+
+fn parse_boundaries(args: &mut CommandArgIterator) -> ValkeyResult<Vec<f64>> {
+    let mut boundaries: Vec<f64> = Vec::new();
+    while let Some(arg) = args.peek() {
+        if parse_command_arg_token(arg.as_slice()).is_some() {
+            break;
+        }
+        let value = parse_number_arg(arg, "BOUNDARIES")
+            .map_err(|_| ValkeyError::Str(error_consts::INVALID_HISTOGRAM_BOUNDARIES))?;
+        if value.is_nan() || boundaries.last().is_some_and(|last| *last >= value) {
+            return Err(ValkeyError::Str(error_consts::INVALID_HISTOGRAM_BOUNDARIES));
+        }
+        boundaries.push(value);
+        args.next();
+    }
+    if boundaries.is_empty() || boundaries.len() >= MAX_HISTOGRAM_BINS {
+        return Err(ValkeyError::Str(error_consts::INVALID_HISTOGRAM_BOUNDARIES));
+    }
+    Ok(boundaries)
+}
+
+fn bin_to_value(bin: HistogramBin) -> ValkeyValue {
+    ValkeyValue::Array(vec![
+        ValkeyValue::Float(bin.lower_bound),
+        ValkeyValue::Integer(bin.count as i64),
+    ])
+}
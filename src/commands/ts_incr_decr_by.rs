@@ -1,9 +1,15 @@
 use crate::commands::CommandArgToken;
-use crate::commands::command_parser::{parse_timestamp, parse_value_arg};
+use crate::commands::command_parser::{
+    parse_duration_arg, parse_series_timestamp, parse_value_arg,
+};
 use crate::commands::ts_create::parse_series_options;
 use crate::common::Timestamp;
 use crate::error_consts;
-use crate::series::{SampleAddResult, TimeSeries, create_and_store_series, get_timeseries_mut};
+use crate::series::{
+    SampleAddResult, TimeSeries, TimestampUnit, check_future_timestamp, create_and_store_series,
+    get_max_future_timestamp, get_timeseries_mut,
+};
+use std::time::Duration;
 use valkey_module::{
     AclPermissions, Context, NotifyEvent, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
 };
@@ -16,6 +22,9 @@ pub fn ts_decrby_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     incr_decr(ctx, args, false)
 }
 
+/// TS.INCRBY/TS.DECRBY key value [TIMESTAMP timestamp] [BUCKET_DURATION duration] [series options]
+///
+/// The timestamp is in the unit of the series, and so is the one returned.
 fn incr_decr(ctx: &Context, args: Vec<ValkeyString>, is_increment: bool) -> ValkeyResult {
     if args.len() < 3 {
         return Err(ValkeyError::WrongArity);
@@ -23,7 +32,9 @@ fn incr_decr(ctx: &Context, args: Vec<ValkeyString>, is_increment: bool) -> Valk
 
     let mut args = args;
     let delta = parse_value_arg(&args[2])?;
-    let timestamp = handle_parse_timestamp(&mut args)?;
+    // parsed once the series is known, since it is given in its unit
+    let timestamp_arg = handle_parse_timestamp(&mut args)?;
+    let bucket_duration = handle_parse_bucket_duration(&mut args)?;
     let key_name = &args[1];
 
     if let Some(mut series) = get_timeseries_mut(
@@ -32,38 +43,67 @@ fn incr_decr(ctx: &Context, args: Vec<ValkeyString>, is_increment: bool) -> Valk
         false,
         Some(AclPermissions::UPDATE | AclPermissions::ACCESS),
     )? {
-        handle_update(ctx, &mut series, key_name, timestamp, delta, is_increment)
+        let timestamp = parse_timestamp(ctx, timestamp_arg.as_deref(), series.timestamp_unit)?;
+        handle_update(
+            ctx,
+            &mut series,
+            key_name,
+            timestamp,
+            delta,
+            bucket_duration,
+            is_increment,
+        )
     } else {
-        create_series_and_update(ctx, args, timestamp, delta, is_increment)
+        create_series_and_update(
+            ctx,
+            args,
+            timestamp_arg.as_deref(),
+            delta,
+            bucket_duration,
+            is_increment,
+        )
     }
 }
 
 fn create_series_and_update(
     ctx: &Context,
     mut args: Vec<ValkeyString>,
-    timestamp: Option<Timestamp>,
+    timestamp_arg: Option<&str>,
     delta: f64,
+    bucket_duration: Option<Duration>,
     is_increment: bool,
 ) -> ValkeyResult {
     let key_name = args.remove(1);
     const INVALID_ARGS: &[CommandArgToken] = &[CommandArgToken::OnDuplicate];
 
     let options = parse_series_options(args, 2, INVALID_ARGS)?;
+    // parsed before the series is created, so that an invalid timestamp leaves no empty series
+    let timestamp = parse_timestamp(
+        ctx,
+        timestamp_arg,
+        options.timestamp_unit.unwrap_or_default(),
+    )?;
     let mut series = create_and_store_series(ctx, &key_name, options, true, true)?;
 
-    handle_update(ctx, &mut series, &key_name, timestamp, delta, is_increment)
+    handle_update(
+        ctx,
+        &mut series,
+        &key_name,
+        timestamp,
+        delta,
+        bucket_duration,
+        is_increment,
+    )
 }
 
-fn handle_parse_timestamp(args: &mut Vec<ValkeyString>) -> ValkeyResult<Option<Timestamp>> {
+fn handle_parse_timestamp(args: &mut Vec<ValkeyString>) -> ValkeyResult<Option<String>> {
     if let Some(index) = args
         .iter()
         .position(|x| x.eq_ignore_ascii_case(b"timestamp"))
     {
         return if index < args.len() - 1 {
             args.remove(index);
-            let timestamp_str = args.remove(index).to_string_lossy();
-            let value = parse_timestamp(&timestamp_str)?;
-            Ok(Some(value))
+            Ok(Some(args.remove(index).to_string_lossy()))
         } else {
             Err(ValkeyError::Str("TSDB: missing timestamp value"))
         };
@@ -71,24 +111,63 @@ fn handle_parse_timestamp(args: &mut Vec<ValkeyString>) -> ValkeyResult<Option<T
     Ok(None)
 }
 
+/// Parses the TIMESTAMP argument, if any, in the series' `unit`.
+fn parse_timestamp(
+    ctx: &Context,
+    timestamp_arg: Option<&str>,
+    unit: TimestampUnit,
+) -> ValkeyResult<Option<Timestamp>> {
+    let Some(arg) = timestamp_arg else {
+        return Ok(None);
+    };
+    let timestamp = parse_series_timestamp(arg, unit)?;
+    check_future_timestamp(timestamp, get_max_future_timestamp(ctx))?;
+    Ok(Some(timestamp))
+}
+
+fn handle_parse_bucket_duration(args: &mut Vec<ValkeyString>) -> ValkeyResult<Option<Duration>> {
+    if let Some(index) = args
+        .iter()
+        .position(|x| x.eq_ignore_ascii_case(b"bucket_duration"))
+    {
+        return if index < args.len() - 1 {
+            args.remove(index);
+            let value = args.remove(index);
+            let duration = parse_duration_arg(&value)?;
+            if duration.is_zero() {
+                return Err(ValkeyError::Str(error_consts::INVALID_DURATION));
+            }
+            Ok(Some(duration))
+        } else {
+            Err(ValkeyError::Str("TSDB: missing BUCKET_DURATION value"))
+        };
+    }
+    Ok(None)
+}
+
 fn handle_update(
     ctx: &Context,
     series: &mut TimeSeries,
     key_name: &ValkeyString,
     timestamp: Option<Timestamp>,
     delta: f64,
+    bucket_duration: Option<Duration>,
     is_increment: bool,
 ) -> ValkeyResult {
     let delta = if !is_increment { -delta } else { delta };
+    let unit = series.timestamp_unit;
 
-    let result = series.increment_sample_value(timestamp, delta)?;
+    let result = series.increment_sample_value(timestamp, delta, bucket_duration)?;
     match result {
-        SampleAddResult::Ok(added) => {
-            replicate_and_notify(ctx, key_name, is_increment, added.timestamp)
-        }
+        SampleAddResult::Ok(added) => replicate_and_notify(
+            ctx,
+            key_name,
+            is_increment,
+            unit.from_millis(added.timestamp),
+        ),
         SampleAddResult::Ignored(_ts) => {
             let last_ts = series.last_timestamp();
-            Ok(ValkeyValue::Integer(last_ts))
+            Ok(ValkeyValue::Integer(unit.from_millis(last_ts)))
         }
         SampleAddResult::Duplicate => Err(ValkeyError::Str(error_consts::DUPLICATE_SAMPLE_BLOCKED)),
         SampleAddResult::Error(err) => Err(ValkeyError::Str(err)),
@@ -1,5 +1,7 @@
 use crate::common::constants::META_KEY_LABEL;
 use crate::common::rounding::RoundingStrategy;
+use crate::common::time::current_time_millis;
+use crate::error_consts;
 use crate::series::index::get_timeseries_index;
 use crate::series::{
     SeriesRef, TimeSeries,
@@ -10,29 +12,51 @@ use blart::AsBytes;
 use smallvec::SmallVec;
 use std::collections::HashMap;
 use valkey_module::redisvalue::ValkeyValueKey;
-use valkey_module::{AclPermissions, Context, NextArg, ValkeyResult, ValkeyString, ValkeyValue};
+use valkey_module::{
+    AclPermissions, Context, NextArg, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
+};
+
+/// Optional detail sections of the TS.INFO reply
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum InfoDetail {
+    #[default]
+    None,
+    /// per-chunk statistics
+    Chunks,
+    /// per-chunk statistics and internal fields
+    Debug,
+}
 
+///
+/// TS.INFO key [DEBUG | CHUNKS]
+///
 pub fn ts_info_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let mut args = args.into_iter().skip(1);
     let key = args.next_arg()?;
 
-    let debugging = if let Ok(val) = args.next_str() {
-        val.eq_ignore_ascii_case("debug")
+    let detail = if let Ok(val) = args.next_str() {
+        if val.eq_ignore_ascii_case("debug") {
+            InfoDetail::Debug
+        } else if val.eq_ignore_ascii_case("chunks") {
+            InfoDetail::Chunks
+        } else {
+            return Err(ValkeyError::Str(error_consts::INVALID_ARGUMENT));
+        }
     } else {
-        false
+        InfoDetail::None
     };
 
     args.done()?;
     let series = get_timeseries(ctx, &key, Some(AclPermissions::ACCESS), true)?;
     // must_exist was passed above. Therefore, unwrap is safe here
     let series = series.unwrap();
-    Ok(get_ts_info(ctx, &series, debugging, None))
+    Ok(get_ts_info(ctx, &series, detail, Some(&key)))
 }
 
 fn get_ts_info(
     ctx: &Context,
     ts: &TimeSeries,
-    debug: bool,
+    detail: InfoDetail,
     key: Option<&ValkeyString>,
 ) -> ValkeyValue {
     let mut map: HashMap<ValkeyValueKey, ValkeyValue> = HashMap::with_capacity(ts.labels.len() + 1);
@@ -46,33 +70,75 @@ fn get_ts_info(
         "memoryUsage".into(),
         ValkeyValue::Integer(ts.memory_usage() as i64),
     );
+    map.insert("memoryBreakdown".into(), get_memory_breakdown(ctx, ts));
     map.insert(
         "firstTimestamp".into(),
-        ValkeyValue::Integer(ts.first_timestamp),
+        ValkeyValue::Integer(ts.timestamp_unit.from_millis(ts.first_timestamp)),
     );
     if let Some(last_sample) = ts.last_sample {
         map.insert(
             "lastTimestamp".into(),
-            ValkeyValue::Integer(last_sample.timestamp),
+            ValkeyValue::Integer(ts.timestamp_unit.from_millis(last_sample.timestamp)),
         );
     } else {
         map.insert(
             "lastTimestamp".into(),
-            ValkeyValue::Integer(ts.first_timestamp),
+            ValkeyValue::Integer(ts.timestamp_unit.from_millis(ts.first_timestamp)),
         );
     }
+    let stats = ts.stats();
+    map.insert(
+        "dataSize".into(),
+        ValkeyValue::Integer(stats.data_size as i64),
+    );
+    map.insert("timeSpan".into(), ValkeyValue::Integer(stats.duration));
+    map.insert("sampleRate".into(), ValkeyValue::Float(stats.sample_rate));
+    map.insert(
+        "bytesPerSample".into(),
+        ValkeyValue::Float(stats.bytes_per_sample),
+    );
+    map.insert(
+        "compressionRatio".into(),
+        ValkeyValue::Float(stats.compression_ratio),
+    );
+    if let Some(last_sample) = ts.last_sample {
+        let age = current_time_millis().saturating_sub(last_sample.timestamp);
+        map.insert("lastSampleAge".into(), ValkeyValue::Integer(age.max(0)));
+    } else {
+        map.insert("lastSampleAge".into(), ValkeyValue::Null);
+    }
     map.insert(
         "retentionTime".into(),
         ValkeyValue::Integer(ts.retention.as_millis() as i64),
     );
+    map.insert(
+        "retentionSamples".into(),
+        ValkeyValue::Integer(ts.max_samples.unwrap_or_default() as i64),
+    );
     map.insert(
         "chunkCount".into(),
         ValkeyValue::Integer(ts.chunks.len() as i64),
     );
+    map.insert(
+        "outOfOrderSamples".into(),
+        ValkeyValue::Integer(ts.ooo_samples as i64),
+    );
+    map.insert(
+        "chunkSplits".into(),
+        ValkeyValue::Integer(ts.chunk_splits as i64),
+    );
     map.insert(
         "chunkSize".into(),
         ValkeyValue::Integer(ts.chunk_size_bytes as i64),
     );
+    map.insert(
+        "splitPolicy".into(),
+        ValkeyValue::BulkString(ts.split_policy.to_string()),
+    );
+    map.insert(
+        "timestampUnit".into(),
+        ValkeyValue::BulkString(ts.timestamp_unit.to_string()),
+    );
 
     if ts.chunk_compression.is_compressed() {
         map.insert("chunkType".into(), "compressed".into());
@@ -130,19 +196,22 @@ fn get_ts_info(
     );
 
     if let Some(rounding) = ts.rounding {
-        let (name, digits) = match rounding {
-            RoundingStrategy::SignificantDigits(d) => ("significantDigits", d),
-            RoundingStrategy::DecimalDigits(d) => ("decimalDigits", d),
+        let (name, value) = match rounding {
+            RoundingStrategy::SignificantDigits(d) => {
+                ("significantDigits", ValkeyValue::Integer(d.into()))
+            }
+            RoundingStrategy::DecimalDigits(d) => ("decimalDigits", ValkeyValue::Integer(d.into())),
+            RoundingStrategy::Multiple(step) => ("multiple", ValkeyValue::Float(step)),
         };
-        let result = ValkeyValue::Array(vec![
-            ValkeyValue::from(name),
-            ValkeyValue::Integer(digits.into()), // do we have negative digits?
-        ]);
+        let result = ValkeyValue::Array(vec![ValkeyValue::from(name), value]);
         map.insert("rounding".into(), result);
     }
 
-    if debug {
+    if detail == InfoDetail::Debug {
         map.insert("keySelfName".into(), ValkeyValue::from(key));
+        map.insert("id".into(), ValkeyValue::Integer(ts.id as i64));
+    }
+    if detail != InfoDetail::None {
         // yes, I know its title case, but that's what redis does
         map.insert("Chunks".into(), get_chunks_info(ts));
     }
@@ -150,6 +219,20 @@ fn get_ts_info(
     ValkeyValue::Map(map)
 }
 
+fn get_memory_breakdown(ctx: &Context, ts: &TimeSeries) -> ValkeyValue {
+    let usage = ts.memory_breakdown();
+    let index = get_timeseries_index(ctx).series_memory_share(ts);
+    let mut map: HashMap<ValkeyValueKey, ValkeyValue> = HashMap::with_capacity(4);
+    map.insert("chunks".into(), ValkeyValue::Integer(usage.chunks as i64));
+    map.insert("labels".into(), ValkeyValue::Integer(usage.labels as i64));
+    map.insert(
+        "metadata".into(),
+        ValkeyValue::Integer(usage.metadata as i64),
+    );
+    map.insert("index".into(), ValkeyValue::Integer(index as i64));
+    ValkeyValue::Map(map)
+}
+
 fn get_chunks_info(ts: &TimeSeries) -> ValkeyValue {
     let items = ts
         .chunks
@@ -162,6 +245,10 @@ fn get_chunks_info(ts: &TimeSeries) -> ValkeyValue {
 
 fn get_one_chunk_info(chunk: &TimeSeriesChunk) -> ValkeyValue {
     let mut map: HashMap<ValkeyValueKey, ValkeyValue> = HashMap::with_capacity(6);
+    map.insert(
+        "encoding".into(),
+        ValkeyValue::SimpleStringStatic(chunk.get_encoding().name()),
+    );
     map.insert(
         "startTimestamp".into(),
         ValkeyValue::Integer(chunk.first_timestamp()),
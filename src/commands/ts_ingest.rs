@@ -0,0 +1,285 @@
+use crate::commands::command_parser::parse_metric_name;
+use crate::common::time::current_time_millis;
+use crate::common::{Sample, Timestamp};
+use crate::error_consts;
+use crate::fanout::is_clustered;
+use crate::labels::{Label, MetricName};
+use crate::series::index::{get_series_key_by_id, get_timeseries_index};
+use crate::series::{
+    PerSeriesSamples, SampleAddResult, SeriesGuardMut, TimeSeriesOptions, check_future_timestamp,
+    create_and_store_series, get_max_future_timestamp, get_timeseries_mut,
+    multi_series_merge_samples,
+};
+use ahash::AHashMap;
+use std::ops::DerefMut;
+use valkey_module::{
+    AclPermissions, Context, NotifyEvent, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
+};
+
+struct ParsedLine<'a> {
+    metric: String,
+    labels: Vec<Label>,
+    raw_value: &'a str,
+    sample: Sample,
+}
+
+struct IngestTarget<'a> {
+    key: ValkeyString,
+    created: bool,
+    series: SeriesGuardMut<'a>,
+    lines: Vec<usize>,
+}
+
+/// TS.INGEST payload
+///
+/// Ingests a blob of Prometheus exposition-format text, one `metric{labels} value [timestamp]`
+/// sample per line. Each metric resolves to the series with exactly the same labels, which is
+/// created (keyed by the canonical metric name) if it does not exist. Comment, HELP and TYPE lines
+/// are skipped, and a malformed line or failed sample does not abort the rest of the batch.
+///
+/// Returns `[accepted, rejected]` line counts.
+pub fn ts_ingest_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() != 2 {
+        return Err(ValkeyError::WrongArity);
+    }
+
+    // series keys are derived from the payload, so they cannot be routed to their shards
+    if is_clustered(ctx) {
+        return Err(ValkeyError::Str(
+            error_consts::INGEST_NOT_SUPPORTED_IN_CLUSTER,
+        ));
+    }
+
+    let payload = args[1].try_as_str()?;
+    let now = current_time_millis();
+    let max_timestamp = get_max_future_timestamp(ctx);
+
+    let mut rejected = 0usize;
+    let mut parsed: Vec<ParsedLine> = Vec::new();
+    for line in payload.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_line(line, now) {
+            Some(parsed_line)
+                if check_future_timestamp(parsed_line.sample.timestamp, max_timestamp).is_ok() =>
+            {
+                parsed.push(parsed_line)
+            }
+            _ => rejected += 1,
+        }
+    }
+
+    let mut groups: AHashMap<&str, Vec<usize>> = AHashMap::new();
+    for (index, line) in parsed.iter().enumerate() {
+        groups.entry(line.metric.as_str()).or_default().push(index);
+    }
+
+    let mut targets: Vec<IngestTarget> = Vec::with_capacity(groups.len());
+    for (metric, lines) in groups {
+        let labels = &parsed[lines[0]].labels;
+        match resolve_series(ctx, metric, labels) {
+            Ok((key, series, created)) => targets.push(IngestTarget {
+                key,
+                created,
+                series,
+                lines,
+            }),
+            // lines of series that cannot be resolved or created are counted as rejected below
+            Err(_) => continue,
+        }
+    }
+
+    let mut per_series_samples: Vec<PerSeriesSamples> = Vec::with_capacity(targets.len());
+    for target in targets.iter_mut() {
+        let mut samples = PerSeriesSamples::new(target.series.deref_mut());
+        for &index in target.lines.iter() {
+            samples.add_sample(parsed[index].sample, index);
+        }
+        per_series_samples.push(samples);
+    }
+
+    let mut results = vec![SampleAddResult::Error(error_consts::KEY_NOT_FOUND); parsed.len()];
+    for (index, res) in multi_series_merge_samples(per_series_samples, Some(ctx))? {
+        if let Some(slot) = results.get_mut(index) {
+            *slot = res;
+        }
+    }
+
+    let accepted = results.iter().filter(|res| res.is_ok()).count();
+    rejected += parsed.len() - accepted;
+
+    handle_replication(ctx, &parsed, &results, &targets);
+
+    Ok(ValkeyValue::Array(vec![
+        ValkeyValue::Integer(accepted as i64),
+        ValkeyValue::Integer(rejected as i64),
+    ]))
+}
+
+/// Parses a single `metric{labels} value [timestamp]` line. Returns `None` if the line is malformed.
+fn parse_line(line: &str, now: Timestamp) -> Option<ParsedLine<'_>> {
+    let (metric_str, rest) = line.split_at(metric_end(line)?);
+
+    let labels: Vec<Label> = parse_metric_name(metric_str)
+        .ok()?
+        .into_iter()
+        // an empty label value is equivalent to the label being absent
+        .filter(|label| !label.value.is_empty())
+        .collect();
+
+    let mut parts = rest.split_whitespace();
+    let raw_value = parts.next()?;
+    let value = parse_sample_value(raw_value)?;
+    let timestamp = match parts.next() {
+        Some(ts) => ts.parse::<Timestamp>().ok()?,
+        None => now,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let metric = MetricName::new(&labels).to_string();
+    Some(ParsedLine {
+        metric,
+        labels,
+        raw_value,
+        sample: Sample::new(timestamp, value),
+    })
+}
+
+/// Returns the byte offset just past the metric name and its optional label set.
+fn metric_end(line: &str) -> Option<usize> {
+    let mut in_braces = false;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (pos, ch) in line.char_indices() {
+        if in_quotes {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_quotes = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            '"' if in_braces => in_quotes = true,
+            '{' if !in_braces => in_braces = true,
+            '}' if in_braces => return Some(pos + 1),
+            c if c.is_whitespace() && !in_braces => return Some(pos),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_sample_value(value: &str) -> Option<f64> {
+    match value {
+        "+Inf" => Some(f64::INFINITY),
+        "-Inf" => Some(f64::NEG_INFINITY),
+        "NaN" => Some(f64::NAN),
+        _ => value.parse::<f64>().ok(),
+    }
+}
+
+/// Finds the series with exactly the given labels, creating it under the key `metric` if needed.
+fn resolve_series<'a>(
+    ctx: &'a Context,
+    metric: &str,
+    labels: &[Label],
+) -> ValkeyResult<(ValkeyString, SeriesGuardMut<'a>, bool)> {
+    let series_id = get_timeseries_index(ctx).series_id_by_labels(labels);
+    if let Some(id) = series_id
+        && let Some(key) = get_series_key_by_id(ctx, id)
+        && let Some(series) = get_timeseries_mut(ctx, &key, false, Some(AclPermissions::INSERT))?
+        && series.labels.len() == labels.len()
+    {
+        return Ok((key, series, false));
+    }
+
+    let key = ctx.create_string(metric);
+    let options = TimeSeriesOptions {
+        labels: Some(labels.to_vec()),
+        ..TimeSeriesOptions::from_config()
+    };
+    let series = create_and_store_series(ctx, &key, options, false, true)?;
+    Ok((key, series, true))
+}
+
+fn handle_replication(
+    ctx: &Context,
+    parsed: &[ParsedLine],
+    results: &[SampleAddResult],
+    targets: &[IngestTarget],
+) {
+    // Replicate the accepted lines with explicit timestamps, so that replicas resolve the same
+    // series and store the same samples.
+    let mut payload = String::new();
+    for (line, _) in parsed
+        .iter()
+        .zip(results.iter())
+        .filter(|(_, res)| res.is_ok())
+    {
+        payload.push_str(&line.metric);
+        payload.push(' ');
+        payload.push_str(line.raw_value);
+        payload.push(' ');
+        payload.push_str(&line.sample.timestamp.to_string());
+        payload.push('\n');
+    }
+
+    if payload.is_empty() {
+        return;
+    }
+
+    ctx.replicate("TS.INGEST", &[payload.as_str()]);
+    for target in targets {
+        if target.created {
+            ctx.notify_keyspace_event(NotifyEvent::MODULE, "ts.create", &target.key);
+        }
+        if target.lines.iter().any(|&i| results[i].is_ok()) {
+            ctx.notify_keyspace_event(NotifyEvent::MODULE, "ts.add", &target.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{metric_end, parse_line};
+
+    #[test]
+    fn test_metric_end() {
+        assert_eq!(metric_end("up 1"), Some(2));
+        let line = r#"http_requests{path="/a b",q="x\"}"} 3 1000"#;
+        assert_eq!(
+            &line[..metric_end(line).unwrap()],
+            r#"http_requests{path="/a b",q="x\"}"}"#
+        );
+        assert_eq!(metric_end("no_value"), None);
+        assert_eq!(metric_end(r#"broken{a="1" 2"#), None);
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let line = parse_line(
+            r#"http_requests{status="200",method="GET"} 1027 1395066363000"#,
+            0,
+        )
+        .unwrap();
+        assert_eq!(line.metric, r#"http_requests{method="GET",status="200"}"#);
+        assert_eq!(line.labels.len(), 3);
+        assert_eq!(line.sample.timestamp, 1395066363000);
+        assert_eq!(line.sample.value, 1027.0);
+
+        let line = parse_line("temperature +Inf", 42).unwrap();
+        assert_eq!(line.sample.timestamp, 42);
+        assert!(line.sample.value.is_infinite());
+
+        assert!(parse_line("temperature", 0).is_none());
+        assert!(parse_line("temperature abc", 0).is_none());
+        assert!(parse_line("temperature 1 2 3", 0).is_none());
+        assert!(parse_line(r#"{="x"} 1"#, 0).is_none());
+    }
+}
@@ -1,29 +1,25 @@
-use crate::commands::command_parser::{parse_join_args, parse_timestamp_range};
+use crate::commands::command_parser::{parse_join_args, parse_series_timestamp_range};
 use crate::error_consts;
 use crate::join::{JoinOptions, process_join};
 use crate::series::get_timeseries;
 use valkey_module::{AclPermissions, Context, NextArg, ValkeyError, ValkeyResult, ValkeyString};
 
 /// TS.JOIN key1 key2 fromTimestamp toTimestamp
-///   [INNER | FULL | LEFT | RIGHT | ANTI | SEMI | ASOF [PREVIOUS | NEXT | NEAREST] tolerance [ALLOW_EXACT_MATCH [true|false]]]
+///   [INNER | FULL | LEFT | RIGHT | ANTI | SEMI | ASOF [PREVIOUS | PRIOR | NEXT | NEAREST] tolerance [ALLOW_EXACT_MATCH [true|false]]]
 ///   [FILTER_BY_TS ts...]
 ///   [FILTER_BY_VALUE min max]
 ///   [COUNT count]
 ///   [REDUCE op]
+///   [FILL NULL | ZERO | PREVIOUS]
+///   [DIV_BY_ZERO NAN | NULL]
 ///   [AGGREGATION aggregator bucket_duration [ALIGN align] [BUCKETTIMESTAMP timestamp] [EMPTY]]
+///
+/// Timestamps are in the unit of the first series, and so are the ones returned.
 pub fn ts_join_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let mut args = args.into_iter().skip(1).peekable();
 
     let left_key = args.next_arg()?;
     let right_key = args.next_arg()?;
-    let date_range = parse_timestamp_range(&mut args)?;
-
-    let mut options = JoinOptions {
-        date_range,
-        ..Default::default()
-    };
-
-    parse_join_args(&mut args, &mut options)?;
 
     if left_key == right_key {
         return Err(ValkeyError::Str(error_consts::DUPLICATE_JOIN_KEYS));
@@ -34,7 +30,18 @@ pub fn ts_join_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let left_series = get_timeseries(ctx, &left_key, Some(AclPermissions::ACCESS), true)?.unwrap();
     let right_series =
         get_timeseries(ctx, &right_key, Some(AclPermissions::ACCESS), true)?.unwrap();
+    // the series are needed first, since timestamps are given in the unit of the first one
+    let unit = left_series.timestamp_unit;
+
+    let date_range = parse_series_timestamp_range(&mut args, unit)?;
+
+    let mut options = JoinOptions {
+        date_range,
+        ..Default::default()
+    };
+
+    parse_join_args(&mut args, &mut options, unit)?;
 
     let result = process_join(&left_series, &right_series, &options)?;
-    Ok(result.into())
+    Ok(result.map_timestamps(|ts| unit.from_millis(ts)).into())
 }
@@ -1,9 +1,8 @@
 use super::ts_labelnames_fanout_command::LabelNamesFanoutCommand;
 use crate::commands::command_parser::parse_metadata_command_args;
 use crate::fanout::{FanoutClientCommand, is_clustered};
-use crate::series::index::with_matched_series;
+use crate::series::index::label_names_by_selectors;
 use crate::series::request_types::MatchFilterOptions;
-use std::collections::BTreeSet;
 use valkey_module::{Context, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
 
 /// https://prometheus.io/docs/prometheus/latest/querying/api/#getting-label-names
@@ -36,13 +35,7 @@ pub fn process_label_names_request(
     ctx: &Context,
     options: &MatchFilterOptions,
 ) -> ValkeyResult<Vec<String>> {
-    let mut names: BTreeSet<String> = BTreeSet::new();
-
-    with_matched_series(ctx, &mut names, options, |acc, ts, _| {
-        for label in ts.labels.iter() {
-            acc.insert(label.name.into());
-        }
-    })?;
+    let names = label_names_by_selectors(ctx, options.date_range, &options.matchers)?;
 
     let limit = options.limit.unwrap_or(names.len());
     let names = names.into_iter().take(limit).collect::<Vec<_>>();
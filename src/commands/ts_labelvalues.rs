@@ -1,14 +1,15 @@
 use super::ts_labelvalues_fanout_command::LabelValuesFanoutCommand;
-use crate::commands::command_parser::parse_metadata_command_args;
+use crate::commands::command_parser::parse_label_values_command_args;
 use crate::error_consts;
 use crate::fanout::{FanoutClientCommand, is_clustered};
-use crate::series::index::with_matched_series;
+use crate::labels::filters::RegexMatcher;
+use crate::series::index::{PostingStat, top_label_values, with_matched_series};
 use crate::series::request_types::MatchFilterOptions;
 use std::collections::BTreeSet;
 use valkey_module::ValkeyError::WrongArity;
 use valkey_module::{Context, NextArg, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
 
-// TS.LABELVALUES label [FILTER_BY_RANGE fromTimestamp  fromTimestamp] [LIMIT limit] FILTER seriesMatcher...
+// TS.LABELVALUES label [FILTER_BY_RANGE fromTimestamp  fromTimestamp] [LIMIT limit] [TOPK k] [MATCH regex] FILTER seriesMatcher...
 // https://prometheus.io/docs/prometheus/latest/querying/api/#querying-label-values
 pub fn ts_labelvalues_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     if args.len() < 3 {
@@ -16,7 +17,7 @@ pub fn ts_labelvalues_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResul
     }
     let mut args = args.into_iter().skip(1).peekable();
     let label_name = args.next_arg()?.to_string_lossy();
-    let label_args = parse_metadata_command_args(&mut args, true)?;
+    let (label_args, topk, value_match) = parse_label_values_command_args(&mut args)?;
 
     if is_clustered(ctx) {
         if label_args.matchers.is_empty() {
@@ -26,11 +27,26 @@ pub fn ts_labelvalues_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResul
         }
 
         // in cluster mode, we need to send the request to all nodes
-        let operation = LabelValuesFanoutCommand::new(label_name, label_args);
+        let operation = LabelValuesFanoutCommand::new(label_name, label_args, topk, value_match);
         return operation.exec(ctx);
     }
 
-    let names = process_label_values_request(ctx, &label_name, &label_args)?;
+    if let Some(k) = topk {
+        if label_name.is_empty() {
+            return Err(ValkeyError::Str(error_consts::MISSING_LABEL_VALUE));
+        }
+        let values = top_label_values(
+            ctx,
+            &label_name,
+            label_args.date_range,
+            &label_args.matchers,
+            k,
+            value_match.as_ref(),
+        )?;
+        return Ok(top_values_reply(values));
+    }
+
+    let names = process_label_values_request(ctx, &label_name, &label_args, value_match.as_ref())?;
 
     let label_values = names
         .into_iter()
@@ -40,10 +56,26 @@ pub fn ts_labelvalues_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResul
     Ok(ValkeyValue::Array(label_values))
 }
 
+/// Replies with `[value, series count]` pairs, in the order given.
+pub(super) fn top_values_reply(values: Vec<PostingStat>) -> ValkeyValue {
+    ValkeyValue::Array(
+        values
+            .into_iter()
+            .map(|stat| {
+                ValkeyValue::Array(vec![
+                    ValkeyValue::BulkString(stat.name),
+                    ValkeyValue::Integer(stat.count as i64),
+                ])
+            })
+            .collect(),
+    )
+}
+
 pub fn process_label_values_request(
     ctx: &Context,
     label_name: &str,
     options: &MatchFilterOptions,
+    value_match: Option<&RegexMatcher>,
 ) -> ValkeyResult<Vec<String>> {
     if label_name.is_empty() {
         return Err(ValkeyError::Str(error_consts::MISSING_LABEL_VALUE));
@@ -52,7 +84,9 @@ pub fn process_label_values_request(
     let mut names: BTreeSet<String> = BTreeSet::new();
 
     with_matched_series(ctx, &mut names, options, |acc, ts, _| {
-        if let Some(label) = ts.get_label(label_name) {
+        if let Some(label) = ts.get_label(label_name)
+            && value_match.is_none_or(|re| re.is_match(label.value))
+        {
             acc.insert(label.value.into());
         }
     })?;
@@ -1,26 +1,43 @@
-use super::fanout::generated::{LabelValuesRequest, LabelValuesResponse};
+use super::fanout::generated::{
+    LabelValuesRequest, LabelValuesResponse, PostingStat as MPostingStat,
+};
+use super::ts_labelvalues::top_values_reply;
 use crate::commands::fanout::filters::{deserialize_matchers_list, serialize_matchers_list};
 use crate::commands::process_label_values_request;
 use crate::fanout::FanoutContext;
 use crate::fanout::{FanoutClientCommand, NodeInfo};
-use crate::labels::filters::SeriesSelector;
+use crate::labels::filters::{RegexMatcher, SeriesSelector};
+use crate::series::index::{PostingStat, sort_posting_stats, top_label_values};
 use crate::series::request_types::{MatchFilterOptions, MetaDateRangeFilter};
+use ahash::AHashMap;
 use std::collections::BTreeSet;
-use valkey_module::{Context, Status, ValkeyResult};
+use valkey_module::{Context, Status, ValkeyError, ValkeyResult};
 
 #[derive(Default)]
 pub struct LabelValuesFanoutCommand {
     pub label: String,
     pub options: MatchFilterOptions,
+    pub topk: Option<usize>,
+    pub value_match: Option<RegexMatcher>,
     results: BTreeSet<String>,
+    /// per-value series counts summed over the top-K of each shard
+    top_values: AHashMap<String, u64>,
 }
 
 impl LabelValuesFanoutCommand {
-    pub fn new(label: String, options: MatchFilterOptions) -> Self {
+    pub fn new(
+        label: String,
+        options: MatchFilterOptions,
+        topk: Option<usize>,
+        value_match: Option<RegexMatcher>,
+    ) -> Self {
         Self {
             label,
             options,
+            topk,
+            value_match,
             results: BTreeSet::new(),
+            top_values: AHashMap::new(),
         }
     }
 }
@@ -39,14 +56,45 @@ impl FanoutClientCommand for LabelValuesFanoutCommand {
     ) -> ValkeyResult<LabelValuesResponse> {
         let date_range: Option<MetaDateRangeFilter> = req.range.map(|r| r.into());
         let matchers: Vec<SeriesSelector> = deserialize_matchers_list(Some(req.filters))?;
+        let value_match = if req.value_match.is_empty() {
+            None
+        } else {
+            let re = RegexMatcher::create(&req.value_match)
+                .map_err(|e| ValkeyError::String(format!("TSDB: regex value error: {e:?}")))?;
+            Some(re)
+        };
+        if req.topk > 0 {
+            let top_values = top_label_values(
+                ctx,
+                &req.label,
+                date_range,
+                &matchers,
+                req.topk as usize,
+                value_match.as_ref(),
+            )?;
+            return Ok(LabelValuesResponse {
+                values: vec![],
+                top_values: top_values
+                    .into_iter()
+                    .map(|stat| MPostingStat {
+                        name: stat.name,
+                        count: stat.count,
+                    })
+                    .collect(),
+            });
+        }
         let options = MatchFilterOptions {
             date_range,
             matchers,
             // send all values to requester. Limit is applied in the sender node.
             limit: None,
         };
-        process_label_values_request(ctx, &req.label, &options)
-            .map(|values| LabelValuesResponse { values })
+        process_label_values_request(ctx, &req.label, &options, value_match.as_ref()).map(
+            |values| LabelValuesResponse {
+                values,
+                top_values: vec![],
+            },
+        )
     }
 
     fn generate_request(&self) -> LabelValuesRequest {
@@ -56,6 +104,12 @@ impl FanoutClientCommand for LabelValuesFanoutCommand {
             label: self.label.clone(),
             range: self.options.date_range.map(|x| x.into()),
             filters,
+            topk: self.topk.unwrap_or_default() as u32,
+            value_match: self
+                .value_match
+                .as_ref()
+                .map(|re| re.value.clone())
+                .unwrap_or_default(),
         }
     }
 
@@ -63,9 +117,23 @@ impl FanoutClientCommand for LabelValuesFanoutCommand {
         for value in resp.values {
             self.results.insert(value);
         }
+        for stat in resp.top_values {
+            *self.top_values.entry(stat.name).or_default() += stat.count;
+        }
     }
 
     fn reply(&mut self, ctx: &FanoutContext) -> Status {
+        if let Some(k) = self.topk {
+            let mut values: Vec<PostingStat> = self
+                .top_values
+                .drain()
+                .map(|(name, count)| PostingStat { name, count })
+                .collect();
+            sort_posting_stats(&mut values);
+            values.truncate(k);
+            return ctx.reply(Ok(top_values_reply(values)));
+        }
+
         let limit = self
             .options
             .limit
@@ -1,10 +1,11 @@
-use crate::commands::command_parser::{parse_timestamp, parse_value_arg};
+use crate::commands::command_parser::{parse_series_timestamp, parse_value_arg};
 use crate::common::time::current_time_millis;
 use crate::common::{Sample, Timestamp};
 use crate::error_consts;
 use crate::series::{
-    PerSeriesSamples, SampleAddResult, SeriesGuardMut, TimeSeriesOptions, create_and_store_series,
-    get_timeseries_mut, multi_series_merge_samples,
+    PerSeriesSamples, SampleAddResult, SeriesGuardMut, TimeSeriesOptions, TimestampUnit,
+    check_future_timestamp, create_and_store_series, get_max_future_timestamp, get_timeseries_mut,
+    multi_series_merge_samples,
 };
 use ahash::AHashMap;
 use smallvec::SmallVec;
@@ -20,6 +21,8 @@ struct ParsedInput<'a> {
     raw_value: &'a ValkeyString,
     timestamp: Timestamp,
     value: f64,
+    /// The timestamp unit of the series, in which the timestamp is given and returned
+    unit: TimestampUnit,
     index: usize,
     res: SampleAddResult,
 }
@@ -39,6 +42,8 @@ struct SeriesSamples<'a> {
 /// allows us to add multiple samples at once per series, while parallelizing across series blocks.
 /// Because of that there is extra bookkeeping to do, including mapping results back to the
 /// original input and returning results in input order.
+///
+/// Each timestamp is in the unit of its series, and so is the one returned.
 pub fn ts_madd_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let arg_count = args.len() - 1;
 
@@ -48,8 +53,10 @@ pub fn ts_madd_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
 
     let sample_count = arg_count / 3;
 
+    // "*" is replaced by the current time in the unit of the series, so that it is replicated as is
     let now = current_time_millis();
-    let current_ts = ctx.create_string(now.to_string());
+    let current_ts =
+        TIMESTAMP_UNITS.map(|unit| ctx.create_string(unit.from_millis(now).to_string()));
 
     // Parse once, keep inputs already in original order (no regroup+sort later)
     let (mut input_map, all_inputs) = parse_args(ctx, &args[1..], &current_ts)?;
@@ -57,13 +64,25 @@ pub fn ts_madd_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     // Results aligned by original input order
     let results = handle_update(ctx, &mut input_map, &all_inputs, sample_count)?;
 
-    handle_replication(ctx, &all_inputs);
+    handle_replication(ctx, &all_inputs, &results);
 
     Ok(ValkeyValue::Array(
-        results.into_iter().map(ValkeyValue::from).collect(),
+        results
+            .into_iter()
+            .zip(all_inputs.iter())
+            .map(|(res, input)| res.to_unit(input.unit).into())
+            .collect(),
     ))
 }
 
+/// Every timestamp unit, ordered by `TimestampUnit::as_u8`.
+const TIMESTAMP_UNITS: [TimestampUnit; 4] = [
+    TimestampUnit::Seconds,
+    TimestampUnit::Milliseconds,
+    TimestampUnit::Microseconds,
+    TimestampUnit::Nanoseconds,
+];
+
 fn handle_update(
     ctx: &Context,
     input_map: &mut AHashMap<&ValkeyString, SeriesSamples>,
@@ -121,7 +140,7 @@ fn handle_update(
 fn parse_args<'a>(
     ctx: &'a Context,
     args: &'a [ValkeyString],
-    current_ts: &'a ValkeyString,
+    current_ts: &'a [ValkeyString; 4],
 ) -> ValkeyResult<(
     AHashMap<&'a ValkeyString, SeriesSamples<'a>>,
     Vec<ParsedInput<'a>>,
@@ -133,6 +152,7 @@ fn parse_args<'a>(
     let mut all_inputs: Vec<ParsedInput<'a>> = Vec::with_capacity(sample_count);
 
     let options = TimeSeriesOptions::from_config();
+    let max_timestamp = get_max_future_timestamp(ctx);
 
     for (sample_index, chunk) in args.chunks_exact(3).enumerate() {
         let key = &chunk[0];
@@ -140,16 +160,6 @@ fn parse_args<'a>(
         let raw_timestamp_in = &chunk[1];
         let raw_value = &chunk[2];
 
-        // Normalize replication timestamp first ("*" becomes the concrete current timestamp)
-        let (raw_timestamp, timestamp_str) = {
-            let s = raw_timestamp_in.try_as_str()?;
-            if s == "*" {
-                (current_ts, "*")
-            } else {
-                (raw_timestamp_in, s)
-            }
-        };
-
         let series_samples = input_map.entry(key).or_default();
 
         // Resolve per-series guard once (first time we see a key); cache series-level error.
@@ -173,18 +183,34 @@ fn parse_args<'a>(
                 };
         }
 
+        let unit = series_samples
+            .series
+            .as_ref()
+            .map_or(TimestampUnit::default(), |series| series.timestamp_unit);
+
+        // Normalize replication timestamp ("*" becomes the concrete current timestamp)
+        let raw_timestamp = if raw_timestamp_in.try_as_str()? == "*" {
+            &current_ts[unit.as_u8() as usize]
+        } else {
+            raw_timestamp_in
+        };
+        let timestamp_str = raw_timestamp.try_as_str()?;
+
         // Parse timestamp/value only if the series is usable.
         let mut res = series_samples.err;
         let (timestamp, value) = if !res.is_ok() {
             (0, 0.0)
         } else {
-            let ts = match parse_timestamp(timestamp_str) {
+            let ts = match parse_series_timestamp(timestamp_str, unit) {
                 Ok(ts) => ts,
                 Err(_) => {
                     res = SampleAddResult::Error(error_consts::INVALID_TIMESTAMP);
                     0
                 }
             };
+            if res.is_ok() && check_future_timestamp(ts, max_timestamp).is_err() {
+                res = SampleAddResult::Error(error_consts::SAMPLE_TOO_NEW);
+            }
 
             let v = match parse_value_arg(raw_value) {
                 Ok(v) => v,
@@ -203,6 +229,7 @@ fn parse_args<'a>(
             raw_value,
             timestamp,
             value,
+            unit,
             index: sample_index,
             res,
         };
@@ -213,6 +240,7 @@ fn parse_args<'a>(
             raw_value,
             timestamp,
             value,
+            unit,
             index: sample_index,
             res,
         };
@@ -225,10 +253,12 @@ fn parse_args<'a>(
     Ok((input_map, all_inputs))
 }
 
-fn handle_replication(ctx: &Context, inputs: &[ParsedInput]) {
+/// Replicates only the samples which were actually stored. Samples rejected at parse or merge
+/// time (e.g. TooOld, Duplicate) are excluded so replicas do not re-evaluate them.
+fn handle_replication(ctx: &Context, inputs: &[ParsedInput], results: &[SampleAddResult]) {
     let mut replication_args: SmallVec<_, 24> = SmallVec::new();
     for input in inputs.iter() {
-        if input.res.is_ok() {
+        if results.get(input.index).is_some_and(|res| res.is_ok()) {
             replication_args.push(input.key);
             replication_args.push(input.raw_timestamp);
             replication_args.push(input.raw_value);
@@ -0,0 +1,89 @@
+use super::ts_mbounds_fanout_command::MBoundsFanoutCommand;
+use crate::commands::command_parser::CommandArgToken;
+use crate::commands::{parse_command_arg_token, parse_series_selector_list};
+use crate::error_consts;
+use crate::fanout::{FanoutClientCommand, is_clustered};
+use crate::labels::Label;
+use crate::series::get_series_labels;
+use crate::series::index::with_matched_series;
+use crate::series::request_types::{MBoundsRequest, MBoundsSeriesData, MatchFilterOptions};
+use valkey_module::{Context, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
+
+/// TS.MBOUNDS
+///   [WITHLABELS]
+///   FILTER filterExpr...
+///
+/// Returns the first and last timestamps and the sample count of each matched series, without
+/// reading any samples.
+pub fn ts_mbounds_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() < 3 {
+        return Err(ValkeyError::WrongArity);
+    }
+
+    let options = parse_mbounds_options(args)?;
+
+    if is_clustered(ctx) {
+        let operation = MBoundsFanoutCommand::new(options);
+        return operation.exec(ctx);
+    }
+
+    let results = process_mbounds_request(ctx, options)?;
+
+    Ok(ValkeyValue::Array(
+        results.into_iter().map(|s| s.into()).collect(),
+    ))
+}
+
+fn parse_mbounds_options(args: Vec<ValkeyString>) -> ValkeyResult<MBoundsRequest> {
+    let mut options = MBoundsRequest::default();
+    let mut args = args.into_iter().skip(1).peekable();
+
+    while let Some(arg) = args.next() {
+        let token = parse_command_arg_token(arg.as_slice())
+            .ok_or(ValkeyError::Str(error_consts::INVALID_ARGUMENT))?;
+
+        match token {
+            CommandArgToken::WithLabels => {
+                options.with_labels = true;
+            }
+            CommandArgToken::Filter => {
+                options.filters = parse_series_selector_list(&mut args, &[])?;
+            }
+            _ => return Err(ValkeyError::Str(error_consts::INVALID_ARGUMENT)),
+        }
+    }
+
+    if options.filters.is_empty() {
+        return Err(ValkeyError::Str(error_consts::MISSING_FILTER));
+    }
+
+    Ok(options)
+}
+
+pub fn process_mbounds_request(
+    ctx: &Context,
+    options: MBoundsRequest,
+) -> ValkeyResult<Vec<MBoundsSeriesData>> {
+    let with_labels = options.with_labels;
+    let mut series = Vec::with_capacity(8);
+    let opts: MatchFilterOptions = options.filters.into();
+
+    with_matched_series(ctx, &mut series, &opts, move |acc, series, series_key| {
+        let labels = get_series_labels(series, with_labels, &[])
+            .into_iter()
+            .flatten()
+            .map(|label| Label::new(label.name, label.value))
+            .collect();
+        let bounds =
+            (!series.is_empty()).then(|| (series.first_timestamp, series.last_timestamp()));
+
+        acc.push(MBoundsSeriesData {
+            series_key,
+            labels,
+            bounds,
+            total_samples: series.total_samples,
+        });
+    })?;
+
+    Ok(series)
+}
@@ -0,0 +1,114 @@
+use super::fanout::generated::{MultiBoundsRequest, MultiBoundsResponse, SeriesBoundsValue};
+use crate::commands::fanout::filters::{deserialize_matchers_list, serialize_matchers_list};
+use crate::commands::process_mbounds_request;
+use crate::commands::utils::reply_with_fanout_labels;
+use crate::error_consts;
+use crate::fanout::FanoutContext;
+use crate::fanout::{FanoutClientCommand, NodeInfo};
+use crate::series::request_types::MBoundsRequest;
+use ahash::AHashMap;
+use valkey_module::{Context, Status, ValkeyError, ValkeyResult};
+
+#[derive(Debug, Default)]
+pub struct MBoundsFanoutCommand {
+    options: MBoundsRequest,
+    series: Vec<SeriesBoundsValue>,
+    /// Position of each key in `series`, used to merge duplicate responses for the same key
+    key_index: AHashMap<String, usize>,
+}
+
+impl MBoundsFanoutCommand {
+    pub fn new(options: MBoundsRequest) -> Self {
+        Self {
+            options,
+            series: Vec::new(),
+            key_index: AHashMap::new(),
+        }
+    }
+
+    /// Adds a shard's value, keeping the most recent one if the key was already reported by
+    /// another node (e.g. while its slot is being migrated).
+    fn merge_value(&mut self, value: SeriesBoundsValue) {
+        match self.key_index.get(&value.key) {
+            Some(&index) => {
+                let existing = &mut self.series[index];
+                let existing_rank = (existing.last_timestamp, existing.total_samples);
+                if (value.last_timestamp, value.total_samples) > existing_rank {
+                    *existing = value;
+                }
+            }
+            None => {
+                self.key_index.insert(value.key.clone(), self.series.len());
+                self.series.push(value);
+            }
+        }
+    }
+}
+
+impl FanoutClientCommand for MBoundsFanoutCommand {
+    type Request = MultiBoundsRequest;
+    type Response = MultiBoundsResponse;
+
+    fn name() -> &'static str {
+        "mbounds"
+    }
+
+    fn get_local_response(
+        ctx: &Context,
+        req: MultiBoundsRequest,
+    ) -> ValkeyResult<MultiBoundsResponse> {
+        let filters = deserialize_matchers_list(Some(req.filters))
+            .map_err(|_e| ValkeyError::Str(error_consts::COMMAND_DESERIALIZATION_ERROR))?;
+
+        let request = MBoundsRequest {
+            with_labels: req.with_labels,
+            filters,
+        };
+
+        let results = process_mbounds_request(ctx, request)?;
+        let values = results.into_iter().map(|value| value.into()).collect();
+
+        Ok(MultiBoundsResponse { values })
+    }
+
+    fn generate_request(&self) -> MultiBoundsRequest {
+        let filters =
+            serialize_matchers_list(&self.options.filters).expect("serialize matchers list");
+        MultiBoundsRequest {
+            with_labels: self.options.with_labels,
+            filters,
+        }
+    }
+
+    fn on_response(&mut self, resp: Self::Response, _target: &NodeInfo) {
+        for value in resp.values {
+            self.merge_value(value);
+        }
+    }
+
+    fn reply(&mut self, ctx: &FanoutContext) -> Status {
+        let status = ctx.reply_with_array(self.series.len());
+        if status != Status::Ok {
+            return status;
+        }
+        for value in self.series.iter() {
+            ctx.reply_with_array(5);
+            ctx.reply_with_bulk_string(value.key.as_str());
+            reply_with_fanout_labels(ctx, &value.labels);
+            reply_with_optional_timestamp(ctx, value.first_timestamp);
+            reply_with_optional_timestamp(ctx, value.last_timestamp);
+            let status = ctx.reply_with_i64(value.total_samples as i64);
+            if status != Status::Ok {
+                return status;
+            }
+        }
+        Status::Ok
+    }
+}
+
+fn reply_with_optional_timestamp(ctx: &FanoutContext, ts: Option<i64>) -> Status {
+    match ts {
+        Some(ts) => ctx.reply_with_i64(ts),
+        None => ctx.reply_with_null(),
+    }
+}
@@ -0,0 +1,172 @@
+use crate::commands::command_parser::{CommandArgToken, parse_command_arg_token};
+use crate::common::Sample;
+use crate::error_consts;
+use crate::series::acl::check_key_permissions;
+use crate::series::{
+    SampleAddResult, TimeSeries, delete_series_key, get_timeseries, get_timeseries_mut,
+    run_compactions,
+};
+use std::collections::BTreeMap;
+use valkey_module::{
+    AclPermissions, Context, NotifyEvent, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
+};
+
+/// Number of source samples merged into the destination at a time, so that large sources are
+/// never fully materialized.
+const MERGE_BATCH_SIZE: usize = 4096;
+
+#[derive(Default)]
+struct MergeStats {
+    merged: usize,
+    rejected: usize,
+    /// rejected sample counts keyed by `SampleAddResult::reason_code`
+    rejected_by_reason: BTreeMap<&'static str, usize>,
+}
+
+///
+/// TS.MERGE destKey sourceKey [sourceKey ...] [DELETE]
+///
+pub fn ts_merge_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() < 3 {
+        return Err(ValkeyError::WrongArity);
+    }
+
+    let delete_sources = args.len() > 3
+        && parse_command_arg_token(args[args.len() - 1].as_slice())
+            == Some(CommandArgToken::Delete);
+    let key_count = if delete_sources {
+        args.len() - 2
+    } else {
+        args.len() - 1
+    };
+
+    // the trailing DELETE is not a key, so the key positions are reported explicitly
+    if ctx.is_keys_position_request() {
+        for pos in 1..=key_count {
+            ctx.key_at_pos(pos as i32);
+        }
+        return Ok(ValkeyValue::SimpleStringStatic("OK"));
+    }
+
+    let dest_key = &args[1];
+    let mut sources: Vec<&ValkeyString> = Vec::with_capacity(key_count - 1);
+    for key in args[2..=key_count].iter() {
+        if key == dest_key {
+            return Err(ValkeyError::Str(error_consts::MERGE_SOURCE_IS_DESTINATION));
+        }
+        if !sources.contains(&key) {
+            sources.push(key);
+        }
+    }
+
+    // validate every source before touching the destination
+    for &key in sources.iter() {
+        get_timeseries(ctx, key, Some(AclPermissions::ACCESS), true)?;
+        if delete_sources {
+            check_key_permissions(ctx, key, &AclPermissions::DELETE)?;
+        }
+    }
+
+    let Some(mut dest) = get_timeseries_mut(ctx, dest_key, true, Some(AclPermissions::UPDATE))?
+    else {
+        return Err(ValkeyError::Str(error_consts::KEY_NOT_FOUND));
+    };
+
+    let mut stats = MergeStats::default();
+    let mut fully_merged: Vec<&ValkeyString> = Vec::with_capacity(sources.len());
+    for &key in sources.iter() {
+        let Some(source) = get_timeseries(ctx, key, None, true)? else {
+            continue;
+        };
+        let rejected_before = stats.rejected;
+        merge_series(ctx, &mut dest, &source, &mut stats)?;
+        if stats.rejected == rejected_before {
+            fully_merged.push(key);
+        }
+    }
+    let is_compaction = dest.is_compaction();
+    drop(dest);
+
+    ctx.replicate_verbatim();
+    if stats.merged > 0 {
+        let event = if is_compaction {
+            "ts.add:dest"
+        } else {
+            "ts.add"
+        };
+        ctx.notify_keyspace_event(NotifyEvent::MODULE, event, dest_key);
+    }
+
+    // a source is only removed if none of its samples were lost
+    if delete_sources {
+        for key in fully_merged {
+            delete_series_key(ctx, key)?;
+        }
+    }
+
+    let reasons = stats
+        .rejected_by_reason
+        .into_iter()
+        .map(|(code, count)| (code.into(), ValkeyValue::Integer(count as i64)))
+        .collect();
+
+    Ok(ValkeyValue::Array(vec![
+        ValkeyValue::Integer(stats.merged as i64),
+        ValkeyValue::Integer(stats.rejected as i64),
+        ValkeyValue::OrderedMap(reasons),
+    ]))
+}
+
+/// Streams the samples of `source` into `dest` in batches. Rounding, retention and the duplicate
+/// policy of `dest` apply.
+fn merge_series(
+    ctx: &Context,
+    dest: &mut TimeSeries,
+    source: &TimeSeries,
+    stats: &mut MergeStats,
+) -> ValkeyResult<()> {
+    let mut batch: Vec<Sample> = Vec::with_capacity(MERGE_BATCH_SIZE.min(source.total_samples));
+    for sample in source.iter() {
+        batch.push(sample);
+        if batch.len() == MERGE_BATCH_SIZE {
+            merge_batch(ctx, dest, &batch, stats)?;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        merge_batch(ctx, dest, &batch, stats)?;
+    }
+    Ok(())
+}
+
+fn merge_batch(
+    ctx: &Context,
+    dest: &mut TimeSeries,
+    samples: &[Sample],
+    stats: &mut MergeStats,
+) -> ValkeyResult<()> {
+    let results = dest
+        .merge_samples(samples, None)
+        .map_err(|_| ValkeyError::Str(error_consts::SAMPLE_MERGE_ERROR))?;
+
+    let mut added: Vec<Sample> = Vec::with_capacity(results.len());
+    for res in results.iter() {
+        match res {
+            SampleAddResult::Ok(sample) => added.push(*sample),
+            rejected => {
+                *stats
+                    .rejected_by_reason
+                    .entry(rejected.reason_code())
+                    .or_default() += 1
+            }
+        }
+    }
+
+    stats.merged += added.len();
+    stats.rejected += samples.len() - added.len();
+
+    if let Err(e) = run_compactions(ctx, dest, &added) {
+        ctx.log_warning(&format!("Failed to run compactions after TS.MERGE: {e:?}"));
+    }
+    Ok(())
+}
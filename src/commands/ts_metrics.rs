@@ -0,0 +1,45 @@
+use super::ts_labelvalues_fanout_command::LabelValuesFanoutCommand;
+use crate::commands::command_parser::parse_metrics_command_args;
+use crate::common::constants::METRIC_NAME_LABEL;
+use crate::fanout::{FanoutClientCommand, is_clustered};
+use crate::labels::filters::{LabelFilter, PredicateMatch, SeriesSelector};
+use crate::series::acl::check_metadata_permissions;
+use crate::series::index::get_timeseries_index;
+use crate::series::request_types::MatchFilterOptions;
+use valkey_module::{Context, ValkeyResult, ValkeyString, ValkeyValue};
+
+/// TS.METRICS MATCH regex [LIMIT limit]
+///
+/// Returns the distinct metric names (`__name__` values) matching `regex`, in ascending order.
+pub fn ts_metrics_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1).peekable();
+    let (name_match, limit) = parse_metrics_command_args(&mut args)?;
+
+    check_metadata_permissions(ctx)?;
+
+    if is_clustered(ctx) {
+        // every shard returns the metric names of its series matching `__name__=~regex`, and the
+        // names are merged and limited on this node
+        let filter = LabelFilter {
+            label: METRIC_NAME_LABEL.to_string(),
+            matcher: PredicateMatch::RegexEqual(name_match),
+        };
+        let options = MatchFilterOptions {
+            matchers: vec![SeriesSelector::with_filters(vec![filter])],
+            limit: Some(limit),
+            ..Default::default()
+        };
+        let operation =
+            LabelValuesFanoutCommand::new(METRIC_NAME_LABEL.to_string(), options, None, None);
+        return operation.exec(ctx);
+    }
+
+    let index = get_timeseries_index(ctx);
+    let names = index
+        .get_metric_names(&name_match, limit)
+        .into_iter()
+        .map(ValkeyValue::BulkString)
+        .collect::<Vec<_>>();
+
+    Ok(ValkeyValue::Array(names))
+}
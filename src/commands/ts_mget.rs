@@ -1,6 +1,10 @@
-use super::ts_mget_fanout_command::MGetFanoutCommand;
+use super::ts_mget_fanout_command::{MGetFanoutCommand, reply_with_mget_value};
 use crate::commands::command_parser::CommandArgToken;
-use crate::commands::{parse_command_arg_token, parse_label_list, parse_series_selector_list};
+use crate::commands::{
+    parse_command_arg_token, parse_duration_arg, parse_label_list, parse_series_selector_list,
+};
+use crate::common::replies::reply_with_array;
+use crate::common::time::current_time_millis;
 use crate::error_consts;
 use crate::fanout::{FanoutClientCommand, is_clustered};
 use crate::labels::Label;
@@ -11,7 +15,8 @@ use valkey_module::{Context, NextArg, ValkeyError, ValkeyResult, ValkeyString, V
 
 /// TS.MGET
 ///   [LATEST]
-///   [WITHLABELS | SELECTED_LABELS label...]
+///   [MAXSTALE duration]
+///   [WITHLABELS | SELECTED_LABELS label[=alias]...]
 ///   [FILTER filterExpr...]
 pub fn ts_mget_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     if args.len() < 2 {
@@ -27,9 +32,11 @@ pub fn ts_mget_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
 
     let mget_results = process_mget_request(ctx, options)?;
 
-    let result = mget_results.into_iter().map(|s| s.into()).collect();
-
-    Ok(ValkeyValue::Array(result))
+    reply_with_array(ctx, mget_results.len());
+    for series in mget_results {
+        reply_with_mget_value(ctx, &series.into());
+    }
+    Ok(ValkeyValue::NoReply)
 }
 
 /// Parsing commands with variadic args gets wonky. For example, if we have something like:
@@ -44,6 +51,7 @@ pub fn parse_mget_options(args: Vec<ValkeyString>) -> ValkeyResult<MGetRequest>
         CommandArgToken::SelectedLabels,
         CommandArgToken::Filter,
         CommandArgToken::Latest,
+        CommandArgToken::MaxStale,
         CommandArgToken::WithLabels,
     ];
 
@@ -71,7 +79,8 @@ pub fn parse_mget_options(args: Vec<ValkeyString>) -> ValkeyResult<MGetRequest>
 
         match token {
             CommandArgToken::SelectedLabels => {
-                options.selected_labels = parse_label_list(&mut args, supported_tokens)?;
+                (options.selected_labels, options.label_aliases) =
+                    parse_label_list(&mut args, supported_tokens)?;
                 if options.selected_labels.is_empty() {
                     return Err(ValkeyError::Str(
                         "TSDB: SELECT_LABELS should have at least 1 parameter",
@@ -84,6 +93,12 @@ pub fn parse_mget_options(args: Vec<ValkeyString>) -> ValkeyResult<MGetRequest>
             CommandArgToken::Latest => {
                 options.latest = true;
             }
+            CommandArgToken::MaxStale => {
+                let next = args
+                    .next_arg()
+                    .map_err(|_| ValkeyError::Str(error_consts::MISSING_MAX_STALE_VALUE))?;
+                options.max_stale = Some(parse_duration_arg(&next)?);
+            }
             CommandArgToken::Filter => {
                 return Err(ValkeyError::Str("TSDB: FILTER must be the last argument"));
             }
@@ -121,7 +136,12 @@ pub fn process_mget_request(
 ) -> ValkeyResult<Vec<MGetSeriesData>> {
     let with_labels = options.with_labels;
     let selected_labels = &options.selected_labels;
+    let label_aliases = &options.label_aliases;
     let mut series = Vec::with_capacity(8);
+    // samples before this are considered stale and returned as nil
+    let min_timestamp = options
+        .max_stale
+        .map(|max_stale| current_time_millis().saturating_sub(max_stale.as_millis() as i64));
 
     let opts: MatchFilterOptions = options.filters.into();
 
@@ -131,9 +151,17 @@ pub fn process_mget_request(
         } else {
             series.last_sample
         };
+        let sample = sample.filter(|s| min_timestamp.is_none_or(|min| s.timestamp >= min));
         let labels = get_series_labels(series, with_labels, selected_labels)
             .into_iter()
-            .map(|label| label.map(|x| Label::new(x.name, x.value)))
+            .map(|label| {
+                label.map(|x| {
+                    let name = label_aliases
+                        .get(x.name)
+                        .map_or(x.name, |alias| alias.as_str());
+                    Label::new(name, x.value)
+                })
+            })
             .collect();
 
         acc.push(MGetSeriesData {
@@ -2,16 +2,21 @@ use super::fanout::generated::{Label, MGetValue, MultiGetRequest, MultiGetRespon
 use crate::commands::fanout::filters::{deserialize_matchers_list, serialize_matchers_list};
 use crate::commands::process_mget_request;
 use crate::commands::utils::{reply_with_fanout_labels, reply_with_fanout_sample};
+use crate::common::replies::{reply_with_array, reply_with_bulk_string};
 use crate::error_consts;
 use crate::fanout::FanoutContext;
 use crate::fanout::{FanoutClientCommand, NodeInfo};
 use crate::series::request_types::MGetRequest;
+use ahash::AHashMap;
+use std::time::Duration;
 use valkey_module::{Context, Status, ValkeyError, ValkeyResult, ValkeyValue};
 
 #[derive(Debug, Default)]
 pub struct MGetFanoutCommand {
     options: MGetRequest,
     series: Vec<MGetValue>,
+    /// Position of each key in `series`, used to merge duplicate responses for the same key
+    key_index: AHashMap<String, usize>,
 }
 
 impl MGetFanoutCommand {
@@ -19,6 +24,26 @@ impl MGetFanoutCommand {
         Self {
             options,
             series: Vec::new(),
+            key_index: AHashMap::new(),
+        }
+    }
+
+    /// Adds a shard's value, keeping the one with the latest sample if the key was already
+    /// reported by another node (e.g. while its slot is being migrated).
+    fn merge_value(&mut self, value: MGetValue) {
+        match self.key_index.get(&value.key) {
+            Some(&index) => {
+                let existing = &mut self.series[index];
+                let existing_ts = existing.sample.as_ref().map(|s| s.timestamp);
+                let new_ts = value.sample.as_ref().map(|s| s.timestamp);
+                if new_ts > existing_ts {
+                    *existing = value;
+                }
+            }
+            None => {
+                self.key_index.insert(value.key.clone(), self.series.len());
+                self.series.push(value);
+            }
         }
     }
 }
@@ -39,7 +64,11 @@ impl FanoutClientCommand for MGetFanoutCommand {
             with_labels: req.with_labels,
             filters,
             selected_labels: req.selected_labels,
+            label_aliases: Default::default(),
             latest: req.latest,
+            max_stale: req
+                .max_stale
+                .map(|ms| Duration::from_millis(ms.max(0) as u64)),
         };
 
         let results = process_mget_request(ctx, mreq)?;
@@ -57,11 +86,23 @@ impl FanoutClientCommand for MGetFanoutCommand {
             filters,
             selected_labels: self.options.selected_labels.clone(),
             latest: self.options.latest,
+            max_stale: self.options.max_stale.map(|d| d.as_millis() as i64),
         }
     }
 
     fn on_response(&mut self, resp: Self::Response, _target: &NodeInfo) {
-        self.series.extend(resp.values);
+        let mut values = resp.values;
+        let aliases = &self.options.label_aliases;
+        if !aliases.is_empty() {
+            for label in values.iter_mut().flat_map(|v| v.labels.iter_mut()) {
+                if let Some(alias) = aliases.get(&label.name) {
+                    label.name = alias.clone();
+                }
+            }
+        }
+        for value in values {
+            self.merge_value(value);
+        }
     }
 
     fn reply(&mut self, ctx: &FanoutContext) -> Status {
@@ -96,10 +137,12 @@ fn fanout_label_to_reply(label: Label) -> ValkeyValue {
     if label.name.is_empty() {
         ValkeyValue::Null
     } else {
-        ValkeyValue::Array(vec![
-            ValkeyValue::BulkString(label.name),
-            ValkeyValue::BulkString(label.value),
-        ])
+        let value = if label.value.is_empty() {
+            ValkeyValue::Null
+        } else {
+            ValkeyValue::BulkString(label.value)
+        };
+        ValkeyValue::Array(vec![ValkeyValue::BulkString(label.name), value])
     }
 }
 
@@ -113,9 +156,11 @@ fn fanout_sample_to_reply(sample: Option<Sample>) -> ValkeyValue {
     }
 }
 
-pub(super) fn reply_with_mget_value(ctx: &FanoutContext, value: &MGetValue) -> Status {
-    ctx.reply_with_array(3);
-    ctx.reply_with_bulk_string(value.key.as_str());
+/// Replies with one series of a TS.MGET result. Also used for standalone replies, so that labels
+/// are encoded the same way with and without cluster mode.
+pub(super) fn reply_with_mget_value(ctx: &Context, value: &MGetValue) -> Status {
+    reply_with_array(ctx, 3);
+    reply_with_bulk_string(ctx, value.key.as_str());
     reply_with_fanout_labels(ctx, &value.labels);
     reply_with_fanout_sample(ctx, &value.sample);
     Status::Ok
@@ -2,7 +2,9 @@ use crate::commands::parse_mrange_options;
 use crate::commands::ts_mrange_fanout_command::MRangeFanoutCommand;
 use crate::error_consts;
 use crate::fanout::{FanoutClientCommand, is_clustered};
-use crate::series::mrange::process_mrange_query;
+use crate::series::mrange::{
+    mrange_page_reply, mrange_reply, process_mrange_page_query, process_mrange_query,
+};
 use valkey_module::{Context, NextArg, ValkeyError, ValkeyResult, ValkeyString};
 
 /// TS.MRANGE fromTimestamp toTimestamp
@@ -11,9 +13,11 @@ use valkey_module::{Context, NextArg, ValkeyError, ValkeyResult, ValkeyString};
 //   [FILTER_BY_VALUE min max]
 //   [WITHLABELS | <SELECTED_LABELS label...>]
 //   [COUNT count]
+//   [LIMIT totalSamples]
+//   [CURSOR cursor [PAGE_SIZE seriesCount]]
 //   [[ALIGN align] AGGREGATION aggregator bucketDuration [CONDITION op value] [BUCKETTIMESTAMP bt] [EMPTY]]
 //   FILTER filterExpr...
-//   [GROUPBY label REDUCE reducer]
+//   [GROUPBY label REDUCE reducer [WITHSOURCES]]
 pub fn ts_mrange_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     mrange_internal(ctx, args, false)
 }
@@ -35,10 +39,21 @@ fn mrange_internal(ctx: &Context, args: Vec<ValkeyString>, reverse: bool) -> Val
     args.done()?;
 
     if is_clustered(ctx) {
+        if options.cursor.is_some() {
+            return Err(ValkeyError::Str(
+                error_consts::CURSOR_NOT_SUPPORTED_IN_CLUSTER,
+            ));
+        }
         let operation = MRangeFanoutCommand::new(options);
         return operation.exec(ctx);
     }
 
+    if let Some(cursor) = options.cursor {
+        let (result_rows, next) = process_mrange_page_query(ctx, options, cursor)?;
+        return Ok(mrange_page_reply(result_rows, next));
+    }
+
+    let sample_limit = options.sample_limit;
     let result_rows = process_mrange_query(ctx, options, false)?;
-    Ok(result_rows.into())
+    Ok(mrange_reply(result_rows, sample_limit))
 }
@@ -5,9 +5,12 @@ use crate::fanout::{FanoutClientCommand, NodeInfo};
 use crate::iterators::{MultiSeriesSampleIter, create_sample_iterator_adapter};
 use crate::series::chunks::{TimeSeriesChunk, UncompressedChunk};
 use crate::series::mrange::{
-    build_mrange_grouped_labels, process_mrange_query, sort_mrange_results,
+    build_mrange_grouped_labels, format_group_key, mrange_reply, process_mrange_query,
+    sort_mrange_results,
+};
+use crate::series::request_types::{
+    MRangeOptions, MRangeSeriesResult, RangeGroupingOptions, apply_label_aliases,
 };
-use crate::series::request_types::{MRangeOptions, MRangeSeriesResult, RangeGroupingOptions};
 use orx_parallel::ParIter;
 use orx_parallel::ParIterResult;
 use orx_parallel::{IntoParIter, IterIntoParIter};
@@ -87,7 +90,7 @@ impl FanoutClientCommand for MRangeFanoutCommand {
         match result {
             Ok(mut series) => {
                 sort_mrange_results(&mut series, is_grouped);
-                ctx.reply(Ok(series.into()))
+                ctx.reply(Ok(mrange_reply(series, options.sample_limit)))
             }
             Err(e) => {
                 let warning = format!("Error processing MRange responses: {e:?}");
@@ -152,16 +155,17 @@ fn construct_group_map(series: Vec<MRangeSeriesResult>) -> BTreeMap<String, Grou
     let mut grouped = BTreeMap::new();
     for meta in series {
         if let Some(label) = meta.group_label_value.clone() {
-            grouped
-                .entry(label)
-                .or_insert_with(|| GroupData {
-                    keys: SmallVec::new(),
-                    series: Vec::new(),
-                })
-                .series
-                .push(meta);
+            let data = grouped.entry(label).or_insert_with(|| GroupData {
+                keys: SmallVec::new(),
+                series: Vec::new(),
+            });
+            data.keys.push(meta.key.clone());
+            data.series.push(meta);
         }
     }
+    for data in grouped.values_mut() {
+        data.keys.sort();
+    }
     grouped
 }
 
@@ -174,14 +178,15 @@ fn process_group(
     let samples = process_series_list(&data.series, options);
     let chunk = UncompressedChunk::from_vec(samples);
     let labels = build_mrange_grouped_labels(
-        &group_options.group_label,
+        &group_options.group_labels,
         &label,
         group_options.aggregation.aggregation_name(),
         &data.keys,
+        group_options.with_sources,
     );
 
     MRangeSeriesResult {
-        key: data.keys.join(","),
+        key: format_group_key(&group_options.group_labels, &label),
         group_label_value: Some(label),
         labels,
         data: TimeSeriesChunk::Uncompressed(chunk),
@@ -194,6 +199,7 @@ fn process_series_samples(
 ) -> MRangeSeriesResult {
     let samples = process_series_list(std::slice::from_ref(&series), options);
     series.data = TimeSeriesChunk::Uncompressed(UncompressedChunk::from_vec(samples));
+    apply_label_aliases(&mut series.labels, &options.label_aliases);
     series
 }
 
@@ -6,13 +6,13 @@ use crate::analysis::outliers::{
 use crate::analysis::seasonality::Seasonality;
 use crate::commands::{
     CommandArgIterator, CommandArgToken, parse_command_arg_token, parse_duration_ms,
-    parse_timestamp_range,
+    parse_series_timestamp_bounds,
 };
 use crate::common::Sample;
 use crate::common::hash::IntSet;
 use crate::common::threads::spawn;
 use crate::error_consts;
-use crate::series::{TimestampRange, get_timeseries};
+use crate::series::get_timeseries;
 use std::collections::HashMap;
 use valkey_module::redisvalue::ValkeyValueKey;
 use valkey_module::{
@@ -47,6 +47,8 @@ enum ZScoreType {
 ///     [OUTPUT <full|simple|cleaned>]
 ///     [DIRECTION <positive|negative|both>]
 ///     [SEASONALITY <period1> [period2] ...]
+///
+/// The timestamps are in the unit of the series, and so are the ones returned.
 pub fn ts_outliers_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     if args.len() < 6 {
         return Err(ValkeyError::WrongArity);
@@ -55,8 +57,9 @@ pub fn ts_outliers_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let mut args = args.into_iter().skip(1).peekable();
 
     let key = args.next_arg()?;
-    // Parse timestamps
-    let date_range = parse_timestamp_range(&mut args)?;
+    // parsed once the series is known, since the timestamps are given in its unit
+    let start_arg = args.next_string()?;
+    let end_arg = args.next_string().ok();
 
     let mut anomaly_direction = AnomalyDirection::Both;
     let mut output_format = OutputFormat::Simple;
@@ -99,7 +102,8 @@ pub fn ts_outliers_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     process_request(
         ctx,
         key.to_vec(),
-        date_range,
+        start_arg,
+        end_arg,
         options,
         anomaly_direction,
         output_format,
@@ -109,7 +113,8 @@ pub fn ts_outliers_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
 fn process_request(
     ctx: &Context,
     key: Vec<u8>,
-    date_range: TimestampRange,
+    start_arg: String,
+    end_arg: Option<String>,
     options: AnomalyOptions,
     anomaly_direction: AnomalyDirection,
     output_format: OutputFormat,
@@ -122,10 +127,19 @@ fn process_request(
             let ctx = thread_ctx.lock();
             let key = ctx.create_string(key);
             match get_timeseries(&ctx, &key, Some(AclPermissions::ACCESS), false) {
-                Ok(Some(series)) => {
+                Ok(Some(series)) => parse_series_timestamp_bounds(
+                    &start_arg,
+                    end_arg.as_deref(),
+                    series.timestamp_unit,
+                )
+                .map(|date_range| {
                     let (start, end) = date_range.get_series_range(&series, None, false);
-                    Ok(series.get_range(start, end))
-                }
+                    series
+                        .get_range(start, end)
+                        .into_iter()
+                        .map(|sample| series.to_client_sample(sample))
+                        .collect::<Vec<_>>()
+                }),
                 Ok(None) => Err(ValkeyError::Str(error_consts::KEY_NOT_FOUND)),
                 Err(e) => Err(e),
             }
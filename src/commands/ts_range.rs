@@ -1,6 +1,11 @@
-use crate::commands::command_parser::parse_range_options;
-use crate::iterators::TimeSeriesRangeIterator;
-use crate::series::get_timeseries;
+use crate::commands::command_parser::{
+    CommandArgToken, parse_command_arg_token, parse_range_command_options,
+};
+use crate::common::Sample;
+use crate::common::replies::{is_resp3, reply_with_attribute_usize};
+use crate::iterators::{TimeSeriesRangeIterator, aggregate_range_multi};
+use crate::series::request_types::{AggregatorConfig, RangeOptions};
+use crate::series::{TimeSeries, dedup_runs, get_timeseries, lttb_downsample};
 use valkey_module::{
     AclPermissions, Context, NextArg, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
 };
@@ -9,8 +14,13 @@ use valkey_module::{
 //   [LATEST]
 //   [FILTER_BY_TS ts...]
 //   [FILTER_BY_VALUE min max]
-//   [COUNT count]
-//   [[ALIGN align] AGGREGATION aggregator bucketDuration [CONDITION op value] [BUCKETTIMESTAMP bt] [EMPTY]]
+//   [COUNT count [FROM END]]
+//   [MAXPOINTS n]
+//   [FUNCTION abs|log|log2|log10|sqrt|exp]
+//   [TRANSFORM [SCALE scale] [OFFSET offset] [BEFORE | AFTER]]
+//   [[ALIGN align] AGGREGATION aggregator|[aggregator...] bucketDuration [CONDITION op value] [BUCKETTIMESTAMP bt] [EMPTY] [WITHCOUNTS] [DEDUP [NAN_DISTINCT]]]
+//   [WITHANNOTATIONS]
+//   [INTCAST]
 pub fn ts_range_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     range_internal(ctx, args, false)
 }
@@ -19,8 +29,13 @@ pub fn ts_range_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
 //   [LATEST]
 //   [FILTER_BY_TS ts...]
 //   [FILTER_BY_VALUE min max]
-//   [COUNT count]
-//   [[ALIGN align] AGGREGATION aggregator bucket_duration [CONDITION op value] [BUCKETTIMESTAMP bt] [EMPTY]]
+//   [COUNT count [FROM END]]
+//   [MAXPOINTS n]
+//   [FUNCTION abs|log|log2|log10|sqrt|exp]
+//   [TRANSFORM [SCALE scale] [OFFSET offset] [BEFORE | AFTER]]
+//   [[ALIGN align] AGGREGATION aggregator|[aggregator...] bucket_duration [CONDITION op value] [BUCKETTIMESTAMP bt] [EMPTY] [WITHCOUNTS] [DEDUP [NAN_DISTINCT]]]
+//   [WITHANNOTATIONS]
+//   [INTCAST]
 pub fn ts_revrange_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     range_internal(ctx, args, true)
 }
@@ -29,22 +44,171 @@ fn range_internal(ctx: &Context, args: Vec<ValkeyString>, is_reverse: bool) -> V
     if args.len() < 4 {
         return Err(ValkeyError::WrongArity);
     }
+    let mut args = args;
+    let with_annotations = take_token(&mut args, CommandArgToken::WithAnnotations);
+    let int_cast = take_token(&mut args, CommandArgToken::IntCast);
     let mut args = args.into_iter().skip(1).peekable();
 
     let key = args.next_arg()?;
-    let options = parse_range_options(&mut args)?;
-
-    args.done()?;
 
     // In both cases we pass true for must_exist, meaning that if the series does not exist, we will
     // propagate an error. Because of this, unwrap is safe to use here.
     let series = get_timeseries(ctx, &key, Some(AclPermissions::ACCESS), true)?.unwrap();
-    let iter = TimeSeriesRangeIterator::new(Some(ctx), &series, &options, is_reverse);
+    // the series is needed first, since timestamps are given in its unit
+    let unit = series.timestamp_unit;
+
+    let (options, extra_aggregations) = parse_range_command_options(&mut args, unit)?;
+
+    args.done()?;
+
+    let reply = range_reply(
+        ctx,
+        &series,
+        &options,
+        &extra_aggregations,
+        is_reverse,
+        int_cast,
+    );
+
+    // RESP3 clients get the number of buckets up front, e.g. to size their buffers. It is taken
+    // from the reply, so filters, COUNT, dedup and skipped empty buckets are accounted for.
+    if options.aggregation.is_some()
+        && is_resp3(ctx)
+        && let ValkeyValue::Array(buckets) = &reply
+    {
+        reply_with_attribute_usize(ctx, "bucketCount", buckets.len());
+    }
+
+    if !with_annotations {
+        return Ok(reply);
+    }
+
+    Ok(ValkeyValue::Array(vec![
+        reply,
+        annotations_reply(&series, &options, is_reverse),
+    ]))
+}
 
-    let samples = iter
+/// WITHANNOTATIONS and INTCAST only apply to single series queries, so they are taken out before
+/// the range options shared with TS.MRANGE are parsed.
+fn take_token(args: &mut Vec<ValkeyString>, token: CommandArgToken) -> bool {
+    // skip the command name, key and range
+    let Some(pos) = args
+        .iter()
+        .skip(4)
+        .position(|arg| parse_command_arg_token(arg.as_slice()) == Some(token))
+    else {
+        return false;
+    };
+    args.remove(pos + 4);
+    true
+}
+
+/// Whether `value` converts to an i64 without loss.
+fn is_integral(value: f64) -> bool {
+    // i64::MAX rounds up to 2^63 as a float, which is out of range
+    value.is_finite()
+        && value == value.floor()
+        && value >= i64::MIN as f64
+        && value < i64::MAX as f64
+}
+
+/// With INTCAST, values are replied as integers, but only if every value in the reply converts
+/// without loss. Otherwise, the whole reply keeps using doubles, so that a client sees a single
+/// type per reply.
+fn value_reply(value: f64, as_integer: bool) -> ValkeyValue {
+    if as_integer {
+        ValkeyValue::Integer(value as i64)
+    } else {
+        ValkeyValue::from(value)
+    }
+}
+
+/// The annotations within the range as `[timestamp, text]` pairs, in the order of the samples.
+fn annotations_reply(series: &TimeSeries, options: &RangeOptions, is_reverse: bool) -> ValkeyValue {
+    let (start, end) = options.date_range.get_series_range(series, None, true);
+    let unit = series.timestamp_unit;
+    let to_reply = |(ts, text): (i64, &str)| {
+        ValkeyValue::Array(vec![
+            ValkeyValue::Integer(unit.from_millis(ts)),
+            ValkeyValue::BulkString(text.to_string()),
+        ])
+    };
+    let annotations = series.annotations_in_range(start, end);
+    let annotations = if is_reverse {
+        annotations.rev().map(to_reply).collect()
+    } else {
+        annotations.map(to_reply).collect()
+    };
+    ValkeyValue::Array(annotations)
+}
+
+fn range_reply(
+    ctx: &Context,
+    series: &TimeSeries,
+    options: &RangeOptions,
+    extra_aggregations: &[AggregatorConfig],
+    is_reverse: bool,
+    int_cast: bool,
+) -> ValkeyValue {
+    let unit = series.timestamp_unit;
+    let with_counts = options.aggregation.is_some_and(|agg| agg.with_counts);
+    let dedup = options.aggregation.and_then(|agg| agg.dedup);
+    if !extra_aggregations.is_empty() || with_counts {
+        // one row per bucket: [timestamp, [value per aggregation]], or [timestamp, value] for a
+        // single aggregation, followed by the sample count of the bucket if WITHCOUNTS is given
+        let multiple = !extra_aggregations.is_empty();
+        let mut rows =
+            aggregate_range_multi(Some(ctx), series, options, extra_aggregations, is_reverse);
+        if let Some(dedup) = dedup {
+            rows = dedup_runs(rows, dedup, |row| &row.values);
+        }
+        let as_integer = int_cast
+            && rows
+                .iter()
+                .all(|row| row.values.iter().all(|v| is_integral(*v)));
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                let mut reply = vec![ValkeyValue::from(unit.from_millis(row.timestamp))];
+                if multiple {
+                    let values = row
+                        .values
+                        .into_iter()
+                        .map(|v| value_reply(v, as_integer))
+                        .collect();
+                    reply.push(ValkeyValue::Array(values));
+                } else {
+                    reply.push(value_reply(row.values[0], as_integer));
+                }
+                if let Some(count) = row.count {
+                    reply.push(ValkeyValue::Integer(count as i64));
+                }
+                ValkeyValue::Array(reply)
+            })
+            .collect::<Vec<_>>();
+        return ValkeyValue::Array(rows);
+    }
+    let iter = TimeSeriesRangeIterator::new(Some(ctx), series, options, is_reverse);
+
+    let mut samples: Vec<Sample> = iter.collect();
+    if let Some(max_points) = options.max_points {
+        samples = lttb_downsample(samples, max_points);
+    }
+    if let Some(dedup) = dedup {
+        samples = dedup_runs(samples, dedup, |sample| std::slice::from_ref(&sample.value));
+    }
+    let as_integer = int_cast && samples.iter().all(|sample| is_integral(sample.value));
+    let samples = samples
         .into_iter()
-        .map(|x| x.into())
+        .map(|x| {
+            let sample = series.to_client_sample(x);
+            ValkeyValue::Array(vec![
+                ValkeyValue::Integer(sample.timestamp),
+                value_reply(sample.value, as_integer),
+            ])
+        })
         .collect::<Vec<ValkeyValue>>();
 
-    Ok(ValkeyValue::from(samples))
+    ValkeyValue::from(samples)
 }
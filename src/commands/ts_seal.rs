@@ -0,0 +1,27 @@
+use crate::series::with_timeseries_mut;
+use valkey_module::{
+    AclPermissions, Context, NotifyEvent, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue,
+};
+
+///
+/// TS.SEAL key
+///
+/// Optimizes the chunk currently receiving appends and starts a new one for subsequent samples.
+/// Returns 1 if a chunk was sealed, and 0 if the series is empty or was already sealed.
+pub fn ts_seal_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() != 2 {
+        return Err(ValkeyError::WrongArity);
+    }
+    let key = &args[1];
+
+    let sealed = with_timeseries_mut(ctx, key, Some(AclPermissions::UPDATE), |series| {
+        Ok(series.seal())
+    })?;
+
+    if sealed {
+        ctx.replicate_verbatim();
+        ctx.notify_keyspace_event(NotifyEvent::MODULE, "ts.seal", key);
+    }
+
+    Ok(ValkeyValue::Integer(sealed as i64))
+}
@@ -0,0 +1,28 @@
+use crate::commands::command_parser::parse_series_diff_args;
+use crate::commands::ts_seriesdiff_fanout_command::SeriesDiffFanoutCommand;
+use crate::fanout::{FanoutClientCommand, is_clustered};
+use crate::series::index::series_keys_by_selector_diff;
+use valkey_module::ValkeyError::WrongArity;
+use valkey_module::{Context, ValkeyResult, ValkeyString, ValkeyValue};
+
+/// TS.SERIESDIFF <DIFF | XOR> selectorA selectorB
+pub fn ts_seriesdiff_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() != 4 {
+        return Err(WrongArity);
+    }
+    let mut args = args.into_iter().skip(1).peekable();
+
+    let options = parse_series_diff_args(&mut args)?;
+
+    if is_clustered(ctx) {
+        // a series lives on exactly one shard, so the union of the per-shard
+        // differences is the difference across the cluster
+        let operation = SeriesDiffFanoutCommand::new(options);
+        return operation.exec(ctx);
+    }
+
+    let mut keys = series_keys_by_selector_diff(ctx, &options.left, &options.right, options.mode)?;
+
+    keys.sort_unstable();
+    Ok(ValkeyValue::from(keys))
+}
@@ -0,0 +1,73 @@
+use super::fanout::{IndexQueryResponse, SeriesDiffRequest};
+use crate::fanout::FanoutContext;
+use crate::fanout::{FanoutClientCommand, NodeInfo};
+use crate::labels::filters::SeriesSelector;
+use crate::series::index::series_keys_by_selector_diff;
+use crate::series::request_types::{SeriesDiffMode, SeriesDiffOptions};
+use std::collections::BTreeSet;
+use valkey_module::{Context, Status, ValkeyError, ValkeyResult};
+
+#[derive(Clone, Debug, Default)]
+pub struct SeriesDiffFanoutCommand {
+    options: SeriesDiffOptions,
+    keys: BTreeSet<String>,
+}
+
+impl SeriesDiffFanoutCommand {
+    pub fn new(options: SeriesDiffOptions) -> Self {
+        Self {
+            options,
+            keys: BTreeSet::new(),
+        }
+    }
+}
+
+impl FanoutClientCommand for SeriesDiffFanoutCommand {
+    type Request = SeriesDiffRequest;
+    type Response = IndexQueryResponse;
+
+    fn name() -> &'static str {
+        "series_diff"
+    }
+
+    fn get_local_response(
+        ctx: &Context,
+        req: SeriesDiffRequest,
+    ) -> ValkeyResult<IndexQueryResponse> {
+        let (Some(left), Some(right)) = (&req.left, &req.right) else {
+            return Err(ValkeyError::Str("TSDB: missing filters"));
+        };
+        let left = SeriesSelector::try_from(left)?;
+        let right = SeriesSelector::try_from(right)?;
+        let mode = if req.symmetric {
+            SeriesDiffMode::Xor
+        } else {
+            SeriesDiffMode::Diff
+        };
+        let keys = series_keys_by_selector_diff(ctx, &left, &right, mode)?;
+        let keys = keys.into_iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        Ok(IndexQueryResponse { keys })
+    }
+
+    fn generate_request(&self) -> SeriesDiffRequest {
+        SeriesDiffRequest {
+            left: Some((&self.options.left).into()),
+            right: Some((&self.options.right).into()),
+            symmetric: self.options.mode == SeriesDiffMode::Xor,
+        }
+    }
+
+    fn on_response(&mut self, resp: Self::Response, _target: &NodeInfo) {
+        for key in resp.keys {
+            self.keys.insert(key);
+        }
+    }
+
+    fn reply(&mut self, ctx: &FanoutContext) -> Status {
+        ctx.reply_with_array(self.keys.len());
+        for key in self.keys.iter() {
+            ctx.reply_with_bulk_string(key);
+        }
+        Status::Ok
+    }
+}
@@ -0,0 +1,53 @@
+use crate::commands::command_parser::{CommandArgToken, parse_command_arg_token};
+use crate::error_consts;
+use crate::series::index::{IndexVerifyStats, get_timeseries_index};
+use std::collections::HashMap;
+use valkey_module::{Context, NextArg, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
+
+///
+/// TS.VERIFYINDEX [REPAIR]
+///
+/// Compares the series in the current database with its index and reports the discrepancies
+/// found. Read-only unless `REPAIR` is given, in which case orphaned ids are marked stale.
+pub fn ts_verifyindex_cmd(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1).peekable();
+
+    let mut repair = false;
+    if let Some(arg) = args.next() {
+        match parse_command_arg_token(arg.as_slice()) {
+            Some(CommandArgToken::Repair) => repair = true,
+            _ => return Err(ValkeyError::Str(error_consts::INVALID_ARGUMENT)),
+        }
+    }
+    args.done()?;
+
+    let index = get_timeseries_index(ctx);
+    let stats = index.verify(ctx, repair);
+
+    Ok(verify_stats_reply(&stats))
+}
+
+fn verify_stats_reply(stats: &IndexVerifyStats) -> ValkeyValue {
+    let mut map = HashMap::with_capacity(5);
+    map.insert(
+        "seriesChecked".into(),
+        ValkeyValue::Integer(stats.series_checked as i64),
+    );
+    map.insert(
+        "orphanedIds".into(),
+        ValkeyValue::Integer(stats.orphaned_ids as i64),
+    );
+    map.insert(
+        "missingPostings".into(),
+        ValkeyValue::Integer(stats.missing_postings as i64),
+    );
+    map.insert(
+        "keyMismatches".into(),
+        ValkeyValue::Integer(stats.key_mismatches as i64),
+    );
+    map.insert(
+        "repaired".into(),
+        ValkeyValue::Integer(stats.repaired as i64),
+    );
+    ValkeyValue::Map(map)
+}
@@ -1,20 +1,33 @@
 use super::fanout::generated::{Label as FanoutLabel, Sample as FanoutSample};
 use crate::common::replies::{
-    reply_label_ex, reply_with_array, reply_with_bulk_string, reply_with_labels,
-    reply_with_sample_ex, reply_with_samples,
+    is_resp3, reply_label, reply_with_array, reply_with_bulk_string, reply_with_label_value,
+    reply_with_labels, reply_with_sample_ex, reply_with_samples,
 };
 use crate::series::request_types::MRangeSeriesResult;
+use std::os::raw::c_long;
 use valkey_module::{Context, ValkeyResult, ValkeyValue, raw};
 
+/// Replies with a label received from a shard, encoded as `reply_with_labels` does for local
+/// labels. A placeholder (empty name) for a selected label the series does not have is sent as null.
 pub(super) fn reply_with_fanout_label(ctx: &Context, label: &FanoutLabel) {
     if label.name.is_empty() {
         raw::reply_with_null(ctx.ctx);
         return;
     }
-    reply_label_ex(ctx, &label.name, Some(&label.value));
+    reply_label(ctx, &label.name, &label.value);
 }
 
 pub(super) fn reply_with_fanout_labels(ctx: &Context, v: &[FanoutLabel]) {
+    if is_resp3(ctx) {
+        // placeholders (empty names) have no key, so they are omitted from the map
+        let count = v.iter().filter(|label| !label.name.is_empty()).count();
+        raw::reply_with_map(ctx.ctx, count as c_long);
+        for label in v.iter().filter(|label| !label.name.is_empty()) {
+            reply_with_bulk_string(ctx, &label.name);
+            reply_with_label_value(ctx, &label.value);
+        }
+        return;
+    }
     reply_with_array(ctx, v.len());
     for label in v {
         reply_with_fanout_label(ctx, label);
@@ -5,6 +5,7 @@ pub const METRIC_NAME_LABEL: &str = "__name__";
 
 pub const REDUCER_KEY: &str = "__reducer__";
 pub const SOURCE_KEY: &str = "__source__";
+pub const SOURCES_KEY: &str = "__sources__";
 pub static META_KEY_LABEL: &str = "__meta:key__";
 
 pub const MILLIS_PER_SEC: u64 = 1000;
@@ -30,32 +30,33 @@ pub fn humanize(v: f64) -> String {
     format!("{v:.4}{prefix}")
 }
 
+/// Formats a duration in milliseconds using the units accepted by the duration parser, from
+/// weeks down to milliseconds, e.g. `1h30m` or `-2d`.
 pub fn humanize_duration_ms(v: i64) -> String {
-    let mut v = v;
+    const UNITS: [(&str, u64); 6] = [
+        ("w", 7 * 24 * 60 * 60 * 1000),
+        ("d", 24 * 60 * 60 * 1000),
+        ("h", 60 * 60 * 1000),
+        ("m", 60 * 1000),
+        ("s", 1000),
+        ("ms", 1),
+    ];
+
     if v == 0 {
-        return format!("{v}ms");
+        return "0ms".to_string();
     }
-    let mut prefix: &str;
-    if v.abs() >= 1_000 {
-        prefix = "";
-        for p in ["s", "m", "h", "d", "y"] {
-            if v.abs() < 1_000 {
-                break;
-            }
-            prefix = p;
-            v /= 1_000;
-        }
-        return format!("{v}{prefix}");
+    let mut result = String::new();
+    if v < 0 {
+        result.push('-');
     }
-    prefix = "";
-    for p in ["ms", "us", "ns", "ps", "fs", "as", "zs", "ys"] {
-        if v.abs() >= 1_000 {
-            break;
+    let mut remaining = v.unsigned_abs();
+    for (suffix, unit_ms) in UNITS {
+        if remaining >= unit_ms {
+            result.push_str(&format!("{}{suffix}", remaining / unit_ms));
+            remaining %= unit_ms;
         }
-        prefix = p;
-        v *= 1_000;
     }
-    format!("{v}{prefix}")
+    result
 }
 
 pub fn humanize_duration(v: &Duration) -> String {
@@ -98,3 +99,31 @@ pub fn human_readable_size(size: usize) -> String {
     };
     format!("{value:.1} {unit}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_duration_value;
+
+    #[test]
+    fn test_humanize_duration_ms() {
+        assert_eq!(humanize_duration_ms(0), "0ms");
+        assert_eq!(humanize_duration_ms(250), "250ms");
+        assert_eq!(humanize_duration_ms(1500), "1s500ms");
+        assert_eq!(humanize_duration_ms(60 * 60 * 1000), "1h");
+        assert_eq!(humanize_duration_ms(90 * 60 * 1000), "1h30m");
+        assert_eq!(humanize_duration_ms(-2 * 24 * 60 * 60 * 1000), "-2d");
+        assert_eq!(humanize_duration_ms(15 * 24 * 60 * 60 * 1000), "2w1d");
+        for ms in [
+            1,
+            999,
+            61_001,
+            3_600_000,
+            93_784_005,
+            1_209_600_000,
+            i64::MAX / 4,
+        ] {
+            assert_eq!(parse_duration_value(&humanize_duration_ms(ms)).unwrap(), ms);
+        }
+    }
+}
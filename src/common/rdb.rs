@@ -237,6 +237,10 @@ pub(crate) fn rdb_save_rounding(rdb: *mut RedisModuleIO, rounding: &RoundingStra
             rdb_save_u8(rdb, 2);
             rdb_save_u8(rdb, *digits)
         }
+        RoundingStrategy::Multiple(step) => {
+            rdb_save_u8(rdb, 3);
+            raw::save_double(rdb, *step)
+        }
     }
 }
 
@@ -251,6 +255,10 @@ pub(crate) fn rdb_load_rounding(rdb: *mut RedisModuleIO) -> ValkeyResult<Roundin
             let digits = rdb_load_u8(rdb)?;
             Ok(RoundingStrategy::DecimalDigits(digits))
         }
+        3 => {
+            let step = raw::load_double(rdb)?;
+            Ok(RoundingStrategy::Multiple(step))
+        }
         _ => Err(ValkeyError::String(format!(
             "Invalid rounding marker: {marker}"
         ))),
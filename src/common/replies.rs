@@ -1,6 +1,6 @@
 use crate::common::{Sample, Timestamp};
 use crate::labels::Label;
-use std::os::raw::{c_char, c_long};
+use std::os::raw::{c_char, c_int, c_long};
 use std::{collections::BTreeSet, ffi::CString};
 use valkey_module::redisvalue::ValkeyValueKey;
 use valkey_module::{
@@ -48,7 +48,7 @@ pub fn reply_with_string_iter<C: IntoRawCtx>(ctx: C, v: impl Iterator<Item = Str
         reply_with_bulk_string(raw_ctx, &s);
         len += 1;
     }
-    raw::reply_with_array(raw_ctx, len as c_long);
+    reply_set_array_length(raw_ctx, len);
 }
 
 pub fn reply_with_btree_set<C: IntoRawCtx>(ctx: C, v: &BTreeSet<String>) {
@@ -75,14 +75,51 @@ pub fn reply_label<C: IntoRawCtx>(ctx: C, label: &str, value: &str) {
     reply_label_ex(ctx, label, value);
 }
 
+/// Returns true if the client attached to the context negotiated RESP3 (via HELLO 3).
+pub fn is_resp3<C: IntoRawCtx>(ctx: C) -> bool {
+    let raw_ctx = ctx.into_raw();
+    let flags = unsafe { raw::RedisModule_GetContextFlags.unwrap()(raw_ctx) };
+    (flags & raw::REDISMODULE_CTX_FLAGS_RESP3 as c_int) != 0
+}
+
+/// Replies with a label value. An empty value, e.g. for a selected label the series does not
+/// have, is sent as null.
+pub fn reply_with_label_value<C: IntoRawCtx>(ctx: C, value: &str) {
+    let raw_ctx = ctx.into_raw();
+    if value.is_empty() {
+        raw::reply_with_null(raw_ctx);
+    } else {
+        reply_with_bulk_string(raw_ctx, value);
+    }
+}
+
+/// Replies with labels as an array of `[name, value]` pairs, or as a `name => value` map when
+/// the client uses RESP3. Empty values are reported as null in both cases.
 pub fn reply_with_labels<C: IntoRawCtx>(ctx: C, labels: &[Label]) {
     let raw_ctx = ctx.into_raw();
+    if is_resp3(raw_ctx) {
+        raw::reply_with_map(raw_ctx, labels.len() as c_long);
+        for label in labels {
+            reply_with_bulk_string(raw_ctx, &label.name);
+            reply_with_label_value(raw_ctx, &label.value);
+        }
+        return;
+    }
     reply_with_array(raw_ctx, labels.len());
     for label in labels {
         reply_label(raw_ctx, &label.name, &label.value);
     }
 }
 
+/// Sends a RESP3 attribute holding a single `name => value` entry. Attributes carry metadata about
+/// the reply that follows them, and clients not interested in them skip them. RESP3 only.
+pub fn reply_with_attribute_usize<C: IntoRawCtx>(ctx: C, name: &str, value: usize) {
+    let raw_ctx = ctx.into_raw();
+    raw::reply_with_attribute(raw_ctx, 1);
+    reply_with_bulk_string(raw_ctx, name);
+    reply_with_usize(raw_ctx, value);
+}
+
 pub fn reply_with_sample_ex<C: IntoRawCtx>(ctx: C, timestamp: Timestamp, value: f64) {
     let raw_ctx = ctx.into_raw();
     reply_with_array(raw_ctx, 2);
@@ -105,7 +142,7 @@ pub fn reply_with_samples<C: IntoRawCtx>(ctx: C, samples: impl Iterator<Item = S
         len += 1;
     }
 
-    reply_with_array(raw_ctx, len);
+    reply_set_array_length(raw_ctx, len);
 }
 
 pub fn reply_with_i64<C: IntoRawCtx>(ctx: C, value: i64) -> Status {
@@ -175,6 +212,16 @@ pub fn reply_with_postponed_array<C: IntoRawCtx>(ctx: C) -> Status {
     raw::reply_with_array(raw_ctx, VALKEYMODULE_POSTPONED_ARRAY_LEN as c_long)
 }
 
+/// Sets the length of the innermost array opened with [`reply_with_postponed_array`].
+pub fn reply_set_array_length<C: IntoRawCtx>(ctx: C, len: usize) -> Status {
+    let raw_ctx = ctx.into_raw();
+    let Some(set_array_length) = (unsafe { raw::RedisModule_ReplySetArrayLength }) else {
+        return Status::Err;
+    };
+    unsafe { set_array_length(raw_ctx, len as c_long) };
+    Status::Ok
+}
+
 pub fn reply_with_key<C: IntoRawCtx>(ctx: C, result: ValkeyValueKey) -> Status {
     let raw_ctx = ctx.into_raw();
     match result {
@@ -297,3 +344,108 @@ pub fn reply<C: IntoRawCtx>(ctx: C, result: ValkeyResult) -> Status {
         Err(ValkeyError::Str(s)) => reply_error_string(raw_ctx, s),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Debug, PartialEq)]
+    enum ReplyCall {
+        Array(c_long),
+        SetArrayLength(c_long),
+        LongLong(i64),
+        Double(f64),
+        BulkString(String),
+    }
+
+    thread_local! {
+        static CALLS: RefCell<Vec<ReplyCall>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(call: ReplyCall) -> c_int {
+        CALLS.with(|calls| calls.borrow_mut().push(call));
+        raw::REDISMODULE_OK as c_int
+    }
+
+    fn take_calls() -> Vec<ReplyCall> {
+        CALLS.with(|calls| calls.take())
+    }
+
+    unsafe extern "C" fn mock_array(_ctx: *mut raw::RedisModuleCtx, len: c_long) -> c_int {
+        record(ReplyCall::Array(len))
+    }
+
+    unsafe extern "C" fn mock_set_array_length(_ctx: *mut raw::RedisModuleCtx, len: c_long) {
+        record(ReplyCall::SetArrayLength(len));
+    }
+
+    unsafe extern "C" fn mock_long_long(_ctx: *mut raw::RedisModuleCtx, ll: i64) -> c_int {
+        record(ReplyCall::LongLong(ll))
+    }
+
+    unsafe extern "C" fn mock_double(_ctx: *mut raw::RedisModuleCtx, d: f64) -> c_int {
+        record(ReplyCall::Double(d))
+    }
+
+    unsafe extern "C" fn mock_string_buffer(
+        _ctx: *mut raw::RedisModuleCtx,
+        buf: *const c_char,
+        len: usize,
+    ) -> c_int {
+        let bytes = unsafe { std::slice::from_raw_parts(buf.cast::<u8>(), len) };
+        record(ReplyCall::BulkString(
+            String::from_utf8_lossy(bytes).into_owned(),
+        ))
+    }
+
+    /// Replaces the reply functions of the module API with ones recording their calls, so the
+    /// shape of a reply can be checked without a server.
+    fn install_reply_mocks() {
+        unsafe {
+            raw::RedisModule_ReplyWithArray = Some(mock_array);
+            raw::RedisModule_ReplySetArrayLength = Some(mock_set_array_length);
+            raw::RedisModule_ReplyWithLongLong = Some(mock_long_long);
+            raw::RedisModule_ReplyWithDouble = Some(mock_double);
+            raw::RedisModule_ReplyWithStringBuffer = Some(mock_string_buffer);
+        }
+    }
+
+    #[test]
+    fn test_postponed_arrays_are_closed_with_their_length() {
+        use ReplyCall::*;
+
+        install_reply_mocks();
+        let ctx: *mut raw::RedisModuleCtx = std::ptr::null_mut();
+        let postponed = VALKEYMODULE_POSTPONED_ARRAY_LEN as c_long;
+
+        let samples = [Sample::new(1, 1.5), Sample::new(2, 2.5)];
+        reply_with_samples(ctx, samples.into_iter());
+        assert_eq!(
+            take_calls(),
+            vec![
+                Array(postponed),
+                Array(2),
+                LongLong(1),
+                Double(1.5),
+                Array(2),
+                LongLong(2),
+                Double(2.5),
+                SetArrayLength(2),
+            ]
+        );
+
+        let strings = ["a", "b", "c"].map(String::from);
+        reply_with_string_iter(ctx, strings.into_iter());
+        assert_eq!(
+            take_calls(),
+            vec![
+                Array(postponed),
+                BulkString("a".to_string()),
+                BulkString("b".to_string()),
+                BulkString("c".to_string()),
+                SetArrayLength(3),
+            ]
+        );
+    }
+}
@@ -1,14 +1,18 @@
+use crate::common::hash::hash_f64;
 use get_size2::GetSize;
 use std::f64;
 use std::fmt::Display;
+use std::hash::Hash;
 
 pub const MAX_SIGNIFICANT_DIGITS: u8 = 16;
 pub const MAX_DECIMAL_DIGITS: u8 = 16;
 
-#[derive(Clone, Debug, Hash, PartialEq, Copy, GetSize)]
+#[derive(Clone, Debug, PartialEq, Copy, GetSize)]
 pub enum RoundingStrategy {
     SignificantDigits(u8),
     DecimalDigits(u8),
+    /// Snap values to the nearest multiple of the (positive) step
+    Multiple(f64),
 }
 
 impl RoundingStrategy {
@@ -16,6 +20,18 @@ impl RoundingStrategy {
         match self {
             RoundingStrategy::SignificantDigits(digits) => round_to_sig_figs(value, *digits),
             RoundingStrategy::DecimalDigits(digits) => round_to_decimal_digits(value, *digits),
+            RoundingStrategy::Multiple(step) => round_to_multiple(value, *step),
+        }
+    }
+}
+
+impl Hash for RoundingStrategy {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            RoundingStrategy::SignificantDigits(digits) => digits.hash(state),
+            RoundingStrategy::DecimalDigits(digits) => digits.hash(state),
+            RoundingStrategy::Multiple(step) => hash_f64(*step, state),
         }
     }
 }
@@ -27,6 +43,7 @@ impl Display for RoundingStrategy {
                 write!(f, "significant_digits({digits})")
             }
             RoundingStrategy::DecimalDigits(digits) => write!(f, "decimal_digits({digits})"),
+            RoundingStrategy::Multiple(step) => write!(f, "multiple({step})"),
         }
     }
 }
@@ -69,6 +86,17 @@ pub fn round_to_sig_figs(value: f64, digits: u8) -> f64 {
     (value * magnitude).round() / magnitude
 }
 
+/// Rounds a value to the nearest multiple of `step`. Ties are rounded away from zero, so the
+/// result is symmetric for negative values.
+///
+/// Non-finite values and non-positive steps leave the value unchanged.
+pub fn round_to_multiple(value: f64, step: f64) -> f64 {
+    if !value.is_finite() || !step.is_finite() || step <= 0.0 {
+        return value;
+    }
+    (value / step).round() * step
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,4 +182,38 @@ mod tests {
         assert_eq!(round_to_sig_figs(-123.456, 2), -120.0);
         assert_eq!(round_to_sig_figs(123456789.0, 2), 120000000.0);
     }
+
+    #[test]
+    fn test_round_to_multiple() {
+        assert_eq!(round_to_multiple(1.1, 0.25), 1.0);
+        assert_eq!(round_to_multiple(1.2, 0.25), 1.25);
+        assert_eq!(round_to_multiple(7.0, 5.0), 5.0);
+        assert_eq!(round_to_multiple(0.0, 0.25), 0.0);
+    }
+
+    #[test]
+    fn test_round_to_multiple_negative_values() {
+        assert_eq!(round_to_multiple(-1.1, 0.25), -1.0);
+        assert_eq!(round_to_multiple(-1.2, 0.25), -1.25);
+        assert_eq!(round_to_multiple(-7.6, 5.0), -10.0);
+    }
+
+    #[test]
+    fn test_round_to_multiple_boundaries() {
+        // exact multiples are unchanged
+        assert_eq!(round_to_multiple(0.75, 0.25), 0.75);
+        assert_eq!(round_to_multiple(-0.75, 0.25), -0.75);
+        // halfway values round away from zero
+        assert_eq!(round_to_multiple(0.125, 0.25), 0.25);
+        assert_eq!(round_to_multiple(-0.125, 0.25), -0.25);
+        assert_eq!(round_to_multiple(2.5, 5.0), 5.0);
+    }
+
+    #[test]
+    fn test_round_to_multiple_special_cases() {
+        assert!(round_to_multiple(f64::NAN, 0.25).is_nan());
+        assert_eq!(round_to_multiple(f64::INFINITY, 0.25), f64::INFINITY);
+        assert_eq!(round_to_multiple(1.1, 0.0), 1.1);
+        assert_eq!(round_to_multiple(1.1, -0.25), 1.1);
+    }
 }
@@ -43,3 +43,89 @@ pub fn current_time_millis() -> i64 {
         }
     }
 }
+
+pub const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// Returns the number of days since 1970-01-01 for the given proleptic Gregorian date.
+/// See <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Returns the (year, month, day) of the proleptic Gregorian date `days` after 1970-01-01.
+/// See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Returns `ts` moved by `months` calendar months in UTC, keeping the time of day. The day is
+/// clamped to the length of the target month, so one month before March 31st is the last day of
+/// February.
+pub fn shift_months(ts: i64, months: i64) -> i64 {
+    let days = ts.div_euclid(MILLIS_PER_DAY);
+    let time_of_day = ts.rem_euclid(MILLIS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let month_index = year * 12 + month as i64 - 1 + months;
+    let year = month_index.div_euclid(12);
+    let month = month_index.rem_euclid(12) as u32 + 1;
+    let day = day.min(days_in_month(year, month));
+    days_from_civil(year, month, day) * MILLIS_PER_DAY + time_of_day
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    let next_month = if month == 12 {
+        days_from_civil(year + 1, 1, 1)
+    } else {
+        days_from_civil(year, month + 1, 1)
+    };
+    (next_month - days_from_civil(year, month, 1)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_day_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        for days in (-800_000..800_000).step_by(997) {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+
+    #[test]
+    fn test_shift_months() {
+        let ts = |y, m, d| days_from_civil(y, m, d) * MILLIS_PER_DAY;
+        let noon = MILLIS_PER_DAY / 2;
+        assert_eq!(
+            shift_months(ts(2024, 5, 15) + noon, -1),
+            ts(2024, 4, 15) + noon
+        );
+        assert_eq!(shift_months(ts(2024, 1, 10), -1), ts(2023, 12, 10));
+        assert_eq!(shift_months(ts(2024, 3, 31), -1), ts(2024, 2, 29));
+        assert_eq!(shift_months(ts(2023, 3, 31), -1), ts(2023, 2, 28));
+        assert_eq!(shift_months(ts(2024, 2, 29), -12), ts(2023, 2, 28));
+        assert_eq!(shift_months(ts(2024, 10, 31), 1), ts(2024, 11, 30));
+        assert_eq!(shift_months(ts(2024, 6, 1), 0), ts(2024, 6, 1));
+    }
+}
@@ -1,6 +1,8 @@
+use crate::common::Timestamp;
 use crate::common::constants::MILLIS_PER_YEAR;
 use crate::common::humanize::humanize_duration_ms;
 use crate::common::rounding::RoundingStrategy;
+use crate::common::time::current_time_millis;
 use crate::error_consts;
 use crate::parser::number::parse_number;
 use crate::parser::parse_duration_value;
@@ -62,10 +64,21 @@ pub const DEFAULT_THREADS: i64 = 4;
 pub const RETENTION_POLICY_MIN: i64 = 0;
 pub const RETENTION_POLICY_MAX: i64 = 10 * ONE_YEAR_MS; //
 
+/// 0 means there is no limit on the number of series per db
+pub const MAX_SERIES_DEFAULT: i64 = 0;
+pub const MAX_SERIES_MIN: i64 = 0;
+pub const MAX_SERIES_MAX: i64 = i64::MAX;
+
+/// 0 means samples may have any timestamp in the future
+pub const MAX_FUTURE_SKEW_DEFAULT: i64 = 0;
+pub const MAX_FUTURE_SKEW_MIN: i64 = 0;
+pub const MAX_FUTURE_SKEW_MAX: i64 = ONE_DAY_MS * 365 * 100; // 100 years
+
 // Default values as strings for Valkey configuration registration
 const IGNORE_MAX_VALUE_DIFF_DEFAULT_STRING: &str = "0";
 const RETENTION_POLICY_DEFAULT_STRING: &str = "0";
 const IGNORE_MAX_TIME_DIFF_DEFAULT_STRING: &str = "0";
+const MAX_FUTURE_SKEW_DEFAULT_STRING: &str = "0";
 
 pub(crate) const SIGNIFICANT_DIGITS_DEFAULT_STRING: &str = "none";
 pub(crate) const DECIMAL_DIGITS_DEFAULT_STRING: &str = "none";
@@ -91,6 +104,9 @@ pub struct ConfigSettings {
     pub cluster_map_expiration: Duration,
     pub is_debug_mode_enabled: bool,
     pub num_threads: usize,
+    pub max_series: usize,
+    /// How far past the current time sample timestamps may be. `None` means no limit
+    pub max_future_skew: Option<Duration>,
 }
 
 impl Default for ConfigSettings {
@@ -106,12 +122,16 @@ impl Default for ConfigSettings {
             cluster_map_expiration: Duration::from_millis(CLUSTER_MAP_EXPIRATION_MS_DEFAULT),
             is_debug_mode_enabled: false,
             num_threads: DEFAULT_THREADS as usize,
+            max_series: MAX_SERIES_DEFAULT as usize,
+            max_future_skew: None,
         }
     }
 }
 
 pub static CHUNK_SIZE: AtomicI64 = AtomicI64::new(CHUNK_SIZE_DEFAULT);
 pub static NUM_THREADS: AtomicI64 = AtomicI64::new(DEFAULT_THREADS);
+pub static MAX_SERIES: AtomicI64 = AtomicI64::new(MAX_SERIES_DEFAULT);
+pub static MAX_FUTURE_SKEW: AtomicI64 = AtomicI64::new(MAX_FUTURE_SKEW_DEFAULT);
 pub const DEFAULT_FANOUT_COMMAND_TIMEOUT_MS: u64 = 5000;
 
 lazy_static! {
@@ -144,6 +164,8 @@ lazy_static! {
     static ref IGNORE_MAX_VALUE_DIFF_STRING: ValkeyGILGuard<ValkeyString> = ValkeyGILGuard::new(
         ValkeyString::create(None, IGNORE_MAX_VALUE_DIFF_DEFAULT_STRING)
     );
+    static ref MAX_FUTURE_SKEW_STRING: ValkeyGILGuard<ValkeyString> =
+        ValkeyGILGuard::new(ValkeyString::create(None, MAX_FUTURE_SKEW_DEFAULT_STRING));
     static ref DECIMAL_DIGITS_STRING: ValkeyGILGuard<ValkeyString> =
         ValkeyGILGuard::new(ValkeyString::create(None, DECIMAL_DIGITS_DEFAULT_STRING));
     static ref SIGNIFICANT_DIGITS_STRING: ValkeyGILGuard<ValkeyString> = ValkeyGILGuard::new(
@@ -164,6 +186,13 @@ pub fn get_config() -> ConfigSettings {
     SETTINGS.read().expect("config lock poisoned").clone()
 }
 
+/// Returns the latest timestamp (ms) a sample may have under `ts-max-future-skew`, or `None` if
+/// timestamps in the future are not limited.
+pub fn max_future_timestamp() -> Option<Timestamp> {
+    let skew = MAX_FUTURE_SKEW.load(Ordering::Relaxed);
+    (skew > 0).then(|| current_time_millis().saturating_add(skew))
+}
+
 #[config_changed_event_handler]
 fn config_changed_event_handler(_ctx: &Context, changed_configs: &[&str]) {
     if changed_configs.is_empty() {
@@ -190,6 +219,10 @@ fn config_changed_event_handler(_ctx: &Context, changed_configs: &[&str]) {
                 // nothing to do here
                 cfg.num_threads = NUM_THREADS.load(Ordering::Relaxed) as usize;
             },
+            "ts-max-series" => {
+                cfg.max_series = MAX_SERIES.load(Ordering::Relaxed) as usize;
+                modified = true;
+            },
             "ts-retention-policy" => {
                 let period = *RETENTION_PERIOD.lock().unwrap();
                 cfg.retention_period = if period.is_zero() { None } else { Some(period) };
@@ -203,6 +236,11 @@ fn config_changed_event_handler(_ctx: &Context, changed_configs: &[&str]) {
                 cfg.duplicate_policy.max_value_delta = *IGNORE_MAX_VALUE_DIFF.lock().unwrap();
                 modified = true;
             },
+            "ts-max-future-skew" => {
+                let skew = MAX_FUTURE_SKEW.load(Ordering::Relaxed);
+                cfg.max_future_skew = (skew > 0).then(|| Duration::from_millis(skew as u64));
+                modified = true;
+            },
             "ts-decimal-digits" => {
                 cfg.rounding = *ROUNDING_STRATEGY.lock().unwrap();
                 modified = true;
@@ -366,6 +404,17 @@ fn update_ignore_max_time_diff(val: &str) -> ValkeyResult<()> {
     Ok(())
 }
 
+fn update_max_future_skew(val: &str) -> ValkeyResult<()> {
+    let duration = parse_duration_in_range(
+        "ts-max-future-skew",
+        val,
+        MAX_FUTURE_SKEW_MIN,
+        MAX_FUTURE_SKEW_MAX,
+    )?;
+    MAX_FUTURE_SKEW.store(duration, Ordering::SeqCst);
+    Ok(())
+}
+
 fn update_ignore_max_value_diff(val: &str) -> ValkeyResult<()> {
     let value = parse_number_in_range(
         "ts-ignore-max-value-diff",
@@ -442,6 +491,9 @@ fn on_config_set(
         "ts-ignore-max-time-diff" => {
             return update_ignore_max_time_diff(&v)
         },
+        "ts-max-future-skew" => {
+            return update_max_future_skew(&v)
+        },
         "ts-fanout-command-timeout" => {
             return update_fanout_command_timeout(&v)
         },
@@ -479,7 +531,7 @@ fn update_decimal_digits(val: &str) -> ValkeyResult<()> {
             *strategy = Some(RoundingStrategy::DecimalDigits(digits as u8));
             Ok(())
         }
-        Some(RoundingStrategy::SignificantDigits(_)) => Err(ValkeyError::String(
+        Some(_) => Err(ValkeyError::String(
             "Cannot set both ts-decimal-digits and ts-significant-digits".to_string(),
         )),
     }
@@ -513,7 +565,7 @@ fn update_significant_digits(val: &str) -> ValkeyResult<()> {
             *strategy = Some(RoundingStrategy::SignificantDigits(digits as u8));
             Ok(())
         }
-        Some(RoundingStrategy::DecimalDigits(_)) => Err(ValkeyError::String(
+        Some(_) => Err(ValkeyError::String(
             "Cannot set both ts-decimal-digits and ts-significant-digits".to_string(),
         )),
     }
@@ -636,6 +688,19 @@ pub(super) fn register_config(ctx: &Context, args: &[ValkeyString]) -> ValkeyRes
         Some(Box::new(on_thread_config_set)),
     );
 
+    let max_series_default = get_i64_default(args, "ts-max-series", MAX_SERIES_DEFAULT)?;
+    register_i64_configuration(
+        ctx,
+        "ts-max-series",
+        &MAX_SERIES,
+        max_series_default,
+        MAX_SERIES_MIN,
+        MAX_SERIES_MAX,
+        ConfigurationFlags::DEFAULT,
+        None,
+        None,
+    );
+
     register_string_config(
         ctx,
         args,
@@ -693,6 +758,13 @@ pub(super) fn register_config(ctx: &Context, args: &[ValkeyString]) -> ValkeyRes
         &IGNORE_MAX_VALUE_DIFF_STRING,
         IGNORE_MAX_VALUE_DIFF_DEFAULT_STRING,
     )?;
+    register_string_config(
+        ctx,
+        args,
+        "ts-max-future-skew",
+        &MAX_FUTURE_SKEW_STRING,
+        MAX_FUTURE_SKEW_DEFAULT_STRING,
+    )?;
     register_string_config(
         ctx,
         args,
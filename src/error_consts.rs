@@ -1,14 +1,22 @@
 pub const CANNOT_ADD_SAMPLE: &str = "TSDB: could not add sample";
 pub const CHUNK_SPLIT: &str = "TSDB: could not split chunk";
+pub const CHUNK_IMPORT_ENCODING_MISMATCH: &str =
+    "TSDB: imported chunk encoding does not match the series encoding";
+pub const CHUNK_IMPORT_META_MISMATCH: &str = "TSDB: imported chunk does not match its metadata";
+pub const CHUNK_IMPORT_OUT_OF_ORDER: &str =
+    "TSDB: imported chunk overlaps or precedes existing samples";
 pub const CAPACITY_FULL: &str = "TSDB: chunk at full capacity";
 pub const CHUNK_COMPRESSION: &str = "TSDB: cannot compress chunk";
 pub const CHUNK_DECOMPRESSION: &str = "TSDB: cannot decompress chunk";
 pub const DUPLICATE_LABEL: &str = "TSDB: duplicate label";
 pub const DUPLICATE_LABELS: &str = "TSDB: duplicate labels";
+pub const DUPLICATE_LABEL_ALIAS: &str = "TSDB: duplicate output label name";
 pub const DUPLICATE_SAMPLE: &str = "TSDB: duplicate sample";
 pub const DUPLICATE_UPSERT_SAMPLE: &str = "TSDB: duplicate sample at upsert";
 pub const SAMPLE_TOO_CLOSE: &str = "TSDB: sample too close to previous in value or timestamp";
 pub const DUPLICATE_SERIES: &str = "TSDB: duplicate series";
+pub const MAX_SERIES_REACHED: &str =
+    "TSDB: the maximum number of series (ts-max-series) has been reached";
 pub const SAMPLE_MERGE_ERROR: &str = "TSDB: error merging samples";
 pub const ERROR_FETCHING_SAMPLE: &str = "TSDB: fetching sample";
 pub const INTERNAL_ERROR: &str = "TSDB: internal error";
@@ -16,9 +24,15 @@ pub const INVALID_ALIGN: &str = "TSDB: invalid ALIGN argument";
 pub const INVALID_ARGUMENT: &str = "TSDB: invalid argument";
 pub const INVALID_VALUE: &str = "TSDB: invalid value";
 pub const CANNOT_INCREMENT_DECREMENT_NAN: &str = "TSDB: cannot increment/decrement a NaN value";
+pub const INCREMENT_TIMESTAMP_TOO_OLD: &str =
+    "TSDB: timestamp must be equal to or higher than the maximum existing timestamp";
 pub const INVALID_BUCKET_ALIGNMENT: &str = "TSDB: invalid bucket alignment";
 pub const INVALID_ALIGNMENT_TIMESTAMP: &str = "TSDB: Couldn't parse alignTimestamp";
+pub const INVALID_MONTH_BUCKET_DURATION: &str =
+    "TSDB: bucket duration for month alignment must be a multiple of 30 days";
 pub const INVALID_BUCKET_TIMESTAMP_TYPE: &str = "TSDB: unknown BUCKETTIMESTAMP parameter";
+pub const INVALID_FILL_POLICY: &str =
+    "TSDB: invalid FILL policy. Expected one of NULL, ZERO, PREVIOUS or LINEAR";
 pub const INVALID_BOOLEAN: &str = "TSDB: invalid boolean argument";
 pub const INVALID_CHUNK_ENCODING: &str = "TSDB: invalid chunk encoding method";
 pub const MISSING_CHUNK_ENCODING: &str = "TSDB: missing chunk encoding method";
@@ -31,6 +45,10 @@ pub const INVALID_INTEGER: &str = "TSDB: invalid integer";
 pub const INVALID_JOIN_KEY: &str = "TSDB: invalid join key";
 pub const DUPLICATE_JOIN_KEYS: &str = "TSDB: duplicate join keys";
 pub const MISSING_JOIN_REDUCER: &str = "TSDB: join aggregation requires a reducer";
+pub const INVALID_DIV_BY_ZERO: &str = "TSDB: invalid DIV_BY_ZERO value. Expected NAN or NULL";
+pub const INVALID_JOIN_FILL_POLICY: &str =
+    "TSDB: invalid join FILL policy. Expected one of NULL, ZERO or PREVIOUS";
+pub const JOIN_FILL_NOT_SUPPORTED: &str = "TSDB: cannot use FILL with SEMI or ANTI joins";
 pub const INVALID_ASOF_TOLERANCE: &str = "TSDB: negative ASOF tolerance not valid";
 pub const INVALID_ASOF_STRATEGY: &str = "TSDB: invalid ASOF strategy";
 pub const INVALID_METRIC_NAME: &str = "TSDB: invalid metric name";
@@ -38,9 +56,33 @@ pub const INVALID_OR_MISSING_METRIC_NAME: &str = "TSDB: invalid or missing metri
 pub const METRIC_ALREADY_SET: &str = "TSDB: metric already set";
 pub const INVALID_NUMBER: &str = "TSDB: invalid number";
 pub const INVALID_SERIES_SELECTOR: &str = "TSDB: series selector is invalid";
+pub const INVALID_SERIES_DIFF_MODE: &str = "TSDB: invalid diff mode. Expected DIFF or XOR";
+pub const INVALID_SPLIT_POLICY: &str =
+    "TSDB: invalid SPLIT_POLICY. Expected HALF, INSERTION or a percentage between 1 and 99";
 pub const INVALID_STEP_DURATION: &str = "TSDB: invalid step duration";
 pub const INVALID_TIMESTAMP: &str = "TSDB: invalid timestamp.";
+pub const INVALID_TIMESTAMP_UNIT: &str =
+    "TSDB: invalid TIMESTAMP_UNIT. Expected one of 's', 'ms', 'us' or 'ns'";
+pub const TIMESTAMP_UNIT_OVERFLOW: &str = "TSDB: timestamp is out of range for the series unit";
 pub const UNKNOWN_AGGREGATION_TYPE: &str = "TSDB: unknown aggregation type";
+pub const UNTERMINATED_AGGREGATION_LIST: &str = "TSDB: missing ']' in aggregation list";
+pub const MULTIPLE_AGGREGATIONS_NOT_SUPPORTED: &str =
+    "TSDB: multiple aggregations are only supported by TS.RANGE and TS.REVRANGE";
+pub const MULTIPLE_AGGREGATIONS_PARAM: &str =
+    "TSDB: aggregations taking a parameter cannot be combined with others";
+pub const MULTIPLE_AGGREGATIONS_CONDITION: &str =
+    "TSDB: CONDITION cannot be used with multiple aggregations";
+pub const MULTIPLE_AGGREGATIONS_MAX_POINTS: &str =
+    "TSDB: MAXPOINTS cannot be used with multiple aggregations";
+pub const WITH_COUNTS_NOT_SUPPORTED: &str =
+    "TSDB: WITHCOUNTS is only supported by TS.RANGE and TS.REVRANGE";
+pub const DEDUP_NOT_SUPPORTED: &str = "TSDB: DEDUP is only supported by TS.RANGE and TS.REVRANGE";
+pub const WITH_COUNTS_MAX_POINTS: &str = "TSDB: MAXPOINTS cannot be used with WITHCOUNTS";
+pub const INVALID_TRIM_PERCENTAGE: &str =
+    "TSDB: invalid trimmed_mean percentage. Expected a value in [0, 50)";
+pub const AGGREGATION_PARAM_NOT_SUPPORTED: &str =
+    "TSDB: aggregation type does not take a parameter";
+pub const INVALID_AGGREGATION_PARAM: &str = "TSDB: missing or invalid aggregation parameter";
 pub const INVALID_START_TIMESTAMP: &str = "TSDB: invalid start timestamp.";
 pub const INVALID_END_TIMESTAMP: &str = "TSDB: invalid end timestamp.";
 pub const NEGATIVE_TIMESTAMP: &str = "TSDB: timestamps cannot be negative.";
@@ -57,8 +99,11 @@ pub const KEY_DELETE_PERMISSION_ERROR: &str = "TSDB: key delete permission error
 pub const ALL_KEYS_READ_PERMISSION_ERROR: &str = "TSDB: current user doesn't have read permission to one or more keys that match the specified filter";
 pub const ALL_KEYS_WRITE_PERMISSION_ERROR: &str = "TSDB: current user doesn't have write permission to one or more keys that match the specified filter";
 pub const DUPLICATE_KEY: &str = "TSDB: key already exists";
+pub const MERGE_SOURCE_IS_DESTINATION: &str = "TSDB: the destination key cannot also be a source";
 pub const MISSING_FILTER: &str = "TSDB: please provide at least one matcher";
 pub const INVALID_TIMESTAMP_FILTER: &str = "TSDB: FILTER_BY_TS one or more arguments are missing";
+pub const START_END_WITH_FILTER_BY_RANGE: &str =
+    "TSDB: START/END cannot be combined with FILTER_BY_RANGE";
 pub const INVALID_REGEX: &str = "TSDB: invalid regex";
 pub const INVALID_IGNORE_OPTIONS: &str = "TSDB: invalid ignore options";
 pub const CANNOT_PARSE_IGNORE: &str = "TSDB: Couldn't parse IGNORE";
@@ -66,24 +111,64 @@ pub const NEGATIVE_IGNORE_VALUES: &str = "TSDB: IGNORE values cannot be negative
 
 pub const CANNOT_PARSE_LABELS: &str = "TSDB: Couldn't parse LABELS";
 pub const CANNOT_PARSE_RETENTION: &str = "TSDB: Couldn't parse RETENTION";
+pub const INVALID_RETENTION_SAMPLES: &str =
+    "TSDB: RETENTION_SAMPLES must be a non-negative integer";
 pub const CANNOT_PARSE_AGGREGATION: &str = "TSDB: Couldn't parse AGGREGATION";
 pub const CANNOT_PARSE_MIN: &str = "TSDB: Couldn't parse MIN";
 pub const CANNOT_PARSE_MAX: &str = "TSDB: Couldn't parse MAX";
 
 pub const NO_SERIES_FOUND: &str = "TSDB: no series found";
 pub const SAMPLE_TOO_OLD: &str = "TSDB: sample timestamp exceeds retention period";
+pub const SAMPLE_TOO_NEW: &str = "TSDB: sample timestamp exceeds the maximum future skew";
+pub const ANNOTATION_TOO_OLD: &str = "TSDB: annotation timestamp exceeds retention period";
+pub const ANNOTATION_TOO_LONG: &str = "TSDB: annotation text is too long";
+pub const TOO_MANY_ANNOTATIONS: &str = "TSDB: the series has too many annotations";
 pub const SERIES_NOT_FOUND: &str = "TSDB: series not found";
+pub const INVALID_SERIES_ID: &str = "TSDB: invalid series id";
+pub const BYID_NOT_SUPPORTED_IN_CLUSTER: &str = "TSDB: BYID is not supported in cluster mode";
 pub const GROUP_NOT_FOUND: &str = "TSDB: group not found";
 pub const LABELS_ALREADY_SET: &str = "TSDB: labels already set";
 pub const INVALID_LABEL_NAME: &str = "TSDB: invalid label name";
 pub const INVALID_LABEL_VALUE: &str = "TSDB: invalid label value";
 pub const MISSING_LABEL_VALUE: &str = "TSDB: empty or missing label value";
 pub const MISSING_LIMIT_VALUE: &str = "TSDB: missing LIMIT value";
+pub const MISSING_TOPK_VALUE: &str = "TSDB: missing TOPK value";
+pub const MISSING_MATCH_VALUE: &str = "TSDB: missing MATCH value";
 pub const INVALID_LIMIT_VALUE: &str = "TSDB: invalid LIMIT value";
+pub const INVALID_SAMPLE_LIMIT: &str = "TSDB: LIMIT must be a positive integer";
+pub const MISSING_CURSOR_VALUE: &str = "TSDB: missing CURSOR value";
+pub const INVALID_CURSOR: &str = "TSDB: invalid cursor";
+pub const MISSING_PAGE_SIZE_VALUE: &str = "TSDB: missing PAGE_SIZE value";
+pub const INVALID_CURSOR_COUNT: &str = "TSDB: PAGE_SIZE must be between 1 and 1000";
+pub const CURSOR_WITH_GROUPBY_OR_LIMIT: &str =
+    "TSDB: CURSOR cannot be combined with GROUPBY or LIMIT";
+pub const CURSOR_NOT_SUPPORTED_IN_CLUSTER: &str = "TSDB: CURSOR is not supported in cluster mode";
 pub const MISSING_COUNT_VALUE: &str = "TSDB: missing COUNT value";
+pub const MISSING_MAX_STALE_VALUE: &str = "TSDB: missing MAXSTALE value";
 pub const INVALID_COUNT_VALUE: &str = "TSDB: invalid COUNT value";
 pub const NEGATIVE_COUNT: &str = "TSDB: COUNT should be a positive number";
+pub const INVALID_GC_BATCH_SIZE: &str = "TSDB: COUNT must be a positive integer";
+pub const INGEST_NOT_SUPPORTED_IN_CLUSTER: &str =
+    "TSDB: TS.INGEST is not supported in cluster mode";
+pub const INVALID_COUNT_FROM: &str = "TSDB: expected END after COUNT count FROM";
+pub const INVALID_HISTOGRAM_BUCKETS: &str = "TSDB: BUCKETS must be between 1 and 1000";
+pub const INVALID_HISTOGRAM_BOUNDARIES: &str =
+    "TSDB: BOUNDARIES must be 1 to 999 strictly increasing numbers";
+pub const MISSING_HISTOGRAM_BINNING: &str = "TSDB: either BUCKETS or BOUNDARIES is required";
+pub const HISTOGRAM_BINNING_CONFLICT: &str = "TSDB: only one of BUCKETS or BOUNDARIES may be given";
+pub const INVALID_BATCH_SIZE: &str = "TSDB: BATCH_SIZE must be between 1 and 100000";
+pub const MISSING_MAX_POINTS_VALUE: &str = "TSDB: missing MAXPOINTS value";
+pub const INVALID_MAX_POINTS_VALUE: &str = "TSDB: MAXPOINTS should be a positive integer";
+pub const MISSING_TRANSFORM: &str = "TSDB: TRANSFORM requires SCALE or OFFSET";
+pub const INVALID_TRANSFORM_VALUE: &str = "TSDB: TRANSFORM SCALE and OFFSET must be finite numbers";
+pub const MISSING_VALUE_FUNCTION: &str = "TSDB: missing FUNCTION name";
+pub const INVALID_VALUE_FUNCTION: &str =
+    "TSDB: invalid FUNCTION. Expected one of abs, log, log2, log10, sqrt, exp";
 pub const ROUNDING_ALREADY_SET: &str = "TSDB: rounding already set";
+pub const INVALID_ROUNDING: &str = "TSDB: invalid ROUNDING. Expected MULTIPLE step";
+pub const INVALID_ROUNDING_STEP: &str = "TSDB: ROUNDING MULTIPLE step must be a positive number";
+pub const INVALID_LOOKUP_MODE: &str =
+    "TSDB: invalid lookup mode. Expected PREVIOUS, NEXT, NEAREST or LINEAR";
 pub const DUPLICATE_SAMPLE_BLOCKED: &str = "TSDB: Error at upsert, duplicate sample blocked";
 pub const PERMISSION_DENIED: &str = "TSDB: current user doesn't have read permission to one or more keys that match the specified filter";
 pub const COMMAND_SERIALIZATION_ERROR: &str = "TSDB: command serialization error";
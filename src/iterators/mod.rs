@@ -15,6 +15,6 @@ pub use reduce_iterator::*;
 pub use sample_iter::*;
 pub use sample_merge_iterator::*;
 pub use sample_reducer::SampleReducer;
-pub use timeseries_range_iterator::TimeSeriesRangeIterator;
+pub use timeseries_range_iterator::{AggregateRow, TimeSeriesRangeIterator, aggregate_range_multi};
 pub use timestamp_filter_iterator::TimestampFilterIterator;
 pub use utils::*;
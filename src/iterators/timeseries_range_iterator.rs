@@ -1,7 +1,12 @@
-use crate::common::Sample;
+use crate::aggregators::AggregateIterator;
+use crate::common::{Sample, Timestamp};
 use crate::iterators::create_range_iterator;
-use crate::series::request_types::RangeOptions;
+use crate::iterators::utils::split_transform;
+use crate::series::request_types::{AggregationOptions, AggregatorConfig, RangeOptions};
 use crate::series::{TimeSeries, get_latest_compaction_sample};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
 use valkey_module::Context;
 
 /// An iterator over a TimeSeries based on RangeOptions.
@@ -25,7 +30,16 @@ impl<'a> TimeSeriesRangeIterator<'a> {
     ) -> Self {
         let latest = Self::get_latest_sample(ctx, series, options);
         let size_hint = Self::calculate_size_hint(options, latest.is_some());
-        let inner = create_range_iterator(series, options, &None, latest, is_reverse);
+        let inner = if options.count_from_end {
+            // Walk the range from the far end, so that iteration stops once `count` results are
+            // collected instead of materializing the whole range, then restore the requested order.
+            let mut samples: Vec<Sample> =
+                create_range_iterator(series, options, &None, latest, !is_reverse).collect();
+            samples.reverse();
+            Box::new(samples.into_iter())
+        } else {
+            create_range_iterator(series, options, &None, latest, is_reverse)
+        };
 
         Self { inner, size_hint }
     }
@@ -97,6 +111,154 @@ impl<'a> Iterator for TimeSeriesRangeIterator<'a> {
     }
 }
 
+/// A bucket produced by [`aggregate_range_multi`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateRow {
+    pub timestamp: Timestamp,
+    /// One value per aggregation, in the order they were requested
+    pub values: Vec<f64>,
+    /// The number of samples in the bucket, if WITHCOUNTS was given. Empty buckets count 0.
+    pub count: Option<usize>,
+}
+
+/// Aggregates a range of `series` with the aggregation in `options` followed by `extra_aggregations`,
+/// returning one row per bucket holding a value per aggregation, in that order.
+/// The range is decoded and filtered once, as a stream shared by the aggregations. Since buckets
+/// only depend on sample timestamps, every aggregation yields the same buckets, and the sample
+/// count of a bucket is the one tracked by the first aggregation.
+pub fn aggregate_range_multi(
+    ctx: Option<&Context>,
+    series: &TimeSeries,
+    options: &RangeOptions,
+    extra_aggregations: &[AggregatorConfig],
+    is_reverse: bool,
+) -> Vec<AggregateRow> {
+    let Some(aggregation) = options.aggregation else {
+        return Vec::new();
+    };
+    let aggregation = AggregationOptions {
+        alignment: aggregation
+            .alignment
+            .resolve_series_start(series.first_timestamp),
+        ..aggregation
+    };
+
+    let (before, after) = split_transform(options);
+    let sample_options = RangeOptions {
+        aggregation: None,
+        count: None,
+        count_from_end: false,
+        max_points: None,
+        transform: before,
+        ..options.clone()
+    };
+    let samples = TimeSeriesRangeIterator::new(ctx, series, &sample_options, false);
+
+    let (start_ts, end_ts) = options.get_timestamp_range();
+    let aligned_timestamp = aggregation
+        .alignment
+        .get_aligned_timestamp(start_ts, end_ts);
+
+    let column_count = extra_aggregations.len() + 1;
+    let shared = Rc::new(RefCell::new(SharedSamples {
+        inner: samples,
+        queues: vec![VecDeque::new(); column_count],
+    }));
+    let mut columns: Vec<_> = std::iter::once(aggregation.aggregation)
+        .chain(extra_aggregations.iter().copied())
+        .enumerate()
+        .map(|(index, config)| {
+            let options = AggregationOptions {
+                aggregation: config,
+                ..aggregation
+            };
+            let samples = SharedSampleIter {
+                shared: Rc::clone(&shared),
+                index,
+            };
+            AggregateIterator::new(samples, &options, aligned_timestamp)
+        })
+        .collect();
+
+    let with_counts = aggregation.with_counts;
+    let rows = std::iter::from_fn(|| {
+        let (first, rest) = columns.split_first_mut()?;
+        let (bucket, count) = first.next_with_count()?;
+        let mut values = Vec::with_capacity(column_count);
+        values.push(bucket.value);
+        for column in rest.iter_mut() {
+            let value = column.next().map_or(f64::NAN, |sample| {
+                debug_assert_eq!(sample.timestamp, bucket.timestamp);
+                sample.value
+            });
+            values.push(value);
+        }
+        if let Some(transform) = after {
+            values
+                .iter_mut()
+                .for_each(|value| *value = transform.apply(*value));
+        }
+        Some(AggregateRow {
+            timestamp: bucket.timestamp,
+            values,
+            count: with_counts.then_some(count),
+        })
+    });
+
+    // without reversal, iteration stops once `count` buckets are produced
+    let mut rows: Vec<AggregateRow> = match options.count {
+        Some(count) if !is_reverse && !options.count_from_end => rows.take(count).collect(),
+        _ => rows.collect(),
+    };
+
+    if is_reverse {
+        rows.reverse();
+    }
+    if let Some(count) = options.count
+        && count < rows.len()
+    {
+        if options.count_from_end {
+            rows.drain(..rows.len() - count);
+        } else {
+            rows.truncate(count);
+        }
+    }
+    rows
+}
+
+/// A stream of samples read by several consumers. Each consumer has its own queue, so a sample is
+/// only buffered until every consumer has read it. Consumers advancing in lockstep, like the
+/// aggregations of `aggregate_range_multi`, keep at most about one bucket of samples buffered.
+struct SharedSamples<I: Iterator<Item = Sample>> {
+    inner: I,
+    queues: Vec<VecDeque<Sample>>,
+}
+
+/// The view of consumer `index` over a [`SharedSamples`] stream.
+struct SharedSampleIter<I: Iterator<Item = Sample>> {
+    shared: Rc<RefCell<SharedSamples<I>>>,
+    index: usize,
+}
+
+impl<I: Iterator<Item = Sample>> Iterator for SharedSampleIter<I> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut guard = self.shared.borrow_mut();
+        let shared = &mut *guard;
+        if let Some(sample) = shared.queues[self.index].pop_front() {
+            return Some(sample);
+        }
+        let sample = shared.inner.next()?;
+        for (index, queue) in shared.queues.iter_mut().enumerate() {
+            if index != self.index {
+                queue.push_back(sample);
+            }
+        }
+        Some(sample)
+    }
+}
+
 /// An iterator that yields the latest sample from a compaction series if it exists.
 /// This is used specifically for the "LATEST" option in range queries. This simplifies the logic by
 /// isolating the latest sample retrieval so that the base sample iterator does not need to handle
@@ -131,9 +293,11 @@ impl<'a> Iterator for CompactionLatestSampleIterator<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::aggregators::AggregationType;
+    use crate::aggregators::{AggregationType, BucketAlignment, FillPolicy};
     use crate::common::{Sample, Timestamp};
-    use crate::series::request_types::AggregationOptions;
+    use crate::series::request_types::{
+        AggregationOptions, AggregatorConfig, TransformOrder, ValueFunction, ValueTransform,
+    };
     use crate::series::{TimeSeries, TimestampRange, ValueFilter};
 
     // Helper function to create a test time series with sample data
@@ -160,8 +324,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -180,8 +349,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, true);
@@ -200,8 +374,13 @@ mod tests {
             count: Some(5),
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -210,6 +389,40 @@ mod tests {
         assert!(samples.len() <= 5);
     }
 
+    #[test]
+    fn test_iteration_with_count_from_end() {
+        let series = create_test_series();
+        let mut options = RangeOptions {
+            date_range: date_range(0, 8000),
+            count: Some(3),
+            count_from_end: true,
+            ..Default::default()
+        };
+
+        let samples: Vec<Sample> =
+            TimeSeriesRangeIterator::new(None, &series, &options, false).collect();
+        let timestamps: Vec<Timestamp> = samples.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![6000, 7000, 8000]);
+
+        // in reverse, FROM END is the oldest samples, still newest first
+        let samples: Vec<Sample> =
+            TimeSeriesRangeIterator::new(None, &series, &options, true).collect();
+        let timestamps: Vec<Timestamp> = samples.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![2000, 1000, 0]);
+
+        // the last buckets of an aggregation
+        options.aggregation = Some(AggregationOptions {
+            aggregation: AggregationType::Sum.into(),
+            bucket_duration: 2000,
+            ..Default::default()
+        });
+        options.count = Some(2);
+        let samples: Vec<Sample> =
+            TimeSeriesRangeIterator::new(None, &series, &options, false).collect();
+        let timestamps: Vec<Timestamp> = samples.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![6000, 8000]);
+    }
+
     #[test]
     fn test_iteration_with_timestamp_range() {
         let series = create_test_series();
@@ -218,8 +431,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -238,8 +456,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: Some(ValueFilter { min: 3.0, max: 7.0 }),
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -264,8 +487,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: Some(vec![1000, 3000, 5000]),
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -282,8 +510,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: Some(vec![2000]),
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter_single = TimeSeriesRangeIterator::new(None, &series, &options_single, false);
@@ -315,8 +548,13 @@ mod tests {
                 ..Default::default()
             }),
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -360,8 +598,13 @@ mod tests {
                     ..Default::default()
                 }),
                 timestamp_filter: None,
+                timestamp_ranges: None,
+                max_points: None,
+                count_from_end: false,
                 value_filter: None,
                 latest: false,
+                transform: None,
+                function: None,
             };
 
             let samples: Vec<Sample> =
@@ -408,8 +651,13 @@ mod tests {
                 ..Default::default()
             }),
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let samples: Vec<Sample> =
@@ -446,8 +694,13 @@ mod tests {
                 ..Default::default()
             }),
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let samples: Vec<Sample> =
@@ -484,8 +737,13 @@ mod tests {
                 ..Default::default()
             }),
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let samples: Vec<Sample> =
@@ -519,8 +777,13 @@ mod tests {
                 ..Default::default()
             }),
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let samples: Vec<Sample> =
@@ -540,8 +803,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -564,8 +832,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -585,8 +858,13 @@ mod tests {
             count: Some(5),
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -604,8 +882,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -623,8 +906,13 @@ mod tests {
             count: Some(3),
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: Some(ValueFilter { min: 2.0, max: 8.0 }),
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -645,8 +933,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let mut iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -671,8 +964,13 @@ mod tests {
             count: None,
             aggregation: None,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
             value_filter: None,
             latest: false,
+            transform: None,
+            function: None,
         };
 
         let iter = TimeSeriesRangeIterator::new(None, &series, &options, false);
@@ -682,4 +980,412 @@ mod tests {
         assert!(!samples.is_empty());
         assert!(samples.iter().any(|s| s.timestamp == 3000));
     }
+
+    #[test]
+    fn test_series_start_alignment_is_stable_across_windows() {
+        let mut series = TimeSeries::default();
+        let first_ts = 1700;
+        for i in 0..60 {
+            let _ = series.add(first_ts + i * 1000, i as f64, None);
+        }
+
+        let bucket_duration = 5000;
+        let window_buckets = |start: Timestamp, alignment: BucketAlignment| {
+            let options = RangeOptions {
+                date_range: date_range(start, start + 20000),
+                aggregation: Some(AggregationOptions {
+                    aggregation: AggregationType::Sum.into(),
+                    bucket_duration,
+                    alignment,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            TimeSeriesRangeIterator::new(None, &series, &options, false).collect::<Vec<Sample>>()
+        };
+
+        let mut all_buckets: Vec<Sample> = Vec::new();
+        for start in [0, 2300, 4100, 9999, 13450] {
+            let buckets = window_buckets(start, BucketAlignment::SeriesStart);
+            assert!(!buckets.is_empty());
+            for bucket in buckets.iter() {
+                assert_eq!(
+                    (bucket.timestamp - first_ts).rem_euclid(bucket_duration as i64),
+                    0,
+                    "window start {start}: bucket {}",
+                    bucket.timestamp
+                );
+            }
+            // buckets fully inside both windows have the same value
+            for bucket in buckets[1..buckets.len() - 1].iter() {
+                if let Some(prev) = all_buckets.iter().find(|b| b.timestamp == bucket.timestamp) {
+                    assert_eq!(prev.value, bucket.value);
+                }
+            }
+            all_buckets.extend(buckets[1..buckets.len() - 1].iter().copied());
+        }
+
+        // aligning to the range start shifts the buckets with the window
+        let buckets = window_buckets(2300, BucketAlignment::Start);
+        assert_eq!(buckets[0].timestamp, 2300);
+    }
+    #[test]
+    fn test_aggregate_range_multi_matches_single_aggregations() {
+        let mut series = TimeSeries::default();
+        for i in 0..200 {
+            let value = if i % 17 == 0 {
+                f64::NAN
+            } else {
+                (i % 23) as f64
+            };
+            let _ = series.add(i * 250 + (i % 3) * 40, value, None);
+        }
+        let extra: Vec<AggregatorConfig> =
+            vec![AggregationType::Min.into(), AggregationType::Max.into()];
+
+        for (count, count_from_end) in [(None, false), (Some(7), false), (Some(7), true)] {
+            for report_empty in [false, true] {
+                for is_reverse in [false, true] {
+                    let options = RangeOptions {
+                        date_range: date_range(1000, 45000),
+                        count,
+                        count_from_end,
+                        value_filter: Some(ValueFilter::new(1.0, 20.0).unwrap()),
+                        aggregation: Some(AggregationOptions {
+                            aggregation: AggregationType::Avg.into(),
+                            bucket_duration: 3000,
+                            report_empty,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    };
+                    let rows = aggregate_range_multi(None, &series, &options, &extra, is_reverse);
+                    assert!(!rows.is_empty());
+
+                    for (column, aggregation) in [
+                        AggregationType::Avg,
+                        AggregationType::Min,
+                        AggregationType::Max,
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    {
+                        let mut single = options.clone();
+                        if let Some(agg) = single.aggregation.as_mut() {
+                            agg.aggregation = aggregation.into();
+                        }
+                        let expected: Vec<Sample> =
+                            TimeSeriesRangeIterator::new(None, &series, &single, is_reverse)
+                                .collect();
+                        let actual: Vec<Sample> = rows
+                            .iter()
+                            .map(|row| Sample::new(row.timestamp, row.values[column]))
+                            .collect();
+                        assert_eq!(
+                            actual, expected,
+                            "{aggregation:?}, count {count:?} from end {count_from_end}, empty {report_empty}, reverse {is_reverse}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_aggregate_range_multi_with_counts() {
+        let mut series = TimeSeries::default();
+        for ts in [1000, 1200, 2500, 3900, 7100, 7300, 7500, 7700] {
+            let _ = series.add(ts, 1.0, None);
+        }
+
+        for (report_empty, fill, expected) in [
+            (false, None, vec![(0, 2), (2000, 2), (6000, 4)]),
+            (true, None, vec![(0, 2), (2000, 2), (4000, 0), (6000, 4)]),
+            (
+                true,
+                Some(FillPolicy::Previous),
+                vec![(0, 2), (2000, 2), (4000, 0), (6000, 4)],
+            ),
+        ] {
+            let options = RangeOptions {
+                date_range: date_range(0, 10000),
+                aggregation: Some(AggregationOptions {
+                    aggregation: AggregationType::Sum.into(),
+                    bucket_duration: 2000,
+                    report_empty,
+                    fill,
+                    with_counts: true,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let rows = aggregate_range_multi(None, &series, &options, &[], false);
+            let counts: Vec<(Timestamp, usize)> = rows
+                .iter()
+                .map(|row| (row.timestamp, row.count.unwrap()))
+                .collect();
+            assert_eq!(counts, expected, "empty {report_empty}, fill {fill:?}");
+            // with all values 1.0, sums match counts in non-empty buckets
+            for row in rows.iter().filter(|row| row.count != Some(0)) {
+                assert_eq!(row.values, vec![row.count.unwrap() as f64]);
+            }
+        }
+
+        let options = RangeOptions {
+            date_range: date_range(0, 10000),
+            aggregation: Some(AggregationOptions {
+                aggregation: AggregationType::Sum.into(),
+                bucket_duration: 2000,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let rows = aggregate_range_multi(None, &series, &options, &[], false);
+        assert!(rows.iter().all(|row| row.count.is_none()));
+    }
+
+    #[test]
+    fn test_aggregate_range_multi_counts_follow_their_buckets() {
+        let mut series = TimeSeries::default();
+        for (ts, value) in [
+            (1000, 1.0),
+            (1200, 100.0),
+            (2500, 2.0),
+            (3900, 3.0),
+            (7100, 100.0),
+            (7300, 4.0),
+            (7500, 5.0),
+            (7700, 6.0),
+        ] {
+            let _ = series.add(ts, value, None);
+        }
+
+        let mut options = RangeOptions {
+            date_range: date_range(0, 10000),
+            value_filter: Some(ValueFilter::new(0.0, 10.0).unwrap()),
+            aggregation: Some(AggregationOptions {
+                aggregation: AggregationType::Sum.into(),
+                bucket_duration: 2000,
+                report_empty: true,
+                fill: Some(FillPolicy::Linear),
+                with_counts: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let extra = [AggregatorConfig::from(AggregationType::Max)];
+
+        let rows = aggregate_range_multi(None, &series, &options, &extra, true);
+        let rows: Vec<(Timestamp, Vec<f64>, usize)> = rows
+            .into_iter()
+            .map(|row| (row.timestamp, row.values, row.count.unwrap()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (6000, vec![15.0, 6.0], 3),
+                (4000, vec![10.0, 4.5], 0),
+                (2000, vec![5.0, 3.0], 2),
+                (0, vec![1.0, 1.0], 1),
+            ]
+        );
+
+        options.count = Some(2);
+        let rows = aggregate_range_multi(None, &series, &options, &extra, false);
+        let counts: Vec<(Timestamp, Option<usize>)> =
+            rows.iter().map(|row| (row.timestamp, row.count)).collect();
+        assert_eq!(counts, vec![(0, Some(1)), (2000, Some(2))]);
+    }
+
+    fn transform_options(
+        aggregation: Option<AggregationType>,
+        value_filter: Option<ValueFilter>,
+        transform: ValueTransform,
+    ) -> RangeOptions {
+        RangeOptions {
+            date_range: date_range(0, 10000),
+            aggregation: aggregation.map(|aggregation| AggregationOptions {
+                aggregation: aggregation.into(),
+                bucket_duration: 2000,
+                ..Default::default()
+            }),
+            value_filter,
+            transform: Some(transform),
+            ..Default::default()
+        }
+    }
+
+    fn values(series: &TimeSeries, options: &RangeOptions) -> Vec<f64> {
+        TimeSeriesRangeIterator::new(None, series, options, false)
+            .map(|sample| sample.value)
+            .collect()
+    }
+
+    #[test]
+    fn test_transform_with_aggregation() {
+        let series = create_test_series();
+        let transform = ValueTransform {
+            scale: 10.0,
+            offset: 1.0,
+            order: TransformOrder::BeforeAggregation,
+        };
+        let after = ValueTransform {
+            order: TransformOrder::AfterAggregation,
+            ..transform
+        };
+
+        // buckets of two samples: (0, 1), (2, 3), ...
+        let raw = values(&series, &transform_options(None, None, transform));
+        assert_eq!(
+            raw,
+            (0..10).map(|i| i as f64 * 10.0 + 1.0).collect::<Vec<_>>()
+        );
+
+        // avg is linear, so the order does not change the result
+        let expected = vec![6.0, 26.0, 46.0, 66.0, 86.0];
+        let avg = Some(AggregationType::Avg);
+        assert_eq!(
+            values(&series, &transform_options(avg, None, transform)),
+            expected
+        );
+        assert_eq!(
+            values(&series, &transform_options(avg, None, after)),
+            expected
+        );
+
+        // sum adds the offset once per sample before aggregation, and once per bucket after
+        let sum = Some(AggregationType::Sum);
+        assert_eq!(
+            values(&series, &transform_options(sum, None, transform)),
+            vec![12.0, 52.0, 92.0, 132.0, 172.0]
+        );
+        assert_eq!(
+            values(&series, &transform_options(sum, None, after)),
+            vec![11.0, 51.0, 91.0, 131.0, 171.0]
+        );
+
+        // counts are not affected by transforming samples
+        let count = Some(AggregationType::CountAll);
+        assert_eq!(
+            values(&series, &transform_options(count, None, transform)),
+            vec![2.0; 5]
+        );
+        assert_eq!(
+            values(&series, &transform_options(count, None, after)),
+            vec![21.0; 5]
+        );
+
+        let extra: [AggregatorConfig; 1] = [AggregationType::Max.into()];
+        let rows = aggregate_range_multi(
+            None,
+            &series,
+            &transform_options(avg, None, after),
+            &extra,
+            false,
+        );
+        assert_eq!(rows[0].values, vec![6.0, 11.0]);
+        let rows = aggregate_range_multi(
+            None,
+            &series,
+            &transform_options(sum, None, transform),
+            &extra,
+            false,
+        );
+        assert_eq!(rows[0].values, vec![12.0, 11.0]);
+    }
+
+    #[test]
+    fn test_transform_filters_stored_values() {
+        let series = create_test_series();
+        let transform = ValueTransform {
+            scale: 10.0,
+            ..Default::default()
+        };
+        let filter = Some(ValueFilter { min: 3.0, max: 7.0 });
+
+        assert_eq!(
+            values(&series, &transform_options(None, filter, transform)),
+            vec![30.0, 40.0, 50.0, 60.0, 70.0]
+        );
+        assert_eq!(
+            values(
+                &series,
+                &transform_options(Some(AggregationType::Avg), filter, transform)
+            ),
+            vec![30.0, 45.0, 65.0]
+        );
+    }
+
+    #[test]
+    fn test_function_applies_before_filter_and_aggregation() {
+        let mut series = TimeSeries::default();
+        for i in 0..10 {
+            series.add(i * 1000, (i * i) as f64, None);
+        }
+        let options = |function, aggregation: Option<AggregationType>, value_filter| RangeOptions {
+            date_range: date_range(0, 10000),
+            aggregation: aggregation.map(|aggregation| AggregationOptions {
+                aggregation: aggregation.into(),
+                bucket_duration: 2000,
+                ..Default::default()
+            }),
+            value_filter,
+            function: Some(function),
+            ..Default::default()
+        };
+
+        let sqrt = values(&series, &options(ValueFunction::Sqrt, None, None));
+        assert_eq!(sqrt, (0..10).map(|i| i as f64).collect::<Vec<_>>());
+
+        // buckets hold (0, 1), (4, 9), ... and are averaged after the function
+        let avg = values(
+            &series,
+            &options(ValueFunction::Sqrt, Some(AggregationType::Avg), None),
+        );
+        assert_eq!(avg, vec![0.5, 2.5, 4.5, 6.5, 8.5]);
+
+        // log of 0 is NaN, which any value filter drops
+        let log = values(&series, &options(ValueFunction::Log10, None, None));
+        assert!(log[0].is_nan());
+        let all = Some(ValueFilter::new(f64::MIN, f64::MAX).unwrap());
+        let log = values(&series, &options(ValueFunction::Log10, None, all));
+        assert_eq!(log.len(), 9);
+        assert_eq!(log[0], 0.0);
+
+        // the filter sees the function output
+        let filter = Some(ValueFilter { min: 2.0, max: 4.0 });
+        let filtered = values(&series, &options(ValueFunction::Sqrt, None, filter));
+        assert_eq!(filtered, vec![2.0, 3.0, 4.0]);
+
+        // FUNCTION runs before TRANSFORM
+        let transformed = RangeOptions {
+            transform: Some(ValueTransform {
+                offset: -1.0,
+                ..Default::default()
+            }),
+            ..options(ValueFunction::Sqrt, None, filter)
+        };
+        assert_eq!(values(&series, &transformed), vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_value_function_domain() {
+        assert_eq!(ValueFunction::Abs.apply(-2.5), 2.5);
+        assert_eq!(ValueFunction::Log2.apply(8.0), 3.0);
+        assert_eq!(ValueFunction::Exp.apply(0.0), 1.0);
+        for function in [
+            ValueFunction::Log,
+            ValueFunction::Log2,
+            ValueFunction::Log10,
+        ] {
+            assert!(function.apply(0.0).is_nan());
+            assert!(function.apply(-1.0).is_nan());
+        }
+        assert!(ValueFunction::Sqrt.apply(-4.0).is_nan());
+        assert_eq!(
+            ValueFunction::try_from("LOG10").unwrap(),
+            ValueFunction::Log10
+        );
+        assert!(ValueFunction::try_from("ln").is_err());
+    }
 }
@@ -1,8 +1,10 @@
-use crate::aggregators::AggregateIterator;
+use crate::aggregators::{AggregateIterator, AggregationType, BucketAlignment, BucketLocator};
 use crate::common::hash::IntSet;
 use crate::common::{Sample, Timestamp};
 use crate::iterators::{ReduceIterator, TimestampFilterIterator};
-use crate::series::request_types::{AggregationOptions, RangeGroupingOptions, RangeOptions};
+use crate::series::request_types::{
+    AggregationOptions, RangeGroupingOptions, RangeOptions, TransformOrder, ValueTransform,
+};
 use crate::series::{SeriesSampleIterator, TimeSeries};
 use smallvec::SmallVec;
 
@@ -40,6 +42,24 @@ pub fn create_range_iterator<'a>(
     latest_sample: Option<Sample>,
     is_reverse: bool,
 ) -> Box<dyn Iterator<Item = Sample> + 'a> {
+    // SERIES_START alignment can only be resolved once the series is known
+    let resolved_options;
+    let options = match options.aggregation {
+        Some(aggregation) if aggregation.alignment == BucketAlignment::SeriesStart => {
+            resolved_options = RangeOptions {
+                aggregation: Some(AggregationOptions {
+                    alignment: aggregation
+                        .alignment
+                        .resolve_series_start(series.first_timestamp),
+                    ..aggregation
+                }),
+                ..options.clone()
+            };
+            &resolved_options
+        }
+        _ => options,
+    };
+
     let has_aggregation = options.aggregation.is_some();
     let should_reverse_iter = !has_aggregation && is_reverse;
     let should_reverse_aggr = has_aggregation && is_reverse;
@@ -79,6 +99,11 @@ pub fn create_range_iterator<'a>(
             aggregation: options.aggregation,
             value_filter: options.value_filter,
             timestamp_filter: None,
+            timestamp_ranges: None,
+            max_points: None,
+            count_from_end: false,
+            transform: options.transform,
+            function: options.function,
         };
         chain_latest(
             base_iter,
@@ -88,6 +113,23 @@ pub fn create_range_iterator<'a>(
             should_reverse_aggr,
             is_reverse,
         )
+    } else if let Some(ranges) = options.timestamp_ranges.as_ref() {
+        let base_iter = series.multi_range_iter(ranges, should_reverse_iter);
+        let latest_sample = latest_sample.filter(|sample| {
+            ranges
+                .iter()
+                .any(|&(start, end)| sample.timestamp >= start && sample.timestamp <= end)
+        });
+        chain_latest(
+            base_iter,
+            latest_sample,
+            options,
+            grouping,
+            should_reverse_aggr,
+            is_reverse,
+        )
+    } else if is_sample_count_aggregation(options) {
+        create_sample_count_iterator(series, options, grouping, latest_sample, is_reverse)
     } else {
         let base_iter =
             SeriesSampleIterator::from_range_options(series, options, should_reverse_iter);
@@ -118,8 +160,14 @@ pub fn create_sample_iterator_adapter<'a, T: Iterator<Item = Sample> + 'a>(
         .as_ref()
         .map(|f| TimestampFilter::new(f));
     let val_filter = options.value_filter;
+    // FUNCTION is applied first, so that values it maps to NaN can be dropped by FILTER_BY_VALUE
+    let function = options.function;
 
-    let filtered = base_iter.filter(move |sample| {
+    let filtered = base_iter.map(move |sample| match function {
+        Some(function) => function.apply_sample(sample),
+        None => sample,
+    });
+    let filtered = filtered.filter(move |sample| {
         if let Some(ts) = &ts_filter
             && !ts.matches(sample.timestamp)
         {
@@ -134,9 +182,54 @@ pub fn create_sample_iterator_adapter<'a, T: Iterator<Item = Sample> + 'a>(
     });
 
     let count = options.count;
+    // value filters see the stored (or FUNCTION) values; the transform is applied after them
+    let (before, after) = split_transform(options);
+    let transformed = apply_transform(filtered, before);
 
-    // Helper to apply reversal and limits, then box.
-    // This ensures we only box once at the very end of the chain.
+    match &options.aggregation {
+        Some(agg) => {
+            let aggr_iter = create_aggregate_iterator(transformed, options, agg);
+            let aggr_iter = apply_transform(aggr_iter, after);
+            group_and_finalize(aggr_iter, grouping, is_reverse, count)
+        }
+        None => group_and_finalize(transformed, grouping, is_reverse, count),
+    }
+}
+
+/// Splits TRANSFORM into the part applied to samples before aggregation and the part applied
+/// to aggregated values. Without an aggregation, the transform always applies to the samples.
+pub(crate) fn split_transform(
+    options: &RangeOptions,
+) -> (Option<ValueTransform>, Option<ValueTransform>) {
+    match options.transform {
+        Some(transform)
+            if options.aggregation.is_some()
+                && transform.order == TransformOrder::AfterAggregation =>
+        {
+            (None, Some(transform))
+        }
+        transform => (transform, None),
+    }
+}
+
+fn apply_transform<I: Iterator<Item = Sample>>(
+    iter: I,
+    transform: Option<ValueTransform>,
+) -> impl Iterator<Item = Sample> {
+    iter.map(move |sample| match transform {
+        Some(transform) => transform.apply_sample(sample),
+        None => sample,
+    })
+}
+
+/// Applies grouping, reversal and limits, then boxes.
+/// This ensures we only box once at the very end of the chain.
+fn group_and_finalize<'a, I: Iterator<Item = Sample> + 'a>(
+    iter: I,
+    grouping: &Option<RangeGroupingOptions>,
+    is_reverse: bool,
+    count: Option<usize>,
+) -> Box<dyn Iterator<Item = Sample> + 'a> {
     fn finalize<'a, I: Iterator<Item = Sample> + 'a>(
         iter: I,
         is_reverse: bool,
@@ -150,26 +243,89 @@ pub fn create_sample_iterator_adapter<'a, T: Iterator<Item = Sample> + 'a>(
         }
     }
 
-    match (&options.aggregation, grouping) {
-        (Some(agg), Some(grp)) => {
-            let aggr_iter = create_aggregate_iterator(filtered, options, agg);
+    match grouping {
+        Some(grp) => {
             let aggregator = grp.aggregation.create_aggregator();
-            let reducer = ReduceIterator::new(aggr_iter, aggregator);
+            let reducer = ReduceIterator::new(iter, aggregator);
             finalize(reducer, is_reverse, count)
         }
-        (None, Some(grp)) => {
-            let aggregator = grp.aggregation.create_aggregator();
-            let reducer = ReduceIterator::new(filtered, aggregator);
-            finalize(reducer, is_reverse, count)
-        }
-        (Some(agg), None) => {
-            let aggr_iter = create_aggregate_iterator(filtered, options, agg);
-            finalize(aggr_iter, is_reverse, count)
-        }
-        (None, None) => finalize(filtered, is_reverse, count),
+        None => finalize(iter, is_reverse, count),
     }
 }
 
+/// `countall` only depends on sample timestamps, so unless samples are filtered by value, buckets
+/// can be filled from per-chunk sample counts instead of decoding every sample.
+fn is_sample_count_aggregation(options: &RangeOptions) -> bool {
+    options.value_filter.is_none()
+        && options.timestamp_filter.is_none()
+        && options.timestamp_ranges.is_none()
+        && options.aggregation.is_some_and(|agg| {
+            agg.aggregation.aggregation_type() == AggregationType::CountAll
+                && agg.aggregation.filter().is_none()
+        })
+}
+
+fn create_sample_count_iterator<'a>(
+    series: &'a TimeSeries,
+    options: &RangeOptions,
+    grouping: &Option<RangeGroupingOptions>,
+    latest_sample: Option<Sample>,
+    is_reverse: bool,
+) -> Box<dyn Iterator<Item = Sample> + 'a> {
+    let Some(aggregation) = options.aggregation else {
+        unreachable!("sample counts require an aggregation");
+    };
+    let (start_ts, end_ts) = options.get_timestamp_range();
+    let aligned_timestamp = aggregation
+        .alignment
+        .get_aligned_timestamp(start_ts, end_ts);
+    let start_ts = start_ts.max(series.get_min_timestamp());
+
+    let buckets = BucketLocator::new(&aggregation, aligned_timestamp);
+    let counts = series_sample_counts(series, start_ts, end_ts, buckets)
+        .chain(latest_sample.map(|sample| Sample::new(sample.timestamp, 1.0)));
+    let aggr_iter = AggregateIterator::from_sample_counts(counts, &aggregation, aligned_timestamp);
+    // counts do not depend on sample values, so only a transform after aggregation applies
+    let (_, after) = split_transform(options);
+    let aggr_iter = apply_transform(aggr_iter, after);
+    group_and_finalize(aggr_iter, grouping, is_reverse, options.count)
+}
+
+/// Yields samples whose values are the number of samples of `series` in `[start_ts, end_ts]` they
+/// stand for. The part of a chunk lying within a single bucket is counted with
+/// `TimeSeriesChunk::count_in_range`; chunks spanning several buckets yield each sample with a
+/// count of 1.
+fn series_sample_counts<'a>(
+    series: &'a TimeSeries,
+    start_ts: Timestamp,
+    end_ts: Timestamp,
+    buckets: BucketLocator,
+) -> impl Iterator<Item = Sample> + 'a {
+    series
+        .chunks
+        .iter()
+        .skip_while(move |chunk| chunk.last_timestamp() < start_ts)
+        .take_while(move |chunk| chunk.first_timestamp() <= end_ts)
+        .filter(|chunk| !chunk.is_empty())
+        .flat_map(move |chunk| {
+            let first_ts = start_ts.max(chunk.first_timestamp());
+            let last_ts = end_ts.min(chunk.last_timestamp());
+            if first_ts > last_ts {
+                return Box::new(std::iter::empty()) as Box<dyn Iterator<Item = Sample> + 'a>;
+            }
+            if buckets.bucket_start(first_ts) == buckets.bucket_start(last_ts) {
+                let count = chunk.count_in_range(first_ts, last_ts);
+                let sample = (count > 0).then(|| Sample::new(first_ts, count as f64));
+                return Box::new(sample.into_iter());
+            }
+            Box::new(
+                chunk
+                    .range_iter(first_ts, last_ts)
+                    .map(|sample| Sample::new(sample.timestamp, 1.0)),
+            )
+        })
+}
+
 pub(crate) struct ReverseSampleIter<I>
 where
     I: Iterator<Item = Sample>,
@@ -12,9 +12,9 @@ where
     left: L,
     right: R,
     strategy: AsOfJoinStrategy,
-    tolerance: Duration,
+    tolerance: Option<Duration>,
     allow_eq: bool,
-    items: Vec<(Sample, Sample)>,
+    items: Vec<JoinValue>,
     idx: usize,
 }
 
@@ -27,7 +27,7 @@ where
         left: IL,
         right: IR,
         strategy: AsOfJoinStrategy,
-        tolerance: Duration,
+        tolerance: Option<Duration>,
         allow_eq: bool,
     ) -> Self
     where
@@ -46,13 +46,26 @@ where
         }
     }
 
+    /// Every left sample produces a row. Left samples without a right sample within tolerance
+    /// are returned with a nil right side.
     fn init(&mut self) {
         self.is_init = true;
-        let tolerance = self.tolerance.as_millis() as i64;
+        let tolerance = self.tolerance.map(|t| t.as_millis() as i64);
         let left: Vec<Sample> = self.left.by_ref().collect();
         let right: Vec<Sample> = self.right.by_ref().collect();
-        self.items =
-            join_asof_samples(&left, &right, self.strategy, Some(tolerance), self.allow_eq);
+        let matched = join_asof_samples(&left, &right, self.strategy, tolerance, self.allow_eq);
+
+        // matches are produced in left order, with at most one match per left sample
+        let mut matched = matched.into_iter().peekable();
+        self.items = left
+            .into_iter()
+            .map(
+                |l| match matched.next_if(|(ml, _)| ml.timestamp == l.timestamp) {
+                    Some((l, r)) => JoinValue::both(l, r),
+                    None => JoinValue::left(l),
+                },
+            )
+            .collect();
     }
 }
 
@@ -67,12 +80,10 @@ where
         if !self.is_init {
             self.init();
         }
-        match self.items.get(self.idx) {
-            Some((left, right)) => {
-                self.idx += 1;
-                Some(JoinValue::both(*left, *right))
-            }
-            None => None,
+        let item = self.items.get(self.idx).cloned();
+        if item.is_some() {
+            self.idx += 1;
         }
+        item
     }
 }
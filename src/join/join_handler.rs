@@ -1,8 +1,8 @@
 use crate::aggregators::aggregate;
-use crate::common::Sample;
 use crate::common::binop::BinopFunc;
 use crate::common::threads::join;
-use crate::join::{JoinOptions, JoinType, JoinValue, create_join_iter};
+use crate::common::{Sample, Timestamp};
+use crate::join::{DivByZero, JoinOptions, JoinType, JoinValue, create_join_iter, fill_join_iter};
 use crate::series::TimeSeries;
 use joinkit::EitherOrBoth;
 use valkey_module::{ValkeyError, ValkeyResult, ValkeyValue};
@@ -11,13 +11,55 @@ use valkey_module::{ValkeyError, ValkeyResult, ValkeyValue};
 /// The result of a join operation, which can be either samples (if reduced) or raw join values
 pub enum JoinResultType {
     Samples(Vec<Sample>),
+    /// Reduced samples, where `None` marks a null result (e.g. division by zero with
+    /// `DIV_BY_ZERO NULL`)
+    NullableSamples(Vec<(Timestamp, Option<f64>)>),
     Values(Vec<JoinValue>),
 }
 
+impl JoinResultType {
+    /// Applies `f` to every timestamp of the result, e.g. to return them in the unit of a series.
+    pub fn map_timestamps(self, f: impl Fn(Timestamp) -> Timestamp) -> Self {
+        let map_sample = |sample: Sample| Sample::new(f(sample.timestamp), sample.value);
+        match self {
+            JoinResultType::Samples(samples) => {
+                JoinResultType::Samples(samples.into_iter().map(map_sample).collect())
+            }
+            JoinResultType::NullableSamples(samples) => JoinResultType::NullableSamples(
+                samples
+                    .into_iter()
+                    .map(|(ts, value)| (f(ts), value))
+                    .collect(),
+            ),
+            JoinResultType::Values(values) => JoinResultType::Values(
+                values
+                    .into_iter()
+                    .map(|row| {
+                        JoinValue(match row.0 {
+                            EitherOrBoth::Both(left, right) => {
+                                EitherOrBoth::Both(map_sample(left), map_sample(right))
+                            }
+                            EitherOrBoth::Left(left) => EitherOrBoth::Left(map_sample(left)),
+                            EitherOrBoth::Right(right) => EitherOrBoth::Right(map_sample(right)),
+                        })
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
 impl From<JoinResultType> for ValkeyValue {
     fn from(value: JoinResultType) -> Self {
         let arr = match value {
             JoinResultType::Samples(samples) => samples.iter().map(|x| x.into()).collect(),
+            JoinResultType::NullableSamples(samples) => samples
+                .into_iter()
+                .map(|(ts, value)| match value {
+                    Some(value) => Sample::new(ts, value).into(),
+                    None => ValkeyValue::Array(vec![ValkeyValue::Integer(ts), ValkeyValue::Null]),
+                })
+                .collect(),
             JoinResultType::Values(values) => values.into_iter().map(|x| x.into()).collect(),
         };
         ValkeyValue::Array(arr)
@@ -57,9 +99,34 @@ where
         ));
     }
 
-    let join_iter = create_join_iter(left, right, options.join_type);
+    let mut join_iter = create_join_iter(left, right, options.join_type);
+    if let Some(fill) = options.fill {
+        join_iter = fill_join_iter(join_iter, fill);
+    }
 
     let count = options.count.unwrap_or(usize::MAX);
+    if let Some(op) = options.reducer
+        && op.is_division()
+        && options.div_by_zero == DivByZero::Null
+    {
+        let transform = op.get_handler();
+        let iter = join_iter.map(|x| reduce_null_on_zero_divisor(&x, transform));
+
+        if let Some(aggr_options) = &options.aggregation {
+            // null results are treated as missing samples
+            let sample_iter = iter.filter_map(|(ts, value)| value.map(|v| Sample::new(ts, v)));
+            let (start, end) = options.date_range.get_timestamps(None);
+            let aligned_timestamp = aggr_options.alignment.get_aligned_timestamp(start, end);
+            let result = aggregate(aggr_options, aligned_timestamp, sample_iter)
+                .into_iter()
+                .take(count)
+                .collect();
+            return Ok(JoinResultType::Samples(result));
+        }
+
+        return Ok(JoinResultType::NullableSamples(iter.collect()));
+    }
+
     if let Some(op) = options.reducer {
         let transform = op.get_handler();
         let iter = join_iter.map(|x| transform_join_value_to_sample(&x, transform));
@@ -103,6 +170,20 @@ pub(super) fn transform_join_value_to_sample(item: &JoinValue, f: BinopFunc) ->
     }
 }
 
+/// Applies a division reducer, returning `None` when the right (divisor) value is zero.
+fn reduce_null_on_zero_divisor(item: &JoinValue, f: BinopFunc) -> (Timestamp, Option<f64>) {
+    let sample = transform_join_value_to_sample(item, f);
+    let divisor = match item.0 {
+        EitherOrBoth::Both(_, r) | EitherOrBoth::Right(r) => r.value,
+        EitherOrBoth::Left(_) => f64::NAN,
+    };
+    if divisor == 0.0 {
+        (sample.timestamp, None)
+    } else {
+        (sample.timestamp, Some(sample.value))
+    }
+}
+
 fn fetch_samples(ts: &TimeSeries, options: &JoinOptions) -> Vec<Sample> {
     let (start, end) = options.date_range.get_series_range(ts, None, true);
     let mut samples = ts.get_range_filtered(
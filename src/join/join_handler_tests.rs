@@ -1,12 +1,16 @@
 #[cfg(test)]
 mod tests {
-    use crate::aggregators::{AggregationType, BucketAlignment, BucketTimestamp};
+    use crate::aggregators::{AggregationType, BucketAlignment, BucketTimestamp, FillPolicy};
     use crate::common::Sample;
     use crate::join::join_handler::join_internal;
-    use crate::join::{JoinOptions, JoinReducer, JoinResultType, JoinType, JoinValue};
+    use crate::join::{
+        AsOfJoinOptions, AsOfJoinStrategy, DivByZero, JoinOptions, JoinReducer, JoinResultType,
+        JoinType, JoinValue,
+    };
     use crate::series::request_types::AggregationOptions;
     use joinkit::EitherOrBoth;
     use std::ops::Deref;
+    use std::time::Duration;
 
     fn create_basic_samples() -> (Vec<Sample>, Vec<Sample>) {
         let left = vec![
@@ -31,6 +35,8 @@ mod tests {
             date_range: Default::default(),
             timestamp_filter: None,
             value_filter: None,
+            fill: None,
+            div_by_zero: Default::default(),
         }
     }
 
@@ -202,6 +208,356 @@ mod tests {
         }
     }
 
+    fn create_offset_samples() -> (Vec<Sample>, Vec<Sample>) {
+        // left every 10ms, right every 20ms shifted by 5ms
+        let left = (0..5).map(|i| Sample::new(i * 10, i as f64)).collect();
+        let right = (0..3)
+            .map(|i| Sample::new(5 + i * 20, 100.0 + i as f64))
+            .collect();
+        (left, right)
+    }
+
+    fn to_pairs(values: &[JoinValue]) -> Vec<(i64, Option<f64>, Option<f64>)> {
+        values
+            .iter()
+            .map(|v| match v.deref() {
+                EitherOrBoth::Both(l, r) => {
+                    assert_eq!(l.timestamp, r.timestamp);
+                    (l.timestamp, Some(l.value), Some(r.value))
+                }
+                EitherOrBoth::Left(l) => (l.timestamp, Some(l.value), None),
+                EitherOrBoth::Right(r) => (r.timestamp, None, Some(r.value)),
+            })
+            .collect()
+    }
+
+    fn full_join_with_fill(fill: Option<FillPolicy>) -> Vec<(i64, Option<f64>, Option<f64>)> {
+        let (left, right) = create_offset_samples();
+        let mut options = create_basic_options();
+        options.join_type = JoinType::Full;
+        options.fill = fill;
+
+        match join_internal(left, right, &options).unwrap() {
+            JoinResultType::Values(values) => to_pairs(&values),
+            _ => panic!("Expected Values result type"),
+        }
+    }
+
+    #[test]
+    fn test_join_full_fill_null() {
+        let expected = vec![
+            (0, Some(0.0), None),
+            (5, None, Some(100.0)),
+            (10, Some(1.0), None),
+            (20, Some(2.0), None),
+            (25, None, Some(101.0)),
+            (30, Some(3.0), None),
+            (40, Some(4.0), None),
+            (45, None, Some(102.0)),
+        ];
+        assert_eq!(full_join_with_fill(None), expected);
+        assert_eq!(full_join_with_fill(Some(FillPolicy::Null)), expected);
+    }
+
+    #[test]
+    fn test_join_full_fill_zero() {
+        let expected = vec![
+            (0, Some(0.0), Some(0.0)),
+            (5, Some(0.0), Some(100.0)),
+            (10, Some(1.0), Some(0.0)),
+            (20, Some(2.0), Some(0.0)),
+            (25, Some(0.0), Some(101.0)),
+            (30, Some(3.0), Some(0.0)),
+            (40, Some(4.0), Some(0.0)),
+            (45, Some(0.0), Some(102.0)),
+        ];
+        assert_eq!(full_join_with_fill(Some(FillPolicy::Zero)), expected);
+    }
+
+    #[test]
+    fn test_join_full_fill_previous() {
+        // nothing to carry forward before the first sample of a side
+        let expected = vec![
+            (0, Some(0.0), None),
+            (5, Some(0.0), Some(100.0)),
+            (10, Some(1.0), Some(100.0)),
+            (20, Some(2.0), Some(100.0)),
+            (25, Some(2.0), Some(101.0)),
+            (30, Some(3.0), Some(101.0)),
+            (40, Some(4.0), Some(101.0)),
+            (45, Some(4.0), Some(102.0)),
+        ];
+        assert_eq!(full_join_with_fill(Some(FillPolicy::Previous)), expected);
+    }
+
+    #[test]
+    fn test_join_full_fill_previous_with_reducer() {
+        let (left, right) = create_offset_samples();
+        let mut options = create_basic_options();
+        options.join_type = JoinType::Full;
+        options.fill = Some(FillPolicy::Previous);
+        options.reducer = Some(JoinReducer::Sum);
+
+        let JoinResultType::Samples(samples) = join_internal(left, right, &options).unwrap() else {
+            panic!("Expected Samples result type");
+        };
+        assert_eq!(samples.len(), 8);
+        assert!(samples[0].value.is_nan());
+        assert_eq!(samples[1], Sample::new(5, 100.0));
+        assert_eq!(samples[7], Sample::new(45, 106.0));
+    }
+
+    fn asof_join(
+        strategy: AsOfJoinStrategy,
+        tolerance: Option<u64>,
+    ) -> Vec<(i64, Option<f64>, Option<f64>)> {
+        // left every 10ms; right at 8, 20 and 33
+        let left: Vec<Sample> = (0..5).map(|i| Sample::new(i * 10, i as f64)).collect();
+        let right = vec![
+            Sample::new(8, 100.0),
+            Sample::new(20, 200.0),
+            Sample::new(33, 300.0),
+        ];
+        let mut options = create_basic_options();
+        options.join_type = JoinType::AsOf(AsOfJoinOptions {
+            strategy,
+            tolerance: tolerance.map(Duration::from_millis),
+            allow_exact_match: true,
+        });
+
+        match join_internal(left, right, &options).unwrap() {
+            JoinResultType::Values(values) => values
+                .iter()
+                .map(|v| match v.deref() {
+                    EitherOrBoth::Both(l, r) => (l.timestamp, Some(l.value), Some(r.value)),
+                    EitherOrBoth::Left(l) => (l.timestamp, Some(l.value), None),
+                    EitherOrBoth::Right(r) => (r.timestamp, None, Some(r.value)),
+                })
+                .collect(),
+            _ => panic!("Expected Values result type"),
+        }
+    }
+
+    #[test]
+    fn test_join_asof_prior_with_tolerance() {
+        let result = asof_join(AsOfJoinStrategy::Backward, Some(2));
+        assert_eq!(
+            result,
+            vec![
+                // nothing before the first right sample
+                (0, Some(0.0), None),
+                (10, Some(1.0), Some(100.0)),
+                (20, Some(2.0), Some(200.0)),
+                // 20 is 10ms away
+                (30, Some(3.0), None),
+                (40, Some(4.0), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_asof_next_with_tolerance() {
+        let result = asof_join(AsOfJoinStrategy::Forward, Some(3));
+        assert_eq!(
+            result,
+            vec![
+                (0, Some(0.0), None),
+                (10, Some(1.0), None),
+                (20, Some(2.0), Some(200.0)),
+                (30, Some(3.0), Some(300.0)),
+                // nothing after the last right sample
+                (40, Some(4.0), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_asof_nearest_with_tolerance() {
+        let result = asof_join(AsOfJoinStrategy::Nearest, Some(3));
+        assert_eq!(
+            result,
+            vec![
+                (0, Some(0.0), None),
+                (10, Some(1.0), Some(100.0)),
+                (20, Some(2.0), Some(200.0)),
+                (30, Some(3.0), Some(300.0)),
+                (40, Some(4.0), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_asof_prefers_exact_match() {
+        for strategy in [
+            AsOfJoinStrategy::Backward,
+            AsOfJoinStrategy::Forward,
+            AsOfJoinStrategy::Nearest,
+        ] {
+            let result = asof_join(strategy, Some(100));
+            assert_eq!(result[2], (20, Some(2.0), Some(200.0)), "{strategy}");
+        }
+    }
+
+    #[test]
+    fn test_join_asof_without_tolerance_is_unbounded() {
+        let result = asof_join(AsOfJoinStrategy::Backward, None);
+        assert_eq!(result[0], (0, Some(0.0), None));
+        assert_eq!(result[4], (40, Some(4.0), Some(300.0)));
+
+        let result = asof_join(AsOfJoinStrategy::Forward, None);
+        assert_eq!(result[0], (0, Some(0.0), Some(100.0)));
+        assert_eq!(result[4], (40, Some(4.0), None));
+    }
+
+    fn div_join(
+        join_type: JoinType,
+        fill: Option<FillPolicy>,
+        div_by_zero: DivByZero,
+    ) -> Vec<(i64, Option<f64>)> {
+        let left = vec![
+            Sample::new(10, 10.0),
+            Sample::new(20, 20.0),
+            Sample::new(30, 30.0),
+        ];
+        let right = vec![
+            Sample::new(20, 0.0),
+            Sample::new(25, 5.0),
+            Sample::new(30, 3.0),
+        ];
+        let mut options = create_basic_options();
+        options.join_type = join_type;
+        options.fill = fill;
+        options.div_by_zero = div_by_zero;
+        options.reducer = Some(JoinReducer::Div);
+
+        match join_internal(left, right, &options).unwrap() {
+            JoinResultType::Samples(samples) => samples
+                .into_iter()
+                .map(|s| (s.timestamp, Some(s.value)))
+                .collect(),
+            JoinResultType::NullableSamples(samples) => samples,
+            JoinResultType::Values(_) => panic!("Expected reduced samples"),
+        }
+    }
+
+    fn assert_reduced_eq(actual: &[(i64, Option<f64>)], expected: &[(i64, Option<f64>)]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            let same_value = match (a.1, e.1) {
+                (Some(a), Some(e)) => a == e || (a.is_nan() && e.is_nan()),
+                (None, None) => true,
+                _ => false,
+            };
+            assert!(a.0 == e.0 && same_value, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_join_div_by_zero_nan() {
+        let nan = Some(f64::NAN);
+        let cases = [
+            (JoinType::Inner, vec![(20, nan), (30, Some(10.0))]),
+            (JoinType::Left, vec![(10, nan), (20, nan), (30, Some(10.0))]),
+            (
+                JoinType::Right,
+                vec![(20, nan), (25, nan), (30, Some(10.0))],
+            ),
+            (
+                JoinType::Full,
+                vec![(10, nan), (20, nan), (25, nan), (30, Some(10.0))],
+            ),
+        ];
+        for (join_type, expected) in cases {
+            let actual = div_join(join_type, None, DivByZero::Nan);
+            assert_reduced_eq(&actual, &expected);
+        }
+    }
+
+    #[test]
+    fn test_join_div_by_zero_null() {
+        let nan = Some(f64::NAN);
+        let cases = [
+            (JoinType::Inner, vec![(20, None), (30, Some(10.0))]),
+            // a missing divisor is not a zero divisor
+            (
+                JoinType::Left,
+                vec![(10, nan), (20, None), (30, Some(10.0))],
+            ),
+            (
+                JoinType::Right,
+                vec![(20, None), (25, nan), (30, Some(10.0))],
+            ),
+            (
+                JoinType::Full,
+                vec![(10, nan), (20, None), (25, nan), (30, Some(10.0))],
+            ),
+        ];
+        for (join_type, expected) in cases {
+            let actual = div_join(join_type, None, DivByZero::Null);
+            assert_reduced_eq(&actual, &expected);
+        }
+    }
+
+    #[test]
+    fn test_join_div_by_zero_null_after_fill() {
+        // filled values take part in the division, so a zero fill on the right is a zero divisor
+        let actual = div_join(JoinType::Full, Some(FillPolicy::Zero), DivByZero::Null);
+        assert_reduced_eq(
+            &actual,
+            &[(10, None), (20, None), (25, Some(0.0)), (30, Some(10.0))],
+        );
+
+        let actual = div_join(JoinType::Full, Some(FillPolicy::Previous), DivByZero::Null);
+        assert_reduced_eq(
+            &actual,
+            &[
+                (10, Some(f64::NAN)),
+                (20, None),
+                (25, Some(4.0)),
+                (30, Some(10.0)),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_join_div_by_zero_null_asof() {
+        let join_type = JoinType::AsOf(AsOfJoinOptions {
+            strategy: AsOfJoinStrategy::Backward,
+            tolerance: None,
+            allow_exact_match: true,
+        });
+        let actual = div_join(join_type, None, DivByZero::Null);
+        assert_reduced_eq(
+            &actual,
+            &[(10, Some(f64::NAN)), (20, None), (30, Some(10.0))],
+        );
+    }
+
+    #[test]
+    fn test_join_div_by_zero_null_skipped_by_aggregation() {
+        let left = vec![Sample::new(10, 10.0), Sample::new(20, 20.0)];
+        let right = vec![Sample::new(10, 2.0), Sample::new(20, 0.0)];
+        let mut options = create_basic_options();
+        options.reducer = Some(JoinReducer::Div);
+        options.div_by_zero = DivByZero::Null;
+        options.aggregation = Some(AggregationOptions {
+            aggregation: AggregationType::Sum.into(),
+            bucket_duration: 100,
+            timestamp_output: BucketTimestamp::Start,
+            alignment: BucketAlignment::Start,
+            report_empty: false,
+            fill: None,
+            with_counts: false,
+            dedup: None,
+        });
+
+        let JoinResultType::Samples(samples) = join_internal(left, right, &options).unwrap() else {
+            panic!("Expected Samples result type");
+        };
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].value, 5.0);
+    }
+
     #[test]
     fn test_join_with_reducer() {
         let (left, right) = create_basic_samples();
@@ -256,6 +612,9 @@ mod tests {
             timestamp_output: BucketTimestamp::Start,
             alignment: BucketAlignment::Start,
             report_empty: false,
+            fill: None,
+            with_counts: false,
+            dedup: None,
         });
 
         let result = join_internal(left, right, &options).unwrap();
@@ -429,6 +788,9 @@ mod tests {
             timestamp_output: BucketTimestamp::Start,
             alignment: BucketAlignment::Start,
             report_empty: false,
+            fill: None,
+            with_counts: false,
+            dedup: None,
         });
 
         let result = join_internal(left, right, &options).unwrap();
@@ -679,6 +1041,9 @@ mod tests {
             timestamp_output: BucketTimestamp::Start,
             alignment: BucketAlignment::Start,
             report_empty: false,
+            fill: None,
+            with_counts: false,
+            dedup: None,
         });
 
         let result = join_internal(left, right, &options).unwrap();
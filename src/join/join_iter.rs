@@ -1,8 +1,9 @@
 use super::join_right_iter::JoinRightIter;
 use super::{JoinAsOfIter, JoinkitExt};
 use super::{JoinType, JoinValue};
+use crate::aggregators::FillPolicy;
 use crate::common::Sample;
-use joinkit::Joinkit;
+use joinkit::{EitherOrBoth, Joinkit};
 
 pub fn create_join_iter<L, R, IL, IR>(
     left: IL,
@@ -70,6 +71,53 @@ where
     }
 }
 
+/// Fills the missing side of the one-sided rows produced by outer joins.
+///
+/// Series hold at most one sample per timestamp, so each timestamp yields a single row: `Both` when
+/// the timestamp is present in both series, otherwise a one-sided row. When sampling densities
+/// differ, the denser side produces one-sided rows, which are filled as follows:
+/// - `Null`: the missing side is left empty
+/// - `Zero`: the missing side is `0` at the row timestamp
+/// - `Previous`: the missing side carries the last value seen on that side, or is left empty if
+///   there is none yet
+///
+/// Filled rows are emitted as `Both`, with the filled sample at the row timestamp.
+pub fn fill_join_iter(
+    iter: Box<dyn Iterator<Item = JoinValue>>,
+    fill: FillPolicy,
+) -> Box<dyn Iterator<Item = JoinValue>> {
+    let mut prev_left: Option<f64> = None;
+    let mut prev_right: Option<f64> = None;
+
+    let fill_value = move |prev: Option<f64>| match fill {
+        FillPolicy::Zero => Some(0.0),
+        FillPolicy::Previous => prev,
+        FillPolicy::Null | FillPolicy::Linear => None,
+    };
+
+    Box::new(iter.map(move |row| match row.0 {
+        EitherOrBoth::Both(l, r) => {
+            prev_left = Some(l.value);
+            prev_right = Some(r.value);
+            row
+        }
+        EitherOrBoth::Left(l) => {
+            prev_left = Some(l.value);
+            match fill_value(prev_right) {
+                Some(value) => JoinValue::both(l, Sample::new(l.timestamp, value)),
+                None => row,
+            }
+        }
+        EitherOrBoth::Right(r) => {
+            prev_right = Some(r.value);
+            match fill_value(prev_left) {
+                Some(value) => JoinValue::both(Sample::new(r.timestamp, value), r),
+                None => row,
+            }
+        }
+    }))
+}
+
 #[inline]
 fn compare_by_timestamp(left: &Sample, right: &Sample) -> std::cmp::Ordering {
     left.timestamp.cmp(&right.timestamp)
@@ -52,6 +52,7 @@ fn join_reducer_get(key: &str) -> Option<JoinReducer> {
         "sgn_diff" => JoinReducer::SgnDiff,
         "pct_change" => JoinReducer::PctChange,
 
+        "add" => JoinReducer::Sum,
         "sum" => JoinReducer::Sum,
         "avg" => JoinReducer::Avg,
         "max" => JoinReducer::Max,
@@ -86,6 +87,11 @@ impl JoinReducer {
         }
     }
 
+    /// Whether the reducer divides the left value by the right value
+    pub const fn is_division(&self) -> bool {
+        matches!(self, JoinReducer::Div | JoinReducer::Mod)
+    }
+
     pub const fn get_handler(&self) -> BinopFunc {
         use JoinReducer::*;
         match self {
@@ -3,7 +3,7 @@ use joinkit::EitherOrBoth;
 use std::fmt::Display;
 use std::ops::Deref;
 use std::time::Duration;
-use valkey_module::ValkeyValue;
+use valkey_module::{ValkeyError, ValkeyValue};
 
 mod asof;
 mod join_handler;
@@ -15,8 +15,10 @@ mod join_right_iter;
 mod join_handler_tests;
 pub mod sorted_join_semi;
 
+use crate::aggregators::FillPolicy;
 use crate::common::humanize::humanize_duration;
 use crate::common::{Sample, Timestamp};
+use crate::error_consts;
 use crate::series::TimestampRange;
 pub use join_handler::*;
 pub use join_iter::*;
@@ -133,7 +135,8 @@ impl From<EitherOrBoth<Sample, Sample>> for JoinValue {
 #[derive(Clone, Debug, Copy, PartialEq)]
 pub struct AsOfJoinOptions {
     pub strategy: AsOfJoinStrategy,
-    pub tolerance: Duration,
+    /// Maximum distance between matched timestamps. `None` means unlimited.
+    pub tolerance: Option<Duration>,
     pub allow_exact_match: bool,
 }
 
@@ -172,8 +175,8 @@ impl Display for JoinType {
             }
             JoinType::AsOf(options) => {
                 write!(f, "ASOF JOIN {}", options.strategy)?;
-                if !options.tolerance.is_zero() {
-                    write!(f, " TOLERANCE {}", humanize_duration(&options.tolerance))?;
+                if let Some(tolerance) = &options.tolerance {
+                    write!(f, " TOLERANCE {}", humanize_duration(tolerance))?;
                 }
                 if options.allow_exact_match {
                     write!(f, " ALLOW EXACT MATCH")?;
@@ -184,6 +187,29 @@ impl Display for JoinType {
     }
 }
 
+/// The result of a division reducer (`div`, `mod`) when the right value is zero.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum DivByZero {
+    /// Report the result as NaN
+    #[default]
+    Nan,
+    /// Report the result as null
+    Null,
+}
+
+impl TryFrom<&str> for DivByZero {
+    type Error = ValkeyError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let res = hashify::tiny_map_ignore_case! {
+            value.as_bytes(),
+            "nan" => DivByZero::Nan,
+            "null" => DivByZero::Null,
+        };
+        res.ok_or(ValkeyError::Str(error_consts::INVALID_DIV_BY_ZERO))
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct JoinOptions {
     pub join_type: JoinType,
@@ -193,4 +219,7 @@ pub struct JoinOptions {
     pub value_filter: Option<ValueFilter>,
     pub reducer: Option<JoinReducer>,
     pub aggregation: Option<AggregationOptions>,
+    /// How to fill the missing side of rows produced by outer joins
+    pub fill: Option<FillPolicy>,
+    pub div_by_zero: DivByZero,
 }
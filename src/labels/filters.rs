@@ -1,8 +1,9 @@
 use crate::common::constants::METRIC_NAME_LABEL;
 use crate::labels::parse_series_selector;
-use crate::labels::regex::parse_regex_anchored;
+use crate::labels::regex::{literal_prefix, parse_regex_anchored};
 use crate::parser::ParseError;
 use crate::parser::lex::Token;
+use crate::parser::number::parse_number;
 use enquote::enquote;
 use regex::Regex;
 use smallvec::SmallVec;
@@ -21,12 +22,21 @@ pub enum MatchOp {
     NotEqual,
     RegexEqual,
     RegexNotEqual,
+    /// numeric comparisons of label values
+    Gt,
+    Ge,
+    Lt,
+    Le,
 }
 
 impl MatchOp {
     pub fn is_regex(&self) -> bool {
         matches!(self, MatchOp::RegexEqual | MatchOp::RegexNotEqual)
     }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, MatchOp::Gt | MatchOp::Ge | MatchOp::Lt | MatchOp::Le)
+    }
 }
 
 impl Display for MatchOp {
@@ -36,6 +46,10 @@ impl Display for MatchOp {
             MatchOp::NotEqual => write!(f, "!="),
             MatchOp::RegexEqual => write!(f, "=~"),
             MatchOp::RegexNotEqual => write!(f, "!~"),
+            MatchOp::Gt => write!(f, ">"),
+            MatchOp::Ge => write!(f, ">="),
+            MatchOp::Lt => write!(f, "<"),
+            MatchOp::Le => write!(f, "<="),
         }
     }
 }
@@ -49,6 +63,10 @@ impl TryFrom<Token> for MatchOp {
             Token::OpNotEqual => Ok(MatchOp::NotEqual),
             Token::RegexEqual => Ok(MatchOp::RegexEqual),
             Token::RegexNotEqual => Ok(MatchOp::RegexNotEqual),
+            Token::OpGreaterThan => Ok(MatchOp::Gt),
+            Token::OpGreaterEqual => Ok(MatchOp::Ge),
+            Token::OpLessThan => Ok(MatchOp::Lt),
+            Token::OpLessEqual => Ok(MatchOp::Le),
             _ => Err(ParseError::InvalidMatchOperator(value.to_string())),
         }
     }
@@ -175,13 +193,13 @@ impl Hash for PredicateValue {
 
 #[derive(Clone, Debug)]
 pub struct RegexMatcher {
+    /// the fully anchored regex
     pub(crate) regex: Regex,
-    pub value: String, // original (possibly unanchored) string
+    pub value: String, // original string, stripped of its outer anchors
 }
 
 impl Hash for RegexMatcher {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.regex.as_str().hash(state);
         self.value.hash(state);
     }
 }
@@ -198,10 +216,15 @@ impl RegexMatcher {
 
     pub fn is_match(&self, other: &str) -> bool {
         if other.is_empty() {
-            return is_empty_regex_matcher(&self.regex);
+            return is_empty_regex_matcher(self);
         }
         self.regex.is_match(other)
     }
+
+    /// Returns the literal text every matching value starts with, possibly empty.
+    pub fn literal_prefix(&self) -> &str {
+        literal_prefix(&self.value)
+    }
 }
 
 impl TryFrom<&str> for RegexMatcher {
@@ -222,7 +245,88 @@ impl Eq for RegexMatcher {}
 
 impl PartialEq for RegexMatcher {
     fn eq(&self, other: &Self) -> bool {
-        self.regex.as_str() == other.regex.as_str()
+        // all regexes are fully anchored, so `^a$` and `a` are the same matcher
+        self.value == other.value
+    }
+}
+
+/// Compares label values as numbers, e.g. `shard>10`. Values which are not numbers (including
+/// NaN and the empty value) never match, whatever the operator.
+#[derive(Clone, Debug)]
+pub struct NumericMatcher {
+    pub op: MatchOp,
+    pub bound: f64,
+    pub value: String, // original text of the bound
+}
+
+impl NumericMatcher {
+    pub fn create(op: MatchOp, value: &str) -> Result<Self, ParseError> {
+        if !op.is_numeric() {
+            return Err(ParseError::InvalidMatchOperator(op.to_string()));
+        }
+        let bound = parse_label_number(value)
+            .ok_or_else(|| ParseError::InvalidNumber(value.to_string()))?;
+        Ok(Self {
+            op,
+            bound,
+            value: value.to_string(),
+        })
+    }
+
+    pub fn is_match(&self, other: &str) -> bool {
+        let Some(value) = parse_label_number(other) else {
+            return false;
+        };
+        match self.op {
+            MatchOp::Gt => value > self.bound,
+            MatchOp::Ge => value >= self.bound,
+            MatchOp::Lt => value < self.bound,
+            MatchOp::Le => value <= self.bound,
+            _ => false,
+        }
+    }
+
+    /// Returns the opposite comparison, e.g. `<=` for `>`. Like the original, it does not match
+    /// values which are not numbers.
+    pub fn inverse(self) -> Self {
+        let op = match self.op {
+            MatchOp::Gt => MatchOp::Le,
+            MatchOp::Ge => MatchOp::Lt,
+            MatchOp::Lt => MatchOp::Ge,
+            MatchOp::Le => MatchOp::Gt,
+            op => op,
+        };
+        Self { op, ..self }
+    }
+}
+
+/// Parses a label value as a number. NaN is rejected, so that the comparisons of matching values
+/// follow a total order.
+fn parse_label_number(value: &str) -> Option<f64> {
+    if value.is_empty() {
+        return None;
+    }
+    parse_number(value).ok().filter(|value| !value.is_nan())
+}
+
+impl Display for NumericMatcher {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Hash for NumericMatcher {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.op.to_string().hash(state);
+        self.bound.to_bits().hash(state);
+    }
+}
+
+impl Eq for NumericMatcher {}
+
+impl PartialEq for NumericMatcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.op == other.op && self.bound == other.bound
     }
 }
 
@@ -232,6 +336,7 @@ pub enum PredicateMatch {
     NotEqual(PredicateValue),
     RegexEqual(RegexMatcher),
     RegexNotEqual(RegexMatcher),
+    Numeric(NumericMatcher),
 }
 
 impl PredicateMatch {
@@ -241,6 +346,7 @@ impl PredicateMatch {
             PredicateMatch::NotEqual(_) => MatchOp::NotEqual,
             PredicateMatch::RegexEqual(_) => MatchOp::RegexEqual,
             PredicateMatch::RegexNotEqual(_) => MatchOp::RegexNotEqual,
+            PredicateMatch::Numeric(m) => m.op,
         }
     }
 
@@ -248,8 +354,9 @@ impl PredicateMatch {
         match self {
             PredicateMatch::Equal(value) | PredicateMatch::NotEqual(value) => value.is_empty(),
             PredicateMatch::RegexEqual(re) | PredicateMatch::RegexNotEqual(re) => {
-                re.regex.as_str().is_empty()
+                re.value.is_empty()
             }
+            PredicateMatch::Numeric(_) => false,
         }
     }
 
@@ -263,6 +370,7 @@ impl PredicateMatch {
             PredicateMatch::NotEqual(value) => !value.matches(other),
             PredicateMatch::RegexEqual(re) => re.is_match(other),
             PredicateMatch::RegexNotEqual(re) => !re.is_match(other),
+            PredicateMatch::Numeric(m) => m.is_match(other),
         }
     }
 
@@ -272,6 +380,7 @@ impl PredicateMatch {
             PredicateMatch::NotEqual(value) => PredicateMatch::Equal(value),
             PredicateMatch::RegexEqual(re) => PredicateMatch::RegexNotEqual(re),
             PredicateMatch::RegexNotEqual(re) => PredicateMatch::RegexEqual(re),
+            PredicateMatch::Numeric(m) => PredicateMatch::Numeric(m.inverse()),
         }
     }
 
@@ -281,6 +390,7 @@ impl PredicateMatch {
             PredicateMatch::NotEqual(value) => value.cost(),
             PredicateMatch::RegexEqual(_) => 50,
             PredicateMatch::RegexNotEqual(_) => 55,
+            PredicateMatch::Numeric(_) => 40,
         }
     }
 
@@ -288,6 +398,7 @@ impl PredicateMatch {
         match self {
             PredicateMatch::Equal(value) | PredicateMatch::NotEqual(value) => value.text(),
             PredicateMatch::RegexEqual(re) | PredicateMatch::RegexNotEqual(re) => Some(&re.value),
+            PredicateMatch::Numeric(m) => Some(&m.value),
         }
     }
 }
@@ -299,6 +410,7 @@ impl Display for PredicateMatch {
             PredicateMatch::RegexEqual(re) | PredicateMatch::RegexNotEqual(re) => {
                 re as &dyn Display
             }
+            PredicateMatch::Numeric(m) => m as &dyn Display,
         };
 
         write!(f, "{value}")
@@ -324,6 +436,10 @@ impl Hash for PredicateMatch {
                 state.write_u8(4);
                 re.hash(state);
             }
+            PredicateMatch::Numeric(m) => {
+                state.write_u8(5);
+                m.hash(state);
+            }
         }
     }
 }
@@ -337,6 +453,7 @@ impl PartialEq for PredicateMatch {
             (PredicateMatch::NotEqual(v1), PredicateMatch::NotEqual(v2)) => v1 == v2,
             (PredicateMatch::RegexEqual(re1), PredicateMatch::RegexEqual(re2)) => re1 == re2,
             (PredicateMatch::RegexNotEqual(re1), PredicateMatch::RegexNotEqual(re2)) => re1 == re2,
+            (PredicateMatch::Numeric(m1), PredicateMatch::Numeric(m2)) => m1 == m2,
             _ => false,
         }
     }
@@ -374,6 +491,10 @@ impl LabelFilter {
                     matcher: PredicateMatch::RegexNotEqual(re),
                 })
             }
+            MatchOp::Gt | MatchOp::Ge | MatchOp::Lt | MatchOp::Le => Ok(Self {
+                label,
+                matcher: PredicateMatch::Numeric(NumericMatcher::create(match_op, &value)?),
+            }),
         }
     }
 
@@ -446,6 +567,7 @@ impl Display for LabelFilter {
             PredicateMatch::RegexEqual(re) | PredicateMatch::RegexNotEqual(re) => {
                 write!(f, "{}", enquote('"', &re.value))
             }
+            PredicateMatch::Numeric(m) => write!(f, "{m}"),
         }
     }
 }
@@ -457,18 +579,10 @@ impl Hash for LabelFilter {
     }
 }
 
-fn is_empty_regex_matcher(re: &Regex) -> bool {
-    // cheap check
-    let value = re.as_str();
-    let matches_empty = match value.len() {
-        0 => true,
-        1 => value == "^" || value == "$",
-        2 => value == ".*" || value == "^$" || value == "?:",
-        3 => value == "^.*" || value == ".*$",
-        4 => value == "^.*$" || value == "^?:$",
-        _ => false,
-    };
-    matches_empty || re.is_match("")
+fn is_empty_regex_matcher(re: &RegexMatcher) -> bool {
+    // cheap check on the text stripped of its anchors
+    let matches_empty = matches!(re.value.as_str(), "" | ".*");
+    matches_empty || re.regex.is_match("")
 }
 
 fn get_metric_name(filters: &[LabelFilter]) -> Option<&str> {
@@ -750,7 +864,7 @@ fn find_metric_name_matcher(filters: &[LabelFilter]) -> Option<(usize, &str)> {
 
 #[cfg(test)]
 mod tests {
-    use crate::labels::filters::RegexMatcher;
+    use crate::labels::filters::{MatchOp, NumericMatcher, RegexMatcher};
 
     #[test]
     fn test_match_anchoring() {
@@ -811,4 +925,60 @@ mod tests {
         assert!(matcher.is_match("prod"));
         assert!(!matcher.is_match("dev"));
     }
+
+    #[test]
+    fn test_numeric_matcher_mixed_values() {
+        let values = [
+            "-3", "5", "10", "12", "1e2", "0x10", "abc", "", "NaN", "10abc",
+        ];
+        let cases: &[(MatchOp, &[&str])] = &[
+            (MatchOp::Gt, &["12", "1e2", "0x10"]),
+            (MatchOp::Ge, &["10", "12", "1e2", "0x10"]),
+            (MatchOp::Lt, &["-3", "5"]),
+            (MatchOp::Le, &["-3", "5", "10"]),
+        ];
+
+        for (op, expected) in cases {
+            let matcher = NumericMatcher::create(*op, "10").unwrap();
+            let actual: Vec<&str> = values
+                .iter()
+                .copied()
+                .filter(|v| matcher.is_match(v))
+                .collect();
+            assert_eq!(&actual, expected, "evaluating {op}10");
+        }
+    }
+
+    #[test]
+    fn test_numeric_matcher_inverse_excludes_non_numeric() {
+        let matcher = NumericMatcher::create(MatchOp::Gt, "10").unwrap();
+        let inverse = matcher.clone().inverse();
+        assert_eq!(inverse.op, MatchOp::Le);
+        for value in ["5", "10", "12"] {
+            assert_ne!(matcher.is_match(value), inverse.is_match(value));
+        }
+        for value in ["abc", "", "nan"] {
+            assert!(!matcher.is_match(value));
+            assert!(!inverse.is_match(value));
+        }
+    }
+
+    #[test]
+    fn test_numeric_matcher_infinite_bounds() {
+        let matcher = NumericMatcher::create(MatchOp::Lt, "inf").unwrap();
+        assert!(matcher.is_match("1e300"));
+        assert!(!matcher.is_match("inf"));
+
+        let matcher = NumericMatcher::create(MatchOp::Ge, "-inf").unwrap();
+        assert!(matcher.is_match("-inf"));
+        assert!(!matcher.is_match("nan"));
+    }
+
+    #[test]
+    fn test_numeric_matcher_rejects_invalid_bound() {
+        assert!(NumericMatcher::create(MatchOp::Gt, "abc").is_err());
+        assert!(NumericMatcher::create(MatchOp::Gt, "").is_err());
+        assert!(NumericMatcher::create(MatchOp::Gt, "NaN").is_err());
+        assert!(NumericMatcher::create(MatchOp::Equal, "10").is_err());
+    }
 }
@@ -13,6 +13,9 @@ use valkey_module::{ValkeyResult, ValkeyValue, raw};
 
 const VALUE_SEPARATOR: &str = "=";
 const EMPTY_LABEL: &str = "";
+/// Terminator appended to each interned pair. It matches the sentinel of the postings index keys,
+/// so that the index can reference the same pooled string instead of holding its own copy.
+const LABEL_TERMINATOR: char = '\0';
 
 pub struct InternedLabel<'a> {
     pub name: &'a str,
@@ -48,16 +51,23 @@ impl SeriesLabel for InternedLabel<'_> {
 /// We choose to store the label/value pair as a single interned string in the format "key=value". This reduces
 /// the memory overhead associated with storing separate strings for keys and values (8 bytes vs 16 bytes on 64-bit systems).
 ///
+/// Pairs are interned with a trailing terminator ("key=value\0"), which is the exact layout of
+/// the postings index keys. The index therefore shares the allocation rather than duplicating
+/// every label value, which is significant for high-cardinality labels.
+///
 /// The labels are stored in a sorted order to allow for efficient comparison and retrieval.
 #[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
 pub struct MetricName(Vec<InternedString>);
 
 impl GetSize for MetricName {
-    fn get_size(&self) -> usize {
+    // implemented on the heap size so that the labels are accounted for in the memory usage of
+    // containing structs (e.g. `TimeSeries`)
+    fn get_heap_size(&self) -> usize {
         self.0
             .iter()
             .map(|i| {
-                // we count the full size (stack and heap) only for unique strings
+                // we count the full size (stack and heap) only for unique strings. Strings shared
+                // with the index are accounted for in the index's share of the series memory.
                 if i.is_unique() {
                     i.get_size()
                 } else {
@@ -87,7 +97,10 @@ impl MetricName {
     }
 
     fn split_kv(tag: &InternedString) -> Option<(&str, &str)> {
-        tag.split_once(VALUE_SEPARATOR)
+        let tag: &str = tag;
+        tag.strip_suffix(LABEL_TERMINATOR)
+            .unwrap_or(tag)
+            .split_once(VALUE_SEPARATOR)
     }
 
     fn key_of(tag: &InternedString) -> &str {
@@ -100,7 +113,7 @@ impl MetricName {
 
     /// adds a new label to mn with the given key and value.
     pub fn add_label(&mut self, key: &str, value: &str) {
-        let full_label = format!("{key}{VALUE_SEPARATOR}{value}");
+        let full_label = format!("{key}{VALUE_SEPARATOR}{value}{LABEL_TERMINATOR}");
         let interned_value = InternedString::new(&full_label);
 
         match self.find_index(key) {
@@ -3,6 +3,14 @@ use regex::{Regex, RegexBuilder};
 
 /// Sets the approximate size limit, in bytes, of the compiled regex.
 const REGEX_SIZE_LIMIT: usize = 16 * 1024;
+/// Sets the approximate size limit, in bytes, of the cache used by the lazy DFA while matching.
+/// A matcher is evaluated against every value of a label, so this bounds the memory a single
+/// pathological pattern can claim during the scan.
+const REGEX_DFA_SIZE_LIMIT: usize = 256 * 1024;
+/// Maximum nesting depth of groups and repetitions in a pattern.
+const REGEX_NEST_LIMIT: u32 = 64;
+/// Maximum length, in bytes, of a user-supplied pattern.
+const MAX_REGEX_LENGTH: usize = 4096;
 
 /// remove_start_end_anchors removes '^' at the start of expr and '$' at the end of the expr.
 pub fn remove_start_end_anchors(expr: &str) -> &str {
@@ -78,6 +86,16 @@ pub fn try_escape_for_repeat_re(re: &str) -> String {
     result
 }
 
+fn build_regex(re: &str) -> Result<Regex, regex::Error> {
+    // flags to match Prometheus' behavior. Inline flags such as (?i) remain available to patterns.
+    RegexBuilder::new(re)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .nest_limit(REGEX_NEST_LIMIT)
+        .dot_matches_new_line(true)
+        .build()
+}
+
 /// Parse and potentially transform the regex.
 ///
 /// Go and Rust handle the repeat pattern differently,
@@ -87,27 +105,153 @@ pub fn try_escape_for_repeat_re(re: &str) -> String {
 ///
 /// Regexes used in PromQL are fully anchored.
 fn try_parse_re(original_re: &str) -> Result<Regex, ParseError> {
+    if original_re.len() > MAX_REGEX_LENGTH {
+        return Err(ParseError::InvalidRegex(format!(
+            "pattern exceeds {MAX_REGEX_LENGTH} bytes"
+        )));
+    }
+
     let re = format!("^(?:{original_re})$",);
 
-    // flags to match Prometheus' behavior
-    RegexBuilder::new(&re)
-        .size_limit(REGEX_SIZE_LIMIT)
-        .dot_matches_new_line(true)
-        .build()
-        .or_else(|_| Regex::new(&try_escape_for_repeat_re(&re)))
-        .map_err(|_| ParseError::InvalidRegex(original_re.to_string()))
+    let result = match build_regex(&re) {
+        Err(regex::Error::Syntax(_)) => build_regex(&try_escape_for_repeat_re(&re)),
+        result => result,
+    };
+
+    result.map_err(|e| match e {
+        regex::Error::CompiledTooBig(_) => {
+            ParseError::InvalidRegex(format!("{original_re}: pattern is too complex"))
+        }
+        _ => ParseError::InvalidRegex(original_re.to_string()),
+    })
 }
 
+/// Compiles `value` as a fully anchored regex, i.e. `^(?:value)$`, following Prometheus semantics:
+/// `a` matches only the value "a", and `.*a.*` is needed for a substring match.
+///
+/// The pattern is compiled as given. Anchors supplied by the user are redundant inside the
+/// wrapping group but otherwise harmless, so `a`, `^a`, `a$` and `^a$` are all equivalent.
+/// Stripping them before compiling is unsafe (e.g. `^*` would become the invalid `*`).
+///
+/// Returns the compiled regex along with the pattern stripped of its outer anchors, which is used
+/// to detect trivial patterns such as `.*` and `.+` and to compare matchers.
 pub fn parse_regex_anchored(value: &str) -> Result<(Regex, &str), ParseError> {
-    // ensure all regexes are anchored
-    let unanchored = remove_start_end_anchors(value);
-    let regex = try_parse_re(unanchored)?;
-    Ok((regex, unanchored))
+    let regex = try_parse_re(value)?;
+    Ok((regex, remove_start_end_anchors(value)))
+}
+
+/// Returns the literal text every match of the (anchor-stripped) pattern starts with, e.g. `http_`
+/// for `http_.*`. It is used to narrow index walks, so it errs on the side of returning less:
+/// patterns with an alternation have no prefix, and a literal made optional by a quantifier
+/// is dropped.
+pub fn literal_prefix(pattern: &str) -> &str {
+    const META_CHARS: &str = ".[](){}*+?|\\^$";
+    if pattern.contains('|') {
+        return "";
+    }
+    let end = pattern
+        .find(|c: char| META_CHARS.contains(c))
+        .unwrap_or(pattern.len());
+    let prefix = &pattern[..end];
+    match pattern[end..].chars().next() {
+        Some('*' | '?' | '{') => prefix
+            .char_indices()
+            .last()
+            .map_or("", |(i, _)| &prefix[..i]),
+        _ => prefix,
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::labels::regex::try_escape_for_repeat_re;
+    use crate::labels::regex::{
+        MAX_REGEX_LENGTH, literal_prefix, parse_regex_anchored, try_escape_for_repeat_re,
+    };
+
+    #[test]
+    fn test_parse_regex_anchored() {
+        let cases: &[(&str, &str, &[&str], &[&str])] = &[
+            // (pattern, stripped, matches, does not match)
+            ("a", "a", &["a"], &["", "ab", "ba", "bab"]),
+            ("^a$", "a", &["a"], &["", "ab", "ba", "bab"]),
+            ("^a", "a", &["a"], &["ab", "ba"]),
+            ("a$", "a", &["a"], &["ab", "ba"]),
+            (".*a.*", ".*a.*", &["a", "ab", "ba", "bab"], &["", "b"]),
+            ("^.*a.*$", ".*a.*", &["a", "ab", "ba", "bab"], &["", "b"]),
+            ("a|b", "a|b", &["a", "b"], &["ab", "c"]),
+            ("^a|b$", "a|b", &["a", "b"], &["ab", "ba"]),
+            ("^(a|b)$", "(a|b)", &["a", "b"], &["ab"]),
+            ("a.*", "a.*", &["a", "abc"], &["ba"]),
+            (r"a\$", r"a\$", &["a$"], &["a", "a$b"]),
+            ("^*a", "*a", &["a"], &["ba"]),
+            ("^$", "", &[""], &["a"]),
+            (".", ".", &["a", "\n"], &["", "ab"]),
+        ];
+
+        for (pattern, stripped, matches, non_matches) in cases {
+            let (re, text) = parse_regex_anchored(pattern).unwrap();
+            assert_eq!(text, *stripped, "stripped text of {pattern}");
+            for value in matches.iter() {
+                assert!(re.is_match(value), "{pattern} should match {value:?}");
+            }
+            for value in non_matches.iter() {
+                assert!(!re.is_match(value), "{pattern} should not match {value:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_regex_case_insensitive() {
+        let (re, _) = parse_regex_anchored("(?i)ok").unwrap();
+        for value in ["ok", "OK", "Ok", "oK"] {
+            assert!(re.is_match(value), "(?i)ok should match {value}");
+        }
+        // still fully anchored
+        assert!(!re.is_match("NOK"));
+        assert!(!re.is_match("okay"));
+
+        let (re, _) = parse_regex_anchored("^(?i)ok$").unwrap();
+        assert!(re.is_match("OK"));
+
+        // flags can be scoped to part of the pattern
+        let (re, _) = parse_regex_anchored("(?i:err)or").unwrap();
+        assert!(re.is_match("ERRor"));
+        assert!(!re.is_match("ERROR"));
+    }
+
+    #[test]
+    fn test_parse_regex_limits() {
+        let long = "a".repeat(MAX_REGEX_LENGTH + 1);
+        assert!(parse_regex_anchored(&long).is_err());
+
+        // compiles to a program far larger than the size limit
+        assert!(parse_regex_anchored(r"\w{1000}\w{1000}\w{1000}").is_err());
+
+        let nested = format!("{}a{}", "(".repeat(100), ")".repeat(100));
+        assert!(parse_regex_anchored(&nested).is_err());
+    }
+
+    #[test]
+    fn test_parse_regex_anchored_go_repeat() {
+        let (re, _) = parse_regex_anchored("a{b}").unwrap();
+        assert!(re.is_match("a{b}"));
+        assert!(!re.is_match("xa{b}"));
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(literal_prefix("http_.*"), "http_");
+        assert_eq!(literal_prefix("http_requests_total"), "http_requests_total");
+        assert_eq!(literal_prefix("http_(a|b)"), "");
+        assert_eq!(literal_prefix("http_a|grpc"), "");
+        assert_eq!(literal_prefix("https?_.*"), "http");
+        assert_eq!(literal_prefix("ab*"), "a");
+        assert_eq!(literal_prefix("ab+"), "ab");
+        assert_eq!(literal_prefix("a{2}"), "");
+        assert_eq!(literal_prefix(".*_total"), "");
+        assert_eq!(literal_prefix("(?i)http"), "");
+        assert_eq!(literal_prefix(r"http\.x"), "http");
+    }
 
     #[test]
     fn test_convert_re() {
@@ -161,27 +161,38 @@ valkey_module! {
         ["TS.ALTER", commands::ts_alter_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
         ["TS.ADD", commands::ts_add_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
         ["TS.ADDBULK", commands::ts_addbulk_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
+        ["TS.ANNOTATE", commands::ts_annotate_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
         ["TS.GET", commands::ts_get_cmd, "readonly fast", 1, 1, 1, "fast read timeseries"],
         ["TS.MGET", commands::ts_mget_cmd, "readonly fast", 0, 0, -1, "fast read timeseries"],
+        ["TS.MBOUNDS", commands::ts_mbounds_cmd, "readonly fast", 0, 0, -1, "fast read timeseries"],
         ["TS.MADD", commands::ts_madd_cmd, "write deny-oom", 1, -1, 3, "fast write timeseries"],
         ["TS.DEL", commands::ts_del_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
         ["TS.DECRBY", commands::ts_decrby_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
         ["TS.INCRBY", commands::ts_incrby_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
+        ["TS.INGEST", commands::ts_ingest_cmd, "write deny-oom", 0, 0, 0, "write timeseries"],
         ["TS.JOIN", commands::ts_join_cmd, "readonly", 1, 2, 1, "read timeseries"],
         ["TS.MDEL", commands::ts_mdel_cmd, "write deny-oom", 0, 0, -1, "write timeseries"],
+        ["TS.MERGE", commands::ts_merge_cmd, "write deny-oom getkeys-api", 1, -1, 1, "write timeseries"],
         ["TS.MRANGE", commands::ts_mrange_cmd, "readonly", 0, 0, -1, "read timeseries"],
         ["TS.MREVRANGE", commands::ts_mrevrange_cmd, "readonly", 0, 0, -1, "read timeseries"],
         ["TS.RANGE", commands::ts_range_cmd, "readonly", 1, 1, 1, "read timeseries"],
         ["TS.REVRANGE", commands::ts_revrange_cmd, "readonly", 1, 1, 1, "read timeseries"],
+        ["TS.EXPORT", commands::ts_export_cmd, "readonly", 0, 0, 0, "read timeseries"],
         ["TS.INFO", commands::ts_info_cmd, "readonly", 0, 0, 0, "read fast timeseries"],
         ["TS.QUERYINDEX", commands::ts_queryindex_cmd, "readonly", 0, 0, 0, "read timeseries"],
+        ["TS.SERIESDIFF", commands::ts_seriesdiff_cmd, "readonly", 0, 0, 0, "read timeseries"],
         ["TS.CARD", commands::ts_card_cmd, "readonly", 0, 0, 0, "read timeseries"],
         ["TS.LABELNAMES", commands::ts_labelnames_cmd, "readonly", 0, 0, 0, "read timeseries"],
         ["TS.LABELVALUES", commands::ts_labelvalues_cmd, "readonly", 0, 0, 0, "read timeseries"],
         ["TS.LABELSTATS", commands::ts_labelstats_cmd, "readonly", 0, 0, 0, "read timeseries"],
+        ["TS.METRICS", commands::ts_metrics_cmd, "readonly", 0, 0, 0, "read timeseries"],
         ["TS.CREATERULE", commands::ts_createrule_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
         ["TS.DELETERULE", commands::ts_deleterule_cmd, "write deny-oom", 1, 1, 1, "write timeseries"],
+        ["TS.SEAL", commands::ts_seal_cmd, "write deny-oom", 1, 1, 1, "write fast timeseries"],
+        ["TS.HISTOGRAM", commands::ts_histogram_cmd, "readonly", 1, 1, 1, "read timeseries"],
         ["TS.OUTLIERS", commands::ts_outliers_cmd, "readonly deny-oom", 1, 1, 1, "fast read timeseries"],
+        ["TS.GC", commands::ts_gc_cmd, "readonly", 0, 0, 0, "timeseries admin"],
+        ["TS.VERIFYINDEX", commands::ts_verifyindex_cmd, "readonly", 0, 0, 0, "timeseries admin"],
         ["TS._DEBUG", commands::ts_debug_cmd, "readonly", 0, 0, 0, "read timeseries admin"],
     ]
     event_handlers: [
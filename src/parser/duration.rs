@@ -6,7 +6,7 @@ const MILLIS_PER_MINUTE: f64 = 60.0 * MILLIS_PER_SECOND;
 const MILLIS_PER_HOUR: f64 = 60.0 * MILLIS_PER_MINUTE;
 const MILLIS_PER_DAY: f64 = 24.0 * MILLIS_PER_HOUR;
 const MILLIS_PER_WEEK: f64 = 7.0 * MILLIS_PER_DAY;
-const MILLIS_PER_YEAR: f64 = 365.0 * MILLIS_PER_DAY;
+const MONTHS_PER_YEAR: f64 = 12.0;
 
 /// `positive_duration_value` returns positive duration in milliseconds for the given s
 /// and the given step.
@@ -36,9 +36,40 @@ fn validate_duration(ms: f64) -> ParseResult<f64> {
 ///
 /// Duration in s may be combined, i.e., 2h5m, -2h5m or 2h-5m.
 ///
-/// The returned duration value can be negative.
+/// The returned duration value can be negative. Calendar units (`mo`, `y`) are rejected, since
+/// they do not have a fixed length.
 pub fn parse_duration_internal(s: &str) -> ParseResult<f64> {
+    let (months, ms) = parse_duration_segments(s, false)?;
+    debug_assert_eq!(months, 0);
+    Ok(ms)
+}
+
+pub fn parse_duration_value(s: &str) -> ParseResult<i64> {
+    Ok(parse_duration_internal(s)?.round() as i64)
+}
+
+/// A duration which may include calendar months, as used for timestamps relative to now.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RelativeDuration {
+    /// Whole calendar months. Years count as 12 months.
+    pub months: i64,
+    /// The fixed length part, in milliseconds
+    pub millis: i64,
+}
+
+/// Parses a duration like [parse_duration_value], additionally accepting the calendar units `mo`
+/// (months) and `y` (years), e.g. `1y2mo` or `-1mo12h`. Calendar units must be whole numbers.
+pub fn parse_relative_duration(s: &str) -> ParseResult<RelativeDuration> {
+    let (months, millis) = parse_duration_segments(s, true)?;
+    Ok(RelativeDuration {
+        months,
+        millis: millis.round() as i64,
+    })
+}
+
+fn parse_duration_segments(s: &str, allow_calendar: bool) -> ParseResult<(i64, f64)> {
     let mut duration: f64 = 0.0;
+    let mut months: i64 = 0;
     let mut s = s;
     let mut is_minus = false;
 
@@ -50,30 +81,55 @@ pub fn parse_duration_internal(s: &str) -> ParseResult<f64> {
     }
 
     while !s.is_empty() {
-        let cursor = match scan_duration_segment(s) {
-            Ok((mut value, cursor)) => {
-                if is_minus && (value > 0.0) {
-                    value = -value
-                }
-                duration += value;
-                if value < 0f64 {
-                    is_minus = true
+        let (mut value, unit, cursor) = scan_segment(s)?;
+        if is_minus && (value > 0.0) {
+            value = -value
+        }
+        if value < 0f64 {
+            is_minus = true
+        }
+        match unit {
+            SegmentUnit::Millis => duration += value,
+            SegmentUnit::Months if allow_calendar => {
+                if value.fract() != 0.0 {
+                    return Err(ParseError::InvalidDuration(format!(
+                        "calendar months and years must be whole numbers; got {s}"
+                    )));
                 }
-                cursor
+                months = months.saturating_add(value as i64);
             }
-            Err(e) => return Err(e),
-        };
+            SegmentUnit::Months => return Err(calendar_unit_error(s)),
+        }
         s = cursor;
     }
 
-    validate_duration(duration)
+    validate_duration(duration)?;
+    // keep the calendar part within what can be added to a timestamp
+    if months.abs() > MAX_CALENDAR_MONTHS {
+        let msg = format!("duration ({months} months) is too large");
+        return Err(ParseError::General(msg));
+    }
+    Ok((months, duration))
 }
 
-pub fn parse_duration_value(s: &str) -> ParseResult<i64> {
-    Ok(parse_duration_internal(s)?.round() as i64)
+/// 10,000 years, far beyond any timestamp a series can hold.
+const MAX_CALENDAR_MONTHS: i64 = 12 * 10_000;
+
+fn calendar_unit_error(s: &str) -> ParseError {
+    ParseError::InvalidDuration(format!(
+        "calendar units (mo, y) are only allowed in relative timestamps; got {s}"
+    ))
 }
 
-fn scan_duration_segment(s: &str) -> ParseResult<(f64, &str)> {
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SegmentUnit {
+    /// The segment value is in milliseconds
+    Millis,
+    /// The segment value is in calendar months
+    Months,
+}
+
+fn scan_segment(s: &str) -> ParseResult<(f64, SegmentUnit, &str)> {
     fn parse_prefix(str: &str, i: usize) -> ParseResult<f64> {
         let num_str = &str[0..i];
         match num_str.parse::<f64>() {
@@ -94,28 +150,31 @@ fn scan_duration_segment(s: &str) -> ParseResult<(f64, &str)> {
     }
 
     let number_part = parse_prefix(s, i)?;
+    let mut unit = SegmentUnit::Millis;
     let multiplier = match suffix_char {
-        'm' => {
-            if i + 1 < s.len() {
-                let cursor = &s[i..];
-                let curr = cursor.chars().nth(1).unwrap();
-                if curr == 's' {
-                    // duration in ms
-                    i += 1;
-                    1.0
-                } else {
-                    MILLIS_PER_MINUTE
-                }
-            } else {
-                // duration in minutes
-                MILLIS_PER_MINUTE
+        'm' => match s[i..].chars().nth(1) {
+            Some('s') => {
+                // duration in ms
+                i += 1;
+                1.0
             }
-        }
+            Some('o') => {
+                // calendar months
+                i += 1;
+                unit = SegmentUnit::Months;
+                1.0
+            }
+            // duration in minutes
+            _ => MILLIS_PER_MINUTE,
+        },
         's' => MILLIS_PER_SECOND,
         'h' => MILLIS_PER_HOUR,
         'd' => MILLIS_PER_DAY,
         'w' => MILLIS_PER_WEEK,
-        'y' => MILLIS_PER_YEAR,
+        'y' => {
+            unit = SegmentUnit::Months;
+            MONTHS_PER_YEAR
+        }
         _ => {
             i -= 1;
             1.0
@@ -124,7 +183,7 @@ fn scan_duration_segment(s: &str) -> ParseResult<(f64, &str)> {
 
     let duration = number_part * multiplier;
     let remaining = &s[i + 1..];
-    Ok((duration, remaining))
+    Ok((duration, unit, remaining))
 }
 
 #[cfg(test)]
@@ -132,9 +191,17 @@ mod tests {
     use super::*;
     use crate::parser::duration::{
         MILLIS_PER_DAY, MILLIS_PER_MINUTE, MILLIS_PER_SECOND, parse_duration_value,
-        parse_positive_duration_value, scan_duration_segment,
+        parse_positive_duration_value,
     };
 
+    /// Scans a single fixed-length segment, failing on calendar units.
+    fn scan_duration_segment(s: &str) -> ParseResult<(f64, &str)> {
+        match scan_segment(s)? {
+            (value, SegmentUnit::Millis, remaining) => Ok((value, remaining)),
+            (_, SegmentUnit::Months, _) => Err(calendar_unit_error(s)),
+        }
+    }
+
     #[test]
     fn test_scan_duration_segment_empty() {
         let result = scan_duration_segment("");
@@ -154,7 +221,6 @@ mod tests {
             ("2h", 2.0 * MILLIS_PER_HOUR, ""),
             ("3d", 3.0 * MILLIS_PER_DAY, ""),
             ("1w", 1.0 * MILLIS_PER_WEEK, ""),
-            ("0.5y", 0.5 * MILLIS_PER_YEAR, ""),
             ("100ms", 100.0, ""),
         ];
 
@@ -289,7 +355,8 @@ mod tests {
         f("1h", MILLIS_PER_HOUR);
         f("2d", 2.0 * MILLIS_PER_DAY);
         f("3w", 3.0 * MILLIS_PER_WEEK);
-        f("4y", 4.0 * MILLIS_PER_YEAR);
+        f("90d", 90.0 * MILLIS_PER_DAY);
+        f("1w2d", 9.0 * MILLIS_PER_DAY);
         f("1m34s24ms", 94024f64);
         f("-1m34s24ms", -94024.0);
         f("1m-34s24ms", 25976.0);
@@ -302,7 +369,6 @@ mod tests {
         f("1.2h", 1.2 * MILLIS_PER_HOUR);
         f("1.1d", 1.1 * MILLIS_PER_DAY);
         f("1.1w", 1.1 * MILLIS_PER_WEEK);
-        f("1.3y", 1.3 * MILLIS_PER_YEAR);
         f("1.5m3.4s2.4ms", 93402.4);
 
         // Floating-point durations without suffix.
@@ -332,7 +398,51 @@ mod tests {
         f("2W");
         f("3D");
         f("3H");
-        f("3S")
+        f("3S");
+
+        // calendar units have no fixed length
+        f("1mo");
+        f("4y");
+        f("1.3y");
+        f("1d1mo");
+    }
+
+    #[test]
+    fn test_relative_duration() {
+        fn f(s: &str, months: i64, millis: i64) {
+            assert_eq!(
+                parse_relative_duration(s).unwrap(),
+                RelativeDuration { months, millis },
+                "expr {s}"
+            );
+        }
+
+        f("1w", 0, MILLIS_PER_WEEK as i64);
+        f("90d", 0, 90 * MILLIS_PER_DAY as i64);
+        f("1mo", 1, 0);
+        f("2y", 24, 0);
+        f("1y2mo", 14, 0);
+        f("1mo12h", 1, 12 * MILLIS_PER_HOUR as i64);
+        f("-1mo", -1, 0);
+        f("-1y1mo", -13, 0);
+        f("10ms", 0, 10);
+        f("3m", 0, 3 * MILLIS_PER_MINUTE as i64);
+
+        assert!(parse_relative_duration("1.5mo").is_err());
+        assert!(parse_relative_duration("0.5y").is_err());
+        assert!(parse_relative_duration("1Mo").is_err());
+        assert!(parse_relative_duration("100000y").is_err());
+    }
+
+    #[test]
+    fn test_scan_calendar_segment() {
+        assert_eq!(
+            scan_segment("3mo1d").unwrap(),
+            (3.0, SegmentUnit::Months, "1d")
+        );
+        assert_eq!(scan_segment("2y").unwrap(), (24.0, SegmentUnit::Months, ""));
+        assert!(scan_duration_segment("2y").is_err());
+        assert!(scan_duration_segment("1mo").is_err());
     }
 
     #[test]
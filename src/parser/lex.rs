@@ -67,6 +67,18 @@ pub enum Token {
     #[token("!~")]
     RegexNotEqual,
 
+    #[token(">")]
+    OpGreaterThan,
+
+    #[token(">=")]
+    OpGreaterEqual,
+
+    #[token("<")]
+    OpLessThan,
+
+    #[token("<=")]
+    OpLessEqual,
+
     #[regex("\"(?s:[^\"\\\\]|\\\\.)*", unterminated_string_literal)]
     #[regex("'(?s:[^'\\\\]|\\\\.)*", unterminated_string_literal)]
     #[regex("@[^\"'\\s]\\S+", unterminated_string_literal)]
@@ -99,6 +111,10 @@ impl Token {
             Self::OpNotEqual => "!=",
             Self::RegexEqual => "=~",
             Self::RegexNotEqual => "!~",
+            Self::OpGreaterThan => ">",
+            Self::OpGreaterEqual => ">=",
+            Self::OpLessThan => "<",
+            Self::OpLessEqual => "<=",
 
             Self::Eof => "<eof>",
 
@@ -226,6 +242,10 @@ mod tests {
     #[test_case("!=", OpNotEqual)]
     #[test_case("=~", RegexEqual)]
     #[test_case("!~", RegexNotEqual)]
+    #[test_case(">", OpGreaterThan)]
+    #[test_case(">=", OpGreaterEqual)]
+    #[test_case("<", OpLessThan)]
+    #[test_case("<=", OpLessEqual)]
     fn operator(src: &str, tok: Token) {
         test_tokens!(src, [tok]);
     }
@@ -22,6 +22,10 @@ const SECONDARY_TOKENS: &[Token] = &[
     Token::OpNotEqual,
     Token::RegexEqual,
     Token::RegexNotEqual,
+    Token::OpGreaterThan,
+    Token::OpGreaterEqual,
+    Token::OpLessThan,
+    Token::OpLessEqual,
     Token::LeftBrace,
     Token::Eof,
 ];
@@ -133,6 +137,9 @@ fn parse_redis_ts_predicate(
     if op.is_regex() {
         let value = parse_string_literal(lex)?;
         LabelFilter::create(op, label, value)
+    } else if op.is_numeric() {
+        let value = parse_numeric_bound(lex)?;
+        LabelFilter::create(op, label, value)
     } else {
         let value = parse_matcher_value(lex)?;
         create_equality_filter(label, op, value)
@@ -265,6 +272,10 @@ fn parse_label_filter(
             OpNotEqual,
             RegexEqual,
             RegexNotEqual,
+            OpGreaterThan,
+            OpGreaterEqual,
+            OpLessThan,
+            OpLessEqual,
             Comma,
             RightBrace,
             OpOr,
@@ -275,6 +286,10 @@ fn parse_label_filter(
             OpNotEqual,
             RegexEqual,
             RegexNotEqual,
+            OpGreaterThan,
+            OpGreaterEqual,
+            OpLessThan,
+            OpLessEqual,
             Comma,
             RightBrace,
         ][..]
@@ -298,6 +313,9 @@ fn parse_label_filter(
     if op.is_regex() {
         let value = parse_string_literal(p)?;
         Ok((LabelFilter::create(op, label, value)?, None))
+    } else if op.is_numeric() {
+        let value = parse_numeric_bound(p)?;
+        Ok((LabelFilter::create(op, label, value)?, None))
     } else {
         let value = parse_matcher_value(p)?;
         Ok((create_equality_filter(label, op, value)?, None))
@@ -318,6 +336,18 @@ fn parse_string_literal(lexer: &mut Lexer<Token>) -> ParseResult<String> {
     Ok(extract_string_value(value)?.to_string())
 }
 
+/// Parses the right-hand side of a numeric comparison, e.g. `10` in `shard>10`. Quoted values and
+/// identifiers (e.g. `inf`) are accepted, and are validated when the filter is created.
+fn parse_numeric_bound(lexer: &mut Lexer<Token>) -> ParseResult<String> {
+    use Token::*;
+
+    let (tok, text) = expect_one_of_tokens(lexer, &[Number, StringLiteral, Identifier])?;
+    match tok {
+        StringLiteral => Ok(extract_string_value(text)?.to_string()),
+        _ => Ok(text.to_string()),
+    }
+}
+
 fn parse_matcher_value(lexer: &mut Lexer<Token>) -> ParseResult<PredicateValue> {
     use Token::*;
 
@@ -497,4 +497,43 @@ mod tests {
             assert_contains_matcher(second, "env", MatchOp::RegexEqual, "prod|staging");
         });
     }
+
+    #[test]
+    fn test_parse_series_selector_numeric_comparison() {
+        let cases = [
+            ("shard>10", MatchOp::Gt, "10"),
+            ("shard>=10", MatchOp::Ge, "10"),
+            ("shard<-2.5", MatchOp::Lt, "-2.5"),
+            (r#"shard<="1e3""#, MatchOp::Le, "1e3"),
+            ("{shard>10}", MatchOp::Gt, "10"),
+            ("{shard<=inf}", MatchOp::Le, "inf"),
+        ];
+        for (input, op, value) in cases {
+            let result = parse_series_selector(input).unwrap();
+            with_and_matchers(&result, |matchers| {
+                assert_eq!(matchers.len(), 1, "parsing {input}");
+                assert_matcher(&matchers[0], "shard", op, value);
+            });
+        }
+    }
+
+    #[test]
+    fn test_parse_series_selector_numeric_comparison_with_other_matchers() {
+        let result = parse_series_selector(r#"cpu{shard>=2, shard<8, env="prod"}"#).unwrap();
+        with_and_matchers(&result, |matchers| {
+            assert_eq!(matchers.len(), 4);
+            assert_contains_matcher(matchers, "__name__", MatchOp::Equal, "cpu");
+            assert_contains_matcher(matchers, "shard", MatchOp::Ge, "2");
+            assert_contains_matcher(matchers, "shard", MatchOp::Lt, "8");
+            assert_contains_matcher(matchers, "env", MatchOp::Equal, "prod");
+        });
+    }
+
+    #[test]
+    fn test_parse_series_selector_numeric_comparison_invalid_bound() {
+        assert!(parse_series_selector("shard>abc").is_err());
+        assert!(parse_series_selector(r#"shard>"""#).is_err());
+        assert!(parse_series_selector("shard>").is_err());
+        assert!(parse_series_selector("{shard<(1,2)}").is_err());
+    }
 }
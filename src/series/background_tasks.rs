@@ -258,6 +258,16 @@ fn trim_series(ctx: &ThreadSafeContext<DetachedFromClient>, db: i32) -> usize {
     let last_processed = batch.last().map(|s| s.id).unwrap_or(0);
     let processed = batch.len();
 
+    // flushing writes to the destination series, so it runs before the parallel trim
+    for series in batch.iter_mut() {
+        if let Err(e) = series.flush_expiring_buckets(&ctx_) {
+            log_warning(format!(
+                "Failed to flush compactions of series {}: {e}",
+                series.prometheus_metric_name()
+            ));
+        }
+    }
+
     let total_deletes = batch
         .par_mut()
         .map(|series| match series.trim() {
@@ -343,7 +353,9 @@ fn remove_stale_series_internal(db: i32) {
         let was_none = cursor.is_none();
 
         index.with_postings_mut(&mut state, move |postings, _| {
-            let new_cursor = postings.remove_stale_ids(cursor, STALE_ID_BATCH_SIZE);
+            let new_cursor = postings
+                .remove_stale_ids(cursor, STALE_ID_BATCH_SIZE)
+                .next_key;
             if new_cursor.is_some() {
                 // if we have a new cursor, we need to update it
                 set_stale_id_cursor(db, new_cursor);
@@ -390,7 +402,9 @@ fn optimize_indices(_ctx: &ThreadSafeContext<DetachedFromClient>) {
         .into_par()
         .map(|(db, cursor)| {
             let index = get_db_index(db);
-            let new_cursor = index.optimize_incremental(cursor, INDEX_OPTIMIZE_BATCH_SIZE);
+            let new_cursor = index
+                .optimize_incremental(cursor, INDEX_OPTIMIZE_BATCH_SIZE)
+                .next_key;
             (db, new_cursor)
         })
         .collect::<Vec<_>>();
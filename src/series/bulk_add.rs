@@ -346,6 +346,7 @@ pub fn bulk_insert_samples(
 
     // Process new-chunk groups in parallel (each creates its own chunk).
     if !new_groups.is_empty() {
+        series.resolve_auto_encoding();
         let encoding = series.chunk_compression;
         let chunk_size = series.chunk_size_bytes;
         let new_results: Vec<(usize, TimeSeriesChunk, Vec<SampleAddResult>)> = new_groups
@@ -358,10 +359,13 @@ pub fn bulk_insert_samples(
             .collect::<Vec<_>>();
 
         let chunk_count_before = series.chunks.len();
+        let last_chunk_start = series.chunks.last().map(|c| c.first_timestamp());
 
         for (group_pos, chunk, res) in new_results {
             group_results[group_pos] = Some(res);
-            series.chunks.push(chunk);
+            if !chunk.is_empty() {
+                series.chunks.push(chunk);
+            }
         }
 
         // append new chunks to the series
@@ -369,6 +373,10 @@ pub fn bulk_insert_samples(
             // Sort chunks by the first timestamp to maintain order.
             series.chunks.sort_by_key(|c| c.first_timestamp());
             series.update_first_last_timestamps();
+            // samples past a sealed chunk went to new chunks, so the series is no longer sealed
+            if series.chunks.last().map(|c| c.first_timestamp()) != last_chunk_start {
+                series.sealed = false;
+            }
         }
     }
 
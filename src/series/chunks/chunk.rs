@@ -109,7 +109,20 @@ pub trait Chunk: Sized {
         dp_policy: Option<DuplicatePolicy>,
     ) -> TsdbResult<Vec<SampleAddResult>>;
 
-    fn split(&mut self) -> TsdbResult<Self>;
+    /// Splits the chunk in half, returning the upper half as a new chunk.
+    fn split(&mut self) -> TsdbResult<Self>
+    where
+        Self: Sized,
+    {
+        let mid = self.len() / 2;
+        self.split_at(mid)
+    }
+
+    /// Splits the chunk so that the first `index` samples remain in `self`, returning the rest
+    /// as a new chunk. `index` is clamped to the number of samples in the chunk.
+    fn split_at(&mut self, index: usize) -> TsdbResult<Self>
+    where
+        Self: Sized;
 
     fn optimize(&mut self) -> TsdbResult<()> {
         Ok(())
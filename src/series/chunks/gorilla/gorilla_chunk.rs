@@ -298,7 +298,7 @@ impl Chunk for GorillaChunk {
         Ok(merge_state.result)
     }
 
-    fn split(&mut self) -> TsdbResult<Self>
+    fn split_at(&mut self, index: usize) -> TsdbResult<Self>
     where
         Self: Sized,
     {
@@ -309,7 +309,7 @@ impl Chunk for GorillaChunk {
             return Ok(self.clone());
         }
 
-        let mid = self.len() / 2;
+        let mid = index.min(self.len());
         for (i, value) in self.encoder.iter().enumerate() {
             let sample = value?;
             if i < mid {
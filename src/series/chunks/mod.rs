@@ -13,6 +13,6 @@ pub use chunk::*;
 pub use gorilla::*;
 pub use merge::*;
 pub use pco::*;
-// pub use serialization::*;
+pub use serialization::*;
 pub use timeseries_chunk::*;
 pub use uncompressed::*;
@@ -234,7 +234,8 @@ impl PcoChunk {
 
     /// estimate remaining capacity based on the current data size and chunk max_size
     pub fn remaining_capacity(&self) -> usize {
-        self.max_size - self.data_size()
+        // compressed blocks are variable length, so a chunk may overshoot max_size
+        self.max_size.saturating_sub(self.data_size())
     }
 
     /// Estimate the number of samples that can be stored in the remaining capacity
@@ -273,6 +274,27 @@ impl PcoChunk {
         }
     }
 
+    /// Returns the number of samples with timestamps in `[start_ts, end_ts]`. Only the timestamp
+    /// stream is decompressed.
+    pub fn count_in_range(&self, start_ts: Timestamp, end_ts: Timestamp) -> usize {
+        if self.is_empty() || end_ts < self.min_time || start_ts > self.max_time {
+            return 0;
+        }
+        if start_ts <= self.min_time && end_ts >= self.max_time {
+            return self.count;
+        }
+        let mut timestamps = get_pooled_vec_i64(self.count);
+        if let Err(e) = decompress_timestamps(&self.timestamps, &mut timestamps) {
+            log_warning(format!(
+                "pco chunk count_in_range(): error decompressing timestamps: {e}"
+            ));
+            return 0;
+        }
+        let start_idx = timestamps.partition_point(|&ts| ts < start_ts);
+        let end_idx = timestamps.partition_point(|&ts| ts <= end_ts);
+        end_idx.saturating_sub(start_idx)
+    }
+
     fn merge_internal(
         &mut self,
         current: &[Sample],
@@ -492,7 +514,7 @@ impl Chunk for PcoChunk {
         Ok(result)
     }
 
-    fn split(&mut self) -> TsdbResult<Self>
+    fn split_at(&mut self, index: usize) -> TsdbResult<Self>
     where
         Self: Sized,
     {
@@ -502,7 +524,7 @@ impl Chunk for PcoChunk {
             return Ok(result);
         }
 
-        let mid = self.len() / 2;
+        let mid = index.min(self.len());
 
         // this compression method does not do streaming compression, so we have to accumulate all the samples
         // in a new chunk and then swap it with the old
@@ -1309,4 +1331,64 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Vec::<SampleAddResult>::new());
     }
+
+    #[test]
+    fn test_serialize_round_trip_preserves_exact_values() {
+        let values = [
+            0.1 + 0.2,
+            -0.0,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+            f64::MIN,
+            1e-300,
+            std::f64::consts::PI,
+            f64::NAN,
+        ];
+        let samples: Vec<Sample> = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| Sample::new(1000 + i as Timestamp * 17, *v))
+            .collect();
+
+        let mut chunk = PcoChunk::default();
+        chunk.set_data(&samples).unwrap();
+
+        let mut buf = Vec::new();
+        chunk.serialize(&mut buf);
+        let restored = PcoChunk::deserialize(&buf).unwrap();
+
+        assert_eq!(restored.len(), samples.len());
+        assert_eq!(restored.first_timestamp(), chunk.first_timestamp());
+        assert_eq!(restored.last_timestamp(), chunk.last_timestamp());
+
+        let actual = decompress(&restored);
+        for (expected, actual) in samples.iter().zip(actual.iter()) {
+            assert_eq!(expected.timestamp, actual.timestamp);
+            // compare bit patterns so -0.0 and NaN are checked exactly
+            assert_eq!(expected.value.to_bits(), actual.value.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_capacity_accounting_with_variable_length_blocks() {
+        let mut chunk = PcoChunk::with_max_size(256);
+        let data = generate_samples(2000);
+
+        // merging in bulk can overshoot max_size, since blocks are variable length
+        chunk.merge_samples(&data, None).unwrap();
+        assert!(chunk.is_full());
+        assert_eq!(chunk.remaining_capacity(), 0);
+        assert_eq!(chunk.remaining_samples(), 0);
+
+        let mut right = chunk.split().unwrap();
+        assert_eq!(chunk.len() + right.len(), data.len());
+        assert!(chunk.last_timestamp() < right.first_timestamp());
+
+        let mut combined = decompress(&chunk);
+        combined.extend(decompress(&right));
+        assert_eq!(combined, data);
+
+        right.clear();
+        assert_eq!(right.remaining_samples(), 0);
+    }
 }
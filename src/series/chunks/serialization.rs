@@ -1,9 +1,73 @@
-use crate::common::Sample;
-use crate::series::chunks::{GorillaChunk, PcoChunk, TimeSeriesChunk, UncompressedChunk};
+use crate::common::{Sample, Timestamp};
+use crate::error::{TsdbError, TsdbResult};
+use crate::series::chunks::{
+    Chunk, ChunkEncoding, GorillaChunk, PcoChunk, TimeSeriesChunk, UncompressedChunk,
+};
 use valkey_module::{ValkeyError, ValkeyResult};
 
-// for future compatibility
-const VERSION: u32 = 1;
+/// Version of the encoded chunk format produced by [`encode_chunk`]. It must be bumped whenever the
+/// serialized layout of any chunk type changes, so that importers can reject incompatible data.
+pub const CHUNK_EXPORT_VERSION: u8 = 1;
+
+/// Chunk metadata which is available without decoding any samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkMeta {
+    pub encoding: ChunkEncoding,
+    pub first_timestamp: Timestamp,
+    pub last_timestamp: Timestamp,
+    pub count: usize,
+}
+
+impl From<&TimeSeriesChunk> for ChunkMeta {
+    fn from(chunk: &TimeSeriesChunk) -> Self {
+        ChunkMeta {
+            encoding: chunk.get_encoding(),
+            first_timestamp: chunk.first_timestamp(),
+            last_timestamp: chunk.last_timestamp(),
+            count: chunk.len(),
+        }
+    }
+}
+
+/// A chunk in exported form: its metadata and the versioned, still encoded payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodedChunk {
+    pub meta: ChunkMeta,
+    pub data: Vec<u8>,
+}
+
+impl From<&TimeSeriesChunk> for EncodedChunk {
+    fn from(chunk: &TimeSeriesChunk) -> Self {
+        let mut data = Vec::with_capacity(chunk.size() + 16);
+        encode_chunk(chunk, &mut data);
+        EncodedChunk {
+            meta: ChunkMeta::from(chunk),
+            data,
+        }
+    }
+}
+
+/// Writes the compressed payload of `chunk` to `dest`, prefixed by [`CHUNK_EXPORT_VERSION`].
+/// Samples are neither decoded nor re-encoded.
+pub fn encode_chunk(chunk: &TimeSeriesChunk, dest: &mut Vec<u8>) {
+    dest.push(CHUNK_EXPORT_VERSION);
+    chunk.serialize(dest);
+}
+
+/// Restores a chunk written by [`encode_chunk`], rejecting payloads of any other format version.
+pub fn decode_chunk(buf: &[u8]) -> TsdbResult<TimeSeriesChunk> {
+    let Some((&version, payload)) = buf.split_first() else {
+        return Err(TsdbError::DecodingError(
+            "Empty buffer decoding chunk".to_string(),
+        ));
+    };
+    if version != CHUNK_EXPORT_VERSION {
+        return Err(TsdbError::DecodingError(format!(
+            "Unsupported chunk export version: {version}"
+        )));
+    }
+    TimeSeriesChunk::deserialize(payload)
+}
 
 pub fn samples_to_chunk(samples: &[Sample]) -> ValkeyResult<TimeSeriesChunk> {
     let mut chunk = if samples.len() >= 1000 {
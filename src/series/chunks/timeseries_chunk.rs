@@ -68,7 +68,7 @@ impl TimeSeriesChunk {
     pub fn utilization(&self) -> f64 {
         let used = self.size();
         let total = self.max_size();
-        (used / total) as f64
+        used as f64 / total as f64
     }
 
     /// Get an estimate of the remaining capacity in number of samples
@@ -121,6 +121,25 @@ impl TimeSeriesChunk {
         false
     }
 
+    /// Returns the number of samples with timestamps in `[start_ts, end_ts]`. The result is the
+    /// same as `range_iter(start_ts, end_ts).count()`, but sample values are not decoded unless
+    /// the encoding interleaves them with timestamps.
+    pub fn count_in_range(&self, start_ts: Timestamp, end_ts: Timestamp) -> usize {
+        use TimeSeriesChunk::*;
+        if self.is_empty() || !self.overlaps(start_ts, end_ts) {
+            return 0;
+        }
+        if self.is_contained_by_range(start_ts, end_ts) {
+            return self.len();
+        }
+        match self {
+            Uncompressed(chunk) => chunk.count_in_range(start_ts, end_ts),
+            // timestamps and values share one bit stream, so both have to be read
+            Gorilla(chunk) => chunk.range_iter(start_ts, end_ts).count(),
+            Pco(chunk) => chunk.count_in_range(start_ts, end_ts),
+        }
+    }
+
     // todo: make this a trait method
     pub fn iter(&self) -> Box<dyn Iterator<Item = Sample> + '_> {
         use TimeSeriesChunk::*;
@@ -236,6 +255,33 @@ impl TimeSeriesChunk {
         Ok(res.iter().filter(|s| s.is_ok()).count())
     }
 
+    /// Appends all samples of `other` to this chunk, provided both chunks share the same encoding,
+    /// `other` starts strictly after this chunk ends, and their combined size fits within this
+    /// chunk's max size. Returns `false` (leaving both chunks untouched) if the merge is not possible.
+    /// An empty `other` is never merged.
+    pub fn merge_with(&mut self, other: &TimeSeriesChunk) -> TsdbResult<bool> {
+        if other.is_empty() {
+            return Ok(false);
+        }
+        if self.get_encoding() != other.get_encoding()
+            || self.size() + other.size() > self.max_size()
+        {
+            return Ok(false);
+        }
+        if self.is_empty() {
+            *self = other.clone();
+            return Ok(true);
+        }
+        if other.first_timestamp() <= self.last_timestamp() {
+            return Ok(false);
+        }
+        let mut samples = Vec::with_capacity(self.len() + other.len());
+        samples.extend(self.iter());
+        samples.extend(other.iter());
+        self.set_data(&samples)?;
+        Ok(true)
+    }
+
     pub fn memory_usage(&self) -> usize {
         size_of::<Self>() + self.get_heap_size()
     }
@@ -413,15 +459,15 @@ impl Chunk for TimeSeriesChunk {
         }
     }
 
-    fn split(&mut self) -> TsdbResult<Self>
+    fn split_at(&mut self, index: usize) -> TsdbResult<Self>
     where
         Self: Sized,
     {
         use TimeSeriesChunk::*;
         match self {
-            Uncompressed(chunk) => Ok(Uncompressed(chunk.split()?)),
-            Gorilla(chunk) => Ok(Gorilla(chunk.split()?)),
-            Pco(chunk) => Ok(Pco(chunk.split()?)),
+            Uncompressed(chunk) => Ok(Uncompressed(chunk.split_at(index)?)),
+            Gorilla(chunk) => Ok(Gorilla(chunk.split_at(index)?)),
+            Pco(chunk) => Ok(Pco(chunk.split_at(index)?)),
         }
     }
 
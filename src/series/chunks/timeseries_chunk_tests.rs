@@ -7,7 +7,10 @@ mod tests {
     use crate::series::chunks::merge::merge_by_capacity;
     use crate::series::{
         DuplicatePolicy, SampleAddResult,
-        chunks::{Chunk, ChunkEncoding, TimeSeriesChunk},
+        chunks::{
+            CHUNK_EXPORT_VERSION, Chunk, ChunkEncoding, EncodedChunk, TimeSeriesChunk,
+            decode_chunk, encode_chunk,
+        },
     };
     use crate::tests::generators::DataGenerator;
     use std::time::Duration;
@@ -2444,6 +2447,39 @@ mod tests {
         assert!(chunk.has_samples_in_range(5, 55)); // Wider range including all samples
     }
 
+    #[test]
+    fn test_count_in_range_matches_range_iter() {
+        let mut samples = generate_random_samples(500);
+        // NaN samples are still samples
+        samples[7].value = f64::NAN;
+        let first_ts = samples[0].timestamp;
+        let last_ts = samples[samples.len() - 1].timestamp;
+
+        let ranges = [
+            (first_ts, last_ts),
+            (first_ts - 5000, last_ts + 5000),
+            (first_ts + 1, last_ts - 1),
+            (first_ts + 500, first_ts + 500), // between samples
+            (first_ts + 10_000, first_ts + 10_000),
+            (first_ts + 123_456, last_ts + 1),
+            (0, first_ts - 1),
+            (last_ts + 1, i64::MAX),
+        ];
+
+        for encoding in CHUNK_TYPES {
+            let mut chunk = TimeSeriesChunk::new(encoding, 64 * 1024);
+            chunk.set_data(&samples).unwrap();
+
+            for (start, end) in ranges {
+                assert_eq!(
+                    chunk.count_in_range(start, end),
+                    chunk.range_iter(start, end).count(),
+                    "{encoding}: range {start}..={end}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_has_samples_in_range_empty_chunk() {
         let chunk = TimeSeriesChunk::new(ChunkEncoding::Uncompressed, 100);
@@ -2650,4 +2686,39 @@ mod tests {
             assert_eq!(original_samples, deserialized_samples);
         }
     }
+
+    #[test]
+    fn test_encode_chunk_round_trip() {
+        for &encoding in CHUNK_TYPES.iter() {
+            let mut chunk = TimeSeriesChunk::new(encoding, 4096);
+            for sample in generate_random_samples(200).iter() {
+                chunk.add_sample(sample).unwrap();
+            }
+
+            let encoded = EncodedChunk::from(&chunk);
+            assert_eq!(encoded.data[0], CHUNK_EXPORT_VERSION);
+            assert_eq!(encoded.meta.encoding, encoding);
+            assert_eq!(encoded.meta.count, 200);
+            assert_eq!(encoded.meta.first_timestamp, chunk.first_timestamp());
+            assert_eq!(encoded.meta.last_timestamp, chunk.last_timestamp());
+
+            let decoded = decode_chunk(&encoded.data).unwrap();
+            assert_eq!(decoded, chunk);
+        }
+    }
+
+    #[test]
+    fn test_decode_chunk_rejects_unknown_version() {
+        let mut chunk = TimeSeriesChunk::new(ChunkEncoding::Gorilla, 1024);
+        chunk.add_sample(&Sample::new(100, 1.0)).unwrap();
+
+        let mut data = Vec::new();
+        encode_chunk(&chunk, &mut data);
+        data[0] = CHUNK_EXPORT_VERSION + 1;
+        assert!(matches!(
+            decode_chunk(&data),
+            Err(TsdbError::DecodingError(_))
+        ));
+        assert!(decode_chunk(&[]).is_err());
+    }
 }
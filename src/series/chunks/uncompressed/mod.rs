@@ -125,6 +125,13 @@ impl UncompressedChunk {
         SampleIter::vec(slice)
     }
 
+    /// Returns the number of samples with timestamps in `[start_ts, end_ts]`.
+    pub fn count_in_range(&self, start_ts: Timestamp, end_ts: Timestamp) -> usize {
+        let start_idx = self.samples.partition_point(|s| s.timestamp < start_ts);
+        let end_idx = self.samples.partition_point(|s| s.timestamp <= end_ts);
+        end_idx.saturating_sub(start_idx)
+    }
+
     pub fn samples_by_timestamps(&self, timestamps: &[Timestamp]) -> TsdbResult<Vec<Sample>> {
         if self.len() == 0 || timestamps.is_empty() {
             return Ok(vec![]);
@@ -368,10 +375,18 @@ impl Chunk for UncompressedChunk {
         if samples.len() == 1 {
             if self.is_empty() {
                 self.add_sample(&first)?;
-            } else {
-                self.upsert_sample(first, DuplicatePolicy::KeepLast)?;
+                return Ok(vec![SampleAddResult::Ok(first)]);
+            }
+            let policy = dp_policy.unwrap_or(DuplicatePolicy::KeepLast);
+            if let Err(e) = self.upsert_sample(first, policy) {
+                return match e {
+                    TsdbError::DuplicateSample(_) => Ok(vec![SampleAddResult::Duplicate]),
+                    e => Err(e),
+                };
             }
-            return Ok(vec![SampleAddResult::Ok(first)]);
+            // report the stored value, which the duplicate policy may have changed
+            let stored = self.get_sample(first.timestamp).unwrap_or(first);
+            return Ok(vec![SampleAddResult::Ok(stored)]);
         }
 
         if self.is_empty() || first.timestamp > self.last_timestamp() {
@@ -425,7 +440,7 @@ impl Chunk for UncompressedChunk {
         Ok(state.res)
     }
 
-    fn split(&mut self) -> TsdbResult<Self>
+    fn split_at(&mut self, index: usize) -> TsdbResult<Self>
     where
         Self: Sized,
     {
@@ -439,9 +454,9 @@ impl Chunk for UncompressedChunk {
             return Ok(result);
         }
 
-        let half = self.samples.len() / 2;
+        let mid = index.min(self.samples.len());
         let samples = std::mem::take(&mut self.samples);
-        let (left, right) = samples.split_at(half);
+        let (left, right) = samples.split_at(mid);
         self.samples = left.to_vec();
 
         Ok(Self {
@@ -75,8 +75,12 @@ impl RdbSerializable for CompactionRule {
 
     fn rdb_load(rdb: *mut raw::RedisModuleIO) -> ValkeyResult<Self> {
         let dest_id = raw::load_unsigned(rdb)? as SeriesRef;
-        let aggregator = Aggregator::rdb_load(rdb)?;
+        let mut aggregator = Aggregator::rdb_load(rdb)?;
         let bucket_duration = raw::load_unsigned(rdb)?;
+        // older versions persisted the rate window in seconds
+        if let Aggregator::Rate(r) = &mut aggregator {
+            r.set_window_ms(bucket_duration);
+        }
         let align_timestamp = rdb_load_timestamp(rdb)?;
         let start_ts = rdb_load_timestamp(rdb)?;
         let has_samples = rdb_load_bool(rdb)?;
@@ -116,6 +120,8 @@ impl<'a> CompactionContext<'a> {
 
     fn start_bucket(&mut self, bucket_start: Timestamp, sample: Sample) {
         self.rule.bucket_start = Some(bucket_start);
+        let bucket_end = bucket_start.saturating_add_unsigned(self.rule.bucket_duration);
+        self.rule.aggregator.set_bucket_end(bucket_end);
         // Start a new bucket with the new sample
         self.update(sample.timestamp, sample.value);
     }
@@ -134,6 +140,9 @@ pub enum CompactionOp {
     Upsert(Sample),
     /// Remove a range from source and reflect it into destinations (and ongoing aggregation state)
     RemoveRange { start: Timestamp, end: Timestamp },
+    /// Write the partial value of open buckets which started before `before` to the destinations,
+    /// ahead of the source trimming the samples feeding them
+    Flush { before: Timestamp },
 }
 
 pub fn apply_compaction(
@@ -158,6 +167,7 @@ fn apply_op(ctx: &mut CompactionContext<'_>, op: CompactionOp) -> TsdbResult<()>
         CompactionOp::RemoveRange { start, end } => {
             handle_compaction_range_removal(ctx, start, end)
         }
+        CompactionOp::Flush { before } => flush_open_bucket(ctx, before),
     }
 }
 
@@ -228,16 +238,24 @@ fn handle_compaction_upsert(ctx: &mut CompactionContext, sample: Sample) -> Tsdb
     };
 
     let duration = ctx.rule.bucket_duration;
-    let bucket_end = current_bucket_start.saturating_add_unsigned(duration);
+    let bucket_end = bucket_start.saturating_add_unsigned(duration);
 
     if bucket_start == current_bucket_start {
+        if ctx.parent.is_older_than_retention(bucket_start) {
+            // The bucket reaches past the source retention, so recalculating it from the source would
+            // drop the expired samples. Fold the sample into the running aggregate instead, which is
+            // exact for a new timestamp, while a replaced value stays in the aggregate.
+            ctx.update(ts, sample.value);
+            return Ok(());
+        }
         // This sample belongs to the current aggregation bucket
         // We need to recalculate the entire bucket since we don't know what changed
         recalculate_current_bucket(ctx, current_bucket_start, bucket_end)?;
         return Ok(());
     }
 
-    // This is a historical upsert - need to recalculate the affected bucket
+    // This is a historical upsert - need to recalculate the affected bucket only. Its end must be
+    // derived from its own start, otherwise every bucket up to the open one is folded into it.
     recalculate_bucket(ctx, bucket_start, bucket_end, null_ts_filter)
 }
 
@@ -248,6 +266,7 @@ fn recalculate_current_bucket(
     bucket_end: Timestamp,
 ) -> TsdbResult<()> {
     // Reset the aggregator and recalculate from all samples in the bucket
+    ctx.rule.aggregator.set_bucket_end(bucket_end);
     let has_samples = calculate_range(
         ctx.parent,
         &mut ctx.rule.aggregator,
@@ -277,6 +296,7 @@ where
     // Create a new aggregator for this bucket
     let mut bucket_aggregator = ctx.rule.aggregator.clone();
     AggregationHandler::reset(&mut bucket_aggregator);
+    bucket_aggregator.set_bucket_end(bucket_end);
 
     // Aggregate all samples in this bucket
     let has_samples = calculate_range(
@@ -298,6 +318,20 @@ where
     Ok(())
 }
 
+/// Writes the partial value of the open bucket to the destination if the bucket started before
+/// `before`. The bucket stays open, and the value written when it closes replaces the partial one.
+fn flush_open_bucket(ctx: &mut CompactionContext, before: Timestamp) -> TsdbResult<()> {
+    let Some(bucket_start) = ctx.rule.bucket_start else {
+        return Ok(());
+    };
+    if bucket_start >= before || !ctx.has_samples() {
+        return Ok(());
+    }
+    let mut aggregator = ctx.rule.aggregator.clone();
+    let value = AggregationHandler::finalize(&mut aggregator);
+    add_dest_bucket(ctx, bucket_start, value)
+}
+
 /// When a range of samples is removed, we need to remove samples in the corresponding
 /// rule destination series that overlap with the range.
 ///
@@ -365,10 +399,10 @@ fn adjust_current_bucket_after_removal(
         let mut new_aggregator = ctx.rule.aggregator.clone();
         AggregationHandler::reset(&mut new_aggregator);
 
-        // Re-aggregate samples that are not in the removal range
+        // Re-aggregate samples that are not in the removal range. The bucket end is exclusive.
         let bucket_samples = ctx
             .parent
-            .range_iter(current_bucket_start, current_bucket_end)
+            .range_iter(current_bucket_start, current_bucket_end - 1)
             .filter(|sample| sample.timestamp < removal_start || sample.timestamp > removal_end);
 
         let mut has_samples = false;
@@ -643,6 +677,33 @@ impl TimeSeries {
         }
         apply_compaction(ctx, self, CompactionOp::Upsert(value))
     }
+
+    /// Flushes the open buckets of the compaction rules which are fed by samples about to fall out
+    /// of retention, so that trimming the series does not lose them. Unlike other compaction ops,
+    /// this does not cascade to the rules of the destination series, which are trimmed on their own.
+    pub fn flush_expiring_buckets(&mut self, ctx: &Context) -> TsdbResult<()> {
+        if self.rules.is_empty() || self.retention.is_zero() {
+            return Ok(());
+        }
+        let min_timestamp = self.get_min_timestamp();
+        if self.first_timestamp >= min_timestamp {
+            return Ok(());
+        }
+
+        let destinations = get_compaction_series(ctx, self);
+        if destinations.is_empty() {
+            return Ok(());
+        }
+        let mut added: SmallVec<SeriesRef, TEMP_VEC_LEN> = SmallVec::new();
+        let op = CompactionOp::Flush {
+            before: min_timestamp,
+        };
+        apply_rules_on_destinations(self, destinations, op, &mut added)?;
+        if !added.is_empty() {
+            notify_compaction(ctx, &added);
+        }
+        Ok(())
+    }
 }
 
 pub(crate) fn get_latest_compaction_sample(ctx: &Context, series: &TimeSeries) -> Option<Sample> {
@@ -653,6 +714,10 @@ pub(crate) fn get_latest_compaction_sample(ctx: &Context, series: &TimeSeries) -
     };
 
     let rule = parent.get_rule_by_dest_id(series.id)?;
+    if !rule.has_samples {
+        // the open bucket is empty (e.g. all of its samples were removed)
+        return None;
+    }
     let start = rule.bucket_start?;
 
     let mut agg = rule.aggregator.clone();
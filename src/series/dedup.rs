@@ -0,0 +1,100 @@
+use crate::series::request_types::DedupOptions;
+
+/// Drops rows repeating the values of the previously emitted row, keeping the first and last row
+/// of each run of equal values so that the points where the values change are preserved. The
+/// final row is always kept. `values` returns the values of a row; rows are equal if all their
+/// values are.
+pub fn dedup_runs<T>(rows: Vec<T>, options: DedupOptions, values: impl Fn(&T) -> &[f64]) -> Vec<T> {
+    if rows.len() <= 2 {
+        return rows;
+    }
+
+    let is_equal = |a: &[f64], b: &[f64]| {
+        a.len() == b.len()
+            && a.iter()
+                .zip(b)
+                .all(|(x, y)| x == y || (options.nan_equal && x.is_nan() && y.is_nan()))
+    };
+
+    let mut result: Vec<T> = Vec::with_capacity(rows.len());
+    // the latest row of the current run, if it has not been emitted yet
+    let mut pending: Option<T> = None;
+
+    for row in rows {
+        let same_run = result
+            .last()
+            .is_some_and(|emitted| is_equal(values(emitted), values(&row)));
+        if same_run {
+            pending = Some(row);
+            continue;
+        }
+        if let Some(last) = pending.take() {
+            result.push(last);
+        }
+        result.push(row);
+    }
+    if let Some(last) = pending {
+        result.push(last);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Sample;
+
+    fn dedup(values: &[f64], nan_equal: bool) -> Vec<Sample> {
+        let samples = values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| Sample::new(i as i64, value))
+            .collect();
+        dedup_runs(samples, DedupOptions { nan_equal }, |s| {
+            std::slice::from_ref(&s.value)
+        })
+    }
+
+    fn timestamps(samples: &[Sample]) -> Vec<i64> {
+        samples.iter().map(|s| s.timestamp).collect()
+    }
+
+    #[test]
+    fn test_dedup_keeps_run_boundaries() {
+        let result = dedup(&[1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0, 1.0, 1.0], true);
+        assert_eq!(timestamps(&result), vec![0, 3, 4, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_dedup_always_keeps_final_row() {
+        let result = dedup(&[5.0, 5.0, 5.0, 5.0], true);
+        assert_eq!(timestamps(&result), vec![0, 3]);
+
+        let result = dedup(&[1.0, 2.0, 3.0], true);
+        assert_eq!(timestamps(&result), vec![0, 1, 2]);
+
+        assert!(dedup(&[], true).is_empty());
+        assert_eq!(timestamps(&dedup(&[4.0], true)), vec![0]);
+    }
+
+    #[test]
+    fn test_dedup_nan_equality() {
+        let values = [f64::NAN, f64::NAN, f64::NAN, 1.0];
+        assert_eq!(timestamps(&dedup(&values, true)), vec![0, 2, 3]);
+        assert_eq!(timestamps(&dedup(&values, false)), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_dedup_compares_all_values() {
+        let rows: Vec<(i64, Vec<f64>)> = vec![
+            (0, vec![1.0, 2.0]),
+            (1, vec![1.0, 2.0]),
+            (2, vec![1.0, 2.0]),
+            (3, vec![1.0, 3.0]),
+            (4, vec![1.0, 3.0]),
+        ];
+        let result = dedup_runs(rows, DedupOptions { nan_equal: true }, |row| &row.1);
+        let timestamps: Vec<i64> = result.iter().map(|row| row.0).collect();
+        assert_eq!(timestamps, vec![0, 2, 3, 4]);
+    }
+}
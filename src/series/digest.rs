@@ -41,6 +41,10 @@ pub(super) fn calc_rounding_digest(rounding: &RoundingStrategy, digest: &mut Dig
             digest.add_string_buffer(b"dec");
             digest.add_long_long(*digits as i64);
         }
+        RoundingStrategy::Multiple(step) => {
+            digest.add_string_buffer(b"mul");
+            digest.add_long_long(step.to_bits() as i64);
+        }
     };
 }
 
@@ -1,3 +1,4 @@
+use crate::common::string_interner::InternedString;
 use blart::{AsBytes, NoPrefixesBytes};
 use get_size2::GetSize;
 use std::borrow::Borrow;
@@ -5,8 +6,16 @@ use std::fmt::Display;
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 
+/// Key of the postings index, stored as a sentinel-terminated string so that no key is a prefix
+/// of another (required by the ART).
+///
+/// Keys are backed by the global string pool. Since `MetricName` interns its `name=value` pairs
+/// in the same sentinel-terminated form, an index key and the labels of every series carrying
+/// that pair share a single allocation. This matters for high-cardinality labels (e.g. `trace_id`),
+/// where the key bytes would otherwise dominate the memory of the index. The pooled string is
+/// released once the last series referencing it is dropped and its postings entry is removed.
 #[derive(Debug, Clone, PartialEq, Eq, GetSize)]
-pub struct IndexKey(Box<[u8]>);
+pub struct IndexKey(InternedString);
 
 const SENTINEL: u8 = 0;
 
@@ -16,8 +25,7 @@ impl IndexKey {
     }
 
     pub fn as_str(&self) -> &str {
-        let buf = &self.0[..self.0.len() - 1];
-        std::str::from_utf8(buf).unwrap()
+        self.sub_string(0)
     }
 
     pub fn split(&self) -> Option<(&str, &str)> {
@@ -27,14 +35,20 @@ impl IndexKey {
     }
 
     pub(crate) fn sub_string(&self, start: usize) -> &str {
-        let buf = &self.0[start..self.0.len() - 1];
-        // SAFETY: We always ensure that the inner bytes are valid UTF-8 when constructing an IndexKey.
-        unsafe { std::str::from_utf8_unchecked(buf) }
+        let key: &str = &self.0;
+        &key[start..key.len() - 1]
     }
 
     pub fn len(&self) -> usize {
         self.0.len() - 1
     }
+
+    /// Returns the number of external references to the pooled key string (the index entry
+    /// itself included).
+    #[cfg(test)]
+    pub(crate) fn ref_count(&self) -> usize {
+        self.0.ref_count()
+    }
 }
 
 impl Display for IndexKey {
@@ -47,13 +61,13 @@ impl Deref for IndexKey {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        self.0.as_bytes()
     }
 }
 
 impl AsBytes for IndexKey {
     fn as_bytes(&self) -> &[u8] {
-        &self.0
+        self.0.as_bytes()
     }
 }
 
@@ -68,7 +82,7 @@ impl From<&[u8]> for IndexKey {
         let utf8 = String::from_utf8_lossy(key);
         let mut v = utf8.as_bytes().to_vec();
         v.push(SENTINEL);
-        IndexKey(v.into_boxed_slice())
+        IndexKey(InternedString::from(v.as_slice()))
     }
 }
 
@@ -82,7 +96,7 @@ impl From<&str> for IndexKey {
     fn from(key: &str) -> Self {
         let mut key = key.as_bytes().to_vec();
         key.push(SENTINEL);
-        IndexKey(key.into_boxed_slice())
+        IndexKey(InternedString::from(key.as_slice()))
     }
 }
 
@@ -100,7 +114,7 @@ impl Borrow<[u8]> for IndexKey {
 
 impl Hash for IndexKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.hash(state);
+        self.0.as_bytes().hash(state);
     }
 }
 
@@ -143,6 +157,22 @@ mod tests {
         assert_eq!(key.sub_string(6), "value");
     }
 
+    #[test]
+    fn test_shares_storage_with_metric_name() {
+        use crate::labels::MetricName;
+
+        let key = IndexKey::for_label_value("trace_id", "c0ffee-shared-storage");
+        assert_eq!(key.ref_count(), 1);
+
+        let mut name = MetricName::default();
+        name.add_label("trace_id", "c0ffee-shared-storage");
+        assert_eq!(key.ref_count(), 2);
+        assert_eq!(name.get_value("trace_id"), Some("c0ffee-shared-storage"));
+
+        drop(name);
+        assert_eq!(key.ref_count(), 1);
+    }
+
     #[test]
     fn test_len() {
         let key = IndexKey::from("test_key");
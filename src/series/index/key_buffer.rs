@@ -25,7 +25,9 @@ impl KeyBuffer {
             buf[len] = b'=';
             KeyBuffer::Stack(buf, len + 1)
         } else {
-            let v = data.as_bytes().to_vec();
+            let mut v = Vec::with_capacity(len + 1);
+            v.extend_from_slice(data.as_bytes());
+            v.push(b'=');
             KeyBuffer::Heap(v.into_boxed_slice())
         }
     }
@@ -64,6 +64,12 @@ pub fn reset_timeseries_id(id: u64) {
     TIMESERIES_ID.store(id, std::sync::atomic::Ordering::SeqCst);
 }
 
+/// Makes sure ids handed out by [`next_timeseries_id`] are greater than `id`. Called for series
+/// loaded with an id assigned before a restart, so new series never reuse a loaded id.
+pub fn reserve_timeseries_id(id: SeriesRef) {
+    TIMESERIES_ID.fetch_max(id.saturating_add(1), std::sync::atomic::Ordering::SeqCst);
+}
+
 pub fn get_db_index(db: i32) -> TimeSeriesIndexGuard<'static> {
     let guard = TIMESERIES_INDEX.guard();
     TimeSeriesIndexGuard::new(guard, db)
@@ -78,6 +78,11 @@ fn posting_stat_to_value(stat: &PostingStat) -> ValkeyValue {
     ValkeyValue::Map(res)
 }
 
+/// Sorts stats by descending count, breaking ties by ascending name.
+pub fn sort_posting_stats(stats: &mut [PostingStat]) {
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+}
+
 pub(crate) struct StatsMaxHeap {
     max_length: usize,
     min_value: usize,
@@ -10,9 +10,9 @@
 //! Licensed under the Apache License, Version 2.0 (the "License");
 #[cfg(test)]
 mod tests {
-    use crate::labels::filters::{LabelFilter, MatchOp, SeriesSelector};
+    use crate::labels::filters::{LabelFilter, MatchOp, RegexMatcher, SeriesSelector};
     use crate::labels::{Label, MetricName};
-    use crate::series::index::{TimeSeriesIndex, next_timeseries_id};
+    use crate::series::index::{PostingsBitmap, TimeSeriesIndex, next_timeseries_id};
     use crate::series::{SeriesRef, TimeSeries};
     use std::collections::{HashMap, HashSet};
 
@@ -587,6 +587,160 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_postings_for_regex_anchoring() {
+        use MatchOp::*;
+
+        let mut ix: TimeSeriesIndex = TimeSeriesIndex::default();
+        let mut labels_map: HashMap<SeriesRef, Vec<Label>> = HashMap::new();
+
+        let series_data = HashMap::from([
+            (1, labels_from_strings(&["n", "1", "i", "a"])),
+            (2, labels_from_strings(&["n", "1", "i", "ab"])),
+            (3, labels_from_strings(&["n", "1", "i", "ba"])),
+            (4, labels_from_strings(&["n", "1", "i", "b"])),
+            (5, labels_from_strings(&["n", "1"])),
+        ]);
+
+        for (series_ref, labels) in series_data.iter() {
+            add_series(&mut ix, &mut labels_map, *series_ref, labels);
+        }
+
+        // regex matchers are fully anchored, so user-supplied anchors must not change the result
+        let cases: &[(MatchOp, &str, &[SeriesRef])] = &[
+            (RegexEqual, "a", &[1]),
+            (RegexEqual, "^a", &[1]),
+            (RegexEqual, "a$", &[1]),
+            (RegexEqual, "^a$", &[1]),
+            (RegexEqual, "a.*", &[1, 2]),
+            (RegexEqual, ".*a", &[1, 3]),
+            (RegexEqual, ".*a.*", &[1, 2, 3]),
+            (RegexEqual, "^.*a.*$", &[1, 2, 3]),
+            (RegexEqual, "^a|b$", &[1, 4]),
+            (RegexEqual, "", &[5]),
+            (RegexEqual, "^$", &[5]),
+            (RegexEqual, "a|", &[1, 5]),
+            (RegexNotEqual, "a", &[2, 3, 4, 5]),
+            (RegexNotEqual, "^a$", &[2, 3, 4, 5]),
+            (RegexNotEqual, ".*a.*", &[4, 5]),
+            (RegexNotEqual, "^.*a.*$", &[4, 5]),
+            (RegexNotEqual, "", &[1, 2, 3, 4]),
+            (RegexNotEqual, "^$", &[1, 2, 3, 4]),
+        ];
+
+        for (op, pattern, expected) in cases {
+            let filter = LabelFilter::create(*op, "i", *pattern).unwrap();
+            let name = filter.to_string();
+            let selector = SeriesSelector::with_filters(vec![filter]);
+            let postings = ix.postings_for_selector(&selector).unwrap();
+            let mut actual: Vec<SeriesRef> = postings.iter().collect();
+            actual.sort();
+            assert_eq!(&actual, expected, "Evaluating {{{name}}}");
+        }
+    }
+
+    #[test]
+    fn test_postings_for_case_insensitive_regex() {
+        use MatchOp::*;
+
+        let mut ix: TimeSeriesIndex = TimeSeriesIndex::default();
+        let mut labels_map: HashMap<SeriesRef, Vec<Label>> = HashMap::new();
+
+        let series_data = HashMap::from([
+            (1, labels_from_strings(&["status", "OK"])),
+            (2, labels_from_strings(&["status", "Ok"])),
+            (3, labels_from_strings(&["status", "ok"])),
+            (4, labels_from_strings(&["status", "NOK"])),
+            (5, labels_from_strings(&["status", "error"])),
+        ]);
+
+        for (series_ref, labels) in series_data.iter() {
+            add_series(&mut ix, &mut labels_map, *series_ref, labels);
+        }
+
+        let cases: &[(MatchOp, &str, &[SeriesRef])] = &[
+            (RegexEqual, "ok", &[3]),
+            (RegexEqual, "(?i)ok", &[1, 2, 3]),
+            (RegexEqual, "^(?i)ok$", &[1, 2, 3]),
+            (RegexEqual, "(?i).*ok", &[1, 2, 3, 4]),
+            (RegexEqual, "(?i)ok|ERROR", &[1, 2, 3, 5]),
+            (RegexNotEqual, "(?i)ok", &[4, 5]),
+        ];
+
+        for (op, pattern, expected) in cases {
+            let filter = LabelFilter::create(*op, "status", *pattern).unwrap();
+            let name = filter.to_string();
+            let selector = SeriesSelector::with_filters(vec![filter]);
+            let postings = ix.postings_for_selector(&selector).unwrap();
+            let mut actual: Vec<SeriesRef> = postings.iter().collect();
+            actual.sort();
+            assert_eq!(&actual, expected, "Evaluating {{{name}}}");
+        }
+    }
+
+    #[test]
+    fn test_postings_for_numeric_comparison() {
+        use MatchOp::*;
+
+        let mut ix: TimeSeriesIndex = TimeSeriesIndex::default();
+        let mut labels_map: HashMap<SeriesRef, Vec<Label>> = HashMap::new();
+
+        let series_data = HashMap::from([
+            (1, labels_from_strings(&["n", "1", "shard", "-3"])),
+            (2, labels_from_strings(&["n", "1", "shard", "5"])),
+            (3, labels_from_strings(&["n", "1", "shard", "10"])),
+            (4, labels_from_strings(&["n", "1", "shard", "12.5"])),
+            (5, labels_from_strings(&["n", "1", "shard", "1e2"])),
+            (6, labels_from_strings(&["n", "1", "shard", "primary"])),
+            (7, labels_from_strings(&["n", "1", "shard", "NaN"])),
+            (8, labels_from_strings(&["n", "1"])),
+        ]);
+
+        for (series_ref, labels) in series_data.iter() {
+            add_series(&mut ix, &mut labels_map, *series_ref, labels);
+        }
+
+        // non-numeric values and missing labels never match, whatever the operator
+        let cases: &[(MatchOp, &str, &[SeriesRef])] = &[
+            (Gt, "10", &[4, 5]),
+            (Ge, "10", &[3, 4, 5]),
+            (Lt, "10", &[1, 2]),
+            (Le, "10", &[1, 2, 3]),
+            (Gt, "-inf", &[1, 2, 3, 4, 5]),
+            (Lt, "-100", &[]),
+        ];
+
+        for (op, bound, expected) in cases {
+            let filter = LabelFilter::create(*op, "shard", *bound).unwrap();
+            let name = filter.to_string();
+            let selector = SeriesSelector::with_filters(vec![filter]);
+            let postings = ix.postings_for_selector(&selector).unwrap();
+            let mut actual: Vec<SeriesRef> = postings.iter().collect();
+            actual.sort();
+            assert_eq!(&actual, expected, "Evaluating {{{name}}}");
+        }
+
+        // combined with other matchers
+        let filters = vec![
+            LabelFilter::create(Ge, "shard", "0").unwrap(),
+            LabelFilter::create(Lt, "shard", "50").unwrap(),
+            LabelFilter::create(NotEqual, "shard", "10").unwrap(),
+        ];
+        let selector = SeriesSelector::with_filters(filters);
+        let postings = ix.postings_for_selector(&selector).unwrap();
+        let mut actual: Vec<SeriesRef> = postings.iter().collect();
+        actual.sort();
+        assert_eq!(actual, vec![2, 4]);
+    }
+
+    #[test]
+    fn test_label_filter_rejects_pathological_regex() {
+        let nested = format!("{}a{}", "(".repeat(100), ")".repeat(100));
+        assert!(LabelFilter::create(MatchOp::RegexEqual, "i", nested).is_err());
+        assert!(LabelFilter::create(MatchOp::RegexEqual, "i", "a".repeat(5000)).is_err());
+        assert!(LabelFilter::create(MatchOp::RegexNotEqual, "i", r"(\w{500}){100}").is_err());
+    }
+
     fn create_series_from_metric_name(prometheus_name: &str) -> TimeSeries {
         let mut ts = TimeSeries::new();
         ts.id = next_timeseries_id();
@@ -1030,4 +1184,165 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn test_top_label_values_and_value_cardinality() {
+        let mut ix = TimeSeriesIndex::new();
+        let mut labels_map: HashMap<SeriesRef, Vec<Label>> = HashMap::new();
+
+        let series = [
+            ["job", "api", "zone", "a"],
+            ["job", "api", "zone", "b"],
+            ["job", "api", "zone", "c"],
+            ["job", "db", "zone", "a"],
+            ["job", "db", "zone", "b"],
+            ["job", "cache", "zone", "a"],
+            ["job", "batch", "zone", "a"],
+        ];
+        for (i, labels) in series.iter().enumerate() {
+            let labels = labels_from_strings(labels);
+            add_series(&mut ix, &mut labels_map, (i + 1) as SeriesRef, &labels);
+        }
+
+        let mut state = ();
+        ix.with_postings(&mut state, |postings, _| {
+            let all = &postings.all_postings;
+
+            let top: Vec<(String, u64)> = postings
+                .top_label_values("job", all, 3, None)
+                .into_iter()
+                .map(|stat| (stat.name, stat.count))
+                .collect();
+            // "batch" and "cache" tie with a single series; ties resolve by value
+            assert_eq!(
+                top,
+                vec![
+                    ("api".to_string(), 3),
+                    ("db".to_string(), 2),
+                    ("batch".to_string(), 1),
+                ]
+            );
+
+            // restricted to series in zone "a"
+            let zone_a = postings.postings_for_label_value("zone", "a");
+            let top: Vec<String> = postings
+                .top_label_values("job", &zone_a, 10, None)
+                .into_iter()
+                .map(|stat| stat.name)
+                .collect();
+            assert_eq!(top, vec!["api", "batch", "cache", "db"]);
+
+            assert!(postings.top_label_values("job", all, 0, None).is_empty());
+            assert!(
+                postings
+                    .top_label_values("missing", all, 3, None)
+                    .is_empty()
+            );
+
+            // restricted to values matching a regex
+            let re = RegexMatcher::create("(api|c.*)").unwrap();
+            let top: Vec<String> = postings
+                .top_label_values("job", all, 10, Some(&re))
+                .into_iter()
+                .map(|stat| stat.name)
+                .collect();
+            assert_eq!(top, vec!["api", "cache"]);
+            assert_eq!(
+                postings.get_label_values("job", Some(&re)),
+                vec!["api", "cache"]
+            );
+
+            // anchored: a partial value matches nothing
+            let re = RegexMatcher::create("ap").unwrap();
+            assert!(postings.get_label_values("job", Some(&re)).is_empty());
+        });
+    }
+
+    #[test]
+    fn test_get_metric_names() {
+        let mut ix = TimeSeriesIndex::new();
+        let mut labels_map: HashMap<SeriesRef, Vec<Label>> = HashMap::new();
+
+        let series = [
+            ["__name__", "http_requests_total", "job", "api"],
+            ["__name__", "http_requests_total", "job", "db"],
+            ["__name__", "http_errors_total", "job", "api"],
+            ["__name__", "grpc_requests_total", "job", "api"],
+            ["__name__", "https_requests_total", "job", "api"],
+            ["__name__", "up", "job", "http_proxy"],
+        ];
+        for (i, labels) in series.iter().enumerate() {
+            let labels = labels_from_strings(labels);
+            add_series(&mut ix, &mut labels_map, (i + 1) as SeriesRef, &labels);
+        }
+
+        let re = RegexMatcher::create("http_.*").unwrap();
+        assert_eq!(
+            ix.get_metric_names(&re, 100),
+            vec!["http_errors_total", "http_requests_total"]
+        );
+        assert_eq!(ix.get_metric_names(&re, 1), vec!["http_errors_total"]);
+
+        let re = RegexMatcher::create(".*requests.*").unwrap();
+        assert_eq!(
+            ix.get_metric_names(&re, 100),
+            vec![
+                "grpc_requests_total",
+                "http_requests_total",
+                "https_requests_total"
+            ]
+        );
+
+        let re = RegexMatcher::create("https?_requests_total|up").unwrap();
+        assert_eq!(
+            ix.get_metric_names(&re, 100),
+            vec!["http_requests_total", "https_requests_total", "up"]
+        );
+
+        let re = RegexMatcher::create("missing.*").unwrap();
+        assert!(ix.get_metric_names(&re, 100).is_empty());
+    }
+
+    #[test]
+    fn test_label_names_for_postings() {
+        let mut ix = TimeSeriesIndex::new();
+        let mut labels_map: HashMap<SeriesRef, Vec<Label>> = HashMap::new();
+
+        let series: [&[&str]; 4] = [
+            &["job", "api", "zone", "a"],
+            &["job", "api", "zone", "b", "tier", "gold"],
+            &["job", "db", "region", "eu"],
+            &["host", "h1"],
+        ];
+        for (i, labels) in series.iter().enumerate() {
+            let labels = labels_from_strings(*labels);
+            add_series(&mut ix, &mut labels_map, (i + 1) as SeriesRef, &labels);
+        }
+
+        let mut state = ();
+        ix.with_postings(&mut state, |postings, _| {
+            let names = postings.label_names_for_postings(&postings.all_postings);
+            assert_eq!(
+                names.into_iter().collect::<Vec<_>>(),
+                vec!["host", "job", "region", "tier", "zone"]
+            );
+
+            let api = postings.postings_for_label_value("job", "api");
+            let names = postings.label_names_for_postings(&api);
+            assert_eq!(
+                names.into_iter().collect::<Vec<_>>(),
+                vec!["job", "tier", "zone"]
+            );
+
+            let h1 = postings.postings_for_label_value("host", "h1");
+            let names = postings.label_names_for_postings(&h1);
+            assert_eq!(names.into_iter().collect::<Vec<_>>(), vec!["host"]);
+
+            assert!(
+                postings
+                    .label_names_for_postings(&PostingsBitmap::new())
+                    .is_empty()
+            );
+        });
+    }
 }
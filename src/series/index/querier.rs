@@ -12,18 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::postings::{KeyType, Postings};
+use super::posting_stats::PostingStat;
+use super::postings::{KeyType, Postings, PostingsBitmap};
 use super::{get_db_index, get_timeseries_index};
 use crate::common::Timestamp;
 use crate::common::context::get_current_db;
 use crate::common::hash::IntMap;
 use crate::error_consts;
-use crate::labels::filters::SeriesSelector;
+use crate::labels::filters::{RegexMatcher, SeriesSelector};
 use crate::series::acl::{check_key_read_permission, has_all_keys_permissions};
-use crate::series::request_types::MetaDateRangeFilter;
+use crate::series::request_types::{MetaDateRangeFilter, SeriesDiffMode};
 use crate::series::{SeriesGuard, SeriesRef, TimeSeries, get_timeseries};
 use blart::AsBytes;
 use orx_parallel::{IterIntoParIter, ParIter};
+use std::collections::{BTreeMap, BTreeSet};
 use valkey_module::{AclPermissions, Context, ValkeyError, ValkeyResult, ValkeyString};
 
 pub fn series_by_selectors<'a>(
@@ -43,6 +45,36 @@ pub fn series_by_selectors<'a>(
     collect_series_from_postings(ctx, &postings, series_refs.iter(), range)
 }
 
+/// Returns up to `count` matching series with ids greater than `after`, in id order, along with
+/// the id to resume from. The returned id is 0 once no matching ids remain past this page.
+pub fn series_page_by_selectors<'a>(
+    ctx: &'a Context,
+    selectors: &[SeriesSelector],
+    after: SeriesRef,
+    count: usize,
+) -> ValkeyResult<(Vec<(SeriesGuard<'a>, ValkeyString)>, SeriesRef)> {
+    if selectors.is_empty() || count == 0 {
+        return Ok((Vec::new(), 0));
+    }
+
+    let db = get_current_db(ctx);
+    let index = get_db_index(db);
+    let postings = index.get_postings();
+
+    let series_refs = postings.postings_for_selectors(selectors)?;
+    let mut cursor = series_refs.cursor();
+    cursor.reset_at_or_after(after.saturating_add(1));
+
+    // read one id past the page to tell whether another page follows
+    let mut buf = vec![0_u64; count + 1];
+    let n = cursor.read_many(&mut buf);
+    let next = if n > count { buf[count - 1] } else { 0 };
+    let page = &buf[..n.min(count)];
+
+    let series = collect_series_from_postings(ctx, &postings, page.iter().copied(), None)?;
+    Ok((series, next))
+}
+
 pub fn series_keys_by_selectors(
     ctx: &Context,
     selectors: &[SeriesSelector],
@@ -60,6 +92,36 @@ pub fn series_keys_by_selectors(
     collect_series_keys(ctx, &postings, series_refs.iter(), range)
 }
 
+/// Returns the keys of the series in `left` minus `right` ([`SeriesDiffMode::Diff`]), or in
+/// exactly one of the two ([`SeriesDiffMode::Xor`]). Both selectors are resolved against the
+/// same postings snapshot.
+pub fn series_keys_by_selector_diff(
+    ctx: &Context,
+    left: &SeriesSelector,
+    right: &SeriesSelector,
+    mode: SeriesDiffMode,
+) -> ValkeyResult<Vec<ValkeyString>> {
+    let db = get_current_db(ctx);
+    let index = get_db_index(db);
+    let postings = index.get_postings();
+
+    let mut result = postings
+        .postings_for_selectors(std::slice::from_ref(left))?
+        .into_owned();
+    let right_refs = postings.postings_for_selectors(std::slice::from_ref(right))?;
+
+    match mode {
+        SeriesDiffMode::Diff => result.andnot_inplace(&right_refs),
+        SeriesDiffMode::Xor => result.xor_inplace(&right_refs),
+    }
+
+    if result.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    collect_series_keys(ctx, &postings, result.iter(), None)
+}
+
 fn collect_series_keys(
     ctx: &Context,
     postings: &Postings,
@@ -224,3 +286,141 @@ pub fn count_matched_series(
     };
     Ok(count)
 }
+
+/// Series count and per-label distinct value counts for a set of matchers.
+#[derive(Debug, Default)]
+pub struct LabelValueCardinality {
+    pub series_count: usize,
+    pub value_count_by_label_name: Vec<PostingStat>,
+}
+
+/// Returns the number of series matching `matchers` together with, for each label, the number of
+/// distinct values it takes among those series. Useful for spotting label explosions.
+/// Only the labels of the matched series are read, so the counts are exact and the cost does not
+/// depend on the size of the index.
+pub fn label_value_cardinality(
+    ctx: &Context,
+    date_range: Option<MetaDateRangeFilter>,
+    matchers: &[SeriesSelector],
+) -> ValkeyResult<LabelValueCardinality> {
+    if matchers.is_empty() {
+        return Err(ValkeyError::Str(error_consts::MISSING_FILTER));
+    }
+
+    let db = get_current_db(ctx);
+    let index = get_db_index(db);
+    let postings = index.get_postings();
+    let matched = matched_postings(ctx, &postings, date_range, matchers)?;
+
+    let mut values_by_name: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (guard, _) in collect_series_from_postings(ctx, &postings, matched.iter(), None)? {
+        for label in guard.as_ref().labels.iter() {
+            if label.value.is_empty() {
+                continue;
+            }
+            let values = match values_by_name.get_mut(label.name) {
+                Some(values) => values,
+                None => values_by_name.entry(label.name.to_string()).or_default(),
+            };
+            if !values.contains(label.value) {
+                values.insert(label.value.to_string());
+            }
+        }
+    }
+
+    let value_count_by_label_name = values_by_name
+        .into_iter()
+        .map(|(name, values)| PostingStat {
+            name,
+            count: values.len() as u64,
+        })
+        .collect();
+
+    Ok(LabelValueCardinality {
+        series_count: matched.cardinality() as usize,
+        value_count_by_label_name,
+    })
+}
+
+/// Returns the values of `label` held by the most series matching `matchers`, with their series
+/// counts, keeping at most `k` values. If given, only values matching `value_match` are considered.
+pub fn top_label_values(
+    ctx: &Context,
+    label: &str,
+    date_range: Option<MetaDateRangeFilter>,
+    matchers: &[SeriesSelector],
+    k: usize,
+    value_match: Option<&RegexMatcher>,
+) -> ValkeyResult<Vec<PostingStat>> {
+    if matchers.is_empty() {
+        return Err(ValkeyError::Str(error_consts::MISSING_FILTER));
+    }
+
+    let db = get_current_db(ctx);
+    let index = get_db_index(db);
+    let postings = index.get_postings();
+    let matched = matched_postings(ctx, &postings, date_range, matchers)?;
+
+    Ok(postings.top_label_values(label, &matched, k, value_match))
+}
+
+/// Matched sets smaller than `index entries / LABEL_NAMES_INDEX_SCAN_RATIO` are resolved by
+/// reading the labels of each series; larger ones by walking the index, whose cost is bounded by
+/// the index size rather than the number of matched series.
+const LABEL_NAMES_INDEX_SCAN_RATIO: usize = 16;
+
+/// Returns the names of the labels present on the series matching `matchers`.
+pub fn label_names_by_selectors(
+    ctx: &Context,
+    date_range: Option<MetaDateRangeFilter>,
+    matchers: &[SeriesSelector],
+) -> ValkeyResult<BTreeSet<String>> {
+    if matchers.is_empty() {
+        return Err(ValkeyError::Str(error_consts::MISSING_FILTER));
+    }
+
+    let db = get_current_db(ctx);
+    let index = get_db_index(db);
+    let postings = index.get_postings();
+    let matched = matched_postings(ctx, &postings, date_range, matchers)?;
+
+    let matched_count = matched.cardinality() as usize;
+    let index_scan_threshold = postings.label_index.len() / LABEL_NAMES_INDEX_SCAN_RATIO;
+
+    // the index walk cannot check per-key ACLs, so it is only used when every key is readable
+    let current_user = ctx.get_current_user();
+    if matched_count > index_scan_threshold
+        && has_all_keys_permissions(ctx, &current_user, Some(AclPermissions::ACCESS))
+    {
+        return Ok(postings.label_names_for_postings(&matched));
+    }
+
+    let mut names: BTreeSet<String> = BTreeSet::new();
+    for (guard, _) in collect_series_from_postings(ctx, &postings, matched.iter(), None)? {
+        for label in guard.as_ref().labels.iter() {
+            names.insert(label.name.into());
+        }
+    }
+    Ok(names)
+}
+
+/// Resolves `matchers` to a postings bitmap, keeping only series with samples in `date_range`
+/// if one is given.
+fn matched_postings(
+    ctx: &Context,
+    postings: &Postings,
+    date_range: Option<MetaDateRangeFilter>,
+    matchers: &[SeriesSelector],
+) -> ValkeyResult<PostingsBitmap> {
+    let series_refs = postings.postings_for_selectors(matchers)?;
+    let Some(range) = date_range else {
+        return Ok(series_refs.into_owned());
+    };
+
+    let series = collect_series_from_postings(ctx, postings, series_refs.iter(), Some(range))?;
+    let mut bitmap = PostingsBitmap::new();
+    for (guard, _) in series.iter() {
+        bitmap.add(guard.id);
+    }
+    Ok(bitmap)
+}
@@ -4,21 +4,23 @@ use std::hash::BuildHasher;
 use std::sync::{RwLock, RwLockReadGuard};
 
 use super::posting_stats::{PostingStat, PostingsStats, StatsMaxHeap};
-use super::postings::{Postings, PostingsBitmap};
+use super::postings::{IndexedSeriesState, MaintenanceBatch, Postings, PostingsBitmap};
 use crate::common::constants::METRIC_NAME_LABEL;
 use crate::common::context::is_real_user_client;
 use crate::common::hash::DeterministicHasher;
 use crate::error_consts;
-use crate::labels::filters::SeriesSelector;
-use crate::labels::{Label, SeriesLabel};
+use crate::labels::filters::{RegexMatcher, SeriesSelector};
+use crate::labels::{Label, MetricName, SeriesLabel};
 use crate::series::acl::{clone_permissions, has_all_keys_permissions};
 use crate::series::index::IndexKey;
+use crate::series::series_data_type::VK_TIME_SERIES_TYPE;
 use crate::series::{SeriesRef, TimeSeries};
 use blart::AsBytes;
 use croaring::Bitmap64;
 use std::mem::size_of;
 use std::ops::{ControlFlow, Deref, DerefMut};
-use valkey_module::{AclPermissions, Context, ValkeyError, ValkeyResult, ValkeyString};
+use valkey_module::key::ValkeyKey;
+use valkey_module::{AclPermissions, Context, KeysCursor, ValkeyError, ValkeyResult, ValkeyString};
 
 /// A read-only guard for accessing Postings data.
 /// This provides a safe, ergonomic way to read from Postings without allowing modification.
@@ -111,12 +113,61 @@ impl TimeSeriesIndex {
         inner.index_timeseries(ts, key);
     }
 
+    /// Indexes a newly created series, unless the index already holds `max_series` series
+    /// (0 means no limit). The count is checked under the same write lock as the insert, so
+    /// concurrent creates cannot both slip past the limit.
+    pub fn index_new_timeseries(
+        &self,
+        ts: &TimeSeries,
+        key: &[u8],
+        max_series: usize,
+    ) -> ValkeyResult<()> {
+        debug_assert!(ts.id != 0);
+        let mut inner = self.inner.write().unwrap();
+        if max_series > 0 && inner.count() >= max_series {
+            return Err(ValkeyError::Str(error_consts::MAX_SERIES_REACHED));
+        }
+        inner.index_timeseries(ts, key);
+        Ok(())
+    }
+
     pub fn reindex_timeseries(&self, series: &TimeSeries, key: &[u8]) {
         let mut inner = self.inner.write().unwrap();
         inner.remove_timeseries(series);
         inner.index_timeseries(series, key);
     }
 
+    /// Replaces the labels of an indexed series and updates its postings.
+    ///
+    /// The old postings are removed and the new ones added under a single write lock, so concurrent
+    /// queries see either the old or the new label set, never a partially indexed series. If the new
+    /// labels include a metric name that identifies another series, the series is left unchanged
+    /// and `DUPLICATE_SERIES` is returned, naming the conflicting key.
+    pub fn relabel_timeseries(
+        &self,
+        series: &mut TimeSeries,
+        labels: MetricName,
+        key: &[u8],
+    ) -> ValkeyResult<()> {
+        let mut inner = self.inner.write().unwrap();
+        inner.remove_timeseries(series);
+
+        // check only after removing the series' own postings, since its old labels may be a
+        // superset of the new ones
+        if labels.get_value(METRIC_NAME_LABEL).is_some()
+            && let Some(id) = inner.posting_id_by_labels(&labels.to_label_vec())
+            && id != series.id
+        {
+            let err = duplicate_series_error(&inner, id);
+            inner.index_timeseries(series, key);
+            return Err(err);
+        }
+
+        series.labels = labels;
+        inner.index_timeseries(series, key);
+        Ok(())
+    }
+
     pub fn remove_timeseries(&self, series: &TimeSeries) {
         let mut inner = self.inner.write().unwrap();
         inner.remove_timeseries(series);
@@ -127,6 +178,11 @@ impl TimeSeriesIndex {
         inner.has_id(id)
     }
 
+    pub fn series_memory_share(&self, series: &TimeSeries) -> usize {
+        let inner = self.inner.read().unwrap();
+        inner.series_memory_share(series)
+    }
+
     pub fn get_postings(&'_ self) -> PostingsReadGuard<'_> {
         let guard = self.inner.read().unwrap();
         PostingsReadGuard::new(guard)
@@ -153,6 +209,16 @@ impl TimeSeriesIndex {
         }
     }
 
+    /// Returns `DUPLICATE_SERIES` if the labels identify an existing series. The error names the
+    /// key of the conflicting series, so callers can tell which key is holding the metric name.
+    pub fn ensure_unique_labels(&self, labels: &[Label]) -> ValkeyResult<()> {
+        let inner = self.inner.read().unwrap();
+        match inner.posting_id_by_labels(labels) {
+            Some(id) => Err(duplicate_series_error(&inner, id)),
+            None => Ok(()),
+        }
+    }
+
     /// Retrieves the series identifier (ID) corresponding to a specific set of labels.
     ///
     /// This method looks up the series ID in the underlying postings index based on the provided
@@ -205,9 +271,18 @@ impl TimeSeriesIndex {
         inner.get_label_names()
     }
 
-    pub fn get_label_values(&self, label_name: &str) -> Vec<String> {
+    pub fn get_label_values(
+        &self,
+        label_name: &str,
+        value_match: Option<&RegexMatcher>,
+    ) -> Vec<String> {
+        let inner = self.inner.read().unwrap();
+        inner.get_label_values(label_name, value_match)
+    }
+
+    pub fn get_metric_names(&self, name_match: &RegexMatcher, limit: usize) -> Vec<String> {
         let inner = self.inner.read().unwrap();
-        inner.get_label_values(label_name)
+        inner.get_metric_names(name_match, limit)
     }
 
     /// Returns the series keys that match the given selectors.
@@ -504,34 +579,122 @@ impl TimeSeriesIndex {
         inner.mark_id_as_stale(id);
     }
 
-    pub fn remove_stale_ids(&self) -> usize {
-        const BATCH_SIZE: usize = 100;
+    /// Drives stale id removal to completion and then optimizes every postings bitmap.
+    ///
+    /// The work is split into batches of at most `batch_size` index entries. The write lock is
+    /// released between batches, so queries can interleave with a full pass over a large index.
+    pub fn collect_garbage(&self, batch_size: usize) -> IndexGcStats {
+        let mut stats = IndexGcStats {
+            stale_ids_removed: self.inner.read().unwrap().stale_ids.cardinality() as usize,
+            ..Default::default()
+        };
 
-        let mut inner = self.inner.write().expect("TimeSeries lock poisoned");
-        let old_count = inner.stale_ids.cardinality();
-        if old_count == 0 {
-            return 0; // No stale IDs to remove
+        let mut cursor = None;
+        loop {
+            let batch = self
+                .inner
+                .write()
+                .unwrap()
+                .remove_stale_ids(cursor, batch_size);
+            stats.batches += 1;
+            stats.empty_postings_removed += batch.removed;
+            cursor = batch.next_key;
+            if cursor.is_none() {
+                break;
+            }
         }
 
-        let mut cursor = None;
-        while let Some(new_cursor) = inner.remove_stale_ids(cursor, BATCH_SIZE) {
-            // Continue removing stale IDs in batches
-            cursor = Some(new_cursor);
+        loop {
+            let batch = self.optimize_incremental(cursor, batch_size);
+            stats.batches += 1;
+            stats.bitmaps_optimized += batch.processed;
+            stats.empty_postings_removed += batch.removed;
+            cursor = batch.next_key;
+            if cursor.is_none() {
+                break;
+            }
         }
 
-        (old_count - inner.stale_ids.cardinality()) as usize // Return number of removed IDs
+        stats
     }
 
-    pub fn optimize_incremental(
+    /// Walks the keyspace of the db the index belongs to and compares every series with the
+    /// index, without changing either. With `repair`, ids referenced by the index but carried by
+    /// no series are then marked stale, so queries skip them and the next gc pass purges them.
+    pub fn verify(&self, ctx: &Context, repair: bool) -> IndexVerifyStats {
+        let mut stats = IndexVerifyStats::default();
+        let mut live_ids = PostingsBitmap::new();
+
+        let orphaned_ids = {
+            let inner = self.inner.read().unwrap();
+            let check_key = |ctx: &Context, key_name: ValkeyString, _key: Option<&ValkeyKey>| {
+                let key = ctx.open_key(&key_name);
+                let Ok(Some(series)) = key.get_value::<TimeSeries>(&VK_TIME_SERIES_TYPE) else {
+                    return;
+                };
+                stats.series_checked += 1;
+                live_ids.add(series.id);
+                match inner.check_indexed_series(series, key_name.as_slice()) {
+                    IndexedSeriesState::Consistent => {}
+                    IndexedSeriesState::MissingPostings => stats.missing_postings += 1,
+                    IndexedSeriesState::KeyMismatch => stats.key_mismatches += 1,
+                }
+            };
+            let cursor = KeysCursor::new();
+            while cursor.scan(ctx, &check_key) {}
+            inner.orphaned_ids(&live_ids)
+        };
+        stats.orphaned_ids = orphaned_ids.cardinality() as usize;
+
+        if repair && !orphaned_ids.is_empty() {
+            let mut inner = self.inner.write().unwrap();
+            for id in orphaned_ids.iter() {
+                inner.mark_id_as_stale(id);
+            }
+            stats.repaired = stats.orphaned_ids;
+        }
+
+        stats
+    }
+
+    pub(crate) fn optimize_incremental(
         &self,
         start_prefix: Option<IndexKey>,
         count: usize,
-    ) -> Option<IndexKey> {
+    ) -> MaintenanceBatch {
         let mut inner = self.inner.write().unwrap();
         inner.optimize_postings(start_prefix, count)
     }
 }
 
+/// Discrepancies found by [`TimeSeriesIndex::verify`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IndexVerifyStats {
+    /// Number of series found in the keyspace.
+    pub series_checked: usize,
+    /// Number of ids referenced by the index that no series in the keyspace carries.
+    pub orphaned_ids: usize,
+    /// Number of series that are not indexed, or are missing from some of their postings.
+    pub missing_postings: usize,
+    /// Number of series whose id is indexed under a different key.
+    pub key_mismatches: usize,
+    /// Number of orphaned ids marked stale (with `REPAIR`).
+    pub repaired: usize,
+}
+
+/// Summary of a full garbage collection pass over the index.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IndexGcStats {
+    /// Number of stale series ids purged from the postings.
+    pub stale_ids_removed: usize,
+    /// Number of postings entries dropped because they no longer reference any series.
+    pub empty_postings_removed: usize,
+    /// Number of postings bitmaps optimized.
+    pub bitmaps_optimized: usize,
+    /// Number of batches (write lock acquisitions) used to complete the pass.
+    pub batches: usize,
+}
+
 /// Helper struct for batch iteration over the label index
 struct BatchIterator<'a> {
     index: &'a TimeSeriesIndex,
@@ -629,3 +792,14 @@ impl<'a> BatchIterator<'a> {
 fn get_bitmap_size(bmp: &PostingsBitmap) -> usize {
     bmp.cardinality() as usize * size_of::<SeriesRef>()
 }
+
+fn duplicate_series_error(postings: &Postings, id: SeriesRef) -> ValkeyError {
+    match postings.get_key_by_id(id) {
+        Some(key) => ValkeyError::String(format!(
+            "{}: existing key '{}'",
+            error_consts::DUPLICATE_SERIES,
+            String::from_utf8_lossy(key)
+        )),
+        None => ValkeyError::Str(error_consts::DUPLICATE_SERIES),
+    }
+}
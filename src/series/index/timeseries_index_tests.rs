@@ -1,8 +1,13 @@
 #[cfg(test)]
 mod tests {
+    use crate::common::constants::METRIC_NAME_LABEL;
+    use crate::error_consts;
     use crate::labels::Label;
     use crate::labels::filters::SeriesSelector;
-    use crate::series::index::{TimeSeriesIndex, next_timeseries_id};
+    use crate::series::index::postings::IndexedSeriesState;
+    use crate::series::index::{
+        PostingsBitmap, TimeSeriesIndex, next_timeseries_id, reserve_timeseries_id,
+    };
     use crate::series::time_series::TimeSeries;
 
     fn create_series_from_metric_name(prometheus_name: &str) -> TimeSeries {
@@ -39,6 +44,101 @@ mod tests {
         assert_eq!(index.label_count(), 5); // metric_name + region + env
     }
 
+    #[test]
+    fn test_relabel_time_series() {
+        let index = TimeSeriesIndex::new();
+        let mut ts = create_series_from_metric_name(r#"latency{region="us-east-1",env="qa"}"#);
+        index.index_timeseries(&ts, b"time-series-1");
+
+        let labels = r#"latency{region="eu-west-1"}"#.parse().unwrap();
+        index
+            .relabel_timeseries(&mut ts, labels, b"time-series-1")
+            .unwrap();
+
+        assert_eq!(ts.labels.get_value("region"), Some("eu-west-1"));
+        assert_eq!(index.count(), 1);
+        assert_eq!(index.label_count(), 2); // metric_name + region
+        let old = vec![Label::new("region", "us-east-1")];
+        assert!(index.postings_by_labels(&old).is_empty());
+        let new = vec![Label::new("region", "eu-west-1")];
+        assert!(index.postings_by_labels(&new).contains(ts.id));
+    }
+
+    #[test]
+    fn test_relabel_time_series_rejects_duplicate() {
+        let index = TimeSeriesIndex::new();
+        let existing = create_series_from_metric_name(r#"latency{region="us-east-1"}"#);
+        let mut ts = create_series_from_metric_name(r#"latency{region="us-east-1",env="qa"}"#);
+        index.index_timeseries(&existing, b"time-series-1");
+        index.index_timeseries(&ts, b"time-series-2");
+
+        let labels = r#"latency{region="us-east-1"}"#.parse().unwrap();
+        let err = index
+            .relabel_timeseries(&mut ts, labels, b"time-series-2")
+            .unwrap_err();
+        assert!(err.to_string().contains("'time-series-1'"));
+
+        // the series keeps its labels and postings
+        assert_eq!(ts.labels.get_value("env"), Some("qa"));
+        assert_eq!(index.count(), 2);
+        let env = vec![Label::new("env", "qa")];
+        assert!(index.postings_by_labels(&env).contains(ts.id));
+    }
+
+    #[test]
+    fn test_ensure_unique_labels_names_existing_key() {
+        let index = TimeSeriesIndex::new();
+        let existing = create_series_from_metric_name(r#"latency{region="us-east-1"}"#);
+        index.index_timeseries(&existing, b"time-series-1");
+
+        let labels = existing.labels.to_label_vec();
+        let err = index.ensure_unique_labels(&labels).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.starts_with(error_consts::DUPLICATE_SERIES));
+        assert!(msg.contains("'time-series-1'"));
+
+        let other = vec![
+            Label::new(METRIC_NAME_LABEL, "latency"),
+            Label::new("region", "eu-west-1"),
+        ];
+        assert!(index.ensure_unique_labels(&other).is_ok());
+    }
+
+    #[test]
+    fn test_index_new_timeseries_enforces_max_series() {
+        let index = TimeSeriesIndex::new();
+        for i in 0..3 {
+            let ts = create_series_from_metric_name(&format!("cpu{{host=\"h{i}\"}}"));
+            let key = format!("ts-{i}");
+            index
+                .index_new_timeseries(&ts, key.as_bytes(), 3)
+                .expect("below the limit");
+        }
+
+        let ts = create_series_from_metric_name(r#"cpu{host="h3"}"#);
+        let err = index.index_new_timeseries(&ts, b"ts-3", 3).unwrap_err();
+        assert_eq!(err.to_string(), error_consts::MAX_SERIES_REACHED);
+        assert_eq!(index.count(), 3);
+        assert!(!index.has_id(ts.id));
+
+        // 0 means no limit
+        index.index_new_timeseries(&ts, b"ts-3", 0).unwrap();
+        assert_eq!(index.count(), 4);
+    }
+
+    #[test]
+    fn test_reserve_timeseries_id_skips_loaded_ids() {
+        // other tests allocate ids concurrently, so only check that the next id moves past the
+        // loaded one
+        let loaded_id = next_timeseries_id() + 1_000_000;
+        reserve_timeseries_id(loaded_id);
+        assert!(next_timeseries_id() > loaded_id);
+
+        // a lower id never moves the counter back
+        reserve_timeseries_id(1);
+        assert!(next_timeseries_id() > loaded_id);
+    }
+
     #[test]
     fn test_remove_time_series() {
         let index = TimeSeriesIndex::new();
@@ -436,4 +536,102 @@ mod tests {
             .expect("db_queries metric should be present");
         assert_eq!(db_metric.count, 1);
     }
+
+    #[test]
+    fn test_collect_garbage_removes_stale_ids_across_batches() {
+        let index = TimeSeriesIndex::new();
+        let mut stale = Vec::new();
+        let mut live = Vec::new();
+        for i in 0..20 {
+            let ts =
+                create_series_from_metric_name(&format!(r#"requests{{pod="pod-{i}",env="prod"}}"#));
+            index.index_timeseries(&ts, format!("ts-{i}").as_bytes());
+            if i % 2 == 0 {
+                stale.push(ts);
+            } else {
+                live.push(ts);
+            }
+        }
+        for ts in stale.iter() {
+            index.mark_id_as_stale(ts.id);
+        }
+
+        // a small batch size forces the pass to resume from a cursor several times
+        let stats = index.collect_garbage(3);
+
+        assert_eq!(stats.stale_ids_removed, 10);
+        assert_eq!(stats.empty_postings_removed, 10); // one "pod" entry per stale series
+        assert_eq!(stats.bitmaps_optimized, index.label_count());
+        assert!(stats.batches > 2);
+
+        assert_eq!(index.count(), 10);
+        assert_eq!(index.label_count(), 12); // metric_name + env + 10 pods
+        for ts in stale.iter() {
+            let pod = vec![Label::new("pod", ts.labels.get_value("pod").unwrap())];
+            assert!(index.postings_by_labels(&pod).is_empty());
+        }
+        let env = vec![Label::new("env", "prod")];
+        let env_postings = index.postings_by_labels(&env);
+        assert_eq!(env_postings.cardinality(), 10);
+        for ts in live.iter() {
+            assert!(env_postings.contains(ts.id));
+        }
+
+        // nothing left to reclaim
+        let stats = index.collect_garbage(3);
+        assert_eq!(stats.stale_ids_removed, 0);
+        assert_eq!(stats.empty_postings_removed, 0);
+    }
+
+    #[test]
+    fn test_check_indexed_series() {
+        let index = TimeSeriesIndex::new();
+        let indexed = create_series_from_metric_name(r#"requests{pod="a",env="prod"}"#);
+        let unindexed = create_series_from_metric_name(r#"requests{pod="b",env="prod"}"#);
+        index.index_timeseries(&indexed, b"ts-a");
+
+        let check = |series: &TimeSeries, key: &[u8]| {
+            index.with_postings(&mut (), |postings, _| {
+                postings.check_indexed_series(series, key)
+            })
+        };
+
+        assert_eq!(check(&indexed, b"ts-a"), IndexedSeriesState::Consistent);
+        assert_eq!(
+            check(&indexed, b"ts-other"),
+            IndexedSeriesState::KeyMismatch
+        );
+        assert_eq!(
+            check(&unindexed, b"ts-b"),
+            IndexedSeriesState::MissingPostings
+        );
+
+        // a label posting that lost the id
+        index.with_postings_mut(&mut (), |postings, _| {
+            postings.remove_posting_for_label_value("pod", "a", indexed.id)
+        });
+        assert_eq!(
+            check(&indexed, b"ts-a"),
+            IndexedSeriesState::MissingPostings
+        );
+    }
+
+    #[test]
+    fn test_orphaned_ids() {
+        let index = TimeSeriesIndex::new();
+        let live = create_series_from_metric_name(r#"requests{pod="a"}"#);
+        let orphan = create_series_from_metric_name(r#"requests{pod="b"}"#);
+        let stale = create_series_from_metric_name(r#"requests{pod="c"}"#);
+        index.index_timeseries(&live, b"ts-a");
+        index.index_timeseries(&orphan, b"ts-b");
+        index.index_timeseries(&stale, b"ts-c");
+        index.mark_id_as_stale(stale.id);
+
+        let mut live_ids = PostingsBitmap::new();
+        live_ids.add(live.id);
+
+        let orphans = index.with_postings(&mut (), |postings, _| postings.orphaned_ids(&live_ids));
+        // stale ids are already on their way out of the index, so they are not reported
+        assert_eq!(orphans.iter().collect::<Vec<_>>(), vec![orphan.id]);
+    }
 }
@@ -0,0 +1,113 @@
+use crate::common::Sample;
+
+/// Downsamples `samples` to at most `max_points` points using the Largest-Triangle-Three-Buckets
+/// algorithm, which preserves the visual shape of the series better than bucketed aggregation.
+///
+/// The first and last samples are always retained. If the input already has `max_points` samples
+/// or fewer it is returned unchanged, and if `max_points` is less than 3 only the endpoints are kept.
+pub fn lttb_downsample(samples: Vec<Sample>, max_points: usize) -> Vec<Sample> {
+    let len = samples.len();
+    if len <= max_points || len <= 2 {
+        return samples;
+    }
+
+    if max_points < 3 {
+        return vec![samples[0], samples[len - 1]];
+    }
+
+    let mut result = Vec::with_capacity(max_points);
+    // Bucket size. Leave room for the start and end points
+    let every = (len - 2) as f64 / (max_points - 2) as f64;
+    let mut a = 0;
+
+    result.push(samples[0]);
+
+    for i in 0..max_points - 2 {
+        // Average point of the next bucket, used as the third vertex of the triangle
+        let avg_start = ((i + 1) as f64 * every) as usize + 1;
+        let avg_end = (((i + 2) as f64 * every) as usize + 1).min(len);
+        let avg_len = (avg_end - avg_start) as f64;
+
+        let (sum_x, sum_y) = samples[avg_start..avg_end]
+            .iter()
+            .fold((0.0, 0.0), |(x, y), s| {
+                (x + s.timestamp as f64, y + s.value)
+            });
+        let avg_x = sum_x / avg_len;
+        let avg_y = sum_y / avg_len;
+
+        // Pick the point in the current bucket forming the largest triangle
+        let range_start = (i as f64 * every) as usize + 1;
+        let range_end = ((i + 1) as f64 * every) as usize + 1;
+
+        let point_a = samples[a];
+        let a_x = point_a.timestamp as f64;
+        let a_y = point_a.value;
+
+        let mut max_area = -1.0;
+        let mut next_a = range_start;
+        for (idx, sample) in samples[range_start..range_end].iter().enumerate() {
+            let area = ((a_x - avg_x) * (sample.value - a_y)
+                - (a_x - sample.timestamp as f64) * (avg_y - a_y))
+                .abs();
+            if area > max_area {
+                max_area = area;
+                next_a = range_start + idx;
+            }
+        }
+
+        result.push(samples[next_a]);
+        a = next_a;
+    }
+
+    result.push(samples[len - 1]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lttb_downsample;
+    use crate::common::Sample;
+
+    fn make_samples(count: usize) -> Vec<Sample> {
+        (0..count)
+            .map(|i| Sample::new(i as i64 * 10, ((i as f64) / 3.0).sin()))
+            .collect()
+    }
+
+    #[test]
+    fn returns_input_when_smaller_than_max_points() {
+        let samples = make_samples(5);
+        assert_eq!(lttb_downsample(samples.clone(), 10), samples);
+        assert_eq!(lttb_downsample(samples.clone(), 5), samples);
+    }
+
+    #[test]
+    fn keeps_only_endpoints_below_three_points() {
+        let samples = make_samples(20);
+        for max_points in [1, 2] {
+            let result = lttb_downsample(samples.clone(), max_points);
+            assert_eq!(result, vec![samples[0], samples[19]]);
+        }
+    }
+
+    #[test]
+    fn always_retains_endpoints() {
+        let samples = make_samples(1000);
+        for max_points in [3, 7, 50, 999] {
+            let result = lttb_downsample(samples.clone(), max_points);
+            assert_eq!(result.len(), max_points);
+            assert_eq!(result.first(), samples.first());
+            assert_eq!(result.last(), samples.last());
+            assert!(result.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        }
+    }
+
+    #[test]
+    fn preserves_spikes() {
+        let mut samples: Vec<Sample> = (0..100).map(|i| Sample::new(i, 0.0)).collect();
+        samples[42].value = 100.0;
+        let result = lttb_downsample(samples, 10);
+        assert!(result.iter().any(|s| s.timestamp == 42 && s.value == 100.0));
+    }
+}
@@ -4,10 +4,12 @@ mod bulk_add;
 pub mod chunks;
 mod compaction;
 mod compaction_policy;
+mod dedup;
 mod defrag;
 mod digest;
 mod guard;
 pub mod index;
+mod lttb;
 pub mod mrange;
 mod multi_del;
 pub mod request_types;
@@ -25,8 +27,10 @@ mod utils;
 pub use bulk_add::*;
 pub use compaction::*;
 pub use compaction_policy::*;
+pub use dedup::dedup_runs;
 pub use defrag::defrag_series;
 pub use guard::*;
+pub use lttb::lttb_downsample;
 pub use multi_del::*;
 pub use sample_merge::*;
 pub use series_sample_iterator::SeriesSampleIterator;
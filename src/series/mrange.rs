@@ -1,19 +1,23 @@
 use crate::common::Sample;
-use crate::common::constants::{REDUCER_KEY, SOURCE_KEY};
+use crate::common::constants::{REDUCER_KEY, SOURCE_KEY, SOURCES_KEY};
 use crate::error_consts;
 use crate::iterators::create_sample_iterator_adapter;
 use crate::iterators::{MultiSeriesSampleIter, SampleReducer, create_range_iterator};
 use crate::labels::Label;
 use crate::series::acl::check_metadata_permissions;
 use crate::series::chunks::{Chunk, GorillaChunk, TimeSeriesChunk, UncompressedChunk};
-use crate::series::index::series_by_selectors;
+use crate::series::index::{series_by_selectors, series_page_by_selectors};
 use crate::series::request_types::{
-    MRangeOptions, MRangeSeriesResult, RangeGroupingOptions, RangeOptions,
+    MRangeCursor, MRangeOptions, MRangeSeriesResult, RangeGroupingOptions, RangeOptions,
+    apply_label_aliases,
 };
-use crate::series::{TimeSeries, get_latest_compaction_sample};
+use crate::series::{SeriesGuard, SeriesRef, TimeSeries, get_latest_compaction_sample};
 use ahash::AHashMap;
 use orx_parallel::{IntoParIter, IterIntoParIter, ParIter};
-use valkey_module::{Context, ValkeyError, ValkeyResult};
+use valkey_module::{Context, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
+
+/// Number of series per page when CURSOR is given without COUNT.
+pub const DEFAULT_MRANGE_CURSOR_COUNT: usize = 100;
 
 struct MRangeSeriesMeta<'a> {
     series: &'a TimeSeries,
@@ -34,22 +38,69 @@ pub fn process_mrange_query(
     }
 
     let series_guards = series_by_selectors(ctx, &options.filters, None)?;
+    let series_metas = collect_series_metas(ctx, &options.range, &series_guards);
+
+    Ok(process_mrange(series_metas, options, clustered))
+}
+
+/// Runs one page of a CURSOR query, returning the page and the cursor to resume from, which is
+/// 0 once the matched series are exhausted.
+pub fn process_mrange_page_query(
+    ctx: &Context,
+    options: MRangeOptions,
+    cursor: MRangeCursor,
+) -> ValkeyResult<(Vec<MRangeSeriesResult>, SeriesRef)> {
+    check_metadata_permissions(ctx)?;
 
-    let series_metas: Vec<MRangeSeriesMeta> = series_guards
+    if options.filters.is_empty() {
+        return Err(ValkeyError::Str(error_consts::MISSING_FILTER));
+    }
+
+    let (series_guards, next) =
+        series_page_by_selectors(ctx, &options.filters, cursor.after, cursor.count)?;
+    let series_metas = collect_series_metas(ctx, &options.range, &series_guards);
+
+    Ok((process_mrange(series_metas, options, false), next))
+}
+
+fn collect_series_metas<'a>(
+    ctx: &Context,
+    options: &RangeOptions,
+    series_guards: &'a [(SeriesGuard<'_>, ValkeyString)],
+) -> Vec<MRangeSeriesMeta<'a>> {
+    series_guards
         .iter()
         .map(|(guard, key)| MRangeSeriesMeta {
             series: guard,
             source_key: key.to_string(),
             group_label_value: None,
             latest: {
-                // This is done upfront to enable parallel series processing below
+                // This is done upfront to enable parallel series processing
                 // (Context cannot be shared across threads)
-                get_latest(&options.range, ctx, guard)
+                get_latest(options, ctx, guard)
             },
         })
-        .collect();
+        .collect()
+}
 
-    Ok(process_mrange(series_metas, options, clustered))
+/// Cursors are the decimal id of the last series returned, with 0 marking both the first page
+/// and the end of the iteration.
+pub(crate) fn encode_mrange_cursor(id: SeriesRef) -> String {
+    id.to_string()
+}
+
+pub(crate) fn decode_mrange_cursor(cursor: &str) -> ValkeyResult<SeriesRef> {
+    cursor
+        .parse::<SeriesRef>()
+        .map_err(|_| ValkeyError::Str(error_consts::INVALID_CURSOR))
+}
+
+/// Builds the reply for a CURSOR query: `[cursor, [series...]]`.
+pub(crate) fn mrange_page_reply(results: Vec<MRangeSeriesResult>, next: SeriesRef) -> ValkeyValue {
+    ValkeyValue::Array(vec![
+        ValkeyValue::BulkString(encode_mrange_cursor(next)),
+        results.into(),
+    ])
 }
 
 fn process_mrange(
@@ -81,6 +132,8 @@ fn process_mrange(
 
     let mut items = if is_grouped {
         handle_grouping(metas, options)
+    } else if let Some(limit) = options.sample_limit {
+        handle_sample_limit(metas, options, limit)
     } else {
         handle_non_grouped(metas, options, false)
     };
@@ -162,18 +215,95 @@ fn handle_non_grouped(
                 TimeSeriesChunk::Uncompressed(chunk)
             };
 
-            let labels = convert_labels(meta.series, options.with_labels, &options.selected_labels);
-
-            MRangeSeriesResult {
-                group_label_value: meta.group_label_value,
-                key: meta.source_key,
-                labels,
-                data,
-            }
+            series_result(meta, &options, data)
         })
         .collect()
 }
 
+fn series_result(
+    meta: MRangeSeriesMeta,
+    options: &MRangeOptions,
+    data: TimeSeriesChunk,
+) -> MRangeSeriesResult {
+    let mut labels = convert_labels(meta.series, options.with_labels, &options.selected_labels);
+    apply_label_aliases(&mut labels, &options.label_aliases);
+
+    MRangeSeriesResult {
+        group_label_value: meta.group_label_value,
+        key: meta.source_key,
+        labels,
+        data,
+    }
+}
+
+/// Collects series in reply (key) order with a running total of samples, stopping once more than
+/// `limit` samples are collected. The one extra sample lets [`limit_total_samples`] tell that the
+/// result was truncated, without reading the remaining series.
+fn handle_sample_limit(
+    mut metas: Vec<MRangeSeriesMeta>,
+    options: MRangeOptions,
+    limit: usize,
+) -> Vec<MRangeSeriesResult> {
+    metas.sort_by(|a, b| a.source_key.cmp(&b.source_key));
+
+    let mut remaining = limit.saturating_add(1);
+    let mut results = Vec::new();
+    for meta in metas {
+        if remaining == 0 {
+            break;
+        }
+        let samples: Vec<Sample> = create_iter(meta.series, &options, meta.latest)
+            .take(remaining)
+            .collect();
+        remaining -= samples.len();
+        let data = TimeSeriesChunk::Uncompressed(UncompressedChunk::from_vec(samples));
+        results.push(series_result(meta, &options, data));
+    }
+    results
+}
+
+/// Caps the total number of samples across `results`, which must be in reply order. The series
+/// reaching the limit is cut short and the series after it are dropped. Returns whether any sample
+/// was left out.
+pub(crate) fn limit_total_samples(results: &mut Vec<MRangeSeriesResult>, limit: usize) -> bool {
+    let mut remaining = limit;
+    let mut kept = 0;
+    let mut truncated = false;
+    for series in results.iter_mut() {
+        if remaining == 0 {
+            break;
+        }
+        kept += 1;
+        let len = series.data.len();
+        if len > remaining {
+            let samples = series.data.iter().take(remaining).collect();
+            series.data = TimeSeriesChunk::Uncompressed(UncompressedChunk::from_vec(samples));
+            truncated = true;
+            remaining = 0;
+        } else {
+            remaining -= len;
+        }
+    }
+    if kept < results.len() {
+        truncated |= results[kept..].iter().any(|series| !series.data.is_empty());
+        results.truncate(kept);
+    }
+    truncated
+}
+
+/// Builds the MRANGE reply. With LIMIT, the series are wrapped as `[truncated, [series...]]`,
+/// where `truncated` is 1 if samples or series were left out to honour the limit.
+pub(crate) fn mrange_reply(
+    mut results: Vec<MRangeSeriesResult>,
+    sample_limit: Option<usize>,
+) -> ValkeyValue {
+    let Some(limit) = sample_limit else {
+        return results.into();
+    };
+    let truncated = limit_total_samples(&mut results, limit);
+    ValkeyValue::Array(vec![ValkeyValue::Integer(truncated as i64), results.into()])
+}
+
 fn handle_grouping(
     metas: Vec<MRangeSeriesMeta>,
     options: MRangeOptions,
@@ -202,7 +332,7 @@ fn handle_grouping(
                 .expect("Grouping options should be present");
             let data = get_grouped_samples(&group_data.series, &options, grouping, count);
             let labels = group_data.labels;
-            let key = format!("{}={}", grouping.group_label, label_value);
+            let key = format_group_key(&grouping.group_labels, &label_value);
             let chunk = TimeSeriesChunk::Uncompressed(UncompressedChunk::from_vec(data));
             MRangeSeriesResult {
                 key,
@@ -225,12 +355,23 @@ fn get_grouped_samples(
     // todo: choose approach based on data size and available memory?
     let is_reverse = options.is_reverse;
 
+    // MultiSeriesSampleIter merges in ascending timestamp order, so the per-series iterators
+    // are always built forward. The reduced output is reversed at the end if needed.
+    //
     // todo(perf): with sufficient memory, we could parallel load all samples into memory first,
     // and construct the MultiSeriesSampleIter from those. In low memory, we could use the code
     // below which iterates sequentially
     let iterators = series_metas
         .iter()
-        .map(|meta| create_iter(meta.series, options, meta.latest))
+        .map(|meta| {
+            create_range_iterator(
+                meta.series,
+                &options.range,
+                &options.grouping,
+                meta.latest,
+                false,
+            )
+        })
         .collect::<Vec<_>>();
 
     let multi_iter = MultiSeriesSampleIter::new(iterators);
@@ -245,14 +386,18 @@ pub(crate) fn collect_samples<I: Iterator<Item = Sample>>(
     is_reverse: bool,
     count: Option<usize>,
 ) -> Vec<Sample> {
-    let mut samples = if let Some(count) = count {
-        iter.take(count).collect::<Vec<_>>()
-    } else {
-        iter.collect::<Vec<_>>()
-    };
+    if !is_reverse {
+        return match count {
+            Some(count) => iter.take(count).collect(),
+            None => iter.collect(),
+        };
+    }
 
-    if is_reverse {
-        samples.reverse();
+    // in reverse, COUNT keeps the `count` most recent samples
+    let mut samples = iter.collect::<Vec<_>>();
+    samples.reverse();
+    if let Some(count) = count {
+        samples.truncate(count);
     }
     samples
 }
@@ -284,35 +429,89 @@ fn convert_labels(
         .collect()
 }
 
+/// Maximum number of source keys listed in the `__sources__` label of a group.
+const MAX_GROUP_SOURCES: usize = 100;
+
+/// Separator used to join the values of the GROUPBY labels into a single group key.
+const GROUP_KEY_SEPARATOR: char = '\u{1f}';
+
+/// Returns the composite group key for a series, or `None` if the series lacks any of the
+/// grouping labels.
+fn get_group_key(series: &TimeSeries, group_labels: &[String]) -> Option<String> {
+    let mut key = String::new();
+    for (i, name) in group_labels.iter().enumerate() {
+        let value = series.label_value(name)?;
+        if i > 0 {
+            key.push(GROUP_KEY_SEPARATOR);
+        }
+        key.push_str(value);
+    }
+    Some(key)
+}
+
+fn split_group_key<'a>(
+    group_labels: &'a [String],
+    group_key: &'a str,
+) -> impl Iterator<Item = (&'a String, &'a str)> {
+    group_labels
+        .iter()
+        .zip(group_key.split(GROUP_KEY_SEPARATOR))
+}
+
+/// Formats a group key for display, e.g. `region=us,service=api`.
+pub(crate) fn format_group_key(group_labels: &[String], group_key: &str) -> String {
+    split_group_key(group_labels, group_key)
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Joins the keys of the series contributing to a group, listing at most `MAX_GROUP_SOURCES`
+/// of them. A truncated list ends with a marker holding the number of omitted keys.
+fn format_group_sources(source_identifiers: &[String]) -> String {
+    if source_identifiers.len() <= MAX_GROUP_SOURCES {
+        return source_identifiers.join(",");
+    }
+    let omitted = source_identifiers.len() - MAX_GROUP_SOURCES;
+    let mut sources = source_identifiers[..MAX_GROUP_SOURCES].join(",");
+    sources.push_str(&format!(",...(+{omitted} more)"));
+    sources
+}
+
 pub(crate) fn build_mrange_grouped_labels(
-    group_label_name: &str,
-    group_label_value: &str,
+    group_labels: &[String],
+    group_key: &str,
     reducer_name_str: &str,
     source_identifiers: &[String],
+    with_sources: bool,
 ) -> Vec<Label> {
     let sources = source_identifiers.join(",");
-    vec![
-        Label {
-            name: group_label_name.into(),
-            value: group_label_value.to_string(),
-        },
-        Label {
-            name: REDUCER_KEY.into(),
-            value: reducer_name_str.into(),
-        },
-        Label {
-            name: SOURCE_KEY.into(),
-            value: sources,
-        },
-    ]
+    let mut labels: Vec<Label> = split_group_key(group_labels, group_key)
+        .map(|(name, value)| Label {
+            name: name.clone(),
+            value: value.to_string(),
+        })
+        .collect();
+    labels.push(Label {
+        name: REDUCER_KEY.into(),
+        value: reducer_name_str.into(),
+    });
+    labels.push(Label {
+        name: SOURCE_KEY.into(),
+        value: sources,
+    });
+    if with_sources {
+        labels.push(Label {
+            name: SOURCES_KEY.into(),
+            value: format_group_sources(source_identifiers),
+        });
+    }
+    labels
 }
 
 fn collect_group_label_values(metas: &mut Vec<MRangeSeriesMeta>, grouping: &RangeGroupingOptions) {
     for meta in metas.iter_mut() {
-        meta.group_label_value = meta
-            .series
-            .label_value(&grouping.group_label)
-            .map(|s| s.to_string());
+        meta.group_label_value = get_group_key(meta.series, &grouping.group_labels);
     }
 }
 
@@ -327,7 +526,7 @@ fn group_series_by_label<'a>(
     with_labels: bool,
 ) -> AHashMap<String, GroupedSeriesData<'a>> {
     let mut grouped: AHashMap<String, GroupedSeriesData<'a>> = AHashMap::new();
-    let group_by_label_name = &grouping.group_label;
+    let group_labels = &grouping.group_labels;
     let reducer_name = grouping.aggregation.aggregation_name();
 
     for mut meta in metas.into_iter() {
@@ -353,10 +552,11 @@ fn group_series_by_label<'a>(
             source_keys.sort();
 
             group_data.labels = build_mrange_grouped_labels(
-                group_by_label_name,
+                group_labels,
                 label_value_str,
                 reducer_name,
                 &source_keys,
+                grouping.with_sources,
             );
         }
     }
@@ -375,3 +575,161 @@ pub fn create_mrange_iterator_adapter<'a>(
         options.is_reverse,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        GROUP_KEY_SEPARATOR, MAX_GROUP_SOURCES, build_mrange_grouped_labels, collect_samples,
+        decode_mrange_cursor, encode_mrange_cursor, format_group_key, format_group_sources,
+        limit_total_samples,
+    };
+    use crate::common::Sample;
+    use crate::series::chunks::{Chunk, TimeSeriesChunk, UncompressedChunk};
+    use crate::series::request_types::MRangeSeriesResult;
+
+    fn samples() -> Vec<Sample> {
+        (1..=5).map(|i| Sample::new(i * 10, i as f64)).collect()
+    }
+
+    #[test]
+    fn collect_samples_forward_keeps_oldest() {
+        let result = collect_samples(samples().into_iter(), false, Some(2));
+        let timestamps: Vec<_> = result.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![10, 20]);
+    }
+
+    #[test]
+    fn collect_samples_reverse_keeps_newest() {
+        let result = collect_samples(samples().into_iter(), true, Some(2));
+        let timestamps: Vec<_> = result.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![50, 40]);
+
+        let result = collect_samples(samples().into_iter(), true, None);
+        let timestamps: Vec<_> = result.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![50, 40, 30, 20, 10]);
+    }
+
+    #[test]
+    fn grouped_labels_for_multiple_group_labels() {
+        let group_labels = vec!["region".to_string(), "service".to_string()];
+        let group_key = format!("us{GROUP_KEY_SEPARATOR}api");
+
+        assert_eq!(
+            format_group_key(&group_labels, &group_key),
+            "region=us,service=api"
+        );
+
+        let sources = vec!["ts1".to_string(), "ts2".to_string()];
+        let labels = build_mrange_grouped_labels(&group_labels, &group_key, "sum", &sources, false);
+        let pairs: Vec<_> = labels
+            .iter()
+            .map(|l| (l.name.as_str(), l.value.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("region", "us"),
+                ("service", "api"),
+                ("__reducer__", "sum"),
+                ("__source__", "ts1,ts2"),
+            ]
+        );
+
+        // WITHSOURCES adds the capped list after the existing labels
+        let labels = build_mrange_grouped_labels(&group_labels, &group_key, "sum", &sources, true);
+        let last = labels.last().unwrap();
+        assert_eq!(labels.len(), 5);
+        assert_eq!(
+            (last.name.as_str(), last.value.as_str()),
+            ("__sources__", "ts1,ts2")
+        );
+    }
+
+    #[test]
+    fn group_sources_are_truncated() {
+        let keys: Vec<String> = (0..MAX_GROUP_SOURCES + 5)
+            .map(|i| format!("ts{i}"))
+            .collect();
+
+        let sources = format_group_sources(&keys[..3]);
+        assert_eq!(sources, "ts0,ts1,ts2");
+
+        let sources = format_group_sources(&keys);
+        assert!(sources.starts_with("ts0,ts1,"));
+        assert!(sources.ends_with(&format!("ts{},...(+5 more)", MAX_GROUP_SOURCES - 1)));
+    }
+
+    fn series_result(key: &str, count: i64) -> MRangeSeriesResult {
+        let samples = (1..=count).map(|i| Sample::new(i, i as f64)).collect();
+        MRangeSeriesResult {
+            key: key.to_string(),
+            group_label_value: None,
+            labels: vec![],
+            data: TimeSeriesChunk::Uncompressed(UncompressedChunk::from_vec(samples)),
+        }
+    }
+
+    fn sample_counts(results: &[MRangeSeriesResult]) -> Vec<(&str, usize)> {
+        results
+            .iter()
+            .map(|r| (r.key.as_str(), r.data.len()))
+            .collect()
+    }
+
+    #[test]
+    fn limit_total_samples_cuts_series_at_limit() {
+        let mut results = vec![
+            series_result("a", 3),
+            series_result("b", 3),
+            series_result("c", 3),
+        ];
+        assert!(limit_total_samples(&mut results, 5));
+        assert_eq!(sample_counts(&results), vec![("a", 3), ("b", 2)]);
+
+        let timestamps: Vec<_> = results[1].data.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![1, 2]);
+    }
+
+    #[test]
+    fn limit_total_samples_drops_series_after_limit() {
+        let mut results = vec![
+            series_result("a", 2),
+            series_result("b", 0),
+            series_result("c", 1),
+        ];
+        assert!(limit_total_samples(&mut results, 2));
+        assert_eq!(sample_counts(&results), vec![("a", 2)]);
+    }
+
+    #[test]
+    fn limit_total_samples_not_truncated_when_dropped_series_are_empty() {
+        let mut results = vec![
+            series_result("a", 2),
+            series_result("b", 0),
+            series_result("c", 0),
+        ];
+        assert!(!limit_total_samples(&mut results, 2));
+        assert_eq!(sample_counts(&results), vec![("a", 2)]);
+    }
+
+    #[test]
+    fn limit_total_samples_not_truncated_when_under_limit() {
+        let mut results = vec![series_result("a", 2), series_result("b", 3)];
+        assert!(!limit_total_samples(&mut results, 5));
+        assert_eq!(sample_counts(&results), vec![("a", 2), ("b", 3)]);
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        for id in [0, 1, 42, u64::MAX] {
+            assert_eq!(decode_mrange_cursor(&encode_mrange_cursor(id)).unwrap(), id);
+        }
+    }
+
+    #[test]
+    fn invalid_cursor_is_rejected() {
+        for cursor in ["", "-1", "abc", "1.5"] {
+            assert!(decode_mrange_cursor(cursor).is_err(), "{cursor}");
+        }
+    }
+}
@@ -2,7 +2,9 @@ use crate::common::Timestamp;
 use crate::common::context::is_real_user_client;
 use crate::common::threads::NUM_THREADS;
 use crate::labels::filters::SeriesSelector;
-use crate::series::index::{PostingsBitmap, get_timeseries_index, with_timeseries_postings};
+use crate::series::index::{
+    PostingsBitmap, get_timeseries_index, mark_series_for_removal, with_timeseries_postings,
+};
 use crate::series::series_data_type::VK_TIME_SERIES_TYPE;
 use crate::series::{
     CompactionOp, SeriesGuardMut, SeriesRef, TimeSeries, TimestampRange, apply_compaction,
@@ -32,9 +34,28 @@ pub fn delete_series_by_selectors(
     }
 }
 
-fn delete_key(ctx: &Context, key: &ValkeyString) -> ValkeyResult<usize> {
-    match ctx.open_key_writable(key).delete() {
-        Ok(_) => Ok(1),
+/// Deletes a key matched by the index, but only if it still holds a time series. The series id is
+/// marked stale afterward so that no postings are left behind, even when the free callback skips
+/// index maintenance (e.g. during a flush).
+pub(crate) fn delete_series_key(ctx: &Context, key: &ValkeyString) -> ValkeyResult<usize> {
+    let valkey_key = ctx.open_key_writable(key);
+    let id = match valkey_key.get_value::<TimeSeries>(&VK_TIME_SERIES_TYPE) {
+        Ok(Some(series)) => series.id,
+        Ok(None) => return Ok(0),
+        Err(_) => {
+            let msg = format!(
+                "multi-del: key {} no longer holds a time series, skipping",
+                key.to_string_lossy()
+            );
+            ctx.log_warning(&msg);
+            return Ok(0);
+        }
+    };
+    match valkey_key.delete() {
+        Ok(_) => {
+            mark_series_for_removal(ctx, id);
+            Ok(1)
+        }
         Err(e) => {
             let msg = format!(
                 "multi-del: error deleting key {}: {:?}",
@@ -53,7 +74,7 @@ fn handle_delete_keys(ctx: &Context, filters: &[SeriesSelector]) -> ValkeyResult
     let keys = index.keys_for_selectors(ctx, filters, Some(AclPermissions::DELETE))?;
     let mut total_deleted = 0;
     for key in keys {
-        total_deleted += delete_key(ctx, &ctx.create_string(key.as_ref()))?;
+        total_deleted += delete_series_key(ctx, &key)?;
     }
     Ok(total_deleted)
 }
@@ -1,20 +1,23 @@
 use crate::aggregators::{
     Aggregator, AllAggregator, AnyAggregator, CountIfAggregator, NoneAggregator, ShareAggregator,
-    SumIfAggregator,
+    SumIfAggregator, TrimmedMeanAggregator,
 };
 use crate::common::binop::ComparisonOperator;
 use crate::common::hash::hash_f64;
 use crate::common::{Sample, Timestamp};
+use crate::error_consts;
 use crate::labels::Label;
 use crate::labels::filters::SeriesSelector;
 use crate::series::chunks::TimeSeriesChunk;
-use crate::series::{DateRange, TimestampRange, ValueFilter};
+use crate::series::{DateRange, SeriesRef, TimestampRange, ValueFilter};
+use ahash::AHashMap;
 use get_size2::GetSize;
 use std::fmt::Display;
 use std::hash::Hash;
+use std::time::Duration;
 use valkey_module::{RedisModuleIO, ValkeyError, ValkeyResult, ValkeyString, ValkeyValue};
 
-pub use crate::aggregators::{AggregationType, BucketAlignment, BucketTimestamp};
+pub use crate::aggregators::{AggregationType, BucketAlignment, BucketTimestamp, FillPolicy};
 use crate::common::rdb::{rdb_load_f64, rdb_load_u8, rdb_save_f64, rdb_save_u8};
 
 #[derive(Debug, Copy, Clone, GetSize)]
@@ -67,10 +70,12 @@ impl ValueComparisonFilter {
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct AggregatorConfig {
     pub(crate) aggregation: AggregationType,
     pub(crate) value_filter: Option<ValueComparisonFilter>,
+    /// Numeric argument for aggregations that take one (e.g. the trimmed_mean percentage)
+    pub(crate) param: Option<f64>,
 }
 
 impl AggregatorConfig {
@@ -90,9 +95,28 @@ impl AggregatorConfig {
         Ok(Self {
             aggregation,
             value_filter,
+            param: None,
         })
     }
 
+    /// Sets the numeric parameter of the aggregation, validating it against the aggregation type.
+    pub fn with_param(mut self, param: f64) -> ValkeyResult<Self> {
+        match self.aggregation {
+            AggregationType::TrimmedMean => {
+                if !(0.0..50.0).contains(&param) {
+                    return Err(ValkeyError::Str(error_consts::INVALID_TRIM_PERCENTAGE));
+                }
+            }
+            _ => {
+                return Err(ValkeyError::Str(
+                    error_consts::AGGREGATION_PARAM_NOT_SUPPORTED,
+                ));
+            }
+        }
+        self.param = Some(param);
+        Ok(self)
+    }
+
     pub fn aggregation_type(&self) -> AggregationType {
         self.aggregation
     }
@@ -105,8 +129,17 @@ impl AggregatorConfig {
         self.value_filter
     }
 
+    pub fn param(&self) -> Option<f64> {
+        self.param
+    }
+
     pub fn create_aggregator(&self) -> Aggregator {
         let aggr_type = self.aggregation;
+        if aggr_type == AggregationType::TrimmedMean {
+            return Aggregator::TrimmedMean(TrimmedMeanAggregator::new(
+                self.param.unwrap_or_default(),
+            ));
+        }
         if let Some(filter) = self.value_filter {
             match self.aggregation {
                 AggregationType::All => {
@@ -140,6 +173,17 @@ impl Default for AggregatorConfig {
         Self {
             aggregation: AggregationType::Avg,
             value_filter: None,
+            param: None,
+        }
+    }
+}
+
+impl Hash for AggregatorConfig {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.aggregation.hash(state);
+        self.value_filter.hash(state);
+        if let Some(param) = self.param {
+            hash_f64(param, state);
         }
     }
 }
@@ -150,6 +194,7 @@ impl From<AggregationType> for AggregatorConfig {
         Self {
             aggregation,
             value_filter: None,
+            param: None,
         }
     }
 }
@@ -161,6 +206,26 @@ pub struct AggregationOptions {
     pub timestamp_output: BucketTimestamp,
     pub alignment: BucketAlignment,
     pub report_empty: bool,
+    /// How empty buckets are populated. Setting a policy implies `report_empty`.
+    pub fill: Option<FillPolicy>,
+    /// WITHCOUNTS: also report the number of samples in each bucket
+    pub with_counts: bool,
+    /// DEDUP: drop buckets repeating the value of the previous bucket
+    pub dedup: Option<DedupOptions>,
+}
+
+/// Options for DEDUP, which drops buckets whose values equal those of the previous bucket, keeping
+/// the first and last bucket of each run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DedupOptions {
+    /// Whether two NaN values are considered equal. Set unless NAN_DISTINCT is given.
+    pub nan_equal: bool,
+}
+
+impl Default for DedupOptions {
+    fn default() -> Self {
+        Self { nan_equal: true }
+    }
 }
 
 /// A filter that can be either inclusive or exclusive over a date range.
@@ -226,6 +291,9 @@ impl Default for AggregationOptions {
             timestamp_output: BucketTimestamp::Start,
             alignment: BucketAlignment::Default,
             report_empty: false,
+            fill: None,
+            with_counts: false,
+            dedup: None,
         }
     }
 }
@@ -261,10 +329,128 @@ impl From<SeriesSelector> for MatchFilterOptions {
     }
 }
 
+/// How `TS.SERIESDIFF` combines the series matched by its two selectors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesDiffMode {
+    /// Series matched by the left selector but not by the right one.
+    #[default]
+    Diff,
+    /// Series matched by exactly one of the two selectors.
+    Xor,
+}
+
+impl TryFrom<&str> for SeriesDiffMode {
+    type Error = ValkeyError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mode = hashify::tiny_map_ignore_case! {
+            value.as_bytes(),
+            "diff" => SeriesDiffMode::Diff,
+            "xor" => SeriesDiffMode::Xor,
+        };
+        mode.ok_or(ValkeyError::Str(error_consts::INVALID_SERIES_DIFF_MODE))
+    }
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct SeriesDiffOptions {
+    pub mode: SeriesDiffMode,
+    pub left: SeriesSelector,
+    pub right: SeriesSelector,
+}
+
+/// Whether TRANSFORM applies to the samples fed to the aggregation or to the aggregated values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TransformOrder {
+    #[default]
+    BeforeAggregation,
+    AfterAggregation,
+}
+
+/// A linear conversion of values (`value * scale + offset`) applied at query time, e.g. for unit
+/// conversion. Stored samples are never modified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueTransform {
+    pub scale: f64,
+    pub offset: f64,
+    pub order: TransformOrder,
+}
+
+impl Default for ValueTransform {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            offset: 0.0,
+            order: TransformOrder::default(),
+        }
+    }
+}
+
+impl ValueTransform {
+    pub fn apply(&self, value: f64) -> f64 {
+        value * self.scale + self.offset
+    }
+
+    pub fn apply_sample(&self, sample: Sample) -> Sample {
+        Sample::new(sample.timestamp, self.apply(sample.value))
+    }
+}
+
+/// A scalar function applied to sample values at query time (`FUNCTION name`). Values outside the
+/// domain of the function, such as the log of a non-positive value, become NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueFunction {
+    Abs,
+    Log,
+    Log2,
+    Log10,
+    Sqrt,
+    Exp,
+}
+
+impl ValueFunction {
+    pub fn apply(&self, value: f64) -> f64 {
+        match self {
+            ValueFunction::Abs => value.abs(),
+            ValueFunction::Log | ValueFunction::Log2 | ValueFunction::Log10 if value <= 0.0 => {
+                f64::NAN
+            }
+            ValueFunction::Log => value.ln(),
+            ValueFunction::Log2 => value.log2(),
+            ValueFunction::Log10 => value.log10(),
+            ValueFunction::Sqrt => value.sqrt(),
+            ValueFunction::Exp => value.exp(),
+        }
+    }
+
+    pub fn apply_sample(&self, sample: Sample) -> Sample {
+        Sample::new(sample.timestamp, self.apply(sample.value))
+    }
+}
+
+impl TryFrom<&str> for ValueFunction {
+    type Error = ValkeyError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let function = hashify::tiny_map_ignore_case! {
+            value.as_bytes(),
+            "abs" => ValueFunction::Abs,
+            "log" => ValueFunction::Log,
+            "log2" => ValueFunction::Log2,
+            "log10" => ValueFunction::Log10,
+            "sqrt" => ValueFunction::Sqrt,
+            "exp" => ValueFunction::Exp,
+        };
+        function.ok_or(ValkeyError::Str(error_consts::INVALID_VALUE_FUNCTION))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RangeGroupingOptions {
     pub aggregation: AggregatorConfig,
-    pub group_label: String,
+    pub group_labels: Vec<String>,
+    /// WITHSOURCES: add the `__sources__` label listing the keys of each group.
+    pub with_sources: bool,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -274,7 +460,17 @@ pub struct RangeOptions {
     pub latest: bool,
     pub aggregation: Option<AggregationOptions>,
     pub timestamp_filter: Option<Vec<Timestamp>>,
+    /// Sorted, disjoint `(start, end)` intervals from FILTER_BY_TS_RANGE.
+    pub timestamp_ranges: Option<Vec<(Timestamp, Timestamp)>>,
     pub value_filter: Option<ValueFilter>,
+    /// MAXPOINTS: downsample the result to at most this many points using LTTB.
+    pub max_points: Option<usize>,
+    /// COUNT n FROM END: return the last `count` results of the range rather than the first.
+    pub count_from_end: bool,
+    /// TRANSFORM: convert returned values at query time
+    pub transform: Option<ValueTransform>,
+    /// FUNCTION: scalar function applied to each sample value at query time
+    pub function: Option<ValueFunction>,
 }
 
 impl RangeOptions {
@@ -290,14 +486,44 @@ impl RangeOptions {
     }
 }
 
+/// Output names of selected labels, keyed by the source label name (`SELECTED_LABELS old=new`).
+/// Aliases only apply to the returned labels, never to matching or grouping.
+pub type LabelAliases = AHashMap<String, String>;
+
+/// Renames the labels that have an alias
+pub fn apply_label_aliases(labels: &mut [Label], aliases: &LabelAliases) {
+    if aliases.is_empty() {
+        return;
+    }
+    for label in labels.iter_mut() {
+        if let Some(alias) = aliases.get(&label.name) {
+            label.name = alias.clone();
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct MRangeOptions {
     pub range: RangeOptions,
     pub filters: Vec<SeriesSelector>,
     pub with_labels: bool,
     pub selected_labels: Vec<String>,
+    /// Applied by the node building the reply; not sent to other nodes.
+    pub label_aliases: LabelAliases,
     pub grouping: Option<RangeGroupingOptions>,
     pub is_reverse: bool,
+    /// LIMIT: cap on the total number of samples across all series, unlike COUNT which applies
+    /// per series. Applied by the node building the reply; not sent to other nodes.
+    pub sample_limit: Option<usize>,
+    /// CURSOR: return one page of series, resuming after a previous page. Standalone only.
+    pub cursor: Option<MRangeCursor>,
+}
+
+/// Position of a paged MRANGE: the page holds up to `count` series with ids greater than `after`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MRangeCursor {
+    pub after: SeriesRef,
+    pub count: usize,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -331,7 +557,11 @@ pub struct MGetRequest {
     pub with_labels: bool,
     pub filters: Vec<SeriesSelector>,
     pub selected_labels: Vec<String>,
+    /// Applied by the node building the reply; not sent to other nodes.
+    pub label_aliases: LabelAliases,
     pub latest: bool,
+    /// MAXSTALE: samples older than this (relative to now) are returned as nil.
+    pub max_stale: Option<Duration>,
 }
 
 pub struct MGetSeriesData {
@@ -351,10 +581,11 @@ impl From<MGetSeriesData> for ValkeyValue {
             })
             .collect();
 
+        // a series without samples still reports its labels, with a nil sample
         let sample_value: ValkeyValue = if let Some(sample) = series.sample {
             sample.into()
         } else {
-            ValkeyValue::Array(vec![])
+            ValkeyValue::Null
         };
         let series = vec![
             ValkeyValue::from(series.series_key),
@@ -365,6 +596,42 @@ impl From<MGetSeriesData> for ValkeyValue {
     }
 }
 
+#[derive(Debug, Default, Clone)]
+pub struct MBoundsRequest {
+    pub with_labels: bool,
+    pub filters: Vec<SeriesSelector>,
+}
+
+/// The time coverage of a series, read from its metadata without touching samples.
+pub struct MBoundsSeriesData {
+    pub series_key: ValkeyString,
+    pub labels: Vec<Label>,
+    /// First and last timestamps, or `None` if the series has no samples
+    pub bounds: Option<(Timestamp, Timestamp)>,
+    pub total_samples: usize,
+}
+
+impl From<MBoundsSeriesData> for ValkeyValue {
+    fn from(series: MBoundsSeriesData) -> Self {
+        let labels: Vec<_> = series
+            .labels
+            .into_iter()
+            .map(|label| label.into())
+            .collect();
+        let (first, last) = match series.bounds {
+            Some((first, last)) => (ValkeyValue::Integer(first), ValkeyValue::Integer(last)),
+            None => (ValkeyValue::Null, ValkeyValue::Null),
+        };
+        ValkeyValue::Array(vec![
+            ValkeyValue::from(series.series_key),
+            ValkeyValue::Array(labels),
+            first,
+            last,
+            ValkeyValue::Integer(series.total_samples as i64),
+        ])
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct MDelRequest {
     pub range: Option<TimestampRange>,
@@ -4,7 +4,9 @@ use crate::error::TsdbResult;
 use crate::error_consts;
 use crate::series::chunks::{Chunk, TimeSeriesChunk};
 use crate::series::index::get_series_key_by_id;
-use crate::series::{DuplicatePolicy, SampleAddResult, TimeSeries, find_last_ge_index};
+use crate::series::{
+    DuplicatePolicy, SampleAddResult, TimeSeries, find_last_ge_index, split_chunk,
+};
 use orx_parallel::ParIterResult;
 use orx_parallel::{ParIter, ParallelizableCollectionMut};
 use smallvec::{SmallVec, smallvec};
@@ -20,6 +22,11 @@ struct GroupedSamples {
     chunk_index: usize,
     samples: SmallVec<Sample, 8>,
     indices: SmallVec<usize, 8>,
+    /// Samples folded into an earlier sample with the same timestamp, as
+    /// (original index, index of the sample they were folded into)
+    coalesced: SmallVec<(usize, usize), 4>,
+    /// Samples rejected while coalescing (e.g. under the BLOCK policy)
+    rejected: SmallVec<usize, 4>,
 }
 
 impl GroupedSamples {
@@ -28,9 +35,45 @@ impl GroupedSamples {
             chunk_index,
             samples: SmallVec::new(),
             indices: SmallVec::new(),
+            coalesced: SmallVec::new(),
+            rejected: SmallVec::new(),
         }
     }
 
+    /// Folds samples sharing a timestamp into a single sample using the duplicate policy, so
+    /// that chunks never see more than one incoming sample per timestamp.
+    ///
+    /// NOTE: samples **must** be sorted by timestamp.
+    fn coalesce_duplicates(&mut self, policy: DuplicatePolicy) {
+        if self.samples.len() < 2 {
+            return;
+        }
+
+        let mut samples: SmallVec<Sample, 8> = SmallVec::with_capacity(self.samples.len());
+        let mut indices: SmallVec<usize, 8> = SmallVec::with_capacity(self.indices.len());
+
+        for (&sample, &index) in self.samples.iter().zip(self.indices.iter()) {
+            if let Some(last) = samples.last_mut()
+                && last.timestamp == sample.timestamp
+            {
+                let kept_index = *indices.last().expect("indices and samples out of sync");
+                match policy.duplicate_value(sample.timestamp, last.value, sample.value) {
+                    Ok(value) => {
+                        last.value = value;
+                        self.coalesced.push((index, kept_index));
+                    }
+                    Err(_) => self.rejected.push(index),
+                }
+                continue;
+            }
+            samples.push(sample);
+            indices.push(index);
+        }
+
+        self.samples = samples;
+        self.indices = indices;
+    }
+
     /// Sorts samples by timestamp in ascending order, maintaining the relationship with their original indices.
     fn sort_by_timestamp(&mut self) {
         // Create a vector of (index, sample) pairs
@@ -66,7 +109,7 @@ impl GroupedSamples {
         policy: DuplicatePolicy,
     ) -> SmallVec<(usize, SampleAddResult), 8> {
         // Merge samples into this chunk
-        match chunk.merge_samples(&self.samples, Some(policy)) {
+        let mut results = match chunk.merge_samples(&self.samples, Some(policy)) {
             Ok(chunk_results) => chunk_results
                 .iter()
                 .zip(self.indices.iter().cloned())
@@ -80,7 +123,24 @@ impl GroupedSamples {
                     .map(|index| (index, err))
                     .collect::<SmallVec<_, 8>>()
             }
+        };
+
+        // coalesced samples share the outcome of the sample they were folded into
+        for &(index, kept_index) in self.coalesced.iter() {
+            let kept_result = results
+                .iter()
+                .find(|(i, _)| *i == kept_index)
+                .map(|(_, res)| *res)
+                .unwrap_or_default();
+            results.push((index, kept_result));
         }
+        results.extend(
+            self.rejected
+                .iter()
+                .map(|&index| (index, SampleAddResult::Duplicate)),
+        );
+
+        results
     }
 }
 
@@ -137,8 +197,11 @@ fn group_samples_by_chunk(
     samples: &[Sample],
     results: &mut [SampleAddResult],
     earliest_allowed_timestamp: Timestamp,
+    policy: DuplicatePolicy,
 ) -> TsdbResult<IntMap<usize, GroupedSamples>> {
     let mut chunk_groups: IntMap<usize, GroupedSamples> = IntMap::default();
+    let last_timestamp = series.last_sample.map(|s| s.timestamp);
+    let split_policy = series.split_policy;
 
     for (index, &sample) in samples.iter().enumerate() {
         if sample.timestamp < earliest_allowed_timestamp {
@@ -146,6 +209,10 @@ fn group_samples_by_chunk(
             continue;
         }
 
+        if last_timestamp.is_some_and(|last| sample.timestamp < last) {
+            series.ooo_samples += 1;
+        }
+
         let adjusted_sample = Sample {
             value: series.adjust_value(sample.value),
             timestamp: sample.timestamp,
@@ -153,6 +220,11 @@ fn group_samples_by_chunk(
 
         let chunk_index = if series.is_empty() || sample.timestamp < series.first_timestamp {
             0
+        } else if series.sealed && last_timestamp.is_some_and(|last| sample.timestamp > last) {
+            // the first sample past a sealed chunk starts a new one, which takes the rest
+            series.add_chunk_with_sample(adjusted_sample)?;
+            results[index] = SampleAddResult::Ok(adjusted_sample);
+            continue;
         } else {
             loop {
                 let (index, _) = find_last_ge_index(&series.chunks, sample.timestamp);
@@ -161,7 +233,9 @@ fn group_samples_by_chunk(
                 let chunk = &mut series.chunks[index];
 
                 if chunk.should_split() {
-                    chunk.split()?;
+                    let new_chunk = split_chunk(chunk, split_policy, Some(sample.timestamp))?;
+                    series.chunks.insert(index + 1, new_chunk);
+                    series.chunk_splits += 1;
                     continue;
                 }
 
@@ -177,6 +251,7 @@ fn group_samples_by_chunk(
 
     for group in chunk_groups.values_mut() {
         group.sort_by_timestamp();
+        group.coalesce_duplicates(policy);
     }
 
     Ok(chunk_groups)
@@ -222,8 +297,13 @@ pub(super) fn merge_samples(
     }
 
     // Group samples by chunk. Map is chunk_idx -> Vec<(original_index, sample)>
-    let chunk_groups =
-        group_samples_by_chunk(series, samples, &mut results, earliest_allowed_timestamp)?;
+    let chunk_groups = group_samples_by_chunk(
+        series,
+        samples,
+        &mut results,
+        earliest_allowed_timestamp,
+        policy,
+    )?;
 
     let chunk_results = if chunk_groups.len() == 1 {
         // If all samples belong to a single chunk, handle it directly without parallelism
@@ -271,18 +351,58 @@ pub(super) fn merge_samples(
             if sample.timestamp < series.first_timestamp || series.is_empty() {
                 series.first_timestamp = sample.timestamp;
             }
-
-            // Update sample count
-            series.total_samples += 1;
         }
     }
 
+    // Samples resolved against an existing timestamp (or coalesced with each other) don't add
+    // to the count, so recompute it from the chunks rather than counting successful results.
+    series.total_samples = series.chunks.iter().map(|chunk| chunk.len()).sum();
+
     // Update last_sample
     series.update_last_sample();
+    series.enforce_max_samples();
 
     Ok(results)
 }
 
+/// A window size for `merge_samples_chunked` that keeps the per-window allocations small while
+/// still merging runs of samples into each chunk in a single write.
+pub const MERGE_WINDOW_SIZE: usize = 10_000;
+
+/// Merges `samples` into `series` a window of about `window_size` samples at a time, passing each
+/// window and its results to `on_results` before merging the next one. Unlike `merge_samples`,
+/// only a single window is grouped by chunk at once, which bounds the memory used by huge batches.
+///
+/// Windows never split a run of samples sharing a timestamp, so duplicates within the batch are
+/// coalesced as they would be by `merge_samples`, and the results are the same. The one exception
+/// is a series with a sample limit, which is trimmed after every window rather than once.
+///
+/// NOTE: `samples` **must** be sorted by timestamp.
+pub(super) fn merge_samples_chunked<F>(
+    series: &mut TimeSeries,
+    samples: &[Sample],
+    policy_override: Option<DuplicatePolicy>,
+    window_size: usize,
+    mut on_results: F,
+) -> TsdbResult<()>
+where
+    F: FnMut(&[Sample], &[SampleAddResult]),
+{
+    let window_size = window_size.max(1);
+    let mut start = 0;
+    while start < samples.len() {
+        let mut end = (start + window_size).min(samples.len());
+        while end < samples.len() && samples[end].timestamp == samples[end - 1].timestamp {
+            end += 1;
+        }
+        let window = &samples[start..end];
+        let results = merge_samples(series, window, policy_override)?;
+        on_results(window, &results);
+        start = end;
+    }
+    Ok(())
+}
+
 /// Merges samples across multiple series, supporting parallel processing when applicable.
 ///
 /// ### Parameters
@@ -1,10 +1,14 @@
 use crate::common::Sample;
 use crate::common::rdb::*;
+use crate::error_consts;
 use crate::labels::MetricName;
 use crate::series::chunks::{Chunk, ChunkEncoding, TimeSeriesChunk};
 use crate::series::compaction::CompactionRule;
-use crate::series::{SampleDuplicatePolicy, TimeSeries, TimeseriesId};
-use valkey_module::{ValkeyResult, raw};
+use crate::series::{
+    ChunkSplitPolicy, SampleDuplicatePolicy, TimeSeries, TimeseriesId, TimestampUnit,
+};
+use std::collections::BTreeMap;
+use valkey_module::{ValkeyError, ValkeyResult, raw};
 
 pub fn rdb_save_series(series: &TimeSeries, rdb: *mut raw::RedisModuleIO) {
     raw::save_unsigned(rdb, series.id);
@@ -31,6 +35,33 @@ pub fn rdb_save_series(series: &TimeSeries, rdb: *mut raw::RedisModuleIO) {
     for rule in series.rules.iter() {
         rule.rdb_save(rdb);
     }
+
+    // everything below was added in encoding version 2
+    // ingestion counters
+    rdb_save_usize(rdb, series.ooo_samples);
+    rdb_save_usize(rdb, series.chunk_splits);
+
+    // count-based retention. 0 means unlimited
+    rdb_save_usize(rdb, series.max_samples.unwrap_or_default());
+
+    // chunk split policy
+    raw::save_unsigned(rdb, series.split_policy.as_u8() as u64);
+
+    // pending ENCODING AUTO selection
+    raw::save_unsigned(rdb, series.auto_encoding as u64);
+
+    // client timestamp unit
+    raw::save_unsigned(rdb, series.timestamp_unit.as_u8() as u64);
+
+    // annotations
+    rdb_save_usize(rdb, series.annotations.len());
+    for (timestamp, text) in series.annotations.iter() {
+        raw::save_signed(rdb, *timestamp);
+        rdb_save_string(rdb, text);
+    }
+
+    // set by TS.SEAL
+    raw::save_unsigned(rdb, series.sealed as u64);
 }
 
 pub fn rdb_load_series(rdb: *mut raw::RedisModuleIO, enc_ver: i32) -> ValkeyResult<TimeSeries> {
@@ -74,11 +105,69 @@ pub fn rdb_load_series(rdb: *mut raw::RedisModuleIO, enc_ver: i32) -> ValkeyResu
     }
     rules.shrink_to_fit();
 
+    // the remaining fields were all added in encoding version 2
+    let has_v2_fields = enc_ver >= 2;
+
+    let (ooo_samples, chunk_splits) = if has_v2_fields {
+        (rdb_load_usize(rdb)?, rdb_load_usize(rdb)?)
+    } else {
+        (0, 0)
+    };
+
+    let max_samples = if has_v2_fields {
+        Some(rdb_load_usize(rdb)?).filter(|max| *max > 0)
+    } else {
+        None
+    };
+
+    let split_policy = if has_v2_fields {
+        let value = raw::load_unsigned(rdb)?;
+        u8::try_from(value)
+            .ok()
+            .and_then(|v| ChunkSplitPolicy::from_u8(v).ok())
+            .ok_or(ValkeyError::Str(error_consts::INVALID_SPLIT_POLICY))?
+    } else {
+        ChunkSplitPolicy::default()
+    };
+
+    let auto_encoding = if has_v2_fields {
+        raw::load_unsigned(rdb)? != 0
+    } else {
+        false
+    };
+
+    let timestamp_unit = if has_v2_fields {
+        let value = raw::load_unsigned(rdb)?;
+        u8::try_from(value)
+            .ok()
+            .and_then(|v| TimestampUnit::from_u8(v).ok())
+            .ok_or(ValkeyError::Str(error_consts::INVALID_TIMESTAMP_UNIT))?
+    } else {
+        TimestampUnit::default()
+    };
+
+    let mut annotations = BTreeMap::new();
+    if has_v2_fields {
+        let count = rdb_load_usize(rdb)?;
+        for _ in 0..count {
+            let timestamp = raw::load_signed(rdb)?;
+            annotations.insert(timestamp, rdb_load_string(rdb)?);
+        }
+    }
+
+    let sealed = if has_v2_fields {
+        raw::load_unsigned(rdb)? != 0
+    } else {
+        false
+    };
+
     let ts = TimeSeries {
         id,
         labels,
         retention,
+        max_samples,
         chunk_compression,
+        auto_encoding,
         sample_duplicates,
         rounding,
         chunk_size_bytes,
@@ -89,6 +178,12 @@ pub fn rdb_load_series(rdb: *mut raw::RedisModuleIO, enc_ver: i32) -> ValkeyResu
         _db: 0,
         src_series,
         rules,
+        ooo_samples,
+        chunk_splits,
+        split_policy,
+        timestamp_unit,
+        annotations,
+        sealed,
     };
 
     // ts.update_meta();
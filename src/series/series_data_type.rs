@@ -12,7 +12,7 @@ use valkey_module::{
 use crate::common::context::get_current_db;
 use crate::series::TimeSeries;
 use crate::series::defrag_series;
-use crate::series::index::{get_db_index, next_timeseries_id};
+use crate::series::index::{get_db_index, next_timeseries_id, reserve_timeseries_id};
 use crate::series::serialization::{rdb_load_series, rdb_save_series};
 use std::os::raw::{c_int, c_void};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -22,7 +22,10 @@ use valkey_module::server_events::FlushSubevent;
 use valkey_module_macros::flush_event_handler;
 
 /// TimeSeries Module data type RDB encoding version.
-const TIMESERIES_TYPE_ENCODING_VERSION: i32 = 1;
+/// - 2: adds the out-of-order sample and chunk split counters, the sample count retention limit,
+///   the chunk split policy, the pending ENCODING AUTO selection, the client timestamp unit,
+///   annotations and the `TS.SEAL` flag
+const TIMESERIES_TYPE_ENCODING_VERSION: i32 = 2;
 
 pub static VK_TIME_SERIES_TYPE: ValkeyType = ValkeyType::new(
     "TSDB-TYPE",
@@ -88,7 +91,11 @@ unsafe extern "C" fn rdb_save(rdb: *mut RedisModuleIO, value: *mut c_void) {
 
 unsafe extern "C" fn rdb_load(rdb: *mut RedisModuleIO, enc_ver: c_int) -> *mut c_void {
     match rdb_load_series(rdb, enc_ver) {
-        Ok(series) => Box::into_raw(Box::new(series)) as *mut std::ffi::c_void,
+        Ok(series) => {
+            // also covers RESTORE and replica full syncs, which do not raise a "loaded" event
+            reserve_timeseries_id(series.id);
+            Box::into_raw(Box::new(series)) as *mut std::ffi::c_void
+        }
         Err(e) => {
             logging::log_notice(format!("Failed to load series from RDB. {e:?}"));
             std::ptr::null_mut()
@@ -1,27 +1,34 @@
 use super::chunks::utils::{filter_samples_by_value, filter_timestamp_slice};
-use super::{SampleAddResult, SampleDuplicatePolicy, TimeSeriesOptions, ValueFilter};
+use super::{
+    ChunkSplitPolicy, SampleAddResult, SampleDuplicatePolicy, SampleLookupMode, TimeSeriesOptions,
+    TimestampUnit, ValueFilter,
+};
 use crate::common::hash::IntMap;
 use crate::common::rounding::RoundingStrategy;
 use crate::common::time::current_time_millis;
-use crate::common::{Sample, Timestamp};
+use crate::common::{SAMPLE_SIZE, Sample, Timestamp};
 use crate::config::DEFAULT_CHUNK_SIZE_BYTES;
 use crate::error::{TsdbError, TsdbResult};
 use crate::labels::{InternedLabel, MetricName};
 use crate::series::DuplicatePolicy;
-use crate::series::chunks::{Chunk, ChunkEncoding, TimeSeriesChunk, validate_chunk_size};
+use crate::series::chunks::{
+    Chunk, ChunkEncoding, ChunkMeta, EncodedChunk, TimeSeriesChunk, decode_chunk,
+    validate_chunk_size,
+};
 use crate::series::compaction::CompactionRule;
 use crate::series::digest::{
     calc_compaction_digest, calc_duplicate_policy_digest, calc_metric_name_digest,
     calc_rounding_digest,
 };
 use crate::series::index::next_timeseries_id;
-use crate::series::sample_merge::merge_samples;
+use crate::series::sample_merge::{merge_samples, merge_samples_chunked};
 use crate::series::series_sample_iterator::SeriesSampleIterator;
 use crate::{config, error_consts};
 use get_size2::GetSize;
 use orx_parallel::ParIterResult;
 use orx_parallel::{IntoParIter, ParIter, Parallelizable, ParallelizableCollectionMut};
 use smallvec::SmallVec;
+use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::mem::size_of;
 use std::sync::atomic::AtomicBool;
@@ -43,10 +50,15 @@ pub struct TimeSeries {
     pub labels: MetricName,
     /// Duration for which data is retained before automatic removal
     pub retention: Duration,
+    /// Maximum number of samples to retain. Older samples are removed once the count is exceeded.
+    pub max_samples: Option<usize>,
     /// Policy for handling duplicate samples
     pub sample_duplicates: SampleDuplicatePolicy,
     /// The chunk compression algorithm used (Uncompressed, Gorilla, or Pco)
     pub chunk_compression: ChunkEncoding,
+    /// Set for `ENCODING AUTO` series until the encoding of new chunks has been chosen from the
+    /// samples of the first chunk. See [`select_chunk_encoding`].
+    pub auto_encoding: bool,
     /// Optional strategy for rounding values (either by significant or decimal digits)
     pub rounding: Option<RoundingStrategy>,
     /// Target size for chunks in bytes
@@ -62,11 +74,53 @@ pub struct TimeSeries {
     pub last_sample: Option<Sample>,
     pub src_series: Option<TimeseriesId>,
     pub rules: Vec<CompactionRule>,
+    /// Number of samples that arrived with a timestamp older than the last sample
+    pub ooo_samples: usize,
+    /// Number of chunk splits caused by inserts into full chunks
+    pub chunk_splits: usize,
+    /// Where full chunks are divided when a sample has to be inserted into them
+    pub split_policy: ChunkSplitPolicy,
+    /// The unit of timestamps sent to and returned by single-series commands
+    pub timestamp_unit: TimestampUnit,
+    /// Sparse notes (e.g. deploy markers) keyed by timestamp. Kept apart from the samples, and
+    /// removed along with them when their timestamp is deleted or expires.
+    pub annotations: BTreeMap<Timestamp, String>,
+    /// Set by `TS.SEAL`: the last chunk no longer receives appends, and the next sample past it
+    /// starts a new chunk.
+    pub sealed: bool,
     /// Internal bookkeeping for current db. Simplifies event handling related to indexing.
     /// This is not part of the time series data itself, nor is it stored to rdb.
     pub(crate) _db: i32,
 }
 
+/// Ingestion statistics for a series, derived from its metadata without walking samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeriesStats {
+    pub total_samples: usize,
+    /// Time (ms) between the first and last sample
+    pub duration: i64,
+    /// Average number of samples per second over `duration`
+    pub sample_rate: f64,
+    pub chunk_count: usize,
+    /// Bytes used by sample data across all chunks
+    pub data_size: usize,
+    /// Average number of data bytes per sample
+    pub bytes_per_sample: f64,
+    /// Uncompressed sample size (`SAMPLE_SIZE`) divided by `bytes_per_sample`
+    pub compression_ratio: f64,
+}
+
+/// Breakdown of `TimeSeries::memory_usage` by component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeriesMemoryUsage {
+    /// Chunk structures and their sample buffers
+    pub chunks: usize,
+    /// Interned labels. Label/value pairs shared with other series only count their handle.
+    pub labels: usize,
+    /// Everything else: the series struct itself, rules and bookkeeping
+    pub metadata: usize,
+}
+
 impl TimeSeries {
     /// Create a new empty time series.
     pub fn new() -> Self {
@@ -81,14 +135,27 @@ impl TimeSeries {
         }
 
         res.chunk_compression = options.chunk_compression;
+        res.auto_encoding = options.auto_encoding;
         res.retention = options.retention.unwrap_or_else(|| {
             let retention = config::RETENTION_PERIOD
                 .lock()
                 .expect("failed to lock RETENTION_PERIOD mutex");
             *retention
         });
+        res.max_samples = options.max_samples.filter(|max| *max > 0);
         res.rounding = options.rounding;
-        res.sample_duplicates = options.sample_duplicate_policy.unwrap_or_default();
+        // like the retention, a series created without a duplicate policy inherits the module default
+        res.sample_duplicates = options.sample_duplicate_policy.unwrap_or_else(|| {
+            let policy = config::DUPLICATE_POLICY
+                .lock()
+                .expect("failed to lock DUPLICATE_POLICY mutex");
+            SampleDuplicatePolicy {
+                policy: Some(*policy),
+                ..Default::default()
+            }
+        });
+        res.split_policy = options.split_policy.unwrap_or_default();
+        res.timestamp_unit = options.timestamp_unit.unwrap_or_default();
 
         // if !options.labels.iter().any(|x| x.name == METRIC_NAME_LABEL) {
         //     return Err(TsdbError::InvalidMetric(
@@ -144,6 +211,61 @@ impl TimeSeries {
         self.chunk_compression != ChunkEncoding::Uncompressed
     }
 
+    /// Metadata of each chunk in timestamp order, obtained without decoding samples.
+    pub fn chunks_meta(&self) -> impl Iterator<Item = ChunkMeta> + '_ {
+        self.chunks
+            .iter()
+            .filter(|c| !c.is_empty())
+            .map(ChunkMeta::from)
+    }
+
+    /// The encoded payload of each chunk in timestamp order, for bulk export. Chunks are copied
+    /// as-is, so exporting is cheap regardless of the number of samples.
+    pub fn encoded_chunks(&self) -> impl Iterator<Item = EncodedChunk> + '_ {
+        self.chunks
+            .iter()
+            .filter(|c| !c.is_empty())
+            .map(EncodedChunk::from)
+    }
+
+    /// Appends a chunk produced by [`TimeSeries::encoded_chunks`], bypassing sample ingestion.
+    /// The chunk must use the series encoding, agree with its metadata and start after the last
+    /// sample of the series, so chunks must be imported in timestamp order.
+    pub fn import_chunk(&mut self, encoded: &EncodedChunk) -> TsdbResult<()> {
+        let meta = &encoded.meta;
+        if meta.encoding != self.chunk_compression {
+            return Err(TsdbError::InvalidCompression(
+                error_consts::CHUNK_IMPORT_ENCODING_MISMATCH.to_string(),
+            ));
+        }
+
+        let chunk = decode_chunk(&encoded.data)?;
+        if chunk.is_empty() || ChunkMeta::from(&chunk) != *meta {
+            return Err(TsdbError::General(
+                error_consts::CHUNK_IMPORT_META_MISMATCH.to_string(),
+            ));
+        }
+
+        if !self.is_empty() && meta.first_timestamp <= self.last_timestamp() {
+            return Err(TsdbError::General(
+                error_consts::CHUNK_IMPORT_OUT_OF_ORDER.to_string(),
+            ));
+        }
+
+        // drop the empty chunk a new series starts with, so the import does not leave a gap
+        self.chunks.retain(|c| !c.is_empty());
+        if self.total_samples == 0 {
+            self.first_timestamp = meta.first_timestamp;
+        }
+        self.total_samples += meta.count;
+        self.last_sample = chunk.last_sample();
+        self.chunks.push(chunk);
+        self.sealed = false;
+        self.enforce_max_samples();
+
+        Ok(())
+    }
+
     /// Get the full metric name of the time series, including labels in Prometheus format.
     /// For example,
     ///
@@ -198,9 +320,18 @@ impl TimeSeries {
 
             // - ts < last_ts: upsert (no validation)
             // - ts >= last_ts: validate duplicates; if ignored -> return Ignored(last_ts)
-            // - ts == last_ts: if not ignored, still go to upsert
-            if ts >= last_ts && !self.validate_sample(&sample, &last, dp_override).is_ok() {
-                return SampleAddResult::Ignored(last_ts);
+            // - ts == last_ts: if the stored sample would not change, there is nothing to write.
+            //   Otherwise, still go to upsert
+            if ts >= last_ts {
+                match self.validate_sample(&sample, &last, dp_override) {
+                    SampleAddResult::Ok(resolved) => {
+                        if ts == last_ts && resolved.value == last.value {
+                            return SampleAddResult::Ok(last);
+                        }
+                    }
+                    SampleAddResult::Ignored(_) => return SampleAddResult::Ignored(last_ts),
+                    res => return res,
+                }
             }
             if ts <= last_ts {
                 return self.upsert_sample(sample, dp_override);
@@ -210,12 +341,32 @@ impl TimeSeries {
         self.add_sample_internal(sample)
     }
 
+    /// Checks a sample at or after the last sample of the series.
+    ///
+    /// A sample at the timestamp of the last sample is resolved by the duplicate policy first:
+    /// - `BLOCK` rejects it as a `Duplicate`, whether or not its value differs.
+    /// - otherwise `Ok` holds the value the policy would store. If that is the stored value, as
+    ///   when re-sending the last sample under `LAST`, the write is an idempotent no-op and
+    ///   `Ok(last_sample)` is returned rather than a duplicate or ignored result.
+    ///
+    /// Samples within the `IGNORE` thresholds (`ignoreMaxTimediff` and `ignoreMaxValDiff`)
+    /// of the last sample are `Ignored`.
     pub(crate) fn validate_sample(
         &self,
         sample: &Sample,
         last_sample: &Sample,
         on_duplicate: Option<DuplicatePolicy>,
     ) -> SampleAddResult {
+        if sample.timestamp == last_sample.timestamp {
+            let policy = self.sample_duplicates.resolve_policy(on_duplicate);
+            match policy.duplicate_value(sample.timestamp, last_sample.value, sample.value) {
+                Ok(value) if value == last_sample.value => {
+                    return SampleAddResult::Ok(*last_sample);
+                }
+                Ok(_) => {}
+                Err(_) => return SampleAddResult::Duplicate,
+            }
+        }
         if self
             .sample_duplicates
             .is_duplicate(sample, last_sample, on_duplicate)
@@ -227,6 +378,9 @@ impl TimeSeries {
     }
 
     pub(super) fn add_sample_internal(&mut self, sample: Sample) -> SampleAddResult {
+        if self.sealed {
+            return self.handle_full_chunk(sample);
+        }
         let chunk = self.get_last_chunk();
         if chunk.is_full() {
             return self.handle_full_chunk(sample);
@@ -234,6 +388,7 @@ impl TimeSeries {
         match chunk.add_sample(&sample) {
             Ok(_) => {
                 self.record_appended_sample(sample);
+                self.enforce_max_samples();
                 SampleAddResult::Ok(sample)
             }
             Err(TsdbError::CapacityFull(_)) => self.handle_full_chunk(sample),
@@ -242,10 +397,11 @@ impl TimeSeries {
     }
 
     /// (Possibly) add a new chunk and append the given sample.
-    fn add_chunk_with_sample(&mut self, sample: Sample) -> TsdbResult<()> {
+    pub(super) fn add_chunk_with_sample(&mut self, sample: Sample) -> TsdbResult<()> {
         let mut chunk = self.create_chunk();
         chunk.add_sample(&sample)?;
         self.chunks.push(chunk);
+        self.sealed = false;
         // trim chunks to keep memory usage in check
         self.chunks.shrink_to_fit();
         self.record_appended_sample(sample);
@@ -256,15 +412,33 @@ impl TimeSeries {
     pub(super) fn append_chunk(&mut self) {
         let new_chunk = self.create_chunk();
         self.chunks.push(new_chunk);
+        self.sealed = false;
     }
 
     pub(super) fn create_chunk(&mut self) -> TimeSeriesChunk {
+        self.resolve_auto_encoding();
         TimeSeriesChunk::new(self.chunk_compression, self.chunk_size_bytes)
     }
 
+    /// For `ENCODING AUTO` series, picks the encoding of new chunks once a chunk holds samples
+    /// and another one is needed. Chunks created before that keep their (uncompressed) encoding.
+    pub(super) fn resolve_auto_encoding(&mut self) {
+        if !self.auto_encoding {
+            return;
+        }
+        let Some(chunk) = self.chunks.last().filter(|c| !c.is_empty()) else {
+            return;
+        };
+        self.chunk_compression = select_chunk_encoding(chunk.iter());
+        self.auto_encoding = false;
+    }
+
     fn handle_full_chunk(&mut self, sample: Sample) -> SampleAddResult {
         match self.add_chunk_with_sample(sample) {
-            Ok(_) => SampleAddResult::Ok(sample),
+            Ok(_) => {
+                self.enforce_max_samples();
+                SampleAddResult::Ok(sample)
+            }
             Err(TsdbError::DuplicateSample(_)) => SampleAddResult::Duplicate,
             Err(_) => SampleAddResult::Error(error_consts::CANNOT_ADD_SAMPLE),
         }
@@ -287,6 +461,13 @@ impl TimeSeries {
             .sample_duplicates
             .resolve_policy(duplicate_policy_override);
 
+        if self
+            .last_sample
+            .is_some_and(|last| sample.timestamp < last.timestamp)
+        {
+            self.ooo_samples += 1;
+        }
+
         let chunks_len = self.chunks.len();
         debug_assert!(chunks_len > 0, "upsert called on empty series");
 
@@ -314,50 +495,62 @@ impl TimeSeries {
         // if chunk can accept the sample without splitting, do it directly
         if !chunk.should_split() {
             let old_size = chunk.len();
-            let (size, res) = chunk.upsert(sample, duplicate_policy);
+            let (_, res) = chunk.upsert(sample, duplicate_policy);
             if res.is_ok() {
-                self.total_samples += size.saturating_sub(old_size);
+                // chunk types differ in what `upsert` reports, so compare lengths instead
+                self.total_samples += chunk.len().saturating_sub(old_size);
                 if is_last {
                     self.update_last_sample();
                 }
                 self.first_timestamp = sample.timestamp.min(self.first_timestamp);
+                self.enforce_max_samples();
             }
             return res;
         }
 
-        // otherwise split the chunk and upsert into the new chunk
-        match chunk.split() {
-            Ok(mut new_chunk) => {
-                let (size, res) = new_chunk.upsert(sample, duplicate_policy);
-                if !res.is_ok() {
-                    return res;
-                }
+        // otherwise split the chunk and upsert into whichever half the sample belongs to
+        let old_len = chunk.len();
+        let mut new_chunk = match split_chunk(chunk, self.split_policy, Some(sample.timestamp)) {
+            Ok(new_chunk) => new_chunk,
+            Err(_) => return SampleAddResult::Error(error_consts::CHUNK_SPLIT),
+        };
+        self.chunk_splits += 1;
 
-                // best-effort trim; don't block ingestion on failure
-                if let Err(e) = self.trim() {
-                    logging::log_warning(format!("TSDB: Error trimming time series: {e:?}"));
-                }
+        let goes_left = new_chunk.is_empty() || sample.timestamp < new_chunk.first_timestamp();
+        let (_, res) = if goes_left {
+            chunk.upsert(sample, duplicate_policy)
+        } else {
+            new_chunk.upsert(sample, duplicate_policy)
+        };
+        let new_len = chunk.len() + new_chunk.len();
 
-                // insert the new chunk in order
-                let insert_at = self
-                    .chunks
-                    .partition_point(|c| c.first_timestamp() <= new_chunk.first_timestamp());
-                self.chunks.insert(insert_at, new_chunk);
+        // the split-off samples must be kept even if the upsert itself failed
+        if !new_chunk.is_empty() {
+            self.chunks.insert(target_idx + 1, new_chunk);
+        }
+        self.total_samples = (self.total_samples + new_len).saturating_sub(old_len);
 
-                self.total_samples += size;
-                if is_last {
-                    self.update_last_sample();
-                }
-                self.first_timestamp = sample.timestamp.min(self.first_timestamp);
+        if !res.is_ok() {
+            return res;
+        }
 
-                SampleAddResult::Ok(sample)
-            }
-            Err(_) => SampleAddResult::Error(error_consts::CHUNK_SPLIT),
+        // best-effort trim; don't block ingestion on failure
+        if let Err(e) = self.trim() {
+            logging::log_warning(format!("TSDB: Error trimming time series: {e:?}"));
         }
+
+        if is_last {
+            self.update_last_sample();
+        }
+        self.first_timestamp = sample.timestamp.min(self.first_timestamp);
+        self.enforce_max_samples();
+
+        res
     }
 
     pub(crate) fn split_chunks_if_needed(&mut self) -> TsdbResult<()> {
         let errored: AtomicBool = AtomicBool::new(false);
+        let policy = self.split_policy;
 
         // todo: track error, but allow partials
         let new_chunks = if self.is_compressed() {
@@ -365,7 +558,7 @@ impl TimeSeries {
                 .par_mut()
                 .filter(|c| c.is_full())
                 .flat_map(|chunks| {
-                    if let Ok(split_chunk) = chunks.split() {
+                    if let Ok(split_chunk) = split_chunk(chunks, policy, None) {
                         Some(split_chunk)
                     } else {
                         errored.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -376,7 +569,7 @@ impl TimeSeries {
         } else {
             let mut new_chunks = Vec::with_capacity(std::cmp::max(2, self.chunks.len() / 6));
             for c in self.chunks.iter_mut().filter(|c| c.is_full()) {
-                if let Ok(split_chunk) = c.split() {
+                if let Ok(split_chunk) = split_chunk(c, policy, None) {
                     new_chunks.push(split_chunk);
                 } else {
                     errored.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -386,6 +579,7 @@ impl TimeSeries {
         };
 
         if !new_chunks.is_empty() {
+            self.chunk_splits += new_chunks.len();
             self.chunks.extend(new_chunks);
             self.chunks.sort_by_key(|chunk| chunk.first_timestamp());
         }
@@ -431,6 +625,25 @@ impl TimeSeries {
         merge_samples(self, samples, policy_override)
     }
 
+    /// Merges `samples` like [`merge_samples`](Self::merge_samples), but `window_size` samples at a
+    /// time, passing each window and its results to `on_results` as it completes. This caps the
+    /// memory used to merge very large batches. See [`MERGE_WINDOW_SIZE`](crate::series::MERGE_WINDOW_SIZE)
+    /// for a default window.
+    ///
+    /// `samples` **must** be sorted by timestamp.
+    pub fn merge_samples_chunked<F>(
+        &mut self,
+        samples: &[Sample],
+        policy_override: Option<DuplicatePolicy>,
+        window_size: usize,
+        on_results: F,
+    ) -> TsdbResult<()>
+    where
+        F: FnMut(&[Sample], &[SampleAddResult]),
+    {
+        merge_samples_chunked(self, samples, policy_override, window_size, on_results)
+    }
+
     /// Get the time series between given start and end time (both inclusive).
     pub fn get_range(&self, start_time: Timestamp, end_time: Timestamp) -> Vec<Sample> {
         if !self.overlaps(start_time, end_time) {
@@ -449,6 +662,14 @@ impl TimeSeries {
         samples
     }
 
+    /// Returns the samples in the range in descending timestamp order (newest first).
+    pub fn get_range_rev(&self, start_time: Timestamp, end_time: Timestamp) -> Vec<Sample> {
+        if !self.overlaps(start_time, end_time) {
+            return Vec::new();
+        }
+        self.range_iter_rev(start_time, end_time).collect()
+    }
+
     pub fn get_range_filtered(
         &self,
         start_timestamp: Timestamp,
@@ -489,6 +710,67 @@ impl TimeSeries {
         }
     }
 
+    /// Returns the value of the series at an arbitrary timestamp, resolved according to `mode`.
+    /// Previous and Next return `None` when there is no sample on that side of `ts`, while Nearest
+    /// falls back to whichever endpoint exists. Linear returns `None` outside the sample range.
+    pub fn sample_at(&self, ts: Timestamp, mode: SampleLookupMode) -> Option<Sample> {
+        match mode {
+            SampleLookupMode::Previous => self.sample_at_or_before(ts),
+            SampleLookupMode::Next => self.sample_at_or_after(ts),
+            SampleLookupMode::Nearest => {
+                match (self.sample_at_or_before(ts), self.sample_at_or_after(ts)) {
+                    (Some(prev), Some(next)) => {
+                        if ts.abs_diff(prev.timestamp) <= next.timestamp.abs_diff(ts) {
+                            Some(prev)
+                        } else {
+                            Some(next)
+                        }
+                    }
+                    (prev, next) => prev.or(next),
+                }
+            }
+            SampleLookupMode::Linear => {
+                let prev = self.sample_at_or_before(ts)?;
+                if prev.timestamp == ts {
+                    return Some(prev);
+                }
+                let next = self.sample_at_or_after(ts)?;
+                let ratio = (ts - prev.timestamp) as f64 / (next.timestamp - prev.timestamp) as f64;
+                let value = prev.value + (next.value - prev.value) * ratio;
+                Some(Sample::new(ts, value))
+            }
+        }
+    }
+
+    /// Returns the latest sample with a timestamp <= `ts`, crossing chunk boundaries if needed.
+    fn sample_at_or_before(&self, ts: Timestamp) -> Option<Sample> {
+        let last = self.last_sample?;
+        if ts >= last.timestamp {
+            return Some(last);
+        }
+        let min_ts = self.get_min_timestamp();
+        if ts < min_ts {
+            return None;
+        }
+        if let Some(chunk) = self.chunk_containing_timestamp(ts)
+            && let Ok(samples) = chunk.get_range(chunk.first_timestamp(), ts)
+            && let Some(sample) = samples.last()
+        {
+            return (sample.timestamp >= min_ts).then_some(*sample);
+        }
+        // ts falls between chunks
+        self.range_iter_rev(min_ts, ts).next()
+    }
+
+    /// Returns the earliest sample with a timestamp >= `ts`, crossing chunk boundaries if needed.
+    fn sample_at_or_after(&self, ts: Timestamp) -> Option<Sample> {
+        let last = self.last_sample?;
+        if ts > last.timestamp {
+            return None;
+        }
+        self.range_iter(ts, last.timestamp).next()
+    }
+
     pub fn samples_by_timestamps(&self, timestamps: &[Timestamp]) -> TsdbResult<Vec<Sample>> {
         if self.is_empty() || timestamps.is_empty() {
             return Ok(vec![]);
@@ -556,6 +838,33 @@ impl TimeSeries {
         SeriesSampleIterator::new(self, start, end, false)
     }
 
+    /// Iterates the samples in the range from newest to oldest, walking chunks last to first.
+    pub fn range_iter_rev(&self, start: Timestamp, end: Timestamp) -> SeriesSampleIterator<'_> {
+        let start = start.max(self.get_min_timestamp());
+        SeriesSampleIterator::new(self, start, end, true)
+    }
+
+    /// Iterates the samples falling in any of the given sorted, disjoint ranges. In reverse,
+    /// the ranges are visited from last to first so the output stays in descending order.
+    pub fn multi_range_iter<'a>(
+        &'a self,
+        ranges: &[(Timestamp, Timestamp)],
+        is_reverse: bool,
+    ) -> impl Iterator<Item = Sample> + 'a {
+        let min_ts = self.get_min_timestamp();
+        let mut ranges: Vec<(Timestamp, Timestamp)> = ranges
+            .iter()
+            .filter(|(start, end)| *end >= min_ts && self.overlaps(*start, *end))
+            .copied()
+            .collect();
+        if is_reverse {
+            ranges.reverse();
+        }
+        ranges.into_iter().flat_map(move |(start, end)| {
+            SeriesSampleIterator::new(self, start.max(min_ts), end, is_reverse)
+        })
+    }
+
     pub fn overlaps(&self, start_ts: Timestamp, end_ts: Timestamp) -> bool {
         !self.is_empty() && self.last_timestamp() >= start_ts && self.first_timestamp <= end_ts
     }
@@ -582,7 +891,74 @@ impl TimeSeries {
         deleted_count
     }
 
+    /// Removes samples that fall outside the retention limits of the series.
+    ///
+    /// Both the time-based `retention` and the count-based `max_samples` are applied, so a sample is
+    /// kept only if it satisfies both, i.e. whichever limit is stricter wins.
     pub(super) fn trim(&mut self) -> TsdbResult<usize> {
+        let deleted_count = self.trim_expired()?;
+        Ok(deleted_count + self.trim_to_max_samples()?)
+    }
+
+    /// Removes the oldest samples while the series holds more than `max_samples`.
+    fn trim_to_max_samples(&mut self) -> TsdbResult<usize> {
+        let Some(max_samples) = self.max_samples else {
+            return Ok(0);
+        };
+        let mut excess = self.total_samples.saturating_sub(max_samples);
+        if excess == 0 {
+            return Ok(0);
+        }
+
+        // drop whole chunks first
+        let mut deleted_count = 0;
+        let mut expired_chunks = 0;
+        for chunk in self.chunks.iter() {
+            let len = chunk.len();
+            if len > excess {
+                break;
+            }
+            excess -= len;
+            deleted_count += len;
+            expired_chunks += 1;
+        }
+        self.chunks.drain(..expired_chunks);
+
+        // then the oldest samples of the first remaining chunk
+        if excess > 0
+            && let Some(chunk) = self.chunks.first_mut()
+            && let Some(last_expired) = chunk.iter().nth(excess - 1)
+        {
+            let first_ts = chunk.first_timestamp();
+            deleted_count += chunk
+                .remove_range(first_ts, last_expired.timestamp)
+                .map_err(|_| TsdbError::RemoveRangeError)?;
+        }
+
+        self.total_samples -= deleted_count;
+        if deleted_count > 0 {
+            self.update_first_last_timestamps();
+            // drop the annotations of the removed samples
+            if self.is_empty() {
+                self.annotations.clear();
+            } else {
+                self.remove_annotations_before(self.first_timestamp);
+            }
+        }
+
+        Ok(deleted_count)
+    }
+
+    /// Applies the count-based retention after samples are added.
+    pub(crate) fn enforce_max_samples(&mut self) {
+        if self.max_samples.is_some_and(|max| self.total_samples > max)
+            && let Err(e) = self.trim_to_max_samples()
+        {
+            logging::log_warning(format!("TSDB: Error trimming time series: {e:?}"));
+        }
+    }
+
+    fn trim_expired(&mut self) -> TsdbResult<usize> {
         let min_timestamp = self.get_min_timestamp();
         if self.first_timestamp == min_timestamp {
             return Ok(0);
@@ -606,6 +982,9 @@ impl TimeSeries {
         if deleted_count > 0 {
             // Update first_timestamp and last_timestamp
             self.update_first_last_timestamps();
+            // TS.ANNOTATE rejects timestamps older than the retention window, so annotations can
+            // only fall out of it when the window moves, which is when samples expire too
+            self.remove_annotations_before(min_timestamp);
         }
 
         self.chunks.shrink_to_fit();
@@ -616,6 +995,8 @@ impl TimeSeries {
     pub fn remove_range(&mut self, start_ts: Timestamp, end_ts: Timestamp) -> TsdbResult<usize> {
         debug_assert!(start_ts <= end_ts);
 
+        self.remove_annotations(start_ts, end_ts);
+
         let mut deleted_samples = 0;
 
         fn remove_internal(
@@ -673,6 +1054,67 @@ impl TimeSeries {
         Ok(deleted_samples)
     }
 
+    /// Sets the annotation at `timestamp`, replacing any existing one. An empty `text` removes it.
+    pub fn annotate(&mut self, timestamp: Timestamp, text: String) {
+        if text.is_empty() {
+            self.annotations.remove(&timestamp);
+        } else {
+            self.annotations.insert(timestamp, text);
+        }
+    }
+
+    /// Returns the annotations with timestamps in `[start_ts, end_ts]`, in ascending order.
+    pub fn annotations_in_range(
+        &self,
+        start_ts: Timestamp,
+        end_ts: Timestamp,
+    ) -> impl DoubleEndedIterator<Item = (Timestamp, &str)> {
+        let range = if start_ts <= end_ts {
+            self.annotations.range(start_ts..=end_ts)
+        } else {
+            self.annotations.range(0..0)
+        };
+        range.map(|(ts, text)| (*ts, text.as_str()))
+    }
+
+    fn remove_annotations(&mut self, start_ts: Timestamp, end_ts: Timestamp) {
+        if self.annotations.is_empty() || start_ts > end_ts {
+            return;
+        }
+        let removed: Vec<Timestamp> = self
+            .annotations
+            .range(start_ts..=end_ts)
+            .map(|(ts, _)| *ts)
+            .collect();
+        for ts in removed {
+            self.annotations.remove(&ts);
+        }
+    }
+
+    /// Removes the annotations with timestamps before `timestamp`.
+    fn remove_annotations_before(&mut self, timestamp: Timestamp) {
+        if self
+            .annotations
+            .first_key_value()
+            .is_some_and(|(ts, _)| *ts < timestamp)
+        {
+            self.annotations = self.annotations.split_off(&timestamp);
+        }
+    }
+
+    /// Returns the number of samples `remove_range` would delete for the same range, without
+    /// modifying the series.
+    pub fn count_range(&self, start_ts: Timestamp, end_ts: Timestamp) -> usize {
+        let Some((start_index, end_index)) = self.get_chunk_index_bounds(start_ts, end_ts) else {
+            return 0;
+        };
+
+        self.chunks[start_index..=end_index]
+            .iter()
+            .map(|chunk| chunk.count_in_range(start_ts, end_ts))
+            .sum()
+    }
+
     /// Checks if the time series has at least one sample in the given time range.
     ///
     /// ## Arguments
@@ -689,8 +1131,12 @@ impl TimeSeries {
             return false;
         }
 
-        // Find the actual min timestamp accounting for retention
+        // Find the actual min timestamp accounting for retention. Samples older than this may not
+        // have been trimmed yet, but must not count.
         let min_timestamp = self.get_min_timestamp().max(start_time);
+        if min_timestamp > end_time {
+            return false;
+        }
 
         // Get chunk index bounds for the range
         let Some((start_index, end_index)) = self.get_chunk_index_bounds(min_timestamp, end_time)
@@ -702,28 +1148,37 @@ impl TimeSeries {
         let chunks = &self.chunks[start_index..=end_index];
         match (self.is_compressed(), chunks) {
             (_, []) => false,
-            (_, [chunk]) => chunk.has_samples_in_range(start_time, end_time),
+            (_, [chunk]) => chunk.has_samples_in_range(min_timestamp, end_time),
             // Uncompressed chunks, iterate linearly
             (false, many) => many
                 .iter()
-                .any(|c| c.has_samples_in_range(start_time, end_time)),
+                .any(|c| c.has_samples_in_range(min_timestamp, end_time)),
             // Compressed chunks, check each chunk in parallel
             (true, many) => many
                 .par()
-                .any(|&chunk| chunk.has_samples_in_range(start_time, end_time)),
+                .any(|&chunk| chunk.has_samples_in_range(min_timestamp, end_time)),
         }
     }
 
+    /// Adds `delta` to the last sample value and stores the result at `timestamp`.
+    ///
+    /// If `bucket_duration` is given, the timestamp (the current time if omitted) is aligned to the
+    /// start of its bucket. Increments within the last bucket update it in place, while crossing a
+    /// bucket boundary starts a new bucket with `delta` as its value.
     pub fn increment_sample_value(
         &mut self,
         timestamp: Option<Timestamp>,
         delta: f64,
+        bucket_duration: Option<Duration>,
     ) -> ValkeyResult<SampleAddResult> {
         if delta.is_nan() {
             return Err(ValkeyError::Str(
                 error_consts::CANNOT_INCREMENT_DECREMENT_NAN,
             ));
         }
+        if let Some(bucket_duration) = bucket_duration {
+            return self.increment_bucket_value(timestamp, delta, bucket_duration);
+        }
         // if we have at least one sample, increment the last one
         let (timestamp, last_ts, value) = if let Some(sample) = self.last_sample {
             if sample.value.is_nan() {
@@ -741,15 +1196,44 @@ impl TimeSeries {
         };
 
         if timestamp < last_ts {
-            return Err(ValkeyError::Str(
-                "TSDB: timestamp must be equal to or higher than the maximum existing timestamp",
-            ));
+            return Err(ValkeyError::Str(error_consts::INCREMENT_TIMESTAMP_TOO_OLD));
         }
 
         // todo: should we add a flag to skip adjust_value()?
         Ok(self.add(timestamp, value, Some(DuplicatePolicy::KeepLast)))
     }
 
+    fn increment_bucket_value(
+        &mut self,
+        timestamp: Option<Timestamp>,
+        delta: f64,
+        bucket_duration: Duration,
+    ) -> ValkeyResult<SampleAddResult> {
+        let width = bucket_duration.as_millis() as i64;
+        if width <= 0 {
+            return Err(ValkeyError::Str(error_consts::INVALID_DURATION));
+        }
+        let ts = timestamp.unwrap_or_else(current_time_millis);
+        let bucket_start = ts - ts.rem_euclid(width);
+
+        let value = match self.last_sample {
+            Some(sample) if sample.timestamp > bucket_start => {
+                return Err(ValkeyError::Str(error_consts::INCREMENT_TIMESTAMP_TOO_OLD));
+            }
+            Some(sample) if sample.timestamp == bucket_start => {
+                if sample.value.is_nan() {
+                    return Err(ValkeyError::Str(
+                        error_consts::CANNOT_INCREMENT_DECREMENT_NAN,
+                    ));
+                }
+                sample.value + delta
+            }
+            _ => delta,
+        };
+
+        Ok(self.add(bucket_start, value, Some(DuplicatePolicy::KeepLast)))
+    }
+
     pub(super) fn update_first_last_timestamps(&mut self) {
         if let Some(first_chunk) = self.chunks.first() {
             self.first_timestamp = first_chunk.first_timestamp();
@@ -772,6 +1256,48 @@ impl TimeSeries {
         size_of::<Self>() + self.get_heap_size()
     }
 
+    pub fn memory_breakdown(&self) -> SeriesMemoryUsage {
+        let chunks = self.chunks.iter().map(|c| c.memory_usage()).sum();
+        // the `MetricName` header is part of `size_of::<Self>()`, so only its heap is counted here
+        let labels = self.labels.get_heap_size();
+        let metadata = self
+            .memory_usage()
+            .saturating_sub(chunks)
+            .saturating_sub(labels);
+        SeriesMemoryUsage {
+            chunks,
+            labels,
+            metadata,
+        }
+    }
+
+    pub fn stats(&self) -> SeriesStats {
+        let duration = self.last_sample.map_or(0, |last| {
+            last.timestamp.saturating_sub(self.first_timestamp)
+        });
+        let sample_rate = if duration > 0 {
+            self.total_samples as f64 * 1000.0 / duration as f64
+        } else {
+            0.0
+        };
+        let data_size = self.data_size();
+        let (bytes_per_sample, compression_ratio) = if self.total_samples > 0 && data_size > 0 {
+            let bytes_per_sample = data_size as f64 / self.total_samples as f64;
+            (bytes_per_sample, SAMPLE_SIZE as f64 / bytes_per_sample)
+        } else {
+            (0.0, 0.0)
+        };
+        SeriesStats {
+            total_samples: self.total_samples,
+            duration,
+            sample_rate,
+            chunk_count: self.chunks.len(),
+            data_size,
+            bytes_per_sample,
+            compression_ratio,
+        }
+    }
+
     /// Returns the minimum timestamp of the time series, considering the retention period.
     pub(crate) fn get_min_timestamp(&self) -> Timestamp {
         if self.retention.is_zero() {
@@ -855,19 +1381,96 @@ impl TimeSeries {
         Some((start_idx, end_idx))
     }
 
+    /// Finalizes the chunk currently receiving appends: it is optimized and the next sample past
+    /// it starts a new chunk. Returns `false` if there was nothing to seal, i.e. the series is
+    /// empty or already sealed.
+    pub fn seal(&mut self) -> bool {
+        if self.sealed {
+            return false;
+        }
+        let Some(last) = self.chunks.last_mut().filter(|c| !c.is_empty()) else {
+            return false;
+        };
+        let _ = last.optimize();
+        self.sealed = true;
+        true
+    }
+
     pub fn optimize(&mut self) {
-        // todo: merge chunks if possible
+        self.merge_adjacent_chunks();
         self.chunks.par_mut().for_each(|chunk| {
             let _ = chunk.optimize();
         });
     }
 
+    /// Re-encodes every chunk of the series using `encoding`, and makes it the encoding used for
+    /// new chunks. Samples are decoded in order and appended to fresh chunks, so the result holds
+    /// exactly the same samples. On error the series is left untouched.
+    /// Returns `false` if the series already uses `encoding`.
+    pub fn set_encoding(&mut self, encoding: ChunkEncoding) -> TsdbResult<bool> {
+        if encoding == self.chunk_compression
+            && self.chunks.iter().all(|c| c.get_encoding() == encoding)
+        {
+            return Ok(false);
+        }
+
+        let chunk_size = self.chunk_size_bytes;
+        let new_chunk = || TimeSeriesChunk::new(encoding, chunk_size);
+        let mut chunks: Vec<TimeSeriesChunk> = Vec::with_capacity(self.chunks.len());
+        let mut current = new_chunk();
+        for sample in self.chunks.iter().flat_map(|c| c.iter()) {
+            if current.is_full() {
+                chunks.push(std::mem::replace(&mut current, new_chunk()));
+            }
+            match current.add_sample(&sample) {
+                Ok(_) => {}
+                Err(TsdbError::CapacityFull(_)) => {
+                    chunks.push(std::mem::replace(&mut current, new_chunk()));
+                    current.add_sample(&sample)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if !current.is_empty() || chunks.is_empty() {
+            chunks.push(current);
+        }
+
+        self.chunks = chunks;
+        self.chunk_compression = encoding;
+        self.auto_encoding = false;
+        Ok(true)
+    }
+
+    /// Folds under-full neighbouring chunks into each other when their combined size fits within a
+    /// single chunk. Chunks only merge with neighbours of the same encoding, so sample order and
+    /// per-chunk compression are preserved.
+    fn merge_adjacent_chunks(&mut self) {
+        let mut i = 0;
+        while i + 1 < self.chunks.len() {
+            let (left, right) = self.chunks.split_at_mut(i + 1);
+            match left[i].merge_with(&right[0]) {
+                Ok(true) => {
+                    self.chunks.remove(i + 1);
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
     #[cfg(test)]
     pub(super) fn update_state_from_chunks(&mut self) {
         self.update_first_last_timestamps();
         self.total_samples = self.chunks.iter().map(|x| x.len()).sum();
     }
 
+    /// Returns `sample` with its timestamp in the unit clients use for this series.
+    pub fn to_client_sample(&self, sample: Sample) -> Sample {
+        Sample::new(
+            self.timestamp_unit.from_millis(sample.timestamp),
+            sample.value,
+        )
+    }
+
     pub fn is_compaction(&self) -> bool {
         self.src_series.is_some()
     }
@@ -877,11 +1480,15 @@ impl TimeSeries {
         calc_metric_name_digest(&self.labels, digest);
         let retention_msecs = self.retention.as_millis() as i64;
         digest.add_long_long(retention_msecs);
+        digest.add_long_long(self.max_samples.map_or(-1, |max| max as i64));
 
         // Handle sample_duplicates
         calc_duplicate_policy_digest(&self.sample_duplicates, digest);
 
         digest.add_string_buffer(self.chunk_compression.name().as_bytes());
+        digest.add_long_long(self.auto_encoding as i64);
+        digest.add_long_long(self.split_policy.as_u8() as i64);
+        digest.add_long_long(self.timestamp_unit.as_u8() as i64);
 
         if let Some(rounding) = &self.rounding {
             calc_rounding_digest(rounding, digest);
@@ -910,6 +1517,12 @@ impl TimeSeries {
             -1 // use -1 to indicate no source series
         };
         digest.add_long_long(src_id);
+        digest.add_long_long(self.sealed as i64);
+        digest.add_long_long(self.annotations.len() as i64);
+        for (timestamp, text) in self.annotations.iter() {
+            digest.add_long_long(*timestamp);
+            digest.add_string_buffer(text.as_bytes());
+        }
         // add rules
         digest.add_long_long(self.rules.len() as i64);
         for rule in self.rules.iter() {
@@ -926,8 +1539,10 @@ impl Default for TimeSeries {
             id: 0,
             labels: Default::default(),
             retention: Default::default(),
+            max_samples: None,
             sample_duplicates: Default::default(),
             chunk_compression: Default::default(),
+            auto_encoding: false,
             chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES,
             chunks: vec![],
             total_samples: 0,
@@ -936,11 +1551,86 @@ impl Default for TimeSeries {
             last_sample: None,
             src_series: None,
             rules: vec![],
+            ooo_samples: 0,
+            chunk_splits: 0,
+            split_policy: ChunkSplitPolicy::default(),
+            timestamp_unit: TimestampUnit::default(),
+            annotations: BTreeMap::new(),
+            sealed: false,
             _db: 0,
         }
     }
 }
 
+/// Splits `chunk` as directed by `policy`, returning the upper part as a new chunk. `ts` is the
+/// timestamp of the sample whose insertion caused the split, if any.
+pub(crate) fn split_chunk(
+    chunk: &mut TimeSeriesChunk,
+    policy: ChunkSplitPolicy,
+    ts: Option<Timestamp>,
+) -> TsdbResult<TimeSeriesChunk> {
+    let insert_pos = match (policy, ts) {
+        (ChunkSplitPolicy::InsertionPoint, Some(ts)) => {
+            Some(chunk.iter().take_while(|s| s.timestamp < ts).count())
+        }
+        _ => None,
+    };
+    let index = policy.split_index(chunk.len(), insert_pos);
+    chunk.split_at(index)
+}
+
+/// Share of consecutive samples with an unchanged value above which Gorilla is preferred.
+const AUTO_ENCODING_REPEAT_RATIO: f64 = 0.5;
+/// Relative spread of values (standard deviation over mean magnitude) below which Gorilla is
+/// preferred.
+const AUTO_ENCODING_MAX_RELATIVE_SPREAD: f64 = 0.01;
+
+/// Chooses the encoding for the chunks of an `ENCODING AUTO` series from a sample of its data.
+///
+/// Gorilla stores each value as the XOR with its predecessor, which is cheapest when consecutive
+/// values share most of their bits. It is chosen when the values are monotonic (counters), when at
+/// least half of them repeat the previous value, or when they stay within 1% of their mean. Any other
+/// data, typically noisy gauges, is left to Pco. The choice depends only on the samples, so the same
+/// input always yields the same encoding.
+pub(crate) fn select_chunk_encoding(samples: impl Iterator<Item = Sample>) -> ChunkEncoding {
+    let mut count = 0usize;
+    let mut repeats = 0usize;
+    let mut non_decreasing = true;
+    let mut non_increasing = true;
+    let mut prev: Option<f64> = None;
+    // Welford's running mean and variance
+    let mut mean = 0.0;
+    let mut m2 = 0.0;
+
+    for sample in samples {
+        let value = sample.value;
+        if let Some(prev) = prev {
+            if value == prev {
+                repeats += 1;
+            }
+            non_decreasing &= value >= prev;
+            non_increasing &= value <= prev;
+        }
+        prev = Some(value);
+        count += 1;
+        let delta = value - mean;
+        mean += delta / count as f64;
+        m2 += delta * (value - mean);
+    }
+
+    if count < 2 || non_decreasing || non_increasing {
+        return ChunkEncoding::Gorilla;
+    }
+    if repeats as f64 >= AUTO_ENCODING_REPEAT_RATIO * (count - 1) as f64 {
+        return ChunkEncoding::Gorilla;
+    }
+    let std_dev = (m2 / count as f64).sqrt();
+    if std_dev <= AUTO_ENCODING_MAX_RELATIVE_SPREAD * mean.abs() {
+        return ChunkEncoding::Gorilla;
+    }
+    ChunkEncoding::Pco
+}
+
 fn binary_search_chunks_by_timestamp(chunks: &[TimeSeriesChunk], ts: Timestamp) -> (usize, bool) {
     match chunks.binary_search_by(|probe| {
         if ts < probe.first_timestamp() {
@@ -958,8 +1648,25 @@ fn binary_search_chunks_by_timestamp(chunks: &[TimeSeriesChunk], ts: Timestamp)
 
 const LINEAR_SCAN_MAX: usize = 16;
 
+/// Fast path for the chunk searches below: appends almost always target the last chunk, so check
+/// it before scanning. Returns `None` if `ts` precedes the last chunk (or it is empty), and
+/// otherwise the last chunk's index and whether `ts` falls within its samples.
+#[inline]
+fn last_chunk_position(chunks: &[TimeSeriesChunk], ts: Timestamp) -> Option<(usize, bool)> {
+    let last = chunks.last()?;
+    if last.is_empty() || ts < last.first_timestamp() {
+        return None;
+    }
+    Some((chunks.len() - 1, ts <= last.last_timestamp()))
+}
+
 /// Find the index of the first chunk in which the timestamp belongs. Assumes !chunks.is_empty()
 pub(super) fn find_start_chunk_index(arr: &[TimeSeriesChunk], ts: Timestamp) -> usize {
+    match last_chunk_position(arr, ts) {
+        Some((index, true)) => return index,
+        Some((_, false)) => return arr.len(),
+        None => {}
+    }
     match arr {
         [] => 0,
         [first, ..] if ts <= first.first_timestamp() => 0,
@@ -975,7 +1682,15 @@ pub(super) fn find_start_chunk_index(arr: &[TimeSeriesChunk], ts: Timestamp) ->
 }
 
 /// Return the index of the chunk in which the timestamp belongs. Assumes !chunks.is_empty()
-fn get_chunk_index(chunks: &[TimeSeriesChunk], timestamp: Timestamp) -> (usize, bool) {
+pub(super) fn get_chunk_index(chunks: &[TimeSeriesChunk], timestamp: Timestamp) -> (usize, bool) {
+    match last_chunk_position(chunks, timestamp) {
+        Some((index, true)) => (index, true),
+        Some((_, false)) => (chunks.len(), false),
+        None => scan_chunk_index(chunks, timestamp),
+    }
+}
+
+fn scan_chunk_index(chunks: &[TimeSeriesChunk], timestamp: Timestamp) -> (usize, bool) {
     match chunks {
         [] => (0, false),
         _ if chunks.len() <= LINEAR_SCAN_MAX => chunks
@@ -993,7 +1708,12 @@ fn get_chunk_index(chunks: &[TimeSeriesChunk], timestamp: Timestamp) -> (usize,
     }
 }
 
+/// Returns the index of the last chunk starting at or before `ts`, and whether `ts` falls within it.
+/// Timestamps past the end of the series resolve to the last chunk, where they are appended.
 pub(super) fn find_last_ge_index(chunks: &[TimeSeriesChunk], ts: Timestamp) -> (usize, bool) {
+    if let Some(position) = last_chunk_position(chunks, ts) {
+        return position;
+    }
     match chunks {
         [] => (0, false),
         _ if chunks.len() <= LINEAR_SCAN_MAX => chunks
@@ -1048,4 +1768,44 @@ mod tests {
         assert_eq!(ts.first_timestamp, 100);
         assert_eq!(ts.last_timestamp(), 100);
     }
+
+    #[test]
+    fn test_with_options_inherits_duplicate_policy_from_config() {
+        let create = |policy: Option<DuplicatePolicy>| {
+            TimeSeries::with_options(TimeSeriesOptions {
+                sample_duplicate_policy: policy.map(|policy| SampleDuplicatePolicy {
+                    policy: Some(policy),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .unwrap()
+        };
+
+        // keep the config change as short-lived as possible, since it is shared by all tests
+        let (inherited, explicit) = {
+            let mut config_policy = config::DUPLICATE_POLICY.lock().unwrap();
+            let previous = std::mem::replace(&mut *config_policy, DuplicatePolicy::Max);
+            drop(config_policy);
+            let inherited = create(None);
+            let explicit = create(Some(DuplicatePolicy::Min));
+            *config::DUPLICATE_POLICY.lock().unwrap() = previous;
+            (inherited, explicit)
+        };
+
+        assert_eq!(
+            inherited.sample_duplicates.policy,
+            Some(DuplicatePolicy::Max)
+        );
+        assert_eq!(
+            explicit.sample_duplicates.policy,
+            Some(DuplicatePolicy::Min)
+        );
+
+        // the policy is fixed at creation, later config changes do not affect the series
+        assert_eq!(
+            inherited.sample_duplicates.resolve_policy(None),
+            DuplicatePolicy::Max
+        );
+    }
 }
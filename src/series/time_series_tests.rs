@@ -1,13 +1,23 @@
 #[cfg(test)]
 mod tests {
+    use crate::aggregators::{AggregationType, BucketAlignment, BucketTimestamp, aggregate};
     use crate::common::rounding::RoundingStrategy;
     use crate::common::time::current_time_millis;
-    use crate::common::{Sample, Timestamp};
-    use crate::series::chunks::{Chunk, ChunkEncoding, GorillaChunk, TimeSeriesChunk};
+    use crate::common::{SAMPLE_SIZE, Sample, Timestamp};
+    use crate::error::TsdbError;
+    use crate::iterators::create_range_iterator;
+    use crate::labels::{Label, MetricName};
+    use crate::series::chunks::{
+        Chunk, ChunkEncoding, GorillaChunk, TimeSeriesChunk, decode_chunk,
+    };
+    use crate::series::request_types::{AggregationOptions, RangeOptions};
     use crate::series::{
-        DuplicatePolicy, SampleAddResult, TimeSeries, TimeSeriesOptions, ValueFilter,
+        ChunkSplitPolicy, DuplicatePolicy, SampleAddResult, SampleLookupMode, SeriesSampleIterator,
+        TimeSeries, TimeSeriesOptions, TimestampUnit, ValueFilter, find_last_ge_index,
+        find_start_chunk_index, get_chunk_index,
     };
     use crate::tests::generators::{DataGenerator, RandAlgo};
+    use get_size2::GetSize;
     use std::time::Duration;
 
     fn create_test_series() -> TimeSeries {
@@ -43,6 +53,28 @@ mod tests {
         chunk
     }
 
+    /// A series of `n_chunks` chunks holding `samples_per_chunk` samples each. The i-th sample
+    /// (counting from 1) has timestamp `1000 + i * 1000` and value `i`.
+    fn series_with_chunks(n_chunks: usize, samples_per_chunk: usize) -> TimeSeries {
+        let mut time_series = TimeSeries::default();
+        let mut count: usize = 0;
+        for _ in 0..n_chunks {
+            let mut chunk = create_chunk(None);
+            for _ in 0..samples_per_chunk {
+                count += 1;
+                chunk
+                    .add_sample(&Sample {
+                        timestamp: 1000 + (count * 1000) as Timestamp,
+                        value: count as f64,
+                    })
+                    .unwrap();
+            }
+            time_series.chunks.push(chunk);
+        }
+        time_series.update_state_from_chunks();
+        time_series
+    }
+
     fn assert_sample_added(result: SampleAddResult, expected_sample: Sample) {
         match result {
             SampleAddResult::Ok(sample) => assert_eq!(sample, expected_sample),
@@ -115,6 +147,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_with_multiple_rounding() {
+        let mut ts = TimeSeries::new();
+        ts.rounding = Some(RoundingStrategy::Multiple(0.25));
+
+        assert!(ts.add(100, 1.1, None).is_ok());
+        assert!(ts.add(200, -1.2, None).is_ok());
+        assert!(ts.add(300, 0.125, None).is_ok());
+
+        let values: Vec<f64> = ts.get_range(0, 400).iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![1.0, -1.25, 0.25]);
+    }
+
+    #[test]
+    fn test_add_multiple_rounding_applies_before_duplicate_detection() {
+        let mut ts = TimeSeries::new();
+        ts.rounding = Some(RoundingStrategy::Multiple(0.5));
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::KeepLast);
+        ts.sample_duplicates.max_time_delta = 10;
+        ts.sample_duplicates.max_value_delta = 0.0;
+
+        assert!(ts.add(100, 2.1, None).is_ok());
+        // 1.9 snaps to 2.0, the same as the last value, so it is ignored
+        assert_eq!(ts.add(105, 1.9, None), SampleAddResult::Ignored(100));
+        assert_eq!(ts.len(), 1);
+    }
+
+    #[test]
+    fn test_add_ignore_window() {
+        let mut ts = TimeSeries::new();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::KeepLast);
+        ts.sample_duplicates.max_time_delta = 100;
+        ts.sample_duplicates.max_value_delta = 0.5;
+
+        // the first sample is always stored
+        assert!(ts.add(1000, 10.0, None).is_ok());
+
+        // within the window and the value threshold
+        assert_eq!(ts.add(1050, 10.4, None), SampleAddResult::Ignored(1000));
+        assert_eq!(ts.add(1100, 9.5, None), SampleAddResult::Ignored(1000));
+
+        // a larger value change is stored, even within the window
+        assert!(ts.add(1120, 11.0, None).is_ok());
+
+        // the window is measured from the last stored sample, so a gap larger than it is stored
+        assert_eq!(ts.add(1200, 11.0, None), SampleAddResult::Ignored(1120));
+        assert!(ts.add(1221, 11.0, None).is_ok());
+
+        let timestamps: Vec<_> = ts.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, vec![1000, 1120, 1221]);
+        assert_eq!(ts.len(), 3);
+    }
+
+    #[test]
+    fn test_add_ignore_window_requires_keep_last() {
+        let mut ts = TimeSeries::new();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::Max);
+        ts.sample_duplicates.max_time_delta = 100;
+        ts.sample_duplicates.max_value_delta = 0.5;
+
+        assert!(ts.add(1000, 10.0, None).is_ok());
+        assert!(ts.add(1050, 10.0, None).is_ok());
+        // ON_DUPLICATE LAST enables the window for a single sample
+        assert_eq!(
+            ts.add(1060, 10.0, Some(DuplicatePolicy::KeepLast)),
+            SampleAddResult::Ignored(1050)
+        );
+        assert_eq!(ts.len(), 2);
+    }
+
     #[test]
     fn test_add_duplicate_timestamp() {
         let mut ts = TimeSeries::new();
@@ -137,6 +239,162 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_duplicate_timestamp_min_policy() {
+        let mut ts = TimeSeries::new();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::Min);
+
+        assert!(ts.add(100, 200.0, None).is_ok());
+
+        // larger value is discarded
+        assert!(ts.add(100, 300.0, None).is_ok());
+        // smaller value wins
+        assert!(ts.add(100, 150.0, None).is_ok());
+
+        assert_eq!(ts.total_samples, 1);
+        assert_eq!(
+            ts.last_sample,
+            Some(Sample {
+                timestamp: 100,
+                value: 150.0
+            })
+        );
+    }
+
+    #[test]
+    fn test_add_duplicate_timestamp_max_policy() {
+        let mut ts = TimeSeries::new();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::Max);
+
+        assert!(ts.add(100, 200.0, None).is_ok());
+        assert!(ts.add(100, 150.0, None).is_ok());
+        assert!(ts.add(100, 300.0, None).is_ok());
+
+        assert_eq!(ts.total_samples, 1);
+        assert_eq!(
+            ts.last_sample,
+            Some(Sample {
+                timestamp: 100,
+                value: 300.0
+            })
+        );
+    }
+
+    #[test]
+    fn test_add_at_last_timestamp_per_policy() {
+        use DuplicatePolicy::*;
+        // (policy, value added at the last timestamp, expected result, expected stored value).
+        // Collisions that leave the stored value unchanged return the stored sample
+        let cases = [
+            (Block, 10.0, SampleAddResult::Duplicate, 10.0),
+            (Block, 20.0, SampleAddResult::Duplicate, 10.0),
+            (
+                KeepLast,
+                10.0,
+                SampleAddResult::Ok(Sample::new(100, 10.0)),
+                10.0,
+            ),
+            (
+                KeepLast,
+                20.0,
+                SampleAddResult::Ok(Sample::new(100, 20.0)),
+                20.0,
+            ),
+            (
+                KeepFirst,
+                10.0,
+                SampleAddResult::Ok(Sample::new(100, 10.0)),
+                10.0,
+            ),
+            (
+                KeepFirst,
+                20.0,
+                SampleAddResult::Ok(Sample::new(100, 10.0)),
+                10.0,
+            ),
+            (Min, 10.0, SampleAddResult::Ok(Sample::new(100, 10.0)), 10.0),
+            (Min, 5.0, SampleAddResult::Ok(Sample::new(100, 5.0)), 5.0),
+            (Max, 10.0, SampleAddResult::Ok(Sample::new(100, 10.0)), 10.0),
+            (Max, 20.0, SampleAddResult::Ok(Sample::new(100, 20.0)), 20.0),
+            // the policy applies to the stored value, while the result holds the added sample
+            (Sum, 10.0, SampleAddResult::Ok(Sample::new(100, 10.0)), 20.0),
+            (Sum, 5.0, SampleAddResult::Ok(Sample::new(100, 5.0)), 15.0),
+        ];
+
+        for (policy, value, expected, stored) in cases {
+            let mut ts = TimeSeries::new();
+            ts.sample_duplicates.policy = Some(policy);
+            assert!(ts.add(50, 1.0, None).is_ok());
+            assert!(ts.add(100, 10.0, None).is_ok());
+
+            let result = ts.add(100, value, None);
+            assert_eq!(result, expected, "policy {policy}, value {value}");
+            assert_eq!(ts.total_samples, 2, "policy {policy}, value {value}");
+            assert_eq!(
+                ts.last_sample,
+                Some(Sample::new(100, stored)),
+                "policy {policy}, value {value}"
+            );
+            assert_eq!(ts.get_range(0, 200).last().unwrap().value, stored);
+        }
+    }
+
+    #[test]
+    fn test_add_repeated_last_sample_is_idempotent_within_ignore_window() {
+        let mut ts = TimeSeries::new();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::KeepLast);
+        ts.sample_duplicates.max_time_delta = 100;
+        ts.sample_duplicates.max_value_delta = 0.5;
+
+        assert!(ts.add(1000, 10.0, None).is_ok());
+        // an exact repeat of the last sample is accepted rather than ignored
+        assert_eq!(
+            ts.add(1000, 10.0, None),
+            SampleAddResult::Ok(Sample::new(1000, 10.0))
+        );
+        // a different value within the value threshold is still ignored
+        assert_eq!(ts.add(1000, 10.2, None), SampleAddResult::Ignored(1000));
+        // BLOCK set with ON_DUPLICATE rejects any write to the last timestamp
+        assert_eq!(
+            ts.add(1000, 10.0, Some(DuplicatePolicy::Block)),
+            SampleAddResult::Duplicate
+        );
+        assert_eq!(ts.last_sample, Some(Sample::new(1000, 10.0)));
+        assert_eq!(ts.len(), 1);
+    }
+
+    #[test]
+    fn test_add_duplicate_min_max_override_on_older_sample() {
+        let mut ts = TimeSeries::new();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::Block);
+
+        assert!(ts.add(100, 50.0, None).is_ok());
+        assert!(ts.add(200, 60.0, None).is_ok());
+
+        // upsert into the past with per-call overrides
+        assert!(ts.add(100, 10.0, Some(DuplicatePolicy::Min)).is_ok());
+        assert!(ts.add(100, 5.0, Some(DuplicatePolicy::Max)).is_ok());
+        assert!(ts.add(200, 70.0, Some(DuplicatePolicy::Max)).is_ok());
+
+        let samples = ts.get_range(0, 300);
+        assert_eq!(
+            samples,
+            vec![
+                Sample {
+                    timestamp: 100,
+                    value: 10.0
+                },
+                Sample {
+                    timestamp: 200,
+                    value: 70.0
+                },
+            ]
+        );
+
+        // without an override the series policy still blocks
+        assert!(!ts.add(100, 1.0, None).is_ok());
+    }
+
     #[test]
     fn test_add_duplicate_with_override_policy() {
         let mut ts = TimeSeries::new();
@@ -454,6 +712,19 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_out_of_order_add_bumps_counter() {
+        let mut series = create_test_series();
+        for (ts, value) in [(300, 3.0), (100, 1.0), (500, 5.0), (200, 2.0), (400, 4.0)] {
+            series.add(ts, value, None);
+        }
+        assert_eq!(series.ooo_samples, 3);
+
+        // an in-order add leaves the counter alone
+        series.add(600, 6.0, None);
+        assert_eq!(series.ooo_samples, 3);
+    }
+
     #[test]
     fn test_upsert_with_duplicate_policy() {
         let mut series = create_test_series();
@@ -721,6 +992,34 @@ mod tests {
         assert_eq!(ts.len(), initial_len + samples_to_add.len());
     }
 
+    #[test]
+    fn test_merge_samples_pco_splits_without_losing_samples() {
+        let mut ts = TimeSeries::with_options(TimeSeriesOptions {
+            chunk_compression: ChunkEncoding::Pco,
+            chunk_size: Some(1024),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let data = DataGenerator::builder()
+            .start(1000)
+            .interval(Duration::from_millis(1000))
+            .algorithm(RandAlgo::StdNorm)
+            .samples(2000)
+            .build()
+            .generate();
+
+        for batch in data.chunks(200) {
+            let results = ts.merge_samples(batch, None).unwrap();
+            assert!(results.iter().all(|r| r.is_ok()));
+        }
+
+        assert!(ts.chunks.len() > 1, "expected chunks to be split");
+        assert!(ts.chunks.iter().all(|c| !c.should_split()));
+        assert_eq!(ts.len(), data.len());
+        assert_eq!(ts.get_range(0, i64::MAX), data);
+    }
+
     #[test]
     fn test_merge_samples_older_than_retention() {
         let mut ts = TimeSeries::with_options(TimeSeriesOptions {
@@ -765,6 +1064,57 @@ mod tests {
         assert_eq!(ts.len(), initial_len); // No change
     }
 
+    #[test]
+    fn test_merge_samples_chunked_matches_one_shot() {
+        let mut base = TimeSeries::with_options(TimeSeriesOptions {
+            retention: Some(Duration::from_secs(200)),
+            chunk_size: Some(1024),
+            ..Default::default()
+        })
+        .unwrap();
+        // existing samples every 7ms, so that part of the batch lands on them
+        for i in 0..20_000 {
+            base.add(1_000_000 + i * 7, i as f64, None);
+        }
+
+        // a sorted batch with runs of duplicate timestamps, some of them older than the retention
+        let mut samples: Vec<Sample> = (0..60_000)
+            .map(|i| Sample::new(1_000_000 - 100_000 + i * 3 - i % 2, (i % 97) as f64))
+            .collect();
+        samples.extend((0..50).map(|i| Sample::new(1_200_000, i as f64)));
+        samples.sort_by_key(|s| s.timestamp);
+
+        for policy in [DuplicatePolicy::Sum, DuplicatePolicy::Block] {
+            let mut one_shot = base.clone();
+            let expected = one_shot.merge_samples(&samples, Some(policy)).unwrap();
+            assert!(
+                expected
+                    .iter()
+                    .any(|r| matches!(r, SampleAddResult::TooOld))
+            );
+
+            for window_size in [1, 1000, 10_000, samples.len()] {
+                let mut chunked = base.clone();
+                let mut results = Vec::with_capacity(samples.len());
+                chunked
+                    .merge_samples_chunked(&samples, Some(policy), window_size, |window, res| {
+                        assert_eq!(window.len(), res.len());
+                        results.extend_from_slice(res);
+                    })
+                    .unwrap();
+
+                assert_eq!(results, expected, "{policy:?}, window {window_size}");
+                assert_eq!(chunked.len(), one_shot.len());
+                assert_eq!(chunked.first_timestamp, one_shot.first_timestamp);
+                assert_eq!(chunked.last_sample, one_shot.last_sample);
+                assert_eq!(
+                    chunked.get_range(0, i64::MAX),
+                    one_shot.get_range(0, i64::MAX)
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_merge_samples_with_rounding() {
         let mut ts = TimeSeries::with_options(TimeSeriesOptions {
@@ -792,6 +1142,73 @@ mod tests {
         assert_ok(results[1], SampleAddResult::Ok(Sample::new(200, 5.68)));
     }
 
+    #[test]
+    fn test_add_sum_policy_many_collisions() {
+        let mut ts = TimeSeries::new();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::Sum);
+
+        for i in 1..=100 {
+            assert!(ts.add(100, i as f64, None).is_ok());
+        }
+
+        assert_eq!(ts.len(), 1);
+        assert_eq!(ts.get_sample(100).unwrap().unwrap().value, 5050.0);
+    }
+
+    #[test]
+    fn test_merge_samples_sum_policy_across_chunks() {
+        let mut ts = TimeSeries::new();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::Sum);
+        ts.chunks = vec![
+            create_chunk_with_timestamps(100, 110),
+            create_chunk_with_timestamps(200, 210),
+        ];
+        ts.update_state_from_chunks();
+        let initial_len = ts.len();
+
+        let mut samples = Vec::new();
+        for i in 1..=20 {
+            samples.push(Sample::new(105, i as f64));
+            samples.push(Sample::new(205, i as f64));
+        }
+        // a new timestamp that also collides within the batch
+        samples.push(Sample::new(150, 1.5));
+        samples.push(Sample::new(150, 2.5));
+
+        let results = ts.merge_samples(&samples, None).unwrap();
+        assert_eq!(results.len(), samples.len());
+        assert!(results.iter().all(|r| r.is_ok()));
+
+        // 1.0 + ts for the existing value, plus 1 + 2 + ... + 20
+        assert_eq!(ts.get_sample(105).unwrap().unwrap().value, 106.0 + 210.0);
+        assert_eq!(ts.get_sample(205).unwrap().unwrap().value, 206.0 + 210.0);
+        assert_eq!(ts.get_sample(150).unwrap().unwrap().value, 4.0);
+        assert_eq!(ts.len(), initial_len + 1);
+    }
+
+    #[test]
+    fn test_merge_samples_sum_policy_with_rounding() {
+        let mut ts = TimeSeries::with_options(TimeSeriesOptions {
+            rounding: Some(RoundingStrategy::DecimalDigits(1)),
+            ..Default::default()
+        })
+        .unwrap();
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::Sum);
+
+        // each value is rounded to 0.1 before it is accumulated
+        let samples: Vec<Sample> = (0..10).map(|_| Sample::new(100, 0.123)).collect();
+        let results = ts.merge_samples(&samples, None).unwrap();
+        assert!(results.iter().all(|r| r.is_ok()));
+
+        for _ in 0..10 {
+            assert!(ts.add(100, 0.123, None).is_ok());
+        }
+
+        assert_eq!(ts.len(), 1);
+        let value = ts.get_sample(100).unwrap().unwrap().value;
+        assert!((value - 2.0).abs() < 1e-9, "unexpected sum {value}");
+    }
+
     #[test]
     fn test_samples_by_timestamps_exact_match_one_chunk() {
         // Set up a TimeSeries instance with a single chunk containing specific timestamps
@@ -1039,6 +1456,131 @@ mod tests {
         assert_eq!(time_series.last_sample, Some(sample3));
     }
 
+    #[test]
+    fn test_has_samples_in_range_respects_retention() {
+        let mut ts = create_test_series();
+        ts.retention = Duration::from_millis(1000);
+        ts.add(100, 1.0, None);
+        ts.add(200, 2.0, None);
+        ts.add(5000, 3.0, None);
+
+        // 100 and 200 have expired, even though they have not been trimmed yet
+        assert_eq!(ts.total_samples, 3);
+        assert!(!ts.has_samples_in_range(0, 1000));
+        assert!(!ts.has_samples_in_range(0, 3999));
+        assert!(ts.has_samples_in_range(0, 5000));
+        assert!(ts.has_samples_in_range(4500, 6000));
+    }
+
+    #[test]
+    fn test_max_samples_drops_oldest_on_add() {
+        let mut ts = create_test_series();
+        ts.max_samples = Some(500);
+
+        // enough samples to span several chunks
+        for i in 0..2000 {
+            assert!(ts.add(1000 + i, i as f64, None).is_ok());
+        }
+
+        assert_eq!(ts.total_samples, 500);
+        assert_eq!(ts.chunks.iter().map(|c| c.len()).sum::<usize>(), 500);
+        assert_eq!(ts.first_timestamp, 2500);
+        assert_eq!(ts.last_sample.map(|s| s.timestamp), Some(2999));
+        let first = ts.iter().next().unwrap();
+        assert_eq!(first.timestamp, 2500);
+    }
+
+    #[test]
+    fn test_trim_applies_stricter_of_retention_and_max_samples() {
+        let mut ts = create_test_series();
+        for i in 0..100 {
+            ts.add(i * 10, i as f64, None);
+        }
+
+        // count limit is stricter: keeps the last 20 samples
+        ts.retention = Duration::from_millis(500);
+        ts.max_samples = Some(20);
+        ts.trim().unwrap();
+        assert_eq!(ts.total_samples, 20);
+        assert_eq!(ts.first_timestamp, 800);
+
+        // time limit is stricter: removes more than the count limit would
+        ts.max_samples = Some(50);
+        ts.retention = Duration::from_millis(100);
+        ts.trim().unwrap();
+        assert!(ts.total_samples < 20);
+        assert!(ts.first_timestamp >= 890);
+    }
+
+    fn annotation_timestamps(ts: &TimeSeries) -> Vec<Timestamp> {
+        ts.annotations.keys().copied().collect()
+    }
+
+    #[test]
+    fn test_annotations_in_range() {
+        let mut ts = create_test_series();
+        ts.annotate(100, "deploy v1".to_string());
+        ts.annotate(300, "incident".to_string());
+        ts.annotate(500, "deploy v2".to_string());
+        // replaces the existing note
+        ts.annotate(300, "incident resolved".to_string());
+
+        let notes: Vec<_> = ts.annotations_in_range(100, 300).collect();
+        assert_eq!(notes, vec![(100, "deploy v1"), (300, "incident resolved")]);
+        assert_eq!(ts.annotations_in_range(301, 499).count(), 0);
+        assert_eq!(ts.annotations_in_range(500, 100).count(), 0);
+
+        // an empty note removes it
+        ts.annotate(100, String::new());
+        assert_eq!(annotation_timestamps(&ts), vec![300, 500]);
+    }
+
+    #[test]
+    fn test_remove_range_removes_annotations() {
+        let mut ts = create_test_series();
+        for i in 0..10 {
+            ts.add(i * 100, i as f64, None);
+        }
+        ts.annotate(200, "a".to_string());
+        ts.annotate(450, "b".to_string());
+        ts.annotate(800, "c".to_string());
+
+        ts.remove_range(200, 500).unwrap();
+        assert_eq!(annotation_timestamps(&ts), vec![800]);
+
+        // annotations are removed even if there are no samples in the range
+        ts.annotate(5000, "d".to_string());
+        assert_eq!(ts.remove_range(4000, 6000).unwrap(), 0);
+        assert_eq!(annotation_timestamps(&ts), vec![800]);
+    }
+
+    #[test]
+    fn test_trim_removes_expired_annotations() {
+        let mut ts = create_test_series();
+        for i in 0..100 {
+            ts.add(i * 10, i as f64, None);
+        }
+        ts.annotate(-50, "before the first sample".to_string());
+        ts.annotate(100, "a".to_string());
+        ts.annotate(850, "b".to_string());
+        ts.annotate(990, "c".to_string());
+
+        // without a retention limit, nothing expires
+        ts.trim().unwrap();
+        assert_eq!(annotation_timestamps(&ts), vec![-50, 100, 850, 990]);
+
+        ts.retention = Duration::from_millis(500);
+        ts.trim().unwrap();
+        assert_eq!(annotation_timestamps(&ts), vec![850, 990]);
+
+        // the count limit drops the annotations of the removed samples
+        ts.retention = Duration::ZERO;
+        ts.max_samples = Some(5);
+        ts.trim().unwrap();
+        assert_eq!(ts.first_timestamp, 950);
+        assert_eq!(annotation_timestamps(&ts), vec![990]);
+    }
+
     #[test]
     fn test_trim_partial_chunks() {
         // Set up a TimeSeries with chunks such that some are before the min_timestamp
@@ -1320,6 +1862,109 @@ mod tests {
         assert!(time_series.chunks.is_empty());
     }
 
+    #[test]
+    fn test_count_range_matches_remove_range() {
+        for encoding in [ChunkEncoding::Uncompressed, ChunkEncoding::Gorilla] {
+            let mut template = TimeSeries::with_options(TimeSeriesOptions {
+                chunk_compression: encoding,
+                chunk_size: Some(128),
+                ..Default::default()
+            })
+            .unwrap();
+            for i in 0..500 {
+                template.add(i * 10, i as f64, None);
+            }
+            assert!(template.chunks.len() > 2);
+
+            let first_chunk_end = template.chunks[0].last_timestamp();
+            let ranges = [
+                (0, 4990),                                   // everything
+                (15, 35),                                    // inside a single chunk
+                (first_chunk_end - 5, first_chunk_end + 25), // across a chunk boundary
+                (first_chunk_end, first_chunk_end),          // a single chunk edge sample
+                (123, 3456),                                 // partial chunks at both ends
+                (5000, 6000),                                // past the last sample
+                (-100, -1),                                  // before the first sample
+            ];
+
+            for (start, end) in ranges {
+                let mut series = template.clone();
+                let expected = series.count_range(start, end);
+                let removed = series.remove_range(start, end).unwrap();
+                assert_eq!(
+                    expected, removed,
+                    "count mismatch for range {start}..={end} with {encoding:?}"
+                );
+                // counting never modifies the series
+                assert_eq!(template.total_samples, 500);
+            }
+        }
+    }
+
+    #[test]
+    fn test_countall_aggregation_matches_decoded_count() {
+        // irregular spacing with a few NaNs, spread over many small chunks
+        let mut samples = Vec::with_capacity(20_000);
+        let mut ts = 1_000;
+        for i in 0..20_000 {
+            ts += 1 + (i * 7919) % 37;
+            let value = if i % 101 == 0 { f64::NAN } else { i as f64 };
+            samples.push(Sample::new(ts, value));
+        }
+        let last_ts = ts;
+
+        for encoding in [
+            ChunkEncoding::Uncompressed,
+            ChunkEncoding::Gorilla,
+            ChunkEncoding::Pco,
+        ] {
+            let mut series = TimeSeries::with_options(TimeSeriesOptions {
+                chunk_compression: encoding,
+                chunk_size: Some(1024),
+                ..Default::default()
+            })
+            .unwrap();
+            for sample in samples.iter() {
+                series.add(sample.timestamp, sample.value, None);
+            }
+            assert!(series.chunks.len() > 10);
+
+            // buckets much smaller than, close to, and much larger than a chunk's span
+            for bucket_duration in [50, 1_000, 7_777, 100_000] {
+                for (start, end) in [(0, last_ts), (1_234, last_ts - 4_321)] {
+                    for report_empty in [false, true] {
+                        let aggregation = AggregationOptions {
+                            aggregation: AggregationType::CountAll.into(),
+                            bucket_duration,
+                            timestamp_output: BucketTimestamp::Start,
+                            alignment: BucketAlignment::Start,
+                            report_empty,
+                            fill: None,
+                            with_counts: false,
+                            dedup: None,
+                        };
+                        let mut options = RangeOptions::with_range(start, end).unwrap();
+                        options.aggregation = Some(aggregation);
+
+                        let fast: Vec<Sample> =
+                            create_range_iterator(&series, &options, &None, None, false).collect();
+
+                        let aligned = aggregation.alignment.get_aligned_timestamp(start, end);
+                        let decoded = SeriesSampleIterator::new(&series, start, end, false);
+                        let slow = aggregate(&aggregation, aligned, decoded);
+
+                        assert_eq!(
+                            fast, slow,
+                            "{encoding}: bucket {bucket_duration}, range {start}..={end}"
+                        );
+                        let total: f64 = fast.iter().map(|sample| sample.value).sum();
+                        assert_eq!(total as usize, series.count_range(start, end));
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_remove_range_updates_first_last_timestamps() {
         let mut time_series = TimeSeries::default();
@@ -1499,25 +2144,242 @@ mod tests {
     }
 
     #[test]
-    fn test_get_range_filtered_empty_no_filters_no_samples() {
-        let ts = TimeSeries::new();
-        let start_timestamp = 100;
-        let end_timestamp = 200;
+    fn test_get_range_rev_across_multiple_chunks() {
+        let time_series = series_with_chunks(4, 5);
 
-        let result = ts.get_range_filtered(start_timestamp, end_timestamp, None, None);
-        assert!(result.is_empty());
+        let mut expected = time_series.get_range(2000, 14000);
+        expected.reverse();
+
+        let result = time_series.get_range_rev(2000, 14000);
+        assert_eq!(result.len(), 13);
+        assert_eq!(result.first().unwrap().timestamp, 14000);
+        assert_eq!(result.last().unwrap().timestamp, 2000);
+        assert_eq!(result, expected);
+
+        let iterated: Vec<Sample> = time_series.range_iter_rev(2000, 14000).collect();
+        assert_eq!(iterated, expected);
+
+        assert!(time_series.get_range_rev(50_000, 60_000).is_empty());
     }
 
     #[test]
-    fn test_get_range_filtered_with_timestamp_filter() {
-        let mut ts = TimeSeries::new();
-        let data = DataGenerator::builder()
-            .start(1000)
-            .interval(Duration::from_millis(1000))
-            .algorithm(RandAlgo::Deriv)
-            .samples(10)
-            .build()
-            .generate();
+    fn test_multi_range_iter_disjoint_ranges() {
+        let time_series = series_with_chunks(4, 5);
+
+        let ranges = [
+            (2000, 3000),
+            (6500, 8000),
+            (20_000, 30_000),
+            (50_000, 60_000),
+        ];
+
+        let forward: Vec<Timestamp> = time_series
+            .multi_range_iter(&ranges, false)
+            .map(|s| s.timestamp)
+            .collect();
+        assert_eq!(forward, vec![2000, 3000, 7000, 8000, 20_000, 21_000]);
+
+        let reverse: Vec<Timestamp> = time_series
+            .multi_range_iter(&ranges, true)
+            .map(|s| s.timestamp)
+            .collect();
+        assert_eq!(reverse, vec![21_000, 20_000, 8000, 7000, 3000, 2000]);
+    }
+
+    #[test]
+    fn test_sample_at_modes() {
+        let time_series = series_with_chunks(4, 5);
+
+        // 6500 lies between the first and second chunks
+        let at = |ts, mode| time_series.sample_at(ts, mode);
+        assert_eq!(
+            at(6500, SampleLookupMode::Previous),
+            Some(Sample::new(6000, 5.0))
+        );
+        assert_eq!(
+            at(6500, SampleLookupMode::Next),
+            Some(Sample::new(7000, 6.0))
+        );
+        assert_eq!(
+            at(6500, SampleLookupMode::Nearest),
+            Some(Sample::new(6000, 5.0))
+        );
+        assert_eq!(
+            at(6600, SampleLookupMode::Nearest),
+            Some(Sample::new(7000, 6.0))
+        );
+        assert_eq!(
+            at(6500, SampleLookupMode::Linear),
+            Some(Sample::new(6500, 5.5))
+        );
+        assert_eq!(
+            at(8250, SampleLookupMode::Linear),
+            Some(Sample::new(8250, 7.25))
+        );
+
+        // exact matches
+        for mode in [
+            SampleLookupMode::Previous,
+            SampleLookupMode::Next,
+            SampleLookupMode::Nearest,
+            SampleLookupMode::Linear,
+        ] {
+            assert_eq!(at(7000, mode), Some(Sample::new(7000, 6.0)));
+        }
+
+        // before the first sample
+        assert_eq!(at(1000, SampleLookupMode::Previous), None);
+        assert_eq!(
+            at(1000, SampleLookupMode::Next),
+            Some(Sample::new(2000, 1.0))
+        );
+        assert_eq!(
+            at(1000, SampleLookupMode::Nearest),
+            Some(Sample::new(2000, 1.0))
+        );
+        assert_eq!(at(1000, SampleLookupMode::Linear), None);
+
+        // after the last sample
+        assert_eq!(
+            at(25_000, SampleLookupMode::Previous),
+            Some(Sample::new(21_000, 20.0))
+        );
+        assert_eq!(at(25_000, SampleLookupMode::Next), None);
+        assert_eq!(
+            at(25_000, SampleLookupMode::Nearest),
+            Some(Sample::new(21_000, 20.0))
+        );
+        assert_eq!(at(25_000, SampleLookupMode::Linear), None);
+
+        let empty = TimeSeries::default();
+        assert_eq!(empty.sample_at(1000, SampleLookupMode::Nearest), None);
+    }
+
+    #[test]
+    fn test_chunk_index_last_chunk_fast_path() {
+        // 4 chunks are searched linearly, 32 with a binary search
+        for n_chunks in [4, 32] {
+            let ts = series_with_chunks(n_chunks, 5);
+            let chunks = &ts.chunks;
+            let last_index = chunks.len() - 1;
+            let last_ts = ts.last_timestamp();
+
+            for timestamp in (0..last_ts + 2000).step_by(250) {
+                let index = get_chunk_index(chunks, timestamp);
+                if let Some(i) = chunks
+                    .iter()
+                    .position(|c| c.is_timestamp_in_range(timestamp))
+                {
+                    assert_eq!(index, (i, true), "ts {timestamp}");
+                } else if timestamp > last_ts {
+                    assert_eq!(index, (chunks.len(), false), "ts {timestamp}");
+                }
+            }
+
+            let last = &chunks[last_index];
+            for timestamp in [
+                last.first_timestamp(),
+                last.first_timestamp() + 500,
+                last_ts,
+            ] {
+                assert_eq!(get_chunk_index(chunks, timestamp), (last_index, true));
+                assert_eq!(find_last_ge_index(chunks, timestamp), (last_index, true));
+            }
+
+            // earlier chunks are still found by the scan
+            let first_last_ts = chunks[0].last_timestamp();
+            assert_eq!(get_chunk_index(chunks, first_last_ts), (0, true));
+            assert_eq!(find_last_ge_index(chunks, first_last_ts), (0, true));
+            assert_eq!(ts.get_range(0, i64::MAX).len(), ts.total_samples);
+        }
+    }
+
+    #[test]
+    fn test_chunk_lookups_past_the_end() {
+        for n_chunks in [4, 32] {
+            let ts = series_with_chunks(n_chunks, 5);
+            let chunks = &ts.chunks;
+            let last_index = chunks.len() - 1;
+            let last_ts = ts.last_timestamp();
+
+            assert_eq!(find_last_ge_index(chunks, last_ts + 1), (last_index, false));
+            assert_eq!(find_start_chunk_index(chunks, last_ts + 1), chunks.len());
+            assert_eq!(
+                find_start_chunk_index(chunks, chunks[last_index].first_timestamp()),
+                last_index
+            );
+
+            assert_eq!(ts.get_chunk_index_bounds(last_ts + 1, last_ts + 100), None);
+            assert_eq!(
+                ts.get_chunk_index_bounds(last_ts, last_ts + 100),
+                Some((last_index, last_index))
+            );
+            assert_eq!(
+                ts.get_chunk_index_bounds(0, last_ts + 100),
+                Some((0, last_index))
+            );
+            assert_eq!(ts.count_range(0, last_ts + 100), ts.total_samples);
+        }
+    }
+
+    #[test]
+    fn test_remove_range_past_the_end() {
+        for n_chunks in [4, 32] {
+            let mut ts = series_with_chunks(n_chunks, 5);
+            let last_ts = ts.last_timestamp();
+
+            assert_eq!(ts.remove_range(0, last_ts + 1000).unwrap(), n_chunks * 5);
+            assert!(ts.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_merge_samples_past_the_end() {
+        for n_chunks in [4, 32] {
+            let mut ts = series_with_chunks(n_chunks, 5);
+            let n_samples = ts.total_samples;
+            let last_ts = ts.last_timestamp();
+
+            // an out-of-order sample along with samples past the end of the series
+            let samples = vec![
+                Sample::new(1500, 0.5),
+                Sample::new(last_ts + 1000, 1.0),
+                Sample::new(last_ts + 2000, 2.0),
+            ];
+            let results = ts.merge_samples(&samples, None).unwrap();
+            assert!(results.iter().all(|r| r.is_ok()));
+
+            // the new samples are appended to the last chunk
+            assert_eq!(ts.chunks.len(), n_chunks);
+            assert_eq!(ts.chunks[n_chunks - 1].last_timestamp(), last_ts + 2000);
+            assert_eq!(ts.total_samples, n_samples + 3);
+
+            let range = ts.get_range(0, i64::MAX);
+            assert_eq!(range.len(), ts.total_samples);
+            assert!(range.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        }
+    }
+
+    #[test]
+    fn test_get_range_filtered_empty_no_filters_no_samples() {
+        let ts = TimeSeries::new();
+        let start_timestamp = 100;
+        let end_timestamp = 200;
+
+        let result = ts.get_range_filtered(start_timestamp, end_timestamp, None, None);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_get_range_filtered_with_timestamp_filter() {
+        let mut ts = TimeSeries::new();
+        let data = DataGenerator::builder()
+            .start(1000)
+            .interval(Duration::from_millis(1000))
+            .algorithm(RandAlgo::Deriv)
+            .samples(10)
+            .build()
+            .generate();
 
         for sample in data.iter() {
             assert!(ts.add(sample.timestamp, sample.value, None).is_ok());
@@ -1721,7 +2583,7 @@ mod tests {
         let mut ts = TimeSeries::new();
         ts.add(100, 200.0, None);
 
-        let result = ts.increment_sample_value(Some(100), 50.0);
+        let result = ts.increment_sample_value(Some(100), 50.0, None);
         assert!(result.is_ok());
         let sample = ts.get_sample(100).unwrap().unwrap();
         assert_eq!(sample.value, 250.0);
@@ -1731,7 +2593,7 @@ mod tests {
     fn test_increment_sample_value_with_new_sample() {
         let mut ts = TimeSeries::new();
 
-        let result = ts.increment_sample_value(Some(100), 50.0);
+        let result = ts.increment_sample_value(Some(100), 50.0, None);
         assert!(result.is_ok());
         let sample = ts.get_sample(100).unwrap().unwrap();
         assert_eq!(sample.value, 50.0);
@@ -1742,7 +2604,7 @@ mod tests {
         let mut ts = TimeSeries::new();
         ts.add(100, 200.0, None);
 
-        let result = ts.increment_sample_value(None, 50.0);
+        let result = ts.increment_sample_value(None, 50.0, None);
         assert!(result.is_ok());
         let sample = ts.get_sample(100).unwrap().unwrap();
         assert_eq!(sample.value, 250.0);
@@ -1753,7 +2615,7 @@ mod tests {
         let mut ts = TimeSeries::new();
         ts.add(100, 200.0, None);
 
-        let result = ts.increment_sample_value(Some(50), 50.0);
+        let result = ts.increment_sample_value(Some(50), 50.0, None);
         assert!(result.is_err());
     }
 
@@ -1762,7 +2624,7 @@ mod tests {
         let mut ts = TimeSeries::new();
         ts.add(100, 200.0, None);
 
-        let result = ts.increment_sample_value(Some(100), f64::NAN);
+        let result = ts.increment_sample_value(Some(100), f64::NAN, None);
         assert!(result.is_err());
     }
 
@@ -1770,10 +2632,721 @@ mod tests {
     fn test_increment_sample_value_on_nan_raises_error() {
         let mut ts = TimeSeries::new();
 
-        let result = ts.increment_sample_value(Some(100), f64::NAN);
+        let result = ts.increment_sample_value(Some(100), f64::NAN, None);
         assert!(result.is_err());
 
-        let result = ts.increment_sample_value(Some(100), 50.0);
+        let result = ts.increment_sample_value(Some(100), 50.0, None);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_increment_sample_value_with_bucket_duration() {
+        let mut ts = TimeSeries::new();
+        let bucket = Some(Duration::from_millis(1000));
+
+        ts.increment_sample_value(Some(1200), 1.0, bucket).unwrap();
+        ts.increment_sample_value(Some(1999), 2.0, bucket).unwrap();
+        assert_eq!(ts.total_samples, 1);
+        assert_eq!(ts.get_sample(1000).unwrap().unwrap().value, 3.0);
+
+        // crossing a bucket boundary starts a new bucket
+        ts.increment_sample_value(Some(2000), 5.0, bucket).unwrap();
+        assert_eq!(ts.total_samples, 2);
+        assert_eq!(ts.get_sample(1000).unwrap().unwrap().value, 3.0);
+        assert_eq!(ts.get_sample(2000).unwrap().unwrap().value, 5.0);
+
+        // skipping buckets does not back-fill them
+        ts.increment_sample_value(Some(4500), 1.0, bucket).unwrap();
+        assert_eq!(ts.total_samples, 3);
+        assert_eq!(ts.get_sample(4000).unwrap().unwrap().value, 1.0);
+
+        // earlier buckets are rejected
+        let result = ts.increment_sample_value(Some(3999), 1.0, bucket);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_optimize_merges_underfull_chunks() {
+        let mut ts = create_test_series();
+        ts.chunks = (0..8)
+            .map(|i| create_chunk_with_timestamps(i * 10, i * 10 + 4))
+            .collect();
+        ts.update_state_from_chunks();
+        let expected: Vec<Sample> = ts.iter().collect();
+
+        ts.optimize();
+
+        assert!(ts.chunks.len() < 8);
+        assert_eq!(ts.total_samples, expected.len());
+        assert_eq!(ts.iter().collect::<Vec<_>>(), expected);
+        for pair in ts.chunks.windows(2) {
+            assert!(pair[0].last_timestamp() < pair[1].first_timestamp());
+        }
+    }
+
+    #[test]
+    fn test_optimize_does_not_merge_mismatched_encodings() {
+        let mut ts = create_test_series();
+        let mut uncompressed = TimeSeriesChunk::new(ChunkEncoding::Uncompressed, 1024);
+        for ts in 10..15 {
+            uncompressed
+                .add_sample(&Sample {
+                    timestamp: ts,
+                    value: ts as f64,
+                })
+                .unwrap();
+        }
+        ts.chunks = vec![create_chunk_with_timestamps(0, 4), uncompressed];
+        ts.update_state_from_chunks();
+
+        ts.optimize();
+
+        assert_eq!(ts.chunks.len(), 2);
+        assert_eq!(ts.chunks[0].get_encoding(), ChunkEncoding::Gorilla);
+        assert_eq!(ts.chunks[1].get_encoding(), ChunkEncoding::Uncompressed);
+        assert_eq!(ts.total_samples, 10);
+    }
+
+    #[test]
+    fn test_optimize_split_and_sealed_series() {
+        let mut ts = TimeSeries::with_options(TimeSeriesOptions {
+            chunk_compression: ChunkEncoding::Uncompressed,
+            chunk_size: Some(10 * size_of::<Sample>()),
+            ..Default::default()
+        })
+        .unwrap();
+        for i in 0..30 {
+            assert!(ts.add(i * 10, i as f64, None).is_ok());
+        }
+        // out of order samples split the full chunks they land in
+        for t in (5..300).step_by(40) {
+            assert!(ts.add(t, 0.5, None).is_ok());
+        }
+        assert!(ts.chunk_splits > 0);
+        let expected: Vec<Sample> = ts.iter().collect();
+
+        assert!(ts.seal());
+        ts.optimize();
+
+        assert_eq!(ts.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(ts.total_samples, expected.len());
+        assert_eq!(ts.last_sample, expected.last().copied());
+        let non_empty: Vec<_> = ts.chunks.iter().filter(|c| !c.is_empty()).collect();
+        for pair in non_empty.windows(2) {
+            assert!(pair[0].last_timestamp() < pair[1].first_timestamp());
+        }
+
+        // the seal survives the optimization: the next sample starts a new chunk
+        assert!(ts.add(1000, 1.0, None).is_ok());
+        assert_eq!(ts.chunks.last().unwrap().len(), 1);
+        assert_eq!(ts.last_sample, Some(Sample::new(1000, 1.0)));
+    }
+
+    #[test]
+    fn test_seal_optimizes_last_chunk_and_starts_a_new_one() {
+        let mut ts = create_test_series();
+        assert!(!ts.seal());
+        assert!(ts.chunks.is_empty());
+
+        ts.chunk_compression = ChunkEncoding::Uncompressed;
+        for i in 0..5 {
+            assert!(ts.add(1000 + i * 10, i as f64, None).is_ok());
+        }
+        let TimeSeriesChunk::Uncompressed(chunk) = &ts.chunks[0] else {
+            panic!("expected an uncompressed chunk");
+        };
+        assert!(chunk.samples.capacity() > chunk.samples.len());
+
+        assert!(ts.seal());
+        assert!(ts.sealed);
+        assert_eq!(ts.chunks.len(), 1);
+        let TimeSeriesChunk::Uncompressed(chunk) = &ts.chunks[0] else {
+            panic!("expected an uncompressed chunk");
+        };
+        assert_eq!(chunk.samples.capacity(), chunk.samples.len());
+
+        // sealing again before any append is a no-op
+        assert!(!ts.seal());
+        assert_eq!(ts.chunks.len(), 1);
+
+        // updating the last sample of the sealed chunk still updates the series' last sample
+        ts.sample_duplicates.policy = Some(DuplicatePolicy::KeepLast);
+        assert!(ts.add(1040, 100.0, None).is_ok());
+        assert_eq!(ts.last_sample, Some(Sample::new(1040, 100.0)));
+        assert_eq!(ts.chunks.len(), 1);
+        assert!(ts.sealed);
+
+        assert!(ts.add(1050, 5.0, None).is_ok());
+        assert_eq!(ts.chunks.len(), 2);
+        assert_eq!(ts.chunks[0].len(), 5);
+        assert_eq!(ts.chunks[1].len(), 1);
+        assert_eq!(ts.total_samples, 6);
+        assert_eq!(ts.last_sample, Some(Sample::new(1050, 5.0)));
+        assert!(!ts.sealed);
+    }
+
+    #[test]
+    fn test_merge_samples_after_seal() {
+        let mut ts = create_test_series();
+        for i in 0..5 {
+            assert!(ts.add(1000 + i * 10, i as f64, None).is_ok());
+        }
+        assert!(ts.seal());
+
+        let samples = [
+            Sample::new(1015, 1.5),
+            Sample::new(1100, 10.0),
+            Sample::new(1110, 11.0),
+        ];
+        let results = ts.merge_samples(&samples, None).unwrap();
+        assert!(results.iter().all(|res| res.is_ok()));
+
+        // the late sample goes to the sealed chunk, the newer ones to a new chunk
+        assert_eq!(ts.chunks.len(), 2);
+        assert_eq!(ts.chunks[0].len(), 6);
+        assert_eq!(ts.chunks[1].len(), 2);
+        assert_eq!(ts.chunks[1].first_timestamp(), 1100);
+        assert_eq!(ts.total_samples, 8);
+        assert_eq!(ts.last_sample, Some(Sample::new(1110, 11.0)));
+        assert!(!ts.sealed);
+    }
+
+    #[test]
+    fn test_trim_and_remove_range_after_seal() {
+        let mut ts = create_test_series();
+        for i in 0..10 {
+            assert!(ts.add(1000 + i * 10, i as f64, None).is_ok());
+        }
+        assert!(ts.seal());
+
+        assert_eq!(ts.remove_range(1000, 1020).unwrap(), 3);
+        assert_eq!(ts.last_sample, Some(Sample::new(1090, 9.0)));
+
+        ts.max_samples = Some(4);
+        ts.enforce_max_samples();
+        assert_eq!(ts.total_samples, 4);
+        assert_eq!(ts.first_timestamp, 1060);
+        assert_eq!(ts.last_sample, Some(Sample::new(1090, 9.0)));
+
+        // the series stays sealed
+        assert!(ts.add(2000, 1.0, None).is_ok());
+        assert_eq!(ts.chunks.len(), 2);
+        assert_eq!(ts.last_sample, Some(Sample::new(2000, 1.0)));
+    }
+
+    #[test]
+    fn test_set_encoding_round_trips_samples() {
+        let mut ts = create_test_series();
+        ts.chunk_compression = ChunkEncoding::Uncompressed;
+        ts.chunks = vec![ts.create_chunk()];
+
+        let data = DataGenerator::builder()
+            .start(1000)
+            .interval(Duration::from_millis(1000))
+            .algorithm(RandAlgo::Deriv)
+            .samples(2000)
+            .build()
+            .generate();
+        for sample in data.iter() {
+            assert!(ts.add(sample.timestamp, sample.value, None).is_ok());
+        }
+        let expected = ts.get_range(0, i64::MAX);
+        assert_eq!(expected, data);
+
+        for encoding in [
+            ChunkEncoding::Gorilla,
+            ChunkEncoding::Pco,
+            ChunkEncoding::Uncompressed,
+            ChunkEncoding::Pco,
+            ChunkEncoding::Gorilla,
+        ] {
+            assert!(ts.set_encoding(encoding).unwrap());
+            assert_eq!(ts.chunk_compression, encoding);
+            assert!(ts.chunks.iter().all(|c| c.get_encoding() == encoding));
+            assert_eq!(ts.get_range(0, i64::MAX), expected);
+            assert_eq!(ts.total_samples, expected.len());
+        }
+
+        assert!(!ts.set_encoding(ChunkEncoding::Gorilla).unwrap());
+
+        // new samples go to chunks of the new encoding
+        let last = expected.last().unwrap().timestamp;
+        assert!(ts.add(last + 1000, 1.0, None).is_ok());
+        assert!(
+            ts.chunks
+                .iter()
+                .all(|c| c.get_encoding() == ChunkEncoding::Gorilla)
+        );
+    }
+
+    #[test]
+    fn test_stats_from_metadata() {
+        let mut ts = create_test_series();
+        let stats = ts.stats();
+        assert_eq!(stats.total_samples, 0);
+        assert_eq!(stats.duration, 0);
+        assert_eq!(stats.sample_rate, 0.0);
+        assert_eq!(stats.bytes_per_sample, 0.0);
+        assert_eq!(stats.compression_ratio, 0.0);
+
+        for i in 0..=10 {
+            ts.add(1000 + i * 500, i as f64, None);
+        }
+        let stats = ts.stats();
+        assert_eq!(stats.total_samples, 11);
+        assert_eq!(stats.duration, 5000);
+        assert_eq!(stats.sample_rate, 2.2);
+        assert_eq!(stats.chunk_count, ts.chunks.len());
+        assert_eq!(stats.data_size, ts.data_size());
+        let bytes_per_sample = ts.data_size() as f64 / 11.0;
+        assert_eq!(stats.bytes_per_sample, bytes_per_sample);
+        assert_eq!(
+            stats.compression_ratio,
+            SAMPLE_SIZE as f64 / bytes_per_sample
+        );
+    }
+
+    #[test]
+    fn test_memory_breakdown_adds_up() {
+        let mut ts = create_test_series();
+        ts.labels = MetricName::new(&[Label::new("host", "unique-breakdown-host")]);
+        for i in 0..1000 {
+            ts.add(1000 + i * 10, i as f64, None);
+        }
+        let usage = ts.memory_breakdown();
+        assert!(usage.chunks > 0);
+        assert_eq!(usage.labels, ts.labels.get_heap_size());
+        assert!(usage.labels > 0);
+        assert!(usage.metadata >= size_of::<TimeSeries>());
+        assert_eq!(
+            usage.chunks + usage.labels + usage.metadata,
+            ts.memory_usage()
+        );
+    }
+
+    #[test]
+    fn test_memory_usage_counts_labels() {
+        let mut ts = create_test_series();
+        let without_labels = ts.memory_usage();
+
+        ts.labels = MetricName::new(&[Label::new("host", "unique-usage-host")]);
+        let label_size = ts.labels.get_heap_size();
+        assert!(label_size > 0);
+        assert_eq!(ts.memory_usage(), without_labels + label_size);
+    }
+
+    /// Fills the first chunk with out-of-order samples until the next insert splits it, then
+    /// returns the series after that insert.
+    fn split_first_chunk(policy: ChunkSplitPolicy, trigger_ts: Timestamp) -> TimeSeries {
+        let mut ts = TimeSeries::with_options(TimeSeriesOptions {
+            chunk_compression: ChunkEncoding::Uncompressed,
+            chunk_size: Some(10 * size_of::<Sample>()),
+            split_policy: Some(policy),
+            ..Default::default()
+        })
+        .unwrap();
+        for i in 0..30 {
+            assert!(ts.add(100 + i * 10, i as f64, None).is_ok());
+        }
+        assert_eq!(ts.chunks.len(), 3);
+
+        let mut ooo_ts = 101;
+        while !ts.chunks[0].should_split() {
+            assert!(ts.add(ooo_ts, 0.5, None).is_ok());
+            ooo_ts += 1;
+        }
+        let expected_len = ts.len() + 1;
+
+        assert!(ts.add(trigger_ts, 0.5, None).is_ok());
+        assert_eq!(ts.chunk_splits, 1);
+        assert_eq!(ts.chunks.len(), 4);
+        assert_eq!(ts.len(), expected_len);
+        assert_eq!(ts.iter().count(), expected_len);
+        assert_eq!(ts.get_sample(trigger_ts).unwrap().unwrap().value, 0.5);
+        assert!(
+            ts.chunks
+                .windows(2)
+                .all(|w| w[0].last_timestamp() < w[1].first_timestamp())
+        );
+        ts
+    }
+
+    fn left_fill_ratio(ts: &TimeSeries) -> f64 {
+        let left = ts.chunks[0].len();
+        let right = ts.chunks[1].len();
+        left as f64 / (left + right) as f64
+    }
+
+    #[test]
+    fn test_split_policy_half() {
+        let ts = split_first_chunk(ChunkSplitPolicy::Half, 185);
+        let left = ts.chunks[0].len() as i64;
+        let right = ts.chunks[1].len() as i64;
+        // the inserted sample lands on one of the halves
+        assert!((left - right).abs() <= 1, "left={left}, right={right}");
+    }
+
+    #[test]
+    fn test_split_policy_ratio() {
+        let ts = split_first_chunk(ChunkSplitPolicy::Ratio(90), 185);
+        assert!(left_fill_ratio(&ts) >= 0.75, "{}", left_fill_ratio(&ts));
+
+        let ts = split_first_chunk(ChunkSplitPolicy::Ratio(10), 105);
+        assert!(left_fill_ratio(&ts) <= 0.25, "{}", left_fill_ratio(&ts));
+    }
+
+    #[test]
+    fn test_split_policy_insertion_point() {
+        let ts = split_first_chunk(ChunkSplitPolicy::InsertionPoint, 185);
+        // only the samples after the insertion point are moved
+        assert_eq!(ts.chunks[0].last_timestamp(), 185);
+        assert_eq!(ts.chunks[1].first_timestamp(), 190);
+        assert_eq!(ts.chunks[1].len(), 1);
+        assert!(left_fill_ratio(&ts) > 0.9);
+
+        // inserting before every sample falls back to splitting in half
+        let ts = split_first_chunk(ChunkSplitPolicy::InsertionPoint, 50);
+        let left = ts.chunks[0].len() as i64;
+        let right = ts.chunks[1].len() as i64;
+        assert!((left - right).abs() <= 1, "left={left}, right={right}");
+    }
+
+    #[test]
+    fn test_split_policy_merge_samples() {
+        for policy in [
+            ChunkSplitPolicy::Half,
+            ChunkSplitPolicy::Ratio(90),
+            ChunkSplitPolicy::InsertionPoint,
+        ] {
+            let mut ts = TimeSeries::with_options(TimeSeriesOptions {
+                chunk_compression: ChunkEncoding::Uncompressed,
+                chunk_size: Some(10 * size_of::<Sample>()),
+                split_policy: Some(policy),
+                ..Default::default()
+            })
+            .unwrap();
+            for i in 0..30 {
+                assert!(ts.add(i * 10, i as f64, None).is_ok());
+            }
+            let late: Vec<Sample> = (0..90)
+                .filter(|t| t % 10 != 0)
+                .map(|t| Sample::new(t, 0.5))
+                .collect();
+            for batch in late.chunks(4) {
+                ts.merge_samples(batch, None).unwrap();
+            }
+            assert_eq!(ts.len(), 30 + late.len(), "{policy}");
+            assert_eq!(ts.iter().count(), ts.len(), "{policy}");
+            assert!(ts.chunk_splits > 0, "{policy}");
+        }
+    }
+
+    #[test]
+    fn test_split_policy_index() {
+        assert_eq!(ChunkSplitPolicy::Half.split_index(12, Some(11)), 6);
+        assert_eq!(ChunkSplitPolicy::Ratio(90).split_index(12, None), 10);
+        assert_eq!(ChunkSplitPolicy::Ratio(1).split_index(12, None), 1);
+        assert_eq!(ChunkSplitPolicy::Ratio(99).split_index(12, None), 11);
+        assert_eq!(
+            ChunkSplitPolicy::InsertionPoint.split_index(12, Some(11)),
+            11
+        );
+        assert_eq!(ChunkSplitPolicy::InsertionPoint.split_index(12, Some(0)), 6);
+        assert_eq!(ChunkSplitPolicy::InsertionPoint.split_index(12, None), 6);
+        assert_eq!(
+            ChunkSplitPolicy::new_ratio(50).unwrap(),
+            ChunkSplitPolicy::Half
+        );
+        assert!(ChunkSplitPolicy::new_ratio(0).is_err());
+        assert!(ChunkSplitPolicy::new_ratio(100).is_err());
+        for policy in [
+            ChunkSplitPolicy::Half,
+            ChunkSplitPolicy::Ratio(75),
+            ChunkSplitPolicy::InsertionPoint,
+        ] {
+            assert_eq!(ChunkSplitPolicy::from_u8(policy.as_u8()).unwrap(), policy);
+            let name = policy.to_string();
+            assert_eq!(ChunkSplitPolicy::try_from(name.as_str()).unwrap(), policy);
+        }
+    }
+
+    #[test]
+    fn test_timestamp_unit_round_trip() {
+        for unit in [
+            TimestampUnit::Seconds,
+            TimestampUnit::Milliseconds,
+            TimestampUnit::Microseconds,
+            TimestampUnit::Nanoseconds,
+        ] {
+            let mut ts = TimeSeries::with_options(TimeSeriesOptions {
+                timestamp_unit: Some(unit),
+                ..Default::default()
+            })
+            .unwrap();
+
+            // timestamps as a client using `unit` would send them, on millisecond boundaries
+            let client_ts: Vec<i64> = (1..=20)
+                .map(|i| unit.from_millis(i * 1000 + (i % 3) * 7))
+                .collect();
+            for (i, &value) in client_ts.iter().take(10).enumerate() {
+                assert!(
+                    ts.add(unit.to_millis(value).unwrap(), i as f64, None)
+                        .is_ok()
+                );
+            }
+            let batch: Vec<Sample> = client_ts[10..]
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| Sample::new(unit.to_millis(value).unwrap(), (i + 10) as f64))
+                .collect();
+            ts.merge_samples(&batch, None).unwrap();
+
+            let returned: Vec<i64> = ts
+                .iter()
+                .map(|sample| ts.to_client_sample(sample).timestamp)
+                .collect();
+            assert_eq!(returned, client_ts, "{unit}");
+
+            assert_eq!(TimestampUnit::from_u8(unit.as_u8()).unwrap(), unit);
+            assert_eq!(TimestampUnit::try_from(unit.name()).unwrap(), unit);
+        }
+    }
+
+    #[test]
+    fn test_timestamp_unit_conversion_edges() {
+        assert!(
+            TimestampUnit::Seconds
+                .to_millis(i64::MAX / 1000 + 1)
+                .is_err()
+        );
+        assert!(
+            TimestampUnit::Seconds
+                .end_to_millis(i64::MAX / 1000)
+                .is_err()
+        );
+        assert_eq!(
+            TimestampUnit::Seconds.to_millis(i64::MAX / 1000).unwrap() % 1000,
+            0
+        );
+        // sub-millisecond precision is truncated, and output saturates rather than wrapping
+        assert_eq!(TimestampUnit::Microseconds.to_millis(1999).unwrap(), 1);
+        assert_eq!(TimestampUnit::Nanoseconds.to_millis(1_999_999).unwrap(), 1);
+        assert_eq!(TimestampUnit::Nanoseconds.from_millis(i64::MAX), i64::MAX);
+        assert_eq!(TimestampUnit::Seconds.from_millis(1999), 1);
+        // range bounds cover every millisecond of the value
+        assert_eq!(TimestampUnit::Seconds.start_to_millis(2).unwrap(), 2000);
+        assert_eq!(TimestampUnit::Seconds.end_to_millis(2).unwrap(), 2999);
+        assert_eq!(
+            TimestampUnit::Microseconds.start_to_millis(1001).unwrap(),
+            2
+        );
+        assert_eq!(TimestampUnit::Microseconds.end_to_millis(1999).unwrap(), 1);
+        assert!(TimestampUnit::try_from("minutes").is_err());
+    }
+
+    #[test]
+    fn test_encoded_chunks_matches_chunks_meta() {
+        let mut ts = TimeSeries::with_options(TimeSeriesOptions {
+            chunk_size: Some(256),
+            ..Default::default()
+        })
+        .unwrap();
+        for i in 0..1000 {
+            ts.add(1000 + i * 10, i as f64, None);
+        }
+        assert!(ts.chunks.len() > 1);
+
+        let metas: Vec<_> = ts.chunks_meta().collect();
+        let encoded: Vec<_> = ts.encoded_chunks().collect();
+        assert_eq!(metas.len(), ts.chunks.len());
+        assert_eq!(metas, encoded.iter().map(|c| c.meta).collect::<Vec<_>>());
+        assert_eq!(metas.iter().map(|m| m.count).sum::<usize>(), ts.len());
+        assert_eq!(metas[0].first_timestamp, ts.first_timestamp);
+        assert_eq!(
+            metas.last().unwrap().last_timestamp,
+            ts.last_sample.unwrap().timestamp
+        );
+        assert!(
+            metas
+                .windows(2)
+                .all(|w| w[0].last_timestamp < w[1].first_timestamp)
+        );
+
+        for (chunk, exported) in ts.chunks.iter().zip(encoded.iter()) {
+            assert_eq!(&decode_chunk(&exported.data).unwrap(), chunk);
+        }
+    }
+
+    #[test]
+    fn test_import_chunks_round_trip() {
+        for encoding in [
+            ChunkEncoding::Uncompressed,
+            ChunkEncoding::Gorilla,
+            ChunkEncoding::Pco,
+        ] {
+            let options = TimeSeriesOptions {
+                chunk_compression: encoding,
+                chunk_size: Some(512),
+                ..Default::default()
+            };
+            let mut source = TimeSeries::with_options(options.clone()).unwrap();
+            for i in 0..2000 {
+                source.add(1000 + i * 10, (i % 97) as f64 * 1.5, None);
+            }
+            assert!(source.chunks.len() > 1);
+
+            let mut dest = TimeSeries::with_options(options).unwrap();
+            for chunk in source.encoded_chunks() {
+                dest.import_chunk(&chunk).unwrap();
+            }
+
+            assert_eq!(dest.len(), source.len());
+            assert_eq!(dest.first_timestamp, source.first_timestamp);
+            assert_eq!(dest.last_sample, source.last_sample);
+            assert_eq!(
+                dest.get_range(i64::MIN, i64::MAX),
+                source.get_range(i64::MIN, i64::MAX)
+            );
+
+            // appending after import continues from the imported data
+            assert!(dest.add(1_000_000, 1.0, None).is_ok());
+            assert_eq!(dest.len(), source.len() + 1);
+        }
+    }
+
+    #[test]
+    fn test_import_chunk_validation() {
+        let mut source = TimeSeries::with_options(TimeSeriesOptions {
+            chunk_compression: ChunkEncoding::Gorilla,
+            chunk_size: Some(256),
+            ..Default::default()
+        })
+        .unwrap();
+        for i in 0..2000 {
+            source.add(1000 + i * 10, i as f64, None);
+        }
+        let exported: Vec<_> = source.encoded_chunks().collect();
+        assert!(exported.len() > 2);
+
+        // the encoding must match the destination
+        let mut dest = TimeSeries::with_options(TimeSeriesOptions {
+            chunk_compression: ChunkEncoding::Pco,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(matches!(
+            dest.import_chunk(&exported[0]),
+            Err(TsdbError::InvalidCompression(_))
+        ));
+        assert!(dest.is_empty());
+
+        let mut dest = TimeSeries::with_options(TimeSeriesOptions {
+            chunk_compression: ChunkEncoding::Gorilla,
+            ..Default::default()
+        })
+        .unwrap();
+
+        // metadata must describe the payload
+        let mut tampered = exported[0].clone();
+        tampered.meta.count += 1;
+        assert!(dest.import_chunk(&tampered).is_err());
+
+        // unknown format versions are rejected
+        let mut tampered = exported[0].clone();
+        tampered.data[0] = tampered.data[0].wrapping_add(1);
+        assert!(matches!(
+            dest.import_chunk(&tampered),
+            Err(TsdbError::DecodingError(_))
+        ));
+        assert!(dest.is_empty());
+
+        // chunks must be imported in order, without overlap
+        dest.import_chunk(&exported[1]).unwrap();
+        assert!(dest.import_chunk(&exported[0]).is_err());
+        assert!(dest.import_chunk(&exported[1]).is_err());
+        assert_eq!(dest.len(), exported[1].meta.count);
+        dest.import_chunk(&exported[2]).unwrap();
+        assert_eq!(dest.len(), exported[1].meta.count + exported[2].meta.count);
+        assert_eq!(dest.first_timestamp, exported[1].meta.first_timestamp);
+    }
+
+    fn noisy_value(i: i64) -> f64 {
+        ((i * 7919) % 1000) as f64 * 0.37 - 150.0
+    }
+
+    #[test]
+    fn test_select_chunk_encoding() {
+        use crate::series::time_series::select_chunk_encoding;
+
+        let samples = |f: fn(i64) -> f64| (0..200).map(move |i| Sample::new(1000 + i * 10, f(i)));
+
+        // counters, repeated values and low spread favour Gorilla
+        assert_eq!(
+            select_chunk_encoding(samples(|i| (i * 3) as f64)),
+            ChunkEncoding::Gorilla
+        );
+        assert_eq!(
+            select_chunk_encoding(samples(
+                |i| (i / 4) as f64 * if i % 8 < 4 { 1.0 } else { -1.0 }
+            )),
+            ChunkEncoding::Gorilla
+        );
+        assert_eq!(
+            select_chunk_encoding(samples(|i| 1000.0 + (i % 3) as f64)),
+            ChunkEncoding::Gorilla
+        );
+        assert_eq!(
+            select_chunk_encoding(std::iter::empty()),
+            ChunkEncoding::Gorilla
+        );
+
+        // noisy gauges favour Pco
+        assert_eq!(
+            select_chunk_encoding(samples(noisy_value)),
+            ChunkEncoding::Pco
+        );
+        // the choice is deterministic
+        assert_eq!(
+            select_chunk_encoding(samples(noisy_value)),
+            select_chunk_encoding(samples(noisy_value))
+        );
+    }
+
+    #[test]
+    fn test_auto_encoding_switches_after_first_chunk() {
+        for (value, expected) in [
+            (
+                (|i: i64| i as f64) as fn(i64) -> f64,
+                ChunkEncoding::Gorilla,
+            ),
+            (noisy_value, ChunkEncoding::Pco),
+        ] {
+            let mut ts = TimeSeries::with_options(TimeSeriesOptions {
+                chunk_compression: ChunkEncoding::Uncompressed,
+                auto_encoding: true,
+                chunk_size: Some(256),
+                ..Default::default()
+            })
+            .unwrap();
+
+            for i in 0..1000 {
+                ts.add(1000 + i * 10, value(i), None);
+            }
+
+            assert!(ts.chunks.len() > 1);
+            assert!(!ts.auto_encoding);
+            assert_eq!(ts.chunk_compression, expected);
+            assert_eq!(ts.chunks[0].get_encoding(), ChunkEncoding::Uncompressed);
+            assert!(ts.chunks[1..].iter().all(|c| c.get_encoding() == expected));
+
+            let samples = ts.get_range(i64::MIN, i64::MAX);
+            assert_eq!(samples.len(), 1000);
+            assert!(
+                samples
+                    .iter()
+                    .enumerate()
+                    .all(|(i, s)| s.value == value(i as i64))
+            );
+        }
+    }
 }
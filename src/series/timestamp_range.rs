@@ -1,9 +1,9 @@
 use crate::common::Timestamp;
 use crate::common::constants::MAX_TIMESTAMP;
 use crate::common::humanize::humanize_duration_ms;
-use crate::common::time::current_time_millis;
+use crate::common::time::{current_time_millis, shift_months};
 use crate::error_consts;
-use crate::parser::duration::parse_duration_value;
+use crate::parser::duration::{parse_duration_value, parse_relative_duration};
 use crate::parser::timestamp::parse_timestamp;
 use crate::series::TimeSeries;
 use std::cmp::Ordering;
@@ -40,6 +40,9 @@ pub enum TimestampValue {
     Specific(Timestamp),
     /// A timestamp with a given delta(ms) from the current timestamp
     Relative(i64),
+    /// A delta(ms) from the resolved start of a range (e.g. `start+30m`). Only meaningful as the
+    /// end of a range; elsewhere it resolves against the current time like `Relative`.
+    StartOffset(i64),
 }
 
 impl TimestampValue {
@@ -50,7 +53,7 @@ impl TimestampValue {
             Latest => MAX_TIMESTAMP,
             Now => now.unwrap_or_else(current_time_millis),
             Specific(ts) => *ts,
-            Relative(delta) => now
+            Relative(delta) | StartOffset(delta) => now
                 .unwrap_or_else(current_time_millis)
                 .saturating_add(*delta),
         }
@@ -64,7 +67,7 @@ impl TimestampValue {
             Latest => series.last_timestamp(),
             Now => now.unwrap_or_else(current_time_millis),
             Specific(ts) => *ts,
-            Relative(delta) => {
+            Relative(delta) | StartOffset(delta) => {
                 let now = now.unwrap_or_else(current_time_millis);
                 now.saturating_add(*delta)
             }
@@ -94,17 +97,32 @@ impl TryFrom<&str> for TimestampValue {
             }
         }
 
-        // Ergonomics. Support something like TS.RANGE key -6hrs -3hrs
+        // Opt-in offset from the start of the range, e.g. TS.RANGE key -6hrs start+1hr
+        if len > START_OFFSET_PREFIX.len()
+            && value[..START_OFFSET_PREFIX.len()].eq_ignore_ascii_case(START_OFFSET_PREFIX)
+        {
+            let ms = parse_duration_ms(&value[START_OFFSET_PREFIX.len()..])?;
+            return Ok(StartOffset(ms));
+        }
+
+        // Ergonomics. Support something like TS.RANGE key -6hrs -3hrs, or -1mo
         if let Some(ch) = value.chars().next()
             && (ch == '-' || ch == '+')
         {
-            let value = &value[1..];
-            let mut ms = parse_duration_ms(value)?;
-
-            if ch == '-' {
-                ms = -ms;
+            let duration = parse_relative_duration(&value[1..])
+                .map_err(|_| ValkeyError::Str(error_consts::INVALID_DURATION))?;
+            let (months, ms) = if ch == '-' {
+                (-duration.months, -duration.millis)
+            } else {
+                (duration.months, duration.millis)
+            };
+            if months == 0 {
+                return Ok(Relative(ms));
             }
-            return Ok(Relative(ms));
+            // months vary in length, so they are measured from the current date. The result stays
+            // relative, so that it resolves against the same "now" as the other end of the range.
+            let now = current_time_millis();
+            return Ok(Relative(shift_months(now, months) - now + ms));
         }
 
         let ts = parse_timestamp(value, false)
@@ -118,6 +136,8 @@ impl TryFrom<&str> for TimestampValue {
     }
 }
 
+const START_OFFSET_PREFIX: &str = "start+";
+
 fn parse_duration_ms(arg: &str) -> ValkeyResult<i64> {
     parse_duration_value(arg).map_err(|_| ValkeyError::Str(error_consts::INVALID_DURATION))
 }
@@ -180,6 +200,10 @@ impl Display for TimestampValue {
                     write!(f, "{human} from now")
                 }
             }
+            StartOffset(delta) => {
+                let human = humanize_duration_ms(*delta);
+                write!(f, "{human} after start")
+            }
         }
     }
 }
@@ -195,6 +219,10 @@ impl PartialOrd for TimestampValue {
         use TimestampValue::*;
 
         match (self, other) {
+            (StartOffset(x), StartOffset(y)) => x.partial_cmp(y),
+            // an offset end is always at or after whatever start it is anchored to
+            (_, StartOffset(delta)) => 0i64.partial_cmp(delta),
+            (StartOffset(delta), _) => delta.partial_cmp(&0i64),
             (Now, Now) => Some(Ordering::Equal),
             (Earliest, Earliest) => Some(Ordering::Equal),
             (Latest, Latest) => Some(Ordering::Equal),
@@ -210,7 +238,10 @@ impl PartialOrd for TimestampValue {
             }
             (Relative(y), Now) => y.partial_cmp(&0i64),
             (Now, Relative(y)) => 0i64.partial_cmp(y),
-            (Specific(_), Relative(y)) => 0i64.partial_cmp(y),
+            (Specific(v), Relative(delta)) => {
+                let relative = current_time_millis() + *delta;
+                v.partial_cmp(&relative)
+            }
             (Relative(delta), Specific(y)) => {
                 let relative = current_time_millis() + *delta;
                 relative.partial_cmp(y)
@@ -259,10 +290,13 @@ impl TimestampRange {
     ) -> (Timestamp, Timestamp) {
         use TimestampValue::*;
 
+        // resolve "now" once so that relative ends are consistent with relative starts
+        let now = Some(now.unwrap_or_else(current_time_millis));
+
         // In case a retention is set shouldn't return chunks older than the retention
         let mut start_timestamp = self.start.as_series_timestamp(series, now);
-        let end_timestamp = if let Relative(delta) = self.end {
-            start_timestamp.wrapping_add(delta)
+        let end_timestamp = if let StartOffset(delta) = self.end {
+            start_timestamp.saturating_add(delta)
         } else {
             self.end.as_series_timestamp(series, now)
         };
@@ -278,9 +312,10 @@ impl TimestampRange {
     pub fn get_timestamps(&self, now: Option<Timestamp>) -> (Timestamp, Timestamp) {
         use TimestampValue::*;
 
+        let now = Some(now.unwrap_or_else(current_time_millis));
         let start_timestamp = self.start.as_timestamp(now);
-        let end_timestamp = if let Relative(delta) = self.end {
-            start_timestamp.wrapping_add(delta)
+        let end_timestamp = if let StartOffset(delta) = self.end {
+            start_timestamp.saturating_add(delta)
         } else {
             self.end.as_timestamp(now)
         };
@@ -400,6 +435,46 @@ mod tests {
         assert_eq!(result.unwrap(), TimestampValue::Relative(expected_delta));
     }
 
+    #[test]
+    fn test_timestamp_range_value_try_from_weeks_and_days() {
+        let day = 24 * 60 * 60 * 1000;
+        assert_eq!(
+            TimestampValue::try_from("-1w").unwrap(),
+            TimestampValue::Relative(-7 * day)
+        );
+        assert_eq!(
+            TimestampValue::try_from("-90d").unwrap(),
+            TimestampValue::Relative(-90 * day)
+        );
+        assert_eq!(
+            TimestampValue::try_from("+1w1d").unwrap(),
+            TimestampValue::Relative(8 * day)
+        );
+    }
+
+    #[test]
+    fn test_timestamp_range_value_try_from_calendar_months() {
+        let day = 24 * 60 * 60 * 1000;
+        let Ok(TimestampValue::Relative(delta)) = TimestampValue::try_from("-1mo") else {
+            panic!("expected a relative timestamp");
+        };
+        assert!((-31 * day..=-28 * day).contains(&delta), "{delta}");
+
+        let Ok(TimestampValue::Relative(delta)) = TimestampValue::try_from("-1y") else {
+            panic!("expected a relative timestamp");
+        };
+        assert!((-366 * day..=-365 * day).contains(&delta), "{delta}");
+
+        let Ok(TimestampValue::Relative(delta)) = TimestampValue::try_from("-1mo1d") else {
+            panic!("expected a relative timestamp");
+        };
+        assert!((-32 * day..=-29 * day).contains(&delta), "{delta}");
+
+        // an offset from the start of a range has a fixed length
+        assert!(TimestampValue::try_from("start+1mo").is_err());
+        assert!(TimestampValue::try_from("-1.5mo").is_err());
+    }
+
     #[test]
     fn test_timestamp_range_value_try_from_invalid_timestamp() {
         let input = "invalid_timestamp";
@@ -915,7 +990,7 @@ mod tests {
     }
 
     #[test]
-    fn test_get_series_range_with_relative_end() {
+    fn test_get_series_range_with_start_offset_end() {
         let series = TimeSeries {
             first_timestamp: 1000,
             last_sample: Some(Sample {
@@ -926,7 +1001,7 @@ mod tests {
         };
         let start = TimestampValue::Specific(2000);
         let end_delta = 3000; // 3 seconds
-        let end = TimestampValue::Relative(end_delta);
+        let end = TimestampValue::StartOffset(end_delta);
         let timestamp_range = TimestampRange::new(start, end).unwrap();
 
         let (start_timestamp, end_timestamp) =
@@ -970,4 +1045,60 @@ mod tests {
             series.last_timestamp(),
         );
     }
+
+    #[test]
+    fn test_get_timestamps_resolves_relative_ends_against_now() {
+        const HOUR: i64 = 60 * 60 * 1000;
+        let now = 10 * HOUR;
+
+        let range = TimestampRange::new(
+            TimestampValue::try_from("-1h").unwrap(),
+            TimestampValue::try_from("-30m").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            range.get_timestamps(Some(now)),
+            (now - HOUR, now - HOUR / 2)
+        );
+
+        let range = TimestampRange::new(
+            TimestampValue::try_from("-1h").unwrap(),
+            TimestampValue::try_from("*").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(range.get_timestamps(Some(now)), (now - HOUR, now));
+
+        let range = TimestampRange::new(
+            TimestampValue::try_from("-").unwrap(),
+            TimestampValue::try_from("+").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(range.get_timestamps(Some(now)), (0, MAX_TIMESTAMP));
+
+        // an absolute start with a relative end still resolves the end against now
+        let range = TimestampRange::new(
+            TimestampValue::Specific(1000),
+            TimestampValue::Relative(-HOUR),
+        )
+        .unwrap();
+        assert_eq!(range.get_timestamps(Some(now)), (1000, now - HOUR));
+    }
+
+    #[test]
+    fn test_start_offset_end_is_relative_to_start() {
+        const HOUR: i64 = 60 * 60 * 1000;
+        let now = 10 * HOUR;
+
+        let end = TimestampValue::try_from("start+30m").unwrap();
+        assert_eq!(end, TimestampValue::StartOffset(HOUR / 2));
+
+        let range = TimestampRange::new(TimestampValue::Relative(-HOUR), end).unwrap();
+        assert_eq!(
+            range.get_timestamps(Some(now)),
+            (now - HOUR, now - HOUR / 2)
+        );
+
+        let range = TimestampRange::new(TimestampValue::Specific(1000), end).unwrap();
+        assert_eq!(range.get_timestamps(Some(now)), (1000, 1000 + HOUR / 2));
+    }
 }
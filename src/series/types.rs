@@ -11,9 +11,11 @@ use crate::labels::Label;
 use crate::series::SeriesRef;
 use crate::series::chunks::ChunkEncoding;
 use get_size2::GetSize;
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::str::FromStr;
+use std::sync::{LazyLock, Mutex};
 use std::time::Duration;
 use valkey_module::{ValkeyError, ValkeyResult, ValkeyValue, raw};
 
@@ -192,6 +194,203 @@ impl TryFrom<String> for DuplicatePolicy {
     }
 }
 
+/// Determines where a full chunk is divided when a sample has to be inserted into it.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, GetSize, Hash)]
+pub enum ChunkSplitPolicy {
+    /// Split into two equally sized chunks
+    #[default]
+    Half,
+    /// Keep the given percentage (1-99) of samples in the left chunk. A high ratio leaves the
+    /// left chunk nearly full, which suits mostly in-order data with occasional late samples.
+    Ratio(u8),
+    /// Split at the position of the inserted sample, so that samples on either side of it are
+    /// not moved unnecessarily. Falls back to `Half` if that would leave one side empty.
+    InsertionPoint,
+}
+
+impl ChunkSplitPolicy {
+    pub fn new_ratio(percent: u8) -> TsdbResult<Self> {
+        match percent {
+            50 => Ok(ChunkSplitPolicy::Half),
+            1..=99 => Ok(ChunkSplitPolicy::Ratio(percent)),
+            _ => Err(TsdbError::InvalidConfiguration(
+                error_consts::INVALID_SPLIT_POLICY.to_string(),
+            )),
+        }
+    }
+
+    /// Returns the number of samples that stay in the left chunk when splitting a chunk of
+    /// `len` samples. `insert_pos` is the number of samples in the chunk which precede the
+    /// sample being inserted, if known.
+    pub fn split_index(&self, len: usize, insert_pos: Option<usize>) -> usize {
+        let half = len / 2;
+        if len < 2 {
+            return half;
+        }
+        match *self {
+            ChunkSplitPolicy::Half => half,
+            ChunkSplitPolicy::Ratio(percent) => (len * percent as usize / 100).clamp(1, len - 1),
+            ChunkSplitPolicy::InsertionPoint => match insert_pos {
+                Some(pos) if pos > 0 && pos < len => pos,
+                _ => half,
+            },
+        }
+    }
+
+    /// The value stored in rdb: 0 for `InsertionPoint`, otherwise the left-hand percentage.
+    pub fn as_u8(&self) -> u8 {
+        match *self {
+            ChunkSplitPolicy::Half => 50,
+            ChunkSplitPolicy::Ratio(percent) => percent,
+            ChunkSplitPolicy::InsertionPoint => 0,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> TsdbResult<Self> {
+        if value == 0 {
+            return Ok(ChunkSplitPolicy::InsertionPoint);
+        }
+        Self::new_ratio(value)
+    }
+}
+
+impl Display for ChunkSplitPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChunkSplitPolicy::Half => write!(f, "half"),
+            ChunkSplitPolicy::Ratio(percent) => write!(f, "{percent}"),
+            ChunkSplitPolicy::InsertionPoint => write!(f, "insertion"),
+        }
+    }
+}
+
+impl TryFrom<&str> for ChunkSplitPolicy {
+    type Error = ValkeyError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let invalid = || ValkeyError::Str(error_consts::INVALID_SPLIT_POLICY);
+        if s.eq_ignore_ascii_case("half") {
+            return Ok(ChunkSplitPolicy::Half);
+        }
+        if s.eq_ignore_ascii_case("insertion") {
+            return Ok(ChunkSplitPolicy::InsertionPoint);
+        }
+        let percent = s.parse::<u8>().map_err(|_| invalid())?;
+        ChunkSplitPolicy::new_ratio(percent).map_err(|_| invalid())
+    }
+}
+
+/// The unit of the timestamps a client sends to and receives from a series. Samples are always
+/// stored in milliseconds; other units are converted at the command boundary.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, GetSize, Hash)]
+pub enum TimestampUnit {
+    Seconds,
+    #[default]
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+impl TimestampUnit {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimestampUnit::Seconds => "s",
+            TimestampUnit::Milliseconds => "ms",
+            TimestampUnit::Microseconds => "us",
+            TimestampUnit::Nanoseconds => "ns",
+        }
+    }
+
+    /// Converts a client timestamp in this unit to milliseconds. Sub-millisecond precision is
+    /// truncated towards negative infinity, so that conversion is monotonic.
+    pub fn to_millis(&self, value: i64) -> ValkeyResult<Timestamp> {
+        match self {
+            TimestampUnit::Seconds => value
+                .checked_mul(1000)
+                .ok_or(ValkeyError::Str(error_consts::TIMESTAMP_UNIT_OVERFLOW)),
+            TimestampUnit::Milliseconds => Ok(value),
+            TimestampUnit::Microseconds => Ok(value.div_euclid(1_000)),
+            TimestampUnit::Nanoseconds => Ok(value.div_euclid(1_000_000)),
+        }
+    }
+
+    /// The first millisecond covered by `value`, used for the start of a range. A finer unit value
+    /// between two milliseconds is rounded up, since no stored sample can precede it in `self`.
+    pub fn start_to_millis(&self, value: i64) -> ValkeyResult<Timestamp> {
+        let divisor = match self {
+            TimestampUnit::Seconds | TimestampUnit::Milliseconds => return self.to_millis(value),
+            TimestampUnit::Microseconds => 1_000,
+            TimestampUnit::Nanoseconds => 1_000_000,
+        };
+        Ok(value.div_euclid(divisor) + (value.rem_euclid(divisor) != 0) as i64)
+    }
+
+    /// The last millisecond covered by `value`, used for the end of a range.
+    pub fn end_to_millis(&self, value: i64) -> ValkeyResult<Timestamp> {
+        match self {
+            TimestampUnit::Seconds => value
+                .checked_mul(1000)
+                .and_then(|ts| ts.checked_add(999))
+                .ok_or(ValkeyError::Str(error_consts::TIMESTAMP_UNIT_OVERFLOW)),
+            _ => self.to_millis(value),
+        }
+    }
+
+    /// Converts a stored millisecond timestamp to this unit. Values which cannot be represented
+    /// saturate at the `i64` bounds.
+    pub fn from_millis(&self, ts: Timestamp) -> i64 {
+        match self {
+            TimestampUnit::Seconds => ts.div_euclid(1000),
+            TimestampUnit::Milliseconds => ts,
+            TimestampUnit::Microseconds => ts.saturating_mul(1_000),
+            TimestampUnit::Nanoseconds => ts.saturating_mul(1_000_000),
+        }
+    }
+
+    /// The value stored in rdb.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            TimestampUnit::Seconds => 0,
+            TimestampUnit::Milliseconds => 1,
+            TimestampUnit::Microseconds => 2,
+            TimestampUnit::Nanoseconds => 3,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> TsdbResult<Self> {
+        match value {
+            0 => Ok(TimestampUnit::Seconds),
+            1 => Ok(TimestampUnit::Milliseconds),
+            2 => Ok(TimestampUnit::Microseconds),
+            3 => Ok(TimestampUnit::Nanoseconds),
+            _ => Err(TsdbError::InvalidConfiguration(
+                error_consts::INVALID_TIMESTAMP_UNIT.to_string(),
+            )),
+        }
+    }
+}
+
+impl Display for TimestampUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl TryFrom<&str> for TimestampUnit {
+    type Error = ValkeyError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        hashify::tiny_map_ignore_case! {
+            s.as_bytes(),
+            "s" => TimestampUnit::Seconds,
+            "ms" => TimestampUnit::Milliseconds,
+            "us" => TimestampUnit::Microseconds,
+            "ns" => TimestampUnit::Nanoseconds,
+        }
+        .ok_or(ValkeyError::Str(error_consts::INVALID_TIMESTAMP_UNIT))
+    }
+}
+
 /// A struct that defines the policy for determining and handling duplicate samples in a dataset.
 #[derive(Copy, Clone, Default, Debug, GetSize, PartialEq)]
 pub struct SampleDuplicatePolicy {
@@ -267,6 +466,20 @@ impl SampleDuplicatePolicy {
     }
 }
 
+/// Selects how `TimeSeries::sample_at` resolves a timestamp which may not have an exact match.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SampleLookupMode {
+    /// The latest sample at or before the timestamp
+    #[default]
+    Previous,
+    /// The earliest sample at or after the timestamp
+    Next,
+    /// Whichever of the surrounding samples is closest. Ties resolve to the previous sample
+    Nearest,
+    /// Linear interpolation between the surrounding samples
+    Linear,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum SampleAddResult {
     Ok(Sample),
@@ -281,6 +494,52 @@ impl SampleAddResult {
     pub fn is_ok(&self) -> bool {
         matches!(self, SampleAddResult::Ok(_))
     }
+
+    /// A stable short code identifying the outcome, so that clients can branch on the rejection
+    /// reason without parsing the human readable message.
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            SampleAddResult::Ok(_) => "OK",
+            SampleAddResult::Duplicate => "DUPLICATE",
+            SampleAddResult::Ignored(_) => "IGNORED",
+            SampleAddResult::TooOld => "TOO_OLD",
+            SampleAddResult::Error(msg) => match *msg {
+                error_consts::DUPLICATE_SAMPLE_BLOCKED => "DUPLICATE",
+                error_consts::SAMPLE_TOO_OLD => "TOO_OLD",
+                error_consts::SAMPLE_TOO_NEW => "TOO_NEW",
+                error_consts::INVALID_TIMESTAMP => "INVALID_TIMESTAMP",
+                error_consts::INVALID_VALUE => "INVALID_VALUE",
+                error_consts::KEY_NOT_FOUND => "KEY_NOT_FOUND",
+                error_consts::INVALID_TIMESERIES_KEY => "WRONGTYPE",
+                error_consts::PERMISSION_DENIED => "NOPERM",
+                _ => "ERROR",
+            },
+        }
+    }
+
+    /// Returns the result with its timestamp in `unit`, as replied to the clients of a series.
+    pub fn to_unit(self, unit: TimestampUnit) -> Self {
+        match self {
+            SampleAddResult::Ok(sample) => SampleAddResult::Ok(Sample::new(
+                unit.from_millis(sample.timestamp),
+                sample.value,
+            )),
+            SampleAddResult::Ignored(ts) => SampleAddResult::Ignored(unit.from_millis(ts)),
+            res => res,
+        }
+    }
+}
+
+/// Error replies must be `'static`, so each distinct "CODE message" string is built once and kept
+/// for the lifetime of the module. Both parts are static strings, so the cache stays small.
+fn coded_error(code: &'static str, message: &'static str) -> &'static str {
+    static CODED_ERRORS: LazyLock<Mutex<HashMap<(&'static str, &'static str), &'static str>>> =
+        LazyLock::new(Default::default);
+
+    let mut cache = CODED_ERRORS.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry((code, message))
+        .or_insert_with(|| Box::leak(format!("{code} {message}").into_boxed_str()))
 }
 
 impl Display for SampleAddResult {
@@ -300,11 +559,18 @@ impl From<SampleAddResult> for ValkeyValue {
         match res {
             SampleAddResult::Ok(ts) => ValkeyValue::Integer(ts.timestamp),
             SampleAddResult::Ignored(ts) => ValkeyValue::Integer(ts),
-            SampleAddResult::Duplicate => {
-                ValkeyValue::SimpleStringStatic(error_consts::DUPLICATE_SAMPLE)
+            // rejections are reported as "CODE message" errors
+            SampleAddResult::Duplicate => ValkeyValue::StaticError(coded_error(
+                res.reason_code(),
+                error_consts::DUPLICATE_SAMPLE,
+            )),
+            SampleAddResult::TooOld => ValkeyValue::StaticError(coded_error(
+                res.reason_code(),
+                error_consts::SAMPLE_TOO_OLD,
+            )),
+            SampleAddResult::Error(e) => {
+                ValkeyValue::StaticError(coded_error(res.reason_code(), e))
             }
-            SampleAddResult::TooOld => ValkeyValue::StaticError(error_consts::SAMPLE_TOO_OLD),
-            SampleAddResult::Error(e) => ValkeyValue::StaticError(e),
         }
     }
 }
@@ -327,12 +593,18 @@ pub struct TimeSeriesOptions {
     /// The source ID of the series, if this is a derived series
     pub src_id: Option<SeriesRef>,
     pub chunk_compression: ChunkEncoding,
+    /// Choose the encoding from the data once the first chunk fills (`ENCODING AUTO`)
+    pub auto_encoding: bool,
     pub chunk_size: Option<usize>,
     pub retention: Option<Duration>,
+    /// Maximum number of samples to retain. `Some(0)` explicitly disables the limit.
+    pub max_samples: Option<usize>,
     pub sample_duplicate_policy: Option<SampleDuplicatePolicy>,
     pub labels: Option<Vec<Label>>,
     pub rounding: Option<RoundingStrategy>,
     pub on_duplicate: Option<DuplicatePolicy>,
+    pub split_policy: Option<ChunkSplitPolicy>,
+    pub timestamp_unit: Option<TimestampUnit>,
 }
 
 impl TimeSeriesOptions {
@@ -369,6 +641,7 @@ impl TimeSeriesOptions {
                 None
             },
             chunk_compression: chunk_encoding,
+            auto_encoding: false,
             chunk_size: Some(chunk_size),
             rounding,
             sample_duplicate_policy: Some(SampleDuplicatePolicy {
@@ -386,12 +659,16 @@ impl Default for TimeSeriesOptions {
         Self {
             src_id: None,
             chunk_compression: ChunkEncoding::default(),
+            auto_encoding: false,
             chunk_size: Some(CHUNK_SIZE_DEFAULT as usize),
             retention: None,
+            max_samples: None,
             sample_duplicate_policy: None,
             labels: None,
             rounding: None,
             on_duplicate: None,
+            split_policy: None,
+            timestamp_unit: None,
         }
     }
 }
@@ -401,12 +678,16 @@ impl From<&ConfigSettings> for TimeSeriesOptions {
         Self {
             src_id: None,
             chunk_compression: settings.chunk_encoding,
+            auto_encoding: false,
             chunk_size: Some(settings.chunk_size_bytes),
             retention: settings.retention_period,
+            max_samples: None,
             sample_duplicate_policy: Some(settings.duplicate_policy),
             labels: None,
             rounding: settings.rounding,
             on_duplicate: None,
+            split_policy: None,
+            timestamp_unit: None,
         }
     }
 }
@@ -452,11 +733,13 @@ impl ValueFilter {
 
 #[cfg(test)]
 mod tests {
-    use super::DuplicatePolicy;
+    use super::{DuplicatePolicy, SampleAddResult};
     use crate::common::Sample;
     use crate::error::TsdbError;
+    use crate::error_consts;
     use crate::series::SampleDuplicatePolicy;
     use std::str::FromStr;
+    use valkey_module::ValkeyValue;
 
     #[test]
     fn test_duplicate_policy_parse() {
@@ -716,4 +999,51 @@ mod tests {
         // both NaN — never a duplicate
         assert!(!policy.is_duplicate(&current_nan, &last_nan, None));
     }
+
+    #[test]
+    fn test_sample_add_result_reason_code() {
+        assert_eq!(SampleAddResult::Ok(Sample::new(1, 1.0)).reason_code(), "OK");
+        assert_eq!(SampleAddResult::Ignored(1).reason_code(), "IGNORED");
+        assert_eq!(SampleAddResult::Duplicate.reason_code(), "DUPLICATE");
+        assert_eq!(SampleAddResult::TooOld.reason_code(), "TOO_OLD");
+        assert_eq!(
+            SampleAddResult::Error(error_consts::SAMPLE_TOO_NEW).reason_code(),
+            "TOO_NEW"
+        );
+        assert_eq!(
+            SampleAddResult::Error(error_consts::DUPLICATE_SAMPLE_BLOCKED).reason_code(),
+            "DUPLICATE"
+        );
+        assert_eq!(
+            SampleAddResult::Error(error_consts::INVALID_TIMESTAMP).reason_code(),
+            "INVALID_TIMESTAMP"
+        );
+        assert_eq!(
+            SampleAddResult::Error(error_consts::INVALID_TIMESERIES_KEY).reason_code(),
+            "WRONGTYPE"
+        );
+        assert_eq!(
+            SampleAddResult::Error(error_consts::CANNOT_ADD_SAMPLE).reason_code(),
+            "ERROR"
+        );
+    }
+
+    #[test]
+    fn test_sample_add_result_reply_includes_reason_code() {
+        let reply = ValkeyValue::from(SampleAddResult::TooOld);
+        assert_eq!(
+            reply,
+            ValkeyValue::StaticError("TOO_OLD TSDB: sample timestamp exceeds retention period")
+        );
+
+        let reply = ValkeyValue::from(SampleAddResult::Error(error_consts::INVALID_VALUE));
+        assert_eq!(
+            reply,
+            ValkeyValue::StaticError("INVALID_VALUE TSDB: invalid value")
+        );
+        assert_eq!(
+            ValkeyValue::from(SampleAddResult::Ignored(100)),
+            ValkeyValue::Integer(100)
+        );
+    }
 }
@@ -1,5 +1,7 @@
+use crate::common::Timestamp;
 use crate::common::constants::METRIC_NAME_LABEL;
-use crate::common::context::get_current_db;
+use crate::common::context::{get_current_db, is_real_user_client};
+use crate::config::{MAX_SERIES, max_future_timestamp};
 use crate::error_consts;
 use crate::labels::{InternedLabel, Label};
 use crate::series::acl::check_key_permissions;
@@ -10,6 +12,7 @@ use crate::series::{
     SeriesGuard, SeriesGuardMut, TimeSeries, TimeSeriesOptions, create_compaction_rules_from_config,
 };
 use std::ops::Deref;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use valkey_module::key::ValkeyKeyWritable;
 use valkey_module::{
@@ -93,6 +96,30 @@ pub fn get_timeseries_mut<'a>(
     }
 }
 
+/// Returns the latest timestamp (ms) a sample written by the current command may have under
+/// `ts-max-future-skew`, or `None` if it is not limited.
+///
+/// Only user writes are limited. Replicated and AOF commands were accepted by the node which first
+/// ran them, and judging them again by this node's clock could make it diverge.
+pub fn get_max_future_timestamp(ctx: &Context) -> Option<Timestamp> {
+    if is_real_user_client(ctx) {
+        max_future_timestamp()
+    } else {
+        None
+    }
+}
+
+/// Fails if `timestamp` (ms) is later than `max_timestamp`, as returned by [`get_max_future_timestamp`].
+pub fn check_future_timestamp(
+    timestamp: Timestamp,
+    max_timestamp: Option<Timestamp>,
+) -> ValkeyResult<()> {
+    if max_timestamp.is_some_and(|max_ts| timestamp > max_ts) {
+        return Err(ValkeyError::Str(error_consts::SAMPLE_TOO_NEW));
+    }
+    Ok(())
+}
+
 pub(crate) fn invalid_series_key_error() -> ValkeyError {
     ValkeyError::Str(error_consts::KEY_NOT_FOUND)
 }
@@ -117,13 +144,18 @@ pub fn create_series(
     // opting in to Prometheus semantics, meaning a metric name is unique to a series.
     if ts.labels.get_value(METRIC_NAME_LABEL).is_some() {
         let labels = ts.labels.to_label_vec();
-        // will return an error if the series already exists
-        if index.series_id_by_labels(&labels).is_some() {
-            return Err(ValkeyError::Str(error_consts::DUPLICATE_SERIES));
-        }
+        // will return an error naming the existing key if the series already exists
+        index.ensure_unique_labels(&labels)?;
     }
 
-    index.index_timeseries(&ts, key.iter().as_slice());
+    // the limit only applies to user writes. Series created by the primary or loaded from the AOF
+    // were already accepted, and refusing them here would make this node diverge.
+    let max_series = if is_real_user_client(ctx) {
+        MAX_SERIES.load(Ordering::Relaxed) as usize
+    } else {
+        0
+    };
+    index.index_new_timeseries(&ts, key.iter().as_slice(), max_series)?;
     Ok(ts)
 }
 
@@ -250,3 +282,36 @@ pub fn get_series_labels<'a>(
             .collect::<Vec<_>>()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::time::current_time_millis;
+    use crate::config::MAX_FUTURE_SKEW;
+
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+
+    #[test]
+    fn test_check_future_timestamp() {
+        assert!(check_future_timestamp(i64::MAX, None).is_ok());
+        assert!(check_future_timestamp(1000, Some(1000)).is_ok());
+        assert!(check_future_timestamp(999, Some(1000)).is_ok());
+
+        let err = check_future_timestamp(1001, Some(1000)).unwrap_err();
+        assert!(matches!(err, ValkeyError::Str(msg) if msg == error_consts::SAMPLE_TOO_NEW));
+    }
+
+    #[test]
+    fn test_max_future_timestamp_follows_config() {
+        assert_eq!(max_future_timestamp(), None);
+
+        MAX_FUTURE_SKEW.store(HOUR_MS, Ordering::Relaxed);
+        let max_timestamp = max_future_timestamp();
+        MAX_FUTURE_SKEW.store(0, Ordering::Relaxed);
+
+        let now = current_time_millis();
+        assert!(check_future_timestamp(now, max_timestamp).is_ok());
+        assert!(check_future_timestamp(now + HOUR_MS / 2, max_timestamp).is_ok());
+        assert!(check_future_timestamp(now + 2 * HOUR_MS, max_timestamp).is_err());
+    }
+}
@@ -46,9 +46,9 @@ fn handle_loaded(ctx: &Context, key: &[u8]) {
     if !index.has_id(series.id) {
         index.index_timeseries(&series, key);
 
-        // On module load, our series id generator would have been reset to zero. We have to ensure
-        // that after a load the counter has the value of the highest series id
-        TIMESERIES_ID.fetch_max(series.id, std::sync::atomic::Ordering::Relaxed);
+        // On module load, our series id generator would have been reset. The counter holds the
+        // next id to hand out, so it must move past the highest loaded id
+        reserve_timeseries_id(series.id);
     } else {
         logging::log_warning("Trying to load a series that is already in the index");
     }
@@ -246,9 +246,32 @@ class TestCompactionAdd(ValkeyTimeSeriesTestCaseBase):
         dest_samples_after = self.client.execute_command("TS.RANGE", dest_key, "-", "+")
         assert len(dest_samples_after) >= initial_count
 
-        # Value should have changed due to upsert: (10 + 40 + 20 + 30) / 4 = 25
+        # Only the first bucket [1000, 11000) is recalculated: (10 + 40 + 20) / 3
+        # The sample at 16000 belongs to the still-open bucket and must not be folded in.
         new_value = float(dest_samples_after[0][1])
-        assert new_value == 25.0
+        assert new_value == pytest.approx(70.0 / 3)
+
+    def test_compaction_historical_upsert_only_touches_its_bucket(self):
+        """An upsert into an older closed bucket must not fold in later buckets"""
+        source_key = "test:source:hist_upsert"
+        dest_key = "test:dest:hist_upsert"
+
+        self.create_source_and_dest_series(source_key, dest_key)
+        self.add_compaction_rule(source_key, dest_key, "sum", 10)
+
+        for ts, value in [(0, 1), (5, 2), (10, 10), (15, 20), (20, 100), (25, 200), (30, 1000)]:
+            self.add_sample(source_key, ts, value)
+
+        assert self.client.execute_command("TS.RANGE", dest_key, "-", "+") == [
+            [0, b'3'], [10, b'30'], [20, b'300']
+        ]
+
+        # upsert into the oldest closed bucket
+        self.add_sample(source_key, 3, 4)
+
+        assert self.client.execute_command("TS.RANGE", dest_key, "-", "+") == [
+            [0, b'7'], [10, b'30'], [20, b'300']
+        ]
 
     def test_compaction_across_multiple_destination_series(self):
         """Test that one source can compact to multiple destinations with different rules"""
@@ -329,6 +352,30 @@ class TestCompactionAdd(ValkeyTimeSeriesTestCaseBase):
         dest_samples = self.client.execute_command("TS.RANGE", dest_key, "-", "+")
         assert len(dest_samples) >= 1
 
+    def test_compaction_keeps_bucket_past_source_retention(self):
+        """Raw samples expire from a short-retention source while the rollup keeps their aggregate"""
+        source_key = "test:source:short"
+        dest_key = "test:dest:long"
+
+        self.create_source_and_dest_series(source_key, dest_key, 100)
+        self.add_compaction_rule(source_key, dest_key, "sum", 1000)
+
+        for ts, value in [(10, 1.0), (20, 2.0), (30, 3.0), (500, 4.0)]:
+            self.add_sample(source_key, ts, value)
+
+        # the first samples are now past the source retention
+        assert self.client.execute_command("TS.RANGE", source_key, "-", "+") == [[500, b"4"]]
+
+        # an out-of-order sample in the open bucket must not drop the expired samples from it
+        self.add_sample(source_key, 450, 5.0)
+        # close the bucket
+        self.add_sample(source_key, 1500, 6.0)
+
+        dest_samples = self.client.execute_command("TS.RANGE", dest_key, "-", "+")
+        assert len(dest_samples) == 1
+        assert dest_samples[0][0] == 0
+        assert float(dest_samples[0][1]) == 15.0
+
     def _test_compaction_error_handling(self):
         """Test compaction handles errors gracefully"""
         source_key = "test:source:error"
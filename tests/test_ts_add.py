@@ -112,6 +112,48 @@ class TestTimeseriesAdd(ValkeyTimeSeriesTestCaseBase):
         info = self.ts_info("ts_chunk_size")
         assert info["chunkSize"] == chunk_size
 
+    def test_add_auto_create_with_options(self):
+        """Test that create options given on TS.ADD apply to the auto-created series"""
+        self.client.execute_command(
+            "TS.ADD", "ts_auto_opts", 1000, 1.5,
+            "RETENTION", 60000, "ENCODING", "UNCOMPRESSED", "CHUNK_SIZE", 8192,
+            "LABELS", "sensor", "a"
+        )
+
+        info = self.ts_info("ts_auto_opts")
+        assert info["retentionTime"] == 60000
+        assert info["chunkType"] == "uncompressed"
+        assert info["chunkSize"] == 8192
+        assert info["labels"]["sensor"] == "a"
+
+    def test_add_existing_series_ignores_options(self):
+        """Test that create options on TS.ADD are ignored if the series already exists"""
+        self.client.execute_command("TS.CREATE", "ts_existing", "RETENTION", 5000, "CHUNK_SIZE", 4096)
+
+        result = self.client.execute_command(
+            "TS.ADD", "ts_existing", 1000, 2.5,
+            "RETENTION", 60000, "ENCODING", "UNCOMPRESSED", "CHUNK_SIZE", 8192
+        )
+        assert result == 1000
+
+        info = self.ts_info("ts_existing")
+        assert info["retentionTime"] == 5000
+        assert info["chunkSize"] == 4096
+        assert info["chunkType"] == "compressed"
+        assert self.client.execute_command("TS.RANGE", "ts_existing", "-", "+") == [[1000, b'2.5']]
+
+    def test_add_on_duplicate_existing_series(self):
+        """Test that ON_DUPLICATE overrides the policy of an existing series"""
+        self.client.execute_command("TS.CREATE", "ts_ondup", "DUPLICATE_POLICY", "BLOCK")
+        self.client.execute_command("TS.ADD", "ts_ondup", 1000, 1)
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command("TS.ADD", "ts_ondup", 1000, 2)
+
+        assert self.client.execute_command("TS.ADD", "ts_ondup", 1000, 3, "ON_DUPLICATE", "LAST") == 1000
+        assert self.client.execute_command("TS.ADD", "ts_ondup", 1000, 5, "ON_DUPLICATE", "SUM") == 1000
+        assert self.client.execute_command("TS.RANGE", "ts_ondup", "-", "+") == [[1000, b'8']]
+
     def test_add_with_duplicate_policy(self):
         """Test TS.ADD with different duplicate policies"""
 
@@ -185,6 +227,21 @@ class TestTimeseriesAdd(ValkeyTimeSeriesTestCaseBase):
             )
         assert "duplicate" in str(exception_info.value)
 
+    def test_add_repeated_last_sample(self):
+        """Test re-adding the last sample: a no-op under LAST, rejected under BLOCK"""
+        timestamp = 160000
+        self.client.execute_command("TS.CREATE", "ts_repeat_last", "DUPLICATE_POLICY", "LAST")
+        self.client.execute_command("TS.ADD", "ts_repeat_last", timestamp, 10.0)
+        assert self.client.execute_command("TS.ADD", "ts_repeat_last", timestamp, 10.0) == timestamp
+        assert self.client.execute_command("TS.RANGE", "ts_repeat_last", "-", "+") == [[timestamp, b'10']]
+
+        self.client.execute_command("TS.CREATE", "ts_repeat_block", "DUPLICATE_POLICY", "BLOCK")
+        self.client.execute_command("TS.ADD", "ts_repeat_block", timestamp, 10.0)
+        for value in [10.0, 20.0]:
+            with pytest.raises(ResponseError, match="duplicate"):
+                self.client.execute_command("TS.ADD", "ts_repeat_block", timestamp, value)
+        assert self.client.execute_command("TS.RANGE", "ts_repeat_block", "-", "+") == [[timestamp, b'10']]
+
     def test_add_with_labels_creation(self):
         """Test TS.ADD with labels when creating a new timeseries"""
         timestamp = 160000
@@ -268,6 +325,27 @@ class TestTimeseriesAdd(ValkeyTimeSeriesTestCaseBase):
         samples = self.client.execute_command("TS.RANGE", "ts_decimal", "-", "+")
         assert samples[0][1] == b'123.46'  # Rounded to 2 decimal places
 
+    def test_add_with_rounding_multiple(self):
+        """Test TS.ADD with ROUNDING MULTIPLE"""
+        self.client.execute_command(
+            "TS.ADD", "ts_multiple", 1000, 1.1, "ROUNDING", "MULTIPLE", 0.25
+        )
+        self.client.execute_command("TS.ADD", "ts_multiple", 2000, -1.2)
+
+        samples = self.client.execute_command("TS.RANGE", "ts_multiple", "-", "+")
+        assert samples == [[1000, b'1'], [2000, b'-1.25']]
+
+        for step in [0, -0.25, 'abc']:
+            with pytest.raises(ResponseError):
+                self.client.execute_command(
+                    "TS.ADD", "ts_multiple_bad", 1000, 1.1, "ROUNDING", "MULTIPLE", step
+                )
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command(
+                "TS.ADD", "ts_multiple_bad", 1000, 1.1, "DECIMAL_DIGITS", 2, "ROUNDING", "MULTIPLE", 0.5
+            )
+
     def test_add_with_significant_digits(self):
         """Test TS.ADD with a significant digits option"""
         timestamp = 160000
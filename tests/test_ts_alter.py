@@ -5,7 +5,6 @@ from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
 from valkeytestframework.conftest import resource_port_tracker
 
 
-# todo: validate index changes when labels are altered
 class TestTimeSeriesAlter(ValkeyTimeSeriesTestCaseBase):
 
     def setup_data(self):
@@ -59,6 +58,26 @@ class TestTimeSeriesAlter(ValkeyTimeSeriesTestCaseBase):
         assert labels['sensor'] == 'temp'
         assert labels['area'] == 'A1'
 
+    def test_alter_encoding(self):
+        """Test re-encoding an existing series"""
+        self.setup_data()
+        for ts in range(2000, 12000, 10):
+            self.client.execute_command('TS.ADD', self.key, ts, ts % 97)
+        expected = self.client.execute_command('TS.RANGE', self.key, '-', '+')
+
+        assert self.client.execute_command('TS.ALTER', self.key, 'ENCODING', 'UNCOMPRESSED') == b'OK'
+        info = self.ts_info(self.key)
+        assert info['chunkType'] == 'uncompressed'
+        assert self.client.execute_command('TS.RANGE', self.key, '-', '+') == expected
+
+        assert self.client.execute_command('TS.ALTER', self.key, 'ENCODING', 'PCO') == b'OK'
+        info = self.ts_info(self.key)
+        assert info['chunkType'] == 'compressed'
+        assert self.client.execute_command('TS.RANGE', self.key, '-', '+') == expected
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.ALTER', self.key, 'ENCODING', 'INVALID')
+
     def test_alter_labels(self):
         """Test altering labels"""
         self.setup_data()
@@ -83,6 +102,28 @@ class TestTimeSeriesAlter(ValkeyTimeSeriesTestCaseBase):
 
         assert labels is None or len(labels) == 0
 
+    def test_alter_labels_reindexes(self):
+        """Test that altered labels are reflected in the index"""
+        self.client.execute_command('TS.CREATE', 'ts:reindex', 'LABELS', 'region', 'us', 'team', 'a')
+
+        assert self.client.execute_command('TS.ALTER', 'ts:reindex', 'LABELS', 'region', 'eu') == b'OK'
+
+        assert self.client.execute_command('TS.QUERYINDEX', 'region=eu') == [b'ts:reindex']
+        assert self.client.execute_command('TS.QUERYINDEX', 'region=us') == []
+        assert self.client.execute_command('TS.QUERYINDEX', 'team=a') == []
+
+    def test_alter_labels_duplicate_metric_name(self):
+        """Test that ALTER cannot create a duplicate metric name"""
+        self.client.execute_command('TS.CREATE', 'ts:first', 'LABELS', '__name__', 'cpu', 'host', 'a')
+        self.client.execute_command('TS.CREATE', 'ts:second', 'LABELS', '__name__', 'cpu', 'host', 'b')
+
+        with pytest.raises(ResponseError, match="duplicate series: existing key 'ts:first'"):
+            self.client.execute_command('TS.ALTER', 'ts:second', 'LABELS', '__name__', 'cpu', 'host', 'a')
+
+        # the rejected series keeps its labels and stays queryable
+        assert self.client.execute_command('TS.QUERYINDEX', 'host=b') == [b'ts:second']
+        assert self.ts_info('ts:second')['labels']['host'] == 'b'
+
     def test_alter_duplicate_policy(self):
         """Test altering the duplicate policy"""
         self.setup_data()
@@ -103,6 +144,34 @@ class TestTimeSeriesAlter(ValkeyTimeSeriesTestCaseBase):
         assert labels['sensor'] == 'temp'
         assert labels['area'] == 'A1'
 
+    def test_alter_ignore(self):
+        """Test that IGNORE can be set with ALTER and survives unrelated changes"""
+        self.setup_data()
+
+        assert self.client.execute_command(
+            'TS.ALTER', self.key, 'DUPLICATE_POLICY', 'LAST', 'IGNORE', 100, 0.5
+        ) == b'OK'
+        info = self.ts_info(self.key)
+        assert info['duplicatePolicy'] == 'last'
+        assert info['ignoreMaxTimeDiff'] == 100
+        assert info['ignoreMaxValDiff'] == '0.5'
+
+        # altering something else keeps the thresholds and the policy
+        assert self.client.execute_command('TS.ALTER', self.key, 'RETENTION', 60000) == b'OK'
+        info = self.ts_info(self.key)
+        assert info['duplicatePolicy'] == 'last'
+        assert info['ignoreMaxTimeDiff'] == 100
+        assert info['ignoreMaxValDiff'] == '0.5'
+
+        # within the window and value threshold of the last sample (1000, 25)
+        assert self.client.execute_command('TS.ADD', self.key, 1050, 25.3) == 1000
+        # a larger value change is stored
+        assert self.client.execute_command('TS.ADD', self.key, 1060, 27) == 1060
+        # as is a sample past the window
+        assert self.client.execute_command('TS.ADD', self.key, 1200, 27) == 1200
+        samples = self.client.execute_command('TS.RANGE', self.key, '-', '+')
+        assert [s[0] for s in samples] == [1000, 1060, 1200]
+
     def test_alter_multiple_properties(self):
         """Test altering multiple properties at once"""
         self.setup_data()
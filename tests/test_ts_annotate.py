@@ -0,0 +1,110 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.util.waiters import *
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTsAnnotate(ValkeyTimeSeriesTestCaseBase):
+
+    def setup_series(self, *args):
+        self.client.execute_command('TS.CREATE', 'ts1', *args)
+        for ts in range(1000, 6000, 1000):
+            self.client.execute_command('TS.ADD', 'ts1', ts, ts / 1000)
+
+    def test_range_with_annotations(self):
+        self.setup_series()
+        assert self.client.execute_command('TS.ANNOTATE', 'ts1', 2000, 'deploy v1') == 2000
+        # an annotation does not need a sample at its timestamp
+        assert self.client.execute_command('TS.ANNOTATE', 'ts1', 3500, 'incident') == 3500
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 5000, 'deploy v2')
+
+        result = self.client.execute_command('TS.RANGE', 'ts1', 1500, 4000, 'WITHANNOTATIONS')
+        assert result == [
+            [[2000, b'2'], [3000, b'3'], [4000, b'4']],
+            [[2000, b'deploy v1'], [3500, b'incident']],
+        ]
+
+        result = self.client.execute_command('TS.REVRANGE', 'ts1', '-', '+', 'WITHANNOTATIONS')
+        assert result[1] == [[5000, b'deploy v2'], [3500, b'incident'], [2000, b'deploy v1']]
+
+        # annotations are returned alongside aggregations, and ignore the sample filters
+        result = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'FILTER_BY_VALUE', 10, 20,
+                                             'AGGREGATION', 'sum', 2000, 'WITHANNOTATIONS')
+        assert result == [[], [[2000, b'deploy v1'], [3500, b'incident'], [5000, b'deploy v2']]]
+
+        # the reply shape is unchanged without WITHANNOTATIONS
+        assert self.client.execute_command('TS.RANGE', 'ts1', 1000, 1000) == [[1000, b'1']]
+
+    def test_replace_and_remove(self):
+        self.setup_series()
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 2000, 'first')
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 2000, 'second')
+        result = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'WITHANNOTATIONS')
+        assert result[1] == [[2000, b'second']]
+
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 2000, '')
+        result = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'WITHANNOTATIONS')
+        assert result[1] == []
+
+    def test_del_removes_annotations(self):
+        self.setup_series()
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 2000, 'a')
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 2500, 'b')
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 4000, 'c')
+
+        assert self.client.execute_command('TS.DEL', 'ts1', 2000, 3000) == 2
+        result = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'WITHANNOTATIONS')
+        assert result[1] == [[4000, b'c']]
+
+    def test_annotations_respect_retention(self):
+        self.setup_series('RETENTION', 2000)
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 3500, 'kept')
+
+        with pytest.raises(ResponseError, match="annotation timestamp exceeds retention period"):
+            self.client.execute_command('TS.ANNOTATE', 'ts1', 2000, 'too old')
+
+        # an out-of-order insert trims the expired samples and annotations
+        self.client.execute_command('TS.ADD', 'ts1', 10000, 10)
+        self.client.execute_command('TS.ADD', 'ts1', 9000, 9)
+        result = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'WITHANNOTATIONS')
+        assert result == [[[9000, b'9'], [10000, b'10']], []]
+
+    def test_annotations_survive_reload(self):
+        self.setup_series()
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 1000, 'deploy')
+        self.client.execute_command('TS.ANNOTATE', 'ts1', 4000, 'incident')
+        before = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'WITHANNOTATIONS')
+
+        self.client.execute_command('DEBUG', 'RELOAD')
+
+        after = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'WITHANNOTATIONS')
+        assert after == before
+        assert after[1] == [[1000, b'deploy'], [4000, b'incident']]
+
+    def test_errors(self):
+        self.setup_series()
+
+        with pytest.raises(ResponseError, match="wrong number of arguments"):
+            self.client.execute_command('TS.ANNOTATE', 'ts1', 1000)
+
+        with pytest.raises(ResponseError, match="the key does not exist"):
+            self.client.execute_command('TS.ANNOTATE', 'missing', 1000, 'note')
+
+        with pytest.raises(ResponseError, match="annotation text is too long"):
+            self.client.execute_command('TS.ANNOTATE', 'ts1', 1000, 'x' * 1025)
+
+    def test_annotation_limit(self):
+        self.client.execute_command('TS.CREATE', 'notes')
+        pipe = self.client.pipeline(transaction=False)
+        for ts in range(10000):
+            pipe.execute_command('TS.ANNOTATE', 'notes', ts, 'n')
+        pipe.execute()
+
+        with pytest.raises(ResponseError, match="the series has too many annotations"):
+            self.client.execute_command('TS.ANNOTATE', 'notes', 10000, 'n')
+
+        # replacing or removing an existing annotation is still allowed
+        assert self.client.execute_command('TS.ANNOTATE', 'notes', 0, 'replaced') == 0
+        assert self.client.execute_command('TS.ANNOTATE', 'notes', 1, '') == 1
+        assert self.client.execute_command('TS.ANNOTATE', 'notes', 10000, 'n') == 10000
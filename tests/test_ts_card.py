@@ -370,3 +370,31 @@ class TestTsCard(ValkeyTimeSeriesTestCaseBase):
         query_result = self.client.execute_command('TS.QUERYINDEX', *complex_filter)
 
         assert card_result == len(query_result)
+
+    def test_card_withlabels(self):
+        """Test TS.CARD WITHLABELS returns distinct value counts per label"""
+
+        self.setup_data()
+
+        def to_dict(reply):
+            return {reply[i].decode(): reply[i + 1] for i in range(0, len(reply), 2)}
+
+        result = to_dict(self.client.execute_command('TS.CARD', 'WITHLABELS', 'FILTER', 'sensor=temp'))
+        assert result['totalSeries'] == 2
+        assert 'complete' not in result
+        assert result['valueCountByLabelName'] == [[b'area', 2], [b'location', 2], [b'sensor', 1]]
+
+        # LIMIT keeps the labels with the most distinct values, ties broken by name
+        result = to_dict(self.client.execute_command('TS.CARD', 'WITHLABELS', 'LIMIT', 1,
+                                                     'FILTER', 'area=~"A|B|C"'))
+        assert result['totalSeries'] == 4
+        assert result['valueCountByLabelName'] == [[b'area', 3]]
+
+        # only series with samples in the range are considered
+        result = to_dict(self.client.execute_command('TS.CARD', 'WITHLABELS', 'FILTER_BY_RANGE', 2600, 3000,
+                                                     'FILTER', 'area=~"A|B|C"'))
+        assert result['totalSeries'] == 1
+        assert result['valueCountByLabelName'] == [[b'area', 1], [b'location', 1], [b'sensor', 1]]
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.CARD', 'WITHLABELS')
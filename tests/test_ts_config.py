@@ -2,6 +2,7 @@
 import time
 
 import pytest
+from valkey import ResponseError
 from valkeytestframework.conftest import resource_port_tracker
 from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
 
@@ -24,6 +25,8 @@ class TestTimeseriesConfig(ValkeyTimeSeriesTestCaseBase):
         self.set_config("ts-chunk-size", DEFAULT_CHUNK_SIZE)
         self.set_config("ts-duplicate-policy", DEFAULT_DUPLICATE_POLICY)
         self.set_config("ts-retention-policy", DEFAULT_RETENTION)
+        self.set_config("ts-max-series", 0)
+        self.set_config("ts-max-future-skew", 0)
 
     def test_config_chunk_size_applies_to_new_series(self):
         key = "ts_cfg_chunksize"
@@ -438,3 +441,60 @@ class TestTimeseriesConfig(ValkeyTimeSeriesTestCaseBase):
                 self.assert_compaction_rule(key, "avg", 10000)
             else:
                 self.assert_no_compaction_rules(key)
+
+    def test_config_max_series_rejects_new_series(self):
+        try:
+            self.set_config("ts-max-series", 3)
+            for i in range(3):
+                self.client.execute_command("TS.CREATE", f"ts_cfg_max_series_{i}")
+
+            with pytest.raises(ResponseError, match="maximum number of series"):
+                self.client.execute_command("TS.CREATE", "ts_cfg_max_series_3")
+            # a series is also created implicitly by TS.ADD
+            with pytest.raises(ResponseError, match="maximum number of series"):
+                self.client.execute_command("TS.ADD", "ts_cfg_max_series_3", 1000, 1)
+            assert self.client.execute_command("EXISTS", "ts_cfg_max_series_3") == 0
+
+            # deleting a series makes room for another
+            self.client.execute_command("DEL", "ts_cfg_max_series_0")
+            self.client.execute_command("TS.CREATE", "ts_cfg_max_series_3")
+        finally:
+            self.reset_defaults()
+
+    def test_config_max_future_skew_rejects_future_samples(self):
+        key = "ts_cfg_future_skew"
+        now = int(time.time() * 1000)
+        hour = 60 * 60 * 1000
+        try:
+            self.set_config("ts-max-future-skew", "1h")
+
+            # a rejected sample must not create the series
+            with pytest.raises(ResponseError, match="maximum future skew"):
+                self.client.execute_command("TS.ADD", key, now + 2 * hour, 1)
+            assert self.client.execute_command("EXISTS", key) == 0
+
+            assert self.client.execute_command("TS.ADD", key, "*", 1) > 0
+            assert self.client.execute_command("TS.ADD", key, now + hour // 2, 2) == now + hour // 2
+            with pytest.raises(ResponseError, match="maximum future skew"):
+                self.client.execute_command("TS.ADD", key, now + 2 * hour, 3)
+
+            result = self.client.execute_command("TS.MADD",
+                                                 key, now + hour // 4, 4,
+                                                 key, now + 2 * hour, 5)
+            assert result[0] == now + hour // 4
+            assert str(result[1]) == "TOO_NEW TSDB: sample timestamp exceeds the maximum future skew"
+
+            with pytest.raises(ResponseError, match="maximum future skew"):
+                self.client.execute_command("TS.INCRBY", key, 1, "TIMESTAMP", now + 2 * hour)
+
+            # a single sample too far ahead rejects the whole batch
+            payload = f'{{"values":[7,8],"timestamps":[{now + hour // 2 + 1},{now + 2 * hour}]}}'
+            with pytest.raises(ResponseError, match="maximum future skew"):
+                self.client.execute_command("TS.ADDBULK", key, payload)
+            assert self.client.execute_command("TS.GET", key)[0] == now + hour // 2
+
+            # disabling the config accepts any timestamp again
+            self.set_config("ts-max-future-skew", 0)
+            assert self.client.execute_command("TS.ADD", key, now + 2 * hour, 6) == now + 2 * hour
+        finally:
+            self.reset_defaults()
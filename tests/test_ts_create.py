@@ -59,6 +59,31 @@ class TestTimeSeriesBasic(ValkeyTimeSeriesTestCaseBase):
             else:
                 assert info['chunkType'] == 'compressed'
 
+    def test_create_with_auto_encoding(self):
+        """Test that ENCODING AUTO starts uncompressed and compresses once the first chunk fills"""
+        client = self.server.get_new_client()
+        assert client.execute_command("TS.CREATE", "auto", "ENCODING", "AUTO", "CHUNK_SIZE", 128) == b'OK'
+        assert self.ts_info("auto")['chunkType'] == 'uncompressed'
+
+        # the pending choice survives a reload
+        client.execute_command("DEBUG", "RELOAD")
+        assert self.ts_info("auto")['chunkType'] == 'uncompressed'
+
+        for i in range(500):
+            client.execute_command("TS.ADD", "auto", 1000 + i * 10, i)
+        assert self.ts_info("auto")['chunkType'] == 'compressed'
+
+        samples = client.execute_command("TS.RANGE", "auto", "-", "+")
+        assert len(samples) == 500
+        assert [int(float(v)) for _, v in samples] == list(range(500))
+
+        # an explicit encoding cancels AUTO
+        assert client.execute_command("TS.CREATE", "fixed", "ENCODING", "AUTO", "ENCODING", "UNCOMPRESSED",
+                                      "CHUNK_SIZE", 128) == b'OK'
+        for i in range(500):
+            client.execute_command("TS.ADD", "fixed", 1000 + i * 10, i)
+        assert self.ts_info("fixed")['chunkType'] == 'uncompressed'
+
     def test_create_with_chunk_size(self):
         """Test creating time series with different chunk sizes"""
         client = self.server.get_new_client()
@@ -98,6 +123,16 @@ class TestTimeSeriesBasic(ValkeyTimeSeriesTestCaseBase):
         assert labels['__name__'] == 'temperature'
         assert labels['city'] == 'CDMX'
 
+    def test_create_duplicate_metric_name(self):
+        """Test that a duplicate metric name is rejected and the existing key is reported"""
+        self.client.execute_command('TS.CREATE', 'ts:first', 'LABELS', '__name__', 'cpu', 'host', 'a')
+
+        with pytest.raises(ResponseError) as excinfo:
+            self.client.execute_command('TS.CREATE', 'ts:second', 'LABELS', '__name__', 'cpu', 'host', 'a')
+        assert "duplicate series" in str(excinfo.value)
+        assert "ts:first" in str(excinfo.value)
+        assert self.client.execute_command('EXISTS', 'ts:second') == 0
+
     def test_create_errors(self):
         """Test error cases for TS.CREATE"""
         client = self.server.get_new_client()
@@ -156,3 +191,138 @@ class TestTimeSeriesBasic(ValkeyTimeSeriesTestCaseBase):
 
         # Verify count in database
         assert client.execute_command("DBSIZE") == 10
+
+    def test_create_with_retention_samples(self):
+        """Test that RETENTION_SAMPLES keeps only the newest samples"""
+        client = self.server.get_new_client()
+        max_samples, extra = 100, 25
+        assert client.execute_command("TS.CREATE", "capped", "RETENTION_SAMPLES", max_samples) == b'OK'
+        assert self.ts_info("capped")['retentionSamples'] == max_samples
+
+        for i in range(max_samples + extra):
+            client.execute_command("TS.ADD", "capped", 1000 + i, i)
+
+        samples = client.execute_command("TS.RANGE", "capped", "-", "+")
+        assert len(samples) == max_samples
+        # the oldest `extra` samples are gone
+        assert samples[0][0] == 1000 + extra
+        assert samples[-1][0] == 1000 + max_samples + extra - 1
+        assert self.ts_info("capped")['totalSamples'] == max_samples
+
+        # the limit survives a reload
+        client.execute_command("DEBUG", "RELOAD")
+        assert self.ts_info("capped")['retentionSamples'] == max_samples
+
+        # ALTER applies a lower limit immediately, and 0 removes it
+        assert client.execute_command("TS.ALTER", "capped", "RETENTION_SAMPLES", 10) == b'OK'
+        assert len(client.execute_command("TS.RANGE", "capped", "-", "+")) == 10
+        assert client.execute_command("TS.ALTER", "capped", "RETENTION_SAMPLES", 0) == b'OK'
+        client.execute_command("TS.ADD", "capped", 5000, 1)
+        assert len(client.execute_command("TS.RANGE", "capped", "-", "+")) == 11
+
+        with pytest.raises(ResponseError, match="RETENTION_SAMPLES must be a non-negative integer"):
+            client.execute_command("TS.CREATE", "bad", "RETENTION_SAMPLES", -1)
+        with pytest.raises(ResponseError, match="RETENTION_SAMPLES must be a non-negative integer"):
+            client.execute_command("TS.CREATE", "bad", "RETENTION_SAMPLES", "abc")
+
+    def test_create_with_split_policy(self):
+        """Test that SPLIT_POLICY is stored, reported and survives a reload"""
+        client = self.server.get_new_client()
+        assert client.execute_command("TS.CREATE", "default_split") == b'OK'
+        assert self.ts_info("default_split")['splitPolicy'] == 'half'
+
+        for key, policy, expected in [("ins", "INSERTION", "insertion"), ("ratio", "90", "90"), ("half", "50", "half")]:
+            assert client.execute_command("TS.CREATE", key, "ENCODING", "UNCOMPRESSED", "SPLIT_POLICY", policy) == b'OK'
+            assert self.ts_info(key)['splitPolicy'] == expected
+
+        # out-of-order inserts into full chunks keep every sample
+        for i in range(2000):
+            client.execute_command("TS.ADD", "ratio", 1000 + i * 10, i)
+        for i in range(2000):
+            client.execute_command("TS.ADD", "ratio", 1005 + i * 10, i)
+        info = self.ts_info("ratio")
+        assert info['totalSamples'] == 4000
+        assert info['chunkSplits'] > 0
+        assert len(client.execute_command("TS.RANGE", "ratio", "-", "+")) == 4000
+
+        client.execute_command("DEBUG", "RELOAD")
+        assert self.ts_info("ins")['splitPolicy'] == 'insertion'
+        assert self.ts_info("ratio")['splitPolicy'] == '90'
+
+        assert client.execute_command("TS.ALTER", "ratio", "SPLIT_POLICY", "insertion") == b'OK'
+        assert self.ts_info("ratio")['splitPolicy'] == 'insertion'
+
+        for bad in ["0", "100", "third", "-5"]:
+            with pytest.raises(ResponseError, match="invalid SPLIT_POLICY"):
+                client.execute_command("TS.CREATE", "bad", "SPLIT_POLICY", bad)
+        with pytest.raises(ResponseError, match="invalid SPLIT_POLICY"):
+            client.execute_command("TS.CREATE", "bad", "SPLIT_POLICY")
+
+    def test_create_with_timestamp_unit(self):
+        """Test that timestamps round-trip in each TIMESTAMP_UNIT and survive a reload"""
+        client = self.server.get_new_client()
+        assert client.execute_command("TS.CREATE", "default_unit") == b'OK'
+        assert self.ts_info("default_unit")['timestampUnit'] == 'ms'
+
+        cases = [("sec", "s", [10, 11, 12]),
+                 ("milli", "ms", [10_000, 11_000, 12_000]),
+                 ("micro", "us", [10_000_000, 11_001_000, 12_000_000]),
+                 ("nano", "NS", [10_000_000_000, 11_000_000_000, 12_005_000_000])]
+        for key, unit, timestamps in cases:
+            assert client.execute_command("TS.CREATE", key, "TIMESTAMP_UNIT", unit) == b'OK'
+            for i, ts in enumerate(timestamps):
+                assert client.execute_command("TS.ADD", key, ts, i) == ts
+            expected = [[ts, str(i).encode()] for i, ts in enumerate(timestamps)]
+            assert client.execute_command("TS.RANGE", key, "-", "+") == expected
+            assert client.execute_command("TS.REVRANGE", key, "-", "+") == expected[::-1]
+            assert client.execute_command("TS.RANGE", key, timestamps[1], timestamps[2]) == expected[1:]
+            assert client.execute_command("TS.RANGE", key, "-", "+", "FILTER_BY_TS", timestamps[1]) == expected[1:2]
+            assert client.execute_command("TS.GET", key) == expected[-1]
+            assert client.execute_command("TS.GET", key, "AT", timestamps[1]) == expected[1]
+            info = self.ts_info(key)
+            assert info['firstTimestamp'] == timestamps[0]
+            assert info['lastTimestamp'] == timestamps[-1]
+
+        # samples are stored in ms, so a range bound in seconds covers the whole second
+        assert client.execute_command("TS.CREATE", "coarse", "TIMESTAMP_UNIT", "s") == b'OK'
+        client.execute_command("TS.ADD", "coarse", "1970-01-01T00:00:10.500Z", 1)
+        assert client.execute_command("TS.RANGE", "coarse", 10, 10) == [[10, b'1']]
+        assert client.execute_command("TS.RANGE", "coarse", 0, 9) == []
+        assert client.execute_command("TS.RANGE", "coarse", "-", "+", "FILTER_BY_TS", 10) == [[10, b'1']]
+
+        client.execute_command("DEBUG", "RELOAD")
+        assert self.ts_info("micro")['timestampUnit'] == 'us'
+        assert client.execute_command("TS.GET", "sec") == [12, b'2']
+
+        assert client.execute_command("TS.ALTER", "sec", "TIMESTAMP_UNIT", "ms") == b'OK'
+        assert client.execute_command("TS.GET", "sec") == [12_000, b'2']
+
+        # the unit also applies to a series created by TS.ADD
+        assert client.execute_command("TS.ADD", "added", 5, 1, "TIMESTAMP_UNIT", "s") == 5
+        assert client.execute_command("TS.RANGE", "added", "-", "+") == [[5, b'1']]
+
+        with pytest.raises(ResponseError, match="out of range for the series unit"):
+            client.execute_command("TS.ADD", "coarse", 2 ** 62, 1)
+        for bad in ["minutes", "1"]:
+            with pytest.raises(ResponseError, match="invalid TIMESTAMP_UNIT"):
+                client.execute_command("TS.CREATE", "bad", "TIMESTAMP_UNIT", bad)
+        with pytest.raises(ResponseError, match="invalid TIMESTAMP_UNIT"):
+            client.execute_command("TS.CREATE", "bad", "TIMESTAMP_UNIT")
+
+    def test_timestamp_unit_in_other_commands(self):
+        """Test that commands taking or returning timestamps use the series TIMESTAMP_UNIT"""
+        client = self.server.get_new_client()
+        assert client.execute_command("TS.CREATE", "left", "TIMESTAMP_UNIT", "s") == b'OK'
+        assert client.execute_command("TS.CREATE", "right", "TIMESTAMP_UNIT", "s") == b'OK'
+
+        assert client.execute_command("TS.MADD", "left", 10, 1, "left", 11, 2, "right", 11, 5) == [10, 11, 11]
+        assert client.execute_command("TS.INCRBY", "left", 3, "TIMESTAMP", 12) == 12
+        assert client.execute_command("TS.RANGE", "left", "-", "+") == [[10, b'1'], [11, b'2'], [12, b'5']]
+
+        assert client.execute_command("TS.JOIN", "left", "right", 11, 11) == [[[11, b'2'], [11, b'5']]]
+
+        assert client.execute_command("TS.INCRBY", "counter", 1, "TIMESTAMP", 7, "TIMESTAMP_UNIT", "s") == 7
+        assert client.execute_command("TS.RANGE", "counter", "-", "+") == [[7, b'1']]
+
+        assert client.execute_command("TS.DEL", "left", 10, 11) == 2
+        assert client.execute_command("TS.RANGE", "left", "-", "+") == [[12, b'5']]
@@ -160,6 +160,7 @@ class TestTimeSeriesDebug(ValkeyTimeSeriesTestCaseDebugMode):
             'ts-ignore-max-time-diff',
             'ts-ignore-max-value-diff',
             'ts-num-threads',
+            'ts-max-future-skew',
             'ts-fanout-command-timeout',
             'ts-cluster-map-expiration-ms',
         ]
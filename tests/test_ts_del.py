@@ -125,3 +125,30 @@ class TestTsDel(ValkeyTimeSeriesTestCaseBase):
         assert len(result) == 4
         timestamps = [entry[0] for entry in result]
         assert 1020 not in timestamps
+
+    def test_del_dryrun_matches_delete(self):
+        """DRYRUN reports exactly what the delete removes, without modifying the series"""
+        self.client.execute_command('TS.CREATE', 'ts9', 'CHUNK_SIZE', 128)
+        args = []
+        for i in range(1000):
+            args += ['ts9', 1000 + i * 10, i]
+        self.client.execute_command('TS.MADD', *args)
+
+        ranges = [(1000, 1000), (1005, 1095), (1234, 5678), ('-', '+'), (20000, 30000)]
+        for start, end in ranges:
+            before = self.client.execute_command('TS.RANGE', 'ts9', '-', '+')
+            expected = self.client.execute_command('TS.DEL', 'ts9', start, end, 'DRYRUN')
+            assert self.client.execute_command('TS.RANGE', 'ts9', '-', '+') == before
+
+            deleted = self.client.execute_command('TS.DEL', 'ts9', start, end)
+            assert deleted == expected, f"range {start}..{end}"
+            self.client.execute_command('TS.MADD', *args)
+
+    def test_del_dryrun_errors(self):
+        self.client.execute_command('TS.CREATE', 'ts10')
+
+        with pytest.raises(Exception, match="invalid argument"):
+            self.client.execute_command('TS.DEL', 'ts10', 0, 100, 'DRYRUM')
+
+        with pytest.raises(Exception, match="key does not exist"):
+            self.client.execute_command('TS.DEL', 'missing', 0, 100, 'DRYRUN')
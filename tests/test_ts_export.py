@@ -0,0 +1,59 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTsExport(ValkeyTimeSeriesTestCaseBase):
+
+    def setup_data(self):
+        self.client.execute_command('TS.CREATE', 'latency:us', 'LABELS', 'region', 'us', 'job', 'api')
+        self.client.execute_command('TS.CREATE', 'latency:eu', 'LABELS', 'region', 'eu', 'job', 'api')
+        self.client.execute_command('TS.CREATE', 'other', 'LABELS', 'job', 'worker')
+        for i in range(5):
+            self.client.execute_command('TS.ADD', 'latency:us', 1000 + i * 1000, i)
+            self.client.execute_command('TS.ADD', 'latency:eu', 1000 + i * 1000, 10 + i)
+
+    @staticmethod
+    def flatten(frames):
+        return [(ts, float(value)) for frame in frames for ts, value in frame]
+
+    def test_export_basic(self):
+        self.setup_data()
+
+        result = self.client.execute_command('TS.EXPORT', '-', '+', 'FILTER', 'job=api')
+        assert len(result) == 2
+
+        # Series are ordered by sorted label set, labels are sorted by name
+        labels = [[(name, value) for name, value in series[0]] for series in result]
+        assert labels == [
+            [(b'job', b'api'), (b'region', b'eu')],
+            [(b'job', b'api'), (b'region', b'us')],
+        ]
+
+        eu_frames = result[0][1]
+        assert len(eu_frames) == 1
+        assert self.flatten(eu_frames) == [(1000 + i * 1000, 10.0 + i) for i in range(5)]
+
+    def test_export_batches_frames(self):
+        self.setup_data()
+
+        result = self.client.execute_command('TS.EXPORT', 1000, 4000, 'BATCH_SIZE', 3,
+                                             'FILTER', 'region=us')
+        assert len(result) == 1
+        frames = result[0][1]
+        assert [len(frame) for frame in frames] == [3, 1]
+        assert self.flatten(frames) == [(1000, 0.0), (2000, 1.0), (3000, 2.0), (4000, 3.0)]
+
+        # No samples in range means no frames
+        result = self.client.execute_command('TS.EXPORT', 50000, 60000, 'FILTER', 'region=us')
+        assert result[0][1] == []
+
+    def test_export_errors(self):
+        self.setup_data()
+
+        with pytest.raises(ResponseError, match="BATCH_SIZE must be between"):
+            self.client.execute_command('TS.EXPORT', '-', '+', 'BATCH_SIZE', 0, 'FILTER', 'job=api')
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.EXPORT', '-', '+', 'job=api', 'region=us')
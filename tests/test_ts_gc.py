@@ -0,0 +1,58 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+def to_dict(reply):
+    if isinstance(reply, dict):
+        items = reply.items()
+    else:
+        items = zip(reply[::2], reply[1::2])
+    return {(k.decode() if isinstance(k, bytes) else k): v for k, v in items}
+
+
+class TestTsGc(ValkeyTimeSeriesTestCaseBase):
+
+    def test_gc_empty_index(self):
+        stats = to_dict(self.client.execute_command('TS.GC'))
+        assert stats['staleIdsRemoved'] == 0
+        assert stats['emptyPostingsRemoved'] == 0
+        assert stats['bitmapsOptimized'] == 0
+        assert stats['batches'] >= 1
+
+    def test_gc_optimizes_all_postings_in_batches(self):
+        for i in range(10):
+            self.client.execute_command('TS.CREATE', f'gc:{i}', 'LABELS', 'pod', f'pod-{i}', 'env', 'prod')
+
+        stats = to_dict(self.client.execute_command('TS.GC', 'COUNT', 3))
+        # 10 pod values + env=prod
+        assert stats['bitmapsOptimized'] == 11
+        assert stats['staleIdsRemoved'] == 0
+        assert stats['batches'] > 2
+
+        # the index is intact after the pass
+        keys = self.client.execute_command('TS.QUERYINDEX', 'env=prod')
+        assert len(keys) == 10
+
+    def test_gc_after_deletes(self):
+        for i in range(5):
+            self.client.execute_command('TS.CREATE', f'gc:del:{i}', 'LABELS', 'pod', f'pod-{i}')
+        for i in range(5):
+            self.client.execute_command('DEL', f'gc:del:{i}')
+
+        stats = to_dict(self.client.execute_command('TS.GC'))
+        assert stats['bitmapsOptimized'] == 0
+        assert self.client.execute_command('TS.QUERYINDEX', 'pod=~"pod-.*"') == []
+
+    def test_gc_invalid_args(self):
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.GC', 'COUNT')
+        with pytest.raises(ResponseError, match="COUNT must be a positive integer"):
+            self.client.execute_command('TS.GC', 'COUNT', 0)
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.GC', 'COUNT', -1)
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.GC', 'FOO')
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.GC', 'COUNT', 10, 'extra')
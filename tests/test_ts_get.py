@@ -1,6 +1,7 @@
 import math
 
 import pytest
+from valkey import ResponseError
 from valkeytestframework.util.waiters import *
 from valkeytestframework.conftest import resource_port_tracker
 from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
@@ -167,6 +168,33 @@ class TestTsGet(ValkeyTimeSeriesTestCaseBase):
         assert result == []
         assert result_latest == []
 
+    def test_get_at_timestamp(self):
+        """Test TS.GET AT with the different lookup modes"""
+        self.client.execute_command('TS.CREATE', 'ts_at')
+        self.client.execute_command('TS.MADD', 'ts_at', 1000, 10, 'ts_at', 2000, 20, 'ts_at', 4000, 40)
+
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 2500) == [2000, b'20']
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 2500, 'PREVIOUS') == [2000, b'20']
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 2500, 'NEXT') == [4000, b'40']
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 3500, 'NEAREST') == [4000, b'40']
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 3000, 'linear') == [3000, b'30']
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 2000, 'LINEAR') == [2000, b'20']
+
+        # before the first sample
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 500, 'PREVIOUS') == []
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 500, 'NEAREST') == [1000, b'10']
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 500, 'LINEAR') == []
+
+        # after the last sample
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 5000, 'NEXT') == []
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 5000, 'NEAREST') == [4000, b'40']
+        assert self.client.execute_command('TS.GET', 'ts_at', 'AT', 5000, 'LINEAR') == []
+
+        self.verify_error_response(self.client, 'TS.GET ts_at AT 1000 CUBIC',
+                                   "TSDB: invalid lookup mode. Expected PREVIOUS, NEXT, NEAREST or LINEAR")
+        self.verify_error_response(self.client, 'TS.GET ts_at AT abc',
+                                   "TSDB: invalid timestamp.")
+
     def test_get_no_args(self):
         """Test TS.GET with no arguments"""
         # Test missing key argument
@@ -191,3 +219,40 @@ class TestTsGet(ValkeyTimeSeriesTestCaseBase):
         # Get the latest sample
         result = self.client.execute_command('TS.GET', 'ts_nan')
         assert math.isnan(float(result[1]))
+
+    def test_get_by_id(self):
+        """Test TS.GET BYID returns the key and last sample of the series with that id"""
+        self.client.execute_command('TS.CREATE', 'by_id')
+        self.client.execute_command('TS.ADD', 'by_id', 1000, 1)
+        self.client.execute_command('TS.ADD', 'by_id', 2000, 2)
+        series_id = self.ts_info('by_id', True)['id']
+
+        result = self.client.execute_command('TS.GET', 'BYID', series_id)
+        assert result == [b'by_id', [2000, b'2']]
+
+        # a removed id returns nil
+        self.client.execute_command('DEL', 'by_id')
+        assert self.client.execute_command('TS.GET', 'BYID', series_id) is None
+
+        # an id that was never assigned returns nil
+        assert self.client.execute_command('TS.GET', 'BYID', 1_000_000) is None
+
+    def test_get_by_id_empty_series(self):
+        self.client.execute_command('TS.CREATE', 'by_id_empty')
+        series_id = self.ts_info('by_id_empty', True)['id']
+
+        result = self.client.execute_command('TS.GET', 'BYID', series_id)
+        assert result == [b'by_id_empty', []]
+
+    def test_get_key_named_byid(self):
+        """A series named BYID is still readable with TS.GET key [LATEST]"""
+        self.client.execute_command('TS.ADD', 'BYID', 1000, 5)
+
+        assert self.client.execute_command('TS.GET', 'BYID') == [1000, b'5']
+        assert self.client.execute_command('TS.GET', 'BYID', 'LATEST') == [1000, b'5']
+
+    def test_get_by_id_invalid(self):
+        with pytest.raises(ResponseError, match="invalid series id"):
+            self.client.execute_command('TS.GET', 'BYID', 'abc')
+        with pytest.raises(ResponseError, match="invalid series id"):
+            self.client.execute_command('TS.GET', 'BYID', -1)
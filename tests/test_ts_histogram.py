@@ -0,0 +1,66 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTsHistogram(ValkeyTimeSeriesTestCaseBase):
+
+    def setup_data(self):
+        self.client.execute_command('TS.CREATE', 'temp')
+        for i in range(101):
+            self.client.execute_command('TS.ADD', 'temp', 1000 + i * 1000, i)
+
+    @staticmethod
+    def parse(result):
+        return [(float(bound), count) for bound, count in result]
+
+    def test_histogram_buckets(self):
+        self.setup_data()
+
+        result = self.parse(self.client.execute_command('TS.HISTOGRAM', 'temp', '-', '+', 'BUCKETS', 10))
+        assert [bound for bound, _ in result] == [float(i * 10) for i in range(10)]
+        # boundary values go to the upper bin, the maximum to the last bin
+        assert [count for _, count in result] == [10] * 9 + [11]
+
+    def test_histogram_respects_range(self):
+        self.setup_data()
+
+        result = self.parse(self.client.execute_command('TS.HISTOGRAM', 'temp', 1000, 10000, 'BUCKETS', 3))
+        assert result == [(0.0, 3), (3.0, 3), (6.0, 4)]
+
+    def test_histogram_boundaries(self):
+        self.setup_data()
+
+        result = self.parse(self.client.execute_command('TS.HISTOGRAM', 'temp', '-', '+',
+                                                        'BOUNDARIES', 10, 50.5, 90))
+        assert result == [(float('-inf'), 10), (10.0, 41), (50.5, 39), (90.0, 11)]
+
+    def test_histogram_constant_and_empty(self):
+        self.client.execute_command('TS.CREATE', 'flat')
+        for i in range(5):
+            self.client.execute_command('TS.ADD', 'flat', 1000 + i, 7)
+
+        result = self.parse(self.client.execute_command('TS.HISTOGRAM', 'flat', '-', '+', 'BUCKETS', 4))
+        assert result == [(7.0, 5)]
+
+        assert self.client.execute_command('TS.HISTOGRAM', 'flat', 0, 10, 'BUCKETS', 4) == []
+        assert self.client.execute_command('TS.HISTOGRAM', 'flat', 0, 10, 'BOUNDARIES', 1) == []
+
+    def test_histogram_errors(self):
+        self.setup_data()
+
+        with pytest.raises(ResponseError, match="BUCKETS must be between 1 and 1000"):
+            self.client.execute_command('TS.HISTOGRAM', 'temp', '-', '+', 'BUCKETS', 0)
+        with pytest.raises(ResponseError, match="BUCKETS must be between 1 and 1000"):
+            self.client.execute_command('TS.HISTOGRAM', 'temp', '-', '+', 'BUCKETS', 1001)
+        with pytest.raises(ResponseError, match="strictly increasing"):
+            self.client.execute_command('TS.HISTOGRAM', 'temp', '-', '+', 'BOUNDARIES', 5, 5)
+        with pytest.raises(ResponseError, match="strictly increasing"):
+            self.client.execute_command('TS.HISTOGRAM', 'temp', '-', '+', 'BOUNDARIES', 'abc')
+        with pytest.raises(ResponseError, match="only one of BUCKETS or BOUNDARIES"):
+            self.client.execute_command('TS.HISTOGRAM', 'temp', '-', '+', 'BUCKETS', 2, 'BOUNDARIES', 1)
+        with pytest.raises(ResponseError, match="wrong number of arguments"):
+            self.client.execute_command('TS.HISTOGRAM', 'temp', '-', '+')
+        with pytest.raises(ResponseError, match="key does not exist"):
+            self.client.execute_command('TS.HISTOGRAM', 'missing', '-', '+', 'BUCKETS', 2)
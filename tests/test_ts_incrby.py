@@ -225,3 +225,38 @@ class TestTimeSeriesIncrby(ValkeyTimeSeriesTestCaseBase):
         sample = self.client.execute_command('TS.GET', 'ts_nan_sample')
         assert sample[0] == 1000
         assert sample[1].lower() == b'nan'
+
+    def test_incrby_bucket_duration(self):
+        """TS.INCRBY with BUCKET_DURATION keeps one counter per bucket"""
+        for ts in [1200, 1999]:
+            self.client.execute_command('TS.INCRBY', 'ts_bucketed', 1, 'TIMESTAMP', ts, 'BUCKET_DURATION', 1000)
+        samples = self.client.execute_command('TS.RANGE', 'ts_bucketed', '-', '+')
+        assert samples == [[1000, b'2']]
+
+        # Crossing the bucket boundary starts a new bucket
+        result = self.client.execute_command('TS.INCRBY', 'ts_bucketed', 3, 'TIMESTAMP', 2000, 'BUCKET_DURATION', '1s')
+        assert result == 2000
+        self.client.execute_command('TS.DECRBY', 'ts_bucketed', 1, 'TIMESTAMP', 2500, 'BUCKET_DURATION', '1s')
+        samples = self.client.execute_command('TS.RANGE', 'ts_bucketed', '-', '+')
+        assert samples == [[1000, b'2'], [2000, b'2']]
+
+        # Earlier buckets cannot be updated
+        self.verify_error_response(
+            self.client, 'TS.INCRBY ts_bucketed 1 TIMESTAMP 1500 BUCKET_DURATION 1000',
+            "TSDB: timestamp must be equal to or higher than the maximum existing timestamp"
+        )
+
+        # Without BUCKET_DURATION the running total behavior is unchanged
+        self.client.execute_command('TS.INCRBY', 'ts_bucketed', 1, 'TIMESTAMP', 3000)
+        sample = self.client.execute_command('TS.GET', 'ts_bucketed')
+        assert sample == [3000, b'3']
+
+    def test_incrby_bucket_duration_errors(self):
+        self.verify_error_response(
+            self.client, 'TS.INCRBY ts_bucket_err 1 BUCKET_DURATION',
+            "TSDB: missing BUCKET_DURATION value"
+        )
+        self.verify_error_response(
+            self.client, 'TS.INCRBY ts_bucket_err 1 BUCKET_DURATION 0',
+            "TSDB: invalid duration"
+        )
@@ -3,6 +3,7 @@ from valkey import ResponseError
 from valkeytestframework.util.waiters import *
 from valkeytestframework.conftest import resource_port_tracker
 from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+from common import parse_info_response
 
 
 class TestTimeseriesInfo(ValkeyTimeSeriesTestCaseBase):
@@ -26,6 +27,77 @@ class TestTimeseriesInfo(ValkeyTimeSeriesTestCaseBase):
         # assert info['rules'] == []
         assert info['duplicatePolicy'] == "block"  # default
 
+    def test_info_ingestion_stats(self):
+        """Test the ingestion statistics reported by TS.INFO"""
+        key = 'ts_stats'
+        self.client.execute_command('TS.CREATE', key)
+        info = dict(zip(*[iter(self.client.execute_command('TS.INFO', key))] * 2))
+        assert info[b'timeSpan'] == 0
+        assert float(info[b'sampleRate']) == 0.0
+        assert info[b'lastSampleAge'] is None
+
+        for i in range(11):
+            self.client.execute_command('TS.ADD', key, 1000 + i * 500, i)
+
+        info = dict(zip(*[iter(self.client.execute_command('TS.INFO', key))] * 2))
+        assert info[b'timeSpan'] == 5000
+        assert float(info[b'sampleRate']) == 2.2
+        assert info[b'dataSize'] > 0
+        assert info[b'lastSampleAge'] > 0
+
+    def test_info_compression_stats(self):
+        """Test the bytes per sample and compression ratio reported by TS.INFO"""
+        key = 'ts_compression'
+        self.client.execute_command('TS.CREATE', key, 'ENCODING', 'UNCOMPRESSED')
+        info = dict(zip(*[iter(self.client.execute_command('TS.INFO', key))] * 2))
+        assert float(info[b'bytesPerSample']) == 0.0
+        assert float(info[b'compressionRatio']) == 0.0
+
+        for i in range(100):
+            self.client.execute_command('TS.ADD', key, 1000 + i, i)
+
+        # uncompressed samples take exactly 16 bytes each
+        info = dict(zip(*[iter(self.client.execute_command('TS.INFO', key))] * 2))
+        assert info[b'dataSize'] == 1600
+        assert float(info[b'bytesPerSample']) == 16.0
+        assert float(info[b'compressionRatio']) == 1.0
+
+        key = 'ts_compressed'
+        self.client.execute_command('TS.CREATE', key, 'ENCODING', 'COMPRESSED')
+        for i in range(100):
+            self.client.execute_command('TS.ADD', key, 1000 + i, 1)
+
+        info = dict(zip(*[iter(self.client.execute_command('TS.INFO', key))] * 2))
+        bytes_per_sample = float(info[b'bytesPerSample'])
+        assert bytes_per_sample == info[b'dataSize'] / 100
+        assert float(info[b'compressionRatio']) == pytest.approx(16 / bytes_per_sample)
+        assert float(info[b'compressionRatio']) > 1.0
+
+    def test_info_out_of_order_counters(self):
+        """Test the out-of-order sample counter reported by TS.INFO"""
+        key = 'ts_ooo'
+        self.client.execute_command('TS.CREATE', key)
+        for ts in [300, 100, 500, 200, 400]:
+            self.client.execute_command('TS.ADD', key, ts, ts)
+
+        info = dict(zip(*[iter(self.client.execute_command('TS.INFO', key))] * 2))
+        assert info[b'outOfOrderSamples'] == 3
+        assert info[b'chunkSplits'] == 0
+
+    def test_info_memory_breakdown(self):
+        """Test the memory breakdown reported by TS.INFO"""
+        key = 'ts_memory'
+        self.client.execute_command('TS.CREATE', key, 'LABELS', 'host', 'breakdown-host')
+        for i in range(100):
+            self.client.execute_command('TS.ADD', key, 1000 + i * 10, i)
+
+        info = dict(zip(*[iter(self.client.execute_command('TS.INFO', key))] * 2))
+        breakdown = dict(zip(*[iter(info[b'memoryBreakdown'])] * 2))
+        assert breakdown[b'chunks'] > 0
+        assert breakdown[b'labels'] > 0
+        assert breakdown[b'index'] > 0
+        assert breakdown[b'chunks'] + breakdown[b'labels'] + breakdown[b'metadata'] == info[b'memoryUsage']
+
     def test_info_with_options(self):
         """Test TS.INFO on a time series created with options"""
         key = 'ts_options'
@@ -115,6 +187,30 @@ class TestTimeseriesInfo(ValkeyTimeSeriesTestCaseBase):
         assert first_chunk['samples'] > 0
         assert first_chunk['size'] > 0
 
+    def test_info_chunks(self):
+        """Test that TS.INFO CHUNKS lists every chunk of a multi-chunk series"""
+        key = 'ts_chunks'
+        self.client.execute_command('TS.CREATE', key, 'ENCODING', 'GORILLA', 'CHUNK_SIZE', 128)
+        for i in range(1000):
+            self.client.execute_command('TS.ADD', key, 1000 + i * 10, i % 7)
+
+        info = parse_info_response(self.client.execute_command('TS.INFO', key, 'CHUNKS'))
+        assert 'keySelfName' not in info
+        chunks = info['chunks']
+        assert len(chunks) == info['chunkCount'] > 1
+        assert sum(chunk['samples'] for chunk in chunks) == info['totalSamples'] == 1000
+        assert chunks[0]['startTimestamp'] == info['firstTimestamp']
+        assert chunks[-1]['endTimestamp'] == info['lastTimestamp']
+        for prev, cur in zip(chunks, chunks[1:]):
+            assert prev['endTimestamp'] < cur['startTimestamp']
+        assert all(chunk['encoding'] == b'gorilla' for chunk in chunks)
+
+        # the default reply stays compact
+        assert 'chunks' not in self.ts_info(key)
+
+        with pytest.raises(ResponseError, match="invalid argument"):
+            self.client.execute_command('TS.INFO', key, 'SAMPLES')
+
     def test_info_reflects_single_rule_creation(self):
         """Test that TS.INFO shows rules after TS.CREATERULE"""
         source_key = 'ts_source'
@@ -0,0 +1,66 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTsIngest(ValkeyTimeSeriesTestCaseBase):
+
+    def test_ingest_creates_series(self):
+        payload = "\n".join([
+            "# HELP http_requests_total The total number of HTTP requests.",
+            "# TYPE http_requests_total counter",
+            'http_requests_total{method="post",code="200"} 1027 1395066363000',
+            'http_requests_total{method="post",code="400"} 3 1395066363000',
+            'http_requests_total{method="post",code="200"} 1030 1395066364000',
+            "",
+            "temperature 21.5 1000",
+        ])
+        result = self.client.execute_command('TS.INGEST', payload)
+        assert result == [4, 0]
+
+        key = 'http_requests_total{code="200",method="post"}'
+        samples = self.client.execute_command('TS.RANGE', key, '-', '+')
+        assert samples == [[1395066363000, b'1027'], [1395066364000, b'1030']]
+
+        info = self.ts_info(key)
+        assert info['labels']['method'] == 'post'
+        assert info['labels']['code'] == '200'
+
+        keys = self.client.execute_command('TS.QUERYINDEX', 'http_requests_total{method="post"}')
+        assert len(keys) == 2
+
+        assert self.client.execute_command('TS.GET', 'temperature{}') == [1000, b'21.5']
+
+    def test_ingest_uses_existing_series(self):
+        self.client.execute_command('TS.CREATE', 'cpu:host1', 'LABELS', '__name__', 'cpu', 'host', 'h1')
+
+        result = self.client.execute_command('TS.INGEST', 'cpu{host="h1"} 0.5 1000\ncpu{host="h1"} 0.7 2000')
+        assert result == [2, 0]
+
+        samples = self.client.execute_command('TS.RANGE', 'cpu:host1', '-', '+')
+        assert samples == [[1000, b'0.5'], [2000, b'0.7']]
+
+    def test_ingest_rejects_malformed_lines(self):
+        payload = "\n".join([
+            'up{job="api"} 1 1000',
+            'up{job="api" 1 2000',
+            'up{job="api"} not_a_number 3000',
+            'up{job="api"} 1 3000 extra',
+            'up{job="api"} 0 4000',
+        ])
+        result = self.client.execute_command('TS.INGEST', payload)
+        assert result == [2, 3]
+
+        samples = self.client.execute_command('TS.RANGE', 'up{job="api"}', '-', '+')
+        assert samples == [[1000, b'1'], [4000, b'0']]
+
+    def test_ingest_special_values(self):
+        result = self.client.execute_command('TS.INGEST', 'm 1 1000\nm +Inf 2000\nm -Inf 3000')
+        assert result == [3, 0]
+        samples = self.client.execute_command('TS.RANGE', 'm{}', '-', '+')
+        assert [s[0] for s in samples] == [1000, 2000, 3000]
+
+    def test_ingest_arity(self):
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.INGEST')
@@ -1,3 +1,4 @@
+import math
 import pytest
 from valkey import ResponseError
 from valkeytestframework.util.waiters import *
@@ -179,6 +180,74 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
             else:
                 assert right is None
 
+    def test_full_join_fill(self):
+        """Test full join with FILL on series sampled at different offsets"""
+
+        def to_pair(row):
+            left, right = row
+            ts = left[0] if left is not None else right[0]
+            return (
+                ts,
+                float(left[1]) if left is not None else None,
+                float(right[1]) if right is not None else None,
+            )
+
+        self.client.execute_command("TS.CREATE", "fill_left")
+        self.client.execute_command("TS.CREATE", "fill_right")
+        for i in range(5):
+            self.client.execute_command("TS.ADD", "fill_left", 1000 + i * 10, i)
+        for i in range(3):
+            self.client.execute_command("TS.ADD", "fill_right", 1005 + i * 20, 100 + i)
+
+        result = self.client.execute_command(
+            "TS.JOIN", "fill_left", "fill_right", "-", "+", "FULL", "FILL", "ZERO"
+        )
+        assert len(result) == 8
+        rows = [to_pair(row) for row in result]
+        assert rows[1] == (1005, 0.0, 100.0)
+        assert rows[2] == (1010, 1.0, 0.0)
+
+        result = self.client.execute_command(
+            "TS.JOIN", "fill_left", "fill_right", "-", "+", "FULL", "FILL", "PREVIOUS"
+        )
+        rows = [to_pair(row) for row in result]
+        assert rows[0] == (1000, 0.0, None)
+        assert rows[3] == (1020, 2.0, 100.0)
+        assert rows[4] == (1025, 2.0, 101.0)
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command(
+                "TS.JOIN", "fill_left", "fill_right", "-", "+", "FULL", "FILL", "LINEAR"
+            )
+        with pytest.raises(ResponseError):
+            self.client.execute_command(
+                "TS.JOIN", "fill_left", "fill_right", "-", "+", "SEMI", "FILL", "ZERO"
+            )
+
+    def test_reducer_div_by_zero(self):
+        """Test the DIV_BY_ZERO option of division reducers"""
+        self.client.execute_command("TS.CREATE", "div_left")
+        self.client.execute_command("TS.CREATE", "div_right")
+        self.client.execute_command("TS.MADD", "div_left", 1000, 10, "div_left", 2000, 20)
+        self.client.execute_command("TS.MADD", "div_right", 1000, 0, "div_right", 2000, 4)
+
+        result = self.client.execute_command(
+            "TS.JOIN", "div_left", "div_right", "-", "+", "REDUCE", "div"
+        )
+        assert math.isnan(float(result[0][1]))
+        assert float(result[1][1]) == 5
+
+        result = self.client.execute_command(
+            "TS.JOIN", "div_left", "div_right", "-", "+", "REDUCE", "div", "DIV_BY_ZERO", "NULL"
+        )
+        assert result[0] == [1000, None]
+        assert float(result[1][1]) == 5
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command(
+                "TS.JOIN", "div_left", "div_right", "-", "+", "REDUCE", "div", "DIV_BY_ZERO", "ZERO"
+            )
+
     def test_anti_join(self):
         """Test anti-join operation"""
 
@@ -481,6 +550,8 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
         # Verify PREVIOUS matches
         for item in result:
             left, right = item
+            if right is None:
+                continue
 
             ts = left[0]
             right_ts = right[0]
@@ -505,6 +576,8 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
         # Verify NEXT matches
         for item in result:
             left, right = item
+            if right is None:
+                continue
 
             ts = left[0]
             right_ts = right[0]
@@ -529,6 +602,8 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
         # Verify NEAREST matches
         for item in result:
             left, right = item
+            if right is None:
+                continue
 
             ts = left[0]
             right_ts = right[0]
@@ -581,6 +656,8 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
         # Validates series join at the exact tolerance boundary
         for item in result:
             left, right = item
+            if right is None:
+                continue
 
             ts = left[0]
             right_stamp = right[0]
@@ -651,7 +728,10 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
             "ASOF", "NEXT", "100"  # Only 100ms tolerance
         )
 
-        assert len(result) == 0
+        # every left sample is returned, with a nil right side
+        assert len(result) == 3
+        for left, right in result:
+            assert right is None
 
     def test_asof_multiple_candidates(self):
         """Test ASOF join behavior when multiple candidates are within tolerance"""
@@ -694,6 +774,14 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
         assert right_ts == base_time + 4800
         assert right_val == 200
 
+        # PRIOR is an alias for PREVIOUS
+        prior = self.client.execute_command(
+            "TS.JOIN", left_series, right_series,
+            base_time, base_time + 10000,
+            "ASOF", "PRIOR", "1000"
+        )
+        assert prior == result
+
         # Test NEXT - should match closest next
         result = self.client.execute_command(
             "TS.JOIN", left_series, right_series,
@@ -757,8 +845,12 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
             "REDUCE", "sum"
         )
 
+        # The first left sample has no previous right sample within range, so it reduces to NaN
+        assert len(result) == 5
+        assert math.isnan(float(result[0][1]))
+
         # Verify reduced values
-        for i, item in enumerate(result):
+        for i, item in enumerate(result[1:]):
             ts, value = item
             expected_left = (i + 1) * 10
             expected_right = (i + 1) * 5
@@ -809,7 +901,8 @@ class TestTSJoin(ValkeyTimeSeriesTestCaseBase):
             "ASOF", "NEAREST", "1000"
         )
 
-        assert len(result) == 0
+        assert len(result) == 1
+        assert result[0][1] is None
 
         # Test with single matching point
         self.client.execute_command("TS.ADD", right_series, base_time, 200)
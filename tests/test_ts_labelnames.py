@@ -277,3 +277,30 @@ class TestTimeSeriesLabelNames(ValkeyTimeSeriesTestCaseBase):
         # Verify no labels are returned
         result = self.client.execute_command('TS.LABELNAMES', 'FILTER', 'name=cpu')
         assert result == []
+
+    def test_labelnames_small_and_large_matched_sets(self):
+        """Names must be the same whether they are read from the series or from the index"""
+        for i in range(200):
+            labels = ['id', str(i), 'group', 'even' if i % 2 == 0 else 'odd']
+            if i % 50 == 0:
+                labels += ['milestone', 'yes']
+            if i == 7:
+                labels += ['lucky', 'yes']
+            self.client.execute_command('TS.CREATE', f'series:{i}', 'LABELS', *labels)
+
+        # a handful of series out of a large index
+        result = self.client.execute_command('TS.LABELNAMES', 'FILTER', 'id=7 or id=8')
+        assert result == [b'group', b'id', b'lucky']
+
+        result = self.client.execute_command('TS.LABELNAMES', 'FILTER', 'id=100')
+        assert result == [b'group', b'id', b'milestone']
+
+        # most of the index
+        result = self.client.execute_command('TS.LABELNAMES', 'FILTER', 'group=odd')
+        assert result == [b'group', b'id', b'lucky']
+
+        result = self.client.execute_command('TS.LABELNAMES', 'FILTER', 'group=~"even|odd"')
+        assert result == [b'group', b'id', b'lucky', b'milestone']
+
+        result = self.client.execute_command('TS.LABELNAMES', 'LIMIT', 2, 'FILTER', 'group=~"even|odd"')
+        assert result == [b'group', b'id']
@@ -140,6 +140,41 @@ class TestTimeSeriesLabelValues(ValkeyTimeSeriesTestCaseBase):
         result = self.client.execute_command('TS.LABELVALUES', 'node', 'LIMIT', 1, "FILTER", 'datacenter=dc2')
         assert result == [b'server2']
 
+    def test_label_values_topk(self):
+        """Test retrieving the most common label values with TOPK"""
+        self.setup_test_data(self.client)
+
+        # cpu is attached to two usage series; disk and memory tie and are ordered by value
+        result = self.client.execute_command('TS.LABELVALUES', 'name', 'TOPK', 2, 'FILTER', 'type=usage')
+        assert result == [[b'cpu', 2], [b'disk', 1]]
+
+        result = self.client.execute_command('TS.LABELVALUES', 'datacenter', 'TOPK', 5, 'FILTER', 'name=cpu')
+        assert result == [[b'dc1', 2], [b'dc2', 1]]
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.LABELVALUES', 'name', 'TOPK', 0, 'FILTER', 'type=usage')
+
+    def test_label_values_with_match(self):
+        """Test restricting returned label values with MATCH"""
+        self.setup_test_data(self.client)
+
+        result = self.client.execute_command('TS.LABELVALUES', 'name', 'MATCH', 'c.*|disk', 'FILTER', 'type=usage')
+        assert result == [b'cpu', b'disk']
+
+        # the regex is anchored, so a partial value does not match
+        result = self.client.execute_command('TS.LABELVALUES', 'name', 'MATCH', 'mem', 'FILTER', 'type=usage')
+        assert result == []
+
+        result = self.client.execute_command('TS.LABELVALUES', 'node', 'MATCH', 'server[12]', 'TOPK', 5,
+                                             'FILTER', 'type=usage')
+        assert result == [[b'server2', 2], [b'server1', 1]]
+
+        with pytest.raises(ResponseError, match="invalid regex"):
+            self.client.execute_command('TS.LABELVALUES', 'name', 'MATCH', 'cpu(', 'FILTER', 'type=usage')
+
+        with pytest.raises(ResponseError, match="missing MATCH value"):
+            self.client.execute_command('TS.LABELVALUES', 'name', 'FILTER', 'type=usage', 'MATCH')
+
     def test_label_values_with_combined_parameters(self):
         """Test retrieving label values with combined parameters"""
         self.setup_test_data(self.client)
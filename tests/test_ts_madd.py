@@ -87,7 +87,7 @@ class TestTsMadd(ValkeyTimeSeriesTestCaseBase):
 
         print(result)
         # Verify timestamps (should fail for duplicate)
-        assert result[0] == b'TSDB: duplicate sample'  # Error code for duplicate timestamp
+        assert str(result[0]) == 'DUPLICATE TSDB: duplicate sample'
         assert result[1] == 2000
 
         # Verify data - the original sample should remain unchanged
@@ -118,6 +118,49 @@ class TestTsMadd(ValkeyTimeSeriesTestCaseBase):
         assert float(range_result[0][1]) == 20.0  # Value was updated to higher value per MAX policy
         assert float(range_result[1][1]) == 30.0
 
+    def test_madd_sum_policy_same_timestamp(self):
+        """Samples sharing a timestamp in a single MADD are accumulated under the SUM policy"""
+        self.client.execute_command('TS.CREATE', 'ts_dup_sum', 'DUPLICATE_POLICY', 'SUM')
+        self.client.execute_command('TS.ADD', 'ts_dup_sum', 1000, 1.0)
+
+        args = []
+        for i in range(1, 11):
+            args += ['ts_dup_sum', 1000, i]
+        result = self.client.execute_command('TS.MADD', *args)
+        assert result == [1000] * 10
+
+        range_result = self.client.execute_command('TS.RANGE', 'ts_dup_sum', '-', '+')
+        assert range_result == [[1000, b'56']]
+        info = self.ts_info('ts_dup_sum')
+        assert info['totalSamples'] == 1
+
+    def test_madd_per_sample_status_across_keys(self):
+        """Test that TS.MADD replies are aligned with input order across interleaved keys"""
+        self.client.execute_command('TS.CREATE', 'ts_a', 'RETENTION', 1000)
+        self.client.execute_command('TS.CREATE', 'ts_b', 'DUPLICATE_POLICY', 'BLOCK')
+        self.client.execute_command('SET', 'not_ts', 'hello')
+        self.client.execute_command('TS.ADD', 'ts_a', 10000, 1)
+        self.client.execute_command('TS.ADD', 'ts_b', 2000, 1)
+
+        result = self.client.execute_command('TS.MADD',
+                                             'ts_b', 3000, 3,
+                                             'ts_a', 5000, 5,
+                                             'not_ts', 1000, 1,
+                                             'ts_b', 2000, 2,
+                                             'ts_a', 11000, 11,
+                                             'ts_b', 'bad', 1)
+
+        assert len(result) == 6
+        assert result[0] == 3000
+        assert str(result[1]) == 'TOO_OLD TSDB: sample timestamp exceeds retention period'
+        assert str(result[2]) == 'WRONGTYPE TSDB: the key is not a TSDB key'
+        assert str(result[3]) == 'DUPLICATE TSDB: duplicate sample'
+        assert result[4] == 11000
+        assert str(result[5]).startswith('INVALID_TIMESTAMP TSDB: invalid timestamp')
+
+        assert self.client.execute_command('TS.RANGE', 'ts_a', '-', '+') == [[10000, b'1'], [11000, b'11']]
+        assert self.client.execute_command('TS.RANGE', 'ts_b', '-', '+') == [[2000, b'1'], [3000, b'3']]
+
     def test_madd_with_retention(self):
         """Test adding samples with a retention period"""
         # Create time series with retention
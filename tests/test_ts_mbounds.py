@@ -0,0 +1,63 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.util.waiters import *
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTimeSeriesMBounds(ValkeyTimeSeriesTestCaseBase):
+
+    def actual_bounds(self, key):
+        samples = self.client.execute_command('TS.RANGE', key, '-', '+')
+        if not samples:
+            return [None, None, 0]
+        return [samples[0][0], samples[-1][0], len(samples)]
+
+    def bounds_by_key(self, *args):
+        result = self.client.execute_command('TS.MBOUNDS', *args)
+        return {item[0]: item for item in result}
+
+    def test_bounds_match_series_after_inserts_and_trim(self):
+        self.client.execute_command('TS.CREATE', 'ts1', 'LABELS', 'name', 'cpu', 'node', 'node1')
+        self.client.execute_command('TS.CREATE', 'ts2', 'LABELS', 'name', 'cpu', 'node', 'node2')
+        self.client.execute_command('TS.CREATE', 'empty', 'LABELS', 'name', 'cpu', 'node', 'node4')
+        self.client.execute_command('TS.CREATE', 'other', 'LABELS', 'name', 'memory')
+
+        for ts in range(1000, 6000, 500):
+            self.client.execute_command('TS.ADD', 'ts1', ts, ts)
+        # out-of-order insert before the current first sample
+        self.client.execute_command('TS.ADD', 'ts1', 200, 1)
+        self.client.execute_command('TS.ADD', 'ts2', 3000, 1)
+
+        # trim both ends of ts1
+        self.client.execute_command('TS.DEL', 'ts1', 0, 1500)
+        self.client.execute_command('TS.DEL', 'ts1', 5000, 6000)
+
+        result = self.bounds_by_key('FILTER', 'name=cpu')
+        assert sorted(result.keys()) == [b'empty', b'ts1', b'ts2']
+
+        assert result[b'ts1'][2:] == [2000, 4500, 6]
+        for key in [b'ts1', b'ts2', b'empty']:
+            assert result[key][1] == []
+            assert result[key][2:] == self.actual_bounds(key.decode())
+
+        assert result[b'ts2'][2:] == [3000, 3000, 1]
+        assert result[b'empty'][2:] == [None, None, 0]
+
+    def test_withlabels(self):
+        self.client.execute_command('TS.CREATE', 'ts1', 'LABELS', 'name', 'cpu', 'node', 'node1')
+        self.client.execute_command('TS.ADD', 'ts1', 1000, 1)
+
+        result = self.client.execute_command('TS.MBOUNDS', 'WITHLABELS', 'FILTER', 'node=node1')
+        assert result == [[b'ts1', [[b'name', b'cpu'], [b'node', b'node1']], 1000, 1000, 1]]
+
+    def test_no_matches(self):
+        self.client.execute_command('TS.CREATE', 'ts1', 'LABELS', 'name', 'cpu')
+        assert self.client.execute_command('TS.MBOUNDS', 'FILTER', 'name=disk') == []
+
+    def test_errors(self):
+        with pytest.raises(ResponseError, match="wrong number of arguments"):
+            self.client.execute_command('TS.MBOUNDS', 'FILTER')
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.MBOUNDS', 'LATEST', 'FILTER', 'name=cpu')
@@ -0,0 +1,37 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.util.waiters import *
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTimeSeriesMBoundsCluster(ValkeyTimeSeriesTestCaseBase):
+
+    def get_cluster_env(self):
+        """Return cluster environment configuration"""
+        return {
+            'cluster': True,
+            'num_shards': 3,
+            'num_replicas': 1
+        }
+
+    def test_cluster_mbounds_cross_shard(self):
+        client = self.client
+        client.execute_command('TS.CREATE', 'ts:{shard1}:cpu1', 'LABELS', 'name', 'cpu', 'node', 'node1')
+        client.execute_command('TS.CREATE', 'ts:{shard2}:cpu2', 'LABELS', 'name', 'cpu', 'node', 'node2')
+        client.execute_command('TS.CREATE', 'ts:{shard3}:cpu3', 'LABELS', 'name', 'cpu', 'node', 'node3')
+        client.execute_command('TS.CREATE', 'ts:{shard3}:mem1', 'LABELS', 'name', 'memory', 'node', 'node3')
+
+        for ts in range(1000, 4000, 1000):
+            client.execute_command('TS.ADD', 'ts:{shard1}:cpu1', ts, 1)
+        client.execute_command('TS.ADD', 'ts:{shard2}:cpu2', 5000, 1)
+        client.execute_command('TS.DEL', 'ts:{shard1}:cpu1', 0, 1000)
+
+        result = client.execute_command('TS.MBOUNDS', 'WITHLABELS', 'FILTER', 'name=cpu')
+        result.sort(key=lambda x: x[0])
+
+        assert result == [
+            [b'ts:{shard1}:cpu1', [[b'name', b'cpu'], [b'node', b'node1']], 2000, 3000, 2],
+            [b'ts:{shard2}:cpu2', [[b'name', b'cpu'], [b'node', b'node2']], 5000, 5000, 1],
+            [b'ts:{shard3}:cpu3', [[b'name', b'cpu'], [b'node', b'node3']], None, None, 0],
+        ]
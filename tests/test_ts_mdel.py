@@ -57,6 +57,21 @@ class TestTimeSeriesMdel(ValkeyTimeSeriesTestCaseBase):
         result = self.client.execute_command('TS.GET', 'ts7')
         assert result is not None
 
+    def test_mdel_removes_index_entries(self):
+        """Deleted series must no longer be returned by index queries"""
+        self.setup_test_data(self.client)
+
+        deleted_count = self.client.execute_command('TS.MDEL', 'FILTER', 'node=node1')
+        assert deleted_count == 3
+
+        assert self.client.execute_command('TS.QUERYINDEX', 'node=node1') == []
+        assert self.client.execute_command('TS.CARD', 'FILTER', 'node=node1') == 0
+        assert b'node1' not in self.client.execute_command('TS.LABELVALUES', 'node', 'FILTER', 'type=usage')
+        assert sorted(self.client.execute_command('TS.QUERYINDEX', 'type=usage')) == [b'ts2', b'ts4', b'ts7']
+
+        # A second pass finds nothing left to delete
+        assert self.client.execute_command('TS.MDEL', 'FILTER', 'node=node1') == 0
+
     def test_mdel_with_timerange(self):
         self.setup_test_data(self.client)
 
@@ -0,0 +1,104 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTsMerge(ValkeyTimeSeriesTestCaseBase):
+
+    def test_merge_interleaved_sources(self):
+        self.client.execute_command('TS.CREATE', 'dst')
+        self.client.execute_command('TS.CREATE', 'src1')
+        self.client.execute_command('TS.CREATE', 'src2')
+        self.client.execute_command('TS.MADD', 'src1', 1000, 1, 'src1', 3000, 3)
+        self.client.execute_command('TS.MADD', 'src2', 2000, 2, 'src2', 4000, 4)
+
+        result = self.client.execute_command('TS.MERGE', 'dst', 'src1', 'src2')
+        assert result == [4, 0, []]
+
+        samples = self.client.execute_command('TS.RANGE', 'dst', '-', '+')
+        assert samples == [[1000, b'1'], [2000, b'2'], [3000, b'3'], [4000, b'4']]
+
+        # sources are kept by default
+        assert self.client.execute_command('EXISTS', 'src1', 'src2') == 2
+
+    def test_merge_applies_destination_duplicate_policy(self):
+        self.client.execute_command('TS.CREATE', 'dst', 'DUPLICATE_POLICY', 'BLOCK')
+        self.client.execute_command('TS.ADD', 'dst', 1000, 10)
+        self.client.execute_command('TS.CREATE', 'src')
+        self.client.execute_command('TS.MADD', 'src', 1000, 1, 'src', 2000, 2)
+
+        result = self.client.execute_command('TS.MERGE', 'dst', 'src')
+        assert result == [1, 1, [b'DUPLICATE', 1]]
+        samples = self.client.execute_command('TS.RANGE', 'dst', '-', '+')
+        assert samples == [[1000, b'10'], [2000, b'2']]
+
+        self.client.execute_command('TS.CREATE', 'dst_sum', 'DUPLICATE_POLICY', 'SUM')
+        self.client.execute_command('TS.ADD', 'dst_sum', 1000, 10)
+        result = self.client.execute_command('TS.MERGE', 'dst_sum', 'src')
+        assert result == [2, 0, []]
+        samples = self.client.execute_command('TS.RANGE', 'dst_sum', '-', '+')
+        assert samples == [[1000, b'11'], [2000, b'2']]
+
+    def test_merge_applies_destination_settings(self):
+        self.client.execute_command('TS.CREATE', 'dst', 'RETENTION', 1000, 'DECIMAL_DIGITS', 1)
+        self.client.execute_command('TS.ADD', 'dst', 10000, 0)
+        self.client.execute_command('TS.CREATE', 'src')
+        # the first sample is outside the destination's retention window
+        self.client.execute_command('TS.MADD', 'src', 5000, 1.26, 'src', 9500, 2.44)
+
+        result = self.client.execute_command('TS.MERGE', 'dst', 'src')
+        assert result == [1, 1, [b'TOO_OLD', 1]]
+        samples = self.client.execute_command('TS.RANGE', 'dst', '-', '+')
+        assert samples == [[9500, b'2.4'], [10000, b'0']]
+
+    def test_merge_large_source_in_batches(self):
+        self.client.execute_command('TS.CREATE', 'dst')
+        self.client.execute_command('TS.CREATE', 'src')
+        count = 10000
+        for start in range(0, count, 1000):
+            args = []
+            for i in range(start, start + 1000):
+                args += ['src', i + 1, i]
+            self.client.execute_command('TS.MADD', *args)
+
+        result = self.client.execute_command('TS.MERGE', 'dst', 'src')
+        assert result == [count, 0, []]
+        counts = self.client.execute_command('TS.RANGE', 'dst', '-', '+', 'AGGREGATION', 'count', 1000000)
+        assert counts == [[0, str(count).encode()]]
+
+    def test_merge_delete_sources(self):
+        self.client.execute_command('TS.CREATE', 'dst', 'DUPLICATE_POLICY', 'BLOCK')
+        self.client.execute_command('TS.ADD', 'dst', 1000, 10)
+        self.client.execute_command('TS.CREATE', 'clean', 'LABELS', 'group', 'merge')
+        self.client.execute_command('TS.ADD', 'clean', 2000, 2)
+        self.client.execute_command('TS.CREATE', 'conflicting', 'LABELS', 'group', 'merge')
+        self.client.execute_command('TS.ADD', 'conflicting', 1000, 1)
+
+        result = self.client.execute_command('TS.MERGE', 'dst', 'clean', 'conflicting', 'DELETE')
+        assert result == [1, 1, [b'DUPLICATE', 1]]
+
+        # a source with rejected samples is kept so that no data is lost
+        assert self.client.execute_command('EXISTS', 'clean') == 0
+        assert self.client.execute_command('EXISTS', 'conflicting') == 1
+        assert self.client.execute_command('TS.QUERYINDEX', 'group=merge') == [b'conflicting']
+
+    def test_merge_errors(self):
+        self.client.execute_command('TS.CREATE', 'dst')
+        self.client.execute_command('TS.CREATE', 'src')
+        self.client.execute_command('SET', 'str', 'value')
+
+        with pytest.raises(ResponseError, match="wrong number of arguments"):
+            self.client.execute_command('TS.MERGE', 'dst')
+
+        with pytest.raises(ResponseError, match="cannot also be a source"):
+            self.client.execute_command('TS.MERGE', 'dst', 'src', 'dst')
+
+        with pytest.raises(ResponseError, match="key does not exist"):
+            self.client.execute_command('TS.MERGE', 'dst', 'missing')
+
+        with pytest.raises(ResponseError, match="key does not exist"):
+            self.client.execute_command('TS.MERGE', 'missing', 'src')
+
+        with pytest.raises(ResponseError, match="WRONGTYPE"):
+            self.client.execute_command('TS.MERGE', 'dst', 'str')
@@ -0,0 +1,55 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTimeSeriesMetrics(ValkeyTimeSeriesTestCaseBase):
+
+    def setup_test_data(self):
+        series = [
+            ('ts1', 'http_requests_total', 'api'),
+            ('ts2', 'http_requests_total', 'db'),
+            ('ts3', 'http_errors_total', 'api'),
+            ('ts4', 'grpc_requests_total', 'api'),
+            ('ts5', 'https_requests_total', 'api'),
+            ('ts6', 'up', 'http_proxy'),
+        ]
+        for key, name, job in series:
+            self.client.execute_command('TS.CREATE', key, 'LABELS', '__name__', name, 'job', job)
+
+    def test_metrics_match_prefix(self):
+        self.setup_test_data()
+
+        result = self.client.execute_command('TS.METRICS', 'MATCH', 'http_.*')
+        assert result == [b'http_errors_total', b'http_requests_total']
+
+    def test_metrics_match_is_sorted_and_distinct(self):
+        self.setup_test_data()
+
+        result = self.client.execute_command('TS.METRICS', 'MATCH', '.*requests_total')
+        assert result == [b'grpc_requests_total', b'http_requests_total', b'https_requests_total']
+
+        result = self.client.execute_command('TS.METRICS', 'MATCH', 'https?_requests_total|up')
+        assert result == [b'http_requests_total', b'https_requests_total', b'up']
+
+    def test_metrics_limit(self):
+        self.setup_test_data()
+
+        result = self.client.execute_command('TS.METRICS', 'MATCH', '.*', 'LIMIT', 2)
+        assert result == [b'grpc_requests_total', b'http_errors_total']
+
+    def test_metrics_no_match(self):
+        self.setup_test_data()
+
+        assert self.client.execute_command('TS.METRICS', 'MATCH', 'missing.*') == []
+
+    def test_metrics_errors(self):
+        with pytest.raises(ResponseError, match="missing MATCH value"):
+            self.client.execute_command('TS.METRICS')
+
+        with pytest.raises(ResponseError, match="invalid regex"):
+            self.client.execute_command('TS.METRICS', 'MATCH', 'http_(')
+
+        with pytest.raises(ResponseError, match="LIMIT must be greater than 0"):
+            self.client.execute_command('TS.METRICS', 'MATCH', '.*', 'LIMIT', 0)
@@ -0,0 +1,30 @@
+from valkey import ValkeyCluster
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesClusterTestCase
+
+
+class TestTimeSeriesMetricsCluster(ValkeyTimeSeriesClusterTestCase):
+
+    @staticmethod
+    def setup_test_data(client):
+        series = [
+            ('ts1:{1}', 'http_requests_total'),
+            ('ts2:{2}', 'http_requests_total'),
+            ('ts3:{3}', 'http_errors_total'),
+            ('ts4:{1}', 'grpc_requests_total'),
+            ('ts5:{2}', 'up'),
+        ]
+        for key, name in series:
+            client.execute_command('TS.CREATE', key, 'LABELS', '__name__', name)
+
+    def test_metrics_merged_across_shards(self):
+        cluster: ValkeyCluster = self.new_cluster_client()
+        client = self.new_client_for_primary(0)
+
+        self.setup_test_data(cluster)
+
+        result = client.execute_command('TS.METRICS', 'MATCH', 'http_.*')
+        assert result == [b'http_errors_total', b'http_requests_total']
+
+        result = client.execute_command('TS.METRICS', 'MATCH', '.*', 'LIMIT', 3)
+        assert result == [b'grpc_requests_total', b'http_errors_total', b'http_requests_total']
@@ -1,7 +1,7 @@
 import math
 
 import pytest
-from valkey import ResponseError
+from valkey import ResponseError, Valkey
 from valkeytestframework.util.waiters import *
 from valkeytestframework.conftest import resource_port_tracker
 from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
@@ -116,6 +116,19 @@ class TestTimeSeriesMget(ValkeyTimeSeriesTestCaseBase):
 
             assert labels == expected_labels[i], f"Labels mismatch at index {i}: {labels} != {expected_labels[i]}"
 
+    def test_mget_selected_labels_rename(self):
+        """Test renaming labels on output with SELECTED_LABELS old=new"""
+        self.setup_test_data(self.client)
+
+        result = self.client.execute_command('TS.MGET', 'SELECTED_LABELS', 'name=metric', 'type', 'FILTER', 'name=cpu')
+        assert len(result) == 4
+        for item in result:
+            labels = item[1]
+            assert labels[0] == [b'metric', b'cpu']
+
+        with pytest.raises(ResponseError, match="duplicate output label name"):
+            self.client.execute_command('TS.MGET', 'SELECTED_LABELS', 'name=type', 'type', 'FILTER', 'name=cpu')
+
     def test_mget_with_complex_filter(self):
         """Test TS.MGET with complex filters"""
         self.setup_test_data(self.client)
@@ -154,6 +167,20 @@ class TestTimeSeriesMget(ValkeyTimeSeriesTestCaseBase):
 
         assert result[1][0] == b'empty_ts2'
         assert result[1][1] == []
+        assert result[0][2] is None
+        assert result[1][2] is None
+
+        # Labels are still returned when requested
+        result = self.client.execute_command('TS.MGET', 'WITHLABELS', 'FILTER', 'name=empty')
+        result.sort(key=lambda x: x[0])
+        assert result[0][1] == [[b'name', b'empty'], [b'type', b'test']]
+        assert result[0][2] is None
+
+        result = self.client.execute_command('TS.MGET', 'SELECTED_LABELS', 'type=kind', 'missing',
+                                             'FILTER', 'name=empty')
+        result.sort(key=lambda x: x[0])
+        assert result[1][1] == [[b'kind', b'test2'], None]
+        assert result[1][2] is None
 
     def test_mget_error_cases(self):
         """Test error cases for TS.MGET"""
@@ -223,6 +250,30 @@ class TestTimeSeriesMget(ValkeyTimeSeriesTestCaseBase):
         assert result[0][2][0] == 3000  # latest timestamp
         assert result[0][2][1] == b'80'  # latest value
 
+    def test_mget_max_stale(self):
+        """Test TS.MGET MAXSTALE returns nil for series whose last sample is too old"""
+        self.client.execute_command('TS.CREATE', 'fresh', 'LABELS', 'kind', 'staleness')
+        self.client.execute_command('TS.CREATE', 'stale', 'LABELS', 'kind', 'staleness')
+
+        self.client.execute_command('TS.ADD', 'fresh', '*', 1)
+        self.client.execute_command('TS.ADD', 'stale', 1000, 2)
+
+        result = self.client.execute_command('TS.MGET', 'MAXSTALE', '5m', 'FILTER', 'kind=staleness')
+        values = {key: sample for key, _labels, sample in result}
+        assert values[b'fresh'] is not None
+        assert float(values[b'fresh'][1]) == 1
+        assert values[b'stale'] is None
+
+        # without MAXSTALE both samples are returned
+        result = self.client.execute_command('TS.MGET', 'FILTER', 'kind=staleness')
+        assert all(sample is not None for _key, _labels, sample in result)
+
+    def test_mget_max_stale_invalid(self):
+        self.setup_test_data(self.client)
+
+        with pytest.raises(ResponseError, match="invalid duration"):
+            self.client.execute_command('TS.MGET', 'MAXSTALE', 'soon', 'FILTER', 'name=cpu')
+
     def test_mget_with_nan_samples(self):
         """Test TS.MGET behavior when some series have NaN samples"""
         self.setup_test_data(self.client)
@@ -244,3 +295,17 @@ class TestTimeSeriesMget(ValkeyTimeSeriesTestCaseBase):
         assert result[1][0] == b'ts4'
         assert result[1][2][0] == 2000  # latest timestamp for ts4
         assert math.isnan(float(result[1][2][1]))  # latest value for ts4
+
+    def test_mget_resp3_labels(self):
+        """Test TS.MGET returns labels as a map for RESP3 clients, leaving out missing selected labels"""
+        self.setup_test_data(self.client)
+
+        kwargs = self.client.connection_pool.connection_kwargs
+        resp3_client = Valkey(host=kwargs['host'], port=kwargs['port'], protocol=3)
+
+        result = resp3_client.execute_command('TS.MGET', 'WITHLABELS', 'FILTER', 'node=node3', 'name=cpu')
+        assert result == [[b'ts6', {b'name': b'cpu', b'node': b'node3'}, [1000, 60.0]]]
+
+        result = resp3_client.execute_command('TS.MGET', 'SELECTED_LABELS', 'name', 'type', 'FILTER', 'node=node3')
+        result.sort(key=lambda x: x[0])
+        assert [item[1] for item in result] == [{b'name': b'cpu'}, {b'name': b'disk', b'type': b'usage'}]
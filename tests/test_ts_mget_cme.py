@@ -179,6 +179,23 @@ class TestTimeSeriesMgetCluster(ValkeyTimeSeriesTestCaseBase):
         assert latest_samples[0] == 15000  # start of last bucket
         assert float(latest_samples[1]) == 800.0  # average of last bucket
 
+    def test_mget_cme_max_stale(self):
+        """Test TS.MGET MAXSTALE is applied by each shard"""
+        self.setup_test_data(self.client)
+
+        # refresh one series per shard; the rest keep their old samples
+        self.client.execute_command('TS.ADD', 'ts:{shard1}:cpu1', '*', 1)
+        self.client.execute_command('TS.ADD', 'ts:{shard2}:cpu3', '*', 3)
+
+        result = self.client.execute_command('TS.MGET', 'MAXSTALE', '5m', 'FILTER', 'name=cpu')
+        values = {key: sample for key, _labels, sample in result}
+
+        assert len(values) == 4
+        assert values[b'ts:{shard1}:cpu1'] is not None
+        assert values[b'ts:{shard2}:cpu3'] is not None
+        assert values[b'ts:{shard1}:cpu2'] is None
+        assert values[b'ts:{shard2}:cpu4'] is None
+
     def test_mget_cme_empty_result(self):
         """Test TS.MGET with no matching series across cluster"""
         self.setup_test_data(self.client)
@@ -257,9 +274,15 @@ class TestTimeSeriesMgetCluster(ValkeyTimeSeriesTestCaseBase):
         result.sort(key=lambda x: x[0])
 
         assert len(result) == 3
-        # All should have empty samples
+        # All should have nil samples
         for item in result:
-            assert item[2] == []
+            assert item[2] is None
+
+        # Labels are still returned when requested
+        result = self.client.execute_command('TS.MGET', 'SELECTED_LABELS', 'shard', 'FILTER', 'name=empty')
+        result.sort(key=lambda x: x[0])
+        assert [item[1] for item in result] == [[[b'shard', b'1']], [[b'shard', b'2']], [[b'shard', b'3']]]
+        assert all(item[2] is None for item in result)
 
     def test_mget_cme_concurrent_updates(self):
         """Test TS.MGET consistency during concurrent updates"""
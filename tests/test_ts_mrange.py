@@ -2,6 +2,7 @@ from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
 from valkeytestframework.conftest import resource_port_tracker
 import time
 import pytest
+from valkey import Valkey, ResponseError
 
 
 class TestTimeSeriesMRange(ValkeyTimeSeriesTestCaseBase):
@@ -64,6 +65,33 @@ class TestTimeSeriesMRange(ValkeyTimeSeriesTestCaseBase):
             assert labels_dict['location'] == 'kitchen'
             assert labels_dict['sensor'] in ['temp', 'humid']
 
+    def test_mrange_withlabels_resp3(self):
+        """Test TS.MRANGE WITHLABELS returns labels as a map for RESP3 clients"""
+
+        self.setup_data()
+
+        kwargs = self.client.connection_pool.connection_kwargs
+        resp3_client = Valkey(host=kwargs['host'], port=kwargs['port'], protocol=3)
+
+        result = resp3_client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 10,
+                                              'WITHLABELS', 'FILTER', 'sensor=temp', 'location=kitchen')
+        assert len(result) == 1
+        assert result[0][0] == b'ts1'
+        assert result[0][1] == {b'sensor': b'temp', b'location': b'kitchen'}
+        assert result[0][2] == [[1000, 20.0], [1010, 21.0]]
+
+        # a selected label the series does not have maps to null
+        result = resp3_client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 10,
+                                              'SELECTED_LABELS', 'sensor', 'missing',
+                                              'FILTER', 'sensor=temp', 'location=kitchen')
+        assert result[0][1] == {b'sensor': b'temp', b'missing': None}
+
+        # RESP2 output is unchanged
+        result = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 10,
+                                             'WITHLABELS', 'FILTER', 'sensor=temp', 'location=kitchen')
+        assert result[0][1] == [[b'sensor', b'temp'], [b'location', b'kitchen']]
+        assert result[0][2] == [[1000, b'20'], [1010, b'21']]
+
     def test_mrange_selected_labels(self):
         """Test TS.MRANGE with the SELECTED_LABELS option"""
 
@@ -80,6 +108,30 @@ class TestTimeSeriesMRange(ValkeyTimeSeriesTestCaseBase):
             assert len(labels_dict) == 1  # Only the 'sensor' label should be returned
             assert labels_dict['sensor'] == 'humid'
 
+    def test_mrange_selected_labels_rename(self):
+        """Test renaming labels on output with SELECTED_LABELS old=new"""
+
+        self.setup_data()
+
+        result = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                             'FILTER', 'sensor=humid', 'SELECTED_LABELS', 'sensor=kind')
+
+        assert len(result) == 2
+        for series in result:
+            labels_dict = {item[0].decode(): item[1].decode() for item in series[1]}
+            assert labels_dict == {'kind': 'humid'}
+
+        # renames do not affect matching
+        result = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                             'FILTER', 'kind=humid', 'SELECTED_LABELS', 'sensor=kind')
+        assert len(result) == 0
+
+        # output names must be unique
+        with pytest.raises(ResponseError, match="duplicate output label name"):
+            self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                        'FILTER', 'sensor=humid', 'SELECTED_LABELS', 'sensor=location',
+                                        'location')
+
     def test_mrange_filter_by_value(self):
         """Test TS.MRANGE with the FILTER_BY_VALUE option"""
 
@@ -300,6 +352,77 @@ class TestTimeSeriesMRange(ValkeyTimeSeriesTestCaseBase):
             assert labels_dict['location'] in ['kitchen', 'living_room']
             assert labels_dict['__reducer__'] == 'max'
 
+    def test_mrange_groupby_multiple_labels(self):
+        """Test TS.MRANGE GROUPBY with more than one label"""
+        self.setup_data()
+        self.client.execute_command('TS.CREATE', 'ts5', 'LABELS', 'sensor', 'temp', 'location', 'kitchen')
+        for i in range(0, 100, 10):
+            self.client.execute_command('TS.ADD', 'ts5', self.start_ts + i, 1)
+
+        result = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                             'WITHLABELS',
+                                             'FILTER', 'sensor=(temp,humid)',
+                                             'GROUPBY', 'sensor', 'location',
+                                             'REDUCE', 'sum')
+
+        # One group per (sensor, location) pair
+        assert len(result) == 4
+        groups = {}
+        for series in result:
+            labels = [(name.decode(), value.decode()) for name, value in series[1]]
+            assert [name for name, _ in labels] == ['sensor', 'location', '__reducer__', '__source__']
+            labels_dict = dict(labels)
+            assert series[0].decode() == f"sensor={labels_dict['sensor']},location={labels_dict['location']}"
+            groups[(labels_dict['sensor'], labels_dict['location'])] = (labels_dict['__source__'], series[2])
+
+        sources, samples = groups[('temp', 'kitchen')]
+        assert sources == 'ts1,ts5'
+        assert float(samples[0][1]) == 21.0
+        assert groups[('humid', 'living_room')][0] == 'ts4'
+
+    def test_mrange_groupby_duplicate_label(self):
+        """Test TS.MRANGE GROUPBY rejects repeated labels"""
+        self.setup_data()
+
+        with pytest.raises(ResponseError, match="duplicate label"):
+            self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                        'FILTER', 'sensor=temp',
+                                        'GROUPBY', 'sensor', 'sensor',
+                                        'REDUCE', 'sum')
+
+    def test_mrange_groupby_with_sources(self):
+        """Test TS.MRANGE GROUPBY ... WITHSOURCES lists a capped set of keys in __sources__"""
+        for i in range(105):
+            key = f'src:{i:03d}'
+            self.client.execute_command('TS.CREATE', key, 'LABELS', 'kind', 'many')
+            self.client.execute_command('TS.ADD', key, 1000, i)
+
+        result = self.client.execute_command('TS.MRANGE', '-', '+',
+                                             'WITHLABELS',
+                                             'FILTER', 'kind=many',
+                                             'GROUPBY', 'kind',
+                                             'REDUCE', 'count')
+        assert len(result) == 1
+        labels = {name.decode(): value.decode() for name, value in result[0][1]}
+        # without WITHSOURCES, __source__ lists every key and there is no __sources__ label
+        assert len(labels['__source__'].split(',')) == 105
+        assert '__sources__' not in labels
+
+        result = self.client.execute_command('TS.MRANGE', '-', '+',
+                                             'WITHLABELS',
+                                             'FILTER', 'kind=many',
+                                             'GROUPBY', 'kind',
+                                             'REDUCE', 'count', 'WITHSOURCES')
+        assert len(result) == 1
+        labels = {name.decode(): value.decode() for name, value in result[0][1]}
+        assert len(labels['__source__'].split(',')) == 105
+        sources = labels['__sources__'].split(',')
+        assert len(sources) == 101
+        assert sources[0] == 'src:000'
+        assert sources[99] == 'src:099'
+        assert sources[100] == '...(+5 more)'
+        assert float(result[0][2][0][1]) == 105
+
     def test_mrange_count_zero(self):
         """Test TS.MRANGE with COUNT 0 (should return empty results)"""
         self.setup_data()
@@ -313,6 +436,118 @@ class TestTimeSeriesMRange(ValkeyTimeSeriesTestCaseBase):
             # But no data points
             assert len(series[2]) == 0
 
+    def test_mrange_limit_total_samples(self):
+        """Test TS.MRANGE LIMIT caps the samples returned across all series"""
+        self.setup_data()
+
+        result = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                             'LIMIT', 15, 'FILTER', 'sensor=temp')
+        truncated, series = result
+        assert truncated == 1
+        assert [s[0] for s in series] == [b'ts1', b'ts2']
+        assert len(series[0][2]) == 10
+        assert len(series[1][2]) == 5
+        assert series[1][2][-1][0] == self.start_ts + 40
+
+        # series past the limit are left out
+        result = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                             'LIMIT', 10, 'FILTER', 'location=kitchen')
+        truncated, series = result
+        assert truncated == 1
+        assert [s[0] for s in series] == [b'ts1']
+
+        # COUNT applies per series before the limit
+        result = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                             'COUNT', 3, 'LIMIT', 100, 'FILTER', 'sensor=temp')
+        truncated, series = result
+        assert truncated == 0
+        assert [len(s[2]) for s in series] == [3, 3]
+
+    def test_mrange_limit_drops_empty_series_without_truncation(self):
+        """Test TS.MRANGE LIMIT does not report truncation when only empty series are left out"""
+        self.client.execute_command('TS.ADD', 'lim:a', 1000, 1, 'LABELS', 'kind', 'lim')
+        self.client.execute_command('TS.ADD', 'lim:a', 2000, 2)
+        self.client.execute_command('TS.ADD', 'lim:b', 9000, 3, 'LABELS', 'kind', 'lim')
+
+        truncated, series = self.client.execute_command('TS.MRANGE', 0, 5000,
+                                                         'LIMIT', 2, 'FILTER', 'kind=lim')
+        assert truncated == 0
+        assert [s[0] for s in series] == [b'lim:a']
+        assert len(series[0][2]) == 2
+
+    def test_mrevrange_limit_total_samples(self):
+        """Test TS.MREVRANGE LIMIT keeps the newest samples of each series"""
+        self.setup_data()
+
+        truncated, series = self.client.execute_command('TS.MREVRANGE', self.start_ts, self.start_ts + 100,
+                                                        'LIMIT', 12, 'FILTER', 'sensor=temp')
+        assert truncated == 1
+        assert [s[0] for s in series] == [b'ts1', b'ts2']
+        assert [sample[0] for sample in series[1][2]] == [self.start_ts + 90, self.start_ts + 80]
+
+    def test_mrange_limit_invalid(self):
+        self.setup_data()
+
+        for value in ['0', '-1', 'abc']:
+            with pytest.raises(ResponseError, match="LIMIT must be a positive integer"):
+                self.client.execute_command('TS.MRANGE', '-', '+', 'LIMIT', value, 'FILTER', 'sensor=temp')
+
+    def test_mrange_cursor_pages_through_series(self):
+        """Test TS.MRANGE CURSOR returns every matched series once, COUNT series at a time"""
+        for i in range(7):
+            self.client.execute_command('TS.ADD', f'page:{i}', 1000, i, 'LABELS', 'group', 'paged')
+
+        seen = []
+        cursor = '0'
+        pages = 0
+        while True:
+            cursor, series = self.client.execute_command('TS.MRANGE', '-', '+', 'CURSOR', cursor, 'PAGE_SIZE', 3,
+                                                         'FILTER', 'group=paged')
+            cursor = cursor.decode()
+            pages += 1
+            assert len(series) <= 3
+            seen.extend(s[0] for s in series)
+            if cursor == '0':
+                break
+
+        assert pages == 3
+        assert sorted(seen) == sorted(f'page:{i}'.encode() for i in range(7))
+
+    def test_mrange_cursor_page_size_and_count(self):
+        """Test PAGE_SIZE sizes the page while COUNT caps samples per series"""
+        self.setup_data()
+
+        cursor, series = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                                     'COUNT', 2, 'CURSOR', 0, 'PAGE_SIZE', 1,
+                                                     'FILTER', 'sensor=temp')
+        assert cursor != b'0'
+        assert len(series) == 1
+        assert len(series[0][2]) == 2
+
+        # COUNT directly after the cursor still caps the samples per series
+        cursor, series = self.client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 100,
+                                                     'CURSOR', cursor, 'COUNT', 2,
+                                                     'FILTER', 'sensor=temp')
+        assert cursor == b'0'
+        assert len(series) == 1
+        assert len(series[0][2]) == 2
+
+    def test_mrange_cursor_invalid(self):
+        self.setup_data()
+
+        with pytest.raises(ResponseError, match="invalid cursor"):
+            self.client.execute_command('TS.MRANGE', '-', '+', 'CURSOR', 'abc', 'FILTER', 'sensor=temp')
+        for value in ['0', '1001', 'abc']:
+            with pytest.raises(ResponseError, match="PAGE_SIZE must be between 1 and 1000"):
+                self.client.execute_command('TS.MRANGE', '-', '+', 'CURSOR', 0, 'PAGE_SIZE', value,
+                                            'FILTER', 'sensor=temp')
+        with pytest.raises(ResponseError, match="CURSOR cannot be combined with GROUPBY or LIMIT"):
+            self.client.execute_command('TS.MRANGE', '-', '+', 'CURSOR', 0, 'LIMIT', 10,
+                                        'FILTER', 'sensor=temp')
+        with pytest.raises(ResponseError, match="CURSOR cannot be combined with GROUPBY or LIMIT"):
+            self.client.execute_command('TS.MRANGE', '-', '+', 'CURSOR', 0, 'FILTER', 'sensor=temp',
+                                        'GROUPBY', 'location', 'REDUCE', 'sum')
+
     def test_mrange_count_with_filter_by_value(self):
         """Test TS.MRANGE COUNT combined with FILTER_BY_VALUE"""
         self.setup_data()
@@ -1,4 +1,5 @@
-from valkey import ValkeyCluster
+import pytest
+from valkey import Valkey, ValkeyCluster, ResponseError
 from valkeytestframework.conftest import resource_port_tracker
 from valkey_timeseries_test_case import ValkeyTimeSeriesClusterTestCase
 
@@ -400,3 +401,31 @@ class TestTimeSeriesMRangeClustered(ValkeyTimeSeriesClusterTestCase):
                 assert series[2][0][1] == b'40'
             elif 'ts:{slot2}:dst_rev' in key:
                 assert series[2][0][1] == b'30'
+
+    def test_mrange_cme_cursor_rejected(self):
+        """Series ids are local to each node, so CURSOR is rejected in cluster mode."""
+        self.setup_clustered_data()
+
+        client = self.new_client_for_primary(0)
+        with pytest.raises(ResponseError, match="CURSOR is not supported in cluster mode"):
+            client.execute_command('TS.MRANGE', '-', '+', 'CURSOR', 0, 'FILTER', 'sensor=temp')
+
+    def test_cme_resp3_labels_match_standalone(self):
+        """RESP3 label maps from the cluster fanout are encoded as on a standalone server."""
+        self.setup_clustered_data()
+
+        server = self.replication_groups[0].primary.server
+        client = Valkey(host=server.bind_ip, port=server.port, protocol=3)
+
+        result = client.execute_command('TS.MRANGE', self.start_ts, self.start_ts + 10,
+                                        'SELECTED_LABELS', 'sensor', 'missing',
+                                        'FILTER', 'sensor=temp', 'region=east')
+        assert len(result) == 1
+        assert result[0][1] == {b'sensor': b'temp', b'missing': None}
+
+        result = client.execute_command('TS.MGET', 'WITHLABELS', 'FILTER', 'sensor=temp', 'region=east')
+        assert result == [[b'ts:{slot1}:temp1', {b'sensor': b'temp', b'region': b'east'}, [1090, 29.0]]]
+
+        result = client.execute_command('TS.MGET', 'SELECTED_LABELS', 'sensor', 'missing',
+                                        'FILTER', 'sensor=temp', 'region=east')
+        assert result[0][1] == {b'sensor': b'temp'}
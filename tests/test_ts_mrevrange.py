@@ -184,6 +184,23 @@ class TestTimeSeriesMRevRange(ValkeyTimeSeriesTestCaseBase):
             val = float(val.decode())
             assert val > 40
 
+    def test_mrevrange_groupby_count_keeps_newest(self):
+        """COUNT with GROUPBY in reverse returns the most recent reduced samples"""
+        self.setup_data()
+
+        result = self.client.execute_command('TS.MREVRANGE', self.start_ts, self.start_ts + 100,
+                                             'COUNT', 3,
+                                             'FILTER', 'sensor=temp',
+                                             'GROUPBY', 'sensor',
+                                             'REDUCE', 'sum')
+
+        assert len(result) == 1
+        timestamps = [sample[0] for sample in result[0][2]]
+        assert timestamps == [self.start_ts + 90, self.start_ts + 80, self.start_ts + 70]
+
+        # ts1 + ts2 at start_ts + 90: (20 + 9) + (25 + 9)
+        assert float(result[0][2][0][1]) == pytest.approx(63.0)
+
     def test_mrevrange_empty(self):
         """Test TS.MREVRANGE with empty filter results"""
         self.setup_data()
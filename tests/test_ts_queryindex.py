@@ -156,6 +156,26 @@ class TestTsQueryIndex(ValkeyTimeSeriesTestCaseBase):
         result1 = sorted(self.client.execute_command('TS.QUERYINDEX', 'name=(cpu,disk)'))
         assert result1 == [b'ts1', b'ts2', b'ts5', b'ts6', b'ts7']
 
+    def test_numeric_comparison(self):
+        """Test numeric comparison of label values; non-numeric values never match"""
+        self.client.execute_command('TS.CREATE', 'shard1', 'LABELS', 'shard', '1', 'tier', 'hot')
+        self.client.execute_command('TS.CREATE', 'shard5', 'LABELS', 'shard', '5', 'tier', 'hot')
+        self.client.execute_command('TS.CREATE', 'shard10', 'LABELS', 'shard', '10', 'tier', 'cold')
+        self.client.execute_command('TS.CREATE', 'shard12', 'LABELS', 'shard', '12.5', 'tier', 'cold')
+        self.client.execute_command('TS.CREATE', 'primary', 'LABELS', 'shard', 'primary', 'tier', 'hot')
+        self.client.execute_command('TS.CREATE', 'unsharded', 'LABELS', 'tier', 'hot')
+
+        assert sorted(self.client.execute_command('TS.QUERYINDEX', 'shard>5')) == [b'shard10', b'shard12']
+        assert sorted(self.client.execute_command('TS.QUERYINDEX', 'shard>=5')) == [b'shard10', b'shard12', b'shard5']
+        assert sorted(self.client.execute_command('TS.QUERYINDEX', 'shard<10')) == [b'shard1', b'shard5']
+        assert sorted(self.client.execute_command('TS.QUERYINDEX', 'shard<=10')) == [b'shard1', b'shard10', b'shard5']
+
+        result = self.client.execute_command('TS.QUERYINDEX', '{shard>1, shard<=10, tier="hot"}')
+        assert result == [b'shard5']
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.QUERYINDEX', 'shard>primary')
+
     def setup_or_test_data(self, client):
         client.execute_command('TS.CREATE', 'ts1', 'METRIC', 'http_status{status="200",method="GET"}')
         client.execute_command('TS.CREATE', 'ts2', 'METRIC', 'http_status{status="200",method="POST"}')
@@ -243,3 +263,33 @@ class TestTsQueryIndex(ValkeyTimeSeriesTestCaseBase):
         # query for series without data in range
         result = self.client.execute_command('TS.QUERYINDEX', 'FILTER_BY_RANGE', 'NOT', start_ts, end_ts, 'name=cpu')
         assert result == [b'ts10']
+
+    def test_start_end_range(self):
+        """Test narrowing matched series with START/END"""
+        self.client.execute_command('TS.CREATE', 'early', 'LABELS', 'name', 'cpu')
+        self.client.execute_command('TS.CREATE', 'late', 'LABELS', 'name', 'cpu')
+        self.client.execute_command('TS.CREATE', 'empty', 'LABELS', 'name', 'cpu')
+        self.client.execute_command('TS.ADD', 'early', 1000, 1)
+        self.client.execute_command('TS.ADD', 'late', 5000, 1)
+
+        assert self.client.execute_command('TS.QUERYINDEX', 'START', 4000, 'FILTER', 'name=cpu') == [b'late']
+        assert self.client.execute_command('TS.QUERYINDEX', 'END', 2000, 'name=cpu') == [b'early']
+        result = self.client.execute_command('TS.QUERYINDEX', 'START', 500, 'END', 6000, 'FILTER', 'name=cpu')
+        assert result == [b'early', b'late']
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.QUERYINDEX', 'FILTER_BY_RANGE', 0, 10, 'START', 0, 'name=cpu')
+
+    def test_range_respects_retention(self):
+        """Samples outside the retention period don't count, even before they are trimmed"""
+        self.client.execute_command('TS.CREATE', 'host:a', 'RETENTION', 1000, 'LABELS', 'job', 'node')
+        self.client.execute_command('TS.CREATE', 'host:b', 'LABELS', 'job', 'node')
+        self.client.execute_command('TS.ADD', 'host:a', 100, 1)
+        self.client.execute_command('TS.ADD', 'host:a', 5000, 1)
+        self.client.execute_command('TS.ADD', 'host:b', 100, 1)
+
+        result = self.client.execute_command('TS.QUERYINDEX', 'FILTER_BY_RANGE', 0, 1000, 'job=node')
+        assert result == [b'host:b']
+
+        result = self.client.execute_command('TS.QUERYINDEX', 'FILTER_BY_RANGE', 'NOT', 0, 1000, 'job=node')
+        assert result == [b'host:a']
@@ -1,4 +1,6 @@
 import math
+import socket
+import time
 
 import pytest
 from valkey import ResponseError
@@ -45,6 +47,26 @@ class TestTimeSeriesRange(ValkeyTimeSeriesTestCaseBase):
         result = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'COUNT', 2)
         assert result == [[1000, b'10.1'], [2000, b'20.2']]
 
+    def test_range_with_count_from_end(self):
+        """Test TS.RANGE with COUNT n FROM END returning the tail of the range"""
+
+        self.setup_data()
+
+        result = self.client.execute_command('TS.RANGE', 'ts1', '-', 4000, 'COUNT', 2, 'FROM', 'END')
+        assert result == [[3000, b'30.3'], [4000, b'40.4']]
+
+        # REVRANGE keeps its order, returning the oldest samples
+        result = self.client.execute_command('TS.REVRANGE', 'ts1', '-', '+', 'COUNT', 2, 'FROM', 'END')
+        assert result == [[2000, b'20.2'], [1000, b'10.1']]
+
+        # the last buckets of an aggregation
+        result = self.client.execute_command('TS.RANGE', 'ts1', 0, '+', 'COUNT', 1, 'FROM', 'END',
+                                             'AGGREGATION', 'max', 2000)
+        assert result == [[4000, b'50.5']]
+
+        with pytest.raises(ResponseError, match="expected END after COUNT count FROM"):
+            self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'COUNT', 2, 'FROM', 'START')
+
     def test_range_filter_by_ts(self):
         """Test TS.RANGE with FILTER_BY_TS"""
 
@@ -91,6 +113,62 @@ class TestTimeSeriesRange(ValkeyTimeSeriesTestCaseBase):
                                              'FILTER_BY_VALUE', 35, 60)
         assert result == [[4000, b'40.4'], [5000, b'50.5']]
 
+    def test_range_filter_by_ts_range(self):
+        """Test TS.RANGE with multiple FILTER_BY_TS_RANGE intervals"""
+
+        self.setup_data()
+
+        result = self.client.execute_command('TS.RANGE', 'ts1', '-', '+',
+                                             'FILTER_BY_TS_RANGE', 4000, 5000, 900, 1500, 1200, 2000)
+        assert result == [[1000, b'10.1'], [2000, b'20.2'], [4000, b'40.4'], [5000, b'50.5']]
+
+        result = self.client.execute_command('TS.REVRANGE', 'ts1', '-', '+',
+                                             'FILTER_BY_TS_RANGE', 1000, 2000, 4000, 5000)
+        assert result == [[5000, b'50.5'], [4000, b'40.4'], [2000, b'20.2'], [1000, b'10.1']]
+
+        # intervals are clipped to the query range
+        result = self.client.execute_command('TS.RANGE', 'ts1', 2000, 4500,
+                                             'FILTER_BY_TS_RANGE', 0, 2500, 3500, 9000)
+        assert result == [[2000, b'20.2'], [4000, b'40.4']]
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'FILTER_BY_TS_RANGE', 3000, 2000)
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'FILTER_BY_TS_RANGE', 1000)
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.RANGE', 'ts1', '-', '+',
+                                        'FILTER_BY_TS', 1000, 'FILTER_BY_TS_RANGE', 1000, 2000)
+
+    def test_range_latest(self):
+        """Test TS.RANGE LATEST includes the open compaction bucket only within the query range"""
+
+        self.setup_data()
+        self.client.execute_command('TS.CREATE', 'ts1:sum')
+        self.client.execute_command('TS.CREATERULE', 'ts1', 'ts1:sum', 'AGGREGATION', 'sum', 2000)
+
+        self.client.execute_command('TS.ADD', 'ts1', 6000, 1)
+        self.client.execute_command('TS.ADD', 'ts1', 6500, 2)
+
+        result = self.client.execute_command('TS.RANGE', 'ts1:sum', '-', '+')
+        assert result == []
+
+        result = self.client.execute_command('TS.RANGE', 'ts1:sum', '-', '+', 'LATEST')
+        assert result == [[6000, b'3']]
+
+        result = self.client.execute_command('TS.REVRANGE', 'ts1:sum', '-', '+', 'LATEST')
+        assert result == [[6000, b'3']]
+
+        # the open bucket lies outside the requested range
+        result = self.client.execute_command('TS.RANGE', 'ts1:sum', 0, 5999, 'LATEST')
+        assert result == []
+
+        # LATEST is a no-op for a series which is not a compaction
+        with_latest = self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'LATEST')
+        without_latest = self.client.execute_command('TS.RANGE', 'ts1', '-', '+')
+        assert with_latest == without_latest
+
     def test_range_aggregation_options(self):
         """Test TS.RANGE aggregation with ALIGN, BUCKETTIMESTAMP, EMPTY"""
 
@@ -109,6 +187,219 @@ class TestTimeSeriesRange(ValkeyTimeSeriesTestCaseBase):
         # Bucket 2 (4000-5999): sum(40.4, 50.5) = 90.9, mid timestamp = 5000
         assert result[2] == [5000, b'90.9']
 
+    def test_range_multiple_aggregations(self):
+        """Test TS.RANGE with a list of aggregations over the same buckets"""
+        self.client.execute_command('TS.CREATE', 'multi')
+        for i in range(60):
+            self.client.execute_command('TS.ADD', 'multi', 1000 + i * 250, (i * 7) % 13)
+
+        for command in ['TS.RANGE', 'TS.REVRANGE']:
+            result = self.client.execute_command(command, 'multi', '-', '+',
+                                                 'AGGREGATION', '[avg', 'min', 'max]', 2000)
+            assert len(result) > 1
+            for index, aggregation in enumerate(['avg', 'min', 'max']):
+                single = self.client.execute_command(command, 'multi', '-', '+',
+                                                     'AGGREGATION', aggregation, 2000)
+                assert [[ts, values[index]] for ts, values in result] == single
+
+            # the list may also be passed as a single argument
+            combined = self.client.execute_command(command, 'multi', '-', '+',
+                                                   'AGGREGATION', '[avg min max]', 2000)
+            assert combined == result
+
+        # a single aggregation keeps the flat reply
+        result = self.client.execute_command('TS.RANGE', 'multi', '-', '+', 'AGGREGATION', '[sum]', 2000)
+        assert result == self.client.execute_command('TS.RANGE', 'multi', '-', '+', 'AGGREGATION', 'sum', 2000)
+
+        with pytest.raises(ResponseError, match="missing"):
+            self.client.execute_command('TS.RANGE', 'multi', '-', '+', 'AGGREGATION', '[avg', 'min', 2000)
+        with pytest.raises(ResponseError, match="CONDITION"):
+            self.client.execute_command('TS.RANGE', 'multi', '-', '+', 'AGGREGATION', '[sum count]', 2000,
+                                        'CONDITION', '>', 3)
+        with pytest.raises(ResponseError, match="only supported by TS.RANGE"):
+            self.client.execute_command('TS.MRANGE', '-', '+', 'AGGREGATION', '[avg max]', 2000,
+                                        'FILTER', 'a=b')
+
+    def send_raw_resp3(self, *commands):
+        """Sends commands over a raw RESP3 connection and returns the unparsed replies"""
+        kwargs = self.client.connection_pool.connection_kwargs
+        payload = b''
+        for command in [['HELLO', '3'], *commands, ['PING']]:
+            payload += f'*{len(command)}\r\n'.encode()
+            for arg in command:
+                arg = str(arg).encode()
+                payload += b'$%d\r\n%s\r\n' % (len(arg), arg)
+        with socket.create_connection((kwargs['host'], kwargs['port'])) as sock:
+            sock.sendall(payload)
+            data = b''
+            while not data.endswith(b'+PONG\r\n'):
+                chunk = sock.recv(65536)
+                assert chunk, 'connection closed before all replies were read'
+                data += chunk
+        return data
+
+    def test_range_bucket_count_resp3(self):
+        """Test RESP3 clients receive the bucket count as an attribute of aggregated replies"""
+        self.client.execute_command('TS.CREATE', 'buckets')
+        for i, ts in enumerate([1000, 1200, 2500, 3900, 7100, 7700]):
+            self.client.execute_command('TS.ADD', 'buckets', ts, i)
+
+        # buckets 0, 2000, 4000 (empty) and 6000
+        data = self.send_raw_resp3(['TS.RANGE', 'buckets', '-', '+', 'AGGREGATION', 'sum', 2000, 'EMPTY'])
+        assert b'|1\r\n$11\r\nbucketCount\r\n:4\r\n*4\r\n' in data
+
+        # without EMPTY the skipped empty buckets are not counted
+        data = self.send_raw_resp3(['TS.REVRANGE', 'buckets', '-', '+', 'AGGREGATION', 'sum', 2000])
+        assert b'|1\r\n$11\r\nbucketCount\r\n:3\r\n*3\r\n' in data
+
+        # COUNT limits the buckets returned, and the count with them
+        data = self.send_raw_resp3(['TS.RANGE', 'buckets', '-', '+', 'COUNT', 2, 'AGGREGATION', 'sum', 2000, 'EMPTY'])
+        assert b'|1\r\n$11\r\nbucketCount\r\n:2\r\n*2\r\n' in data
+
+        # buckets holding only filtered out samples are not counted
+        data = self.send_raw_resp3(['TS.RANGE', 'buckets', '-', '+', 'FILTER_BY_VALUE', 2, 3,
+                                    'AGGREGATION', 'sum', 2000])
+        assert b'|1\r\n$11\r\nbucketCount\r\n:1\r\n*1\r\n' in data
+        data = self.send_raw_resp3(['TS.RANGE', 'buckets', '-', '+', 'FILTER_BY_TS', 1000, 7700,
+                                    'AGGREGATION', 'sum', 2000])
+        assert b'|1\r\n$11\r\nbucketCount\r\n:2\r\n*2\r\n' in data
+
+        # partial buckets at either end of the range are counted
+        data = self.send_raw_resp3(['TS.RANGE', 'buckets', 1100, 7000, 'AGGREGATION', 'sum', 2000, 'EMPTY'])
+        assert b'|1\r\n$11\r\nbucketCount\r\n:2\r\n*2\r\n' in data
+
+        # no attribute without aggregation, or for RESP2 clients
+        data = self.send_raw_resp3(['TS.RANGE', 'buckets', '-', '+'])
+        assert b'bucketCount' not in data
+        result = self.client.execute_command('TS.RANGE', 'buckets', '-', '+', 'AGGREGATION', 'sum', 2000)
+        assert [ts for ts, _ in result] == [0, 2000, 6000]
+
+    def test_range_with_counts(self):
+        """Test WITHCOUNTS reports the number of samples in each bucket"""
+        self.client.execute_command('TS.CREATE', 'counted')
+        timestamps = [1000, 1200, 2500, 3900, 7100, 7300, 7500, 7700]
+        for i, ts in enumerate(timestamps):
+            self.client.execute_command('TS.ADD', 'counted', ts, i)
+
+        result = self.client.execute_command('TS.RANGE', 'counted', '-', '+',
+                                             'AGGREGATION', 'sum', 2000, 'WITHCOUNTS')
+        assert [[ts, count] for ts, _, count in result] == [[0, 2], [2000, 2], [6000, 4]]
+        # the aggregate itself is unchanged
+        plain = self.client.execute_command('TS.RANGE', 'counted', '-', '+', 'AGGREGATION', 'sum', 2000)
+        assert [[ts, value] for ts, value, _ in result] == plain
+
+        # empty buckets count 0
+        result = self.client.execute_command('TS.RANGE', 'counted', '-', '+',
+                                             'AGGREGATION', 'sum', 2000, 'EMPTY', 'WITHCOUNTS')
+        assert [[ts, count] for ts, _, count in result] == [[0, 2], [2000, 2], [4000, 0], [6000, 4]]
+
+        result = self.client.execute_command('TS.REVRANGE', 'counted', '-', '+',
+                                             'AGGREGATION', '[min max]', 2000, 'WITHCOUNTS')
+        assert result[0] == [6000, [b'4', b'7'], 4]
+        assert [count for _, _, count in result] == [4, 2, 2]
+
+        with pytest.raises(ResponseError, match="MAXPOINTS"):
+            self.client.execute_command('TS.RANGE', 'counted', '-', '+', 'MAXPOINTS', 2,
+                                        'AGGREGATION', 'sum', 2000, 'WITHCOUNTS')
+        with pytest.raises(ResponseError, match="only supported by TS.RANGE"):
+            self.client.execute_command('TS.MRANGE', '-', '+', 'AGGREGATION', 'sum', 2000, 'WITHCOUNTS',
+                                        'FILTER', 'a=b')
+
+    def test_range_dedup(self):
+        """Test DEDUP keeps only the first and last bucket of each run of equal values"""
+        self.client.execute_command('TS.CREATE', 'step')
+        values = [1] * 5 + [5] * 5 + [2]
+        for i, value in enumerate(values):
+            # two samples per bucket, so that the average matches the step value
+            self.client.execute_command('TS.ADD', 'step', i * 1000, value)
+            self.client.execute_command('TS.ADD', 'step', i * 1000 + 500, value)
+
+        result = self.client.execute_command('TS.RANGE', 'step', '-', '+',
+                                             'AGGREGATION', 'avg', 1000, 'DEDUP')
+        assert result == [[0, b'1'], [4000, b'1'], [5000, b'5'], [9000, b'5'], [10000, b'2']]
+
+        result = self.client.execute_command('TS.REVRANGE', 'step', '-', '+',
+                                             'AGGREGATION', 'avg', 1000, 'DEDUP', 'NAN_DISTINCT')
+        assert result == [[10000, b'2'], [9000, b'5'], [5000, b'5'], [4000, b'1'], [0, b'1']]
+
+        # the final bucket is always kept, even in the middle of a run
+        result = self.client.execute_command('TS.RANGE', 'step', 0, 7999,
+                                             'AGGREGATION', 'avg', 1000, 'DEDUP')
+        assert result == [[0, b'1'], [4000, b'1'], [5000, b'5'], [7000, b'5']]
+
+        # with several aggregations, buckets are equal if all their values are
+        result = self.client.execute_command('TS.RANGE', 'step', '-', '+',
+                                             'AGGREGATION', '[min max]', 1000, 'WITHCOUNTS', 'DEDUP')
+        assert [row[0] for row in result] == [0, 4000, 5000, 9000, 10000]
+        assert all(row[2] == 2 for row in result)
+
+        with pytest.raises(ResponseError, match="only supported by TS.RANGE"):
+            self.client.execute_command('TS.MRANGE', '-', '+', 'AGGREGATION', 'avg', 1000, 'DEDUP',
+                                        'FILTER', 'a=b')
+
+    def test_range_intcast(self):
+        """Test INTCAST replies with integers only if every value in the reply is a whole number"""
+        self.client.execute_command('TS.CREATE', 'counter')
+        for i, value in enumerate([1, 2, 3, 4.5, 6, 7]):
+            self.client.execute_command('TS.ADD', 'counter', 1000 + i * 1000, value)
+
+        result = self.client.execute_command('TS.RANGE', 'counter', 1000, 3000, 'INTCAST')
+        assert result == [[1000, 1], [2000, 2], [3000, 3]]
+        result = self.client.execute_command('TS.REVRANGE', 'counter', 5000, 6000, 'INTCAST')
+        assert result == [[6000, 7], [5000, 6]]
+
+        # a single fractional value keeps the whole reply as doubles
+        result = self.client.execute_command('TS.RANGE', 'counter', 3000, 5000, 'INTCAST')
+        assert result == [[3000, b'3'], [4000, b'4.5'], [5000, b'6']]
+        # the same range without INTCAST
+        assert self.client.execute_command('TS.RANGE', 'counter', 1000, 3000) == \
+               [[1000, b'1'], [2000, b'2'], [3000, b'3']]
+
+        # the check applies to the aggregated values
+        result = self.client.execute_command('TS.RANGE', 'counter', '-', '+',
+                                             'AGGREGATION', 'sum', 2000, 'INTCAST')
+        assert result == [[0, b'1'], [2000, b'5'], [4000, b'10.5'], [6000, b'7']]
+        result = self.client.execute_command('TS.RANGE', 'counter', '-', '+',
+                                             'AGGREGATION', 'count', 2000, 'INTCAST')
+        assert result == [[0, 1], [2000, 2], [4000, 2], [6000, 1]]
+        result = self.client.execute_command('TS.RANGE', 'counter', '-', '+',
+                                             'AGGREGATION', '[min count]', 2000, 'WITHCOUNTS', 'INTCAST')
+        assert result[0] == [0, [b'1', b'1'], 1]
+        assert result[2] == [4000, [b'4.5', b'2'], 2]
+        result = self.client.execute_command('TS.RANGE', 'counter', 5000, 7000,
+                                             'AGGREGATION', '[min count]', 2000, 'WITHCOUNTS', 'INTCAST')
+        assert result == [[4000, [6, 1], 1], [6000, [7, 1], 1]]
+
+        # values beyond the i64 range can't be converted
+        self.client.execute_command('TS.ADD', 'counter', 8000, 1e19)
+        result = self.client.execute_command('TS.RANGE', 'counter', 7000, 8000, 'INTCAST')
+        assert all(isinstance(value, bytes) for _, value in result)
+
+    def test_range_align_series_start(self):
+        """Test that ALIGN series_start keeps buckets fixed as the query window slides"""
+        self.client.execute_command('TS.CREATE', 'ts_aligned')
+        first_ts = 1700
+        for i in range(60):
+            self.client.execute_command('TS.ADD', 'ts_aligned', first_ts + i * 1000, i)
+
+        buckets = {}
+        for start in [0, 2300, 4100, 9999, 13450]:
+            result = self.client.execute_command('TS.RANGE', 'ts_aligned', start, start + 20000,
+                                                 'ALIGN', 'series_start',
+                                                 'AGGREGATION', 'SUM', 5000)
+            assert len(result) > 2
+            for ts, _ in result:
+                assert (ts - first_ts) % 5000 == 0
+            # buckets wholly inside the window are identical across windows
+            for ts, value in result[1:-1]:
+                assert buckets.setdefault(ts, value) == value
+
+        # '-' is allowed, unlike with 'start'
+        result = self.client.execute_command('TS.RANGE', 'ts_aligned', '-', '+',
+                                             'ALIGN', 'series_start',
+                                             'AGGREGATION', 'COUNT', 5000)
+        assert result[0] == [first_ts, b'5']
+
     def test_aggregation_empty_buckets(self):
         """Test TS.RANGE aggregation with ALIGN, BUCKETTIMESTAMP, EMPTY"""
 
@@ -195,6 +486,30 @@ class TestTimeSeriesRange(ValkeyTimeSeriesTestCaseBase):
         assert result[2] == [3000, b'3']
         assert math.isnan(float(result[3][1])), f"Expected NaN, got {result[3][1]}"
 
+    def test_aggregation_skips_nan_values(self):
+        """Test that NaN samples are stored but excluded from avg/sum unless counted explicitly."""
+        self.client.execute_command('TS.CREATE', 'ts_nan_agg')
+        for ts, value in [(1000, 1.0), (2000, 'nan'), (3000, 3.0), (4000, 'nan'), (5000, 8.0)]:
+            self.client.execute_command('TS.ADD', 'ts_nan_agg', ts, value)
+
+        def aggregate(agg):
+            result = self.client.execute_command('TS.RANGE', 'ts_nan_agg', 0, 9999, 'AGGREGATION', agg, 10000)
+            assert len(result) == 1
+            return float(result[0][1])
+
+        assert aggregate('avg') == 4.0
+        assert aggregate('sum') == 12.0
+        assert aggregate('count') == 3
+        # NaN samples are only included by the aggregators that count them explicitly
+        assert aggregate('countnan') == 2
+        assert aggregate('countall') == 5
+
+        # a bucket holding only NaN samples has no value to average
+        self.client.execute_command('TS.CREATE', 'ts_all_nan')
+        self.client.execute_command('TS.ADD', 'ts_all_nan', 1000, 'nan')
+        result = self.client.execute_command('TS.RANGE', 'ts_all_nan', 0, 9999, 'AGGREGATION', 'avg', 10000)
+        assert all(math.isnan(float(value)) for _, value in result)
+
     def setup_aggregation_data(self):
         """Setup predictable test data for aggregation tests"""
         self.client.execute_command('TS.CREATE', 'agg_test')
@@ -410,13 +725,13 @@ class TestTimeSeriesRange(ValkeyTimeSeriesTestCaseBase):
     def test_increase_aggregation_resets_within_bucket(self):
         """
         Multiple resets inside one bucket: decreases should not reduce the increase.
-        This locks in the "ignore drops" behavior within a bucket.
+        After a reset the counter restarted from zero, so the post-reset value counts as increase.
         """
         self.client.execute_command('TS.CREATE', 'counter_inc_multi_reset')
 
         # Single bucket of 5000ms with ALIGN 0 includes all points:
         # values: 0 -> 10 -> 2 (reset) -> 12 -> 1 (reset) -> 6
-        # Expected increase = (10-0) + (12-2) + (6-1) = 10 + 10 + 5 = 25
+        # Expected increase = (10-0) + 2 + (12-2) + 1 + (6-1) = 10 + 2 + 10 + 1 + 5 = 28
         self.client.execute_command('TS.ADD', 'counter_inc_multi_reset', 1000, 0)
         self.client.execute_command('TS.ADD', 'counter_inc_multi_reset', 1500, 10)
         self.client.execute_command('TS.ADD', 'counter_inc_multi_reset', 2000, 2)
@@ -433,7 +748,87 @@ class TestTimeSeriesRange(ValkeyTimeSeriesTestCaseBase):
 
         assert len(result) == 1
         assert result[0][0] == 0
-        assert float(result[0][1]) == pytest.approx(25.0)
+        assert float(result[0][1]) == pytest.approx(28.0)
+
+    def test_rate_aggregation_empty_buckets(self):
+        """
+        RATE with EMPTY and BUCKETTIMESTAMP END: empty buckets are reported as NaN and
+        timestamps are reported at the bucket end.
+        """
+        self.client.execute_command('TS.CREATE', 'counter_rate_empty')
+
+        self.client.execute_command('TS.ADD', 'counter_rate_empty', 1000, 5)
+        self.client.execute_command('TS.ADD', 'counter_rate_empty', 1500, 8)  # +3 in [1000,2000)
+        self.client.execute_command('TS.ADD', 'counter_rate_empty', 3000, 10)
+        self.client.execute_command('TS.ADD', 'counter_rate_empty', 3500, 4)  # reset: +4 in [3000,4000)
+
+        result = self.client.execute_command(
+            'TS.RANGE', 'counter_rate_empty', 1000, 3999,
+            'ALIGN', 0,
+            'AGGREGATION', 'RATE', 1000,
+            'BUCKETTIMESTAMP', 'END',
+            'EMPTY'
+        )
+
+        assert len(result) == 3
+        by_ts = {ts: float(val) for ts, val in result}
+        assert by_ts[2000] == pytest.approx(3.0)
+        assert math.isnan(by_ts[3000]), "Expected Nan"
+        assert by_ts[4000] == pytest.approx(4.0)
+
+    def test_aggregation_fill_policies(self):
+        """FILL populates empty buckets between real buckets"""
+        self.client.execute_command('TS.CREATE', 'fill_series')
+        self.client.execute_command('TS.ADD', 'fill_series', 1000, 10)
+        self.client.execute_command('TS.ADD', 'fill_series', 4000, 40)
+
+        def values(policy):
+            result = self.client.execute_command(
+                'TS.RANGE', 'fill_series', 1000, 4999, 'ALIGN', 0,
+                'AGGREGATION', 'avg', 1000, 'FILL', policy
+            )
+            assert [ts for ts, _ in result] == [1000, 2000, 3000, 4000]
+            return [float(val) for _, val in result]
+
+        assert values('linear') == [10.0, 20.0, 30.0, 40.0]
+        assert values('previous') == [10.0, 10.0, 10.0, 40.0]
+        assert values('zero') == [10.0, 0.0, 0.0, 40.0]
+        null_values = values('null')
+        assert math.isnan(null_values[1]) and math.isnan(null_values[2])
+
+        with pytest.raises(ResponseError):
+            self.client.execute_command(
+                'TS.RANGE', 'fill_series', '-', '+', 'AGGREGATION', 'avg', 1000, 'FILL', 'cubic'
+            )
+
+    def test_trimmed_mean_aggregation(self):
+        """TRIMMED_MEAN drops the top and bottom pct percent of values before averaging"""
+        self.client.execute_command('TS.CREATE', 'trimmed')
+        values = [1000, 10, 11, 9, 10, 12, 8, 10, 10, -500]
+        for i, value in enumerate(values):
+            self.client.execute_command('TS.ADD', 'trimmed', 1000 + i, value)
+
+        result = self.client.execute_command(
+            'TS.RANGE', 'trimmed', '-', '+', 'AGGREGATION', 'trimmed_mean', 10, 1000
+        )
+        assert len(result) == 1
+        assert float(result[0][1]) == pytest.approx(10.0)
+
+        for pct in [-1, 50, 'abc']:
+            with pytest.raises(ResponseError):
+                self.client.execute_command(
+                    'TS.RANGE', 'trimmed', '-', '+', 'AGGREGATION', 'trimmed_mean', pct, 1000
+                )
+
+    def test_mad_aggregation(self):
+        """MAD is the median of absolute deviations from the bucket median"""
+        self.client.execute_command('TS.CREATE', 'mad')
+        for i, value in enumerate([3, 1, 5, 2, 4]):
+            self.client.execute_command('TS.ADD', 'mad', 1000 + i, value)
+        self.client.execute_command('TS.ADD', 'mad', 2000, 42)
+
+        result = self.client.execute_command('TS.RANGE', 'mad', '-', '+', 'AGGREGATION', 'mad', 1000)
+        assert result == [[1000, b'1'], [2000, b'0']]
 
     def test_last_aggregation(self):
         """Test LAST aggregation"""
@@ -1446,3 +1841,160 @@ class TestTimeSeriesRange(ValkeyTimeSeriesTestCaseBase):
 
         with pytest.raises(ResponseError, match="TSDB: Couldn't parse MAX"):
             self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'FILTER_BY_VALUE', 1000, 'b')
+
+    def test_range_calendar_alignment(self):
+        """Test ALIGN day/week/month snapping buckets to calendar boundaries"""
+        jan_1, jan_31, feb_1 = 1704067200000, 1706659200000, 1706745600000
+        feb_29, mar_1, apr_1, apr_15 = 1709164800000, 1709251200000, 1711929600000, 1713139200000
+        self.client.execute_command('TS.CREATE', 'cal')
+        for ts, value in [(jan_31, 1), (feb_1, 2), (feb_29, 3), (apr_15, 4)]:
+            self.client.execute_command('TS.ADD', 'cal', ts, value)
+
+        result = self.client.execute_command('TS.RANGE', 'cal', '-', '+', 'ALIGN', 'month',
+                                             'AGGREGATION', 'sum', '30d', 'EMPTY')
+        assert result == [[jan_1, b'1'], [feb_1, b'5'], [mar_1, b'0'], [apr_1, b'4']]
+
+        # in UTC+02:00 the last sample of January 31st (23:00 UTC) already falls in February
+        jan_31_late = jan_31 + 23 * 3600 * 1000
+        self.client.execute_command('TS.ADD', 'cal', jan_31_late, 10)
+        result = self.client.execute_command('TS.RANGE', 'cal', jan_31, feb_1, 'ALIGN', 'day+02:00',
+                                             'AGGREGATION', 'sum', '1d')
+        assert result == [[jan_31 - 2 * 3600 * 1000, b'1'], [feb_1 - 2 * 3600 * 1000, b'12']]
+
+        with pytest.raises(ResponseError, match="multiple of 30 days"):
+            self.client.execute_command('TS.RANGE', 'cal', '-', '+', 'ALIGN', 'month',
+                                        'AGGREGATION', 'sum', '45d')
+
+    def test_range_maxpoints(self):
+        """Test TS.RANGE MAXPOINTS downsamples while keeping the endpoints"""
+        self.client.execute_command('TS.CREATE', 'wave')
+        for i in range(500):
+            self.client.execute_command('TS.ADD', 'wave', 1000 + i * 10, math.sin(i / 10.0))
+
+        result = self.client.execute_command('TS.RANGE', 'wave', '-', '+', 'MAXPOINTS', 50)
+        assert len(result) == 50
+        assert result[0][0] == 1000
+        assert result[-1][0] == 1000 + 499 * 10
+        timestamps = [r[0] for r in result]
+        assert timestamps == sorted(timestamps)
+
+        # Fewer samples than MAXPOINTS are returned unchanged
+        result = self.client.execute_command('TS.RANGE', 'wave', 1000, 1040, 'MAXPOINTS', 10)
+        assert [r[0] for r in result] == [1000, 1010, 1020, 1030, 1040]
+
+        # Below 3 points only the endpoints are kept
+        result = self.client.execute_command('TS.RANGE', 'wave', '-', '+', 'MAXPOINTS', 2)
+        assert [r[0] for r in result] == [1000, 1000 + 499 * 10]
+
+        result = self.client.execute_command('TS.REVRANGE', 'wave', '-', '+', 'MAXPOINTS', 20)
+        assert len(result) == 20
+        assert result[0][0] == 1000 + 499 * 10
+        assert result[-1][0] == 1000
+
+    def test_range_maxpoints_invalid(self):
+        """Test TS.RANGE MAXPOINTS argument validation"""
+        self.setup_data()
+
+        for value in [0, -1, 'abc']:
+            with pytest.raises(ResponseError, match="MAXPOINTS should be a positive integer"):
+                self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'MAXPOINTS', value)
+
+        with pytest.raises(ResponseError, match="missing MAXPOINTS value"):
+            self.client.execute_command('TS.RANGE', 'ts1', '-', '+', 'MAXPOINTS')
+
+    def test_range_transform(self):
+        """Test TS.RANGE TRANSFORM converts values at query time"""
+        self.client.execute_command('TS.CREATE', 'bytes')
+        for i in range(10):
+            self.client.execute_command('TS.ADD', 'bytes', 1000 + i * 1000, i * 1000)
+
+        result = self.client.execute_command('TS.RANGE', 'bytes', '-', '+', 'TRANSFORM', 'SCALE', 0.001)
+        assert [float(v) for _, v in result] == pytest.approx([float(i) for i in range(10)])
+        result = self.client.execute_command('TS.REVRANGE', 'bytes', '-', '+', 'TRANSFORM', 'SCALE', 0.001,
+                                             'OFFSET', -1)
+        assert [float(v) for _, v in result] == pytest.approx([float(i - 1) for i in reversed(range(10))])
+
+        # stored samples are unchanged
+        assert self.client.execute_command('TS.GET', 'bytes')[1] == b'9000'
+
+        # avg composes the same either way; sum shows where the offset is applied
+        for order in ['BEFORE', 'AFTER']:
+            result = self.client.execute_command('TS.RANGE', 'bytes', 1000, '+', 'AGGREGATION', 'avg', 2000,
+                                                 'TRANSFORM', 'SCALE', 0.5, 'OFFSET', 1, order)
+            assert [float(v) for _, v in result] == [1.0, 751.0, 1751.0, 2751.0, 3751.0, 4501.0]
+        before = self.client.execute_command('TS.RANGE', 'bytes', 2000, 3000, 'AGGREGATION', 'sum', 2000,
+                                             'TRANSFORM', 'OFFSET', 1)
+        after = self.client.execute_command('TS.RANGE', 'bytes', 2000, 3000, 'AGGREGATION', 'sum', 2000,
+                                            'TRANSFORM', 'OFFSET', 1, 'AFTER')
+        assert before == [[2000, b'3002']]
+        assert after == [[2000, b'3001']]
+
+        # FILTER_BY_VALUE matches the stored values, not the transformed ones
+        result = self.client.execute_command('TS.RANGE', 'bytes', '-', '+', 'FILTER_BY_VALUE', 3000, 5000,
+                                             'TRANSFORM', 'SCALE', 0.5)
+        assert [float(v) for _, v in result] == [1500.0, 2000.0, 2500.0]
+
+        with pytest.raises(ResponseError, match="TRANSFORM requires SCALE or OFFSET"):
+            self.client.execute_command('TS.RANGE', 'bytes', '-', '+', 'TRANSFORM')
+        with pytest.raises(ResponseError, match="must be finite numbers"):
+            self.client.execute_command('TS.RANGE', 'bytes', '-', '+', 'TRANSFORM', 'SCALE', 'abc')
+
+    def test_range_function(self):
+        """Test TS.RANGE FUNCTION applies a scalar function to each sample"""
+        self.client.execute_command('TS.CREATE', 'squares')
+        for i in range(-2, 8):
+            self.client.execute_command('TS.ADD', 'squares', 10000 + i * 1000, i * abs(i))
+
+        result = self.client.execute_command('TS.RANGE', 'squares', '-', '+', 'FUNCTION', 'abs')
+        assert [float(v) for _, v in result] == [4.0, 1.0, 0.0, 1.0, 4.0, 9.0, 16.0, 25.0, 36.0, 49.0]
+
+        # sqrt of negative values and log of non-positive values are NaN
+        result = self.client.execute_command('TS.RANGE', 'squares', '-', '+', 'FUNCTION', 'sqrt')
+        values = [float(v) for _, v in result]
+        assert all(math.isnan(v) for v in values[:2])
+        assert values[2:] == [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]
+
+        # FILTER_BY_VALUE sees the function output, so it can drop the NaN values
+        result = self.client.execute_command('TS.RANGE', 'squares', '-', '+', 'FUNCTION', 'log10',
+                                             'FILTER_BY_VALUE', -1000, 1000)
+        assert [ts for ts, _ in result] == [11000, 12000, 13000, 14000, 15000, 16000, 17000]
+        assert float(result[0][1]) == 0.0
+
+        # the function is applied to samples before aggregation
+        result = self.client.execute_command('TS.RANGE', 'squares', 10000, '+', 'FUNCTION', 'sqrt',
+                                             'AGGREGATION', 'avg', 2000)
+        assert [float(v) for _, v in result] == [0.5, 2.5, 4.5, 6.5]
+
+        result = self.client.execute_command('TS.REVRANGE', 'squares', 10000, '+', 'FUNCTION', 'sqrt',
+                                             'TRANSFORM', 'SCALE', 2, 'AGGREGATION', 'max', 2000)
+        assert [float(v) for _, v in result] == [14.0, 10.0, 6.0, 2.0]
+
+        with pytest.raises(ResponseError, match="invalid FUNCTION"):
+            self.client.execute_command('TS.RANGE', 'squares', '-', '+', 'FUNCTION', 'ln')
+        with pytest.raises(ResponseError, match="missing FUNCTION name"):
+            self.client.execute_command('TS.RANGE', 'squares', '-', '+', 'FUNCTION')
+
+    def test_range_relative_weeks_months(self):
+        """Test relative range bounds in weeks, days and calendar months"""
+        now = int(time.time() * 1000)
+        day = 24 * 60 * 60 * 1000
+        self.client.execute_command('TS.CREATE', 'rel')
+        for days_ago in [400, 100, 60, 20, 5, 1]:
+            self.client.execute_command('TS.ADD', 'rel', now - days_ago * day, days_ago)
+
+        def values(*args):
+            return [int(v) for _, v in self.client.execute_command('TS.RANGE', 'rel', *args)]
+
+        assert values('-1w', '+') == [5, 1]
+        assert values('-90d', '+') == [60, 20, 5, 1]
+        assert values('-1mo', '+') == [20, 5, 1]
+        assert values('-1y', '-1mo') == [100, 60]
+        assert values('-1y2mo', '-2w') == [400, 100, 60, 20]
+
+        # calendar units have no fixed length, so they are rejected where one is needed
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.RANGE', 'rel', '-1d', 'start+1mo')
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.CREATE', 'bad', 'RETENTION', '1y')
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.RANGE', 'rel', '-1.5mo', '+')
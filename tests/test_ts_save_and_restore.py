@@ -52,6 +52,28 @@ class TestTimeseriesSaveRestore(ValkeyTimeSeriesTestCaseBase):
         del ts_info_result_1['memoryUsage']
         assert ts_info_result_2 == ts_info_result_1
 
+    def test_new_series_ids_do_not_collide_after_restart(self):
+        client = self.server.get_new_client()
+        for i in range(5):
+            client.execute_command('TS.CREATE', f'id_series:{i}', 'LABELS', 'group', 'ids')
+        loaded_ids = [self.ts_info(f'id_series:{i}', True)['id'] for i in range(5)]
+
+        client.bgsave()
+        self.server.wait_for_save_done()
+        self.server.restart(remove_rdb=False, remove_nodes_conf=False, connect_client=True)
+        assert self.server.is_alive()
+        wait_for_equal(lambda: self.server.is_rdb_done_loading(), True)
+
+        self.client = self.server.get_new_client()
+        assert [self.ts_info(f'id_series:{i}', True)['id'] for i in range(5)] == loaded_ids
+
+        self.client.execute_command('TS.CREATE', 'id_series:new', 'LABELS', 'group', 'ids')
+        new_id = self.ts_info('id_series:new', True)['id']
+        assert new_id > max(loaded_ids)
+
+        # every series is still reachable through the index
+        assert len(self.client.execute_command('TS.QUERYINDEX', 'group=ids')) == 6
+
     def test_basic_save_many(self):
         client = self.server.get_new_client()
         count = 500
@@ -0,0 +1,123 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.util.waiters import *
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTsSeal(ValkeyTimeSeriesTestCaseBase):
+
+    def test_seal_starts_new_chunk(self):
+        key = 'ts_seal'
+        self.client.execute_command('TS.CREATE', key)
+        # nothing to seal in an empty series
+        assert self.client.execute_command('TS.SEAL', key) == 0
+
+        for i in range(10):
+            self.client.execute_command('TS.ADD', key, 1000 + i * 10, i)
+        assert self.ts_info(key)['chunkCount'] == 1
+
+        assert self.client.execute_command('TS.SEAL', key) == 1
+        assert self.ts_info(key)['chunkCount'] == 1
+        # already sealed
+        assert self.client.execute_command('TS.SEAL', key) == 0
+        assert self.ts_info(key)['chunkCount'] == 1
+
+        self.client.execute_command('TS.ADD', key, 2000, 42)
+        info = self.ts_info(key)
+        assert info['chunkCount'] == 2
+        assert info['totalSamples'] == 11
+        assert info['lastTimestamp'] == 2000
+
+        samples = self.client.execute_command('TS.RANGE', key, '-', '+')
+        assert len(samples) == 11
+        assert samples[-1] == [2000, b'42']
+
+    def test_seal_keeps_samples(self):
+        key = 'ts_seal_samples'
+        self.client.execute_command('TS.CREATE', key, 'DUPLICATE_POLICY', 'LAST')
+        for i in range(10):
+            self.client.execute_command('TS.ADD', key, 1000 + i * 10, i)
+        before = self.client.execute_command('TS.RANGE', key, '-', '+')
+
+        assert self.client.execute_command('TS.SEAL', key) == 1
+        assert self.client.execute_command('TS.RANGE', key, '-', '+') == before
+
+        # the last sample can still be updated after sealing
+        self.client.execute_command('TS.ADD', key, 1090, 100)
+        assert self.client.execute_command('TS.GET', key) == [1090, b'100']
+
+        self.client.execute_command('DEBUG', 'RELOAD')
+        samples = self.client.execute_command('TS.RANGE', key, '-', '+')
+        assert samples[:-1] == before[:-1]
+        assert samples[-1] == [1090, b'100']
+
+    def test_seal_survives_reload(self):
+        key = 'ts_seal_reload'
+        self.client.execute_command('TS.CREATE', key)
+        for i in range(10):
+            self.client.execute_command('TS.ADD', key, 1000 + i * 10, i)
+        assert self.client.execute_command('TS.SEAL', key) == 1
+
+        self.client.execute_command('DEBUG', 'RELOAD')
+        assert self.client.execute_command('TS.GET', key) == [1090, b'9']
+        info = self.ts_info(key)
+        assert info['chunkCount'] == 1
+        assert info['lastTimestamp'] == 1090
+        # the seal was persisted
+        assert self.client.execute_command('TS.SEAL', key) == 0
+
+        self.client.execute_command('TS.ADD', key, 2000, 42)
+        assert self.ts_info(key)['chunkCount'] == 2
+        assert self.client.execute_command('TS.GET', key) == [2000, b'42']
+
+    def test_del_and_trim_after_seal(self):
+        key = 'ts_seal_del'
+        self.client.execute_command('TS.CREATE', key)
+        for i in range(10):
+            self.client.execute_command('TS.ADD', key, 1000 + i * 10, i)
+        assert self.client.execute_command('TS.SEAL', key) == 1
+
+        assert self.client.execute_command('TS.DEL', key, 1000, 1020) == 3
+        assert self.client.execute_command('TS.GET', key) == [1090, b'9']
+        assert self.ts_info(key)['totalSamples'] == 7
+
+        # a sample limit trims the series from the front
+        self.client.execute_command('TS.ALTER', key, 'RETENTION_SAMPLES', 4)
+        assert self.client.execute_command('TS.GET', key) == [1090, b'9']
+        samples = self.client.execute_command('TS.RANGE', key, '-', '+')
+        assert [s[0] for s in samples] == [1060, 1070, 1080, 1090]
+
+        # the next sample still starts a new chunk
+        self.client.execute_command('TS.ADD', key, 2000, 42)
+        assert self.client.execute_command('TS.GET', key) == [2000, b'42']
+        assert self.ts_info(key)['chunkCount'] == 2
+
+    def test_addbulk_after_seal(self):
+        key = 'ts_seal_addbulk'
+        self.client.execute_command('TS.CREATE', key)
+        for i in range(10):
+            self.client.execute_command('TS.ADD', key, 1000 + i * 10, i)
+        assert self.client.execute_command('TS.SEAL', key) == 1
+
+        payload = '{"timestamps": [1005, 2000, 2010], "values": [0.5, 20, 21]}'
+        self.client.execute_command('TS.ADDBULK', key, payload)
+
+        info = self.ts_info(key)
+        assert info['chunkCount'] == 2
+        assert info['totalSamples'] == 13
+        assert info['firstTimestamp'] == 1000
+        assert info['lastTimestamp'] == 2010
+        assert self.client.execute_command('TS.GET', key) == [2010, b'21']
+
+        samples = self.client.execute_command('TS.RANGE', key, '-', '+')
+        timestamps = [s[0] for s in samples]
+        assert timestamps == sorted(timestamps)
+        assert len(timestamps) == 13
+
+    def test_seal_errors(self):
+        with pytest.raises(ResponseError, match="wrong number of arguments"):
+            self.client.execute_command('TS.SEAL')
+
+        with pytest.raises(ResponseError, match="the key does not exist"):
+            self.client.execute_command('TS.SEAL', 'missing')
@@ -0,0 +1,80 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+class TestTsSeriesDiff(ValkeyTimeSeriesTestCaseBase):
+
+    def setup_test_data(self, client):
+        client.execute_command('TS.CREATE', 'ts1', 'LABELS', 'name', 'cpu', 'node', 'node1')
+        client.execute_command('TS.CREATE', 'ts2', 'LABELS', 'name', 'cpu', 'node', 'node2')
+        client.execute_command('TS.CREATE', 'ts3', 'LABELS', 'name', 'memory', 'node', 'node1')
+        client.execute_command('TS.CREATE', 'ts4', 'LABELS', 'name', 'memory', 'node', 'node3')
+
+    def test_diff(self):
+        self.setup_test_data(self.client)
+
+        result = self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'name=cpu', 'node=node1')
+        assert result == [b'ts2']
+
+        result = self.client.execute_command('TS.SERIESDIFF', 'diff', 'node=node1', 'name=cpu')
+        assert result == [b'ts3']
+
+    def test_xor(self):
+        self.setup_test_data(self.client)
+
+        result = self.client.execute_command('TS.SERIESDIFF', 'XOR', 'name=cpu', 'node=node1')
+        assert result == [b'ts2', b'ts3']
+
+        # XOR is symmetric
+        result = self.client.execute_command('TS.SERIESDIFF', 'XOR', 'node=node1', 'name=cpu')
+        assert result == [b'ts2', b'ts3']
+
+    def test_or_selectors(self):
+        self.setup_test_data(self.client)
+
+        result = self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'node=node1 or node=node3', 'name=memory')
+        assert result == [b'ts1']
+
+    def test_empty_results(self):
+        self.setup_test_data(self.client)
+
+        # identical selectors
+        assert self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'name=cpu', 'name=cpu') == []
+        assert self.client.execute_command('TS.SERIESDIFF', 'XOR', 'name=cpu', 'name=cpu') == []
+
+        # left side matches nothing
+        assert self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'name=disk', 'name=cpu') == []
+        result = self.client.execute_command('TS.SERIESDIFF', 'XOR', 'name=disk', 'name=cpu')
+        assert result == [b'ts1', b'ts2']
+
+        # right side matches nothing
+        result = self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'name=cpu', 'name=disk')
+        assert result == [b'ts1', b'ts2']
+
+        # empty database
+        self.client.execute_command('FLUSHALL')
+        assert self.client.execute_command('TS.SERIESDIFF', 'XOR', 'name=cpu', 'name=memory') == []
+
+    def test_deleted_series_are_excluded(self):
+        self.setup_test_data(self.client)
+        self.client.execute_command('DEL', 'ts2')
+
+        result = self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'name=cpu', 'node=node1')
+        assert result == []
+
+    def test_errors(self):
+        self.setup_test_data(self.client)
+
+        with pytest.raises(ResponseError, match="invalid diff mode"):
+            self.client.execute_command('TS.SERIESDIFF', 'UNION', 'name=cpu', 'node=node1')
+
+        with pytest.raises(ResponseError, match="series selector is invalid"):
+            self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'name=cpu', '{')
+
+        with pytest.raises(ResponseError, match="wrong number of arguments"):
+            self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'name=cpu')
+
+        with pytest.raises(ResponseError, match="wrong number of arguments"):
+            self.client.execute_command('TS.SERIESDIFF', 'DIFF', 'name=cpu', 'node=node1', 'node=node2')
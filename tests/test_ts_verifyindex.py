@@ -0,0 +1,68 @@
+import pytest
+from valkey import ResponseError
+from valkeytestframework.conftest import resource_port_tracker
+from valkey_timeseries_test_case import ValkeyTimeSeriesTestCaseBase
+
+
+def to_dict(reply):
+    if isinstance(reply, dict):
+        items = reply.items()
+    else:
+        items = zip(reply[::2], reply[1::2])
+    return {(k.decode() if isinstance(k, bytes) else k): v for k, v in items}
+
+
+class TestTsVerifyIndex(ValkeyTimeSeriesTestCaseBase):
+
+    def verify(self, *args):
+        return to_dict(self.client.execute_command('TS.VERIFYINDEX', *args))
+
+    def assert_consistent(self, stats, series_count):
+        assert stats['seriesChecked'] == series_count
+        assert stats['orphanedIds'] == 0
+        assert stats['missingPostings'] == 0
+        assert stats['keyMismatches'] == 0
+        assert stats['repaired'] == 0
+
+    def test_verify_empty_index(self):
+        self.assert_consistent(self.verify(), 0)
+
+    def test_verify_ignores_other_key_types(self):
+        self.client.execute_command('SET', 'plain', 'value')
+        self.client.execute_command('TS.CREATE', 'vi:1', 'LABELS', 'pod', 'a')
+
+        self.assert_consistent(self.verify(), 1)
+
+    def test_verify_after_changes(self):
+        for i in range(10):
+            self.client.execute_command('TS.CREATE', f'vi:{i}', 'LABELS', 'pod', f'pod-{i}', 'env', 'prod')
+        self.client.execute_command('RENAME', 'vi:0', 'vi:renamed')
+        self.client.execute_command('DEL', 'vi:1')
+        self.client.execute_command('TS.ALTER', 'vi:2', 'LABELS', 'pod', 'changed')
+
+        self.assert_consistent(self.verify(), 9)
+
+    def test_verify_after_swapdb(self):
+        for i in range(3):
+            self.client.execute_command('TS.CREATE', f'vi:db0:{i}', 'LABELS', 'db', '0')
+        self.client.select(1)
+        self.client.execute_command('TS.CREATE', 'vi:db1', 'LABELS', 'db', '1')
+
+        self.client.execute_command('SWAPDB', 0, 1)
+
+        self.assert_consistent(self.verify(), 3)
+        self.client.select(0)
+        self.assert_consistent(self.verify(), 1)
+
+    def test_verify_repair_on_consistent_index(self):
+        for i in range(3):
+            self.client.execute_command('TS.CREATE', f'vi:{i}', 'LABELS', 'pod', f'pod-{i}')
+
+        self.assert_consistent(self.verify('REPAIR'), 3)
+        assert len(self.client.execute_command('TS.QUERYINDEX', 'pod=~"pod-.*"')) == 3
+
+    def test_verify_invalid_argument(self):
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.VERIFYINDEX', 'FIX')
+        with pytest.raises(ResponseError):
+            self.client.execute_command('TS.VERIFYINDEX', 'REPAIR', 'extra')